@@ -21,7 +21,7 @@ use druid::widget::Stepper;
 
 fn steppers() {
     // A Stepper with default parameters
-    let stepper1 = Stepper::new();
+    let stepper1 = Stepper::<f64>::new();
 
     // A Stepper that operates over a custom range
     let stepper2 = Stepper::new().with_range(10.0, 50.0);
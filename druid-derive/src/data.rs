@@ -114,51 +114,45 @@ fn derive_enum(
                     quote!( #same_fn(#var_left, #var_right) )
                 })
                 .collect();
-
-            if let FieldKind::Named = fields.kind {
-                let lefts: Vec<_> = fields
-                    .iter()
-                    .map(|field| {
-                        let ident = field.ident_tokens();
-                        let var = ident_from_str(&format!("__self_{}", field.ident_string()));
-                        quote!( #ident: #var )
-                    })
-                    .collect();
-                let rights: Vec<_> = fields
-                    .iter()
-                    .map(|field| {
-                        let ident = field.ident_tokens();
-                        let var = ident_from_str(&format!("__other_{}", field.ident_string()));
-                        quote!( #ident: #var )
-                    })
-                    .collect();
-
-                Ok(quote! {
-                    (#ident :: #variant { #( #lefts ),* }, #ident :: #variant { #( #rights ),* }) => {
-                        #( #tests )&&*
-                    }
-                })
+            // a variant without fields to compare is the same as any other
+            // instance of that variant.
+            let body = if tests.is_empty() {
+                quote!(true)
             } else {
-                let vars_left: Vec<_> = fields
+                quote!( #( #tests )&&* )
+            };
+
+            // the bindings on either side of the match arm. Ignored fields are
+            // bound too, so that they still count as read; the leading
+            // underscores keep the unused bindings from being warned about.
+            let bindings = |prefix: &str| -> Vec<_> {
+                fields
                     .iter()
-                    .map(|field| ident_from_str(&format!("__self_{}", field.ident_string())))
-                    .collect();
-                let vars_right: Vec<_> = fields
-                    .iter()
-                    .map(|field| ident_from_str(&format!("__other_{}", field.ident_string())))
-                    .collect();
-
-                if fields.iter().count() > 0 {
-                    Ok(quote! {
-                        ( #ident :: #variant( #(#vars_left),* ),  #ident :: #variant( #(#vars_right),* )) => {
-                            #( #tests )&&*
-                        }
-                    })
-                } else {
+                    .map(|field| ident_from_str(&format!("{}{}", prefix, field.ident_string())))
+                    .collect()
+            };
+            let vars_left = bindings("__self_");
+            let vars_right = bindings("__other_");
+
+            match fields.kind {
+                FieldKind::Named => {
+                    let idents: Vec<_> = fields.iter().map(Field::ident_tokens).collect();
                     Ok(quote! {
-                        ( #ident :: #variant ,  #ident :: #variant ) => { true }
+                        (
+                            #ident :: #variant { #( #idents: #vars_left ),* },
+                            #ident :: #variant { #( #idents: #vars_right ),* },
+                        ) => { #body }
                     })
                 }
+                FieldKind::Unnamed if fields.iter().count() > 0 => Ok(quote! {
+                    (
+                        #ident :: #variant( #( #vars_left ),* ),
+                        #ident :: #variant( #( #vars_right ),* ),
+                    ) => { #body }
+                }),
+                FieldKind::Unnamed => Ok(quote! {
+                    ( #ident :: #variant, #ident :: #variant ) => { true }
+                }),
             }
         })
         .collect::<Result<Vec<proc_macro2::TokenStream>, syn::Error>>()?;
@@ -168,6 +162,8 @@ fn derive_enum(
             fn same(&self, other: &Self) -> bool {
                 match (self, other) {
                     #( #cases ),*
+                    // unreachable if the enum only has one variant
+                    #[allow(unreachable_patterns)]
                     _ => false,
                 }
             }
@@ -191,7 +187,13 @@ fn generics_bounds(generics: &syn::Generics) -> proc_macro2::TokenStream {
                 }
             }
             Lifetime(lf) => quote!(#lf),
-            Const(cst) => quote!(#cst),
+            Const(cst) => {
+                // defaults aren't allowed in impl generics
+                let mut cst = cst.clone();
+                cst.eq_token = None;
+                cst.default = None;
+                quote!(#cst)
+            }
         }
     });
 
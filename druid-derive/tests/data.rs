@@ -127,3 +127,61 @@ fn data_attr_eq() {
         f: PanicOnPartialEq,
     });
 }
+
+fn same_sign(one: &i32, two: &i32) -> bool {
+    one.signum() == two.signum()
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Data)]
+enum GenericEnum<T, const N: usize = 2> {
+    Tuple(T, #[data(same_fn = "same_sign")] i32),
+    Named {
+        value: T,
+        #[data(ignore)]
+        cache: [u8; N],
+    },
+    Ignored(#[data(ignore)] Vec<T>),
+    Empty {},
+    Unit,
+}
+
+#[derive(Clone, Data)]
+enum SingleVariantEnum<T> {
+    Only { value: T },
+}
+
+#[test]
+fn test_data_derive_enum_payloads() {
+    let tuple = GenericEnum::<_>::Tuple(1u8, 5);
+    assert!(tuple.same(&GenericEnum::Tuple(1, 10)));
+    assert!(!tuple.same(&GenericEnum::Tuple(1, -5)));
+    assert!(!tuple.same(&GenericEnum::Tuple(2, 5)));
+
+    let named = GenericEnum::<_, 2>::Named {
+        value: 1u8,
+        cache: [0, 0],
+    };
+    assert!(named.same(&GenericEnum::Named {
+        value: 1,
+        cache: [3, 4],
+    }));
+    assert!(!named.same(&GenericEnum::Named {
+        value: 2,
+        cache: [0, 0],
+    }));
+
+    let ignored = GenericEnum::<_>::Ignored(vec![1u8]);
+    assert!(ignored.same(&GenericEnum::Ignored(vec![])));
+    assert!(GenericEnum::<u8>::Empty {}.same(&GenericEnum::Empty {}));
+    assert!(GenericEnum::<u8>::Unit.same(&GenericEnum::Unit));
+
+    // different variants are never the same
+    assert!(!tuple.same(&named));
+    assert!(!ignored.same(&GenericEnum::Empty {}));
+    assert!(!GenericEnum::<u8>::Empty {}.same(&GenericEnum::Unit));
+
+    let only = SingleVariantEnum::Only { value: 1.0 };
+    assert!(only.same(&SingleVariantEnum::Only { value: 1.0 }));
+    assert!(!only.same(&SingleVariantEnum::Only { value: 2.0 }));
+}
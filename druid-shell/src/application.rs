@@ -42,6 +42,18 @@ pub trait AppHandler {
     fn command(&mut self, id: u32) {}
 }
 
+/// The appearance preferred by the user for applications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorScheme {
+    /// Dark text on a light background.
+    Light,
+    /// Light text on a dark background.
+    Dark,
+    /// The user hasn't expressed a preference, or the platform doesn't
+    /// support one.
+    NoPreference,
+}
+
 /// The top level application object.
 ///
 /// This can be thought of as a reference and it can be safely cloned.
@@ -183,4 +195,21 @@ impl Application {
     pub fn get_locale() -> String {
         backend::Application::get_locale()
     }
+
+    /// Returns the light or dark appearance preferred by the user.
+    ///
+    /// Changes are reported to every window, with
+    /// [`WinHandler::color_scheme_changed`].
+    ///
+    /// # Platform support
+    ///
+    /// This reads the system setting on macOS, Windows and the web, and the
+    /// GTK theme on Linux with the GTK backend. Other backends always return
+    /// [`ColorScheme::NoPreference`], and only the web backend doesn't report
+    /// changes.
+    ///
+    /// [`WinHandler::color_scheme_changed`]: crate::WinHandler::color_scheme_changed
+    pub fn get_color_scheme() -> ColorScheme {
+        backend::Application::get_color_scheme()
+    }
 }
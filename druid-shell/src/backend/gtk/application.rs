@@ -19,8 +19,9 @@ use gtk::gio::{ApplicationFlags, Cancellable};
 use gtk::Application as GtkApplication;
 
 use gtk::prelude::{ApplicationExt, GtkApplicationExt};
+use gtk::traits::SettingsExt;
 
-use crate::application::AppHandler;
+use crate::application::{AppHandler, ColorScheme};
 
 use super::clipboard::Clipboard;
 use super::error::Error;
@@ -89,6 +90,23 @@ impl Application {
         }
         locale
     }
+
+    pub fn get_color_scheme() -> ColorScheme {
+        let settings = match gtk::Settings::default() {
+            Some(settings) => settings,
+            None => return ColorScheme::NoPreference,
+        };
+        // Few themes set the preference, but dark themes usually say so in their name.
+        let dark_name = settings
+            .gtk_theme_name()
+            .map(|name| name.to_lowercase().contains("dark"))
+            .unwrap_or(false);
+        if settings.is_gtk_application_prefer_dark_theme() || dark_name {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        }
+    }
 }
 
 impl crate::platform::linux::ApplicationExt for crate::Application {
@@ -15,9 +15,11 @@
 //! Interactions with the system pasteboard on GTK+.
 
 use gtk::gdk::Atom;
+use gtk::gdk_pixbuf::{Colorspace, Pixbuf};
 use gtk::{TargetEntry, TargetFlags};
 
-use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::clipboard::{rgba_pixels, ClipboardFormat, FormatId};
+use crate::piet::{ImageBuf, ImageFormat};
 
 const CLIPBOARD_TARGETS: [&str; 5] = [
     "UTF8_STRING",
@@ -109,6 +111,39 @@ impl Clipboard {
         None
     }
 
+    /// Put an image onto the system clipboard.
+    pub fn put_image(&mut self, image: &ImageBuf) {
+        let (width, height) = (image.width() as i32, image.height() as i32);
+        if width == 0 || height == 0 {
+            tracing::warn!("can't put an empty image on the clipboard");
+            return;
+        }
+        let display = gtk::gdk::Display::default().unwrap();
+        let clipboard = gtk::Clipboard::for_display(&display, &self.selection);
+
+        let pixbuf = Pixbuf::from_mut_slice(
+            rgba_pixels(image),
+            Colorspace::Rgb,
+            true,
+            // bits_per_sample
+            8,
+            width,
+            height,
+            // row stride (in bytes)
+            width * 4,
+        );
+        clipboard.set_image(&pixbuf);
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        let display = gtk::gdk::Display::default().unwrap();
+        let clipboard = gtk::Clipboard::for_display(&display, &self.selection);
+
+        let pixbuf = clipboard.wait_for_image()?;
+        image_from_pixbuf(&pixbuf)
+    }
+
     /// Given a list of supported clipboard types, returns the supported type which has
     /// highest priority on the system clipboard, or `None` if no types are supported.
     pub fn preferred_format(&self, formats: &[FormatId]) -> Option<FormatId> {
@@ -160,3 +195,29 @@ fn make_entries(formats: &[ClipboardFormat]) -> Vec<TargetEntry> {
         .map(|(i, fmt)| TargetEntry::new(fmt.identifier, TargetFlags::all(), i as u32))
         .collect()
 }
+
+fn image_from_pixbuf(pixbuf: &Pixbuf) -> Option<ImageBuf> {
+    let channels = pixbuf.n_channels() as usize;
+    if pixbuf.bits_per_sample() != 8 || !(channels == 3 || channels == 4) {
+        tracing::warn!("unsupported clipboard image format");
+        return None;
+    }
+    let (width, height) = (pixbuf.width() as usize, pixbuf.height() as usize);
+    let stride = pixbuf.rowstride() as usize;
+    let bytes = pixbuf.read_pixel_bytes()?;
+
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        // The last row is not padded to the full stride.
+        let row = bytes.get(y * stride..y * stride + width * channels)?;
+        for px in row.chunks_exact(channels) {
+            pixels.extend_from_slice(&[px[0], px[1], px[2], *px.get(3).unwrap_or(&0xff)]);
+        }
+    }
+    Some(ImageBuf::from_raw(
+        pixels,
+        ImageFormat::RgbaSeparate,
+        width,
+        height,
+    ))
+}
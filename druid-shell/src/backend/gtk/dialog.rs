@@ -14,7 +14,7 @@
 
 //! File open/save dialogs, GTK implementation.
 
-use std::ffi::OsString;
+use std::path::PathBuf;
 
 use anyhow::anyhow;
 use gtk::{FileChooserAction, FileFilter, ResponseType, Window};
@@ -22,7 +22,7 @@ use gtk::{FileChooserAction, FileFilter, ResponseType, Window};
 use gtk::prelude::{FileChooserExt, NativeDialogExt};
 
 use crate::dialog::{FileDialogOptions, FileDialogType, FileSpec};
-use crate::Error;
+use crate::{Error, FileInfo};
 
 fn file_filter(fs: &FileSpec) -> FileFilter {
     let ret = FileFilter::new();
@@ -37,7 +37,7 @@ pub(crate) fn get_file_dialog_path(
     window: &Window,
     ty: FileDialogType,
     options: FileDialogOptions,
-) -> Result<Vec<OsString>, Error> {
+) -> Result<Vec<FileInfo>, Error> {
     // TODO: support message localization
 
     let (title, action) = match (ty, options.select_directories) {
@@ -63,6 +63,8 @@ pub(crate) fn get_file_dialog_path(
         dialog.set_select_multiple(options.multi_selection);
     }
 
+    // The filters with their file types, to find the chosen type.
+    let mut filters = Vec::new();
     // Don't set the filters when showing the folder selection dialog,
     // because then folder traversing won't work.
     if action != FileChooserAction::SelectFolder {
@@ -71,6 +73,7 @@ pub(crate) fn get_file_dialog_path(
             for f in file_types {
                 let filter = file_filter(f);
                 dialog.add_filter(&filter);
+                filters.push((filter.clone(), *f));
 
                 if let Some(default) = &options.default_type {
                     if default == f {
@@ -95,6 +98,12 @@ pub(crate) fn get_file_dialog_path(
         dialog.set_current_name(default_name);
     }
 
+    if let Some(path) = &options.starting_directory {
+        if !dialog.set_current_folder(path) {
+            tracing::warn!("Failed to set the starting directory to {:?}", path);
+        }
+    }
+
     let result = dialog.run();
 
     let result = match result {
@@ -108,7 +117,21 @@ pub(crate) fn get_file_dialog_path(
             filenames if filenames.len() > 1 && action == FileChooserAction::Save => {
                 Err(anyhow!("More than one path received for save action"))
             }
-            filenames => Ok(filenames.into_iter().map(|p| p.into_os_string()).collect()),
+            filenames => {
+                let format = dialog.filter().and_then(|chosen| {
+                    filters
+                        .iter()
+                        .find(|(filter, _)| *filter == chosen)
+                        .map(|(_, spec)| *spec)
+                });
+                Ok(filenames
+                    .into_iter()
+                    .map(|path| FileInfo {
+                        path: with_extension(path, action, format),
+                        format,
+                    })
+                    .collect())
+            }
         },
         ResponseType::Cancel => Err(anyhow!("Dialog was deleted")),
         _ => {
@@ -123,3 +146,23 @@ pub(crate) fn get_file_dialog_path(
 
     Ok(result?)
 }
+
+/// GTK doesn't add the extension of the chosen file type to the saved file,
+/// so this adds its first extension, unless `path` already has one of them.
+fn with_extension(
+    mut path: PathBuf,
+    action: FileChooserAction,
+    format: Option<FileSpec>,
+) -> PathBuf {
+    if let (FileChooserAction::Save, Some(format)) = (action, format) {
+        if let Some(ext) = format.extensions.first() {
+            if !format.matches(&path) {
+                let mut name = path.file_name().unwrap_or_default().to_owned();
+                name.push(".");
+                name.push(ext);
+                path.set_file_name(name);
+            }
+        }
+    }
+    path
+}
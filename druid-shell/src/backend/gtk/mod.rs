@@ -21,5 +21,6 @@ pub mod error;
 pub mod keycodes;
 pub mod menu;
 pub mod screen;
+pub mod tray;
 pub mod util;
 pub mod window;
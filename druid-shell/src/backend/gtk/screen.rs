@@ -14,6 +14,7 @@
 
 //! GTK Monitors and Screen information.
 
+use crate::scale::Scale;
 use crate::screen::Monitor;
 use gtk::gdk::{Display, DisplayManager, Rectangle};
 use kurbo::{Point, Rect, Size};
@@ -25,8 +26,9 @@ fn translate_gdk_rectangle(r: Rectangle) -> Rect {
     )
 }
 
-fn translate_gdk_monitor(mon: gtk::gdk::Monitor) -> Monitor {
+pub(crate) fn translate_gdk_monitor(mon: gtk::gdk::Monitor) -> Monitor {
     let area = translate_gdk_rectangle(mon.geometry());
+    let scale = mon.scale_factor() as f64;
     Monitor::new(
         mon.is_primary(),
         area,
@@ -34,6 +36,7 @@ fn translate_gdk_monitor(mon: gtk::gdk::Monitor) -> Monitor {
             .map(translate_gdk_rectangle)
             .unwrap_or(area),
     )
+    .with_scale(Scale::new(scale, scale))
 }
 
 pub(crate) fn get_monitors() -> Vec<Monitor> {
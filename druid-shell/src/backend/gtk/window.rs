@@ -19,6 +19,7 @@ use std::convert::TryInto;
 use std::ffi::c_void;
 use std::os::raw::{c_int, c_uint};
 use std::panic::Location;
+use std::path::PathBuf;
 use std::ptr;
 use std::slice;
 use std::sync::{Arc, Mutex, Weak};
@@ -30,14 +31,18 @@ use gtk::glib::source::Continue;
 use gtk::glib::translate::FromGlib;
 use gtk::prelude::*;
 use gtk::traits::SettingsExt;
-use gtk::{AccelGroup, ApplicationWindow, DrawingArea};
+use gtk::{
+    AccelGroup, ApplicationWindow, DestDefaults, DrawingArea, SelectionData, TargetEntry,
+    TargetFlags,
+};
 
 use gdk_sys::GdkKeymapKey;
 
 use anyhow::anyhow;
 use cairo::Surface;
 use gtk::gdk::{
-    EventKey, EventMask, EventType, ModifierType, ScrollDirection, Window, WindowTypeHint,
+    Atom, DragAction, DragContext, EventKey, EventMask, EventType, Geometry, Gravity, ModifierType,
+    ScrollDirection, Window, WindowHints, WindowTypeHint,
 };
 
 use instant::Duration;
@@ -50,16 +55,19 @@ use crate::kurbo::{Insets, Point, Rect, Size, Vec2};
 use crate::piet::{Piet, PietText, RenderContext};
 
 use crate::common_util::{ClickCounter, IdleCallback};
-use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo};
+use crate::dialog::{FileDialogOptions, FileDialogType};
 use crate::error::Error as ShellError;
+use crate::file_drop::FileDropEvent;
 use crate::keyboard::{KbKey, KeyEvent, KeyState, Modifiers};
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
 use crate::piet::ImageFormat;
 use crate::region::Region;
 use crate::scale::{Scalable, Scale, ScaledArea};
+use crate::screen::Monitor;
 use crate::text::{simulate_input, Event};
 use crate::window::{
-    self, FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowLevel,
+    self, FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowIcon,
+    WindowLevel,
 };
 
 use super::application::Application;
@@ -149,9 +157,12 @@ pub(crate) struct WindowBuilder {
     state: Option<window::WindowState>,
     size: Size,
     min_size: Option<Size>,
+    max_size: Option<Size>,
     resizable: bool,
     show_titlebar: bool,
     transparent: bool,
+    always_on_top: bool,
+    icon: Option<WindowIcon>,
 }
 
 #[derive(Clone)]
@@ -202,10 +213,30 @@ pub(crate) struct WindowState {
 
     request_animation: Cell<bool>,
     in_draw: Cell<bool>,
+    file_drag: RefCell<FileDrag>,
 
     parent: Option<crate::WindowHandle>,
 }
 
+/// The MIME type GTK uses for dragged files.
+const URI_LIST_TARGET: &str = "text/uri-list";
+
+/// The state of files being dragged over the window.
+///
+/// GTK only sends the paths of the files when we ask for them, and then
+/// asynchronously; until they arrive, the drag isn't accepted.
+#[derive(Default)]
+struct FileDrag {
+    /// The drag that this state belongs to.
+    context: Option<DragContext>,
+    /// The dragged files, once they were received.
+    paths: Option<Vec<PathBuf>>,
+    /// The last position of the pointer, in display points.
+    pos: Point,
+    /// Whether the files were dropped before they were received.
+    dropped: bool,
+}
+
 impl std::fmt::Debug for WindowState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         f.write_str("WindowState{")?;
@@ -230,9 +261,12 @@ impl WindowBuilder {
             level: None,
             state: None,
             min_size: None,
+            max_size: None,
             resizable: true,
             show_titlebar: true,
             transparent: false,
+            always_on_top: false,
+            icon: None,
         }
     }
 
@@ -248,6 +282,10 @@ impl WindowBuilder {
         self.min_size = Some(size);
     }
 
+    pub fn set_max_size(&mut self, size: Size) {
+        self.max_size = Some(size);
+    }
+
     pub fn resizable(&mut self, resizable: bool) {
         self.resizable = resizable;
     }
@@ -256,6 +294,10 @@ impl WindowBuilder {
         self.show_titlebar = show_titlebar;
     }
 
+    pub fn set_always_on_top(&mut self, on_top: bool) {
+        self.always_on_top = on_top;
+    }
+
     pub fn set_transparent(&mut self, transparent: bool) {
         self.transparent = transparent;
     }
@@ -280,6 +322,10 @@ impl WindowBuilder {
         self.menu = Some(menu);
     }
 
+    pub fn set_icon(&mut self, icon: WindowIcon) {
+        self.icon = Some(icon);
+    }
+
     pub fn build(self) -> Result<WindowHandle, ShellError> {
         let handler = self
             .handler
@@ -290,6 +336,10 @@ impl WindowBuilder {
         window.set_title(&self.title);
         window.set_resizable(self.resizable);
         window.set_decorated(self.show_titlebar);
+        window.set_keep_above(self.always_on_top);
+        if let Some(icon) = &self.icon {
+            window.set_icon_list(&make_pixbufs(icon));
+        }
         let mut transparent = false;
         if self.transparent {
             if let Some(screen) = window.screen() {
@@ -308,6 +358,24 @@ impl WindowBuilder {
 
         window.set_default_size(size_px.width as i32, size_px.height as i32);
 
+        if let Some(max_size_dp) = self.max_size {
+            let max_size_px = ScaledArea::from_dp(max_size_dp, scale).size_px();
+            let geometry = Geometry {
+                min_width: 0,
+                min_height: 0,
+                max_width: max_size_px.width.round() as i32,
+                max_height: max_size_px.height.round() as i32,
+                base_width: 0,
+                base_height: 0,
+                width_inc: 0,
+                height_inc: 0,
+                min_aspect: 0.0,
+                max_aspect: 0.0,
+                win_gravity: Gravity::NorthWest,
+            };
+            window.set_geometry_hints(None::<&gtk::Widget>, Some(&geometry), WindowHints::MAX_SIZE);
+        }
+
         let accel_group = AccelGroup::new();
         window.add_accel_group(&accel_group);
 
@@ -366,6 +434,7 @@ impl WindowBuilder {
             deferred_queue: RefCell::new(Vec::new()),
             request_animation: Cell::new(false),
             in_draw: Cell::new(false),
+            file_drag: RefCell::new(FileDrag::default()),
             parent,
         };
 
@@ -783,6 +852,82 @@ impl WindowBuilder {
                 }
             }));
 
+        // The settings are shared by all windows, so the handlers are removed
+        // along with the window.
+        if let Some(settings) = gtk::Settings::default() {
+            let ids = vec![
+                settings.connect_gtk_theme_name_notify(clone!(handle => move |_| {
+                    notify_color_scheme(&handle)
+                })),
+                settings.connect_gtk_application_prefer_dark_theme_notify(
+                    clone!(handle => move |_| notify_color_scheme(&handle)),
+                ),
+            ];
+            let handlers = RefCell::new(Some((settings, ids)));
+            win_state.drawing_area.connect_destroy(move |_widget| {
+                if let Some((settings, ids)) = handlers.take() {
+                    for id in ids {
+                        settings.disconnect(id);
+                    }
+                }
+            });
+        }
+
+        win_state.drawing_area.drag_dest_set(
+            DestDefaults::empty(),
+            &[TargetEntry::new(URI_LIST_TARGET, TargetFlags::OTHER_APP, 0)],
+            DragAction::COPY,
+        );
+
+        win_state.drawing_area.connect_drag_motion(
+            clone!(handle => move |widget, context, x, y, time| {
+                if let Some(state) = handle.state.upgrade() {
+                    let pos = Point::new(x as f64, y as f64).to_dp(state.scale.get());
+                    if state.start_file_drag(context, pos) {
+                        widget.drag_get_data(context, &Atom::intern(URI_LIST_TARGET), time);
+                    }
+                    state.file_drag_move(context, time);
+                }
+                true
+            }),
+        );
+
+        win_state.drawing_area.connect_drag_leave(
+            clone!(handle => move |_widget, _context, _time| {
+                if let Some(state) = handle.state.upgrade() {
+                    // GTK sends this before a drop as well, so we keep the paths around.
+                    state.with_handler(|h| h.file_drag_leave());
+                }
+            }),
+        );
+
+        win_state.drawing_area.connect_drag_drop(
+            clone!(handle => move |widget, context, x, y, time| {
+                if let Some(state) = handle.state.upgrade() {
+                    let pos = Point::new(x as f64, y as f64).to_dp(state.scale.get());
+                    if state.start_file_drag(context, pos) {
+                        widget.drag_get_data(context, &Atom::intern(URI_LIST_TARGET), time);
+                    }
+                    state.file_drag.borrow_mut().dropped = true;
+                    state.file_drop(context, time);
+                }
+                true
+            }),
+        );
+
+        win_state.drawing_area.connect_drag_data_received(
+            clone!(handle => move |_widget, context, _x, _y, selection, _info, time| {
+                if let Some(state) = handle.state.upgrade() {
+                    state.receive_dragged_files(context, selection);
+                    if state.file_drag.borrow().dropped {
+                        state.file_drop(context, time);
+                    } else {
+                        state.file_drag_move(context, time);
+                    }
+                }
+            }),
+        );
+
         vbox.pack_end(&win_state.drawing_area, true, true, 0);
         win_state.drawing_area.realize();
         win_state
@@ -840,6 +985,66 @@ impl WindowState {
         }
     }
 
+    /// Update the position of a file drag, and reset the state if it is a
+    /// new drag. Returns `true` if the dragged files have to be requested.
+    fn start_file_drag(&self, context: &DragContext, pos: Point) -> bool {
+        let mut drag = self.file_drag.borrow_mut();
+        drag.pos = pos;
+        if drag.context.as_ref() == Some(context) {
+            return false;
+        }
+        *drag = FileDrag {
+            context: Some(context.clone()),
+            pos,
+            ..FileDrag::default()
+        };
+        true
+    }
+
+    fn receive_dragged_files(&self, context: &DragContext, selection: &SelectionData) {
+        let mut drag = self.file_drag.borrow_mut();
+        if drag.context.as_ref() == Some(context) {
+            let paths = selection
+                .uris()
+                .iter()
+                .filter_map(|uri| gtk::glib::filename_from_uri(uri).ok())
+                .map(|(path, _)| path)
+                .collect();
+            drag.paths = Some(paths);
+        }
+    }
+
+    /// Returns the event for the current file drag, if the files were received.
+    fn file_drop_event(&self) -> Option<FileDropEvent> {
+        let drag = self.file_drag.borrow();
+        drag.paths.as_ref().map(|paths| FileDropEvent {
+            pos: drag.pos,
+            paths: paths.clone(),
+        })
+    }
+
+    fn file_drag_move(&self, context: &DragContext, time: u32) {
+        let accepted = self
+            .file_drop_event()
+            .and_then(|event| self.with_handler(|h| h.file_drag_move(&event)))
+            .unwrap_or(false);
+        let action = if accepted {
+            DragAction::COPY
+        } else {
+            DragAction::empty()
+        };
+        context.drag_status(action, time);
+    }
+
+    fn file_drop(&self, context: &DragContext, time: u32) {
+        // The files are dropped once they arrive.
+        if let Some(event) = self.file_drop_event() {
+            let accepted = self.with_handler(|h| h.file_drop(&event)).unwrap_or(false);
+            context.drag_finish(accepted, false, time);
+            self.file_drag.replace(FileDrag::default());
+        }
+    }
+
     fn resize_surface(&self, width: i32, height: i32) -> Result<(), anyhow::Error> {
         fn next_size(x: i32) -> i32 {
             // We round up to the nearest multiple of `accuracy`, which is between x/2 and x/4.
@@ -916,13 +1121,7 @@ impl WindowState {
                         FileDialogType::Open,
                         options,
                     ) {
-                        Ok(infos) => infos
-                            .iter()
-                            .map(|path| FileInfo {
-                                path: path.into(),
-                                format: None,
-                            })
-                            .collect(),
+                        Ok(infos) => infos,
                         Err(err) => {
                             tracing::error!("Error trying to open file: {}", err);
                             vec![]
@@ -941,12 +1140,9 @@ impl WindowState {
                         options,
                     )
                     .ok()
-                    .map(|s| FileInfo {
-                        // `get_file_dialog_path` guarantees that save dialogs
-                        // only return on path
-                        path: s.first().unwrap().into(),
-                        format: None,
-                    });
+                    // `get_file_dialog_path` guarantees that save dialogs
+                    // only return one path
+                    .and_then(|infos| infos.into_iter().next());
                     self.with_handler(|h| h.save_as(token, file_info));
                 }
                 DeferredOp::ContextMenu(menu, handle) => {
@@ -1062,6 +1258,13 @@ impl WindowHandle {
         }
     }
 
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        let state = self.state.upgrade()?;
+        let gdk_window = state.window.window()?;
+        let monitor = state.window.display().monitor_at_window(&gdk_window)?;
+        Some(super::screen::translate_gdk_monitor(monitor))
+    }
+
     pub fn set_window_state(&mut self, size_state: window::WindowState) {
         use window::WindowState::{Maximized, Minimized, Restored};
         let cur_size_state = self.get_window_state();
@@ -1096,6 +1299,30 @@ impl WindowHandle {
         warn!("WindowHandle::handle_titlebar is currently unimplemented for gtk.");
     }
 
+    pub fn begin_move_drag(&self) {
+        if let Some(state) = self.state.upgrade() {
+            // The drag has to start from the button press that is being handled.
+            let event = gtk::current_event();
+            match event
+                .as_ref()
+                .and_then(|e| Some((e.button()?, e.root_coords()?, e.time())))
+            {
+                Some((button, (x, y), time)) => {
+                    state
+                        .window
+                        .begin_move_drag(button as i32, x as i32, y as i32, time)
+                }
+                None => warn!("begin_move_drag called outside of a mouse press"),
+            }
+        }
+    }
+
+    pub fn set_always_on_top(&self, on_top: bool) {
+        if let Some(state) = self.state.upgrade() {
+            state.window.set_keep_above(on_top)
+        }
+    }
+
     /// Close the window.
     pub fn close(&self) {
         if let Some(state) = self.state.upgrade() {
@@ -1286,6 +1513,12 @@ impl WindowHandle {
             state.window.set_title(&(title.into()));
         }
     }
+
+    pub fn set_icon(&self, icon: &WindowIcon) {
+        if let Some(state) = self.state.upgrade() {
+            state.window.set_icon_list(&make_pixbufs(icon));
+        }
+    }
 }
 
 // WindowState needs to be Send + Sync so it can be passed into glib closures.
@@ -1357,6 +1590,37 @@ fn run_idle(state: &Arc<WindowState>) -> Continue {
     Continue(false)
 }
 
+fn notify_color_scheme(handle: &WindowHandle) {
+    if let Some(state) = handle.state.upgrade() {
+        let scheme = Application::get_color_scheme();
+        state.with_handler(|h| h.color_scheme_changed(scheme));
+    }
+}
+
+/// Convert the images of an icon to pixbufs, which don't premultiply alpha.
+fn make_pixbufs(icon: &WindowIcon) -> Vec<Pixbuf> {
+    icon.images()
+        .iter()
+        .map(|image| {
+            let (width, height) = (image.width(), image.height());
+            let mut rgba = Vec::with_capacity(width * height * 4);
+            for color in image.pixel_colors().flatten() {
+                let (r, g, b, a) = color.as_rgba8();
+                rgba.extend_from_slice(&[r, g, b, a]);
+            }
+            Pixbuf::from_mut_slice(
+                rgba,
+                Rgb,
+                true,
+                8,
+                width as i32,
+                height as i32,
+                (width * 4) as i32,
+            )
+        })
+        .collect()
+}
+
 fn make_gdk_cursor(cursor: &Cursor, gdk_window: &Window) -> Option<gtk::gdk::Cursor> {
     if let Cursor::Custom(custom) = cursor {
         Some(custom.0.clone())
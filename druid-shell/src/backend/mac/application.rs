@@ -21,14 +21,14 @@ use std::ffi::c_void;
 use std::rc::Rc;
 
 use cocoa::appkit::{NSApp, NSApplication, NSApplicationActivationPolicyRegular};
-use cocoa::base::{id, nil, NO, YES};
+use cocoa::base::{id, nil, BOOL, NO, YES};
 use cocoa::foundation::{NSArray, NSAutoreleasePool};
 use lazy_static::lazy_static;
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
 use objc::{class, msg_send, sel, sel_impl};
 
-use crate::application::AppHandler;
+use crate::application::{AppHandler, ColorScheme};
 
 use super::clipboard::Clipboard;
 use super::error::Error;
@@ -118,6 +118,31 @@ impl Application {
             locale
         }
     }
+
+    pub fn get_color_scheme() -> ColorScheme {
+        unsafe {
+            let app = NSApp();
+            // dark mode was introduced in macOS 10.14, with this method
+            let supported: BOOL = msg_send![app, respondsToSelector: sel!(effectiveAppearance)];
+            if supported == NO {
+                return ColorScheme::Light;
+            }
+            let appearance: id = msg_send![app, effectiveAppearance];
+            let names = NSArray::arrayWithObjects(
+                nil,
+                &[
+                    util::make_nsstring("NSAppearanceNameAqua"),
+                    util::make_nsstring("NSAppearanceNameDarkAqua"),
+                ],
+            );
+            let best: id = msg_send![appearance, bestMatchFromAppearancesWithNames: names];
+            if best != nil && util::from_nsstring(best) == "NSAppearanceNameDarkAqua" {
+                ColorScheme::Dark
+            } else {
+                ColorScheme::Light
+            }
+        }
+    }
 }
 
 impl crate::platform::mac::ApplicationExt for crate::Application {
@@ -20,7 +20,11 @@ use cocoa::foundation::{NSArray, NSInteger, NSUInteger};
 use objc::{class, msg_send, sel, sel_impl};
 
 use super::util;
-use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::clipboard::{decode_png, encode_png, ClipboardFormat, FormatId};
+use crate::piet::ImageBuf;
+
+/// `NSBitmapImageFileTypePNG`
+const PNG_FILE_TYPE: NSUInteger = 4;
 
 #[derive(Debug, Clone, Default)]
 pub struct Clipboard;
@@ -83,6 +87,47 @@ impl Clipboard {
         }
     }
 
+    /// Put an image onto the system clipboard, as a PNG.
+    pub fn put_image(&mut self, image: &ImageBuf) {
+        if let Some(png) = encode_png(image) {
+            self.put_formats(&[ClipboardFormat::new(ClipboardFormat::PNG, png)]);
+        }
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    ///
+    /// Images that are not PNGs, such as the TIFFs that many applications
+    /// use, are converted by `NSImage`.
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        if let Some(png) = self.get_format(ClipboardFormat::PNG) {
+            return decode_png(&png);
+        }
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            let image: id = msg_send![class!(NSImage), alloc];
+            let image: id = msg_send![image, initWithPasteboard: pasteboard];
+            if image.is_null() {
+                return None;
+            }
+            let tiff: id = msg_send![image, TIFFRepresentation];
+            let _: () = msg_send![image, release];
+            if tiff.is_null() {
+                return None;
+            }
+            let rep: id = msg_send![class!(NSBitmapImageRep), imageRepWithData: tiff];
+            if rep.is_null() {
+                return None;
+            }
+            let properties: id = msg_send![class!(NSDictionary), dictionary];
+            let png: id =
+                msg_send![rep, representationUsingType: PNG_FILE_TYPE properties: properties];
+            if png.is_null() {
+                return None;
+            }
+            decode_png(&util::from_nsdata(png))
+        }
+    }
+
     /// Given a list of supported clipboard types, returns the supported type which has
     /// highest priority on the system clipboard, or `None` if no types are supported.
     pub fn preferred_format(&self, formats: &[FormatId]) -> Option<FormatId> {
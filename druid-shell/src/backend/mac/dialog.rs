@@ -54,6 +54,27 @@ pub(crate) unsafe fn get_file_info(
     }
 }
 
+/// The files chosen in an open panel that allows multiple selection.
+pub(crate) unsafe fn get_file_infos(panel: id, result: NSModalResponse) -> Vec<FileInfo> {
+    match result {
+        NSModalResponseOK => {
+            let urls: id = msg_send![panel, URLs];
+            (0..urls.count())
+                .map(|i| {
+                    let path: id = msg_send![urls.objectAtIndex(i), path];
+                    let path: OsString = from_nsstring(path).into();
+                    FileInfo {
+                        path: path.into(),
+                        format: None,
+                    }
+                })
+                .collect()
+        }
+        NSModalResponseCancel => Vec::new(),
+        _ => unreachable!(),
+    }
+}
+
 #[allow(clippy::cognitive_complexity)]
 pub(crate) unsafe fn build_panel(ty: FileDialogType, mut options: FileDialogOptions) -> id {
     let panel: id = match ty {
@@ -25,5 +25,6 @@ mod keyboard;
 pub mod menu;
 pub mod screen;
 pub mod text_input;
+pub mod tray;
 pub mod util;
 pub mod window;
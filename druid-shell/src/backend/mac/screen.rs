@@ -15,6 +15,7 @@
 //! macOS Monitors and Screen information.
 
 use crate::kurbo::Rect;
+use crate::scale::Scale;
 use crate::screen::Monitor;
 use cocoa::appkit::NSScreen;
 use cocoa::base::id;
@@ -25,6 +26,7 @@ pub(crate) fn get_monitors() -> Vec<Monitor> {
     unsafe {
         let screens: id = msg_send![class![NSScreen], screens];
         let mut monitors = Vec::<(Rect, Rect)>::new();
+        let mut scales = Vec::new();
         let mut total_rect = Rect::ZERO;
 
         for idx in 0..screens.count() {
@@ -41,11 +43,17 @@ pub(crate) fn get_monitors() -> Vec<Monitor> {
                 (vis_frame.size.width, vis_frame.size.height),
             );
             monitors.push((frame_r, vis_frame_r));
+            let scale: f64 = msg_send![screen, backingScaleFactor];
+            scales.push(Scale::new(scale, scale));
             total_rect = total_rect.union(frame_r)
         }
         // TODO save this total_rect.y1 for screen coord transformations in get_position/set_position
         // and invalidate on monitor changes
         transform_coords(monitors, total_rect.y1)
+            .into_iter()
+            .zip(scales)
+            .map(|(monitor, scale)| monitor.with_scale(scale))
+            .collect()
     }
 }
 
@@ -0,0 +1,280 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! macOS implementation of tray icons, using status items in the menu bar.
+
+use std::cell::{Cell, RefCell};
+use std::ffi::c_void;
+use std::rc::Rc;
+
+use cocoa::appkit::{NSApp, NSEvent, NSEventModifierFlags, NSEventType};
+use cocoa::base::{id, nil, BOOL, NO, YES};
+use cocoa::foundation::{NSInteger, NSSize};
+use lazy_static::lazy_static;
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+
+use super::menu::Menu;
+use super::util::make_nsstring;
+use crate::error::Error;
+use crate::piet::ImageBuf;
+use crate::tray::TrayHandler;
+
+const TRAY_STATE_IVAR: &str = "trayState";
+
+/// `NSVariableStatusItemLength`
+const VARIABLE_LENGTH: f64 = -1.0;
+
+/// The height of a status item image, in points.
+const ICON_HEIGHT: f64 = 18.0;
+
+// `NSEventMaskLeftMouseUp | NSEventMaskRightMouseUp`
+const ACTION_MASK: u64 = (1 << 2) | (1 << 4);
+
+pub struct TrayIcon {
+    status_item: id,
+    target: id,
+    state: Rc<TrayState>,
+}
+
+/// State shared with the object receiving the actions of the status item.
+struct TrayState {
+    handler: RefCell<Box<dyn TrayHandler>>,
+    status_item: id,
+    menu: Cell<id>,
+}
+
+// Wrap pointer because lazy_static requires Sync.
+struct TargetClass(*const Class);
+unsafe impl Sync for TargetClass {}
+
+lazy_static! {
+    static ref TARGET_CLASS: TargetClass = unsafe {
+        let mut decl =
+            ClassDecl::new("DruidTrayTarget", class!(NSObject)).expect("Tray target class defined");
+        decl.add_ivar::<*mut c_void>(TRAY_STATE_IVAR);
+        decl.add_method(
+            sel!(handleTrayAction:),
+            handle_tray_action as extern "C" fn(&mut Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(handleMenuItem:),
+            handle_menu_item as extern "C" fn(&mut Object, Sel, id),
+        );
+        TargetClass(decl.register())
+    };
+}
+
+impl TrayIcon {
+    pub fn new(handler: Box<dyn TrayHandler>) -> Result<TrayIcon, Error> {
+        unsafe {
+            let status_bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+            let status_item: id = msg_send![status_bar, statusItemWithLength: VARIABLE_LENGTH];
+            let () = msg_send![status_item, retain];
+
+            let state = Rc::new(TrayState {
+                handler: RefCell::new(handler),
+                status_item,
+                menu: Cell::new(nil),
+            });
+            let target: id = msg_send![TARGET_CLASS.0, new];
+            (*target).set_ivar(TRAY_STATE_IVAR, Rc::into_raw(state.clone()) as *mut c_void);
+
+            let button: id = msg_send![status_item, button];
+            let () = msg_send![button, setTarget: target];
+            let () = msg_send![button, setAction: sel!(handleTrayAction:)];
+            let _: NSInteger = msg_send![button, sendActionOn: ACTION_MASK];
+
+            Ok(TrayIcon {
+                status_item,
+                target,
+                state,
+            })
+        }
+    }
+
+    pub fn set_icon(&self, icon: &ImageBuf) {
+        unsafe {
+            let image = make_nsimage(icon);
+            // Template images adapt to the appearance of the menu bar, but
+            // would turn colored icons into silhouettes.
+            let () = msg_send![image, setTemplate: NO];
+            let button: id = msg_send![self.status_item, button];
+            let () = msg_send![button, setImage: image];
+        }
+    }
+
+    pub fn set_tooltip(&self, tooltip: &str) {
+        unsafe {
+            let button: id = msg_send![self.status_item, button];
+            let () = msg_send![button, setToolTip: make_nsstring(tooltip)];
+        }
+    }
+
+    pub fn set_menu(&self, menu: Option<Menu>) {
+        unsafe {
+            let new = match menu {
+                Some(menu) => {
+                    set_item_targets(menu.menu, self.target);
+                    let () = msg_send![menu.menu, retain];
+                    menu.menu
+                }
+                None => nil,
+            };
+            let old = self.state.menu.replace(new);
+            if old != nil {
+                let () = msg_send![old, release];
+            }
+        }
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        unsafe {
+            self.set_menu(None);
+            let status_bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+            let () = msg_send![status_bar, removeStatusItem: self.status_item];
+            let () = msg_send![self.status_item, release];
+
+            let state: *mut c_void = *(*self.target).get_ivar(TRAY_STATE_IVAR);
+            (*self.target).set_ivar(TRAY_STATE_IVAR, std::ptr::null_mut::<c_void>());
+            drop(Rc::from_raw(state as *const TrayState));
+            let () = msg_send![self.target, release];
+        }
+    }
+}
+
+impl TrayState {
+    fn with_handler(&self, f: impl FnOnce(&mut dyn TrayHandler)) {
+        match self.handler.try_borrow_mut() {
+            Ok(mut handler) => f(&mut **handler),
+            Err(_) => tracing::error!("tray handler borrowed recursively"),
+        }
+    }
+
+    unsafe fn show_menu(&self) {
+        let menu = self.menu.get();
+        if menu == nil {
+            return;
+        }
+        // Setting the menu permanently would stop us from receiving clicks,
+        // so it is only attached while it is open.
+        let button: id = msg_send![self.status_item, button];
+        let () = msg_send![self.status_item, setMenu: menu];
+        let () = msg_send![button, performClick: nil];
+        let () = msg_send![self.status_item, setMenu: nil];
+    }
+}
+
+/// Get the state of the tray icon, keeping it alive even if the handler drops the icon.
+unsafe fn tray_state(this: &Object) -> Option<Rc<TrayState>> {
+    let state: *mut c_void = *this.get_ivar(TRAY_STATE_IVAR);
+    if state.is_null() {
+        return None;
+    }
+    let state = state as *const TrayState;
+    Rc::increment_strong_count(state);
+    Some(Rc::from_raw(state))
+}
+
+extern "C" fn handle_tray_action(this: &mut Object, _: Sel, _sender: id) {
+    unsafe {
+        let state = match tray_state(this) {
+            Some(state) => state,
+            None => return,
+        };
+        let event: id = msg_send![NSApp(), currentEvent];
+        let is_right = event.eventType() == NSEventType::NSRightMouseUp
+            || event
+                .modifierFlags()
+                .contains(NSEventModifierFlags::NSControlKeyMask);
+        if is_right {
+            state.show_menu();
+        } else if event.clickCount() == 2 {
+            state.with_handler(|handler| handler.double_clicked());
+        } else {
+            state.with_handler(|handler| handler.clicked());
+        }
+    }
+}
+
+extern "C" fn handle_menu_item(this: &mut Object, _: Sel, item: id) {
+    unsafe {
+        if let Some(state) = tray_state(this) {
+            let tag: isize = msg_send![item, tag];
+            state.with_handler(|handler| handler.command(tag as u32));
+        }
+    }
+}
+
+/// Menu items send their action up the responder chain by default, which
+/// doesn't reach the tray icon; point them at our target instead.
+unsafe fn set_item_targets(menu: id, target: id) {
+    let count: NSInteger = msg_send![menu, numberOfItems];
+    for i in 0..count {
+        let item: id = msg_send![menu, itemAtIndex: i];
+        let has_submenu: BOOL = msg_send![item, hasSubmenu];
+        if has_submenu == YES {
+            let submenu: id = msg_send![item, submenu];
+            set_item_targets(submenu, target);
+        } else {
+            let () = msg_send![item, setTarget: target];
+        }
+    }
+}
+
+/// Create an `NSImage` from an image, scaled to the height of the menu bar.
+unsafe fn make_nsimage(image: &ImageBuf) -> id {
+    let size = NSSize::new(
+        ICON_HEIGHT * image.width() as f64 / image.height().max(1) as f64,
+        ICON_HEIGHT,
+    );
+    let nsimage: id = msg_send![class!(NSImage), alloc];
+    let nsimage: id = msg_send![nsimage, initWithSize: size];
+    let rep = make_image_rep(image);
+    let () = msg_send![nsimage, addRepresentation: rep];
+    let () = msg_send![rep, release];
+    let () = msg_send![nsimage, autorelease];
+    nsimage
+}
+
+/// Create an `NSBitmapImageRep` from an image, which the caller owns.
+pub(super) unsafe fn make_image_rep(image: &ImageBuf) -> id {
+    let width = image.width();
+    let height = image.height();
+    let rep: id = msg_send![class!(NSBitmapImageRep), alloc];
+    let rep: id = msg_send![rep,
+        initWithBitmapDataPlanes: std::ptr::null_mut::<*mut u8>()
+        pixelsWide: width as NSInteger
+        pixelsHigh: height as NSInteger
+        bitsPerSample: 8 as NSInteger
+        samplesPerPixel: 4 as NSInteger
+        hasAlpha: YES
+        isPlanar: NO
+        colorSpaceName: make_nsstring("NSDeviceRGBColorSpace")
+        bytesPerRow: (width * 4) as NSInteger
+        bitsPerPixel: 32 as NSInteger];
+    let data: *mut u8 = msg_send![rep, bitmapData];
+    let data = std::slice::from_raw_parts_mut(data, width * height * 4);
+    let pixels = image.pixel_colors().flatten();
+    for (dest, color) in data.chunks_exact_mut(4).zip(pixels) {
+        // The bitmap uses premultiplied alpha.
+        let (r, g, b, a) = color.as_rgba8();
+        let premul = |c: u8| (c as u16 * a as u16 / 255) as u8;
+        dest.copy_from_slice(&[premul(r), premul(g), premul(b), a]);
+    }
+    rep
+}
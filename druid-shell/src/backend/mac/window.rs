@@ -24,7 +24,8 @@ use std::time::Instant;
 use block::ConcreteBlock;
 use cocoa::appkit::{
     CGFloat, NSApp, NSApplication, NSAutoresizingMaskOptions, NSBackingStoreBuffered, NSColor,
-    NSEvent, NSView, NSViewHeightSizable, NSViewWidthSizable, NSWindow, NSWindowStyleMask,
+    NSEvent, NSFilenamesPboardType, NSView, NSViewHeightSizable, NSViewWidthSizable, NSWindow,
+    NSWindowStyleMask,
 };
 use cocoa::base::{id, nil, BOOL, NO, YES};
 use cocoa::foundation::{
@@ -53,16 +54,20 @@ use super::dialog;
 use super::keyboard::{make_modifiers, KeyboardState};
 use super::menu::Menu;
 use super::text_input::NSRange;
-use super::util::{assert_main_thread, make_nsstring};
+use super::tray::make_image_rep;
+use super::util::{assert_main_thread, from_nsstring, make_nsstring};
 use crate::common_util::IdleCallback;
 use crate::dialog::{FileDialogOptions, FileDialogType};
+use crate::file_drop::FileDropEvent;
 use crate::keyboard_types::KeyState;
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
 use crate::region::Region;
 use crate::scale::Scale;
+use crate::screen::{Monitor, Screen};
 use crate::text::{Event, InputHandler};
 use crate::window::{
-    FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowLevel, WindowState,
+    FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowIcon, WindowLevel,
+    WindowState,
 };
 use crate::Error;
 
@@ -138,12 +143,15 @@ pub(crate) struct WindowBuilder {
     menu: Option<Menu>,
     size: Size,
     min_size: Option<Size>,
+    max_size: Option<Size>,
     position: Option<Point>,
     level: Option<WindowLevel>,
     window_state: Option<WindowState>,
     resizable: bool,
     show_titlebar: bool,
     transparent: bool,
+    always_on_top: bool,
+    icon: Option<WindowIcon>,
 }
 
 #[derive(Clone)]
@@ -192,12 +200,15 @@ impl WindowBuilder {
             menu: None,
             size: Size::new(500., 400.),
             min_size: None,
+            max_size: None,
             position: None,
             level: None,
             window_state: None,
             resizable: true,
             show_titlebar: true,
             transparent: false,
+            always_on_top: false,
+            icon: None,
         }
     }
 
@@ -213,6 +224,10 @@ impl WindowBuilder {
         self.min_size = Some(size);
     }
 
+    pub fn set_max_size(&mut self, size: Size) {
+        self.max_size = Some(size);
+    }
+
     pub fn resizable(&mut self, resizable: bool) {
         self.resizable = resizable;
     }
@@ -221,6 +236,10 @@ impl WindowBuilder {
         self.show_titlebar = show_titlebar;
     }
 
+    pub fn set_always_on_top(&mut self, on_top: bool) {
+        self.always_on_top = on_top;
+    }
+
     pub fn set_transparent(&mut self, transparent: bool) {
         self.transparent = transparent;
     }
@@ -245,6 +264,10 @@ impl WindowBuilder {
         self.menu = Some(menu);
     }
 
+    pub fn set_icon(&mut self, icon: WindowIcon) {
+        self.icon = Some(icon);
+    }
+
     pub fn build(self) -> Result<WindowHandle, Error> {
         assert_main_thread();
         unsafe {
@@ -278,6 +301,11 @@ impl WindowBuilder {
                 window.setContentMinSize_(size);
             }
 
+            if let Some(max_size) = self.max_size {
+                let size = NSSize::new(max_size.width, max_size.height);
+                window.setContentMaxSize_(size);
+            }
+
             if self.transparent {
                 window.setOpaque_(NO);
                 window.setBackgroundColor_(NSColor::clearColor(nil));
@@ -291,6 +319,12 @@ impl WindowBuilder {
             view.initWithFrame_(frame);
 
             let () = msg_send![window, setDelegate: view];
+            let drag_types = NSArray::arrayWithObject(nil, NSFilenamesPboardType);
+            let () = msg_send![view, registerForDraggedTypes: drag_types];
+
+            if let Some(icon) = &self.icon {
+                set_application_icon(icon);
+            }
 
             if let Some(menu) = self.menu {
                 NSApp().setMainMenu_(menu.menu);
@@ -318,6 +352,10 @@ impl WindowBuilder {
                 handle.set_level(level);
             }
 
+            if self.always_on_top {
+                handle.set_always_on_top(true);
+            }
+
             // set_window_state above could have invalidated the frame size
             let frame = NSView::frame(content_view);
 
@@ -377,6 +415,10 @@ lazy_static! {
             }
         }
 
+        decl.add_method(
+            sel!(viewDidChangeEffectiveAppearance),
+            view_did_change_effective_appearance as extern "C" fn(&mut Object, Sel),
+        );
         decl.add_method(
             sel!(windowDidBecomeKey:),
             window_did_become_key as extern "C" fn(&mut Object, Sel, id),
@@ -441,6 +483,22 @@ lazy_static! {
             sel!(magnifyWithEvent:),
             pinch_event as extern "C" fn(&mut Object, Sel, id),
         );
+        decl.add_method(
+            sel!(draggingEntered:),
+            dragging_updated as extern "C" fn(&mut Object, Sel, id) -> NSUInteger,
+        );
+        decl.add_method(
+            sel!(draggingUpdated:),
+            dragging_updated as extern "C" fn(&mut Object, Sel, id) -> NSUInteger,
+        );
+        decl.add_method(
+            sel!(draggingExited:),
+            dragging_exited as extern "C" fn(&mut Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(performDragOperation:),
+            perform_drag_operation as extern "C" fn(&mut Object, Sel, id) -> BOOL,
+        );
         decl.add_method(
             sel!(keyDown:),
             key_down as extern "C" fn(&mut Object, Sel, id),
@@ -788,6 +846,65 @@ extern "C" fn scroll_wheel(this: &mut Object, _: Sel, nsevent: id) {
     }
 }
 
+/// `NSDragOperationNone` and `NSDragOperationCopy`.
+const DRAG_OPERATION_NONE: NSUInteger = 0;
+const DRAG_OPERATION_COPY: NSUInteger = 1;
+
+/// Create a [`FileDropEvent`] from an `NSDraggingInfo`.
+fn file_drop_event(sender: id, view: id) -> FileDropEvent {
+    unsafe {
+        let point: NSPoint = msg_send![sender, draggingLocation];
+        let view_point = view.convertPoint_fromView_(point, nil);
+        let pasteboard: id = msg_send![sender, draggingPasteboard];
+        let filenames: id = msg_send![pasteboard, propertyListForType: NSFilenamesPboardType];
+        let paths = if filenames == nil {
+            Vec::new()
+        } else {
+            (0..filenames.count())
+                .map(|i| from_nsstring(filenames.objectAtIndex(i)).into())
+                .collect()
+        };
+        FileDropEvent {
+            pos: Point::new(view_point.x as f64, view_point.y as f64),
+            paths,
+        }
+    }
+}
+
+extern "C" fn dragging_updated(this: &mut Object, _: Sel, sender: id) -> NSUInteger {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        let event = file_drop_event(sender, this as id);
+        if (*view_state).handler.file_drag_move(&event) {
+            DRAG_OPERATION_COPY
+        } else {
+            DRAG_OPERATION_NONE
+        }
+    }
+}
+
+extern "C" fn dragging_exited(this: &mut Object, _: Sel, _sender: id) {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        (*view_state).handler.file_drag_leave();
+    }
+}
+
+extern "C" fn perform_drag_operation(this: &mut Object, _: Sel, sender: id) -> BOOL {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        let event = file_drop_event(sender, this as id);
+        if (*view_state).handler.file_drop(&event) {
+            YES
+        } else {
+            NO
+        }
+    }
+}
+
 extern "C" fn pinch_event(this: &mut Object, _: Sel, nsevent: id) {
     unsafe {
         let view_state: *mut c_void = *this.get_ivar("viewState");
@@ -981,6 +1098,15 @@ extern "C" fn window_did_become_key(this: &mut Object, _: Sel, _notification: id
     }
 }
 
+extern "C" fn view_did_change_effective_appearance(this: &mut Object, _: Sel) {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        let scheme = crate::Application::get_color_scheme();
+        (*view_state).handler.color_scheme_changed(scheme);
+    }
+}
+
 extern "C" fn window_did_resign_key(this: &mut Object, _: Sel, _notification: id) {
     unsafe {
         let view_state: *mut c_void = *this.get_ivar("viewState");
@@ -1193,15 +1319,20 @@ impl WindowHandle {
         unsafe {
             let panel = dialog::build_panel(ty, opts.clone());
             let block = ConcreteBlock::new(move |response: dialog::NSModalResponse| {
-                let url = dialog::get_file_info(panel, opts.clone(), response);
                 let view = self_clone.nsview.load();
                 if let Some(view) = (*view).as_ref() {
                     let view_state: *mut c_void = *view.get_ivar("viewState");
                     let view_state = &mut *(view_state as *mut ViewState);
-                    if ty == FileDialogType::Open {
-                        (*view_state).handler.open_file(token, url);
-                    } else if ty == FileDialogType::Save {
-                        (*view_state).handler.save_as(token, url);
+                    if ty == FileDialogType::Open && opts.multi_selection {
+                        let urls = dialog::get_file_infos(panel, response);
+                        (*view_state).handler.open_files(token, urls);
+                    } else {
+                        let url = dialog::get_file_info(panel, opts.clone(), response);
+                        if ty == FileDialogType::Open {
+                            (*view_state).handler.open_file(token, url);
+                        } else if ty == FileDialogType::Save {
+                            (*view_state).handler.save_as(token, url);
+                        }
                     }
                 }
             });
@@ -1314,6 +1445,11 @@ impl WindowHandle {
         }
     }
 
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        let rect = Rect::from_origin_size(self.get_position(), self.get_size());
+        Screen::get_monitor_for_rect(rect)
+    }
+
     pub fn get_window_state(&self) -> WindowState {
         unsafe {
             let window: id = msg_send![*self.nsview.load(), window];
@@ -1356,6 +1492,29 @@ impl WindowHandle {
         tracing::warn!("WindowHandle::handle_titlebar is currently unimplemented for Mac.");
     }
 
+    pub fn begin_move_drag(&self) {
+        unsafe {
+            let window: id = msg_send![*self.nsview.load(), window];
+            // The drag has to start from the mouse down that is being handled.
+            let event: id = msg_send![NSApp(), currentEvent];
+            if event != nil {
+                let () = msg_send![window, performWindowDragWithEvent: event];
+            }
+        }
+    }
+
+    pub fn set_always_on_top(&self, on_top: bool) {
+        let level = if on_top {
+            levels::NSFloatingWindowLevel
+        } else {
+            levels::NSNormalWindowLevel
+        };
+        unsafe {
+            let window: id = msg_send![*self.nsview.load(), window];
+            let () = msg_send![window, setLevel: level];
+        }
+    }
+
     pub fn resizable(&self, resizable: bool) {
         unsafe {
             let window: id = msg_send![*self.nsview.load(), window];
@@ -1377,6 +1536,12 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_icon(&self, icon: &WindowIcon) {
+        unsafe {
+            set_application_icon(icon);
+        }
+    }
+
     //FIXME: we should be using the x, y values passed by the caller, but then
     //we have to figure out some way to pass them along with this performSelector:
     //call. This isn't super hard, I'm just not up for it right now.
@@ -1463,6 +1628,30 @@ impl IdleHandle {
 /// of seconds from now.
 ///
 /// This may lose some precision for multi-month durations.
+/// Set the icon of the application in the dock, as windows don't have icons on macOS.
+///
+/// The icon lasts until the application quits; an icon without images restores
+/// the one from the application bundle.
+unsafe fn set_application_icon(icon: &WindowIcon) {
+    let images = icon.images();
+    let nsimage = match images.iter().max_by_key(|image| image.width()) {
+        Some(largest) => {
+            let size = NSSize::new(largest.width() as f64, largest.height() as f64);
+            let nsimage: id = msg_send![class!(NSImage), alloc];
+            let nsimage: id = msg_send![nsimage, initWithSize: size];
+            for image in images {
+                let rep = make_image_rep(image);
+                let () = msg_send![nsimage, addRepresentation: rep];
+                let () = msg_send![rep, release];
+            }
+            let () = msg_send![nsimage, autorelease];
+            nsimage
+        }
+        None => nil,
+    };
+    let () = msg_send![NSApp(), setApplicationIconImage: nsimage];
+}
+
 fn time_interval_from_deadline(deadline: std::time::Instant) -> f64 {
     let now = Instant::now();
     if now >= deadline {
@@ -19,7 +19,7 @@ use super::{
     window::WindowHandle,
 };
 
-use crate::{backend, mouse, AppHandler, TimerToken};
+use crate::{backend, mouse, AppHandler, ColorScheme, TimerToken};
 
 use calloop;
 
@@ -396,6 +396,11 @@ impl Application {
     pub fn get_locale() -> String {
         linux::env::locale()
     }
+
+    pub fn get_color_scheme() -> ColorScheme {
+        // TODO: read the preference from the freedesktop settings portal
+        ColorScheme::NoPreference
+    }
 }
 
 impl surfaces::Compositor for Data {
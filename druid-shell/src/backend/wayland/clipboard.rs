@@ -15,7 +15,8 @@
 //! Interactions with the system pasteboard on wayland compositors.
 use super::application;
 use super::error as waylanderr;
-use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::clipboard::{decode_png, encode_png, ClipboardFormat, FormatId};
+use crate::piet::ImageBuf;
 use std::io::Read;
 use wayland_client as wl;
 use wayland_client::protocol::wl_data_device;
@@ -260,6 +261,18 @@ impl Clipboard {
             )
     }
 
+    /// Put an image onto the system clipboard, as a PNG.
+    pub fn put_image(&mut self, image: &ImageBuf) {
+        if let Some(png) = encode_png(image) {
+            self.put_formats(&[ClipboardFormat::new(ClipboardFormat::PNG, png)]);
+        }
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        decode_png(&self.get_format(ClipboardFormat::PNG)?)
+    }
+
     /// Given a list of supported clipboard types, returns the supported type which has
     /// highest priority on the system clipboard, or `None` if no types are supported.
     pub fn preferred_format(&self, _formats: &[FormatId]) -> Option<FormatId> {
@@ -25,6 +25,7 @@ mod outputs;
 pub mod pointers;
 pub mod screen;
 pub mod surfaces;
+pub mod tray;
 pub mod window;
 
 /// Little enum to make it clearer what some return values mean.
@@ -27,7 +27,7 @@ fn _get_monitors() -> Result<Vec<Monitor>, error::Error> {
                 (m.position.x as f64, m.position.y as f64),
                 (m.logical.width as f64, m.logical.height as f64),
             );
-            Monitor::new(false, rect, rect)
+            Monitor::new(false, rect, rect).with_scale(crate::Scale::new(m.scale, m.scale))
         })
         .collect();
     Ok(monitors)
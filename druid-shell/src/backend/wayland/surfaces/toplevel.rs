@@ -42,6 +42,7 @@ impl Surface {
         handler: Box<dyn window::WinHandler>,
         initial_size: kurbo::Size,
         min_size: Option<kurbo::Size>,
+        max_size: Option<kurbo::Size>,
     ) -> Self {
         let compositor = CompositorHandle::new(c);
         let wl_surface = surface::Surface::new(compositor.clone(), handler, kurbo::Size::ZERO);
@@ -116,6 +117,11 @@ impl Surface {
                 .xdg_toplevel
                 .set_min_size(size.width as i32, size.height as i32);
         }
+        if let Some(size) = max_size {
+            inner
+                .xdg_toplevel
+                .set_max_size(size.width as i32, size.height as i32);
+        }
 
         let handle = Self {
             inner: std::sync::Arc::new(inner),
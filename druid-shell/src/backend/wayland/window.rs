@@ -28,6 +28,7 @@ use crate::{
     mouse::{Cursor, CursorDesc},
     piet::PietText,
     scale::Scale,
+    screen::{Monitor, Screen},
     text::Event,
     window::{self, FileDialogToken, TimerToken, WinHandler, WindowLevel},
     TextFieldToken,
@@ -127,6 +128,11 @@ impl WindowHandle {
         self.inner.surface.get_size()
     }
 
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        let rect = Rect::from_origin_size(self.get_position(), self.get_size());
+        Screen::get_monitor_for_rect(rect)
+    }
+
     pub fn set_window_state(&mut self, _current_state: window::WindowState) {
         tracing::warn!("set_window_state is unimplemented on wayland");
     }
@@ -140,6 +146,14 @@ impl WindowHandle {
         tracing::warn!("handle_titlebar is unimplemented on wayland");
     }
 
+    pub fn begin_move_drag(&self) {
+        tracing::warn!("begin_move_drag is unimplemented on wayland");
+    }
+
+    pub fn set_always_on_top(&self, _on_top: bool) {
+        tracing::warn!("set_always_on_top is unimplemented on wayland");
+    }
+
     /// Close the window.
     pub fn close(&self) {
         if let Some(appdata) = self.inner.appdata.upgrade() {
@@ -275,6 +289,10 @@ impl WindowHandle {
         self.inner.decor.set_title(title);
     }
 
+    pub fn set_icon(&self, _icon: &window::WindowIcon) {
+        tracing::warn!("set_icon not implemented for wayland");
+    }
+
     pub(super) fn run_idle(&self) {
         self.inner.surface.run_idle();
     }
@@ -320,6 +338,7 @@ pub(crate) struct WindowBuilder {
     // pre-scaled
     size: Size,
     min_size: Option<Size>,
+    max_size: Option<Size>,
     resizable: bool,
     show_titlebar: bool,
 }
@@ -336,6 +355,7 @@ impl WindowBuilder {
             level: WindowLevel::AppWindow,
             state: None,
             min_size: None,
+            max_size: None,
             resizable: true,
             show_titlebar: true,
         }
@@ -353,6 +373,14 @@ impl WindowBuilder {
         self.min_size = Some(size);
     }
 
+    pub fn set_max_size(&mut self, size: Size) {
+        self.max_size = Some(size);
+    }
+
+    pub fn set_always_on_top(&mut self, _on_top: bool) {
+        tracing::warn!("set_always_on_top is unimplemented on wayland");
+    }
+
     pub fn resizable(&mut self, resizable: bool) {
         self.resizable = resizable;
     }
@@ -387,6 +415,10 @@ impl WindowBuilder {
         self.menu = Some(menu);
     }
 
+    pub fn set_icon(&mut self, _icon: window::WindowIcon) {
+        tracing::warn!("window icons unimplemented for wayland");
+    }
+
     pub fn build(self) -> Result<WindowHandle, ShellError> {
         if matches!(self.menu, Some(_)) {
             tracing::warn!("menus unimplemented for wayland");
@@ -419,8 +451,13 @@ impl WindowBuilder {
         let handler = self.handler.expect("must set a window handler");
         // compute the initial window size.
         let initial_size = WindowBuilder::initial_window_size(&appdata, self.size);
-        let surface =
-            surfaces::toplevel::Surface::new(appdata.clone(), handler, initial_size, self.min_size);
+        let surface = surfaces::toplevel::Surface::new(
+            appdata.clone(),
+            handler,
+            initial_size,
+            self.min_size,
+            self.max_size,
+        );
 
         (&surface as &dyn surfaces::Decor).set_title(self.title);
 
@@ -14,7 +14,7 @@
 
 //! Web implementation of features at the application scope.
 
-use crate::application::AppHandler;
+use crate::application::{AppHandler, ColorScheme};
 
 use super::clipboard::Clipboard;
 use super::error::Error;
@@ -40,4 +40,20 @@ impl Application {
             .and_then(|w| w.navigator().language())
             .unwrap_or_else(|| "en-US".into())
     }
+
+    pub fn get_color_scheme() -> ColorScheme {
+        let matches = |query| {
+            web_sys::window()
+                .and_then(|w| w.match_media(query).ok().flatten())
+                .map(|list| list.matches())
+                .unwrap_or(false)
+        };
+        if matches("(prefers-color-scheme: dark)") {
+            ColorScheme::Dark
+        } else if matches("(prefers-color-scheme: light)") {
+            ColorScheme::Light
+        } else {
+            ColorScheme::NoPreference
+        }
+    }
 }
@@ -15,6 +15,7 @@
 //! Interactions with the browser pasteboard.
 
 use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::piet::ImageBuf;
 
 /// The browser clipboard.
 #[derive(Debug, Clone, Default)]
@@ -37,6 +38,17 @@ impl Clipboard {
         None
     }
 
+    /// Put an image onto the system clipboard.
+    pub fn put_image(&mut self, _image: &ImageBuf) {
+        tracing::warn!("unimplemented");
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        tracing::warn!("unimplemented");
+        None
+    }
+
     /// Given a list of supported clipboard types, returns the supported type which has
     /// highest priority on the system clipboard, or `None` if no types are supported.
     pub fn preferred_format(&self, _formats: &[FormatId]) -> Option<FormatId> {
@@ -20,4 +20,5 @@ pub mod error;
 pub mod keycodes;
 pub mod menu;
 pub mod screen;
+pub mod tray;
 pub mod window;
@@ -39,6 +39,7 @@ use crate::common_util::{ClickCounter, IdleCallback};
 use crate::dialog::{FileDialogOptions, FileDialogType};
 use crate::error::Error as ShellError;
 use crate::scale::{Scale, ScaledArea};
+use crate::screen::{Monitor, Screen};
 
 use crate::keyboard::{KeyState, Modifiers};
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
@@ -373,6 +374,14 @@ impl WindowBuilder {
         // Ignored
     }
 
+    pub fn set_max_size(&mut self, _: Size) {
+        // Ignored
+    }
+
+    pub fn set_always_on_top(&mut self, _: bool) {
+        // Ignored
+    }
+
     pub fn resizable(&mut self, _resizable: bool) {
         // Ignored
     }
@@ -405,6 +414,10 @@ impl WindowBuilder {
         self.menu = Some(menu);
     }
 
+    pub fn set_icon(&mut self, _icon: window::WindowIcon) {
+        // ignored
+    }
+
     pub fn build(self) -> Result<WindowHandle, Error> {
         let window = web_sys::window().ok_or(Error::NoWindow)?;
         let canvas = window
@@ -503,6 +516,11 @@ impl WindowHandle {
         Size::new(0.0, 0.0)
     }
 
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        let rect = Rect::from_origin_size(self.get_position(), self.get_size());
+        Screen::get_monitor_for_rect(rect)
+    }
+
     pub fn content_insets(&self) -> Insets {
         warn!("WindowHandle::content_insets unimplemented for web.");
         Insets::ZERO
@@ -521,6 +539,14 @@ impl WindowHandle {
         warn!("WindowHandle::handle_titlebar unimplemented for web.");
     }
 
+    pub fn begin_move_drag(&self) {
+        warn!("WindowHandle::begin_move_drag unimplemented for web.");
+    }
+
+    pub fn set_always_on_top(&self, _on_top: bool) {
+        warn!("WindowHandle::set_always_on_top unimplemented for web.");
+    }
+
     pub fn close(&self) {
         // TODO
     }
@@ -685,6 +711,10 @@ impl WindowHandle {
             state.canvas.set_title(&(title.into()))
         }
     }
+
+    pub fn set_icon(&self, _icon: &window::WindowIcon) {
+        warn!("set_icon unimplemented for web");
+    }
 }
 
 unsafe impl Send for IdleHandle {}
@@ -21,14 +21,15 @@ use std::ptr;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
-use winapi::shared::minwindef::{FALSE, HINSTANCE};
+use winapi::shared::minwindef::{DWORD, FALSE, HINSTANCE};
 use winapi::shared::ntdef::LPCWSTR;
 use winapi::shared::windef::{DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, HCURSOR, HWND};
-use winapi::shared::winerror::HRESULT_FROM_WIN32;
+use winapi::shared::winerror::{ERROR_SUCCESS, HRESULT_FROM_WIN32};
 use winapi::um::errhandlingapi::GetLastError;
 use winapi::um::shellscalingapi::PROCESS_PER_MONITOR_DPI_AWARE;
 use winapi::um::winnls::GetUserDefaultLocaleName;
 use winapi::um::winnt::LOCALE_NAME_MAX_LENGTH;
+use winapi::um::winreg::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
 use winapi::um::winuser::{
     DispatchMessageW, GetAncestor, GetMessageW, LoadIconW, PeekMessageW, PostMessageW,
     PostQuitMessage, RegisterClassW, TranslateAcceleratorW, TranslateMessage, GA_ROOT,
@@ -37,7 +38,7 @@ use winapi::um::winuser::{
 
 use piet_common::D2DLoadedFonts;
 
-use crate::application::AppHandler;
+use crate::application::{AppHandler, ColorScheme};
 
 use super::accels;
 use super::clipboard::Clipboard;
@@ -209,4 +210,28 @@ impl Application {
             "en-US".into()
         })
     }
+
+    pub fn get_color_scheme() -> ColorScheme {
+        let key = "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize".to_wide();
+        let value = "AppsUseLightTheme".to_wide();
+        let mut light: DWORD = 0;
+        let mut size = mem::size_of::<DWORD>() as DWORD;
+        let result = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                value.as_ptr(),
+                RRF_RT_REG_DWORD,
+                ptr::null_mut(),
+                &mut light as *mut DWORD as *mut _,
+                &mut size,
+            )
+        };
+        // the value doesn't exist before Windows 10
+        match (result as u32, light) {
+            (ERROR_SUCCESS, 0) => ColorScheme::Dark,
+            (ERROR_SUCCESS, _) => ColorScheme::Light,
+            _ => ColorScheme::NoPreference,
+        }
+    }
 }
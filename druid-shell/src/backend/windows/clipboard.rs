@@ -30,7 +30,8 @@ use winapi::um::winuser::{
 };
 
 use super::util::{FromWide, ToWide};
-use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::clipboard::{decode_dib, encode_dib, ClipboardFormat, FormatId};
+use crate::piet::ImageBuf;
 
 #[derive(Debug, Clone, Default)]
 pub struct Clipboard;
@@ -88,6 +89,27 @@ impl Clipboard {
         .flatten()
     }
 
+    /// Put an image onto the system clipboard, as a `CF_DIB`.
+    pub fn put_image(&mut self, image: &ImageBuf) {
+        if image.width() == 0 || image.height() == 0 {
+            tracing::warn!("can't put an empty image on the clipboard");
+            return;
+        }
+        self.put_formats(&[ClipboardFormat::new("CF_DIB", encode_dib(image))])
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    ///
+    /// Windows provides a `CF_DIB` for any bitmap on the clipboard.
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        let dib = self.get_format("CF_DIB")?;
+        let image = decode_dib(&dib);
+        if image.is_none() {
+            tracing::warn!("unsupported clipboard bitmap");
+        }
+        image
+    }
+
     /// Given a list of supported clipboard types, returns the supported type which has
     /// highest priority on the system clipboard, or `None` if no types are supported.
     pub fn preferred_format(&self, formats: &[FormatId]) -> Option<FormatId> {
@@ -40,7 +40,7 @@ use wio::com::ComPtr;
 
 use super::error::Error;
 use super::util::{as_result, FromWide, ToWide};
-use crate::dialog::{FileDialogOptions, FileDialogType, FileSpec};
+use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo, FileSpec};
 
 // TODO: remove these when they get added to winapi
 DEFINE_GUID! {CLSID_FileOpenDialog,
@@ -76,7 +76,7 @@ pub(crate) unsafe fn get_file_dialog_path(
     hwnd_owner: HWND,
     ty: FileDialogType,
     options: FileDialogOptions,
-) -> Result<OsString, Error> {
+) -> Result<Vec<FileInfo>, Error> {
     let mut pfd: *mut IFileDialog = null_mut();
     let (class, id) = match ty {
         FileDialogType::Open => (&CLSID_FileOpenDialog, IFileOpenDialog::uuidof()),
@@ -163,15 +163,69 @@ pub(crate) unsafe fn get_file_dialog_path(
         }
     }
 
+    // set the texts; the wide strings only need to live until the calls return
+    if let Some(default_name) = &options.default_name {
+        as_result(file_dialog.SetFileName(default_name.to_wide().as_ptr()))?;
+    }
+    if let Some(name_label) = &options.name_label {
+        as_result(file_dialog.SetFileNameLabel(name_label.to_wide().as_ptr()))?;
+    }
+    if let Some(title) = &options.title {
+        as_result(file_dialog.SetTitle(title.to_wide().as_ptr()))?;
+    }
+    if let Some(button_text) = &options.button_text {
+        as_result(file_dialog.SetOkButtonLabel(button_text.to_wide().as_ptr()))?;
+    }
+
     // show the dialog
     as_result(file_dialog.Show(hwnd_owner))?;
+
+    // the file type the user chose, if there are any
+    let format = match &options.allowed_types {
+        Some(types) if !types.is_empty() => {
+            let mut index: UINT = 0;
+            as_result(file_dialog.GetFileTypeIndex(&mut index))?;
+            // the index is one-based
+            index
+                .checked_sub(1)
+                .and_then(|index| types.get(index as usize))
+                .copied()
+        }
+        _ => None,
+    };
+    let file_info = |path: OsString| FileInfo {
+        path: path.into(),
+        format,
+    };
+
+    // `GetResult` fails for open dialogs with multiple selection
+    if ty == FileDialogType::Open && options.multi_selection {
+        let open_dialog = file_dialog.cast::<IFileOpenDialog>().map_err(Error::Hr)?;
+        let mut results_ptr: *mut IShellItemArray = null_mut();
+        as_result(open_dialog.GetResults(&mut results_ptr))?;
+        let results = ComPtr::from_raw(results_ptr);
+        let mut count: DWORD = 0;
+        as_result(results.GetCount(&mut count))?;
+        let mut filenames = Vec::with_capacity(count as usize);
+        for i in 0..count {
+            let mut item_ptr: *mut IShellItem = null_mut();
+            as_result(results.GetItemAt(i, &mut item_ptr))?;
+            filenames.push(file_info(item_path(&ComPtr::from_raw(item_ptr))?));
+        }
+        return Ok(filenames);
+    }
+
     let mut result_ptr: *mut IShellItem = null_mut();
     as_result(file_dialog.GetResult(&mut result_ptr))?;
     let shell_item = ComPtr::from_raw(result_ptr);
+    Ok(vec![file_info(item_path(&shell_item)?)])
+}
+
+/// The file system path of `shell_item`.
+unsafe fn item_path(shell_item: &ComPtr<IShellItem>) -> Result<OsString, Error> {
     let mut display_name: LPWSTR = null_mut();
     as_result(shell_item.GetDisplayName(SIGDN_FILESYSPATH, &mut display_name))?;
     let filename = display_name.to_os_string();
     CoTaskMemFree(display_name as LPVOID);
-
     Ok(filename)
 }
@@ -25,6 +25,7 @@ pub mod menu;
 pub mod paint;
 pub mod screen;
 mod timers;
+pub mod tray;
 pub mod util;
 pub mod window;
 
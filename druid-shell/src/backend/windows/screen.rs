@@ -22,17 +22,16 @@ use winapi::shared::minwindef::*;
 use winapi::shared::windef::*;
 use winapi::shared::winerror::*;
 use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::shellscalingapi::MDT_EFFECTIVE_DPI;
 use winapi::um::winuser::*;
 
+use super::util::OPTIONAL_FUNCTIONS;
 use crate::kurbo::Rect;
+use crate::scale::Scale;
 use crate::screen::Monitor;
 
-unsafe extern "system" fn monitorenumproc(
-    hmonitor: HMONITOR,
-    _hdc: HDC,
-    _lprect: LPRECT,
-    _lparam: LPARAM,
-) -> BOOL {
+/// Get the information about a monitor from its handle.
+pub(crate) unsafe fn monitor_from_handle(hmonitor: HMONITOR) -> Monitor {
     let rect = RECT {
         left: 0,
         top: 0,
@@ -64,8 +63,26 @@ unsafe extern "system" fn monitorenumproc(
         info.rcWork.right as f64,
         info.rcWork.bottom as f64,
     );
+    let scale = match OPTIONAL_FUNCTIONS.GetDpiForMonitor {
+        Some(func) => {
+            let mut dpi_x = 96;
+            let mut dpi_y = 96;
+            func(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+            Scale::new(dpi_x as f64 / 96.0, dpi_y as f64 / 96.0)
+        }
+        None => Scale::default(),
+    };
+    Monitor::new(primary, rect, work_rect).with_scale(scale)
+}
+
+unsafe extern "system" fn monitorenumproc(
+    hmonitor: HMONITOR,
+    _hdc: HDC,
+    _lprect: LPRECT,
+    _lparam: LPARAM,
+) -> BOOL {
     let monitors = _lparam as *mut Vec<Monitor>;
-    (*monitors).push(Monitor::new(primary, rect, work_rect));
+    (*monitors).push(monitor_from_handle(hmonitor));
     TRUE
 }
 
@@ -0,0 +1,337 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Windows implementation of tray icons, using the notification area.
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::ptr::{null, null_mut};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use winapi::shared::minwindef::{HINSTANCE, LPARAM, LRESULT, UINT, WPARAM};
+use winapi::shared::windef::{HICON, HMENU, HWND, POINT};
+use winapi::shared::winerror::HRESULT_FROM_WIN32;
+use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::shellapi::{
+    Shell_NotifyIconW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NIM_MODIFY,
+    NOTIFYICONDATAW,
+};
+use winapi::um::wingdi::{CreateBitmap, DeleteObject};
+use winapi::um::winuser::*;
+
+use super::error::Error as WinError;
+use super::menu::Menu;
+use super::util::ToWide;
+use crate::error::Error;
+use crate::piet::ImageBuf;
+use crate::tray::TrayHandler;
+
+const TRAY_CLASS_NAME: &str = "druid_tray";
+
+/// The message the notification area sends us when the icon is interacted with.
+const WM_TRAY_ICON: UINT = WM_APP + 1;
+
+/// Used to ensure the window class is registered only once per process.
+static TRAY_CLASS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+pub struct TrayIcon {
+    hwnd: HWND,
+    state: Rc<TrayState>,
+}
+
+/// State shared with the window procedure of the hidden window that receives
+/// the notifications of the icon.
+struct TrayState {
+    handler: RefCell<Box<dyn TrayHandler>>,
+    icon: Cell<HICON>,
+    tooltip: RefCell<String>,
+    menu: Cell<HMENU>,
+    /// Explorer sends this when the taskbar is recreated, after which the
+    /// icon needs to be added again.
+    taskbar_created: UINT,
+    /// Set after a double click, so that the release that follows it isn't
+    /// reported as a click.
+    swallow_click: Cell<bool>,
+}
+
+impl TrayIcon {
+    pub fn new(handler: Box<dyn TrayHandler>) -> Result<TrayIcon, Error> {
+        unsafe {
+            register_class();
+            let taskbar_created = RegisterWindowMessageW("TaskbarCreated".to_wide().as_ptr());
+            let state = Rc::new(TrayState {
+                handler: RefCell::new(handler),
+                icon: Cell::new(null_mut()),
+                tooltip: RefCell::new(String::new()),
+                menu: Cell::new(null_mut()),
+                taskbar_created,
+                swallow_click: Cell::new(false),
+            });
+            let class_name = TRAY_CLASS_NAME.to_wide();
+            // This window is never shown; it needs to be a top-level window rather
+            // than a message-only one so that it can receive `TaskbarCreated` and
+            // take the foreground while the menu is open.
+            let hwnd = CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                null(),
+                WS_OVERLAPPED,
+                0,
+                0,
+                0,
+                0,
+                null_mut(),
+                null_mut(),
+                0 as HINSTANCE,
+                Rc::into_raw(state.clone()) as *mut _,
+            );
+            if hwnd.is_null() {
+                return Err(WinError::Hr(HRESULT_FROM_WIN32(GetLastError())).into());
+            }
+            let tray = TrayIcon { hwnd, state };
+            if !tray.notify(NIM_ADD) {
+                return Err(anyhow::anyhow!("failed to add the tray icon").into());
+            }
+            Ok(tray)
+        }
+    }
+
+    pub fn set_icon(&self, icon: &ImageBuf) {
+        let new = make_icon(icon);
+        if new.is_null() {
+            tracing::warn!("failed to create tray icon image");
+            return;
+        }
+        let old = self.state.icon.replace(new);
+        self.notify(NIM_MODIFY);
+        if !old.is_null() {
+            unsafe {
+                DestroyIcon(old);
+            }
+        }
+    }
+
+    pub fn set_tooltip(&self, tooltip: &str) {
+        *self.state.tooltip.borrow_mut() = tooltip.to_owned();
+        self.notify(NIM_MODIFY);
+    }
+
+    pub fn set_menu(&self, menu: Option<Menu>) {
+        let new = menu.map(Menu::into_hmenu).unwrap_or(null_mut());
+        let old = self.state.menu.replace(new);
+        if !old.is_null() {
+            unsafe {
+                DestroyMenu(old);
+            }
+        }
+    }
+
+    /// Send the current icon and tooltip to the notification area.
+    fn notify(&self, message: UINT) -> bool {
+        self.state.notify(self.hwnd, message)
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        self.notify(NIM_DELETE);
+        unsafe {
+            DestroyWindow(self.hwnd);
+            let icon = self.state.icon.replace(null_mut());
+            if !icon.is_null() {
+                DestroyIcon(icon);
+            }
+            let menu = self.state.menu.replace(null_mut());
+            if !menu.is_null() {
+                DestroyMenu(menu);
+            }
+        }
+    }
+}
+
+impl TrayState {
+    fn notify(&self, hwnd: HWND, message: UINT) -> bool {
+        unsafe {
+            let mut data: NOTIFYICONDATAW = mem::zeroed();
+            data.cbSize = mem::size_of::<NOTIFYICONDATAW>() as u32;
+            data.hWnd = hwnd;
+            data.uID = 1;
+            data.uFlags = NIF_MESSAGE | NIF_TIP;
+            data.uCallbackMessage = WM_TRAY_ICON;
+            let icon = self.icon.get();
+            if !icon.is_null() {
+                data.uFlags |= NIF_ICON;
+                data.hIcon = icon;
+            }
+            // The tooltip is truncated to fit, leaving room for the terminator.
+            let tooltip = self.tooltip.borrow().to_wide();
+            let len = (tooltip.len() - 1).min(data.szTip.len() - 1);
+            data.szTip[..len].copy_from_slice(&tooltip[..len]);
+            Shell_NotifyIconW(message, &mut data) != 0
+        }
+    }
+
+    fn with_handler(&self, f: impl FnOnce(&mut dyn TrayHandler)) {
+        match self.handler.try_borrow_mut() {
+            Ok(mut handler) => f(&mut **handler),
+            Err(_) => tracing::error!("tray handler borrowed recursively"),
+        }
+    }
+
+    unsafe fn show_menu(&self, hwnd: HWND) {
+        let menu = self.menu.get();
+        if menu.is_null() {
+            return;
+        }
+        let mut point = POINT { x: 0, y: 0 };
+        GetCursorPos(&mut point);
+        // Without this the menu doesn't close when clicking elsewhere, see
+        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-trackpopupmenu
+        SetForegroundWindow(hwnd);
+        let id = TrackPopupMenu(
+            menu,
+            TPM_RETURNCMD | TPM_RIGHTBUTTON,
+            point.x,
+            point.y,
+            0,
+            hwnd,
+            null(),
+        );
+        PostMessageW(hwnd, WM_NULL, 0, 0);
+        if id != 0 {
+            self.with_handler(|handler| handler.command(id as u32));
+        }
+    }
+
+    unsafe fn window_proc(&self, hwnd: HWND, msg: UINT, lparam: LPARAM) -> Option<LRESULT> {
+        if msg == self.taskbar_created {
+            self.notify(hwnd, NIM_ADD);
+            return Some(0);
+        }
+        if msg != WM_TRAY_ICON {
+            return None;
+        }
+        match lparam as UINT {
+            WM_LBUTTONUP => {
+                if !self.swallow_click.replace(false) {
+                    self.with_handler(|handler| handler.clicked());
+                }
+            }
+            WM_LBUTTONDBLCLK => {
+                self.swallow_click.set(true);
+                self.with_handler(|handler| handler.double_clicked());
+            }
+            WM_RBUTTONUP => self.show_menu(hwnd),
+            _ => (),
+        }
+        Some(0)
+    }
+}
+
+unsafe fn register_class() {
+    if TRAY_CLASS_REGISTERED
+        .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+        .is_ok()
+    {
+        let class_name = TRAY_CLASS_NAME.to_wide();
+        let wnd = WNDCLASSW {
+            style: 0,
+            lpfnWndProc: Some(tray_proc_dispatch),
+            cbClsExtra: 0,
+            cbWndExtra: 0,
+            hInstance: 0 as HINSTANCE,
+            hIcon: null_mut(),
+            hCursor: null_mut(),
+            hbrBackground: null_mut(),
+            lpszMenuName: null(),
+            lpszClassName: class_name.as_ptr(),
+        };
+        if RegisterClassW(&wnd) == 0 {
+            panic!("Error registering tray class");
+        }
+    }
+}
+
+unsafe extern "system" fn tray_proc_dispatch(
+    hwnd: HWND,
+    msg: UINT,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_CREATE {
+        let create_struct = &*(lparam as *const CREATESTRUCTW);
+        SetWindowLongPtrW(hwnd, GWLP_USERDATA, create_struct.lpCreateParams as _);
+    }
+    let state_ptr = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *const TrayState;
+    if state_ptr.is_null() {
+        return DefWindowProcW(hwnd, msg, wparam, lparam);
+    }
+
+    // The handler may drop the tray icon, so keep the state alive until we're done.
+    Rc::increment_strong_count(state_ptr);
+    let state = Rc::from_raw(state_ptr);
+    let result = state.window_proc(hwnd, msg, lparam);
+
+    if msg == WM_NCDESTROY {
+        SetWindowLongPtrW(hwnd, GWLP_USERDATA, 0);
+        mem::drop(Rc::from_raw(state_ptr));
+    }
+
+    match result {
+        Some(lresult) => lresult,
+        None => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+/// Create an icon from an image, preserving its alpha channel.
+pub(super) fn make_icon(image: &ImageBuf) -> HICON {
+    let width = image.width();
+    let height = image.height();
+    let mut bgra = Vec::with_capacity(width * height * 4);
+    for row in image.pixel_colors() {
+        for color in row {
+            let (r, g, b, a) = color.as_rgba8();
+            bgra.extend_from_slice(&[b, g, r, a]);
+        }
+    }
+    unsafe {
+        let color = CreateBitmap(
+            width as i32,
+            height as i32,
+            1,
+            32,
+            bgra.as_ptr() as *const _,
+        );
+        if color.is_null() {
+            return null_mut();
+        }
+        // With a 32-bit color bitmap the alpha channel is used, so the mask
+        // only needs to exist.
+        let mask = CreateBitmap(width as i32, height as i32, 1, 1, null());
+        let mut icon_info = ICONINFO {
+            fIcon: 1,
+            xHotspot: 0,
+            yHotspot: 0,
+            hbmMask: mask,
+            hbmColor: color,
+        };
+        let icon = CreateIconIndirect(&mut icon_info);
+        DeleteObject(color as _);
+        if !mask.is_null() {
+            DeleteObject(mask as _);
+        }
+        icon
+    }
+}
@@ -19,6 +19,7 @@
 use std::cell::{Cell, RefCell};
 use std::mem;
 use std::panic::Location;
+use std::path::PathBuf;
 use std::ptr::{null, null_mut};
 use std::rc::{Rc, Weak};
 use std::sync::{Arc, Mutex};
@@ -37,6 +38,7 @@ use winapi::shared::winerror::*;
 use winapi::um::dcomp::{IDCompositionDevice, IDCompositionTarget, IDCompositionVisual};
 use winapi::um::dwmapi::DwmExtendFrameIntoClientArea;
 use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::shellapi::{DragAcceptFiles, DragFinish, DragQueryFileW, DragQueryPoint, HDROP};
 use winapi::um::shellscalingapi::MDT_EFFECTIVE_DPI;
 use winapi::um::unknwnbase::*;
 use winapi::um::uxtheme::*;
@@ -67,12 +69,14 @@ use super::timers::TimerSlots;
 use super::util::{self, as_result, FromWide, ToWide, OPTIONAL_FUNCTIONS};
 
 use crate::common_util::IdleCallback;
-use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo};
+use crate::dialog::{FileDialogOptions, FileDialogType};
 use crate::error::Error as ShellError;
+use crate::file_drop::FileDropEvent;
 use crate::keyboard::{KbKey, KeyState};
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
 use crate::region::Region;
 use crate::scale::{Scalable, Scale, ScaledArea};
+use crate::screen::Monitor;
 use crate::text::{simulate_input, Event};
 use crate::window;
 use crate::window::{
@@ -96,9 +100,12 @@ pub(crate) struct WindowBuilder {
     size: Option<Size>,
     transparent: bool,
     min_size: Option<Size>,
+    max_size: Option<Size>,
     position: Option<Point>,
     level: Option<WindowLevel>,
     state: window::WindowState,
+    always_on_top: bool,
+    icon: Option<window::WindowIcon>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -160,6 +167,8 @@ enum DeferredOp {
     SetSize(Size),
     SetResizable(bool),
     SetWindowState(window::WindowState),
+    SetAlwaysOnTop(bool),
+    BeginMoveDrag,
     ReleaseMouseCapture,
 }
 
@@ -238,6 +247,18 @@ struct WindowState {
     // False for tooltips, to prevent stealing focus from owner window.
     is_focusable: bool,
     window_level: WindowLevel,
+    /// The large and small icon of the window, which we need to destroy.
+    icons: Cell<[HICON; 2]>,
+}
+
+impl Drop for WindowState {
+    fn drop(&mut self) {
+        for icon in self.icons.get() {
+            if !icon.is_null() {
+                unsafe { DestroyIcon(icon) };
+            }
+        }
+    }
 }
 
 impl std::fmt::Debug for WindowState {
@@ -276,6 +297,7 @@ struct WndState {
     render_target: Option<DeviceContext>,
     dxgi_state: Option<DxgiState>,
     min_size: Option<Size>,
+    max_size: Option<Size>,
     keyboard_state: KeyboardState,
     // Stores a set of all mouse buttons that are currently holding mouse
     // capture. When the first mouse button is down on our window we enter
@@ -359,6 +381,27 @@ fn get_buttons(wparam: WPARAM) -> MouseButtons {
     buttons
 }
 
+/// Returns the position, in pixels, and the paths of the files in a
+/// `WM_DROPFILES` message, if they were dropped on the client area.
+fn get_dropped_files(drop: HDROP) -> Option<(Point, Vec<PathBuf>)> {
+    unsafe {
+        let mut point = POINT { x: 0, y: 0 };
+        if DragQueryPoint(drop, &mut point) == FALSE {
+            return None;
+        }
+        let count = DragQueryFileW(drop, 0xFFFF_FFFF, null_mut(), 0);
+        let paths = (0..count)
+            .map(|i| {
+                let len = DragQueryFileW(drop, i, null_mut(), 0) as usize;
+                let mut buf = vec![0u16; len + 1];
+                DragQueryFileW(drop, i, buf.as_mut_ptr(), buf.len() as UINT);
+                buf[..len].to_os_string().into()
+            })
+            .collect();
+        Some((Point::new(point.x as f64, point.y as f64), paths))
+    }
+}
+
 fn is_point_in_client_rect(hwnd: HWND, x: i32, y: i32) -> bool {
     unsafe {
         let mut client_rect = mem::MaybeUninit::uninit();
@@ -622,23 +665,22 @@ impl MyWndProc {
                     let info = unsafe {
                         get_file_dialog_path(hwnd, FileDialogType::Save, options)
                             .ok()
-                            .map(|os_str| FileInfo {
-                                path: os_str.into(),
-                                format: None,
-                            })
+                            .and_then(|infos| infos.into_iter().next())
                     };
                     self.with_wnd_state(|s| s.handler.save_as(token, info));
                 }
                 DeferredOp::Open(options, token) => {
-                    let info = unsafe {
+                    let multi_selection = options.multi_selection;
+                    let infos = unsafe {
                         get_file_dialog_path(hwnd, FileDialogType::Open, options)
-                            .ok()
-                            .map(|s| FileInfo {
-                                path: s.into(),
-                                format: None,
-                            })
+                            .unwrap_or_default()
                     };
-                    self.with_wnd_state(|s| s.handler.open_file(token, info));
+                    if multi_selection {
+                        self.with_wnd_state(|s| s.handler.open_files(token, infos));
+                    } else {
+                        let info = infos.into_iter().next();
+                        self.with_wnd_state(|s| s.handler.open_file(token, info));
+                    }
                 }
                 DeferredOp::ContextMenu(menu, pos) => {
                     let hmenu = menu.into_hmenu();
@@ -656,6 +698,30 @@ impl MyWndProc {
                         }
                     }
                 }
+                DeferredOp::SetAlwaysOnTop(on_top) => unsafe {
+                    let insert_after = if on_top { HWND_TOPMOST } else { HWND_NOTOPMOST };
+                    if SetWindowPos(
+                        hwnd,
+                        insert_after,
+                        0,
+                        0,
+                        0,
+                        0,
+                        SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+                    ) == 0
+                    {
+                        warn!(
+                            "failed to change window z-order: {}",
+                            Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+                        );
+                    };
+                },
+                DeferredOp::BeginMoveDrag => unsafe {
+                    // Hand the pressed button over to the system, as if it had been pressed
+                    // on the caption; it then moves the window until the button is released.
+                    ReleaseCapture();
+                    PostMessageW(hwnd, WM_NCLBUTTONDOWN, HTCAPTION as WPARAM, 0);
+                },
                 DeferredOp::ReleaseMouseCapture => unsafe {
                     if ReleaseCapture() == FALSE {
                         let result = HRESULT_FROM_WIN32(GetLastError());
@@ -738,6 +804,7 @@ impl WndProc for MyWndProc {
                 if let Some(state) = self.handle.borrow().state.upgrade() {
                     state.hwnd.set(hwnd);
                 }
+                unsafe { DragAcceptFiles(hwnd, TRUE) };
                 if let Some(state) = self.state.borrow_mut().as_mut() {
                     let dxgi_state = unsafe {
                         create_dxgi_state(self.present_strategy, hwnd, self.is_transparent())
@@ -802,6 +869,15 @@ impl WndProc for MyWndProc {
                 self.with_wnd_state(|s| s.handler.got_focus());
                 Some(0)
             }
+            WM_SETTINGCHANGE => unsafe {
+                // the area is "ImmersiveColorSet" when the apps theme changes
+                let area = lparam as LPWSTR;
+                if !area.is_null() && area.from_wide().as_deref() == Some("ImmersiveColorSet") {
+                    let scheme = crate::Application::get_color_scheme();
+                    self.with_wnd_state(|s| s.handler.color_scheme_changed(scheme));
+                }
+                None
+            },
             WM_KILLFOCUS => {
                 self.with_wnd_state(|s| s.handler.lost_focus());
                 Some(0)
@@ -1135,6 +1211,18 @@ impl WndProc for MyWndProc {
                 });
                 Some(0)
             }
+            WM_DROPFILES => {
+                let drop = wparam as HDROP;
+                let event = get_dropped_files(drop).map(|(pos, paths)| FileDropEvent {
+                    pos: pos.to_dp(self.scale()),
+                    paths,
+                });
+                unsafe { DragFinish(drop) };
+                if let Some(event) = event {
+                    self.with_wnd_state(|s| s.handler.file_drop(&event));
+                }
+                Some(0)
+            }
             // Note: we handle the double-click events out of caution here, but we don't expect
             // to actually receive any, because we don't set CS_DBLCLKS on the window class style.
             // And the reason for that is that we want click counts that go above 2, so it just
@@ -1253,6 +1341,11 @@ impl WndProc for MyWndProc {
                         min_max_info.ptMinTrackSize.x = min_size_px.width.round() as i32;
                         min_max_info.ptMinTrackSize.y = min_size_px.height.round() as i32;
                     }
+                    if let Some(max_size_dp) = s.max_size {
+                        let max_size_px = max_size_dp.to_px(self.scale());
+                        min_max_info.ptMaxTrackSize.x = max_size_px.width.round() as i32;
+                        min_max_info.ptMaxTrackSize.y = max_size_px.height.round() as i32;
+                    }
                 });
                 Some(0)
             }
@@ -1285,9 +1378,12 @@ impl WindowBuilder {
             present_strategy: Default::default(),
             size: None,
             min_size: None,
+            max_size: None,
             position: None,
             level: None,
             state: window::WindowState::Restored,
+            always_on_top: false,
+            icon: None,
         }
     }
 
@@ -1304,6 +1400,14 @@ impl WindowBuilder {
         self.min_size = Some(size);
     }
 
+    pub fn set_max_size(&mut self, size: Size) {
+        self.max_size = Some(size);
+    }
+
+    pub fn set_always_on_top(&mut self, on_top: bool) {
+        self.always_on_top = on_top;
+    }
+
     pub fn resizable(&mut self, resizable: bool) {
         self.resizable = resizable;
     }
@@ -1333,6 +1437,10 @@ impl WindowBuilder {
         self.menu = Some(menu);
     }
 
+    pub fn set_icon(&mut self, icon: window::WindowIcon) {
+        self.icon = Some(icon);
+    }
+
     pub fn set_position(&mut self, position: Point) {
         self.position = Some(position);
     }
@@ -1438,6 +1546,7 @@ impl WindowBuilder {
                 active_text_input: Cell::new(None),
                 is_focusable: focusable,
                 window_level,
+                icons: Cell::new([null_mut(); 2]),
             };
             let win = Rc::new(window);
             let handle = WindowHandle {
@@ -1450,6 +1559,7 @@ impl WindowBuilder {
                 render_target: None,
                 dxgi_state: None,
                 min_size: self.min_size,
+                max_size: self.max_size,
                 keyboard_state: KeyboardState::new(),
                 captured_mouse_buttons: MouseButtons::new(),
                 has_mouse_focus: false,
@@ -1471,6 +1581,10 @@ impl WindowBuilder {
                 dwExStyle |= WS_EX_NOREDIRECTIONBITMAP;
             }
 
+            if self.always_on_top {
+                dwExStyle |= WS_EX_TOPMOST;
+            }
+
             match self.state {
                 window::WindowState::Maximized => dwStyle |= WS_MAXIMIZE,
                 window::WindowState::Minimized => dwStyle |= WS_MINIMIZE,
@@ -1495,6 +1609,10 @@ impl WindowBuilder {
                 return Err(Error::NullHwnd);
             }
 
+            if let Some(icon) = &self.icon {
+                handle.set_icon(icon);
+            }
+
             if let Some(size_dp) = self.size {
                 if let Ok(scale) = handle.get_scale() {
                     let size_px = size_dp.to_px(scale);
@@ -1876,6 +1994,29 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_icon(&self, icon: &window::WindowIcon) {
+        if let Some(w) = self.state.upgrade() {
+            let hwnd = w.hwnd.get();
+            unsafe {
+                // The large icon is shown in the taskbar and the task switcher,
+                // the small one in the titlebar.
+                let big = GetSystemMetrics(SM_CXICON) as usize;
+                let small = GetSystemMetrics(SM_CXSMICON) as usize;
+                let icons = [big, small].map(|size| {
+                    icon.best_for(size)
+                        .map_or(null_mut(), super::tray::make_icon)
+                });
+                SendMessageW(hwnd, WM_SETICON, ICON_BIG, icons[0] as LPARAM);
+                SendMessageW(hwnd, WM_SETICON, ICON_SMALL, icons[1] as LPARAM);
+                for old in w.icons.replace(icons) {
+                    if !old.is_null() {
+                        DestroyIcon(old);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn show_titlebar(&self, show_titlebar: bool) {
         self.defer(DeferredOp::ShowTitlebar(show_titlebar));
     }
@@ -1982,6 +2123,18 @@ impl WindowHandle {
         Size::new(0.0, 0.0)
     }
 
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        let hwnd = self.get_hwnd()?;
+        unsafe {
+            let hmonitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+            if hmonitor.is_null() {
+                None
+            } else {
+                Some(super::screen::monitor_from_handle(hmonitor))
+            }
+        }
+    }
+
     pub fn resizable(&self, resizable: bool) {
         self.defer(DeferredOp::SetResizable(resizable));
     }
@@ -2024,6 +2177,14 @@ impl WindowHandle {
         }
     }
 
+    pub fn begin_move_drag(&self) {
+        self.defer(DeferredOp::BeginMoveDrag);
+    }
+
+    pub fn set_always_on_top(&self, on_top: bool) {
+        self.defer(DeferredOp::SetAlwaysOnTop(on_top));
+    }
+
     pub fn set_menu(&self, menu: Menu) {
         let accels = menu.accels();
         let hmenu = menu.into_hmenu();
@@ -33,7 +33,7 @@ use x11rb::protocol::Event;
 use x11rb::resource_manager::Database as ResourceDb;
 use x11rb::xcb_ffi::XCBConnection;
 
-use crate::application::AppHandler;
+use crate::application::{AppHandler, ColorScheme};
 
 use super::clipboard::Clipboard;
 use super::util;
@@ -77,6 +77,12 @@ use crate::backend::shared::xkb;
 //
 // The type of _NET_WM_NAME
 //
+// _NET_WM_ICON
+//
+// The icons of a window, as an array of widths, heights and ARGB pixels.
+//
+// https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm45805407959456
+//
 // CLIPBOARD
 //
 // The name of the clipboard selection; used for implementing copy&paste
@@ -99,6 +105,7 @@ x11rb::atom_manager! {
         _NET_WM_PID,
         _NET_WM_NAME,
         UTF8_STRING,
+        _NET_WM_ICON,
         _NET_WM_WINDOW_TYPE,
         _NET_WM_WINDOW_TYPE_NORMAL,
         _NET_WM_WINDOW_TYPE_DROPDOWN_MENU,
@@ -815,6 +822,11 @@ impl Application {
         linux::env::locale()
     }
 
+    pub fn get_color_scheme() -> ColorScheme {
+        // TODO: read the preference from the freedesktop settings portal
+        ColorScheme::NoPreference
+    }
+
     pub(crate) fn idle_pipe(&self) -> RawFd {
         self.idle_write
     }
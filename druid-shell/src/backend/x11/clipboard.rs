@@ -33,7 +33,8 @@ use x11rb::wrapper::ConnectionExt as _;
 use x11rb::xcb_ffi::XCBConnection;
 
 use super::application::AppAtoms;
-use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::clipboard::{decode_png, encode_png, ClipboardFormat, FormatId};
+use crate::piet::ImageBuf;
 use tracing::{debug, error, warn};
 
 // We can pick an arbitrary atom that is used for the transfer. This is our pick.
@@ -106,6 +107,16 @@ impl Clipboard {
         self.0.borrow().get_string()
     }
 
+    pub fn put_image(&mut self, image: &ImageBuf) {
+        if let Some(png) = encode_png(image) {
+            self.put_formats(&[ClipboardFormat::new(ClipboardFormat::PNG, png)]);
+        }
+    }
+
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        decode_png(&self.get_format(ClipboardFormat::PNG)?)
+    }
+
     pub fn preferred_format(&self, formats: &[FormatId]) -> Option<FormatId> {
         self.0.borrow().preferred_format(formats)
     }
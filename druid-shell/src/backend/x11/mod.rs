@@ -38,4 +38,5 @@ pub mod clipboard;
 pub mod error;
 pub mod menu;
 pub mod screen;
+pub mod tray;
 pub mod window;
@@ -0,0 +1,36 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tray icons, which are not supported by the X11 backend.
+
+use anyhow::anyhow;
+
+use super::menu::Menu;
+use crate::error::Error;
+use crate::piet::ImageBuf;
+use crate::tray::TrayHandler;
+
+pub struct TrayIcon;
+
+impl TrayIcon {
+    pub fn new(_handler: Box<dyn TrayHandler>) -> Result<TrayIcon, Error> {
+        Err(anyhow!("tray icons are not supported by the X11 backend").into())
+    }
+
+    pub fn set_icon(&self, _icon: &ImageBuf) {}
+
+    pub fn set_tooltip(&self, _tooltip: &str) {}
+
+    pub fn set_menu(&self, _menu: Option<Menu>) {}
+}
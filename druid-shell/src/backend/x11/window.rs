@@ -54,9 +54,10 @@ use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
 use crate::piet::{Piet, PietText, RenderContext};
 use crate::region::Region;
 use crate::scale::Scale;
+use crate::screen::{Monitor, Screen};
 use crate::text::{simulate_input, Event};
 use crate::window::{
-    FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowLevel,
+    FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowIcon, WindowLevel,
 };
 use crate::{window, KeyEvent, ScaledArea};
 
@@ -98,10 +99,11 @@ impl From<Visualtype> for xcb_visualtype_t {
     }
 }
 
-fn size_hints(resizable: bool, size: Size, min_size: Size) -> WmSizeHints {
+fn size_hints(resizable: bool, size: Size, min_size: Size, max_size: Option<Size>) -> WmSizeHints {
     let mut size_hints = WmSizeHints::new();
     if resizable {
         size_hints.min_size = Some((min_size.width as i32, min_size.height as i32));
+        size_hints.max_size = max_size.map(|max| (max.width as i32, max.height as i32));
     } else {
         size_hints.min_size = Some((size.width as i32, size.height as i32));
         size_hints.max_size = Some((size.width as i32, size.height as i32));
@@ -117,9 +119,11 @@ pub(crate) struct WindowBuilder {
     position: Option<Point>,
     size: Size,
     min_size: Size,
+    max_size: Option<Size>,
     resizable: bool,
     level: WindowLevel,
     state: Option<window::WindowState>,
+    icon: Option<WindowIcon>,
 }
 
 impl WindowBuilder {
@@ -132,9 +136,11 @@ impl WindowBuilder {
             position: None,
             size: Size::new(500.0, 400.0),
             min_size: Size::new(0.0, 0.0),
+            max_size: None,
             resizable: true,
             level: WindowLevel::AppWindow,
             state: None,
+            icon: None,
         }
     }
 
@@ -155,6 +161,14 @@ impl WindowBuilder {
         self.min_size = min_size;
     }
 
+    pub fn set_max_size(&mut self, max_size: Size) {
+        self.max_size = Some(max_size);
+    }
+
+    pub fn set_always_on_top(&mut self, _on_top: bool) {
+        warn!("WindowBuilder::set_always_on_top is currently unimplemented for X11 backend.");
+    }
+
     pub fn resizable(&mut self, resizable: bool) {
         self.resizable = resizable;
     }
@@ -188,6 +202,10 @@ impl WindowBuilder {
         // TODO(x11/menus): implement WindowBuilder::set_menu (currently a no-op)
     }
 
+    pub fn set_icon(&mut self, icon: WindowIcon) {
+        self.icon = Some(icon);
+    }
+
     fn create_cairo_surface(
         &self,
         window_id: u32,
@@ -424,7 +442,8 @@ impl WindowBuilder {
         .context("set WM_PROTOCOLS")?;
 
         let min_size = self.min_size.to_px(scale);
-        log_x11!(size_hints(self.resizable, size_px, min_size)
+        let max_size = self.max_size.map(|size| size.to_px(scale));
+        log_x11!(size_hints(self.resizable, size_px, min_size, max_size)
             .set_normal_hints(conn.as_ref(), id)
             .context("set wm normal hints"));
 
@@ -476,6 +495,7 @@ impl WindowBuilder {
             area: Cell::new(ScaledArea::from_px(size_px, scale)),
             scale: Cell::new(scale),
             min_size,
+            max_size,
             invalid: RefCell::new(Region::EMPTY),
             destroyed: Cell::new(false),
             timer_queue: Mutex::new(BinaryHeap::new()),
@@ -488,6 +508,9 @@ impl WindowBuilder {
         });
 
         window.set_title(&self.title);
+        if let Some(icon) = &self.icon {
+            window.set_icon(icon);
+        }
         if let Some(pos) = self.position {
             window.set_position(pos);
         }
@@ -561,6 +584,8 @@ pub(crate) struct Window {
     scale: Cell<Scale>,
     // min size in px
     min_size: Size,
+    // max size in px
+    max_size: Option<Size>,
     /// We've told X11 to destroy this window, so don't so any more X requests with this window id.
     destroyed: Cell<bool>,
     /// The region that was invalidated since the last time we rendered.
@@ -858,9 +883,14 @@ impl Window {
     /// Set whether the window should be resizable
     fn resizable(&self, resizable: bool) {
         let conn = self.app.connection().as_ref();
-        log_x11!(size_hints(resizable, self.size().size_px(), self.min_size)
-            .set_normal_hints(conn, self.id)
-            .context("set normal hints"));
+        log_x11!(size_hints(
+            resizable,
+            self.size().size_px(),
+            self.min_size,
+            self.max_size
+        )
+        .set_normal_hints(conn, self.id)
+        .context("set normal hints"));
     }
 
     /// Set whether the window should show titlebar
@@ -1013,6 +1043,36 @@ impl Window {
         ));
     }
 
+    fn set_icon(&self, icon: &WindowIcon) {
+        if self.destroyed() {
+            return;
+        }
+
+        // _NET_WM_ICON holds all the sizes, each as its width and height
+        // followed by its pixels as ARGB, which doesn't premultiply alpha.
+        let mut data = Vec::new();
+        for image in icon.images() {
+            data.extend([image.width() as u32, image.height() as u32]);
+            data.extend(image.pixel_colors().flatten().map(|color| {
+                let (r, g, b, a) = color.as_rgba8();
+                u32::from_be_bytes([a, r, g, b])
+            }));
+        }
+        let conn = self.app.connection();
+        let atoms = self.app.atoms();
+        if data.is_empty() {
+            log_x11!(conn.delete_property(self.id, atoms._NET_WM_ICON));
+        } else {
+            log_x11!(conn.change_property32(
+                xproto::PropMode::REPLACE,
+                self.id,
+                atoms._NET_WM_ICON,
+                AtomEnum::CARDINAL,
+                &data,
+            ));
+        }
+    }
+
     fn set_cursor(&self, cursor: &Cursor) {
         let cursors = &self.app.cursors;
         #[allow(deprecated)]
@@ -1657,6 +1717,11 @@ impl WindowHandle {
         }
     }
 
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        let rect = Rect::from_origin_size(self.get_position(), self.get_size());
+        Screen::get_monitor_for_rect(rect)
+    }
+
     pub fn set_window_state(&self, _state: window::WindowState) {
         warn!("WindowHandle::set_window_state is currently unimplemented for X11 backend.");
     }
@@ -1670,6 +1735,14 @@ impl WindowHandle {
         warn!("WindowHandle::handle_titlebar is currently unimplemented for X11 backend.");
     }
 
+    pub fn begin_move_drag(&self) {
+        warn!("WindowHandle::begin_move_drag is currently unimplemented for X11 backend.");
+    }
+
+    pub fn set_always_on_top(&self, _on_top: bool) {
+        warn!("WindowHandle::set_always_on_top is currently unimplemented for X11 backend.");
+    }
+
     pub fn bring_to_front_and_focus(&self) {
         if let Some(w) = self.window.upgrade() {
             w.bring_to_front_and_focus();
@@ -1710,6 +1783,14 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_icon(&self, icon: &WindowIcon) {
+        if let Some(w) = self.window.upgrade() {
+            w.set_icon(icon);
+        } else {
+            error!("Window {} has already been dropped", self.id);
+        }
+    }
+
     pub fn set_menu(&self, menu: Menu) {
         if let Some(w) = self.window.upgrade() {
             w.set_menu(menu);
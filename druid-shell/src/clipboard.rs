@@ -15,6 +15,8 @@
 //! Interacting with the system pasteboard/clipboard.
 pub use crate::backend::clipboard as backend;
 
+use crate::piet::{ImageBuf, ImageFormat};
+
 /// A handle to the system clipboard.
 ///
 /// To get access to the global clipboard, call [`Application::clipboard()`].
@@ -25,6 +27,13 @@ pub use crate::backend::clipboard as backend;
 /// Copying and pasting text is simple, using [`Clipboard::put_string`] and
 /// [`Clipboard::get_string`]. If this is all you need, you're in luck.
 ///
+/// # Working with images
+///
+/// Images can be copied and pasted with [`Clipboard::put_image`] and
+/// [`Clipboard::get_image`]. These convert between an [`ImageBuf`] and the
+/// image format that other applications on the platform expect: a device
+/// independent bitmap (`CF_DIB`) on Windows, and PNG elsewhere.
+///
 /// # Advanced usage
 ///
 /// When working with data more complicated than plaintext, you will generally
@@ -121,6 +130,9 @@ pub use crate::backend::clipboard as backend;
 /// [`Application::clipboard()`]: struct.Application.html#method.clipboard
 /// [`Clipboard::put_string`]: struct.Clipboard.html#method.put_string
 /// [`Clipboard::get_string`]: struct.Clipboard.html#method.get_string
+/// [`Clipboard::put_image`]: struct.Clipboard.html#method.put_image
+/// [`Clipboard::get_image`]: struct.Clipboard.html#method.get_image
+/// [`ImageBuf`]: crate::piet::ImageBuf
 /// [`FormatId`]: type.FormatId.html
 /// [`Universal Type Identifier`]: https://escapetech.eu/manuals/qdrop/uti.html
 /// [MIME types]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types
@@ -144,6 +156,24 @@ impl Clipboard {
         self.0.get_string()
     }
 
+    /// Put an image onto the system clipboard.
+    ///
+    /// This replaces the current contents of the clipboard.
+    pub fn put_image(&mut self, image: &ImageBuf) {
+        self.0.put_image(image)
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    ///
+    /// Returns `None` if there is no image on the clipboard, or if it is in a
+    /// format that can't be decoded. The returned image always has the
+    /// [`ImageFormat::RgbaSeparate`] format.
+    ///
+    /// [`ImageFormat::RgbaSeparate`]: crate::piet::ImageFormat::RgbaSeparate
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        self.0.get_image()
+    }
+
     /// Given a list of supported clipboard types, returns the supported type which has
     /// highest priority on the system clipboard, or `None` if no types are supported.
     pub fn preferred_format(&self, formats: &[FormatId]) -> Option<FormatId> {
@@ -222,6 +252,7 @@ cfg_if::cfg_if! {
             pub const PDF: &'static str = "com.adobe.pdf";
             pub const TEXT: &'static str = "public.utf8-plain-text";
             pub const SVG: &'static str = "public.svg-image";
+            pub const PNG: &'static str = "public.png";
         }
     } else {
         impl ClipboardFormat {
@@ -235,6 +266,257 @@ cfg_if::cfg_if! {
             }
             pub const PDF: &'static str = "application/pdf";
             pub const SVG: &'static str = "image/svg+xml";
+            pub const PNG: &'static str = "image/png";
         }
     }
 }
+
+/// Returns the pixels of `image` as 8-bit RGBA, with separate alpha.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub(crate) fn rgba_pixels(image: &ImageBuf) -> Vec<u8> {
+    if image.width() == 0 || image.height() == 0 {
+        return Vec::new();
+    }
+    if image.format() == ImageFormat::RgbaSeparate {
+        return image.raw_pixels().to_vec();
+    }
+    image
+        .pixel_colors()
+        .flatten()
+        .flat_map(|color| {
+            let (r, g, b, a) = color.as_rgba8();
+            [r, g, b, a]
+        })
+        .collect()
+}
+
+/// Encode `image` as a device independent bitmap, as used by `CF_DIB`.
+///
+/// The bitmap has 32 bits per pixel; its fourth byte is the alpha channel,
+/// which is preserved by most applications that understand it.
+#[cfg(any(target_os = "windows", test))]
+pub(crate) fn encode_dib(image: &ImageBuf) -> Vec<u8> {
+    const HEADER_SIZE: u32 = 40;
+    let (width, height) = (image.width(), image.height());
+    let pixels = rgba_pixels(image);
+
+    let mut dib = Vec::with_capacity(HEADER_SIZE as usize + pixels.len());
+    dib.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+    dib.extend_from_slice(&(width as i32).to_le_bytes());
+    // A positive height means that the rows are stored bottom-up.
+    dib.extend_from_slice(&(height as i32).to_le_bytes());
+    dib.extend_from_slice(&1u16.to_le_bytes());
+    dib.extend_from_slice(&32u16.to_le_bytes());
+    // BI_RGB, the size of the image, the resolution, and the palette.
+    dib.extend_from_slice(&0u32.to_le_bytes());
+    dib.extend_from_slice(&(pixels.len() as u32).to_le_bytes());
+    dib.extend_from_slice(&[0; 16]);
+
+    if width > 0 {
+        for row in pixels.chunks_exact(width * 4).rev() {
+            for px in row.chunks_exact(4) {
+                dib.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+            }
+        }
+    }
+    dib
+}
+
+/// Decode a device independent bitmap with 24 or 32 bits per pixel.
+///
+/// Returns `None` if the bitmap is malformed, or uses a palette or
+/// compression.
+#[cfg(any(target_os = "windows", test))]
+pub(crate) fn decode_dib(dib: &[u8]) -> Option<ImageBuf> {
+    const BI_RGB: u32 = 0;
+    const BI_BITFIELDS: u32 = 3;
+
+    let u32_at = |offset: usize| -> Option<u32> {
+        let bytes = dib.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    };
+    let header_size = u32_at(0)? as usize;
+    if header_size < 40 {
+        return None;
+    }
+    let width = u32_at(4)? as i32;
+    let height = u32_at(8)? as i32;
+    let bit_count = u32_at(12)? >> 16;
+    let compression = u32_at(16)?;
+    let colors_used = u32_at(32)?;
+    if width <= 0 || height == 0 || colors_used != 0 {
+        return None;
+    }
+
+    let (masks, masks_size) = match (compression, bit_count) {
+        (BI_RGB, 24) => ([0xff0000, 0xff00, 0xff, 0], 0),
+        (BI_RGB, 32) => ([0xff0000, 0xff00, 0xff, 0xff000000], 0),
+        (BI_BITFIELDS, 32) if header_size == 40 => ([u32_at(40)?, u32_at(44)?, u32_at(48)?, 0], 12),
+        (BI_BITFIELDS, 32) if header_size >= 56 => {
+            ([u32_at(40)?, u32_at(44)?, u32_at(48)?, u32_at(52)?], 0)
+        }
+        _ => return None,
+    };
+
+    let (width, bottom_up) = (width as usize, height > 0);
+    let height = height.unsigned_abs() as usize;
+    let bytes_per_pixel = bit_count as usize / 8;
+    // Rows are padded to a multiple of four bytes.
+    let row_size = width.checked_mul(bytes_per_pixel)?;
+    let stride = row_size + (4 - row_size % 4) % 4;
+    let start = header_size + masks_size;
+    let data = dib.get(start..start.checked_add(stride.checked_mul(height)?)?)?;
+
+    let channel = |px: u32, mask: u32| -> u8 {
+        if mask == 0 {
+            return 0xff;
+        }
+        let shift = mask.trailing_zeros();
+        let max = (mask >> shift) as u64;
+        (((px & mask) >> shift) as u64 * 0xff / max) as u8
+    };
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let row = if bottom_up { height - 1 - y } else { y };
+        let row = &data[row * stride..][..width * bytes_per_pixel];
+        for px in row.chunks_exact(bytes_per_pixel) {
+            let px = u32::from_le_bytes([px[0], px[1], px[2], *px.get(3).unwrap_or(&0)]);
+            pixels.extend_from_slice(&[
+                channel(px, masks[0]),
+                channel(px, masks[1]),
+                channel(px, masks[2]),
+                channel(px, masks[3]),
+            ]);
+        }
+    }
+    // Many applications leave the fourth byte of 32-bit bitmaps empty.
+    if masks[3] != 0 && pixels.chunks_exact(4).all(|px| px[3] == 0) {
+        pixels.chunks_exact_mut(4).for_each(|px| px[3] = 0xff);
+    }
+    Some(ImageBuf::from_raw(
+        pixels,
+        ImageFormat::RgbaSeparate,
+        width,
+        height,
+    ))
+}
+
+/// Encode `image` as a PNG.
+#[cfg(any(
+    target_os = "macos",
+    feature = "x11",
+    feature = "wayland",
+    all(test, any(target_os = "linux", target_os = "openbsd"))
+))]
+pub(crate) fn encode_png(image: &ImageBuf) -> Option<Vec<u8>> {
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let result = encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&rgba_pixels(image)));
+    if let Err(err) = result {
+        tracing::warn!("failed to encode clipboard image: {}", err);
+        return None;
+    }
+    Some(png)
+}
+
+/// Decode a PNG, returning `None` if it is malformed.
+#[cfg(any(
+    target_os = "macos",
+    feature = "x11",
+    feature = "wayland",
+    all(test, any(target_os = "linux", target_os = "openbsd"))
+))]
+pub(crate) fn decode_png(data: &[u8]) -> Option<ImageBuf> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().ok()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).ok()?;
+    buf.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|px| [px[0], px[1], px[2], 0xff])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|px| [px[0], px[0], px[0], px[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&v| [v, v, v, 0xff]).collect(),
+        // Palettes are expanded to RGB(A) by the decoder.
+        png::ColorType::Indexed => return None,
+    };
+    Some(ImageBuf::from_raw(
+        pixels,
+        ImageFormat::RgbaSeparate,
+        info.width as usize,
+        info.height as usize,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_image() -> ImageBuf {
+        #[rustfmt::skip]
+        let pixels = vec![
+            0xff, 0x00, 0x00, 0xff,  0x00, 0xff, 0x00, 0x80,  0x00, 0x00, 0xff, 0x00,
+            0x10, 0x20, 0x30, 0xff,  0x40, 0x50, 0x60, 0xff,  0x70, 0x80, 0x90, 0xff,
+        ];
+        ImageBuf::from_raw(pixels, ImageFormat::RgbaSeparate, 3, 2)
+    }
+
+    #[test]
+    fn dib_round_trip() {
+        let image = test_image();
+        let dib = encode_dib(&image);
+        // The header, and two rows of three 32-bit pixels.
+        assert_eq!(dib.len(), 40 + 2 * 3 * 4);
+        let decoded = decode_dib(&dib).unwrap();
+        assert_eq!(decoded.width(), 3);
+        assert_eq!(decoded.height(), 2);
+        assert_eq!(decoded.raw_pixels(), image.raw_pixels());
+
+        assert!(decode_dib(&dib[..50]).is_none());
+        assert!(decode_dib(&[]).is_none());
+    }
+
+    #[test]
+    fn dib_without_alpha_is_opaque() {
+        // A top-down, 24-bit bitmap of two pixels, each row padded to four bytes.
+        let mut dib = encode_dib(&test_image())[..40].to_vec();
+        dib[4..8].copy_from_slice(&2i32.to_le_bytes());
+        dib[8..12].copy_from_slice(&(-1i32).to_le_bytes());
+        dib[14..16].copy_from_slice(&24u16.to_le_bytes());
+        dib.extend_from_slice(&[0x30, 0x20, 0x10, 0x60, 0x50, 0x40, 0, 0]);
+        let decoded = decode_dib(&dib).unwrap();
+        assert_eq!(
+            decoded.raw_pixels(),
+            [0x10, 0x20, 0x30, 0xff, 0x40, 0x50, 0x60, 0xff]
+        );
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "openbsd"))]
+    #[test]
+    fn png_round_trip() {
+        let image = test_image();
+        let decoded = decode_png(&encode_png(&image).unwrap()).unwrap();
+        assert_eq!(decoded.width(), 3);
+        assert_eq!(decoded.raw_pixels(), image.raw_pixels());
+        assert!(decode_png(b"not a png").is_none());
+
+        let gray = ImageBuf::from_raw(vec![0x00, 0x80], ImageFormat::Grayscale, 1, 2);
+        let decoded = decode_png(&encode_png(&gray).unwrap()).unwrap();
+        assert_eq!(
+            decoded.raw_pixels(),
+            [0, 0, 0, 0xff, 0x80, 0x80, 0x80, 0xff]
+        );
+    }
+}
@@ -82,6 +82,19 @@ pub enum FileDialogType {
 /// and then keep saving to that file without showing a save dialog.
 /// Use the save dialog only for selecting a new location.
 ///
+/// # Platform support
+///
+/// The options are passed on to the platform's native dialog. Where a platform
+/// doesn't support an option, it falls back as follows:
+///
+/// - GTK has no setting for [`name_label`], and keeps its own label.
+/// - GTK doesn't filter the directories by [`allowed_types`] in *directories mode*,
+///   because the filter would stop the user from traversing into directories.
+/// - [`packages_as_directories`] only has an effect on macOS.
+/// - [`select_directories`] and [`multi_selection`] have no effect on save dialogs.
+/// - The X11, Wayland and web backends don't have file dialogs yet.
+///   Opening a dialog logs a warning, and returns no token.
+///
 /// # macOS
 ///
 /// The file dialog works a bit differently on macOS. For a lot of applications this doesn't matter
@@ -136,6 +149,8 @@ pub enum FileDialogType {
 /// [`select_directories`]: #method.select_directories
 /// [`allowed_types`]: #method.allowed_types
 /// [`packages_as_directories`]: #method.packages_as_directories
+/// [`name_label`]: #method.name_label
+/// [`multi_selection`]: #method.multi_selection
 /// [druid#998]: https://github.com/xi-editor/druid/issues/998
 #[derive(Debug, Clone, Default)]
 pub struct FileDialogOptions {
@@ -222,7 +237,13 @@ impl FileDialogOptions {
 
     /// Set multiple items to be selectable.
     ///
+    /// The chosen items are passed to [`WinHandler::open_files`], instead of
+    /// [`WinHandler::open_file`].
+    ///
     /// This is only relevant for open dialogs.
+    ///
+    /// [`WinHandler::open_files`]: crate::WinHandler::open_files
+    /// [`WinHandler::open_file`]: crate::WinHandler::open_file
     pub fn multi_selection(mut self) -> Self {
         self.multi_selection = true;
         self
@@ -251,6 +272,33 @@ impl FileDialogOptions {
         self
     }
 
+    /// Set the formats the user can choose from, as pairs of a human readable
+    /// name and an extension.
+    ///
+    /// This sets the [`allowed_types`] to a [`FileSpec`] for each format. In save
+    /// dialogs, the extension of the chosen format is applied to the path, and the
+    /// format is reported in [`FileInfo::format`].
+    ///
+    /// macOS shows a format selector below the files in save dialogs. The other
+    /// platforms don't support that; they list the formats as file type filters,
+    /// and report the selected filter as the format.
+    ///
+    /// ```
+    /// # use druid_shell::FileDialogOptions;
+    /// let options = FileDialogOptions::new()
+    ///     .with_format_choices(&[("Portable Network Graphics", "png"), ("JPEG", "jpg")])
+    ///     .default_name("export");
+    /// ```
+    ///
+    /// [`allowed_types`]: #method.allowed_types
+    pub fn with_format_choices(self, choices: &'static [(&'static str, &'static str)]) -> Self {
+        let types = choices
+            .iter()
+            .map(|(name, extension)| FileSpec::new(name, std::slice::from_ref(extension)))
+            .collect();
+        self.allowed_types(types)
+    }
+
     /// Set the default file type.
     ///
     /// The provided `default_type` must also be present in [`allowed_types`].
@@ -272,6 +320,8 @@ impl FileDialogOptions {
     }
 
     /// Set the text in the label next to the filename editbox.
+    ///
+    /// This is ignored on GTK.
     pub fn name_label(mut self, name_label: impl Into<String>) -> Self {
         self.name_label = Some(name_label.into());
         self
@@ -313,4 +363,19 @@ impl FileSpec {
     pub const fn new(name: &'static str, extensions: &'static [&'static str]) -> Self {
         FileSpec { name, extensions }
     }
+
+    /// Returns `true` if the extension of `path` is one of the extensions of
+    /// this `FileSpec`, ignoring ASCII case.
+    ///
+    /// ```
+    /// # use druid_shell::FileSpec;
+    /// assert!(FileSpec::JPG.matches("holiday/beach.JPEG".as_ref()));
+    /// assert!(!FileSpec::JPG.matches("notes.txt".as_ref()));
+    /// ```
+    pub fn matches(&self, path: &Path) -> bool {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) => self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)),
+            None => false,
+        }
+    }
 }
@@ -0,0 +1,30 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Files dragged onto a window from other applications.
+
+use std::path::PathBuf;
+
+use crate::kurbo::Point;
+
+/// Files that are being dragged over a window, or that were dropped onto it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDropEvent {
+    /// The location of the pointer in [display points] in relation to the window.
+    ///
+    /// [display points]: crate::Scale
+    pub pos: Point,
+    /// The paths of the dragged files.
+    pub paths: Vec<PathBuf>,
+}
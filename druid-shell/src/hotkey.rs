@@ -15,6 +15,7 @@
 //! Hotkeys and helpers for parsing keyboard shortcuts.
 
 use std::borrow::Borrow;
+use std::fmt;
 
 use tracing::warn;
 
@@ -118,6 +119,71 @@ impl HotKey {
     }
 }
 
+/// Formats the hotkey the way the platform shows it in menus, for example
+/// `Ctrl+Shift+S` on Windows and Linux, and `⇧⌘S` on macOS.
+impl fmt::Display for HotKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mods: Modifiers = self.mods.into();
+        if cfg!(target_os = "macos") {
+            let symbols = [
+                (mods.ctrl(), "⌃"),
+                (mods.alt(), "⌥"),
+                (mods.shift(), "⇧"),
+                (mods.meta(), "⌘"),
+            ];
+            for (_, symbol) in symbols.iter().filter(|(active, _)| *active) {
+                f.write_str(symbol)?;
+            }
+        } else {
+            let meta = if cfg!(target_os = "windows") {
+                "Windows"
+            } else {
+                "Super"
+            };
+            let names = [
+                (mods.ctrl(), "Ctrl"),
+                (mods.shift(), "Shift"),
+                (mods.alt(), "Alt"),
+                (mods.meta(), meta),
+            ];
+            for (_, name) in names.iter().filter(|(active, _)| *active) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        f.write_str(&key_name(&self.key))
+    }
+}
+
+/// The name of a key in a formatted [`HotKey`].
+fn key_name(key: &KbKey) -> String {
+    let mac = cfg!(target_os = "macos");
+    let (mac_name, name) = match key {
+        KbKey::Character(c) => match c.as_str() {
+            " " => ("Space", "Space"),
+            "+" if !mac => ("+", "Plus"),
+            "-" if !mac => ("-", "Minus"),
+            _ => return c.to_uppercase(),
+        },
+        KbKey::Enter => ("↩", "Enter"),
+        KbKey::Tab => ("⇥", "Tab"),
+        KbKey::Backspace => ("⌫", "Backspace"),
+        KbKey::Escape => ("⎋", "Esc"),
+        KbKey::Delete => ("⌦", "Del"),
+        KbKey::Insert => ("Ins", "Ins"),
+        KbKey::Home => ("↖", "Home"),
+        KbKey::End => ("↘", "End"),
+        KbKey::PageUp => ("⇞", "PgUp"),
+        KbKey::PageDown => ("⇟", "PgDn"),
+        // These names match LibreOffice.
+        KbKey::ArrowLeft => ("←", "Left"),
+        KbKey::ArrowRight => ("→", "Right"),
+        KbKey::ArrowUp => ("↑", "Up"),
+        KbKey::ArrowDown => ("↓", "Down"),
+        _ => return format!("{:?}", key),
+    };
+    if mac { mac_name } else { name }.to_string()
+}
+
 /// A platform-agnostic representation of keyboard modifiers, for command handling.
 ///
 /// This does one thing: it allows specifying hotkeys that use the Command key
@@ -245,3 +311,26 @@ impl From<SysMods> for RawMods {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let save = HotKey::new(SysMods::CmdShift, "S");
+        let left = HotKey::new(RawMods::Alt, KbKey::ArrowLeft);
+        let zoom = HotKey::new(SysMods::Cmd, "+");
+        let f5 = HotKey::new(None, KbKey::F5);
+        if cfg!(target_os = "macos") {
+            assert_eq!(save.to_string(), "⇧⌘S");
+            assert_eq!(left.to_string(), "⌥←");
+            assert_eq!(zoom.to_string(), "⌘+");
+        } else {
+            assert_eq!(save.to_string(), "Ctrl+Shift+S");
+            assert_eq!(left.to_string(), "Alt+Left");
+            assert_eq!(zoom.to_string(), "Ctrl+Plus");
+        }
+        assert_eq!(f5.to_string(), "F5");
+    }
+}
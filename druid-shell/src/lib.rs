@@ -59,6 +59,7 @@ mod clipboard;
 mod common_util;
 mod dialog;
 mod error;
+mod file_drop;
 mod hotkey;
 mod keyboard;
 mod menu;
@@ -66,16 +67,18 @@ mod mouse;
 mod region;
 mod scale;
 mod screen;
+mod tray;
 mod window;
 
 pub mod platform;
 pub mod text;
 
-pub use application::{AppHandler, Application};
+pub use application::{AppHandler, Application, ColorScheme};
 pub use clipboard::{Clipboard, ClipboardFormat, FormatId};
 pub use common_util::Counter;
 pub use dialog::{FileDialogOptions, FileInfo, FileSpec};
 pub use error::Error;
+pub use file_drop::FileDropEvent;
 pub use hotkey::{HotKey, RawMods, SysMods};
 pub use keyboard::{Code, IntoKey, KbKey, KeyEvent, KeyState, Location, Modifiers};
 pub use menu::Menu;
@@ -83,9 +86,10 @@ pub use mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
 pub use region::Region;
 pub use scale::{Scalable, Scale, ScaledArea};
 pub use screen::{Monitor, Screen};
+pub use tray::{TrayHandler, TrayIcon};
 pub use window::{
     FileDialogToken, IdleHandle, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowBuilder,
-    WindowHandle, WindowLevel, WindowState,
+    WindowHandle, WindowIcon, WindowLevel, WindowState,
 };
 
 pub use keyboard_types;
@@ -17,10 +17,17 @@ impl Region {
     }
 
     /// Adds a rectangle to this region.
+    ///
+    /// A rectangle that is already covered by one in the region is not added,
+    /// and the ones covered by the new rectangle are removed, so that a
+    /// region that is invalidated over and over, for instance by an
+    /// animation, doesn't grow.
     pub fn add_rect(&mut self, rect: Rect) {
-        if rect.area() > 0.0 {
-            self.rects.push(rect);
+        if rect.area() <= 0.0 || self.rects.iter().any(|r| contains(*r, rect)) {
+            return;
         }
+        self.rects.retain(|r| !contains(rect, *r));
+        self.rects.push(rect);
     }
 
     /// Replaces this region with a single rectangle.
@@ -77,7 +84,9 @@ impl Region {
 
     /// Modifies this region by including everything in the other region.
     pub fn union_with(&mut self, other: &Region) {
-        self.rects.extend_from_slice(&other.rects);
+        for &rect in &other.rects {
+            self.add_rect(rect);
+        }
     }
 
     /// Modifies this region by intersecting it with the given rectangle.
@@ -90,6 +99,11 @@ impl Region {
     }
 }
 
+/// Returns `true` if `outer` contains all of `inner`.
+fn contains(outer: Rect, inner: Rect) -> bool {
+    outer.x0 <= inner.x0 && outer.y0 <= inner.y0 && outer.x1 >= inner.x1 && outer.y1 >= inner.y1
+}
+
 impl std::ops::AddAssign<Vec2> for Region {
     fn add_assign(&mut self, rhs: Vec2) {
         for r in &mut self.rects {
@@ -111,3 +125,29 @@ impl From<Rect> for Region {
         Region { rects: vec![rect] }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn covered_rects() {
+        let small = Rect::new(10.0, 10.0, 20.0, 20.0);
+        let big = Rect::new(0.0, 0.0, 50.0, 50.0);
+        let other = Rect::new(40.0, 40.0, 60.0, 60.0);
+
+        let mut region = Region::from(small);
+        region.add_rect(small);
+        region.add_rect(other);
+        assert_eq!(region.rects(), &[small, other]);
+        region.add_rect(big);
+        assert_eq!(region.rects(), &[other, big]);
+        region.add_rect(small);
+        region.add_rect(Rect::ZERO);
+        assert_eq!(region.rects(), &[other, big]);
+
+        let mut union = Region::from(small);
+        union.union_with(&region);
+        assert_eq!(union.rects(), &[other, big]);
+    }
+}
@@ -15,7 +15,8 @@
 //! Module to get information about monitors
 
 use crate::backend;
-use crate::kurbo::Rect;
+use crate::kurbo::{Point, Rect};
+use crate::scale::Scale;
 use std::fmt;
 use std::fmt::Display;
 
@@ -32,6 +33,7 @@ pub struct Monitor {
     // https://docs.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-monitorinfo
     // Unsure about x11
     work_rect: Rect,
+    scale: Scale,
 }
 
 impl Monitor {
@@ -41,8 +43,17 @@ impl Monitor {
             primary,
             rect,
             work_rect,
+            scale: Scale::default(),
         }
     }
+
+    /// Set the scale of the monitor, for backends that know it.
+    #[allow(dead_code)]
+    pub(crate) fn with_scale(mut self, scale: Scale) -> Self {
+        self.scale = scale;
+        self
+    }
+
     /// Returns true if the monitor is the primary monitor.
     /// The primary monitor has its origin at (0, 0) in virtual screen coordinates.
     pub fn is_primary(&self) -> bool {
@@ -59,6 +70,14 @@ impl Monitor {
     pub fn virtual_work_rect(&self) -> Rect {
         self.work_rect
     }
+
+    /// Returns the scale of the monitor, which windows on it use to convert
+    /// between display points and pixels.
+    ///
+    /// This is `1.0` on platforms that don't report a scale per monitor.
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
 }
 
 impl Display for Monitor {
@@ -87,6 +106,21 @@ impl Screen {
         backend::screen::get_monitors()
     }
 
+    /// Returns the monitor that contains `point`, in virtual screen coordinates.
+    pub fn get_monitor_at(point: Point) -> Option<Monitor> {
+        Self::get_monitors()
+            .into_iter()
+            .find(|m| m.virtual_rect().contains(point))
+    }
+
+    /// Returns the monitor that shares the largest area with `rect`, in
+    /// virtual screen coordinates.
+    ///
+    /// If `rect` doesn't overlap any monitor, the primary monitor is returned.
+    pub fn get_monitor_for_rect(rect: Rect) -> Option<Monitor> {
+        monitor_for_rect(&Self::get_monitors(), rect)
+    }
+
     /// Returns the bounding rectangle of the total virtual screen space in pixels.
     pub fn get_display_rect() -> Rect {
         Self::get_monitors()
@@ -95,3 +129,41 @@ impl Screen {
             .fold(Rect::ZERO, |a, b| a.union(b))
     }
 }
+
+fn monitor_for_rect(monitors: &[Monitor], rect: Rect) -> Option<Monitor> {
+    let overlap = |m: &Monitor| m.virtual_rect().intersect(rect).area();
+    monitors
+        .iter()
+        .filter(|m| overlap(m) > 0.0)
+        .max_by(|a, b| {
+            overlap(a)
+                .partial_cmp(&overlap(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .or_else(|| monitors.iter().find(|m| m.is_primary()))
+        .cloned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn monitor_for_rect_picks_largest_overlap() {
+        let left = Rect::new(0.0, 0.0, 100.0, 100.0);
+        let right = Rect::new(100.0, 0.0, 200.0, 100.0);
+        let monitors = [
+            Monitor::new(true, left, left),
+            Monitor::new(false, right, right),
+        ];
+
+        let mostly_right = Rect::new(80.0, 10.0, 150.0, 50.0);
+        let found = monitor_for_rect(&monitors, mostly_right).unwrap();
+        assert_eq!(found.virtual_rect(), right);
+
+        // off-screen rects fall back to the primary monitor
+        let outside = Rect::new(500.0, 500.0, 600.0, 600.0);
+        let found = monitor_for_rect(&monitors, outside).unwrap();
+        assert!(found.is_primary());
+    }
+}
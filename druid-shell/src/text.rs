@@ -418,7 +418,7 @@ pub trait InputHandler {
     /// boundary.
     fn replace_range(&mut self, range: Range<usize>, text: &str);
 
-    /// Given a `Point`, determine the corresponding text position.
+    /// Given a `Point` in window coordinates, determine the corresponding text position.
     fn hit_test_point(&self, point: Point) -> HitTestPoint;
 
     /// Returns the range, in UTF-8 code units, of the line (soft- or hard-wrapped)
@@ -0,0 +1,79 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Icons in the system tray (the notification area on Windows, the menu bar on macOS).
+
+use crate::backend::tray as backend;
+use crate::error::Error;
+use crate::menu::Menu;
+use crate::piet::ImageBuf;
+
+/// App behavior in response to interaction with a [`TrayIcon`].
+///
+/// All methods have default implementations that do nothing.
+pub trait TrayHandler {
+    /// Called when the icon is clicked with the primary mouse button.
+    fn clicked(&mut self) {}
+
+    /// Called when the icon is double-clicked with the primary mouse button.
+    ///
+    /// This is called in addition to [`clicked`] for the first click.
+    ///
+    /// [`clicked`]: TrayHandler::clicked
+    fn double_clicked(&mut self) {}
+
+    /// Called when an item of the icon's menu is selected.
+    ///
+    /// The `id` is the one the item was created with in [`Menu::add_item`].
+    fn command(&mut self, id: u32) {
+        let _ = id;
+    }
+}
+
+/// An icon in the system tray.
+///
+/// The icon stays visible until this is dropped.
+///
+/// Tray icons are currently supported on Windows and macOS; on other platforms
+/// [`TrayIcon::new`] returns an error.
+pub struct TrayIcon(backend::TrayIcon);
+
+impl TrayIcon {
+    /// Add a new icon to the system tray.
+    ///
+    /// Interaction with the icon is reported to the `handler`. The icon is
+    /// empty until [`set_icon`] is called.
+    ///
+    /// [`set_icon`]: TrayIcon::set_icon
+    pub fn new(handler: Box<dyn TrayHandler>) -> Result<TrayIcon, Error> {
+        backend::TrayIcon::new(handler).map(TrayIcon)
+    }
+
+    /// Set the image displayed in the tray.
+    pub fn set_icon(&self, icon: &ImageBuf) {
+        self.0.set_icon(icon)
+    }
+
+    /// Set the text that is shown when hovering over the icon.
+    pub fn set_tooltip(&self, tooltip: &str) {
+        self.0.set_tooltip(tooltip)
+    }
+
+    /// Set the menu that is shown when the icon is right-clicked.
+    ///
+    /// The menu should be created with [`Menu::new_for_popup`].
+    pub fn set_menu(&self, menu: Option<Menu>) {
+        self.0.set_menu(menu.map(Menu::into_inner))
+    }
+}
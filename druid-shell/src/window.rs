@@ -15,19 +15,23 @@
 //! Platform independent window types.
 
 use std::any::Any;
+use std::fmt;
 use std::time::Duration;
 
-use crate::application::Application;
+use crate::application::{Application, ColorScheme};
 use crate::backend::window as backend;
 use crate::common_util::Counter;
 use crate::dialog::{FileDialogOptions, FileInfo};
 use crate::error::Error;
+use crate::file_drop::FileDropEvent;
 use crate::keyboard::KeyEvent;
 use crate::kurbo::{Insets, Point, Rect, Size};
 use crate::menu::Menu;
 use crate::mouse::{Cursor, CursorDesc, MouseEvent};
+use crate::piet::ImageBuf;
 use crate::region::Region;
 use crate::scale::Scale;
+use crate::screen::Monitor;
 use crate::text::{Event, InputHandler};
 use piet_common::PietText;
 #[cfg(feature = "raw-win-handle")]
@@ -168,6 +172,84 @@ pub enum WindowState {
     Restored,
 }
 
+/// The icon of a window, in one or more sizes.
+///
+/// Platforms show the icon at different sizes, for instance in the titlebar
+/// and in the taskbar, and pick the image closest to each of them; supplying
+/// a few sizes (such as 16, 32 and 256 pixels square) looks better than
+/// having a single image scaled.
+///
+/// Images can be in any [`ImageFormat`]; empty images are ignored.
+///
+/// [`ImageFormat`]: crate::piet::ImageFormat
+#[derive(Clone, Default)]
+pub struct WindowIcon {
+    images: Vec<ImageBuf>,
+}
+
+impl WindowIcon {
+    /// Create an icon from a single image.
+    pub fn new(image: ImageBuf) -> WindowIcon {
+        WindowIcon::default().with_size(image)
+    }
+
+    /// Builder-style method for adding another size of the icon.
+    pub fn with_size(mut self, image: ImageBuf) -> WindowIcon {
+        if image.width() == 0 || image.height() == 0 {
+            tracing::warn!("ignoring an empty window icon image");
+        } else {
+            self.images.push(image);
+        }
+        self
+    }
+
+    /// The images of this icon, in the order they were added.
+    pub fn images(&self) -> &[ImageBuf] {
+        &self.images
+    }
+
+    /// The image to show when the icon is `size` pixels square.
+    ///
+    /// This is the smallest image that is at least as large, or the largest
+    /// image if they are all smaller. It is `None` if there are no images.
+    pub fn best_for(&self, size: usize) -> Option<&ImageBuf> {
+        let extent = |image: &&ImageBuf| image.width().max(image.height());
+        self.images
+            .iter()
+            .filter(|image| extent(image) >= size)
+            .min_by_key(extent)
+            .or_else(|| self.images.iter().max_by_key(extent))
+    }
+}
+
+impl From<ImageBuf> for WindowIcon {
+    fn from(image: ImageBuf) -> WindowIcon {
+        WindowIcon::new(image)
+    }
+}
+
+impl PartialEq for WindowIcon {
+    fn eq(&self, other: &WindowIcon) -> bool {
+        self.images.len() == other.images.len()
+            && self
+                .images
+                .iter()
+                .zip(&other.images)
+                .all(|(a, b)| a.ptr_eq(b))
+    }
+}
+
+impl fmt::Debug for WindowIcon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sizes: Vec<_> = self
+            .images
+            .iter()
+            .map(|image| (image.width(), image.height()))
+            .collect();
+        f.debug_struct("WindowIcon").field("sizes", &sizes).finish()
+    }
+}
+
 /// A handle to a platform window object.
 #[derive(Clone, Default, PartialEq)]
 pub struct WindowHandle(pub(crate) backend::WindowHandle);
@@ -211,6 +293,24 @@ impl WindowHandle {
         self.0.handle_titlebar(val);
     }
 
+    /// Start moving the window with the mouse, as if its titlebar was being dragged.
+    ///
+    /// Call this in response to a left button [`WinHandler::mouse_down`]; the platform then
+    /// moves the window until the button is released. This makes it possible to move a window
+    /// that has no titlebar.
+    ///
+    /// This is currently only implemented on Windows, macOS and GTK.
+    pub fn begin_move_drag(&self) {
+        self.0.begin_move_drag();
+    }
+
+    /// Set whether the window is kept on top of other windows.
+    ///
+    /// This is currently only implemented on Windows, macOS and GTK.
+    pub fn set_always_on_top(&self, on_top: bool) {
+        self.0.set_always_on_top(on_top);
+    }
+
     /// Set whether the window should show titlebar.
     pub fn show_titlebar(&self, show_titlebar: bool) {
         self.0.show_titlebar(show_titlebar)
@@ -274,6 +374,15 @@ impl WindowHandle {
         self.0.get_size()
     }
 
+    /// Returns the monitor the window is on.
+    ///
+    /// If the window spans several monitors, this is the one that contains
+    /// the largest part of it. Use this to place new windows on the same
+    /// monitor, for instance.
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        self.0.get_monitor()
+    }
+
     /// Bring this window to the front of the window stack and give it focus.
     pub fn bring_to_front_and_focus(&self) {
         self.0.bring_to_front_and_focus()
@@ -311,6 +420,15 @@ impl WindowHandle {
         self.0.set_menu(menu.into_inner())
     }
 
+    /// Set the icon of this window, which is also shown in the taskbar.
+    ///
+    /// This is currently implemented on Windows, macOS, GTK and X11. macOS
+    /// has no per-window icons; there this sets the icon of the application
+    /// in the dock, for as long as it is running.
+    pub fn set_icon(&self, icon: &WindowIcon) {
+        self.0.set_icon(icon)
+    }
+
     /// Get access to a type that can perform text layout.
     pub fn text(&self) -> PietText {
         self.0.text()
@@ -469,11 +587,29 @@ impl WindowBuilder {
         self.0.set_min_size(size)
     }
 
+    /// Set the window's maximum drawing area size in [display points].
+    ///
+    /// This is a request to the platform, which enforces it while the user
+    /// resizes the window. It has no effect on web, where windows can't be
+    /// resized by the user.
+    ///
+    /// [display points]: crate::Scale
+    pub fn set_max_size(&mut self, size: Size) {
+        self.0.set_max_size(size)
+    }
+
     /// Set whether the window should be resizable.
     pub fn resizable(&mut self, resizable: bool) {
         self.0.resizable(resizable)
     }
 
+    /// Set whether the window is kept on top of other windows.
+    ///
+    /// This is currently only implemented on Windows, macOS and GTK.
+    pub fn set_always_on_top(&mut self, on_top: bool) {
+        self.0.set_always_on_top(on_top)
+    }
+
     /// Set whether the window should have a titlebar and decorations.
     pub fn show_titlebar(&mut self, show_titlebar: bool) {
         self.0.show_titlebar(show_titlebar)
@@ -509,6 +645,13 @@ impl WindowBuilder {
         self.0.set_menu(menu.into_inner())
     }
 
+    /// Set the window's initial icon.
+    ///
+    /// See [`WindowHandle::set_icon`] for the platforms this is supported on.
+    pub fn set_icon(&mut self, icon: WindowIcon) {
+        self.0.set_icon(icon)
+    }
+
     /// Sets the initial state of the window.
     pub fn set_window_state(&mut self, state: WindowState) {
         self.0.set_window_state(state);
@@ -548,6 +691,13 @@ pub trait WinHandler {
     #[allow(unused_variables)]
     fn scale(&mut self, scale: Scale) {}
 
+    /// Called when the user changes the preferred [`ColorScheme`] of the
+    /// system.
+    ///
+    /// See [`Application::get_color_scheme`] for platform support.
+    #[allow(unused_variables)]
+    fn color_scheme_changed(&mut self, scheme: ColorScheme) {}
+
     /// Request the handler to prepare to paint the window contents.  In particular, if there are
     /// any regions that need to be repainted on the next call to `paint`, the handler should
     /// invalidate those regions by calling [`WindowHandle::invalidate_rect`] or
@@ -588,7 +738,7 @@ pub trait WinHandler {
     /// Called when an "Open" dialog with multiple selection is closed.
     ///
     /// `token` is the value returned by [`WindowHandle::open_file`]. `files` contains the information
-    /// of the chosen paths, or `None` if the save dialog was cancelled.
+    /// of the chosen paths, and is empty if the dialog was cancelled.
     #[allow(unused_variables)]
     fn open_files(&mut self, token: FileDialogToken, files: Vec<FileInfo>) {}
 
@@ -665,6 +815,32 @@ pub trait WinHandler {
     /// Called when the mouse cursor has left the application window
     fn mouse_leave(&mut self) {}
 
+    /// Called when files are dragged into the window from another
+    /// application, and whenever they are moved within it.
+    ///
+    /// Return `true` if the files would be accepted if they were dropped at
+    /// this position; the platform uses this to choose the cursor.
+    ///
+    /// This is currently supported on GTK and macOS.
+    #[allow(unused_variables)]
+    fn file_drag_move(&mut self, event: &FileDropEvent) -> bool {
+        false
+    }
+
+    /// Called when dragged files leave the window, or the drag is cancelled.
+    fn file_drag_leave(&mut self) {}
+
+    /// Called when files are dropped onto the window.
+    ///
+    /// Return `true` if the files were accepted.
+    ///
+    /// This is currently supported on GTK, macOS and Windows. Windows does not
+    /// report files being dragged over the window before they are dropped.
+    #[allow(unused_variables)]
+    fn file_drop(&mut self, event: &FileDropEvent) -> bool {
+        false
+    }
+
     /// Called on timer event.
     ///
     /// This is called at (approximately) the requested deadline by a
@@ -712,9 +888,27 @@ impl From<backend::WindowHandle> for WindowHandle {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::piet::ImageFormat;
 
     use static_assertions as sa;
 
     sa::assert_not_impl_any!(WindowHandle: Send, Sync);
     sa::assert_impl_all!(IdleHandle: Send);
+
+    #[test]
+    fn best_icon_size() {
+        let image =
+            |size| ImageBuf::from_raw(vec![0; size * size], ImageFormat::Grayscale, size, size);
+        let icon = WindowIcon::new(image(32))
+            .with_size(image(0))
+            .with_size(image(16))
+            .with_size(image(64));
+        let best = |size| icon.best_for(size).map(ImageBuf::width);
+        assert_eq!(icon.images().len(), 3);
+        assert_eq!(best(16), Some(16));
+        assert_eq!(best(20), Some(32));
+        assert_eq!(best(48), Some(64));
+        assert_eq!(best(256), Some(64));
+        assert!(WindowIcon::default().best_for(16).is_none());
+    }
 }
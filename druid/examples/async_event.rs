@@ -52,7 +52,7 @@ pub fn main() {
 }
 
 fn generate_colors(event_sink: druid::ExtEventSink) {
-    // This function is called in a separate thread, and runs until the program ends.
+    // This function is called in a separate thread, and runs until the application exits.
     // We take an `ExtEventSink` as an argument, we can use this event sink to send
     // commands to the main thread. Every time we generate a new colour we send it
     // to the main thread.
@@ -71,10 +71,14 @@ fn generate_colors(event_sink: druid::ExtEventSink) {
         };
 
         let color_clone = color.clone();
-        // schedule idle callback to change the data
-        event_sink.add_idle_callback(move |data: &mut Color| {
+        // schedule idle callback to change the data; this fails once the
+        // application has exited.
+        let sent = event_sink.add_idle_callback(move |data: &mut Color| {
             *data = color_clone;
         });
+        if sent.is_err() {
+            break;
+        }
         thread::sleep(Duration::from_millis(20));
     }
 }
@@ -23,9 +23,9 @@ use druid::text::{AttributesAdder, RichText, RichTextBuilder};
 use druid::widget::prelude::*;
 use druid::widget::{Controller, LineBreaking, RawLabel, Scroll, Split, TextBox};
 use druid::{
-    AppDelegate, AppLauncher, Color, Command, Data, DelegateCtx, FontFamily, FontStyle, FontWeight,
-    Handled, Lens, LocalizedString, Menu, Selector, Target, Widget, WidgetExt, WindowDesc,
-    WindowId,
+    theme, AppDelegate, AppLauncher, Color, Command, Data, DelegateCtx, FontFamily, FontStyle,
+    FontWeight, Handled, Lens, LocalizedString, Menu, Selector, Target, Widget, WidgetExt,
+    WindowDesc, WindowId,
 };
 
 const WINDOW_TITLE: LocalizedString<AppState> = LocalizedString::new("Minimal Markdown");
@@ -40,7 +40,6 @@ const TEXT: &str = "*Hello* ***world***! This is a `TextBox` where you can \
 
 const SPACER_SIZE: f64 = 8.0;
 const BLOCKQUOTE_COLOR: Color = Color::grey8(0x88);
-const LINK_COLOR: Color = Color::rgb8(0, 0, 0xEE);
 const OPEN_LINK: Selector<String> = Selector::new("druid-example.open-link");
 
 #[derive(Clone, Data, Lens)]
@@ -118,6 +117,11 @@ fn build_root_widget() -> impl Widget<AppState> {
             .with_text_color(Color::BLACK)
             .with_line_break_mode(LineBreaking::WordWrap)
             .lens(AppState::rendered)
+            .env_scope(|env, _| {
+                env.set(theme::LINK_COLOR, Color::rgb8(0, 0, 0xEE));
+                env.set(theme::LINK_VISITED_COLOR, Color::rgb8(0x55, 0x1A, 0x8B));
+                env.set(theme::LINK_HOVER_COLOR, Color::rgb8(0x33, 0x33, 0xFF));
+            })
             .expand_width()
             .padding((SPACER_SIZE * 4.0, SPACER_SIZE)),
     )
@@ -222,10 +226,7 @@ fn add_attribute_for_tag(tag: &Tag, mut attrs: AttributesAdder) {
             attrs.strikethrough(true);
         }
         Tag::Link(_link_ty, target, _title) => {
-            attrs
-                .underline(true)
-                .text_color(LINK_COLOR)
-                .link(OPEN_LINK.with(target.to_string()));
+            attrs.link(OPEN_LINK.with(target.to_string()));
         }
         // ignore other tags for now
         _ => (),
@@ -55,7 +55,7 @@ fn build_app() -> impl Widget<u32> {
             .split_point(0.5)
             .draggable(true)
             .solid_bar(true)
-            .min_size(60.0, 60.0),
+            .with_min_size(60.0, 60.0),
         )
         .border(Color::WHITE, 1.0),
     );
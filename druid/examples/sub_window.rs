@@ -15,7 +15,6 @@
 // On Windows platform, don't show a console when opening the app.
 #![windows_subsystem = "windows"]
 
-use druid::commands::CLOSE_WINDOW;
 use druid::lens::Unit;
 use druid::widget::{
     Align, Button, Checkbox, Controller, ControllerHost, EnvScope, Flex, Label, TextBox,
@@ -23,11 +22,10 @@ use druid::widget::{
 use druid::{
     theme, Affine, AppLauncher, BoxConstraints, Color, Data, Env, Event, EventCtx, LayoutCtx, Lens,
     LensExt, LifeCycle, LifeCycleCtx, LocalizedString, PaintCtx, Point, Rect, RenderContext, Size,
-    TimerToken, UpdateCtx, Widget, WidgetExt, WindowConfig, WindowDesc, WindowId, WindowSizePolicy,
+    UpdateCtx, Widget, WidgetExt, WindowConfig, WindowDesc,
 };
 use druid_shell::piet::Text;
 use druid_shell::{Screen, WindowLevel};
-use instant::{Duration, Instant};
 use piet_common::{TextLayout, TextLayoutBuilder};
 
 const VERTICAL_WIDGET_SPACING: f64 = 20.0;
@@ -68,142 +66,6 @@ pub fn main() {
         .expect("Failed to launch application");
 }
 
-enum TooltipState {
-    Showing(WindowId),
-    Waiting {
-        last_move: Instant,
-        timer_expire: Instant,
-        token: TimerToken,
-        position_in_window_coordinates: Point,
-    },
-    Fresh,
-}
-
-struct TooltipController {
-    tip: String,
-    state: TooltipState,
-}
-
-impl TooltipController {
-    pub fn new(tip: impl Into<String>) -> Self {
-        TooltipController {
-            tip: tip.into(),
-            state: TooltipState::Fresh,
-        }
-    }
-}
-
-impl<T, W: Widget<T>> Controller<T, W> for TooltipController {
-    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
-        let wait_duration = Duration::from_millis(500);
-        let resched_dur = Duration::from_millis(50);
-        let cursor_size = Size::new(15., 15.);
-        let now = Instant::now();
-        let new_state = match &self.state {
-            TooltipState::Fresh => match event {
-                Event::MouseMove(me) if ctx.is_hot() => Some(TooltipState::Waiting {
-                    last_move: now,
-                    timer_expire: now + wait_duration,
-                    token: ctx.request_timer(wait_duration),
-                    position_in_window_coordinates: me.window_pos,
-                }),
-                _ => None,
-            },
-            TooltipState::Waiting {
-                last_move,
-                timer_expire,
-                token,
-                position_in_window_coordinates,
-            } => match event {
-                Event::MouseMove(me) if ctx.is_hot() => {
-                    let (cur_token, cur_expire) = if *timer_expire - now < resched_dur {
-                        (ctx.request_timer(wait_duration), now + wait_duration)
-                    } else {
-                        (*token, *timer_expire)
-                    };
-                    Some(TooltipState::Waiting {
-                        last_move: now,
-                        timer_expire: cur_expire,
-                        token: cur_token,
-                        position_in_window_coordinates: me.window_pos,
-                    })
-                }
-                Event::Timer(tok) if tok == token => {
-                    let deadline = *last_move + wait_duration;
-                    ctx.set_handled();
-                    if deadline > now {
-                        let wait_for = deadline - now;
-                        tracing::info!("Waiting another {:?}", wait_for);
-                        Some(TooltipState::Waiting {
-                            last_move: *last_move,
-                            timer_expire: deadline,
-                            token: ctx.request_timer(wait_for),
-                            position_in_window_coordinates: *position_in_window_coordinates,
-                        })
-                    } else {
-                        let tooltip_position_in_window_coordinates =
-                            (position_in_window_coordinates.to_vec2() + cursor_size.to_vec2())
-                                .to_point();
-                        let win_id = ctx.new_sub_window(
-                            WindowConfig::default()
-                                .show_titlebar(false)
-                                .window_size_policy(WindowSizePolicy::Content)
-                                .set_level(WindowLevel::Tooltip(ctx.window().clone()))
-                                .set_position(tooltip_position_in_window_coordinates),
-                            Label::<()>::new(self.tip.clone()),
-                            (),
-                            env.clone(),
-                        );
-                        Some(TooltipState::Showing(win_id))
-                    }
-                }
-                _ => None,
-            },
-            TooltipState::Showing(win_id) => {
-                match event {
-                    Event::MouseMove(me) if !ctx.is_hot() => {
-                        // TODO another timer on leaving
-                        tracing::info!("Sending close window for {:?}", win_id);
-                        ctx.submit_command(CLOSE_WINDOW.to(*win_id));
-                        Some(TooltipState::Waiting {
-                            last_move: now,
-                            timer_expire: now + wait_duration,
-                            token: ctx.request_timer(wait_duration),
-                            position_in_window_coordinates: me.window_pos,
-                        })
-                    }
-                    _ => None,
-                }
-            }
-        };
-
-        if let Some(state) = new_state {
-            self.state = state;
-        }
-
-        if !ctx.is_handled() {
-            child.event(ctx, event, data, env);
-        }
-    }
-
-    fn lifecycle(
-        &mut self,
-        child: &mut W,
-        ctx: &mut LifeCycleCtx,
-        event: &LifeCycle,
-        data: &T,
-        env: &Env,
-    ) {
-        if let LifeCycle::HotChanged(false) = event {
-            if let TooltipState::Showing(win_id) = self.state {
-                ctx.submit_command(CLOSE_WINDOW.to(win_id));
-            }
-            self.state = TooltipState::Fresh;
-        }
-        child.lifecycle(ctx, event, data, env)
-    }
-}
-
 struct DragWindowController {
     init_pos: Option<Point>,
     //dragging: bool
@@ -330,12 +192,10 @@ impl<W: Widget<bool>> Controller<bool, W> for CancelClose {
 fn build_root_widget() -> impl Widget<HelloState> {
     let label = EnvScope::new(
         |env, _t| env.set(theme::TEXT_COLOR, env.get(theme::PRIMARY_LIGHT)),
-        ControllerHost::new(
-            Label::new(|data: &HelloState, _env: &Env| {
-                format!("Hello {}! {} ", data.name, data.sub.my_stuff)
-            }),
-            TooltipController::new("Tips! Are good"),
-        ),
+        Label::new(|data: &HelloState, _env: &Env| {
+            format!("Hello {}! {} ", data.name, data.sub.my_stuff)
+        })
+        .tooltip("Tips! Are good"),
     );
     // a textbox that modifies `name`.
     let textbox = TextBox::new()
@@ -172,7 +172,8 @@ impl TabsPolicy for NumberedTabs {
     type BodyWidget = Label<DynamicTabData>;
 
     fn tabs_changed(&self, old_data: &DynamicTabData, data: &DynamicTabData) -> bool {
-        old_data.tabs_key() != data.tabs_key()
+        // Moving a tab doesn't change the key, so compare the labels too.
+        old_data.tabs_key() != data.tabs_key() || !old_data.tab_labels.same(&data.tab_labels)
     }
 
     fn tabs(&self, data: &DynamicTabData) -> Vec<Self::Key> {
@@ -193,6 +194,17 @@ impl TabsPolicy for NumberedTabs {
         }
     }
 
+    fn move_tab(&self, key: Self::Key, to: usize, data: &mut DynamicTabData) {
+        if let Some(idx) = data.tab_labels.index_of(&key) {
+            let key = data.tab_labels.remove(idx);
+            data.tab_labels.insert(to, key);
+        }
+    }
+
+    fn new_tab(&self, data: &mut DynamicTabData) {
+        data.add_tab()
+    }
+
     fn tab_label(
         &self,
         _key: Self::Key,
@@ -208,6 +220,8 @@ fn build_tab_widget(tab_config: &TabConfig) -> impl Widget<AppState> {
         .with_axis(tab_config.axis)
         .with_edge(tab_config.edge)
         .with_transition(tab_config.transition)
+        .with_new_tab_button(true)
+        .with_keyboard_cycling(true)
         .lens(AppState::advanced);
 
     let control_dynamic = Flex::column()
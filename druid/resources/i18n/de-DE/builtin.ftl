@@ -39,3 +39,30 @@ common-menu-copy = Kopieren
 common-menu-paste = Einfügen
 common-menu-undo = Rückgängig
 common-menu-redo = Wiederherstellen
+
+# DatePicker widget
+date-picker-date = { $day }. { $month } { $year }
+date-picker-month-title = { $month } { $year }
+date-picker-month-1 = Januar
+date-picker-month-2 = Februar
+date-picker-month-3 = März
+date-picker-month-4 = April
+date-picker-month-5 = Mai
+date-picker-month-6 = Juni
+date-picker-month-7 = Juli
+date-picker-month-8 = August
+date-picker-month-9 = September
+date-picker-month-10 = Oktober
+date-picker-month-11 = November
+date-picker-month-12 = Dezember
+date-picker-weekday-1 = Mo
+date-picker-weekday-2 = Di
+date-picker-weekday-3 = Mi
+date-picker-weekday-4 = Do
+date-picker-weekday-5 = Fr
+date-picker-weekday-6 = Sa
+date-picker-weekday-7 = So
+
+# NumericTextBox widget
+number-decimal-separator = ,
+number-group-separator = .
@@ -39,3 +39,30 @@ common-menu-copy = Copy
 common-menu-paste = Paste
 common-menu-undo = Undo
 common-menu-redo = Redo
+
+# DatePicker widget
+date-picker-date = { $month } { $day }, { $year }
+date-picker-month-title = { $month } { $year }
+date-picker-month-1 = January
+date-picker-month-2 = February
+date-picker-month-3 = March
+date-picker-month-4 = April
+date-picker-month-5 = May
+date-picker-month-6 = June
+date-picker-month-7 = July
+date-picker-month-8 = August
+date-picker-month-9 = September
+date-picker-month-10 = October
+date-picker-month-11 = November
+date-picker-month-12 = December
+date-picker-weekday-1 = Mo
+date-picker-weekday-2 = Tu
+date-picker-weekday-3 = We
+date-picker-weekday-4 = Th
+date-picker-weekday-5 = Fr
+date-picker-weekday-6 = Sa
+date-picker-weekday-7 = Su
+
+# NumericTextBox widget
+number-decimal-separator = .
+number-group-separator = ,
@@ -39,3 +39,30 @@ common-menu-copy = Copier
 common-menu-paste = Coller
 common-menu-undo = Annuler
 common-menu-redo = Rétablir
+
+# DatePicker widget
+date-picker-date = { $day } { $month } { $year }
+date-picker-month-title = { $month } { $year }
+date-picker-month-1 = janvier
+date-picker-month-2 = février
+date-picker-month-3 = mars
+date-picker-month-4 = avril
+date-picker-month-5 = mai
+date-picker-month-6 = juin
+date-picker-month-7 = juillet
+date-picker-month-8 = août
+date-picker-month-9 = septembre
+date-picker-month-10 = octobre
+date-picker-month-11 = novembre
+date-picker-month-12 = décembre
+date-picker-weekday-1 = lu
+date-picker-weekday-2 = ma
+date-picker-weekday-3 = me
+date-picker-weekday-4 = je
+date-picker-weekday-5 = ve
+date-picker-weekday-6 = sa
+date-picker-weekday-7 = di
+
+# NumericTextBox widget
+number-decimal-separator = ,
+number-group-separator = { "\u00A0" }
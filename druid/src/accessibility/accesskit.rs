@@ -0,0 +1,210 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Passing the accessibility tree to [AccessKit].
+//!
+//! [AccessKit]: https://github.com/AccessKit/accesskit
+
+use std::num::NonZeroU128;
+use std::sync::Arc;
+
+use accesskit::{CheckedState, Node, NodeId, Tree, TreeUpdate};
+
+use super::{AccessNode, AccessUpdate, Role};
+use crate::WidgetId;
+
+impl From<Role> for accesskit::Role {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Generic => accesskit::Role::GenericContainer,
+            Role::Window => accesskit::Role::Window,
+            Role::Button => accesskit::Role::Button,
+            Role::CheckBox => accesskit::Role::CheckBox,
+            Role::Switch => accesskit::Role::Switch,
+            Role::RadioButton => accesskit::Role::RadioButton,
+            Role::Label => accesskit::Role::StaticText,
+            Role::TextInput => accesskit::Role::TextField,
+            Role::Slider => accesskit::Role::Slider,
+            Role::ProgressIndicator => accesskit::Role::ProgressIndicator,
+            Role::List => accesskit::Role::List,
+            Role::ListItem => accesskit::Role::ListItem,
+            Role::Image => accesskit::Role::Image,
+        }
+    }
+}
+
+impl AccessUpdate {
+    /// Convert this update to an AccessKit [`TreeUpdate`], for an AccessKit
+    /// platform adapter.
+    ///
+    /// The update contains every node of the tree, identified by the ids of
+    /// the widgets. Its focus is the focused widget, or the window if no
+    /// widget has focus.
+    ///
+    /// [`TreeUpdate`]: accesskit::TreeUpdate
+    pub fn to_accesskit(&self) -> TreeUpdate {
+        let mut nodes = Vec::new();
+        add_nodes(&self.tree, &mut nodes);
+        let focus = self.tree.focused_id().unwrap_or(self.tree.id);
+        TreeUpdate {
+            nodes,
+            tree: Some(Tree::new(node_id(self.tree.id))),
+            focus: Some(node_id(focus)),
+        }
+    }
+}
+
+fn node_id(id: WidgetId) -> NodeId {
+    NodeId(NonZeroU128::new(id.to_raw().into()).expect("widget ids are never zero"))
+}
+
+/// Convert `node` and its descendants, depth-first.
+fn add_nodes(node: &AccessNode, nodes: &mut Vec<(NodeId, Arc<Node>)>) {
+    let checked_state = node.checked.map(|checked| match checked {
+        true => CheckedState::True,
+        false => CheckedState::False,
+    });
+    let focusable = node.focused
+        || matches!(
+            node.role,
+            Role::Button
+                | Role::CheckBox
+                | Role::Switch
+                | Role::RadioButton
+                | Role::TextInput
+                | Role::Slider
+        );
+    let converted = Node {
+        role: node.role.into(),
+        bounds: Some(node.bounds),
+        children: node
+            .children
+            .iter()
+            .map(|child| node_id(child.id))
+            .collect(),
+        name: node.name.as_deref().map(Into::into),
+        value: node.value.as_deref().map(Into::into),
+        checked_state,
+        disabled: node.disabled,
+        focusable,
+        ..Default::default()
+    };
+    nodes.push((node_id(node.id), Arc::new(converted)));
+    for child in &node.children {
+        add_nodes(child, nodes);
+    }
+}
+
+/// Passing the tree to AT-SPI, the accessibility API of Linux and the BSDs.
+#[cfg(any(target_os = "linux", target_os = "openbsd"))]
+pub(crate) mod atspi {
+    use std::collections::hash_map::{Entry, HashMap};
+
+    use accesskit::{ActionHandler, ActionRequest};
+    use accesskit_unix::Adapter;
+    use tracing::{trace, warn};
+
+    use crate::accessibility::AccessHandlerFn;
+    use crate::WindowId;
+
+    /// Assistive technology can't perform actions on widgets yet, so the
+    /// requests are dropped.
+    struct IgnoreActions;
+
+    impl ActionHandler for IgnoreActions {
+        fn do_action(&self, request: ActionRequest) {
+            trace!("ignoring accessibility action {:?}", request.action);
+        }
+    }
+
+    /// Returns a handler that passes the tree of each window to AT-SPI,
+    /// after passing it to `inner`, if there is one.
+    ///
+    /// Each window gets its own adapter when its first tree arrives. If
+    /// there is no accessibility bus, or the adapter can't be registered,
+    /// the window is left out and a warning is logged.
+    pub(crate) fn bridge(mut inner: Option<Box<AccessHandlerFn>>) -> Box<AccessHandlerFn> {
+        let app_name = std::env::current_exe()
+            .ok()
+            .and_then(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "druid".to_string());
+        // `None` for the windows that couldn't be connected.
+        let mut adapters: HashMap<WindowId, Option<Adapter>> = HashMap::new();
+        Box::new(move |window_id, update| {
+            if let Some(inner) = &mut inner {
+                inner(window_id, update);
+            }
+            let tree_update = update.to_accesskit();
+            match adapters.entry(window_id) {
+                Entry::Occupied(entry) => {
+                    if let Some(adapter) = entry.get() {
+                        adapter.update(tree_update);
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    let adapter = Adapter::new(
+                        app_name.clone(),
+                        "druid".to_string(),
+                        env!("CARGO_PKG_VERSION").to_string(),
+                        move || tree_update,
+                        Box::new(IgnoreActions),
+                    );
+                    if adapter.is_none() {
+                        warn!("couldn't connect window {:?} to AT-SPI", window_id);
+                    }
+                    entry.insert(adapter);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accessibility::AccessEvent;
+
+    #[test]
+    fn converts_tree() {
+        let [window, button, check] = crate::tests::helpers::widget_ids();
+        let mut root = AccessNode::new(window, Role::Window);
+        let mut ok = AccessNode::new(button, Role::Button);
+        ok.name = Some("OK".to_string());
+        ok.focused = true;
+        let mut agree = AccessNode::new(check, Role::CheckBox);
+        agree.checked = Some(true);
+        agree.disabled = true;
+        root.children = vec![ok, agree];
+        let update = AccessUpdate {
+            tree: root,
+            events: vec![AccessEvent::FocusChanged(Some(button))],
+        };
+
+        let converted = update.to_accesskit();
+        assert_eq!(converted.tree, Some(Tree::new(node_id(window))));
+        assert_eq!(converted.focus, Some(node_id(button)));
+        let ids: Vec<_> = converted.nodes.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, [node_id(window), node_id(button), node_id(check)]);
+
+        let (_, root) = &converted.nodes[0];
+        assert_eq!(root.role, accesskit::Role::Window);
+        assert_eq!(root.children, [node_id(button), node_id(check)]);
+        let (_, ok) = &converted.nodes[1];
+        assert_eq!(ok.name.as_deref(), Some("OK"));
+        assert!(ok.focusable);
+        let (_, agree) = &converted.nodes[2];
+        assert_eq!(agree.checked_state, Some(CheckedState::True));
+        assert!(agree.disabled);
+    }
+}
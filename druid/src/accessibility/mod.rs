@@ -0,0 +1,291 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A description of the widget tree for assistive technology.
+//!
+//! Widgets describe themselves by setting a [`Role`], and optionally a name,
+//! a value and a checked state, using context methods such as
+//! [`LifeCycleCtx::set_access_role`]. Widgets that don't set a role are not
+//! part of the tree; their described descendants are attached to the nearest
+//! described ancestor instead.
+//!
+//! Whenever the tree changes, druid computes an [`AccessUpdate`] for the window
+//! and passes it to the handler registered with
+//! [`AppLauncher::on_accessibility_update`].
+//!
+//! With the `accesskit` feature, [`AccessUpdate::to_accesskit`] converts the
+//! updates for [AccessKit] platform adapters. On Linux and OpenBSD, druid also
+//! passes the tree of each window to AT-SPI itself, so that screen readers such
+//! as Orca can read the app. Assistive technology can't act on the widgets yet,
+//! and the other platforms need an adapter connected in the handler.
+//!
+//! [`LifeCycleCtx::set_access_role`]: crate::LifeCycleCtx::set_access_role
+//! [`AppLauncher::on_accessibility_update`]: crate::AppLauncher::on_accessibility_update
+//! [AccessKit]: https://github.com/AccessKit/accesskit
+
+#[cfg(feature = "accesskit")]
+mod accesskit;
+
+#[cfg(all(feature = "accesskit", any(target_os = "linux", target_os = "openbsd")))]
+pub(crate) use self::accesskit::atspi;
+
+use crate::kurbo::Rect;
+use crate::{WidgetId, WindowId};
+
+/// A function that receives the accessibility updates of all windows.
+pub(crate) type AccessHandlerFn = dyn FnMut(WindowId, &AccessUpdate);
+
+/// What kind of user interface element a widget is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Role {
+    /// An element without a more specific role.
+    Generic,
+    /// The root of a window.
+    Window,
+    /// A push button.
+    Button,
+    /// A checkbox.
+    CheckBox,
+    /// A toggle switch.
+    Switch,
+    /// A radio button.
+    RadioButton,
+    /// Static text.
+    Label,
+    /// An editable text field.
+    TextInput,
+    /// A slider.
+    Slider,
+    /// A progress indicator.
+    ProgressIndicator,
+    /// A list of items.
+    List,
+    /// An item in a [`Role::List`].
+    ListItem,
+    /// An image.
+    Image,
+}
+
+/// The accessibility properties a widget has set on itself.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct AccessProps {
+    pub(crate) role: Option<Role>,
+    pub(crate) name: Option<String>,
+    pub(crate) value: Option<String>,
+    pub(crate) checked: Option<bool>,
+}
+
+/// One element of the accessibility tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessNode {
+    /// The widget this node describes.
+    pub id: WidgetId,
+    /// The kind of element.
+    pub role: Role,
+    /// The label read out for this element, if any.
+    pub name: Option<String>,
+    /// The current value, for elements such as text fields and sliders.
+    pub value: Option<String>,
+    /// Whether the element is checked, for elements that can be.
+    pub checked: Option<bool>,
+    /// `true` if the widget is disabled.
+    pub disabled: bool,
+    /// `true` if the widget has keyboard focus.
+    pub focused: bool,
+    /// The widget's layout rect, in window coordinates.
+    pub bounds: Rect,
+    /// The described descendants of this widget, in tree order.
+    pub children: Vec<AccessNode>,
+}
+
+impl AccessNode {
+    pub(crate) fn new(id: WidgetId, role: Role) -> Self {
+        AccessNode {
+            id,
+            role,
+            name: None,
+            value: None,
+            checked: None,
+            disabled: false,
+            focused: false,
+            bounds: Rect::ZERO,
+            children: Vec::new(),
+        }
+    }
+
+    /// Find the node describing the widget `id`, searching depth-first.
+    pub fn find(&self, id: WidgetId) -> Option<&AccessNode> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+
+    /// The id of the focused node, if any.
+    pub fn focused_id(&self) -> Option<WidgetId> {
+        if self.focused {
+            return Some(self.id);
+        }
+        self.children.iter().find_map(|child| child.focused_id())
+    }
+
+    /// Call `f` on this node and all of its descendants, depth-first.
+    pub fn visit(&self, f: &mut impl FnMut(&AccessNode)) {
+        f(self);
+        for child in &self.children {
+            child.visit(f);
+        }
+    }
+}
+
+/// A change that assistive technology should announce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessEvent {
+    /// Keyboard focus moved to a different widget, or away from all widgets.
+    FocusChanged(Option<WidgetId>),
+    /// The value or checked state of a widget changed.
+    ValueChanged(WidgetId),
+}
+
+/// The accessibility tree of a window, and what changed since the previous one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessUpdate {
+    /// The whole tree. Its root has [`Role::Window`].
+    pub tree: AccessNode,
+    /// The changes since the previous update of this window.
+    ///
+    /// This is empty for the first update of a window.
+    pub events: Vec<AccessEvent>,
+}
+
+impl AccessUpdate {
+    /// Compute the update from `old` to `new`.
+    pub(crate) fn diff(old: Option<&AccessNode>, new: AccessNode) -> Self {
+        let mut events = Vec::new();
+        if let Some(old) = old {
+            let focus = new.focused_id();
+            if focus != old.focused_id() {
+                events.push(AccessEvent::FocusChanged(focus));
+            }
+            new.visit(&mut |node| {
+                if let Some(prev) = old.find(node.id) {
+                    if prev.value != node.value || prev.checked != node.checked {
+                        events.push(AccessEvent::ValueChanged(node.id));
+                    }
+                }
+            });
+        }
+        AccessUpdate { tree: new, events }
+    }
+}
+
+/// Collects the tree during the `CollectAccessTree` lifecycle pass.
+#[derive(Debug, Clone)]
+pub(crate) struct AccessTreeBuilder {
+    stack: Vec<AccessNode>,
+}
+
+impl AccessTreeBuilder {
+    pub(crate) fn new(root: AccessNode) -> Self {
+        AccessTreeBuilder { stack: vec![root] }
+    }
+
+    /// Start a node; the nodes pushed before the matching [`pop`] are its children.
+    ///
+    /// [`pop`]: AccessTreeBuilder::pop
+    pub(crate) fn push(&mut self, node: AccessNode) {
+        self.stack.push(node);
+    }
+
+    /// Finish the most recently pushed node.
+    pub(crate) fn pop(&mut self) {
+        if self.stack.len() > 1 {
+            let node = self.stack.pop().unwrap();
+            self.stack.last_mut().unwrap().children.push(node);
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> AccessNode {
+        while self.stack.len() > 1 {
+            self.pop();
+        }
+        self.stack.pop().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{click, widget_ids};
+    use crate::widget::{Button, Checkbox, Flex, TextBox};
+    use crate::WidgetExt;
+    use test_log::test;
+
+    #[test]
+    fn roles_names_and_notifications() {
+        let [button_id, check_id, text_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(Button::new("Go").with_id(button_id))
+            .with_child(
+                Checkbox::new("Check")
+                    .lens(lens!((bool, String), 0))
+                    .with_id(check_id),
+            )
+            .with_child(
+                TextBox::new()
+                    .with_placeholder("Name")
+                    .lens(lens!((bool, String), 1))
+                    .with_id(text_id),
+            );
+
+        Harness::create_simple((false, "Ada".to_string()), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            let tree = harness.access_tree();
+            assert_eq!(tree.role, Role::Window);
+            let roles: Vec<_> = tree.children.iter().map(|node| node.role).collect();
+            assert_eq!(roles, [Role::Button, Role::CheckBox, Role::TextInput]);
+
+            let button = tree.find(button_id).unwrap();
+            assert_eq!(button.name.as_deref(), Some("Go"));
+            assert!(button.children.is_empty());
+            let check = tree.find(check_id).unwrap();
+            assert_eq!(check.name.as_deref(), Some("Check"));
+            assert_eq!(check.checked, Some(false));
+            let text = tree.find(text_id).unwrap();
+            assert_eq!(text.name.as_deref(), Some("Name"));
+            assert_eq!(text.value.as_deref(), Some("Ada"));
+            assert_eq!(text.bounds, harness.get_state(text_id).layout_rect());
+
+            let first = harness.accessibility_update().unwrap();
+            assert!(first.events.is_empty());
+            assert!(harness.accessibility_update().is_none());
+
+            click(harness, check.bounds.center());
+            let update = harness.accessibility_update().unwrap();
+            assert!(update.events.contains(&AccessEvent::ValueChanged(check_id)));
+            assert_eq!(update.tree.find(check_id).unwrap().checked, Some(true));
+
+            click(harness, text.bounds.center());
+            let update = harness.accessibility_update().unwrap();
+            assert!(update
+                .events
+                .contains(&AccessEvent::FocusChanged(Some(text_id))));
+            assert!(update.tree.find(text_id).unwrap().focused);
+        });
+    }
+}
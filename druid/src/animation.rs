@@ -0,0 +1,460 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for animating values over time.
+//!
+//! A widget animates by requesting an animation frame, and then receiving
+//! [`Event::AnimFrame`] events with the time since the last frame. A
+//! [`Timeline`] does the bookkeeping: it holds a number of [`Tween`] tracks,
+//! is ticked with each frame, and reports when all of them are done, at which
+//! point the widget can stop requesting frames.
+//!
+//! To animate a field of your data instead, use a [`LensAnimator`].
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use druid::animation::{Easing, Timeline, Tween};
+//! use druid::widget::prelude::*;
+//!
+//! struct Pulse {
+//!     timeline: Timeline,
+//! }
+//!
+//! impl Pulse {
+//!     fn new() -> Pulse {
+//!         let radius = Tween::new(4.0, 12.0, Duration::from_millis(300))
+//!             .with_easing(Easing::EaseOut);
+//!         Pulse {
+//!             timeline: Timeline::new().with_track(radius),
+//!         }
+//!     }
+//! }
+//!
+//! impl Widget<()> for Pulse {
+//!     fn event(&mut self, ctx: &mut EventCtx, event: &Event, _: &mut (), _: &Env) {
+//!         match event {
+//!             Event::MouseDown(_) => {
+//!                 self.timeline.restart();
+//!                 ctx.request_anim_frame();
+//!             }
+//!             Event::AnimFrame(interval) => {
+//!                 if self.timeline.tick(*interval) {
+//!                     ctx.request_anim_frame();
+//!                 }
+//!                 ctx.request_paint();
+//!             }
+//!             _ => (),
+//!         }
+//!     }
+//!     # fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &(), _: &Env) {}
+//!     # fn update(&mut self, _: &mut UpdateCtx, _: &(), _: &(), _: &Env) {}
+//!     # fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &(), _: &Env) -> Size {
+//!     #     bc.constrain((24.0, 24.0))
+//!     # }
+//!
+//!     fn paint(&mut self, ctx: &mut PaintCtx, _: &(), env: &Env) {
+//!         let radius = self.timeline.value(0);
+//!         let circle = druid::kurbo::Circle::new((12.0, 12.0), radius);
+//!         ctx.fill(circle, &env.get(druid::theme::PRIMARY_LIGHT));
+//!     }
+//! }
+//! ```
+//!
+//! [`Event::AnimFrame`]: crate::Event::AnimFrame
+
+use std::time::Duration;
+
+use tracing::trace;
+
+use crate::widget::{prelude::*, Controller};
+use crate::{Lens, LensExt, Selector};
+
+/// A curve describing how an animation progresses over time.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    /// Progress at a constant speed.
+    #[default]
+    Linear,
+    /// Start slowly and speed up, like the CSS `ease-in` curve.
+    EaseIn,
+    /// Start quickly and slow down, like the CSS `ease-out` curve.
+    EaseOut,
+    /// Start and end slowly, like the CSS `ease-in-out` curve.
+    EaseInOut,
+    /// A cubic Bézier curve from `(0, 0)` to `(1, 1)` with the control points
+    /// `(x1, y1)` and `(x2, y2)`, like the CSS `cubic-bezier` function.
+    ///
+    /// The `x` coordinates should be in the range `0..=1`.
+    CubicBezier(f64, f64, f64, f64),
+}
+
+impl Easing {
+    /// The eased progress for the linear progress `t`, which is clamped to `0..=1`.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            Easing::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            Easing::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+        }
+    }
+}
+
+/// Evaluate the `y` of a unit cubic Bézier curve at `x`.
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+    // One coordinate of the curve, and its derivative, at the parameter `t`.
+    let coord = |p1: f64, p2: f64, t: f64| {
+        let u = 1.0 - t;
+        3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+    };
+    let slope = |p1: f64, p2: f64, t: f64| {
+        let u = 1.0 - t;
+        3.0 * u * u * p1 + 6.0 * u * t * (p2 - p1) + 3.0 * t * t * (1.0 - p2)
+    };
+
+    // Newton's method converges quickly where the curve isn't flat...
+    let mut t = x;
+    for _ in 0..8 {
+        let err = coord(x1, x2, t) - x;
+        if err.abs() < 1e-7 {
+            return coord(y1, y2, t);
+        }
+        let d = slope(x1, x2, t);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        t -= err / d;
+    }
+
+    // ...and bisection handles the rest.
+    let (mut lo, mut hi) = (0.0, 1.0);
+    t = x;
+    for _ in 0..32 {
+        let value = coord(x1, x2, t);
+        if (value - x).abs() < 1e-7 {
+            break;
+        }
+        if value < x {
+            lo = t;
+        } else {
+            hi = t;
+        }
+        t = (lo + hi) / 2.0;
+    }
+    coord(y1, y2, t)
+}
+
+/// A value that changes from one number to another over some time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween {
+    from: f64,
+    to: f64,
+    duration: Duration,
+    delay: Duration,
+    easing: Easing,
+}
+
+impl Tween {
+    /// Create a tween from `from` to `to`, taking `duration`.
+    pub fn new(from: f64, to: f64, duration: Duration) -> Tween {
+        Tween {
+            from,
+            to,
+            duration,
+            delay: Duration::ZERO,
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Builder-style method to wait for `delay` before starting.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Builder-style method to set the [`Easing`] curve. The default is [`Easing::Linear`].
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// The value at `elapsed` since the start of the tween, including its delay.
+    pub fn value_at(&self, elapsed: Duration) -> f64 {
+        let progress = self.easing.apply(self.linear_progress(elapsed));
+        self.from + (self.to - self.from) * progress
+    }
+
+    /// Returns `true` if the tween has finished at `elapsed`.
+    pub fn is_done(&self, elapsed: Duration) -> bool {
+        elapsed >= self.delay + self.duration
+    }
+
+    fn linear_progress(&self, elapsed: Duration) -> f64 {
+        let running = elapsed.saturating_sub(self.delay);
+        if self.duration.is_zero() || running >= self.duration {
+            1.0
+        } else {
+            running.as_secs_f64() / self.duration.as_secs_f64()
+        }
+    }
+}
+
+/// A set of [`Tween`] tracks that run together, ticked by animation frames.
+///
+/// Tracks are identified by the order in which they were added, starting at 0.
+/// See the [module documentation] for an example.
+///
+/// [module documentation]: crate::animation
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    tracks: Vec<Tween>,
+    elapsed: Duration,
+}
+
+impl Timeline {
+    /// Create an empty timeline.
+    pub fn new() -> Timeline {
+        Timeline::default()
+    }
+
+    /// Builder-style method to add a track.
+    pub fn with_track(mut self, track: Tween) -> Self {
+        self.add_track(track);
+        self
+    }
+
+    /// Add a track, returning its index.
+    pub fn add_track(&mut self, track: Tween) -> usize {
+        self.tracks.push(track);
+        self.tracks.len() - 1
+    }
+
+    /// Advance the timeline by the interval of an [`Event::AnimFrame`], in nanoseconds.
+    ///
+    /// Returns `true` if any track is still running, in which case the widget
+    /// should request another animation frame.
+    ///
+    /// [`Event::AnimFrame`]: crate::Event::AnimFrame
+    pub fn tick(&mut self, interval: u64) -> bool {
+        if self.is_done() {
+            return false;
+        }
+        self.elapsed += Duration::from_nanos(interval);
+        !self.is_done()
+    }
+
+    /// Start the timeline over from the beginning.
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// The time since the timeline started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns `true` if every track has finished.
+    pub fn is_done(&self) -> bool {
+        self.tracks.iter().all(|track| track.is_done(self.elapsed))
+    }
+
+    /// The current value of the track with the index `track`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such track.
+    pub fn value(&self, track: usize) -> f64 {
+        self.tracks[track].value_at(self.elapsed)
+    }
+}
+
+/// A [`Controller`] that animates a field of the data toward a target.
+///
+/// The field, reached through a `Lens`, is animated when the controlled
+/// widget receives an [`ANIMATE_TO`] command with the target value, either
+/// targeted at the widget or sent to its window. If
+/// another target arrives while animating, the new animation starts from
+/// wherever the value is.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::animation::{Easing, LensAnimator};
+/// use druid::widget::{Button, Flex, ProgressBar};
+/// use druid::{lens, Widget, WidgetExt};
+///
+/// fn loader() -> impl Widget<f64> {
+///     Flex::column()
+///         .with_child(ProgressBar::new())
+///         .with_child(Button::new("Fill").on_click(|ctx, _, _| {
+///             ctx.submit_command(LensAnimator::ANIMATE_TO.with(1.0))
+///         }))
+///         .controller(
+///             LensAnimator::new(lens::Identity, Duration::from_millis(400))
+///                 .with_easing(Easing::EaseInOut),
+///         )
+/// }
+/// ```
+///
+/// [`ANIMATE_TO`]: LensAnimator::ANIMATE_TO
+pub struct LensAnimator<L> {
+    lens: L,
+    duration: Duration,
+    easing: Easing,
+    /// The running animation, and how far it has got.
+    running: Option<(Tween, Duration)>,
+}
+
+impl LensAnimator<()> {
+    /// Animate the field toward the payload.
+    pub const ANIMATE_TO: Selector<f64> = Selector::new("druid-builtin.lens-animator-animate-to");
+}
+
+impl<L> LensAnimator<L> {
+    /// Create a controller that animates the field at `lens` for `duration`.
+    pub fn new(lens: L, duration: Duration) -> Self {
+        LensAnimator {
+            lens,
+            duration,
+            easing: Easing::Linear,
+            running: None,
+        }
+    }
+
+    /// Builder-style method to set the [`Easing`] curve. The default is [`Easing::Linear`].
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Returns `true` while the field is being animated.
+    pub fn is_animating(&self) -> bool {
+        self.running.is_some()
+    }
+}
+
+impl<T: Data, L: Lens<T, f64>, W: Widget<T>> Controller<T, W> for LensAnimator<L> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(LensAnimator::ANIMATE_TO) => {
+                let target = *cmd.get_unchecked(LensAnimator::ANIMATE_TO);
+                let from = self.lens.get(data);
+                let tween = Tween::new(from, target, self.duration).with_easing(self.easing);
+                self.running = Some((tween, Duration::ZERO));
+                ctx.request_anim_frame();
+                ctx.set_handled();
+                return;
+            }
+            Event::AnimFrame(interval) => {
+                if let Some((tween, elapsed)) = &mut self.running {
+                    *elapsed += Duration::from_nanos(*interval);
+                    let value = tween.value_at(*elapsed);
+                    self.lens.put(data, value);
+                    if tween.is_done(*elapsed) {
+                        trace!("LensAnimator finished");
+                        self.running = None;
+                    } else {
+                        ctx.request_anim_frame();
+                    }
+                }
+            }
+            _ => (),
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::widget::SizedBox;
+    use crate::{lens, WidgetExt, WidgetId};
+    use test_log::test;
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn easing_curves() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6);
+            assert!((easing.apply(2.0) - 1.0).abs() < 1e-6);
+        }
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+        assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-6);
+        // A Bézier curve along the diagonal is linear.
+        let diagonal = Easing::CubicBezier(1.0 / 3.0, 1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0);
+        assert!((diagonal.apply(0.3) - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn timeline_reports_completion() {
+        let mut timeline = Timeline::new()
+            .with_track(Tween::new(0.0, 10.0, Duration::from_millis(100)))
+            .with_track(
+                Tween::new(5.0, 0.0, Duration::from_millis(100))
+                    .with_delay(Duration::from_millis(50)),
+            );
+
+        assert!(timeline.tick(50 * MS));
+        assert_eq!(timeline.value(0), 5.0);
+        assert_eq!(timeline.value(1), 5.0);
+        assert!(timeline.tick(50 * MS));
+        assert_eq!(timeline.value(0), 10.0);
+        assert_eq!(timeline.value(1), 2.5);
+        assert!(!timeline.tick(100 * MS));
+        assert!(timeline.is_done());
+        assert_eq!(timeline.value(1), 0.0);
+        assert!(!timeline.tick(100 * MS));
+
+        timeline.restart();
+        assert_eq!(timeline.value(0), 0.0);
+    }
+
+    #[test]
+    fn lens_animator_moves_data() {
+        let id = WidgetId::next();
+        let widget = SizedBox::empty()
+            .controller(LensAnimator::new(
+                lens::Identity,
+                Duration::from_millis(100),
+            ))
+            .with_id(id);
+
+        Harness::create_simple(0.0, widget, |harness| {
+            harness.send_initial_events();
+            harness.submit_command(LensAnimator::ANIMATE_TO.with(10.0).to(id));
+            harness.event(Event::AnimFrame(25 * MS));
+            assert_eq!(*harness.data(), 2.5);
+            harness.event(Event::AnimFrame(100 * MS));
+            assert_eq!(*harness.data(), 10.0);
+            // Once finished, frames don't change the data.
+            harness.update_data(|data| *data = 3.0);
+            harness.event(Event::AnimFrame(25 * MS));
+            assert_eq!(*harness.data(), 3.0);
+        });
+    }
+}
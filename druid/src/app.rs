@@ -14,14 +14,18 @@
 
 //! Window building and app lifecycle.
 
+use crate::accessibility::{AccessHandlerFn, AccessUpdate};
 use crate::ext_event::{ExtEventHost, ExtEventSink};
 use crate::kurbo::{Point, Size};
 use crate::menu::MenuManager;
-use crate::shell::{Application, Error as PlatformError, WindowBuilder, WindowHandle, WindowLevel};
+use crate::shell::{
+    Application, Error as PlatformError, WindowBuilder, WindowHandle, WindowIcon, WindowLevel,
+};
+use crate::theme::Theme;
 use crate::widget::LabelText;
 use crate::win_handler::{AppHandler, AppState};
 use crate::window::WindowId;
-use crate::{AppDelegate, Data, Env, LocalizedString, Menu, Widget};
+use crate::{AppDelegate, Data, Env, KeyMap, LocalizedString, Menu, TrayIcon, Widget};
 
 use tracing::warn;
 
@@ -34,8 +38,12 @@ type EnvSetupFn<T> = dyn FnOnce(&mut Env, &T);
 pub struct AppLauncher<T> {
     windows: Vec<WindowDesc<T>>,
     env_setup: Option<Box<EnvSetupFn<T>>>,
+    theme: Option<Theme>,
     l10n_resources: Option<(Vec<String>, String)>,
     delegate: Option<Box<dyn AppDelegate<T>>>,
+    keymap: KeyMap,
+    tray_icon: Option<TrayIcon<T>>,
+    access_handler: Option<Box<AccessHandlerFn>>,
     ext_event_host: ExtEventHost,
 }
 
@@ -58,12 +66,15 @@ pub struct WindowConfig {
     pub(crate) size_policy: WindowSizePolicy,
     pub(crate) size: Option<Size>,
     pub(crate) min_size: Option<Size>,
+    pub(crate) max_size: Option<Size>,
     pub(crate) position: Option<Point>,
     pub(crate) resizable: Option<bool>,
     pub(crate) transparent: Option<bool>,
     pub(crate) show_titlebar: Option<bool>,
     pub(crate) level: Option<WindowLevel>,
     pub(crate) state: Option<WindowState>,
+    pub(crate) always_on_top: Option<bool>,
+    pub(crate) icon: Option<WindowIcon>,
 }
 
 /// A description of a window to be instantiated.
@@ -85,8 +96,11 @@ pub struct PendingWindow<T> {
     pub(crate) title: LabelText<T>,
     pub(crate) transparent: bool,
     pub(crate) menu: Option<MenuManager<T>>,
+    pub(crate) keymap: KeyMap,
     pub(crate) size_policy: WindowSizePolicy, // This is copied over from the WindowConfig
-                                              // when the native window is constructed.
+    // when the native window is constructed.
+    pub(crate) min_size: Option<Size>, // So are these.
+    pub(crate) max_size: Option<Size>,
 }
 
 impl<T: Data> PendingWindow<T> {
@@ -100,8 +114,11 @@ impl<T: Data> PendingWindow<T> {
             root: Box::new(root),
             title: LocalizedString::new("app-name").into(),
             menu: MenuManager::platform_default(),
+            keymap: KeyMap::new(),
             transparent: false,
             size_policy: WindowSizePolicy::User,
+            min_size: None,
+            max_size: None,
         }
     }
 
@@ -134,6 +151,15 @@ impl<T: Data> PendingWindow<T> {
         self.menu = Some(MenuManager::new(menu));
         self
     }
+
+    /// Set the keyboard shortcuts for this window.
+    ///
+    /// These take precedence over the shortcuts of the application; see
+    /// [`KeyMap`] for how key presses are dispatched.
+    pub fn keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
 }
 
 impl<T: Data> AppLauncher<T> {
@@ -142,8 +168,12 @@ impl<T: Data> AppLauncher<T> {
         AppLauncher {
             windows: vec![window],
             env_setup: None,
+            theme: None,
             l10n_resources: None,
             delegate: None,
+            keymap: KeyMap::new(),
+            tray_icon: None,
+            access_handler: None,
             ext_event_host: ExtEventHost::new(),
         }
     }
@@ -157,6 +187,19 @@ impl<T: Data> AppLauncher<T> {
         self
     }
 
+    /// Set the [`Theme`] of the application.
+    ///
+    /// The theme is applied to the environment before the closure passed to
+    /// [`configure_env`], if any. It can be changed while the application
+    /// runs by submitting [`SET_THEME`].
+    ///
+    /// [`configure_env`]: AppLauncher::configure_env
+    /// [`SET_THEME`]: crate::commands::SET_THEME
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
     /// Set the [`AppDelegate`].
     ///
     /// [`AppDelegate`]: trait.AppDelegate.html
@@ -165,6 +208,43 @@ impl<T: Data> AppLauncher<T> {
         self
     }
 
+    /// Set the keyboard shortcuts of the application.
+    ///
+    /// These apply to all windows, and can be changed later with
+    /// [`DelegateCtx::keymap_mut`]. See [`KeyMap`] for how key presses are
+    /// dispatched.
+    ///
+    /// [`DelegateCtx::keymap_mut`]: crate::DelegateCtx::keymap_mut
+    pub fn keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Add an icon to the system tray.
+    ///
+    /// If the platform doesn't support tray icons, an error is logged and the
+    /// application launches without one. See [`TrayIcon`] for details.
+    pub fn tray_icon(mut self, tray_icon: TrayIcon<T>) -> Self {
+        self.tray_icon = Some(tray_icon);
+        self
+    }
+
+    /// Receive the [accessibility tree] of each window whenever it changes.
+    ///
+    /// This is where a bridge to the platform's accessibility API (such as
+    /// AccessKit) is connected. The tree is only collected if a handler is set,
+    /// or if druid bridges it to the platform itself, as described in the
+    /// [accessibility tree] docs.
+    ///
+    /// [accessibility tree]: crate::accessibility
+    pub fn on_accessibility_update(
+        mut self,
+        handler: impl FnMut(WindowId, &AccessUpdate) + 'static,
+    ) -> Self {
+        self.access_handler = Some(Box::new(handler));
+        self
+    }
+
     /// Initialize a minimal logger with DEBUG max level for printing logs out to stderr.
     ///
     /// This is meant for use during development only.
@@ -247,6 +327,10 @@ impl<T: Data> AppLauncher<T> {
             .map(|it| Env::with_i10n(it.0, &it.1))
             .unwrap_or_else(Env::with_default_i10n);
 
+        if let Some(theme) = self.theme.take() {
+            theme.apply(&mut env);
+        }
+
         if let Some(f) = self.env_setup.take() {
             f(&mut env, &data);
         }
@@ -256,14 +340,27 @@ impl<T: Data> AppLauncher<T> {
             data,
             env,
             self.delegate.take(),
+            self.keymap,
             self.ext_event_host,
         );
+        let access_handler = self.access_handler.take();
+        #[cfg(all(feature = "accesskit", any(target_os = "linux", target_os = "openbsd")))]
+        let access_handler = Some(crate::accessibility::atspi::bridge(access_handler));
+        if let Some(handler) = access_handler {
+            state.set_access_handler(handler);
+        }
 
         for desc in self.windows {
             let window = desc.build_native(&mut state)?;
             window.show();
         }
 
+        if let Some(tray_icon) = self.tray_icon {
+            if let Err(e) = state.add_tray_icon(tray_icon) {
+                tracing::error!("failed to create tray icon: '{}'", e);
+            }
+        }
+
         let handler = AppHandler::new(state);
         app.run(Some(Box::new(handler)));
         Ok(())
@@ -276,12 +373,15 @@ impl Default for WindowConfig {
             size_policy: WindowSizePolicy::User,
             size: None,
             min_size: None,
+            max_size: None,
             position: None,
             resizable: None,
             show_titlebar: None,
             transparent: None,
             level: None,
             state: None,
+            always_on_top: None,
+            icon: None,
         }
     }
 }
@@ -339,6 +439,21 @@ impl WindowConfig {
         self
     }
 
+    /// Set the window's maximum drawing area size in [display points].
+    ///
+    /// The platform enforces this while the user resizes the window; where it
+    /// can't, the window is resized back when it grows too large. A window
+    /// that isn't [`resizable`] keeps its size regardless, and with
+    /// [`WindowSizePolicy::Content`] the content is laid out within the
+    /// minimum and maximum size.
+    ///
+    /// [`resizable`]: #method.resizable
+    /// [display points]: struct.Scale.html
+    pub fn with_max_size(mut self, size: impl Into<Size>) -> Self {
+        self.max_size = Some(size.into());
+        self
+    }
+
     /// Set whether the window should be resizable.
     pub fn resizable(mut self, resizable: bool) -> Self {
         self.resizable = Some(resizable);
@@ -376,6 +491,26 @@ impl WindowConfig {
         self
     }
 
+    /// Set whether the window is kept on top of other windows.
+    ///
+    /// This is useful for tool palettes. It is currently supported on Windows,
+    /// macOS and GTK.
+    pub fn set_always_on_top(mut self, on_top: bool) -> Self {
+        self.always_on_top = Some(on_top);
+        self
+    }
+
+    /// Set the window's icon, which is also shown in the taskbar.
+    ///
+    /// Supplying a [`WindowIcon`] with several sizes gives the best results.
+    /// Window icons are currently supported on Windows, macOS, GTK and X11.
+    /// macOS has no per-window icons; there this sets the icon of the
+    /// application in the dock instead.
+    pub fn with_icon(mut self, icon: impl Into<WindowIcon>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
     /// Set whether the window background should be transparent
     pub fn transparent(mut self, transparent: bool) -> Self {
         self.transparent = Some(transparent);
@@ -417,6 +552,18 @@ impl WindowConfig {
         if let Some(min_size) = self.min_size {
             builder.set_min_size(min_size);
         }
+
+        if let Some(max_size) = self.max_size {
+            builder.set_max_size(max_size);
+        }
+
+        if let Some(on_top) = self.always_on_top {
+            builder.set_always_on_top(on_top);
+        }
+
+        if let Some(icon) = self.icon.clone() {
+            builder.set_icon(icon);
+        }
     }
 
     /// Apply this window configuration to the passed in WindowHandle
@@ -433,7 +580,7 @@ impl WindowConfig {
             win_handle.set_size(size);
         }
 
-        // Can't apply min size currently as window handle
+        // Can't apply min or max size currently as window handle
         // does not support it.
 
         if let Some(position) = self.position {
@@ -447,6 +594,14 @@ impl WindowConfig {
         if let Some(state) = self.state {
             win_handle.set_window_state(state);
         }
+
+        if let Some(on_top) = self.always_on_top {
+            win_handle.set_always_on_top(on_top);
+        }
+
+        if let Some(icon) = &self.icon {
+            win_handle.set_icon(icon);
+        }
     }
 }
 
@@ -489,6 +644,15 @@ impl<T: Data> WindowDesc<T> {
         self
     }
 
+    /// Set the keyboard shortcuts for this window.
+    ///
+    /// These take precedence over the shortcuts of the application; see
+    /// [`KeyMap`] for how key presses are dispatched.
+    pub fn keymap(mut self, keymap: KeyMap) -> Self {
+        self.pending = self.pending.keymap(keymap);
+        self
+    }
+
     /// Set the window size policy
     pub fn window_size_policy(mut self, size_policy: WindowSizePolicy) -> Self {
         #[cfg(windows)]
@@ -541,6 +705,21 @@ impl<T: Data> WindowDesc<T> {
         self
     }
 
+    /// Set the window's maximum drawing area size in [display points].
+    ///
+    /// The platform enforces this while the user resizes the window; where it
+    /// can't, the window is resized back when it grows too large. A window
+    /// that isn't [`resizable`] keeps its size regardless, and with
+    /// [`WindowSizePolicy::Content`] the content is laid out within the
+    /// minimum and maximum size.
+    ///
+    /// [`resizable`]: #method.resizable
+    /// [display points]: struct.Scale.html
+    pub fn with_max_size(mut self, size: impl Into<Size>) -> Self {
+        self.config = self.config.with_max_size(size);
+        self
+    }
+
     /// Builder-style method to set whether this window can be resized.
     pub fn resizable(mut self, resizable: bool) -> Self {
         self.config = self.config.resizable(resizable);
@@ -548,6 +727,11 @@ impl<T: Data> WindowDesc<T> {
     }
 
     /// Builder-style method to set whether this window's titlebar is visible.
+    ///
+    /// A window without a titlebar can still be moved by the user if part of
+    /// it is made a [`draggable_window_region`].
+    ///
+    /// [`draggable_window_region`]: crate::WidgetExt::draggable_window_region
     pub fn show_titlebar(mut self, show_titlebar: bool) -> Self {
         self.config = self.config.show_titlebar(show_titlebar);
         self
@@ -564,8 +748,12 @@ impl<T: Data> WindowDesc<T> {
     /// Sets the initial window position in [display points], relative to the origin
     /// of the [virtual screen].
     ///
+    /// The virtual screen spans all monitors; use [`Screen::get_monitors`] to find
+    /// where each of them is, for instance to open the window on a particular one.
+    ///
     /// [display points]: crate::Scale
     /// [virtual screen]: crate::Screen
+    /// [`Screen::get_monitors`]: crate::Screen::get_monitors
     pub fn set_position(mut self, position: impl Into<Point>) -> Self {
         self.config = self.config.set_position(position.into());
         self
@@ -585,6 +773,27 @@ impl<T: Data> WindowDesc<T> {
         self
     }
 
+    /// Set whether the window is kept on top of other windows.
+    ///
+    /// This is useful for tool palettes. It is currently supported on Windows,
+    /// macOS and GTK.
+    pub fn set_always_on_top(mut self, on_top: bool) -> Self {
+        self.config = self.config.set_always_on_top(on_top);
+        self
+    }
+
+    /// Set the window's icon, from an [`ImageBuf`] or a [`WindowIcon`] with
+    /// several sizes.
+    ///
+    /// To change the icon later, use [`WindowHandle::set_icon`]. See
+    /// [`WindowConfig::with_icon`] for the platforms this is supported on.
+    ///
+    /// [`ImageBuf`]: crate::ImageBuf
+    pub fn with_icon(mut self, icon: impl Into<WindowIcon>) -> Self {
+        self.config = self.config.with_icon(icon);
+        self
+    }
+
     /// Set the [`WindowConfig`] of window.
     pub fn with_config(mut self, config: WindowConfig) -> Self {
         self.config = config;
@@ -17,8 +17,8 @@
 use std::any::{Any, TypeId};
 
 use crate::{
-    commands, core::CommandQueue, ext_event::ExtEventHost, Command, Data, Env, Event, ExtEventSink,
-    Handled, SingleUse, Target, WindowDesc, WindowId,
+    commands, core::CommandQueue, ext_event::ExtEventHost, Command, ContextMenu, Data, Env, Event,
+    ExtEventSink, Handled, KeyMap, Menu, Point, SingleUse, Target, WindowDesc, WindowId,
 };
 
 /// A context passed in to [`AppDelegate`] functions.
@@ -27,6 +27,7 @@ use crate::{
 pub struct DelegateCtx<'a> {
     pub(crate) command_queue: &'a mut CommandQueue,
     pub(crate) ext_event_host: &'a ExtEventHost,
+    pub(crate) keymap: &'a mut KeyMap,
     pub(crate) app_data_type: TypeId,
 }
 
@@ -54,6 +55,20 @@ impl<'a> DelegateCtx<'a> {
         self.ext_event_host.make_sink()
     }
 
+    /// Returns the keyboard shortcuts of the application.
+    ///
+    /// This does not include the shortcuts of individual windows.
+    pub fn keymap(&self) -> &KeyMap {
+        self.keymap
+    }
+
+    /// Returns a mutable reference to the keyboard shortcuts of the application.
+    ///
+    /// Changes take effect with the next key press.
+    pub fn keymap_mut(&mut self) -> &mut KeyMap {
+        self.keymap
+    }
+
     /// Create a new window.
     /// `T` must be the application's root `Data` type (the type provided to [`AppLauncher::launch`]).
     ///
@@ -69,6 +84,18 @@ impl<'a> DelegateCtx<'a> {
             debug_panic!("DelegateCtx::new_window<T> - T must match the application data type.");
         }
     }
+
+    /// Show a context menu in the window `window`, at `location` in the window's
+    /// coordinate space. `T` must be the application's root `Data` type.
+    pub fn show_context_menu<T: Any>(&mut self, menu: Menu<T>, location: Point, window: WindowId) {
+        if self.app_data_type == TypeId::of::<T>() {
+            self.submit_command(Command::from(ContextMenu::new(menu, location)).to(window));
+        } else {
+            debug_panic!(
+                "DelegateCtx::show_context_menu<T> - T must match the application data type."
+            );
+        }
+    }
 }
 
 /// A type that provides hooks for handling and modifying top-level events.
@@ -88,7 +115,12 @@ pub trait AppDelegate<T: Data> {
     /// be the event that was passed in, a different event, or no event. In all cases,
     /// the [`update()`] method will be called as usual.
     ///
+    /// Commands never reach this method; see [`filter_command`] and
+    /// [`command`] for those.
+    ///
     /// [`update()`]: trait.Widget.html#tymethod.update
+    /// [`filter_command`]: AppDelegate::filter_command
+    /// [`command`]: AppDelegate::command
     fn event(
         &mut self,
         ctx: &mut DelegateCtx,
@@ -100,6 +132,40 @@ pub trait AppDelegate<T: Data> {
         Some(event)
     }
 
+    /// A hook for rewriting or swallowing [`Command`]s.
+    ///
+    /// This function is called with every command, before anything else sees
+    /// it: before Druid handles its own commands, such as [`QUIT_APP`] or
+    /// [`CLOSE_ALL_WINDOWS`], and before [`command`]. The command returned here
+    /// is handled instead of the original one; it can be the command that was
+    /// passed in, a different command, or none at all, in which case the
+    /// command is dropped.
+    ///
+    /// This is useful for things like global keybindings, remapping menu
+    /// commands, or logging. The returned command keeps its own [`Target`];
+    /// use [`Command::to`] to change it.
+    ///
+    /// To handle your own commands, prefer [`command`]; use this method when
+    /// the command must be changed or dropped before anything else handles it,
+    /// or to intercept the commands Druid handles itself. A command returned
+    /// from here is then handled as usual: unless Druid handles it, it is
+    /// passed to [`command`], and sent down the tree if that returns
+    /// `Handled::No`.
+    ///
+    /// [`QUIT_APP`]: crate::commands::QUIT_APP
+    /// [`CLOSE_ALL_WINDOWS`]: crate::commands::CLOSE_ALL_WINDOWS
+    /// [`command`]: AppDelegate::command
+    /// [`Target`]: crate::Target
+    fn filter_command(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        cmd: Command,
+        data: &mut T,
+        env: &Env,
+    ) -> Option<Command> {
+        Some(cmd)
+    }
+
     /// The `AppDelegate`s [`Command`] handler.
     ///
     /// This function is called with each ([`Target`], [`Command`]) pair before
@@ -108,12 +174,17 @@ pub trait AppDelegate<T: Data> {
     /// If your implementation returns `Handled::No`, the command will be sent down
     /// the widget tree. Otherwise it will not.
     ///
+    /// Commands handled by Druid itself, such as [`QUIT_APP`], never reach
+    /// this method; use [`filter_command`] to see those as well.
+    ///
     /// To do anything fancier than this, you can submit arbitary commands
     /// via [`DelegateCtx::submit_command`].
     ///
     /// [`Target`]: enum.Target.html
     /// [`Command`]: struct.Command.html
     /// [`DelegateCtx::submit_command`]: struct.DelegateCtx.html#method.submit_command
+    /// [`QUIT_APP`]: crate::commands::QUIT_APP
+    /// [`filter_command`]: AppDelegate::filter_command
     fn command(
         &mut self,
         ctx: &mut DelegateCtx,
@@ -134,3 +205,79 @@ pub trait AppDelegate<T: Data> {
     /// This function is called after a window has been removed.
     fn window_removed(&mut self, id: WindowId, data: &mut T, env: &Env, ctx: &mut DelegateCtx) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::Selector;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use test_log::test;
+
+    const DROPPED: Selector = Selector::new("druid-test.filter-dropped");
+    const KEPT: Selector = Selector::new("druid-test.filter-kept");
+
+    /// Drops `DROPPED` commands, and records the commands that reach `command`.
+    struct Filter(Rc<RefCell<Vec<Command>>>);
+
+    impl AppDelegate<()> for Filter {
+        fn filter_command(
+            &mut self,
+            _: &mut DelegateCtx,
+            cmd: Command,
+            _: &mut (),
+            _: &Env,
+        ) -> Option<Command> {
+            if cmd.is(DROPPED) {
+                None
+            } else {
+                Some(cmd)
+            }
+        }
+
+        fn command(
+            &mut self,
+            _: &mut DelegateCtx,
+            _: Target,
+            cmd: &Command,
+            _: &mut (),
+            _: &Env,
+        ) -> Handled {
+            self.0.borrow_mut().push(cmd.clone());
+            Handled::No
+        }
+    }
+
+    #[test]
+    fn filtered_commands_never_reach_widgets() {
+        let delegated = Rc::new(RefCell::new(Vec::new()));
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let received_2 = received.clone();
+        let widget = ModularWidget::new(()).event_fn(move |_, ctx, event, _, _| {
+            if let Event::Command(cmd) = event {
+                received_2.borrow_mut().push(cmd.clone());
+                // Commands submitted by widgets are filtered as well.
+                if cmd.is(KEPT) {
+                    ctx.submit_command(DROPPED);
+                }
+            }
+        });
+
+        Harness::create_simple((), widget, |harness| {
+            harness.set_delegate(Filter(delegated.clone()));
+            harness.send_initial_events();
+
+            harness.submit_command(DROPPED);
+            assert!(delegated.borrow().is_empty());
+            assert!(received.borrow().is_empty());
+
+            harness.submit_command(KEPT);
+            assert_eq!(delegated.borrow().len(), 1);
+            assert!(delegated.borrow()[0].is(KEPT));
+            assert_eq!(received.borrow().len(), 1);
+            assert!(received.borrow()[0].is(KEPT));
+        });
+    }
+}
@@ -178,7 +178,10 @@ pub mod sys {
     use super::Selector;
     use crate::{
         sub_window::{SubWindowDesc, SubWindowUpdate},
-        FileDialogOptions, FileInfo, Rect, SingleUse, WidgetId, WindowConfig,
+        theme::Theme,
+        widget::{PopupId, Toast},
+        CaptureError, ColorScheme, Command, FileDialogOptions, FileInfo, HotKey, ImageBuf, Rect,
+        SingleUse, WidgetId, WindowConfig,
     };
 
     /// Quit the running application. This command is handled by the druid library.
@@ -223,13 +226,151 @@ pub mod sys {
     pub const CONFIGURE_WINDOW: Selector<WindowConfig> =
         Selector::new("druid-builtin.configure-window");
 
-    /// Display a context (right-click) menu. The payload must be the [`ContextMenu`]
-    /// object to be displayed.
+    /// Render the contents of a window into an image.
     ///
-    /// [`ContextMenu`]: ../struct.ContextMenu.html
-    pub(crate) const SHOW_CONTEXT_MENU: Selector<SingleUse<Box<dyn Any>>> =
+    /// The payload is the region of the window to capture, in display points,
+    /// or `None` for the whole window. The image is rendered at the window's
+    /// current scale, and sent back to the window with a [`WINDOW_CAPTURED`]
+    /// command.
+    ///
+    /// The command must target a specific window.
+    /// When calling `submit_command` on a `Widget`s context, passing `None` as target
+    /// will automatically target the window containing the widget.
+    pub const CAPTURE_WINDOW: Selector<Option<Rect>> =
+        Selector::new("druid-builtin.capture-window");
+
+    /// The result of a [`CAPTURE_WINDOW`] command, sent to the captured window.
+    pub const WINDOW_CAPTURED: Selector<Result<ImageBuf, CaptureError>> =
+        Selector::new("druid-builtin.window-captured");
+
+    /// Display a context (right-click) menu.
+    ///
+    /// The payload must be a [`ContextMenu`] for the application's root data type. You
+    /// don't usually create this command yourself: a [`ContextMenu`] can be converted
+    /// into it directly, and [`EventCtx::show_context_menu`] and
+    /// [`DelegateCtx::show_context_menu`] submit it for you.
+    ///
+    /// The command must target a window, and the menu's location is in that window's
+    /// coordinate space (use [`EventCtx::to_window`] to convert a point from a widget's
+    /// coordinate space). The platform closes the menu when an item is chosen, or when
+    /// the user clicks outside of it or presses escape.
+    ///
+    /// [`ContextMenu`]: crate::ContextMenu
+    /// [`EventCtx::show_context_menu`]: crate::EventCtx::show_context_menu
+    /// [`DelegateCtx::show_context_menu`]: crate::DelegateCtx::show_context_menu
+    /// [`EventCtx::to_window`]: crate::EventCtx::to_window
+    pub const SHOW_CONTEXT_MENU: Selector<SingleUse<Box<dyn Any>>> =
         Selector::new("druid-builtin.show-context-menu");
 
+    /// Show a [`Toast`] message on top of the window.
+    ///
+    /// The message is shown by the nearest [`ToastOverlay`], which is usually
+    /// the root widget of the window. When calling `submit_command` on a
+    /// `Widget`s context, passing `None` as target will automatically target
+    /// the window containing the widget.
+    ///
+    /// [`ToastOverlay`]: crate::widget::ToastOverlay
+    pub const SHOW_TOAST: Selector<Toast> = Selector::new("druid-builtin.show-toast");
+
+    /// Show a [`Popup`] in the window's [`OverlayLayer`].
+    ///
+    /// This is submitted by [`EventCtx::show_popup`].
+    ///
+    /// [`Popup`]: crate::widget::Popup
+    /// [`OverlayLayer`]: crate::widget::OverlayLayer
+    /// [`EventCtx::show_popup`]: crate::EventCtx::show_popup
+    pub(crate) const SHOW_POPUP: Selector<SingleUse<Box<dyn Any>>> =
+        Selector::new("druid-builtin.show-popup");
+
+    /// Dismiss a [`Popup`] shown in the window's [`OverlayLayer`].
+    ///
+    /// When calling `submit_command` on a `Widget`s context, passing `None` as
+    /// target will automatically target the window containing the widget.
+    ///
+    /// [`Popup`]: crate::widget::Popup
+    /// [`OverlayLayer`]: crate::widget::OverlayLayer
+    pub const CLOSE_POPUP: Selector<PopupId> = Selector::new("druid-builtin.close-popup");
+
+    /// A notification that a widget inside a [`Popup`] can submit to dismiss
+    /// the popup.
+    ///
+    /// [`Popup`]: crate::widget::Popup
+    pub const DISMISS_POPUP: Selector = Selector::new("druid-builtin.dismiss-popup");
+
+    /// Sent to the widget that showed a [`Popup`] when the popup is dismissed,
+    /// for whatever reason.
+    ///
+    /// [`Popup`]: crate::widget::Popup
+    pub const POPUP_DISMISSED: Selector<PopupId> = Selector::new("druid-builtin.popup-dismissed");
+
+    /// A notification that a widget inside a [`Dialog`] submits to close the
+    /// dialog with a result.
+    ///
+    /// This is submitted by [`EventCtx::close_dialog`].
+    ///
+    /// [`Dialog`]: crate::widget::Dialog
+    /// [`EventCtx::close_dialog`]: crate::EventCtx::close_dialog
+    pub(crate) const CLOSE_DIALOG: Selector<SingleUse<Box<dyn Any>>> =
+        Selector::new("druid-builtin.close-dialog");
+
+    /// Sent to all windows and the [`AppDelegate`] when the application's
+    /// [`TrayIcon`] is clicked.
+    ///
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`TrayIcon`]: crate::TrayIcon
+    pub const TRAY_ICON_CLICKED: Selector = Selector::new("druid-builtin.tray-icon-clicked");
+
+    /// Sent to all windows and the [`AppDelegate`] when the application's
+    /// [`TrayIcon`] is double-clicked.
+    ///
+    /// A [`TRAY_ICON_CLICKED`] command is sent for the first click as well.
+    ///
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`TrayIcon`]: crate::TrayIcon
+    pub const TRAY_ICON_DOUBLE_CLICKED: Selector =
+        Selector::new("druid-builtin.tray-icon-double-clicked");
+
+    /// Replace the image of the application's [`TrayIcon`].
+    ///
+    /// [`TrayIcon`]: crate::TrayIcon
+    pub const SET_TRAY_ICON: Selector<ImageBuf> = Selector::new("druid-builtin.set-tray-icon");
+
+    /// Replace the tooltip of the application's [`TrayIcon`].
+    ///
+    /// [`TrayIcon`]: crate::TrayIcon
+    pub const SET_TRAY_TOOLTIP: Selector<String> = Selector::new("druid-builtin.set-tray-tooltip");
+
+    /// Apply a [`Theme`] to the application's root [`Env`].
+    ///
+    /// Every window is updated with the new `Env` and repainted, and
+    /// [`THEME_CHANGED`] is then sent to all windows and the [`AppDelegate`].
+    ///
+    /// [`Theme`]: crate::theme::Theme
+    /// [`Env`]: crate::Env
+    /// [`AppDelegate`]: crate::AppDelegate
+    pub const SET_THEME: Selector<Theme> = Selector::new("druid-builtin.set-theme");
+
+    /// Sent to all windows and the [`AppDelegate`] after a [`SET_THEME`]
+    /// command has changed the application's root [`Env`].
+    ///
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`Env`]: crate::Env
+    pub const THEME_CHANGED: Selector = Selector::new("druid-builtin.theme-changed");
+
+    /// Sent to all windows and the [`AppDelegate`] when the user changes the
+    /// light or dark appearance preferred for applications.
+    ///
+    /// The current preference is returned by
+    /// [`Application::get_color_scheme`]. An application that follows it
+    /// can respond by submitting [`SET_THEME`], with
+    /// [`Theme::for_color_scheme`].
+    ///
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`Application::get_color_scheme`]: crate::Application::get_color_scheme
+    /// [`Theme::for_color_scheme`]: crate::theme::Theme::for_color_scheme
+    pub const COLOR_SCHEME_CHANGED: Selector<ColorScheme> =
+        Selector::new("druid-builtin.color-scheme-changed");
+
     /// This is sent to the window handler to create a new sub window.
     pub(crate) const NEW_SUB_WINDOW: Selector<SingleUse<SubWindowDesc>> =
         Selector::new("druid-builtin.new-sub-window");
@@ -330,6 +471,19 @@ pub mod sys {
     /// Select all.
     pub const SELECT_ALL: Selector = Selector::new("druid-builtin.menu-select-all");
 
+    /// Bind a hotkey in the [`KeyMap`] of the target window, replacing an
+    /// existing binding of the same hotkey.
+    ///
+    /// [`KeyMap`]: crate::KeyMap
+    pub(crate) const ADD_KEY_BINDING: Selector<(HotKey, Command)> =
+        Selector::new("druid-builtin.add-key-binding");
+
+    /// Run the action of the [`Click`] controller of the target widget, as if
+    /// it had been clicked.
+    ///
+    /// [`Click`]: crate::widget::Click
+    pub(crate) const CLICK: Selector = Selector::new("druid-builtin.click");
+
     /// Text input state has changed, and we need to notify the platform.
     pub(crate) const INVALIDATE_IME: Selector<ImeInvalidation> =
         Selector::new("druid-builtin.invalidate-ime");
@@ -350,6 +504,37 @@ pub mod sys {
     /// [`scroll_area_to_view`]: crate::EventCtx::scroll_area_to_view()
     pub const SCROLL_TO_VIEW: Selector<Rect> = Selector::new("druid-builtin.scroll-to");
 
+    /// Sent by a [`ClipBox`], and so by [`Scroll`], to the widgets that asked for
+    /// it with [`WATCH_VIEWPORT`], when the visible part of its content changes.
+    ///
+    /// The payload is the visible rectangle, in the coordinate space of the
+    /// `ClipBox`'s child. Widgets that only create children for the visible
+    /// area, like [`LazyList`], use it to decide which children they need.
+    ///
+    /// [`ClipBox`]: crate::widget::ClipBox
+    /// [`Scroll`]: crate::widget::Scroll
+    /// [`LazyList`]: crate::widget::LazyList
+    pub const VIEWPORT_CHANGED: Selector<Rect> = Selector::new("druid-builtin.viewport-changed");
+
+    /// A notification a widget can submit to be sent [`VIEWPORT_CHANGED`] by the
+    /// nearest enclosing [`ClipBox`].
+    ///
+    /// The current viewport is sent in response, and again whenever it changes.
+    ///
+    /// [`ClipBox`]: crate::widget::ClipBox
+    pub const WATCH_VIEWPORT: Selector = Selector::new("druid-builtin.watch-viewport");
+
+    /// A notification submitted by a [`List`] when the user drags an item to
+    /// a new position.
+    ///
+    /// The payload is `(from, to)`: removing the item at `from` and inserting
+    /// it at `to` gives the new order. If the list was created
+    /// [`with_reorder`], its data has already been changed.
+    ///
+    /// [`List`]: crate::widget::List
+    /// [`with_reorder`]: crate::widget::List::with_reorder
+    pub const LIST_REORDER: Selector<(usize, usize)> = Selector::new("druid-builtin.list-reorder");
+
     /// A change that has occured to text state, and needs to be
     /// communicated to the platform.
     pub(crate) struct ImeInvalidation {
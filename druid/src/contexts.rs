@@ -17,24 +17,30 @@
 use std::{
     any::{Any, TypeId},
     collections::{HashMap, VecDeque},
+    future::Future,
     ops::{Deref, DerefMut},
     rc::Rc,
     time::Duration,
 };
 use tracing::{error, trace, warn};
 
+use crate::accessibility::Role;
 use crate::commands::SCROLL_TO_VIEW;
-use crate::core::{CommandQueue, CursorChange, FocusChange, WidgetState};
+use crate::core::{CommandQueue, CursorChange, FocusChange, FocusEntry, WidgetState};
+use crate::drag::{ActiveDrag, BEGIN_DRAG};
 use crate::env::KeyLike;
 use crate::menu::ContextMenu;
-use crate::piet::{Piet, PietText, RenderContext};
+use crate::piet::{Device, ImageFormat, InterpolationMode, Piet, PietText, RenderContext};
 use crate::shell::text::Event as ImeInvalidation;
 use crate::shell::Region;
 use crate::text::{ImeHandlerRef, TextFieldRegistration};
 use crate::{
-    commands, sub_window::SubWindowDesc, widget::Widget, Affine, Command, Cursor, Data, Env,
-    ExtEventSink, Insets, Menu, Notification, Point, Rect, SingleUse, Size, Target, TimerToken,
-    Vec2, WidgetId, WindowConfig, WindowDesc, WindowHandle, WindowId,
+    commands,
+    sub_window::SubWindowDesc,
+    widget::{Dialog, Popup, PopupId, Widget},
+    Affine, Command, Cursor, Data, DragGhost, DragPayload, Env, ExtEventSink, Insets, KeyOrValue,
+    Menu, Monitor, Notification, Point, Rect, SingleUse, Size, Target, TaskToken, TimerToken,
+    ValueType, Vec2, WidgetId, WindowConfig, WindowDesc, WindowHandle, WindowId,
 };
 
 /// A macro for implementing methods on multiple contexts.
@@ -165,6 +171,13 @@ impl_context_method!(
             self.state.window_id
         }
 
+        /// Returns the monitor the current window is on.
+        ///
+        /// See [`WindowHandle::get_monitor`] for details.
+        pub fn monitor(&self) -> Option<Monitor> {
+            self.state.window.get_monitor()
+        }
+
         /// Get an object which can create text layouts.
         pub fn text(&mut self) -> &mut PietText {
             &mut self.state.text
@@ -416,6 +429,53 @@ impl_context_method!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, LifeCycleCtx<'_, '_>,
         self.widget_state.is_explicitly_disabled_new = disabled;
     }
 
+    /// Set the accessibility [`Role`] of this widget.
+    ///
+    /// Only widgets with a role are part of the [accessibility tree]; this is
+    /// usually set once, in [`LifeCycle::WidgetAdded`].
+    ///
+    /// [`Role`]: crate::accessibility::Role
+    /// [accessibility tree]: crate::accessibility
+    /// [`LifeCycle::WidgetAdded`]: crate::LifeCycle::WidgetAdded
+    pub fn set_access_role(&mut self, role: Role) {
+        if self.widget_state.access.role != Some(role) {
+            self.widget_state.access.role = Some(role);
+            self.widget_state.access_changed = true;
+        }
+    }
+
+    /// Set the name assistive technology uses for this widget, such as the
+    /// text of a label or button.
+    pub fn set_access_name(&mut self, name: impl Into<String>) {
+        let name = Some(name.into());
+        if self.widget_state.access.name != name {
+            self.widget_state.access.name = name;
+            self.widget_state.access_changed = true;
+        }
+    }
+
+    /// Set the current value of this widget, such as the text of a text box
+    /// or the position of a slider.
+    ///
+    /// Changing the value is announced as an [`AccessEvent::ValueChanged`].
+    ///
+    /// [`AccessEvent::ValueChanged`]: crate::accessibility::AccessEvent::ValueChanged
+    pub fn set_access_value(&mut self, value: impl Into<String>) {
+        let value = Some(value.into());
+        if self.widget_state.access.value != value {
+            self.widget_state.access.value = value;
+            self.widget_state.access_changed = true;
+        }
+    }
+
+    /// Set whether this widget is checked, for widgets such as checkboxes.
+    pub fn set_access_checked(&mut self, checked: bool) {
+        if self.widget_state.access.checked != Some(checked) {
+            self.widget_state.access.checked = Some(checked);
+            self.widget_state.access_changed = true;
+        }
+    }
+
     /// Indicate that text input state has changed.
     ///
     /// A widget that accepts text input should call this anytime input state
@@ -470,6 +530,62 @@ impl_context_method!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, LifeCycleCtx<'_, '_>,
     pub fn scroll_to_view(&mut self) {
         self.scroll_area_to_view(self.size().to_rect())
     }
+
+    /// Run `future` in the background, and call `callback` with its output
+    /// once it is done.
+    ///
+    /// The future is run on a new thread, by a minimal executor that doesn't
+    /// depend on any async runtime; a future that needs one, such as `tokio`,
+    /// should be spawned on that runtime, and send its output back through
+    /// an [`ExtEventSink`]. The callback is called on the UI thread, in the
+    /// widget's [`event`] method, and can change the data.
+    ///
+    /// `T` must be the data type of the [`WidgetPod`] that contains the
+    /// widget. If there is a lens in between, as in `widget.lens(..)`, the
+    /// types don't match, and the output is dropped.
+    ///
+    /// The task is cancelled when the widget is removed, or with
+    /// [`cancel_task`]; the future is then dropped the next time it yields,
+    /// and the callback is never called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use druid::EventCtx;
+    /// fn refresh(ctx: &mut EventCtx) {
+    ///     ctx.spawn_async(async { 6 * 7 }, |ctx, data: &mut u32, answer| {
+    ///         *data = answer;
+    ///         ctx.request_paint();
+    ///     });
+    /// }
+    /// ```
+    ///
+    /// [`event`]: Widget::event
+    /// [`WidgetPod`]: crate::WidgetPod
+    /// [`cancel_task`]: Self::cancel_task
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn_async<T: Data, R: Send + 'static>(
+        &mut self,
+        future: impl Future<Output = R> + Send + 'static,
+        callback: impl FnOnce(&mut EventCtx, &mut T, R) + 'static,
+    ) -> TaskToken {
+        trace!("spawn_async");
+        let sink = self.state.ext_handle.clone();
+        self.widget_state
+            .tasks
+            .spawn(self.widget_state.id, sink, future, callback)
+    }
+
+    /// Cancel a task started with [`spawn_async`], so that its callback is
+    /// never called.
+    ///
+    /// Returns `false` if the task was already done or cancelled.
+    ///
+    /// [`spawn_async`]: Self::spawn_async
+    pub fn cancel_task(&mut self, token: TaskToken) -> bool {
+        trace!("cancel_task");
+        self.widget_state.tasks.remove(token).is_some()
+    }
 });
 
 // methods on everyone but paintctx
@@ -574,16 +690,15 @@ impl EventCtx<'_, '_> {
     /// Show the context menu in the window containing the current widget.
     /// `T` must be the application's root `Data` type (the type provided to [`AppLauncher::launch`]).
     ///
+    /// `location` is in the window's coordinate space; see [`SHOW_CONTEXT_MENU`].
+    ///
     /// [`AppLauncher::launch`]: struct.AppLauncher.html#method.launch
+    /// [`SHOW_CONTEXT_MENU`]: crate::commands::SHOW_CONTEXT_MENU
     pub fn show_context_menu<T: Any>(&mut self, menu: Menu<T>, location: Point) {
         trace!("show_context_menu");
         if self.state.root_app_data_type == TypeId::of::<T>() {
-            let menu = ContextMenu { menu, location };
-            self.submit_command(
-                commands::SHOW_CONTEXT_MENU
-                    .with(SingleUse::new(Box::new(menu)))
-                    .to(Target::Window(self.state.window_id)),
-            );
+            let menu = ContextMenu::new(menu, location);
+            self.submit_command(Command::from(menu).to(Target::Window(self.state.window_id)));
         } else {
             debug_panic!(
                 "EventCtx::show_context_menu<T> - T must match the application data type."
@@ -591,6 +706,82 @@ impl EventCtx<'_, '_> {
         }
     }
 
+    /// Show a [`Popup`] in the [`OverlayLayer`] of the window containing the
+    /// current widget.
+    ///
+    /// `T` must be the data type of the `OverlayLayer`, which is usually the
+    /// root widget. The popup's anchor is in the window's coordinate space.
+    /// This widget gets [`POPUP_DISMISSED`] when the popup is dismissed.
+    ///
+    /// [`Popup`]: crate::widget::Popup
+    /// [`OverlayLayer`]: crate::widget::OverlayLayer
+    /// [`POPUP_DISMISSED`]: crate::commands::POPUP_DISMISSED
+    pub fn show_popup<T: Any>(&mut self, mut popup: Popup<T>) -> PopupId {
+        trace!("show_popup");
+        popup.requester = Some(self.widget_id());
+        let id = popup.id();
+        let popup: Box<dyn Any> = Box::new(popup);
+        self.submit_command(
+            commands::SHOW_POPUP
+                .with(SingleUse::new(popup))
+                .to(Target::Window(self.state.window_id)),
+        );
+        id
+    }
+
+    /// Show a modal [`Dialog`] in the window's [`OverlayLayer`].
+    ///
+    /// The dialog's callback is called when the dialog is closed, by escape,
+    /// a click outside of it or [`close_dialog`]. The widget that shows the
+    /// dialog also gets [`POPUP_DISMISSED`] with the returned id.
+    ///
+    /// [`Dialog`]: crate::widget::Dialog
+    /// [`OverlayLayer`]: crate::widget::OverlayLayer
+    /// [`close_dialog`]: EventCtx::close_dialog
+    /// [`POPUP_DISMISSED`]: crate::commands::POPUP_DISMISSED
+    pub fn show_dialog<T: Data, R: Any>(&mut self, dialog: Dialog<T, R>) -> PopupId {
+        trace!("show_dialog");
+        self.show_popup(dialog.into_popup())
+    }
+
+    /// Close the [`Dialog`] that contains this widget with `result`.
+    ///
+    /// `result` must have the type of the dialog's cancel result.
+    ///
+    /// [`Dialog`]: crate::widget::Dialog
+    pub fn close_dialog<R: Any>(&mut self, result: R) {
+        trace!("close_dialog");
+        let result: Box<dyn Any> = Box::new(result);
+        self.submit_notification(commands::CLOSE_DIALOG.with(SingleUse::new(result)));
+    }
+
+    /// Start dragging `payload` within the window containing the current widget.
+    ///
+    /// This is usually called from [`MouseMove`], once the pointer has moved far
+    /// enough with a button held down; [`DragSource`] does this for you. Until
+    /// the button is released, the widgets under the pointer get drag events
+    /// instead of mouse moves; see [`Event::DragEnter`]. Pressing escape cancels
+    /// the drag.
+    ///
+    /// If there is a `ghost`, it follows the pointer until the drag ends.
+    ///
+    /// [`MouseMove`]: crate::Event::MouseMove
+    /// [`DragSource`]: crate::widget::DragSource
+    /// [`Event::DragEnter`]: crate::Event::DragEnter
+    pub fn start_drag(&mut self, payload: DragPayload, ghost: Option<DragGhost>) {
+        trace!("start_drag");
+        let drag = ActiveDrag {
+            source: self.widget_id(),
+            payload,
+            ghost,
+        };
+        self.submit_command(
+            BEGIN_DRAG
+                .with(SingleUse::new(drag))
+                .to(Target::Window(self.state.window_id)),
+        );
+    }
+
     /// Set the event as "handled", which stops its propagation to other
     /// widgets.
     pub fn set_handled(&mut self) {
@@ -634,11 +825,17 @@ impl EventCtx<'_, '_> {
 
     /// Transfer focus to the next focusable widget.
     ///
-    /// This should only be called by a widget that currently has focus.
+    /// This should only be called by the widget that currently has focus, or
+    /// one of its ancestors.
+    ///
+    /// The focus goes through the widgets in the order set by [`FocusOrder`],
+    /// and stays inside of a [`FocusGroup`] that has it.
     ///
     /// See [`is_focused`] for more information about focus.
     ///
     /// [`is_focused`]: struct.EventCtx.html#method.is_focused
+    /// [`FocusOrder`]: crate::widget::FocusOrder
+    /// [`FocusGroup`]: crate::widget::FocusGroup
     pub fn focus_next(&mut self) {
         trace!("focus_next");
         if self.has_focus() {
@@ -653,11 +850,17 @@ impl EventCtx<'_, '_> {
 
     /// Transfer focus to the previous focusable widget.
     ///
-    /// This should only be called by a widget that currently has focus.
+    /// This should only be called by the widget that currently has focus, or
+    /// one of its ancestors.
+    ///
+    /// The focus goes through the widgets in the order set by [`FocusOrder`],
+    /// and stays inside of a [`FocusGroup`] that has it.
     ///
     /// See [`is_focused`] for more information about focus.
     ///
     /// [`is_focused`]: struct.EventCtx.html#method.is_focused
+    /// [`FocusOrder`]: crate::widget::FocusOrder
+    /// [`FocusGroup`]: crate::widget::FocusGroup
     pub fn focus_prev(&mut self) {
         trace!("focus_prev");
         if self.has_focus() {
@@ -800,7 +1003,11 @@ impl LifeCycleCtx<'_, '_> {
     /// [`EventCtx::is_focused`]: struct.EventCtx.html#method.is_focused
     pub fn register_for_focus(&mut self) {
         trace!("register_for_focus");
-        self.widget_state.focus_chain.push(self.widget_id());
+        self.widget_state.focus_chain.push(FocusEntry {
+            id: self.widget_id(),
+            order: None,
+            group: None,
+        });
     }
 
     /// Register this widget as accepting text input.
@@ -886,6 +1093,46 @@ impl PaintCtx<'_, '_, '_> {
         &self.region
     }
 
+    /// Resolves `disabled` if the widget is [disabled], and `enabled` otherwise.
+    ///
+    /// This lets custom widgets dim themselves like the built-in ones, which
+    /// use [`DISABLED_TEXT_COLOR`] for text and [`DISABLED_BACKGROUND_LIGHT`]
+    /// and [`DISABLED_BACKGROUND_DARK`] for the background of inputs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::Painter;
+    /// use druid::{theme, RenderContext};
+    ///
+    /// let well = Painter::new(|ctx, _: &(), env| {
+    ///     let color = ctx.resolve_disabled(
+    ///         env,
+    ///         theme::BACKGROUND_LIGHT,
+    ///         theme::DISABLED_BACKGROUND_LIGHT,
+    ///     );
+    ///     let bounds = ctx.size().to_rect();
+    ///     ctx.fill(bounds, &color);
+    /// });
+    /// ```
+    ///
+    /// [disabled]: PaintCtx::is_disabled
+    /// [`DISABLED_TEXT_COLOR`]: crate::theme::DISABLED_TEXT_COLOR
+    /// [`DISABLED_BACKGROUND_LIGHT`]: crate::theme::DISABLED_BACKGROUND_LIGHT
+    /// [`DISABLED_BACKGROUND_DARK`]: crate::theme::DISABLED_BACKGROUND_DARK
+    pub fn resolve_disabled<V: ValueType>(
+        &self,
+        env: &Env,
+        enabled: impl Into<KeyOrValue<V>>,
+        disabled: impl Into<KeyOrValue<V>>,
+    ) -> V {
+        if self.is_disabled() {
+            disabled.into().resolve(env)
+        } else {
+            enabled.into().resolve(env)
+        }
+    }
+
     /// Creates a temporary `PaintCtx` with a new visible region, and calls
     /// the provided function with that `PaintCtx`.
     ///
@@ -938,6 +1185,156 @@ impl PaintCtx<'_, '_, '_> {
         }
     }
 
+    /// Paints whatever `f` paints at a reduced opacity.
+    ///
+    /// The painting is rendered into a separate layer covering the widget's
+    /// [`paint_rect`], which is then drawn with `opacity`, from `0.0` (fully
+    /// transparent) to `1.0` (opaque). The layer is clipped like any other
+    /// painting, and nested calls multiply their opacities.
+    ///
+    /// Operations submitted by [`paint_with_z_index`] are painted at full
+    /// opacity. If the layer can't be created, `f` paints directly into this
+    /// context.
+    ///
+    /// [`paint_rect`]: crate::WidgetPod::paint_rect
+    /// [`paint_with_z_index`]: PaintCtx::paint_with_z_index
+    pub fn with_opacity(&mut self, opacity: f64, f: impl FnOnce(&mut PaintCtx)) {
+        let opacity = opacity.clamp(0.0, 1.0);
+        if opacity >= 1.0 {
+            f(self);
+            return;
+        }
+        if opacity <= 0.0 {
+            return;
+        }
+        let rect = self.size().to_rect() + self.widget_state.paint_insets;
+        self.paint_layer(rect, 0.0, f, |pixels, _, _| {
+            // the pixels are premultiplied, so every channel is scaled
+            for c in pixels {
+                *c = (*c as f64 * opacity).round() as u8;
+            }
+        });
+    }
+
+    /// Paints whatever `f` paints with a Gaussian blur.
+    ///
+    /// `radius` is the standard deviation of the blur, in display points.
+    /// Like [`with_opacity`], the painting is rendered into a separate layer,
+    /// which is blurred in software; this layer covers the widget's
+    /// [`paint_rect`] grown by three times the radius, so that the blur can
+    /// spread outwards. Widgets using this should set their paint insets to
+    /// match.
+    ///
+    /// Operations submitted by [`paint_with_z_index`] are not blurred. If the
+    /// layer can't be created, `f` paints directly into this context.
+    ///
+    /// [`with_opacity`]: PaintCtx::with_opacity
+    /// [`paint_rect`]: crate::WidgetPod::paint_rect
+    /// [`paint_with_z_index`]: PaintCtx::paint_with_z_index
+    pub fn with_blur(&mut self, radius: f64, f: impl FnOnce(&mut PaintCtx)) {
+        let rect = self.size().to_rect() + self.widget_state.paint_insets;
+        self.blur_rect(rect.inflate(radius * 3.0, radius * 3.0), radius, f);
+    }
+
+    /// Paints whatever `f` paints in `rect` with a Gaussian blur.
+    pub(crate) fn blur_rect(&mut self, rect: Rect, radius: f64, f: impl FnOnce(&mut PaintCtx)) {
+        if radius <= 0.0 {
+            f(self);
+            return;
+        }
+        let transform = self.render_ctx.current_transform();
+        // the layer is in device pixels, so the radius is scaled too
+        let radius = radius * transform.determinant().abs().sqrt();
+        self.paint_layer(rect, radius * 3.0, f, |pixels, width, height| {
+            gaussian_blur(pixels, width, height, radius)
+        });
+    }
+
+    /// Paints `f` into an offscreen layer covering `rect`, lets `process`
+    /// modify the premultiplied RGBA pixels of the layer, and draws them.
+    ///
+    /// Only the part of `rect` that needs painting is rendered, plus `margin`
+    /// device pixels around it for effects that read their neighbourhood.
+    fn paint_layer(
+        &mut self,
+        rect: Rect,
+        margin: f64,
+        f: impl FnOnce(&mut PaintCtx),
+        process: impl FnOnce(&mut [u8], usize, usize),
+    ) {
+        // the layer is in device space, so that it isn't scaled when drawn again
+        let transform = self.render_ctx.current_transform();
+        let visible = transform
+            .transform_rect_bbox(self.region.bounding_box())
+            .inflate(margin, margin);
+        let bounds = transform
+            .transform_rect_bbox(rect)
+            .intersect(visible)
+            .expand();
+        if bounds.area() <= 0.0 {
+            return;
+        }
+        let render_err = |e: crate::piet::Error| error!("Failed to create paint layer: '{}'", e);
+        let mut device = match Device::new() {
+            Ok(device) => device,
+            Err(e) => {
+                render_err(e);
+                return f(self);
+            }
+        };
+        let mut target =
+            match device.bitmap_target(bounds.width() as usize, bounds.height() as usize, 1.0) {
+                Ok(target) => target,
+                Err(e) => {
+                    render_err(e);
+                    return f(self);
+                }
+            };
+
+        {
+            let mut piet = target.render_context();
+            let offset = bounds.origin().to_vec2();
+            piet.transform(Affine::translate(-offset) * transform);
+            let mut layer_ctx = PaintCtx {
+                render_ctx: &mut piet,
+                state: self.state,
+                widget_state: self.widget_state,
+                z_ops: Vec::new(),
+                region: self.region.clone(),
+                depth: self.depth,
+            };
+            f(&mut layer_ctx);
+            // z-ordered operations are painted later, outside of the layer
+            for mut op in layer_ctx.z_ops.drain(..) {
+                op.transform = Affine::translate(offset) * op.transform;
+                self.z_ops.push(op);
+            }
+            if let Err(e) = piet.finish() {
+                return render_err(e);
+            }
+        }
+
+        let layer = match target.to_image_buf(ImageFormat::RgbaPremul) {
+            Ok(layer) => layer,
+            Err(e) => return render_err(e),
+        };
+        let mut pixels = layer.raw_pixels().to_vec();
+        process(&mut pixels, layer.width(), layer.height());
+        let image = match self.render_ctx.make_image(
+            layer.width(),
+            layer.height(),
+            &pixels,
+            ImageFormat::RgbaPremul,
+        ) {
+            Ok(image) => image,
+            Err(e) => return render_err(e),
+        };
+        self.with_save(|ctx| {
+            ctx.transform(transform.inverse());
+            ctx.draw_image(&image, bounds, InterpolationMode::NearestNeighbor);
+        });
+    }
+
     /// Allows to specify order for paint operations.
     ///
     /// Larger `z_index` indicate that an operation will be executed later.
@@ -1005,3 +1402,85 @@ impl<'c> DerefMut for PaintCtx<'_, '_, 'c> {
         self.render_ctx
     }
 }
+
+/// Blur premultiplied RGBA `pixels` in place, approximating a Gaussian blur
+/// with standard deviation `sigma` by three successive box blurs.
+fn gaussian_blur(pixels: &mut [u8], width: usize, height: usize, sigma: f64) {
+    // box sizes from "Fast Almost-Gaussian Filtering", W. Jarosz
+    const PASSES: f64 = 3.0;
+    let ideal = (12.0 * sigma * sigma / PASSES + 1.0).sqrt();
+    // the largest odd width not above the ideal one
+    let lower = (ideal.floor() as usize).saturating_sub(1) | 1;
+    let upper = lower + 2;
+    let lower_f = lower as f64;
+    let m = ((12.0 * sigma * sigma
+        - PASSES * lower_f * lower_f
+        - 4.0 * PASSES * lower_f
+        - 3.0 * PASSES)
+        / (-4.0 * lower_f - 4.0))
+        .round();
+
+    let mut scratch = vec![0u8; pixels.len()];
+    for pass in 0..PASSES as usize {
+        let size = if (pass as f64) < m { lower } else { upper };
+        let radius = size / 2;
+        if radius == 0 {
+            continue;
+        }
+        box_blur(pixels, &mut scratch, width, height, radius, true);
+        box_blur(&scratch, pixels, width, height, radius, false);
+    }
+}
+
+/// One pass of a box blur, from `src` into `dst`, either along rows or columns.
+///
+/// Pixels outside of the image count as transparent.
+fn box_blur(
+    src: &[u8],
+    dst: &mut [u8],
+    width: usize,
+    height: usize,
+    radius: usize,
+    horizontal: bool,
+) {
+    let (len, lines) = if horizontal {
+        (width, height)
+    } else {
+        (height, width)
+    };
+    let index = |line: usize, i: usize| {
+        if horizontal {
+            (line * width + i) * 4
+        } else {
+            (i * width + line) * 4
+        }
+    };
+    let divisor = (2 * radius + 1) as u32;
+    for line in 0..lines {
+        let mut sum = [0u32; 4];
+        for i in 0..radius.min(len) {
+            let idx = index(line, i);
+            for c in 0..4 {
+                sum[c] += src[idx + c] as u32;
+            }
+        }
+        for i in 0..len {
+            if i + radius < len {
+                let idx = index(line, i + radius);
+                for c in 0..4 {
+                    sum[c] += src[idx + c] as u32;
+                }
+            }
+            let idx = index(line, i);
+            for c in 0..4 {
+                dst[idx + c] = ((sum[c] + divisor / 2) / divisor) as u8;
+            }
+            if i >= radius {
+                let idx = index(line, i - radius);
+                for c in 0..4 {
+                    sum[c] -= src[idx + c] as u32;
+                }
+            }
+        }
+    }
+}
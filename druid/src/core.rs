@@ -17,10 +17,12 @@
 use std::collections::VecDeque;
 use tracing::{info_span, trace, warn};
 
+use crate::accessibility::{AccessNode, AccessProps};
 use crate::bloom::Bloom;
 use crate::command::sys::{CLOSE_WINDOW, SUB_WINDOW_HOST_TO_PARENT, SUB_WINDOW_PARENT_TO_HOST};
 use crate::commands::SCROLL_TO_VIEW;
 use crate::contexts::ContextState;
+use crate::ext_event::{PendingTasks, TaskCallback, TASK_FINISHED};
 use crate::kurbo::{Affine, Insets, Point, Rect, Shape, Size, Vec2};
 use crate::sub_window::SubWindowUpdate;
 use crate::{
@@ -104,6 +106,10 @@ pub struct WidgetState {
     // similar, and it is used for propagating invalid regions.
     pub(crate) viewport_offset: Vec2,
 
+    // `true` if a child had an invalid region when it was merged into this state. Widgets
+    // that scale their children reset this, because the merged region isn't meaningful then.
+    pub(crate) child_invalidated: bool,
+
     // TODO: consider using bitflags for the booleans.
     // `true` if a descendent of this widget changed its disabled state and should receive
     // LifeCycle::DisabledChanged or InternalLifeCycle::RouteDisabledChanged
@@ -144,7 +150,7 @@ pub struct WidgetState {
 
     pub(crate) update_focus_chain: bool,
 
-    pub(crate) focus_chain: Vec<WidgetId>,
+    pub(crate) focus_chain: Vec<FocusEntry>,
     pub(crate) request_focus: Option<FocusChange>,
     pub(crate) children: Bloom<WidgetId>,
     pub(crate) children_changed: bool,
@@ -156,6 +162,14 @@ pub struct WidgetState {
 
     // Port -> Host
     pub(crate) sub_window_hosts: Vec<(WindowId, WidgetId)>,
+
+    /// The accessibility properties set using the context methods.
+    pub(crate) access: AccessProps,
+    /// This widget or a descendant changed its accessibility properties.
+    pub(crate) access_changed: bool,
+
+    /// The tasks started with `spawn_async`, waiting for their output.
+    pub(crate) tasks: PendingTasks,
 }
 
 /// Methods by which a widget can attempt to change focus state.
@@ -171,6 +185,20 @@ pub(crate) enum FocusChange {
     Previous,
 }
 
+/// A widget in the focus chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FocusEntry {
+    pub(crate) id: WidgetId,
+    /// The order set by the closest [`FocusOrder`] ancestor, if any.
+    ///
+    /// [`FocusOrder`]: crate::widget::FocusOrder
+    pub(crate) order: Option<i32>,
+    /// The id of the closest [`FocusGroup`] ancestor, if any.
+    ///
+    /// [`FocusGroup`]: crate::widget::FocusGroup
+    pub(crate) group: Option<WidgetId>,
+}
+
 /// The possible cursor states for a widget.
 #[derive(Clone, Debug)]
 pub(crate) enum CursorChange {
@@ -718,7 +746,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     }
                 }
             },
-            Event::WindowConnected | Event::WindowCloseRequested => true,
+            Event::WindowConnected | Event::WindowCloseRequested | Event::WindowLostFocus => true,
             Event::WindowDisconnected => {
                 for (window_id, _) in &self.state.sub_window_hosts {
                     ctx.submit_command(CLOSE_WINDOW.to(*window_id))
@@ -808,6 +836,110 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     false
                 }
             }
+            Event::FileDragMove(drop_event) => {
+                let hot_changed = WidgetPod::set_hot_state(
+                    &mut self.inner,
+                    &mut self.state,
+                    ctx.state,
+                    rect,
+                    Some(drop_event.pos),
+                    data,
+                    env,
+                );
+                if self.state.is_hot || hot_changed {
+                    let mut drop_event = drop_event.clone();
+                    drop_event.pos -= rect.origin().to_vec2();
+                    modified_event = Some(Event::FileDragMove(drop_event));
+                    true
+                } else {
+                    false
+                }
+            }
+            Event::FileDragLeave => {
+                // Sent to the widgets that were under the pointer, like `MouseLeave`.
+                WidgetPod::set_hot_state(
+                    &mut self.inner,
+                    &mut self.state,
+                    ctx.state,
+                    rect,
+                    None,
+                    data,
+                    env,
+                )
+            }
+            Event::DropFiles(drop_event) => {
+                WidgetPod::set_hot_state(
+                    &mut self.inner,
+                    &mut self.state,
+                    ctx.state,
+                    rect,
+                    Some(drop_event.pos),
+                    data,
+                    env,
+                );
+                if self.state.is_hot {
+                    let mut drop_event = drop_event.clone();
+                    drop_event.pos -= rect.origin().to_vec2();
+                    modified_event = Some(Event::DropFiles(drop_event));
+                    true
+                } else {
+                    false
+                }
+            }
+            Event::DragEnter(drag_event) | Event::DragOver(drag_event) => {
+                let hot_changed = WidgetPod::set_hot_state(
+                    &mut self.inner,
+                    &mut self.state,
+                    ctx.state,
+                    rect,
+                    Some(drag_event.pos),
+                    data,
+                    env,
+                );
+                if self.state.is_hot {
+                    let mut drag_event = drag_event.clone();
+                    drag_event.pos -= rect.origin().to_vec2();
+                    modified_event = Some(if hot_changed {
+                        Event::DragEnter(drag_event)
+                    } else {
+                        Event::DragOver(drag_event)
+                    });
+                    true
+                } else if hot_changed {
+                    modified_event = Some(Event::DragLeave);
+                    true
+                } else {
+                    false
+                }
+            }
+            Event::DragLeave => WidgetPod::set_hot_state(
+                &mut self.inner,
+                &mut self.state,
+                ctx.state,
+                rect,
+                None,
+                data,
+                env,
+            ),
+            Event::Drop(drag_event) => {
+                WidgetPod::set_hot_state(
+                    &mut self.inner,
+                    &mut self.state,
+                    ctx.state,
+                    rect,
+                    Some(drag_event.pos),
+                    data,
+                    env,
+                );
+                if self.state.is_hot {
+                    let mut drag_event = drag_event.clone();
+                    drag_event.pos -= rect.origin().to_vec2();
+                    modified_event = Some(Event::Drop(drag_event));
+                    true
+                } else {
+                    false
+                }
+            }
             Event::AnimFrame(_) => {
                 let r = self.state.request_anim;
                 self.state.request_anim = false;
@@ -845,6 +977,20 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     }
                     ctx.is_handled = true
                 }
+                Event::Command(cmd) if cmd.is(TASK_FINISHED) => {
+                    let (token, output) = cmd.get_unchecked(TASK_FINISHED);
+                    let callback = inner_ctx.widget_state.tasks.remove(*token);
+                    if let (Some(callback), Some(output)) = (callback, output.take()) {
+                        match callback.downcast::<Box<TaskCallback<T>>>() {
+                            Ok(callback) => callback(&mut inner_ctx, data, output),
+                            Err(_) => warn!(
+                                "{:?}: the data of spawn_async is not the data of the widget",
+                                inner_ctx.widget_id()
+                            ),
+                        }
+                    }
+                    ctx.is_handled = true;
+                }
                 Event::Command(cmd) if cmd.is(SCROLL_TO_VIEW) => {
                     // Submit the SCROLL_TO notification if it was used from a update or lifecycle
                     // call.
@@ -1014,6 +1160,14 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     f.call(&self.state);
                     true
                 }
+                InternalLifeCycle::CollectAccessTree(tree) => {
+                    self.state.access_changed = false;
+                    if let Some(mut node) = self.state.access_node() {
+                        node.focused = ctx.state.focus_widget == Some(self.state.id);
+                        tree.push(node);
+                    }
+                    true
+                }
             },
             LifeCycle::WidgetAdded => {
                 assert!(self.old_data.is_none());
@@ -1127,6 +1281,11 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     ctx.widget_state.focus_chain.extend(&self.state.focus_chain);
                 }
             }
+            LifeCycle::Internal(InternalLifeCycle::CollectAccessTree(tree))
+                if self.state.access.role.is_some() =>
+            {
+                tree.pop();
+            }
             _ => (),
         }
 
@@ -1231,6 +1390,7 @@ impl WidgetState {
             paint_insets: Insets::ZERO,
             invalid: Region::EMPTY,
             viewport_offset: Vec2::ZERO,
+            child_invalidated: false,
             children_disabled_changed: false,
             ancestor_disabled: false,
             is_explicitly_disabled: false,
@@ -1250,13 +1410,26 @@ impl WidgetState {
             cursor_change: CursorChange::Default,
             cursor: None,
             sub_window_hosts: Vec::new(),
+            access: AccessProps::default(),
+            access_changed: false,
             is_explicitly_disabled_new: false,
             update_focus_chain: false,
+            tasks: PendingTasks::default(),
         }
     }
 
-    pub(crate) fn is_disabled(&self) -> bool {
-        self.is_explicitly_disabled || self.ancestor_disabled
+    /// The accessibility node describing this widget, if it has set a role.
+    ///
+    /// The node has no children; those are added while collecting the tree.
+    fn access_node(&self) -> Option<AccessNode> {
+        let role = self.access.role?;
+        let mut node = AccessNode::new(self.id, role);
+        node.name = self.access.name.clone();
+        node.value = self.access.value.clone();
+        node.checked = self.access.checked;
+        node.disabled = self.is_disabled();
+        node.bounds = Rect::from_origin_size(self.window_origin(), self.size);
+        Some(node)
     }
 
     pub(crate) fn tree_disabled_changed(&self) -> bool {
@@ -1275,6 +1448,7 @@ impl WidgetState {
             .with_origin(Point::ORIGIN)
             .inset(self.paint_insets);
         let offset = child_state.layout_rect().origin().to_vec2() - child_state.viewport_offset;
+        self.child_invalidated |= !child_state.invalid.is_empty();
         for &r in child_state.invalid.rects() {
             let r = (r + offset).intersect(clip);
             if r.area() != 0.0 {
@@ -1299,6 +1473,7 @@ impl WidgetState {
         self.request_update |= child_state.request_update;
         self.request_focus = child_state.request_focus.take().or(self.request_focus);
         self.update_focus_chain |= child_state.update_focus_chain;
+        self.access_changed |= child_state.access_changed;
 
         // We reset `child_state.cursor` no matter what, so that on the every pass through the tree,
         // things will be recalculated just from `cursor_change`.
@@ -1323,11 +1498,50 @@ impl WidgetState {
         self.cursor.take().or_else(|| self.cursor_change.cursor())
     }
 
+    /// The id of the widget.
+    pub fn id(&self) -> WidgetId {
+        self.id
+    }
+
+    /// The size of the widget, as returned by its `layout` method.
     #[inline]
-    pub(crate) fn size(&self) -> Size {
+    pub fn size(&self) -> Size {
         self.size
     }
 
+    /// Returns `true` if the mouse is over the widget.
+    ///
+    /// See [`EventCtx::is_hot`] for more details.
+    ///
+    /// [`EventCtx::is_hot`]: crate::EventCtx::is_hot
+    pub fn is_hot(&self) -> bool {
+        self.is_hot
+    }
+
+    /// Returns `true` if the widget is active.
+    ///
+    /// See [`EventCtx::is_active`] for more details.
+    ///
+    /// [`EventCtx::is_active`]: crate::EventCtx::is_active
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    /// Returns `true` if the widget or one of its descendants has keyboard focus.
+    pub fn has_focus(&self) -> bool {
+        self.has_focus
+    }
+
+    /// Returns `true` if the widget is disabled, explicitly or by an ancestor.
+    pub fn is_disabled(&self) -> bool {
+        self.is_explicitly_disabled || self.ancestor_disabled
+    }
+
+    /// Returns `true` if the widget or one of its descendants has requested layout.
+    pub fn needs_layout(&self) -> bool {
+        self.needs_layout
+    }
+
     /// The paint region for this widget.
     ///
     /// For more information, see [`WidgetPod::paint_rect`].
@@ -1346,7 +1560,8 @@ impl WidgetState {
         self.sub_window_hosts.push((window_id, host_id))
     }
 
-    pub(crate) fn window_origin(&self) -> Point {
+    /// The origin of the widget in the window coordinate space.
+    pub fn window_origin(&self) -> Point {
         self.parent_window_origin + self.origin.to_vec2() - self.viewport_offset
     }
 }
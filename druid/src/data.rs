@@ -46,6 +46,10 @@ use piet::ImageBuf;
 /// }
 /// ```
 ///
+/// For enums, two values are the same if they are the same variant, and each
+/// pair of their fields is the same. The field attributes below work on the
+/// fields of enum variants too.
+///
 /// ### Derive macro attributes
 ///
 /// There are a number of field attributes available for use with `derive(Data)`.
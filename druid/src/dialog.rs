@@ -56,6 +56,19 @@ use crate::{FileInfo, FileSpec, Selector};
 /// and then keep saving to that file without showing a save dialog.
 /// Use the save dialog only for selecting a new location.
 ///
+/// # Platform support
+///
+/// The options are passed on to the platform's native dialog. Where a platform
+/// doesn't support an option, it falls back as follows:
+///
+/// - GTK has no setting for [`name_label`], and keeps its own label.
+/// - GTK doesn't filter the directories by [`allowed_types`] in *directories mode*,
+///   because the filter would stop the user from traversing into directories.
+/// - [`packages_as_directories`] only has an effect on macOS.
+/// - [`select_directories`] and [`multi_selection`] have no effect on save dialogs.
+/// - The X11, Wayland and web backends don't have file dialogs yet.
+///   Opening a dialog logs a warning, and no command is submitted.
+///
 /// # macOS
 ///
 /// The file dialog works a bit differently on macOS. For a lot of applications this doesn't matter
@@ -110,6 +123,8 @@ use crate::{FileInfo, FileSpec, Selector};
 /// [`select_directories`]: #method.select_directories
 /// [`allowed_types`]: #method.allowed_types
 /// [`packages_as_directories`]: #method.packages_as_directories
+/// [`name_label`]: #method.name_label
+/// [`multi_selection`]: #method.multi_selection
 /// [druid#998]: https://github.com/xi-editor/druid/issues/998
 #[derive(Debug, Clone, Default)]
 pub struct FileDialogOptions {
@@ -153,7 +168,12 @@ impl FileDialogOptions {
 
     /// Set multiple items to be selectable.
     ///
+    /// The chosen items are then submitted with the [`accept_multiple_command`],
+    /// as a `Vec<FileInfo>`.
+    ///
     /// This is only relevant for open dialogs.
+    ///
+    /// [`accept_multiple_command`]: #method.accept_multiple_command
     pub fn multi_selection(mut self) -> Self {
         self.opt = self.opt.multi_selection();
         self
@@ -177,6 +197,23 @@ impl FileDialogOptions {
         self
     }
 
+    /// Set the formats the user can choose from, as pairs of a human readable
+    /// name and an extension.
+    ///
+    /// This sets the [`allowed_types`] to a [`FileSpec`] for each format. In save
+    /// dialogs, the extension of the chosen format is applied to the path, and the
+    /// format is reported in [`FileInfo::format`].
+    ///
+    /// macOS shows a format selector below the files in save dialogs. The other
+    /// platforms don't support that; they list the formats as file type filters,
+    /// and report the selected filter as the format.
+    ///
+    /// [`allowed_types`]: #method.allowed_types
+    pub fn with_format_choices(mut self, choices: &'static [(&'static str, &'static str)]) -> Self {
+        self.opt = self.opt.with_format_choices(choices);
+        self
+    }
+
     /// Set the default file type.
     ///
     /// The provided `default_type` must also be present in [`allowed_types`].
@@ -198,6 +235,8 @@ impl FileDialogOptions {
     }
 
     /// Set the text in the label next to the filename editbox.
+    ///
+    /// This is ignored on GTK.
     pub fn name_label(mut self, name_label: impl Into<String>) -> Self {
         self.opt = self.opt.name_label(name_label);
         self
@@ -0,0 +1,159 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dragging values between widgets.
+
+use std::any::{type_name, Any};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::kurbo::{Point, Rect, Size, Vec2};
+use crate::{Env, Modifiers, PaintCtx, Selector, SingleUse, WidgetId};
+
+/// Starts a drag in the window that receives it; see [`EventCtx::start_drag`].
+///
+/// [`EventCtx::start_drag`]: crate::EventCtx::start_drag
+pub(crate) const BEGIN_DRAG: Selector<SingleUse<ActiveDrag>> =
+    Selector::new("druid-builtin.begin-drag");
+
+type PaintGhostFn = dyn Fn(&mut PaintCtx, &Env);
+
+/// The value carried by a drag within the application.
+///
+/// A payload can hold a value of any type. Drop targets check for the types
+/// they understand with [`get`] or [`is`], so several kinds of drag can share
+/// one window without getting in each other's way.
+///
+/// Cloning a payload is cheap; every clone refers to the same value.
+///
+/// [`get`]: DragPayload::get
+/// [`is`]: DragPayload::is
+#[derive(Clone)]
+pub struct DragPayload {
+    value: Rc<dyn Any>,
+    type_name: &'static str,
+}
+
+/// Something that is dragged within the application.
+///
+/// This is what [`Event::DragEnter`], [`Event::DragOver`] and [`Event::Drop`]
+/// carry.
+///
+/// [`Event::DragEnter`]: crate::Event::DragEnter
+/// [`Event::DragOver`]: crate::Event::DragOver
+/// [`Event::Drop`]: crate::Event::Drop
+#[derive(Debug, Clone)]
+pub struct DragEvent {
+    /// The position of the pointer in the coordinate space of the receiver.
+    pub pos: Point,
+    /// The position of the pointer in the coordinate space of the window.
+    pub window_pos: Point,
+    /// Keyboard modifiers at the time of the event.
+    pub mods: Modifiers,
+    /// The widget that started the drag.
+    pub source: WidgetId,
+    /// The dragged value.
+    pub payload: DragPayload,
+}
+
+/// The image that follows the pointer during a drag.
+///
+/// A ghost is a painting function and the size of the area it paints in.
+/// It is painted above everything else in the window, with its origin
+/// at the pointer plus the [offset].
+///
+/// [offset]: DragGhost::with_offset
+#[derive(Clone)]
+pub struct DragGhost {
+    size: Size,
+    offset: Vec2,
+    paint: Rc<PaintGhostFn>,
+}
+
+/// The drag that is in progress in a window.
+#[derive(Clone)]
+pub(crate) struct ActiveDrag {
+    pub(crate) source: WidgetId,
+    pub(crate) payload: DragPayload,
+    pub(crate) ghost: Option<DragGhost>,
+}
+
+impl DragPayload {
+    /// Create a payload carrying `value`.
+    pub fn new<P: Any>(value: P) -> Self {
+        DragPayload {
+            value: Rc::new(value),
+            type_name: type_name::<P>(),
+        }
+    }
+
+    /// Returns the value if it is a `P`.
+    pub fn get<P: Any>(&self) -> Option<&P> {
+        self.value.downcast_ref()
+    }
+
+    /// Returns `true` if the value is a `P`.
+    pub fn is<P: Any>(&self) -> bool {
+        self.value.is::<P>()
+    }
+}
+
+impl fmt::Debug for DragPayload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DragPayload({})", self.type_name)
+    }
+}
+
+impl DragGhost {
+    /// Create a ghost of the given size, painted by `paint`.
+    ///
+    /// The painting function is called with the origin of the ghost at
+    /// `(0, 0)`.
+    pub fn new(size: Size, paint: impl Fn(&mut PaintCtx, &Env) + 'static) -> Self {
+        DragGhost {
+            size,
+            offset: Vec2::ZERO,
+            paint: Rc::new(paint),
+        }
+    }
+
+    /// Builder-style method for setting the position of the ghost relative to
+    /// the pointer.
+    ///
+    /// The default is `(0, 0)`, which puts the top left corner of the ghost
+    /// at the pointer. Use the position of the pointer in the dragged widget,
+    /// negated, to make it look like the widget itself is being dragged.
+    pub fn with_offset(mut self, offset: impl Into<Vec2>) -> Self {
+        self.offset = offset.into();
+        self
+    }
+
+    /// The area covered by the ghost when the pointer is at `pos`.
+    pub(crate) fn rect(&self, pos: Point) -> Rect {
+        Rect::from_origin_size(pos + self.offset, self.size)
+    }
+
+    pub(crate) fn paint(&self, ctx: &mut PaintCtx, env: &Env) {
+        (self.paint)(ctx, env)
+    }
+}
+
+impl fmt::Debug for DragGhost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DragGhost")
+            .field("size", &self.size)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
@@ -25,6 +25,7 @@ use std::sync::Arc;
 use crate::kurbo::RoundedRectRadii;
 use crate::localization::L10nManager;
 use crate::text::FontDescriptor;
+use crate::widget::LayoutDirection;
 use crate::{ArcStr, Color, Data, Insets, Point, Rect, Size};
 
 /// An environment passed down through all widget traversals.
@@ -199,6 +200,15 @@ pub struct MissingKeyError {
     key: Arc<str>,
 }
 
+/// The error returned by [`Env::try_get`] and [`KeyOrValue::try_resolve`].
+#[derive(Debug, Clone)]
+pub enum EnvError {
+    /// The key is not in the [`Env`].
+    MissingKey(MissingKeyError),
+    /// The key is in the [`Env`], but its value has a different type.
+    ValueType(ValueTypeError),
+}
+
 impl Env {
     /// State for whether or not to paint colorful rectangles for layout
     /// debugging.
@@ -257,19 +267,27 @@ impl Env {
 
     /// Trys to get a value from the environment.
     ///
-    /// If the value is not found, the raw key is returned as the error.
+    /// This returns an error if the key is not found, or if its value has the
+    /// wrong type. Widgets that can work without a value, such as widgets from
+    /// libraries that can't be sure the application configured their keys,
+    /// should prefer this to [`get`], and fall back to a default.
     ///
-    /// # Panics
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::{Env, Key};
     ///
-    /// Panics if the value for the key is found, but has the wrong type.
-    pub fn try_get<V: ValueType>(&self, key: impl Borrow<Key<V>>) -> Result<V, MissingKeyError> {
-        self.0
-            .map
-            .get(key.borrow().key)
-            .map(|value| value.to_inner_unchecked())
-            .ok_or(MissingKeyError {
-                key: key.borrow().key.into(),
-            })
+    /// const SPACING: Key<f64> = Key::new("org.example.my-widget.spacing");
+    ///
+    /// let env = Env::empty();
+    /// let spacing = env.try_get(SPACING).unwrap_or(4.0);
+    /// assert_eq!(spacing, 4.0);
+    /// ```
+    ///
+    /// [`get`]: Env::get
+    pub fn try_get<V: ValueType>(&self, key: impl Borrow<Key<V>>) -> Result<V, EnvError> {
+        let value = self.try_get_untyped(key)?;
+        Ok(V::try_from_value(value)?)
     }
 
     /// Gets a value from the environment, in its encapsulated [`Value`] form,
@@ -330,6 +348,34 @@ impl Env {
         self.try_set_raw(key, value).unwrap();
     }
 
+    /// Sets every value of `other` in this environment, replacing any
+    /// existing values for the same keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value in `other` is of a different type than the existing
+    /// value for its key.
+    pub(crate) fn set_all(&mut self, other: &Env) {
+        let env = Arc::make_mut(&mut self.0);
+        for (key, value) in other.get_all() {
+            match env.map.entry(key.clone()) {
+                Entry::Occupied(mut e) => {
+                    let existing = e.get_mut();
+                    if !existing.is_same_type(value) {
+                        panic!(
+                            "Incorrect value type for '{}': expected {:?} found {:?}",
+                            key, existing, value
+                        );
+                    }
+                    *existing = value.clone();
+                }
+                Entry::Vacant(e) => {
+                    e.insert(value.clone());
+                }
+            }
+        }
+    }
+
     /// Try to set a resolved `Value` for this key.
     ///
     /// This will return a [`ValueTypeError`] if the value's inner type differs
@@ -443,20 +489,23 @@ impl Value {
 
     fn is_same_type(&self, other: &Value) -> bool {
         use Value::*;
-        matches!(
-            (self, other),
-            (Point(_), Point(_))
-                | (Size(_), Size(_))
-                | (Rect(_), Rect(_))
-                | (Insets(_), Insets(_))
-                | (Color(_), Color(_))
-                | (Float(_), Float(_))
-                | (Bool(_), Bool(_))
-                | (UnsignedInt(_), UnsignedInt(_))
-                | (String(_), String(_))
-                | (Font(_), Font(_))
-                | (RoundedRectRadii(_), RoundedRectRadii(_))
-        )
+        match (self, other) {
+            (Other(a), Other(b)) => (**a).type_id() == (**b).type_id(),
+            _ => matches!(
+                (self, other),
+                (Point(_), Point(_))
+                    | (Size(_), Size(_))
+                    | (Rect(_), Rect(_))
+                    | (Insets(_), Insets(_))
+                    | (Color(_), Color(_))
+                    | (Float(_), Float(_))
+                    | (Bool(_), Bool(_))
+                    | (UnsignedInt(_), UnsignedInt(_))
+                    | (String(_), String(_))
+                    | (Font(_), Font(_))
+                    | (RoundedRectRadii(_), RoundedRectRadii(_))
+            ),
+        }
     }
 }
 
@@ -584,8 +633,30 @@ impl std::fmt::Display for MissingKeyError {
     }
 }
 
+impl std::fmt::Display for EnvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EnvError::MissingKey(err) => write!(f, "{}", err),
+            EnvError::ValueType(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<MissingKeyError> for EnvError {
+    fn from(err: MissingKeyError) -> EnvError {
+        EnvError::MissingKey(err)
+    }
+}
+
+impl From<ValueTypeError> for EnvError {
+    fn from(err: ValueTypeError) -> EnvError {
+        EnvError::ValueType(err)
+    }
+}
+
 impl std::error::Error for ValueTypeError {}
 impl std::error::Error for MissingKeyError {}
+impl std::error::Error for EnvError {}
 
 /// Use this macro for types which are cheap to clone (ie all `Copy` types).
 macro_rules! impl_value_type {
@@ -638,6 +709,22 @@ impl<T: 'static + Send + Sync> ValueType for Arc<T> {
     }
 }
 
+impl ValueType for LayoutDirection {
+    fn try_from_value(value: &Value) -> Result<Self, ValueTypeError> {
+        match value {
+            Value::Other(o) => o.downcast_ref::<LayoutDirection>().copied(),
+            _ => None,
+        }
+        .ok_or_else(|| ValueTypeError::new(any::type_name::<LayoutDirection>(), value.clone()))
+    }
+}
+
+impl From<LayoutDirection> for Value {
+    fn from(direction: LayoutDirection) -> Value {
+        Value::Other(Arc::new(direction))
+    }
+}
+
 impl<T: ValueType> KeyOrValue<T> {
     /// Resolve the concrete type `T` from this `KeyOrValue`, using the provided
     /// [`Env`] if required.
@@ -649,6 +736,17 @@ impl<T: ValueType> KeyOrValue<T> {
             KeyOrValue::Key(key) => env.get(key),
         }
     }
+
+    /// Resolve the concrete type `T` from this `KeyOrValue`, returning an
+    /// error instead of panicking if the key is missing or has the wrong type.
+    ///
+    /// See [`Env::try_get`].
+    pub fn try_resolve(&self, env: &Env) -> Result<T, EnvError> {
+        match self {
+            KeyOrValue::Concrete(ref value) => Ok(value.to_owned()),
+            KeyOrValue::Key(key) => env.try_get(key),
+        }
+    }
 }
 
 impl<T: Into<Value>> From<T> for KeyOrValue<T> {
@@ -702,4 +800,34 @@ mod tests {
 
         assert_send_sync::<Key<()>>();
     }
+
+    #[test]
+    fn set_all_replaces_values() {
+        const SIZE_KEY: Key<f64> = Key::new("org.linebender.test.size");
+        const NAME_KEY: Key<ArcStr> = Key::new("org.linebender.test.name");
+        let mut env = Env::empty().adding(SIZE_KEY, 1.0).adding(NAME_KEY, "old");
+        env.set_all(&Env::empty().adding(SIZE_KEY, 2.0));
+        assert_eq!(env.get(SIZE_KEY), 2.0);
+        assert_eq!(env.get(NAME_KEY).as_ref(), "old");
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_all_checks_types() {
+        const KEY: Key<f64> = Key::new("org.linebender.test.typed");
+        let mut env = Env::empty().adding(KEY, 1.0);
+        env.set_all(&Env::empty().adding(Key::<bool>::new("org.linebender.test.typed"), true));
+    }
+
+    #[test]
+    fn try_get_reports_errors() {
+        const KEY: Key<f64> = Key::new("org.linebender.test.try-get");
+        let env = Env::empty();
+        assert!(matches!(env.try_get(KEY), Err(EnvError::MissingKey(_))));
+
+        let env = env.adding(Key::<bool>::new("org.linebender.test.try-get"), true);
+        assert!(matches!(env.try_get(KEY), Err(EnvError::ValueType(_))));
+        let value: KeyOrValue<f64> = KEY.into();
+        assert!(value.try_resolve(&env).is_err());
+    }
 }
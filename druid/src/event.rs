@@ -18,7 +18,8 @@ use crate::kurbo::{Rect, Shape, Size, Vec2};
 
 use druid_shell::{Clipboard, KeyEvent, TimerToken};
 
-use crate::mouse::MouseEvent;
+use crate::drag::DragEvent;
+use crate::mouse::{FileDropEvent, MouseEvent};
 use crate::{Command, Notification, WidgetId};
 
 /// An event, propagated downwards during event flow.
@@ -74,6 +75,12 @@ pub enum Event {
     /// This event means the window *will* go away; it is safe to dispose of resources and
     /// do any other cleanup.
     WindowDisconnected,
+    /// Sent to all widgets in a given window when that window loses keyboard focus,
+    /// for instance because the user clicked on another window.
+    ///
+    /// This is useful for transient windows such as drop-downs, which should
+    /// close themselves when they are no longer focused.
+    WindowLostFocus,
     /// Called on the root widget when the window size changes.
     ///
     /// Discussion: it's not obvious this should be propagated to user
@@ -115,6 +122,83 @@ pub enum Event {
     ///
     /// The value is a delta.
     Zoom(f64),
+    /// Called when files are dragged over the window from another application.
+    ///
+    /// Like [`MouseMove`], this is sent to the widgets under the pointer,
+    /// which become hot, and to widgets that stop being hot because of it.
+    /// The position is in the coordinate space of the receiver; see
+    /// [`FileDropEvent`]. A widget that would accept the files if they were
+    /// dropped at this position should call [`set_handled`]; the platform uses
+    /// this to give the user feedback, usually by changing the cursor.
+    ///
+    /// This is not sent on every platform; Windows only reports the drop
+    /// itself, with [`DropFiles`].
+    ///
+    /// [`MouseMove`]: Event::MouseMove
+    /// [`DropFiles`]: Event::DropFiles
+    /// [`set_handled`]: crate::EventCtx::set_handled
+    FileDragMove(FileDropEvent),
+    /// Called when files that were dragged over the window leave it, or the
+    /// drag is cancelled.
+    ///
+    /// This is sent to the widgets that are hot, and clears their hot state.
+    /// Some platforms send this right before [`DropFiles`] as well.
+    ///
+    /// [`DropFiles`]: Event::DropFiles
+    FileDragLeave,
+    /// Called when files are dropped onto the window from another application.
+    ///
+    /// This is sent to the widgets under the pointer, with the position in the
+    /// coordinate space of the receiver; see [`FileDropEvent`]. A widget that
+    /// accepts the files should call [`set_handled`].
+    ///
+    /// [`set_handled`]: crate::EventCtx::set_handled
+    DropFiles(FileDropEvent),
+    /// Called when a drag within the application enters a widget.
+    ///
+    /// Drags are started with [`EventCtx::start_drag`]. While a drag is in
+    /// progress, mouse moves are delivered as drag events instead of
+    /// [`MouseMove`]: a widget that the pointer enters gets `DragEnter`, and
+    /// afterwards [`DragOver`] for as long as the pointer stays inside it.
+    /// `DragEnter` is sent instead of the first `DragOver`, so a widget
+    /// should treat it the same way.
+    ///
+    /// A widget that would accept the [payload] if it was dropped at this
+    /// position should call [`set_handled`]; this is used to choose the
+    /// cursor.
+    ///
+    /// [`EventCtx::start_drag`]: crate::EventCtx::start_drag
+    /// [`MouseMove`]: Event::MouseMove
+    /// [`DragOver`]: Event::DragOver
+    /// [payload]: DragEvent::payload
+    /// [`set_handled`]: crate::EventCtx::set_handled
+    DragEnter(DragEvent),
+    /// Called when a drag within the application moves over a widget.
+    ///
+    /// See [`DragEnter`] for details.
+    ///
+    /// [`DragEnter`]: Event::DragEnter
+    DragOver(DragEvent),
+    /// Called when a drag within the application leaves a widget, or the
+    /// drag is cancelled.
+    ///
+    /// This is sent to the widgets that got [`DragEnter`]. When the user
+    /// presses escape, this is sent to all widgets under the pointer and
+    /// the drag ends without a [`Drop`].
+    ///
+    /// [`DragEnter`]: Event::DragEnter
+    /// [`Drop`]: Event::Drop
+    DragLeave,
+    /// Called when a drag within the application is dropped.
+    ///
+    /// This is sent to the widgets under the pointer, with the position in
+    /// the coordinate space of the receiver. A widget that accepts the
+    /// payload should call [`set_handled`]. The widget that started the drag
+    /// gets the usual [`MouseUp`] afterwards.
+    ///
+    /// [`set_handled`]: crate::EventCtx::set_handled
+    /// [`MouseUp`]: Event::MouseUp
+    Drop(DragEvent),
     /// Called on a timer event.
     ///
     /// Request a timer event through [`EventCtx::request_timer()`]. That will
@@ -359,6 +443,10 @@ pub enum InternalLifeCycle {
     },
     /// For testing: apply the given function on every widget.
     DebugInspectState(StateCheckFn),
+    /// Used to collect the accessibility tree of the window.
+    ///
+    /// Every widget that has set an accessibility role adds a node to the tree.
+    CollectAccessTree(AccessTreeCell),
 }
 
 impl Event {
@@ -405,6 +493,24 @@ impl Event {
                     None
                 }
             }
+            Event::FileDragMove(drop_event) => {
+                if force || viewport.winding(drop_event.pos) != 0 {
+                    let mut drop_event = drop_event.clone();
+                    drop_event.pos += offset;
+                    Some(Event::FileDragMove(drop_event))
+                } else {
+                    None
+                }
+            }
+            Event::DropFiles(drop_event) => {
+                if force || viewport.winding(drop_event.pos) != 0 {
+                    let mut drop_event = drop_event.clone();
+                    drop_event.pos += offset;
+                    Some(Event::DropFiles(drop_event))
+                } else {
+                    None
+                }
+            }
             _ => Some(self.clone()),
         }
     }
@@ -431,11 +537,14 @@ impl Event {
             Event::WindowConnected
             | Event::WindowCloseRequested
             | Event::WindowDisconnected
+            | Event::WindowLostFocus
             | Event::WindowSize(_)
             | Event::Timer(_)
             | Event::AnimFrame(_)
             | Event::Command(_)
             | Event::Notification(_)
+            | Event::FileDragLeave
+            | Event::DragLeave
             | Event::Internal(_) => true,
             Event::MouseDown(_)
             | Event::MouseUp(_)
@@ -445,7 +554,12 @@ impl Event {
             | Event::KeyUp(_)
             | Event::Paste(_)
             | Event::ImeStateChange
-            | Event::Zoom(_) => false,
+            | Event::Zoom(_)
+            | Event::FileDragMove(_)
+            | Event::DropFiles(_)
+            | Event::DragEnter(_)
+            | Event::DragOver(_)
+            | Event::Drop(_) => false,
         }
     }
 }
@@ -485,7 +599,9 @@ impl InternalLifeCycle {
             InternalLifeCycle::RouteWidgetAdded
             | InternalLifeCycle::RouteFocusChanged { .. }
             | InternalLifeCycle::RouteDisabledChanged => true,
-            InternalLifeCycle::ParentWindowOrigin => false,
+            InternalLifeCycle::ParentWindowOrigin | InternalLifeCycle::CollectAccessTree(_) => {
+                false
+            }
             InternalLifeCycle::DebugRequestState { .. }
             | InternalLifeCycle::DebugRequestDebugState { .. }
             | InternalLifeCycle::DebugInspectState(_) => true,
@@ -493,13 +609,15 @@ impl InternalLifeCycle {
     }
 }
 
-pub(crate) use state_cell::{DebugStateCell, StateCell, StateCheckFn};
+pub(crate) use state_cell::{AccessTreeCell, DebugStateCell, StateCell, StateCheckFn};
 
 mod state_cell {
+    use crate::accessibility::{AccessNode, AccessTreeBuilder};
     use crate::core::WidgetState;
     use crate::debug_state::DebugState;
     use crate::WidgetId;
     use std::{cell::RefCell, rc::Rc};
+    use tracing::error;
 
     /// An interior-mutable struct for fetching WidgetState.
     #[derive(Clone, Default)]
@@ -509,6 +627,10 @@ mod state_cell {
     #[derive(Clone, Default)]
     pub struct DebugStateCell(Rc<RefCell<Option<DebugState>>>);
 
+    /// An interior-mutable struct for collecting the accessibility tree.
+    #[derive(Clone)]
+    pub struct AccessTreeCell(Rc<RefCell<AccessTreeBuilder>>);
+
     #[derive(Clone)]
     pub struct StateCheckFn(Rc<dyn Fn(&WidgetState)>);
 
@@ -553,6 +675,37 @@ mod state_cell {
         }
     }
 
+    impl AccessTreeCell {
+        /// Create a cell whose tree starts out as just `root`.
+        pub(crate) fn new(root: AccessNode) -> Self {
+            AccessTreeCell(Rc::new(RefCell::new(AccessTreeBuilder::new(root))))
+        }
+
+        pub(crate) fn push(&self, node: AccessNode) {
+            self.0.borrow_mut().push(node)
+        }
+
+        pub(crate) fn pop(&self) {
+            self.0.borrow_mut().pop()
+        }
+
+        /// Take the collected tree.
+        ///
+        /// The cell should have no other owners by now; if it does, a copy of
+        /// the tree collected so far is returned.
+        pub(crate) fn finish(self) -> AccessNode {
+            debug_assert_eq!(Rc::strong_count(&self.0), 1, "AccessTreeCell still shared");
+            match Rc::try_unwrap(self.0) {
+                Ok(builder) => builder.into_inner().finish(),
+                Err(shared) => {
+                    error!("AccessTreeCell still shared, the accessibility tree may be incomplete");
+                    let builder = shared.borrow().clone();
+                    builder.finish()
+                }
+            }
+        }
+    }
+
     impl StateCheckFn {
         #[cfg(not(target_arch = "wasm32"))]
         pub(crate) fn new(f: impl Fn(&WidgetState) + 'static) -> Self {
@@ -590,6 +743,12 @@ mod state_cell {
         }
     }
 
+    impl std::fmt::Debug for AccessTreeCell {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "AccessTreeCell")
+        }
+    }
+
     impl std::fmt::Debug for StateCheckFn {
         fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
             write!(f, "StateCheckFn")
@@ -15,23 +15,57 @@
 //! Simple handle for submitting external events.
 
 use std::any::Any;
-use std::collections::VecDeque;
-use std::sync::{Arc, Mutex};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Wake, Waker},
+};
 
 use crate::command::SelectorSymbol;
 use crate::shell::IdleHandle;
 use crate::win_handler::EXT_EVENT_IDLE_TOKEN;
-use crate::{Command, Data, DruidHandler, Selector, Target, WindowId};
+use crate::{Data, EventCtx, Selector, SingleUse, Target, WidgetId, WindowId};
+
+/// Sent to a widget when a task from [`EventCtx::spawn_async`] is done,
+/// with the output of the task.
+pub(crate) const TASK_FINISHED: Selector<(TaskToken, SingleUse<Box<dyn Any + Send>>)> =
+    Selector::new("druid-builtin.task-finished");
 
 pub(crate) type ExtCommand = (SelectorSymbol, Box<dyn Any + Send>, Target);
 
+/// A callback from [`ExtEventSink::add_idle_callback`]; it is given the root
+/// data, and checks its type itself.
+pub(crate) type ExtCallback = Box<dyn FnOnce(&mut dyn Any) + Send>;
+
+/// The callback for the output of a task, for a widget with data `T`.
+pub(crate) type TaskCallback<T> = dyn FnOnce(&mut EventCtx, &mut T, Box<dyn Any + Send>);
+
+/// Something that has been sent to the application from another thread.
+pub(crate) enum ExtEvent {
+    Command(ExtCommand),
+    Callback(ExtCallback),
+}
+
 /// A thing that can move into other threads and be used to submit commands back
 /// to the running application.
 ///
+/// Everything submitted through the sink is handled on the UI thread, in the
+/// order it was submitted. While the application has no open window, for
+/// instance before the first one is shown, or when only a tray icon is left,
+/// submissions are kept until a window is opened. Once the application has
+/// exited, submitting fails with an [`ExtEventError`].
+///
 /// This API is preliminary, and may be changed or removed without warning.
 #[derive(Clone)]
 pub struct ExtEventSink {
-    queue: Arc<Mutex<VecDeque<ExtCommand>>>,
+    queue: Weak<Mutex<VecDeque<ExtEvent>>>,
     handle: Arc<Mutex<Option<IdleHandle>>>,
 }
 
@@ -40,7 +74,10 @@ pub struct ExtEventSink {
 #[derive(Default)]
 pub(crate) struct ExtEventHost {
     /// A shared queue of items that have been sent to us.
-    queue: Arc<Mutex<VecDeque<ExtCommand>>>,
+    ///
+    /// The sinks only have weak references, so that they can tell when the
+    /// application has gone away.
+    queue: Arc<Mutex<VecDeque<ExtEvent>>>,
     /// This doesn't exist when the app starts and it can go away if a window closes, so we keep a
     /// reference here and can update it when needed. Note that this reference is shared with all
     /// `ExtEventSink`s, so that we can update them too.
@@ -50,6 +87,27 @@ pub(crate) struct ExtEventHost {
     pub(crate) handle_window_id: Option<WindowId>,
 }
 
+/// A token that identifies a task started with [`EventCtx::spawn_async`].
+///
+/// It can be passed to [`EventCtx::cancel_task`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskToken(u64);
+
+/// The tasks a widget has started, and the callbacks for their output.
+///
+/// Copies of a [`WidgetState`] share their tasks; the tasks are cancelled
+/// when the last copy is dropped, which is when the widget is removed.
+///
+/// [`WidgetState`]: crate::WidgetState
+#[derive(Clone, Default)]
+pub(crate) struct PendingTasks(Rc<RefCell<HashMap<TaskToken, PendingTask>>>);
+
+struct PendingTask {
+    callback: Box<dyn Any>,
+    cancelled: Arc<AtomicBool>,
+    thread: thread::Thread,
+}
+
 /// An error that occurs if an external event cannot be submitted.
 /// This probably means that the application has gone away.
 #[derive(Debug, Clone)]
@@ -62,7 +120,7 @@ impl ExtEventHost {
 
     pub(crate) fn make_sink(&self) -> ExtEventSink {
         ExtEventSink {
-            queue: self.queue.clone(),
+            queue: Arc::downgrade(&self.queue),
             handle: self.handle.clone(),
         }
     }
@@ -76,12 +134,74 @@ impl ExtEventHost {
         !self.queue.lock().unwrap().is_empty()
     }
 
-    pub(crate) fn recv(&mut self) -> Option<Command> {
-        self.queue
-            .lock()
-            .unwrap()
-            .pop_front()
-            .map(|(selector, payload, target)| Command::from_ext(selector, payload, target))
+    pub(crate) fn recv(&mut self) -> Option<ExtEvent> {
+        self.queue.lock().unwrap().pop_front()
+    }
+}
+
+impl TaskToken {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn next() -> TaskToken {
+        static TASK_COUNTER: AtomicU64 = AtomicU64::new(0);
+        TaskToken(TASK_COUNTER.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl PendingTasks {
+    /// Run `future` on a new thread, and keep `callback` until its output is
+    /// sent back to `widget`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn spawn<T: 'static, R: Send + 'static>(
+        &self,
+        widget: WidgetId,
+        sink: ExtEventSink,
+        future: impl Future<Output = R> + Send + 'static,
+        callback: impl FnOnce(&mut EventCtx, &mut T, R) + 'static,
+    ) -> TaskToken {
+        let token = TaskToken::next();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+        let thread = thread::spawn(move || {
+            if let Some(output) = block_on(future, &flag) {
+                let output: Box<dyn Any + Send> = Box::new(output);
+                // this fails if the application has exited, and then nobody
+                // is waiting for the output anymore.
+                let _ = sink.submit_command(
+                    TASK_FINISHED,
+                    Box::new((token, SingleUse::new(output))),
+                    Target::Widget(widget),
+                );
+            }
+        });
+        let callback: Box<TaskCallback<T>> = Box::new(move |ctx, data, output| {
+            if let Ok(output) = output.downcast::<R>() {
+                callback(ctx, data, *output)
+            }
+        });
+        let task = PendingTask {
+            callback: Box::new(callback),
+            cancelled,
+            thread: thread.thread().clone(),
+        };
+        self.0.borrow_mut().insert(token, task);
+        token
+    }
+
+    /// Stop waiting for the task, and return its callback.
+    ///
+    /// The callback is a `Box<TaskCallback<T>>`, for the `T` the task was
+    /// started with.
+    pub(crate) fn remove(&self, token: TaskToken) -> Option<Box<dyn Any>> {
+        let mut task = self.0.borrow_mut().remove(&token)?;
+        Some(std::mem::replace(&mut task.callback, Box::new(())))
+    }
+}
+
+impl Drop for PendingTask {
+    fn drop(&mut self) {
+        // wake the thread, so that it sees that it can stop
+        self.cancelled.store(true, Ordering::Release);
+        self.thread.unpark();
     }
 }
 
@@ -106,17 +226,12 @@ impl ExtEventSink {
         payload: impl Into<Box<T>>,
         target: impl Into<Target>,
     ) -> Result<(), ExtEventError> {
-        let target = target.into();
-        let payload = payload.into();
-        if let Some(handle) = self.handle.lock().unwrap().as_mut() {
-            handle.schedule_idle(EXT_EVENT_IDLE_TOKEN);
-        }
-        self.queue.lock().map_err(|_| ExtEventError)?.push_back((
+        let payload: Box<dyn Any + Send> = payload.into();
+        self.submit(ExtEvent::Command((
             selector.symbol(),
             payload,
-            target,
-        ));
-        Ok(())
+            target.into(),
+        )))
     }
 
     /// Schedule an idle callback.
@@ -124,25 +239,107 @@ impl ExtEventSink {
     /// `T` must be the application's root `Data` type (the type provided to [`AppLauncher::launch`]).
     ///
     /// Add an idle callback, which is called (once) when the message loop
-    /// is empty. The idle callback will be run from the main UI thread.
+    /// is empty. The idle callback will be run from the main UI thread,
+    /// and can change the application data; the widgets are updated
+    /// afterwards, as if the change had been made by an event.
     ///
     /// Note: the name "idle" suggests that it will be scheduled with a lower
     /// priority than other UI events, but that's not necessarily the case.
     ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let sink: druid::ExtEventSink = unimplemented!();
+    /// std::thread::spawn(move || {
+    ///     let count = 42; // something slow
+    ///     sink.add_idle_callback(move |data: &mut u32| *data = count)
+    ///         .expect("the application has exited");
+    /// });
+    /// ```
+    ///
     /// [`AppLauncher::launch`]: crate::AppLauncher::launch
-    pub fn add_idle_callback<T: 'static + Data>(&self, cb: impl FnOnce(&mut T) + Send + 'static) {
-        let mut handle = self.handle.lock().unwrap();
-        if let Some(handle) = handle.as_mut() {
-            handle.add_idle(|win_handler| {
-                if let Some(win_handler) = win_handler.as_any().downcast_mut::<DruidHandler<T>>() {
-                    win_handler.app_state.handle_idle_callback(cb);
-                } else {
-                    debug_panic!(
-                        "{} is not the type of root data",
-                        std::any::type_name::<T>()
-                    );
-                }
-            });
+    pub fn add_idle_callback<T: 'static + Data>(
+        &self,
+        cb: impl FnOnce(&mut T) + Send + 'static,
+    ) -> Result<(), ExtEventError> {
+        self.submit(ExtEvent::Callback(Box::new(
+            move |data: &mut dyn Any| match data.downcast_mut::<T>() {
+                Some(data) => cb(data),
+                None => debug_panic!(
+                    "{} is not the type of root data",
+                    std::any::type_name::<T>()
+                ),
+            },
+        )))
+    }
+
+    /// Run `future` on a new thread, and submit its output as a [`Command`]
+    /// with `selector` and `target` when it is done.
+    ///
+    /// The future is run by a minimal executor, which only waits for it to
+    /// wake it again. A future that needs an async runtime, such as `tokio`,
+    /// should be spawned on that runtime instead, and can then call
+    /// [`submit_command`] itself.
+    ///
+    /// The thread's result is the result of submitting the command.
+    ///
+    /// [`Command`]: crate::Command
+    /// [`submit_command`]: ExtEventSink::submit_command
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn_future<T: Any + Send>(
+        &self,
+        selector: Selector<T>,
+        target: impl Into<Target>,
+        future: impl Future<Output = T> + Send + 'static,
+    ) -> thread::JoinHandle<Result<(), ExtEventError>> {
+        let sink = self.clone();
+        let target = target.into();
+        thread::spawn(move || {
+            let output = block_on(future, &AtomicBool::new(false)).unwrap();
+            sink.submit_command(selector, Box::new(output), target)
+        })
+    }
+
+    fn submit(&self, event: ExtEvent) -> Result<(), ExtEventError> {
+        let queue = self.queue.upgrade().ok_or(ExtEventError)?;
+        queue.lock().map_err(|_| ExtEventError)?.push_back(event);
+        // Schedule the idle callback after queueing, so that it can't run
+        // before the event is there. Without a window to wake, the event will
+        // be handled when the next window connects.
+        if let Some(handle) = self.handle.lock().unwrap().as_mut() {
+            handle.schedule_idle(EXT_EVENT_IDLE_TOKEN);
+        }
+        Ok(())
+    }
+}
+
+/// Wakes a thread that is waiting in [`block_on`].
+#[cfg(not(target_arch = "wasm32"))]
+struct ThreadWaker(thread::Thread);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run `future` to completion on the current thread, unless `cancelled` is
+/// set first.
+///
+/// Whoever sets `cancelled` should unpark the thread afterwards.
+#[cfg(not(target_arch = "wasm32"))]
+fn block_on<F: Future>(future: F, cancelled: &AtomicBool) -> Option<F::Output> {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if cancelled.load(Ordering::Acquire) {
+            return None;
+        }
+        match Pin::as_mut(&mut future).poll(&mut cx) {
+            Poll::Ready(output) => return Some(output),
+            Poll::Pending => thread::park(),
         }
     }
 }
@@ -154,3 +351,112 @@ impl std::fmt::Display for ExtEventError {
 }
 
 impl std::error::Error for ExtEventError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::{Command, Event};
+    use std::time::{Duration, Instant};
+
+    const VALUE: Selector<u32> = Selector::new("druid-test.ext-event-value");
+    const SPAWN: Selector = Selector::new("druid-test.ext-event-spawn");
+    const CANCEL: Selector = Selector::new("druid-test.ext-event-cancel");
+
+    /// A future that never finishes, and sets a flag when it is dropped.
+    struct Forever(Arc<AtomicBool>);
+
+    impl Future for Forever {
+        type Output = u32;
+
+        fn poll(self: Pin<&mut Self>, _: &mut Context) -> Poll<u32> {
+            Poll::Pending
+        }
+    }
+
+    impl Drop for Forever {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::Release);
+        }
+    }
+
+    fn wait_for(mut done: impl FnMut() -> bool) {
+        let start = Instant::now();
+        while !done() {
+            assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn recv_command(host: &mut ExtEventHost) -> Option<Command> {
+        match host.recv()? {
+            ExtEvent::Command((selector, payload, target)) => {
+                Some(Command::from_ext(selector, payload, target))
+            }
+            ExtEvent::Callback(_) => panic!("expected a command"),
+        }
+    }
+
+    #[test]
+    fn queued_in_order() {
+        let mut host = ExtEventHost::new();
+        let sink = host.make_sink();
+        sink.submit_command(VALUE, 1, Target::Global).unwrap();
+        sink.add_idle_callback(|data: &mut u32| *data += 1).unwrap();
+        sink.submit_command(VALUE, 2, Target::Global).unwrap();
+
+        assert_eq!(recv_command(&mut host).unwrap().get(VALUE), Some(&1));
+        let mut data = 41u32;
+        match host.recv() {
+            Some(ExtEvent::Callback(callback)) => callback(&mut data),
+            _ => panic!("expected a callback"),
+        }
+        assert_eq!(data, 42);
+        assert_eq!(recv_command(&mut host).unwrap().get(VALUE), Some(&2));
+        assert!(!host.has_pending_items());
+
+        drop(host);
+        assert!(sink.submit_command(VALUE, 3, Target::Global).is_err());
+        assert!(sink.add_idle_callback(|_: &mut u32| ()).is_err());
+    }
+
+    #[test]
+    fn future_output() {
+        let mut host = ExtEventHost::new();
+        let sink = host.make_sink();
+        let thread = sink.spawn_future(VALUE, Target::Global, async { 6 * 7 });
+        assert!(thread.join().unwrap().is_ok());
+        assert_eq!(recv_command(&mut host).unwrap().get(VALUE), Some(&42));
+    }
+
+    #[test]
+    fn spawn_async() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let forever = dropped.clone();
+        let widget = ModularWidget::new(None).event_fn(move |task, ctx, event, _, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(SPAWN) {
+                    ctx.spawn_async(async { 6 * 7 }, |_, data: &mut u32, answer| *data = answer);
+                    *task = Some(
+                        ctx.spawn_async(Forever(forever.clone()), |_, data: &mut u32, _| *data = 0),
+                    );
+                } else if cmd.is(CANCEL) {
+                    assert!(ctx.cancel_task(task.take().unwrap()));
+                }
+            }
+        });
+
+        Harness::create_simple(0u32, widget, |harness| {
+            harness.send_initial_events();
+            harness.submit_command(SPAWN);
+            wait_for(|| {
+                harness.process_ext_events();
+                *harness.data() == 42
+            });
+            assert!(!dropped.load(Ordering::Acquire));
+            harness.submit_command(CANCEL);
+            wait_for(|| dropped.load(Ordering::Acquire));
+        });
+    }
+}
@@ -0,0 +1,208 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Keyboard shortcuts that submit commands.
+
+use crate::shell::{HotKey, IntoKey, RawMods};
+use crate::{Command, KeyEvent};
+
+/// A set of keyboard shortcuts, each of which submits a [`Command`].
+///
+/// A `KeyMap` can be set for the whole application with
+/// [`AppLauncher::keymap`], and for a single window with
+/// [`WindowDesc::keymap`]. The application's `KeyMap` can be changed while the
+/// application is running, with [`DelegateCtx::keymap_mut`].
+///
+/// # Dispatch
+///
+/// A key press is first given to the [`AppDelegate`] and then to the widgets
+/// in the window. Only if no widget handled it are the shortcuts looked at,
+/// in this order:
+///
+/// 1. the hotkeys of the enabled items in the window's menu;
+/// 2. the window's `KeyMap`;
+/// 3. the application's `KeyMap`.
+///
+/// The first matching shortcut wins, so a menu item always takes precedence
+/// over a binding with the same hotkey, and a window binding overrides an
+/// application binding. Key presses that insert text into a focused text
+/// field never trigger shortcuts.
+///
+/// The command of the matching binding is submitted like any other command.
+/// If it has no explicit [`Target`], it is sent to the window in which the key
+/// was pressed.
+///
+/// # Examples
+///
+/// ```
+/// use druid::{commands, KeyMap, Selector, SysMods};
+///
+/// const TOGGLE_SIDEBAR: Selector = Selector::new("my-app.toggle-sidebar");
+///
+/// let keymap = KeyMap::new()
+///     .with_binding(SysMods::Cmd, "b", TOGGLE_SIDEBAR)
+///     .with_binding(SysMods::CmdShift, "w", commands::CLOSE_ALL_WINDOWS);
+/// ```
+///
+/// [`AppLauncher::keymap`]: crate::AppLauncher::keymap
+/// [`WindowDesc::keymap`]: crate::WindowDesc::keymap
+/// [`DelegateCtx::keymap_mut`]: crate::DelegateCtx::keymap_mut
+/// [`AppDelegate`]: crate::AppDelegate
+/// [`Target`]: crate::Target
+#[derive(Debug, Clone, Default)]
+pub struct KeyMap {
+    bindings: Vec<(HotKey, Command)>,
+}
+
+impl KeyMap {
+    /// Create a new, empty `KeyMap`.
+    pub fn new() -> Self {
+        KeyMap::default()
+    }
+
+    /// Builder-style method for adding a binding.
+    ///
+    /// See [`add_binding`] for more details.
+    ///
+    /// [`add_binding`]: KeyMap::add_binding
+    pub fn with_binding(
+        mut self,
+        mods: impl Into<Option<RawMods>>,
+        key: impl IntoKey,
+        cmd: impl Into<Command>,
+    ) -> Self {
+        self.add_binding(mods, key, cmd);
+        self
+    }
+
+    /// Bind the hotkey described by `mods` and `key` to `cmd`.
+    ///
+    /// The arguments describing the hotkey are the same as for
+    /// [`HotKey::new`]; in particular, [`SysMods`] can be used for shortcuts
+    /// that use the Command key on macOS and the Ctrl key elsewhere.
+    ///
+    /// If the hotkey was already bound, the old binding is replaced, and its
+    /// command is returned.
+    ///
+    /// [`HotKey::new`]: crate::HotKey::new
+    /// [`SysMods`]: crate::SysMods
+    pub fn add_binding(
+        &mut self,
+        mods: impl Into<Option<RawMods>>,
+        key: impl IntoKey,
+        cmd: impl Into<Command>,
+    ) -> Option<Command> {
+        self.insert(HotKey::new(mods, key), cmd.into())
+    }
+
+    /// Bind `hotkey` to `cmd`, returning the command it was bound to before.
+    pub(crate) fn insert(&mut self, hotkey: HotKey, cmd: Command) -> Option<Command> {
+        match self.bindings.iter_mut().find(|(hk, _)| *hk == hotkey) {
+            Some((_, old)) => Some(std::mem::replace(old, cmd)),
+            None => {
+                self.bindings.push((hotkey, cmd));
+                None
+            }
+        }
+    }
+
+    /// Remove the binding of the hotkey described by `mods` and `key`,
+    /// returning its command if it was bound.
+    pub fn remove_binding(
+        &mut self,
+        mods: impl Into<Option<RawMods>>,
+        key: impl IntoKey,
+    ) -> Option<Command> {
+        let hotkey = HotKey::new(mods, key);
+        let idx = self.bindings.iter().position(|(hk, _)| *hk == hotkey)?;
+        Some(self.bindings.remove(idx).1)
+    }
+
+    /// Returns the command bound to the hotkey matching `event`, if any.
+    pub fn command_for(&self, event: &KeyEvent) -> Option<&Command> {
+        self.bindings
+            .iter()
+            .find(|(hk, _)| hk.matches(event))
+            .map(|(_, cmd)| cmd)
+    }
+
+    /// Returns an iterator over the bindings, in the order they were added.
+    pub fn bindings(&self) -> impl Iterator<Item = (&HotKey, &Command)> {
+        self.bindings.iter().map(|(hk, cmd)| (hk, cmd))
+    }
+
+    /// Returns `true` if there are no bindings.
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{Record, Recording, TestWidgetExt};
+    use crate::widget::SizedBox;
+    use crate::{Event, KbKey, Modifiers, Selector, SysMods};
+    use test_log::test;
+
+    const FIRST: Selector = Selector::new("druid-test.keymap-first");
+    const SECOND: Selector = Selector::new("druid-test.keymap-second");
+
+    fn cmd_key(key: &str) -> KeyEvent {
+        let mods = if cfg!(target_os = "macos") {
+            Modifiers::META
+        } else {
+            Modifiers::CONTROL
+        };
+        KeyEvent::for_test(mods, KbKey::Character(key.into()))
+    }
+
+    #[test]
+    fn bindings_are_replaced_and_removed() {
+        let mut keymap = KeyMap::new().with_binding(SysMods::Cmd, "k", FIRST);
+        assert!(keymap.command_for(&cmd_key("k")).unwrap().is(FIRST));
+        assert!(keymap
+            .command_for(&KeyEvent::for_test(Modifiers::default(), "k"))
+            .is_none());
+
+        let old = keymap.add_binding(SysMods::Cmd, "k", SECOND);
+        assert!(old.unwrap().is(FIRST));
+        assert!(keymap.command_for(&cmd_key("k")).unwrap().is(SECOND));
+        assert_eq!(keymap.bindings().count(), 1);
+
+        assert!(keymap.remove_binding(SysMods::Cmd, "k").unwrap().is(SECOND));
+        assert!(keymap.command_for(&cmd_key("k")).is_none());
+        assert!(keymap.is_empty());
+    }
+
+    #[test]
+    fn unhandled_key_submits_window_binding() {
+        let recording = Recording::default();
+        let widget = SizedBox::empty().record(&recording);
+
+        Harness::create_simple((), widget, |harness| {
+            harness.window_mut().keymap = KeyMap::new().with_binding(SysMods::Cmd, "k", FIRST);
+            harness.send_initial_events();
+            recording.clear();
+
+            harness.event(Event::KeyDown(cmd_key("k")));
+            // The command is delivered with the next event.
+            harness.submit_command(Selector::NOOP);
+            assert!(recording
+                .drain()
+                .any(|r| matches!(r, Record::E(Event::Command(cmd)) if cmd.is(FIRST))));
+        });
+    }
+}
@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
 use std::marker::PhantomData;
 use std::ops;
 use std::sync::Arc;
@@ -110,6 +113,88 @@ pub trait LensExt<A: ?Sized, B: ?Sized>: Lens<A, B> {
         self.then(Map::new(get, put))
     }
 
+    /// Combine a `Lens<A, B>` with a conversion from `B` to `C`, and the
+    /// conversion back.
+    ///
+    /// Unlike with [`map`], the `C` must represent the whole `B`: when the
+    /// child changes it, the `B` is replaced with `backward` of the new `C`.
+    /// Nothing is written if the `C` didn't change, according to [`Data`].
+    ///
+    /// The two functions must be inverses of each other, for the values that
+    /// can happen: `forward(&backward(&c))` should be the same as `c`, or edits
+    /// won't round trip, and a widget could see its value change when it
+    /// didn't change it. Both should be cheap and free of side effects, as
+    /// `forward` runs on every access.
+    ///
+    /// ```
+    /// # use druid::*;
+    /// // show a duration in seconds as a number of minutes
+    /// let minutes = lens::Identity.bimap(|secs: &u64| *secs as f64 / 60.0, |mins| (mins * 60.0) as u64);
+    /// let mut secs = 90;
+    /// assert_eq!(minutes.get(&secs), 1.5);
+    /// minutes.put(&mut secs, 2.0);
+    /// assert_eq!(secs, 120);
+    /// ```
+    ///
+    /// [`map`]: LensExt::map
+    /// [`Data`]: crate::Data
+    fn bimap<Forward, Backward, C>(
+        self,
+        forward: Forward,
+        backward: Backward,
+    ) -> Then<Self, BiMap<Forward, Backward>, B>
+    where
+        B: Sized,
+        C: Data,
+        Forward: Fn(&B) -> C,
+        Backward: Fn(&C) -> B,
+        Self: Sized,
+    {
+        self.then(BiMap::new(forward, backward))
+    }
+
+    /// Expose the contents of an `Option`, or `C::default()` if it is `None`.
+    ///
+    /// Changes to a `None` are only written back if the value is different
+    /// from the default; see [`UnwrapOr`].
+    ///
+    /// ```
+    /// # use druid::*;
+    /// let lens = lens::Identity.or_default();
+    /// let mut name: Option<String> = None;
+    /// assert_eq!(lens.get(&name), "");
+    /// lens.put(&mut name, String::new());
+    /// assert_eq!(name, None);
+    /// lens.put(&mut name, "Ada".into());
+    /// assert_eq!(name.as_deref(), Some("Ada"));
+    /// ```
+    fn or_default<C>(self) -> Then<Self, UnwrapOr<C>, B>
+    where
+        C: Data + Default,
+        UnwrapOr<C>: Lens<B, C>,
+        Self: Sized,
+    {
+        self.then(UnwrapOr::new(C::default()))
+    }
+
+    /// Expose the contents of an `Option`, or `default` if it is `None`.
+    ///
+    /// Changes to a `None` are only written back if the value is different
+    /// from `default`; see [`UnwrapOr`].
+    ///
+    /// ```
+    /// # use druid::*;
+    /// assert_eq!(lens::Identity.unwrap_or(0.5).get(&None), 0.5);
+    /// ```
+    fn unwrap_or<C>(self, default: C) -> Then<Self, UnwrapOr<C>, B>
+    where
+        C: Data,
+        UnwrapOr<C>: Lens<B, C>,
+        Self: Sized,
+    {
+        self.then(UnwrapOr::new(default))
+    }
+
     /// Invoke a type's `Deref` impl
     ///
     /// ```
@@ -187,6 +272,46 @@ pub trait LensExt<A: ?Sized, B: ?Sized>: Lens<A, B> {
         self.then(Index::new(index))
     }
 
+    /// Access an element of a `Vec`, as an `Option` that is `None` if the
+    /// index is out of bounds.
+    ///
+    /// See [`TryIndex`] for how changes are written back.
+    ///
+    /// ```
+    /// # use druid::*;
+    /// assert_eq!(lens::Identity.try_index(4).get(&vec![0u32, 1, 2, 3]), None);
+    /// ```
+    fn try_index<C>(self, index: usize) -> Then<Self, TryIndex, B>
+    where
+        TryIndex: Lens<B, Option<C>>,
+        Self: Sized,
+    {
+        self.then(TryIndex::new(index))
+    }
+
+    /// Access the value of a key in a map, panicking if it is missing.
+    ///
+    /// See [`MapKey`].
+    fn map_key<K, C>(self, key: K) -> Then<Self, MapKey<K>, B>
+    where
+        MapKey<K>: Lens<B, C>,
+        Self: Sized,
+    {
+        self.then(MapKey::new(key))
+    }
+
+    /// Access the value of a key in a map, as an `Option` that is `None` if
+    /// the key is missing.
+    ///
+    /// See [`TryMapKey`].
+    fn try_map_key<K, C>(self, key: K) -> Then<Self, TryMapKey<K>, B>
+    where
+        TryMapKey<K>: Lens<B, Option<C>>,
+        Self: Sized,
+    {
+        self.then(TryMapKey::new(key))
+    }
+
     /// Adapt to operate on the contents of an `Arc` with efficient copy-on-write semantics
     ///
     /// ```
@@ -390,6 +515,92 @@ where
     }
 }
 
+/// `Lens` built from a conversion and its inverse.
+///
+/// See also `LensExt::bimap`, which documents the invariants the two
+/// functions must uphold.
+#[derive(Debug, Copy, Clone)]
+pub struct BiMap<Forward, Backward> {
+    forward: Forward,
+    backward: Backward,
+}
+
+impl<Forward, Backward> BiMap<Forward, Backward> {
+    /// Construct a mapping
+    ///
+    /// See also `LensExt::bimap`
+    pub fn new<A, B>(forward: Forward, backward: Backward) -> Self
+    where
+        Forward: Fn(&A) -> B,
+        Backward: Fn(&B) -> A,
+    {
+        Self { forward, backward }
+    }
+}
+
+impl<A, B, Forward, Backward> Lens<A, B> for BiMap<Forward, Backward>
+where
+    B: Data,
+    Forward: Fn(&A) -> B,
+    Backward: Fn(&B) -> A,
+{
+    fn with<V, F: FnOnce(&B) -> V>(&self, data: &A, f: F) -> V {
+        f(&(self.forward)(data))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut B) -> V>(&self, data: &mut A, f: F) -> V {
+        let old = (self.forward)(data);
+        let mut temp = old.clone();
+        let x = f(&mut temp);
+        if !old.same(&temp) {
+            *data = (self.backward)(&temp);
+        }
+        x
+    }
+}
+
+/// `Lens` exposing the contents of an `Option`, or a default value if it is
+/// `None`.
+///
+/// See also `LensExt::or_default` and `LensExt::unwrap_or`.
+///
+/// Changes are written back to a `Some`. If the `Option` is `None`, it stays
+/// `None` unless the value is changed to something different from the
+/// default, in which case it becomes `Some`.
+#[derive(Debug, Copy, Clone)]
+pub struct UnwrapOr<T> {
+    default: T,
+}
+
+impl<T> UnwrapOr<T> {
+    /// Construct a lens that shows `default` when the `Option` is `None`.
+    ///
+    /// See also `LensExt::unwrap_or`.
+    pub fn new(default: T) -> Self {
+        Self { default }
+    }
+}
+
+impl<T: Data> Lens<Option<T>, T> for UnwrapOr<T> {
+    fn with<V, F: FnOnce(&T) -> V>(&self, data: &Option<T>, f: F) -> V {
+        f(data.as_ref().unwrap_or(&self.default))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut T) -> V>(&self, data: &mut Option<T>, f: F) -> V {
+        match data {
+            Some(value) => f(value),
+            None => {
+                let mut temp = self.default.clone();
+                let x = f(&mut temp);
+                if !temp.same(&self.default) {
+                    *data = Some(temp);
+                }
+                x
+            }
+        }
+    }
+}
+
 /// `Lens` for invoking `Deref` and `DerefMut` on a type
 ///
 /// See also `LensExt::deref`.
@@ -458,6 +669,209 @@ where
     }
 }
 
+/// `Lens` for an element of a `Vec`, which may not exist.
+///
+/// The element is exposed as an `Option`, which is `None` if the index is
+/// out of bounds. Setting it to `Some` replaces an existing element, but
+/// elements can't be added or removed: the change is ignored if the index is
+/// out of bounds, or if the element is set to `None`.
+///
+/// This works on `im::Vector` as well, with the `im` feature.
+///
+/// See also `LensExt::try_index`, and [`Index`] for a lens that panics if the
+/// index is out of bounds.
+///
+/// # Examples
+///
+/// ```
+/// use druid::lens::TryIndex;
+/// use druid::LensExt;
+///
+/// let names = vec!["Ada".to_string(), "Grace".to_string()];
+/// assert_eq!(TryIndex::new(1).get(&names), Some("Grace".to_string()));
+/// assert_eq!(TryIndex::new(2).get(&names), None);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct TryIndex {
+    index: usize,
+}
+
+impl TryIndex {
+    /// Construct a lens that accesses a particular index, if it exists.
+    pub fn new(index: usize) -> Self {
+        Self { index }
+    }
+}
+
+impl<T: Data> Lens<Vec<T>, Option<T>> for TryIndex {
+    fn with<V, F: FnOnce(&Option<T>) -> V>(&self, data: &Vec<T>, f: F) -> V {
+        f(&data.get(self.index).cloned())
+    }
+    fn with_mut<V, F: FnOnce(&mut Option<T>) -> V>(&self, data: &mut Vec<T>, f: F) -> V {
+        let mut value = data.get(self.index).cloned();
+        let result = f(&mut value);
+        if let (Some(new), Some(old)) = (value, data.get_mut(self.index)) {
+            if !old.same(&new) {
+                *old = new;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(feature = "im")]
+impl<T: Data> Lens<im::Vector<T>, Option<T>> for TryIndex {
+    fn with<V, F: FnOnce(&Option<T>) -> V>(&self, data: &im::Vector<T>, f: F) -> V {
+        f(&data.get(self.index).cloned())
+    }
+    fn with_mut<V, F: FnOnce(&mut Option<T>) -> V>(&self, data: &mut im::Vector<T>, f: F) -> V {
+        let mut value = data.get(self.index).cloned();
+        let result = f(&mut value);
+        if let Some(new) = value {
+            let changed = matches!(data.get(self.index), Some(old) if !old.same(&new));
+            if changed {
+                data.set(self.index, new);
+            }
+        }
+        result
+    }
+}
+
+/// `Lens` for the value of a key in a map.
+///
+/// This works on `HashMap` and `BTreeMap`, and on `im::HashMap` and
+/// `im::OrdMap` with the `im` feature.
+///
+/// See also `LensExt::map_key`.
+///
+/// # Panics
+///
+/// The lens panics if the key is not in the map. Use [`TryMapKey`] to handle
+/// missing keys.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use druid::lens::MapKey;
+/// use druid::LensExt;
+///
+/// let mut ages = HashMap::new();
+/// ages.insert("Ada", 36);
+/// let lens = MapKey::new("Ada");
+/// lens.put(&mut ages, 37);
+/// assert_eq!(lens.get(&ages), 37);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct MapKey<K> {
+    key: K,
+}
+
+impl<K> MapKey<K> {
+    /// Construct a lens that accesses the value of a particular key.
+    pub fn new(key: K) -> Self {
+        Self { key }
+    }
+
+    fn missing(&self) -> !
+    where
+        K: Debug,
+    {
+        panic!("MapKey lens: the key {:?} is not in the map", self.key)
+    }
+}
+
+/// `Lens` for the value of a key in a map, which may not exist.
+///
+/// The value is exposed as an `Option`, which is `None` if the key is not in
+/// the map. Setting it to `Some` inserts the value, and setting it to `None`
+/// removes the key from the map.
+///
+/// This works on the same maps as [`MapKey`]. See also `LensExt::try_map_key`.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use druid::lens::TryMapKey;
+/// use druid::LensExt;
+///
+/// let mut ages = BTreeMap::new();
+/// let lens = TryMapKey::new("Grace");
+/// assert_eq!(lens.get(&ages), None);
+/// lens.put(&mut ages, Some(85));
+/// assert_eq!(ages.get("Grace"), Some(&85));
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct TryMapKey<K> {
+    key: K,
+}
+
+impl<K> TryMapKey<K> {
+    /// Construct a lens that accesses the value of a particular key, if it
+    /// exists.
+    pub fn new(key: K) -> Self {
+        Self { key }
+    }
+}
+
+/// Implements `MapKey` and `TryMapKey` for a map type.
+macro_rules! impl_map_key_lens {
+    ($map:ty, [$($bounds:tt)*], [$($generics:tt)*]) => {
+        impl<K, V, $($generics)*> Lens<$map, V> for MapKey<K>
+        where
+            K: Debug + $($bounds)*,
+            V: Data,
+        {
+            fn with<R, F: FnOnce(&V) -> R>(&self, data: &$map, f: F) -> R {
+                match data.get(&self.key) {
+                    Some(value) => f(value),
+                    None => self.missing(),
+                }
+            }
+            fn with_mut<R, F: FnOnce(&mut V) -> R>(&self, data: &mut $map, f: F) -> R {
+                match data.get_mut(&self.key) {
+                    Some(value) => f(value),
+                    None => self.missing(),
+                }
+            }
+        }
+
+        impl<K, V, $($generics)*> Lens<$map, Option<V>> for TryMapKey<K>
+        where
+            K: $($bounds)*,
+            V: Data,
+        {
+            fn with<R, F: FnOnce(&Option<V>) -> R>(&self, data: &$map, f: F) -> R {
+                f(&data.get(&self.key).cloned())
+            }
+            fn with_mut<R, F: FnOnce(&mut Option<V>) -> R>(&self, data: &mut $map, f: F) -> R {
+                let old = data.get(&self.key).cloned();
+                let mut value = old.clone();
+                let result = f(&mut value);
+                if !old.same(&value) {
+                    match value {
+                        Some(value) => {
+                            data.insert(self.key.clone(), value);
+                        }
+                        None => {
+                            data.remove(&self.key);
+                        }
+                    }
+                }
+                result
+            }
+        }
+    };
+}
+
+impl_map_key_lens!(HashMap<K, V, S>, [Clone + Eq + Hash], [S: BuildHasher]);
+impl_map_key_lens!(BTreeMap<K, V>, [Clone + Ord], []);
+#[cfg(feature = "im")]
+impl_map_key_lens!(im::HashMap<K, V, S>, [Clone + Eq + Hash], [S: BuildHasher]);
+#[cfg(feature = "im")]
+impl_map_key_lens!(im::OrdMap<K, V>, [Clone + Ord], []);
+
 /// The identity lens: the lens which does nothing, i.e. exposes exactly
 /// the original value.
 ///
@@ -52,6 +52,9 @@
 #[allow(clippy::module_inception)]
 #[macro_use]
 mod lens;
-pub use lens::{Constant, Deref, Field, Identity, InArc, Index, Map, Ref, Then, Unit};
+pub use lens::{
+    BiMap, Constant, Deref, Field, Identity, InArc, Index, Map, MapKey, Ref, Then, TryIndex,
+    TryMapKey, Unit, UnwrapOr,
+};
 #[doc(hidden)]
 pub use lens::{Lens, LensExt};
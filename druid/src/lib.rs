@@ -97,6 +97,8 @@
 //!          which is made available via the [`im` module].
 //! * `svg` - Scalable Vector Graphics for icons and other scalable images using the [`usvg` crate].
 //! * `image` - Bitmap image support using the [`image` crate].
+//! * `markdown` - A widget that renders Markdown, parsed with the [`pulldown-cmark` crate].
+//! * `regex` - Regular expression queries for [`text::FindQuery`], using the [`regex` crate].
 //! * `x11` - Work-in-progress X11 for Linux and OpenBSD backend instead of GTK.
 //!
 //! Features can be added with `cargo`. For example, in your `Cargo.toml`:
@@ -125,6 +127,8 @@
 //! [`im` module]: im/index.html
 //! [`usvg` crate]: https://crates.io/crates/usvg
 //! [`image` crate]: https://crates.io/crates/image
+//! [`pulldown-cmark` crate]: https://crates.io/crates/pulldown-cmark
+//! [`regex` crate]: https://crates.io/crates/regex
 
 #![deny(
     rustdoc::broken_intra_doc_links,
@@ -157,6 +161,8 @@ pub mod lens;
 #[macro_use]
 mod util;
 
+pub mod accessibility;
+pub mod animation;
 mod app;
 mod app_delegate;
 mod bloom;
@@ -167,18 +173,23 @@ mod core;
 mod data;
 pub mod debug_state;
 mod dialog;
+mod drag;
 pub mod env;
 mod event;
 mod ext_event;
+mod keymap;
 mod localization;
+mod memo;
 pub mod menu;
 mod mouse;
+mod persist;
 pub mod scroll_component;
 mod sub_window;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod tests;
 pub mod text;
 pub mod theme;
+mod tray;
 pub mod widget;
 mod win_handler;
 mod window;
@@ -192,10 +203,10 @@ pub use piet::{Color, ImageBuf, LinearGradient, RadialGradient, RenderContext, U
 pub use shell::image;
 pub use shell::keyboard_types;
 pub use shell::{
-    Application, Clipboard, ClipboardFormat, Code, Cursor, CursorDesc, Error as PlatformError,
-    FileInfo, FileSpec, FormatId, HotKey, KbKey, KeyEvent, Location, Modifiers, Monitor,
-    MouseButton, MouseButtons, RawMods, Region, Scalable, Scale, Screen, SysMods, TimerToken,
-    WindowHandle, WindowLevel, WindowState,
+    Application, Clipboard, ClipboardFormat, Code, ColorScheme, Cursor, CursorDesc,
+    Error as PlatformError, FileInfo, FileSpec, FormatId, HotKey, KbKey, KeyEvent, Location,
+    Modifiers, Monitor, MouseButton, MouseButtons, RawMods, Region, Scalable, Scale, Screen,
+    SysMods, TimerToken, WindowHandle, WindowIcon, WindowLevel, WindowState,
 };
 
 #[cfg(feature = "raw-win-handle")]
@@ -209,17 +220,22 @@ pub use command::{sys as commands, Command, Notification, Selector, SingleUse, T
 pub use contexts::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, UpdateCtx};
 pub use data::Data;
 pub use dialog::FileDialogOptions;
-pub use env::{Env, Key, KeyOrValue, Value, ValueType, ValueTypeError};
+pub use drag::{DragEvent, DragGhost, DragPayload};
+pub use env::{Env, EnvError, Key, KeyOrValue, Value, ValueType, ValueTypeError};
 pub use event::{Event, InternalEvent, InternalLifeCycle, LifeCycle};
-pub use ext_event::{ExtEventError, ExtEventSink};
+pub use ext_event::{ExtEventError, ExtEventSink, TaskToken};
+pub use keymap::KeyMap;
 pub use lens::{Lens, LensExt};
 pub use localization::LocalizedString;
-pub use menu::{sys as platform_menus, Menu, MenuItem};
-pub use mouse::MouseEvent;
+pub use memo::Memo;
+pub use menu::{sys as platform_menus, ContextMenu, Menu, MenuItem};
+pub use mouse::{FileDropEvent, MouseEvent};
+pub use persist::{PersistWindow, SavedState, WindowGeometry};
+pub use tray::TrayIcon;
 pub use util::Handled;
 pub use widget::{Widget, WidgetExt, WidgetId};
 pub use win_handler::DruidHandler;
-pub use window::{Window, WindowId};
+pub use window::{CaptureError, Window, WindowId};
 
 #[cfg(not(target_arch = "wasm32"))]
 pub(crate) use event::{DebugStateCell, StateCell, StateCheckFn};
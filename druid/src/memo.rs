@@ -0,0 +1,133 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Caching values derived from data.
+
+use crate::Data;
+
+type ComputeFn<In, Out> = dyn Fn(&In) -> Out;
+
+/// A cache for a value that is expensive to compute from some [`Data`].
+///
+/// `Memo` keeps the last input and the value computed from it, and only
+/// computes the value again when it is asked for it with an input that is
+/// not [`same`] as the last one. It is meant to be kept in a widget or a
+/// [`Controller`], for things like a filtered or sorted list that would
+/// otherwise be recomputed on every `update` or `paint`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::Memo;
+///
+/// let mut longest = Memo::new(|words: &Arc<Vec<String>>| {
+///     words.iter().map(|word| word.len()).max().unwrap_or(0)
+/// });
+///
+/// let words = Arc::new(vec!["apple".to_string(), "fig".to_string()]);
+/// assert_eq!(*longest.get(&words), 5);
+/// // this doesn't compute the value again, because the input is the same
+/// assert_eq!(*longest.get(&words.clone()), 5);
+/// ```
+///
+/// [`same`]: Data::same
+/// [`Controller`]: crate::widget::Controller
+pub struct Memo<In, Out> {
+    compute: Box<ComputeFn<In, Out>>,
+    cached: Option<(In, Out)>,
+}
+
+impl<In: Data, Out> Memo<In, Out> {
+    /// Create a `Memo` that computes its value with `compute`.
+    ///
+    /// Nothing is computed until the value is asked for.
+    pub fn new(compute: impl Fn(&In) -> Out + 'static) -> Self {
+        Memo {
+            compute: Box::new(compute),
+            cached: None,
+        }
+    }
+
+    /// The value for `input`.
+    ///
+    /// It is only computed if `input` is not [`same`] as the input of the
+    /// last call.
+    ///
+    /// [`same`]: Data::same
+    pub fn get(&mut self, input: &In) -> &Out {
+        if !self.is_cached(input) {
+            self.cached = Some((input.clone(), (self.compute)(input)));
+        }
+        &self.cached.as_ref().unwrap().1
+    }
+
+    /// The last computed value, if there is one, without checking whether it
+    /// is up to date.
+    pub fn last(&self) -> Option<&Out> {
+        self.cached.as_ref().map(|(_, out)| out)
+    }
+
+    /// Returns `true` if the value for `input` is cached.
+    pub fn is_cached(&self, input: &In) -> bool {
+        matches!(&self.cached, Some((cached, _)) if cached.same(input))
+    }
+
+    /// Forget the cached value, so that the next [`get`] computes it again.
+    ///
+    /// This is needed when the value depends on something besides the input.
+    ///
+    /// [`get`]: Memo::get
+    pub fn invalidate(&mut self) {
+        self.cached = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[test]
+    fn recomputes_when_input_changes() {
+        let count = Rc::new(Cell::new(0));
+        let count_2 = count.clone();
+        let mut sorted = Memo::new(move |items: &Arc<Vec<u32>>| {
+            count_2.set(count_2.get() + 1);
+            let mut items = items.to_vec();
+            items.sort_unstable();
+            items
+        });
+        assert_eq!(sorted.last(), None);
+
+        let items = Arc::new(vec![3, 1, 2]);
+        assert_eq!(sorted.get(&items), &[1, 2, 3]);
+        assert_eq!(sorted.get(&items.clone()), &[1, 2, 3]);
+        assert_eq!(count.get(), 1);
+        assert!(sorted.is_cached(&items));
+
+        // equal, but not the same
+        let copy = Arc::new(vec![3, 1, 2]);
+        assert!(!sorted.is_cached(&copy));
+        assert_eq!(sorted.get(&copy), &[1, 2, 3]);
+        assert_eq!(count.get(), 2);
+
+        sorted.invalidate();
+        assert_eq!(sorted.last(), None);
+        assert_eq!(sorted.get(&copy), &[1, 2, 3]);
+        assert_eq!(count.get(), 3);
+    }
+}
@@ -109,13 +109,15 @@
 //! [`WindowDesc::menu`]: crate::WindowDesc::menu
 //! [`Command`]: crate::Command
 
+use std::any::Any;
 use std::num::NonZeroU32;
 
+use crate::commands::SHOW_CONTEXT_MENU;
 use crate::core::CommandQueue;
 use crate::kurbo::Point;
 use crate::shell::{Counter, HotKey, IntoKey, Menu as PlatformMenu};
 use crate::widget::LabelText;
-use crate::{ArcStr, Command, Data, Env, Lens, RawMods, Target, WindowId};
+use crate::{ArcStr, Command, Data, Env, KeyEvent, Lens, RawMods, SingleUse, Target, WindowId};
 
 static COUNTER: Counter = Counter::new();
 
@@ -135,11 +137,37 @@ pub(crate) struct MenuManager<T> {
 }
 
 /// A menu displayed as a pop-over.
-pub(crate) struct ContextMenu<T> {
+///
+/// This converts into a [`SHOW_CONTEXT_MENU`] command that still has to be targeted at the
+/// window the menu should appear in:
+///
+/// ```no_run
+/// # use druid::{ContextMenu, Command, DelegateCtx, Menu, MenuItem, Point, WindowId};
+/// # fn f(ctx: &mut DelegateCtx, window_id: WindowId) {
+/// let menu = Menu::<u32>::empty().entry(MenuItem::new("Reset").on_activate(|_, n, _| *n = 0));
+/// ctx.submit_command(Command::from(ContextMenu::new(menu, Point::new(20., 20.))).to(window_id));
+/// # }
+/// ```
+///
+/// [`SHOW_CONTEXT_MENU`]: crate::commands::SHOW_CONTEXT_MENU
+pub struct ContextMenu<T> {
     pub(crate) menu: Menu<T>,
     pub(crate) location: Point,
 }
 
+impl<T> ContextMenu<T> {
+    /// Create a context menu to be shown at `location`, in window coordinates.
+    pub fn new(menu: Menu<T>, location: Point) -> ContextMenu<T> {
+        ContextMenu { menu, location }
+    }
+}
+
+impl<T: Any> From<ContextMenu<T>> for Command {
+    fn from(menu: ContextMenu<T>) -> Command {
+        SHOW_CONTEXT_MENU.with(SingleUse::new(Box::new(menu)))
+    }
+}
+
 impl<T: Data> MenuManager<T> {
     /// Create a new [`MenuManager`] for a title-bar menu.
     pub fn new(
@@ -163,6 +191,20 @@ impl<T: Data> MenuManager<T> {
         }
     }
 
+    /// Create a new [`MenuManager`] for the menu of a [`TrayIcon`].
+    ///
+    /// [`TrayIcon`]: crate::TrayIcon
+    pub(crate) fn new_for_tray(
+        build: impl FnMut(Option<WindowId>, &T, &Env) -> Menu<T> + 'static,
+    ) -> MenuManager<T> {
+        MenuManager {
+            build: Some(Box::new(build)),
+            popup: true,
+            old_data: None,
+            menu: None,
+        }
+    }
+
     /// If this platform always expects windows to have a menu by default, returns a menu.
     /// Otherwise, returns `None`.
     #[allow(unreachable_code)]
@@ -193,6 +235,28 @@ impl<T: Data> MenuManager<T> {
         }
     }
 
+    /// Called when a key press was not handled by any widget.
+    ///
+    /// If an enabled item has a hotkey matching the key press, the item is activated and this
+    /// returns `true`.
+    pub fn hotkey_event(
+        &mut self,
+        queue: &mut CommandQueue,
+        window: Option<WindowId>,
+        event: &KeyEvent,
+        data: &mut T,
+        env: &Env,
+    ) -> bool {
+        if let Some(m) = &mut self.menu {
+            if let Some(id) = m.hotkey_item(event, data, env) {
+                let mut ctx = MenuEventCtx { window, queue };
+                m.activate(&mut ctx, id, data, env);
+                return true;
+            }
+        }
+        false
+    }
+
     /// Build an initial menu from the application data.
     pub fn initialize(&mut self, window: Option<WindowId>, data: &T, env: &Env) -> PlatformMenu {
         if let Some(build) = &mut self.build {
@@ -351,6 +415,9 @@ trait MenuVisitor<T> {
     /// Called when the data is changed.
     fn update(&mut self, old_data: &T, data: &T, env: &Env) -> MenuUpdate;
 
+    /// Returns the id of an enabled item whose hotkey matches `event`, if there is one.
+    fn hotkey_item(&mut self, event: &KeyEvent, data: &T, env: &Env) -> Option<MenuItemId>;
+
     /// Called to refresh the menu.
     fn refresh(&mut self, ctx: &mut MenuBuildCtx, data: &T, env: &Env);
 }
@@ -396,6 +463,11 @@ impl<T: Data, U: Data, L: Lens<T, U>> MenuVisitor<T> for MenuLensWrap<L, U> {
         })
     }
 
+    fn hotkey_item(&mut self, event: &KeyEvent, data: &T, env: &Env) -> Option<MenuItemId> {
+        let inner = &mut self.inner;
+        self.lens.with(data, |u| inner.hotkey_item(event, u, env))
+    }
+
     fn refresh(&mut self, ctx: &mut MenuBuildCtx, data: &T, env: &Env) {
         let inner = &mut self.inner;
         self.lens.with(data, |u| inner.refresh(ctx, u, env))
@@ -640,8 +712,14 @@ impl<T: Data> MenuItem<T> {
 
     /// Provide a hotkey for activating this menu item.
     ///
+    /// The hotkey is shown next to the item, and pressing it activates the item even when the
+    /// menu is closed, as long as the item is enabled. Key presses that are handled by a widget
+    /// (for example a [`TextBox`] that has focus) don't activate the item.
+    ///
     /// This is equivalent to
     /// `self.dynamic_hotkey(move |_, _| Some(HotKey::new(mods, key))`
+    ///
+    /// [`TextBox`]: crate::widget::TextBox
     pub fn hotkey(self, mods: impl Into<Option<RawMods>>, key: impl IntoKey) -> Self {
         let hotkey = HotKey::new(mods, key);
         self.dynamic_hotkey(move |_, _| Some(hotkey.clone()))
@@ -705,7 +783,7 @@ impl<T: Data> MenuItem<T> {
                 .as_mut()
                 .map(|s| s(data, env))
                 .unwrap_or(false),
-            enabled: self.enabled.as_mut().map(|e| e(data, env)).unwrap_or(true),
+            enabled: self.is_enabled_for(data, env),
         };
         let ret = self.old_state.as_ref() != Some(&new_state);
         self.old_state = Some(new_state);
@@ -721,6 +799,11 @@ impl<T: Data> MenuItem<T> {
     fn is_enabled(&self) -> bool {
         self.old_state.as_ref().unwrap().enabled
     }
+
+    // Unlike `is_enabled`, this doesn't depend on the last resolved state.
+    fn is_enabled_for(&mut self, data: &T, env: &Env) -> bool {
+        self.enabled.as_mut().map(|e| e(data, env)).unwrap_or(true)
+    }
 }
 
 impl<T: Data> MenuVisitor<T> for Menu<T> {
@@ -749,6 +832,15 @@ impl<T: Data> MenuVisitor<T> for Menu<T> {
         ret
     }
 
+    fn hotkey_item(&mut self, event: &KeyEvent, data: &T, env: &Env) -> Option<MenuItemId> {
+        if !self.item.is_enabled_for(data, env) {
+            return None;
+        }
+        self.children
+            .iter_mut()
+            .find_map(|child| child.hotkey_item(event, data, env))
+    }
+
     fn refresh(&mut self, ctx: &mut MenuBuildCtx, data: &T, env: &Env) {
         self.item.resolve(data, env);
         let children = &mut self.children;
@@ -769,6 +861,10 @@ impl<T: Data> MenuVisitor<T> for MenuEntry<T> {
         self.inner.update(old_data, data, env)
     }
 
+    fn hotkey_item(&mut self, event: &KeyEvent, data: &T, env: &Env) -> Option<MenuItemId> {
+        self.inner.hotkey_item(event, data, env)
+    }
+
     fn refresh(&mut self, ctx: &mut MenuBuildCtx, data: &T, env: &Env) {
         self.inner.refresh(ctx, data, env);
     }
@@ -791,6 +887,18 @@ impl<T: Data> MenuVisitor<T> for MenuItem<T> {
         }
     }
 
+    fn hotkey_item(&mut self, event: &KeyEvent, data: &T, env: &Env) -> Option<MenuItemId> {
+        let matches = match &mut self.hotkey {
+            Some(hotkey) => matches!(hotkey(data, env), Some(h) if h.matches(event)),
+            None => false,
+        };
+        if matches && self.is_enabled_for(data, env) {
+            Some(self.id)
+        } else {
+            None
+        }
+    }
+
     fn refresh(&mut self, ctx: &mut MenuBuildCtx, data: &T, env: &Env) {
         self.resolve(data, env);
         let state = self.old_state.as_ref().unwrap();
@@ -810,6 +918,11 @@ impl<T: Data> MenuVisitor<T> for Separator {
     fn update(&mut self, _old_data: &T, _data: &T, _env: &Env) -> MenuUpdate {
         MenuUpdate::UpToDate
     }
+
+    fn hotkey_item(&mut self, _event: &KeyEvent, _data: &T, _env: &Env) -> Option<MenuItemId> {
+        None
+    }
+
     fn refresh(&mut self, ctx: &mut MenuBuildCtx, _data: &T, _env: &Env) {
         ctx.add_separator();
     }
@@ -838,3 +951,91 @@ impl MenuItemId {
         MenuItemId(NonZeroU32::new(id))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard_types::Key;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::TextBox;
+    use crate::{Event, KeyEvent, Modifiers, MouseButton, MouseEvent, WidgetExt, WidgetId};
+    use test_log::test;
+
+    fn key_down(mods: Modifiers, key: &str) -> Event {
+        Event::KeyDown(KeyEvent::for_test(mods, Key::Character(key.into())))
+    }
+
+    #[test]
+    fn hotkeys_activate_items() {
+        let menu = || {
+            Menu::empty().entry(
+                Menu::new("File")
+                    .entry(
+                        MenuItem::new("New")
+                            .hotkey(RawMods::Ctrl, "n")
+                            .on_activate(|_, data: &mut (String, u32), _| data.1 += 1),
+                    )
+                    .entry(
+                        MenuItem::new("Open")
+                            .hotkey(RawMods::Ctrl, "o")
+                            .on_activate(|_, data: &mut (String, u32), _| data.1 += 100)
+                            .enabled_if(|data: &(String, u32), _| data.1 > 1),
+                    )
+                    .entry(
+                        MenuItem::new("Next")
+                            .hotkey(None, "n")
+                            .on_activate(|_, data: &mut (String, u32), _| data.1 += 10),
+                    ),
+            )
+        };
+        let textbox_id = WidgetId::next();
+        let widget = TextBox::new()
+            .with_id(textbox_id)
+            .lens(lens!((String, u32), 0))
+            .center();
+
+        Harness::create_simple((String::new(), 0), widget, |harness| {
+            let mut manager = MenuManager::new(move |_, _, _| menu());
+            manager.initialize(None, harness.data(), &Env::empty());
+            harness.window_mut().menu = Some(manager);
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.event(key_down(Modifiers::CONTROL, "n"));
+            assert_eq!(harness.data().1, 1);
+            // "Open" is disabled until the count is above one.
+            harness.event(key_down(Modifiers::CONTROL, "o"));
+            assert_eq!(harness.data().1, 1);
+            harness.event(key_down(Modifiers::empty(), "n"));
+            assert_eq!(harness.data().1, 11);
+            harness.event(key_down(Modifiers::CONTROL, "o"));
+            assert_eq!(harness.data().1, 111);
+
+            // While the text box has focus, plain keys are text input.
+            let center = harness.get_state(textbox_id).window_origin() + (5.0, 5.0);
+            harness.event(Event::MouseDown(MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse(center)
+            }));
+            assert_eq!(harness.window().focus, Some(textbox_id));
+            harness.event(key_down(Modifiers::empty(), "n"));
+            assert_eq!(harness.data().1, 111);
+            harness.event(key_down(Modifiers::CONTROL, "n"));
+            assert_eq!(harness.data().1, 112);
+        });
+    }
+
+    #[test]
+    fn context_menu_command() {
+        let window = WindowId::next();
+        let menu = ContextMenu::new(Menu::<u32>::empty(), Point::new(10.0, 20.0));
+        let cmd = Command::from(menu).to(window);
+        assert_eq!(cmd.target(), Target::Window(window));
+
+        let payload = cmd.get(SHOW_CONTEXT_MENU).unwrap().take().unwrap();
+        let menu = payload.downcast::<ContextMenu<u32>>().unwrap();
+        assert_eq!(menu.location, Point::new(10.0, 20.0));
+    }
+}
@@ -14,8 +14,10 @@
 
 //! The mousey bits
 
+use std::path::PathBuf;
+
 use crate::kurbo::{Point, Vec2};
-use crate::{Cursor, Data, Modifiers, MouseButton, MouseButtons};
+use crate::{Cursor, Data, FileSpec, Modifiers, MouseButton, MouseButtons};
 
 /// The state of the mouse for a click, mouse-up, move, or wheel event.
 ///
@@ -95,6 +97,51 @@ impl From<druid_shell::MouseEvent> for MouseEvent {
     }
 }
 
+/// Files that are dragged over a window from another application, or that
+/// were dropped onto it.
+///
+/// This is sent with [`Event::FileDragMove`] and [`Event::DropFiles`]. Like a
+/// [`MouseEvent`], it is delivered to the widgets under the pointer, and `pos`
+/// is in the coordinate space of the receiver: the pointer is within the
+/// bounds of a widget if `ctx.size().to_rect().contains(event.pos)`.
+///
+/// [`Event::FileDragMove`]: crate::Event::FileDragMove
+/// [`Event::DropFiles`]: crate::Event::DropFiles
+#[derive(Debug, Clone)]
+pub struct FileDropEvent {
+    /// The position of the pointer in the coordinate space of the receiver.
+    pub pos: Point,
+    /// The position of the pointer in the coordinate space of the window.
+    pub window_pos: Point,
+    /// The paths of the dragged files.
+    pub paths: Vec<PathBuf>,
+}
+
+impl FileDropEvent {
+    /// Returns `true` if there are files, and all of them match one of `specs`.
+    ///
+    /// This is a convenient way for a widget to decide whether it accepts
+    /// the files.
+    pub fn matches(&self, specs: &[FileSpec]) -> bool {
+        !self.paths.is_empty()
+            && self
+                .paths
+                .iter()
+                .all(|path| specs.iter().any(|spec| spec.matches(path)))
+    }
+}
+
+impl From<druid_shell::FileDropEvent> for FileDropEvent {
+    fn from(src: druid_shell::FileDropEvent) -> FileDropEvent {
+        let druid_shell::FileDropEvent { pos, paths } = src;
+        FileDropEvent {
+            pos,
+            window_pos: pos,
+            paths,
+        }
+    }
+}
+
 impl Data for Cursor {
     fn same(&self, other: &Cursor) -> bool {
         self == other
@@ -0,0 +1,374 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Saving window geometry and application state across runs.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tracing::{trace, warn};
+
+use crate::widget::Controller;
+use crate::{
+    Data, Env, Event, EventCtx, Point, Size, TimerToken, UpdateCtx, Widget, WindowDesc, WindowState,
+};
+
+/// The time [`PersistWindow`] waits after a change before saving, by default.
+const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+
+/// The first line of a saved state file.
+const HEADER: &str = "druid-saved-state 1";
+
+type SaveDataFn<T> = dyn Fn(&T) -> String;
+
+/// The position, size and state of a window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    /// The position of the window, in virtual screen coordinates.
+    pub position: Point,
+    /// The size of the window.
+    pub size: Size,
+    /// Whether the window is maximized.
+    ///
+    /// A minimized window is saved as [`WindowState::Restored`], so that
+    /// the application doesn't start out of sight.
+    pub state: WindowState,
+}
+
+/// What [`PersistWindow`] saves: the geometry of a window, and optionally
+/// some application state.
+///
+/// The application state is a string, in whatever format the application
+/// likes; it is only stored and handed back.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SavedState {
+    /// The geometry of the window, when it was last saved.
+    pub window: Option<WindowGeometry>,
+    /// The application state, as returned by the closure passed to
+    /// [`PersistWindow::with_data`].
+    pub data: Option<String>,
+}
+
+/// A [`Controller`] that saves the geometry of its window, and optionally
+/// the application data, to a file.
+///
+/// It saves shortly after the window has been resized or the data has
+/// changed, so that a burst of changes only causes one write, and again when
+/// the window is closed; that is right before [`AppDelegate::window_removed`]
+/// is called. It should wrap the root widget of the window.
+///
+/// On the next run, [`SavedState::load`] reads the file back, and
+/// [`SavedState::apply_to_window`] gives the window its old geometry. A
+/// missing or corrupt file gives the default `SavedState`, so the window
+/// and data just start out as they would without it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use druid::widget::Label;
+/// use druid::{AppLauncher, PersistWindow, SavedState, WidgetExt, WindowDesc};
+///
+/// let path = "window-state.txt";
+/// let saved = SavedState::load(path);
+/// let ui = Label::new(|data: &String, _: &_| data.clone())
+///     .controller(PersistWindow::new(path).with_data(|data: &String| data.clone()));
+/// let window = saved.apply_to_window(WindowDesc::new(ui));
+/// let data = saved.data.unwrap_or_else(|| "Hello".to_string());
+/// AppLauncher::with_window(window).launch(data).unwrap();
+/// ```
+///
+/// [`Controller`]: crate::widget::Controller
+/// [`AppDelegate::window_removed`]: crate::AppDelegate::window_removed
+pub struct PersistWindow<T> {
+    path: PathBuf,
+    delay: Duration,
+    save_data: Option<Box<SaveDataFn<T>>>,
+    timer: TimerToken,
+    geometry: Option<WindowGeometry>,
+}
+
+impl SavedState {
+    /// Read the state saved at `path`.
+    ///
+    /// If the file doesn't exist or can't be read, this returns the default
+    /// `SavedState`, without a window geometry or data.
+    pub fn load(path: impl AsRef<Path>) -> SavedState {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(text) => SavedState::parse(&text).unwrap_or_else(|| {
+                warn!("ignoring corrupt saved state in {}", path.display());
+                SavedState::default()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => SavedState::default(),
+            Err(err) => {
+                warn!(
+                    "failed to read saved state from {}: {}",
+                    path.display(),
+                    err
+                );
+                SavedState::default()
+            }
+        }
+    }
+
+    /// Write the state to `path`.
+    ///
+    /// The state is written to a temporary file first, which then replaces
+    /// the old one, so that a crash doesn't leave a half-written file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        fs::write(&temp, self.to_string())?;
+        fs::rename(&temp, path)
+    }
+
+    /// Parse the contents of a saved state file.
+    ///
+    /// Returns `None` if `text` is not a saved state.
+    pub fn parse(text: &str) -> Option<SavedState> {
+        let mut lines = text.split_inclusive('\n');
+        if lines.next()?.trim_end() != HEADER {
+            return None;
+        }
+        let mut state = SavedState::default();
+        let (mut position, mut size, mut window_state) = (None, None, WindowState::Restored);
+        for line in &mut lines {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("position") => position = Some(Point::from(parse_pair(&mut words)?)),
+                Some("size") => size = Some(Size::from(parse_pair(&mut words)?)),
+                Some("state") => {
+                    window_state = match words.next()? {
+                        "maximized" => WindowState::Maximized,
+                        "restored" => WindowState::Restored,
+                        _ => return None,
+                    }
+                }
+                Some("data") => {
+                    state.data = Some(lines.collect());
+                    break;
+                }
+                // newer versions may save more
+                _ => (),
+            }
+        }
+        if let (Some(position), Some(size)) = (position, size) {
+            state.window = Some(WindowGeometry {
+                position,
+                size,
+                state: window_state,
+            });
+        }
+        Some(state)
+    }
+
+    /// Give `window` the saved geometry, if there is one.
+    pub fn apply_to_window<T: Data>(&self, window: WindowDesc<T>) -> WindowDesc<T> {
+        match self.window {
+            Some(geometry) => window
+                .window_size(geometry.size)
+                .set_position(geometry.position)
+                .set_window_state(geometry.state),
+            None => window,
+        }
+    }
+}
+
+/// The next two words, as numbers.
+fn parse_pair<'a>(words: &mut impl Iterator<Item = &'a str>) -> Option<(f64, f64)> {
+    let x = words.next()?.parse().ok()?;
+    let y = words.next()?.parse().ok()?;
+    Some((x, y))
+}
+
+impl fmt::Display for SavedState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        if let Some(geometry) = &self.window {
+            let state = match geometry.state {
+                WindowState::Maximized => "maximized",
+                WindowState::Minimized | WindowState::Restored => "restored",
+            };
+            writeln!(
+                f,
+                "position {} {}",
+                geometry.position.x, geometry.position.y
+            )?;
+            writeln!(f, "size {} {}", geometry.size.width, geometry.size.height)?;
+            writeln!(f, "state {}", state)?;
+        }
+        if let Some(data) = &self.data {
+            writeln!(f, "data")?;
+            write!(f, "{}", data)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> PersistWindow<T> {
+    /// Create a controller that saves the window geometry to `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        PersistWindow {
+            path: path.into(),
+            delay: DEFAULT_DELAY,
+            save_data: None,
+            timer: TimerToken::INVALID,
+            geometry: None,
+        }
+    }
+
+    /// Builder-style method to also save the application data.
+    ///
+    /// `save` turns the data into a string, which [`SavedState::load`]
+    /// returns as [`SavedState::data`] on the next run.
+    pub fn with_data(mut self, save: impl Fn(&T) -> String + 'static) -> Self {
+        self.save_data = Some(Box::new(save));
+        self
+    }
+
+    /// Builder-style method to set how long to wait after a change before
+    /// saving.
+    ///
+    /// The default is half a second.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// The path the state is saved to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn save(&mut self, ctx: &mut EventCtx, data: &T) {
+        self.timer = TimerToken::INVALID;
+        let handle = ctx.window();
+        // a minimized window has no useful position, so keep the last one
+        if handle.get_window_state() != WindowState::Minimized {
+            self.geometry = Some(WindowGeometry {
+                position: handle.get_position(),
+                size: handle.get_size(),
+                state: handle.get_window_state(),
+            });
+        }
+        let state = SavedState {
+            window: self.geometry,
+            data: self.save_data.as_ref().map(|save| save(data)),
+        };
+        trace!("saving window state to {}", self.path.display());
+        if let Err(err) = state.save(&self.path) {
+            warn!(
+                "failed to save window state to {}: {}",
+                self.path.display(),
+                err
+            );
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for PersistWindow<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Timer(token) if *token == self.timer => {
+                ctx.set_handled();
+                self.save(ctx, data);
+                return;
+            }
+            Event::WindowSize(_) => self.timer = ctx.request_timer(self.delay),
+            Event::WindowDisconnected => self.save(ctx, data),
+            _ => (),
+        }
+        child.event(ctx, event, data, env)
+    }
+
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if self.save_data.is_some() && !old_data.same(data) {
+            self.timer = ctx.request_timer(self.delay);
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::temp_dir_for_test;
+    use crate::widget::Label;
+    use crate::WidgetExt;
+    use test_log::test;
+
+    #[test]
+    fn round_trip() {
+        let state = SavedState {
+            window: Some(WindowGeometry {
+                position: Point::new(10.0, -20.5),
+                size: Size::new(800.0, 600.0),
+                state: WindowState::Maximized,
+            }),
+            data: Some("first\ndata\nline\n".into()),
+        };
+        assert_eq!(SavedState::parse(&state.to_string()), Some(state));
+        assert_eq!(
+            SavedState::parse(&SavedState::default().to_string()),
+            Some(SavedState::default())
+        );
+
+        assert_eq!(SavedState::parse(""), None);
+        assert_eq!(SavedState::parse("position 1 2\n"), None);
+        assert_eq!(SavedState::parse("druid-saved-state 1\nsize 1 x\n"), None);
+        let unknown = SavedState::parse("druid-saved-state 1\ncolor red\nsize 1 2\n").unwrap();
+        assert_eq!(unknown, SavedState::default());
+    }
+
+    #[test]
+    fn missing_or_corrupt_file() {
+        let dir = temp_dir_for_test();
+        let path = dir.join("state.txt");
+        assert_eq!(SavedState::load(&path), SavedState::default());
+        fs::write(&path, "not a saved state").unwrap();
+        assert_eq!(SavedState::load(&path), SavedState::default());
+    }
+
+    #[test]
+    fn saves_after_changes() {
+        let dir = temp_dir_for_test();
+        let path = dir.join("state.txt");
+        let widget = Label::new(|data: &String, _: &_| data.clone())
+            .controller(PersistWindow::new(&path).with_data(|data: &String| data.clone()));
+
+        Harness::create_simple("a".to_string(), widget, |harness| {
+            harness.send_initial_events();
+            harness.event(Event::WindowSize(Size::new(300.0, 200.0)));
+            harness.update_data(|data| *data = "b".to_string());
+            assert!(!path.exists());
+
+            let tokens: Vec<TimerToken> = harness.window().timers.keys().copied().collect();
+            for token in tokens {
+                harness.event(Event::Timer(token));
+            }
+            let saved = SavedState::load(&path);
+            assert!(saved.window.is_some());
+            assert_eq!(saved.data.as_deref(), Some("b"));
+
+            harness.update_data(|data| *data = "c".to_string());
+            harness.event(Event::WindowDisconnected);
+            assert_eq!(SavedState::load(&path).data.as_deref(), Some("c"));
+        });
+    }
+}
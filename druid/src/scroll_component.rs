@@ -92,6 +92,44 @@ impl Default for ScrollbarsEnabled {
     }
 }
 
+/// When the scrollbars of a scroll area are shown.
+///
+/// Their look comes from the [`SCROLLBAR_COLOR`], [`SCROLLBAR_WIDTH`],
+/// [`SCROLLBAR_RADIUS`] and [`SCROLLBAR_MIN_SIZE`] keys in the [`Env`]; the
+/// last one keeps the thumb large enough to grab on very long content.
+///
+/// [`SCROLLBAR_COLOR`]: theme::SCROLLBAR_COLOR
+/// [`SCROLLBAR_WIDTH`]: theme::SCROLLBAR_WIDTH
+/// [`SCROLLBAR_RADIUS`]: theme::SCROLLBAR_RADIUS
+/// [`SCROLLBAR_MIN_SIZE`]: theme::SCROLLBAR_MIN_SIZE
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ScrollbarBehavior {
+    /// The scrollbars are always shown, as long as there is content to scroll.
+    Always,
+    /// The scrollbars are shown while the mouse is over the scroll area, and
+    /// fade out after it leaves.
+    OnHover,
+    /// The scrollbars are shown when the content scrolls or the scroll area
+    /// changes size, and fade out after [`SCROLLBAR_FADE_DELAY`].
+    ///
+    /// This is the default.
+    ///
+    /// [`SCROLLBAR_FADE_DELAY`]: theme::SCROLLBAR_FADE_DELAY
+    #[default]
+    WhenScrolling,
+}
+
+impl ScrollbarBehavior {
+    /// Whether the scrollbars should fade out now.
+    fn fades(self, hot: bool) -> bool {
+        match self {
+            ScrollbarBehavior::Always => false,
+            ScrollbarBehavior::OnHover => !hot,
+            ScrollbarBehavior::WhenScrolling => true,
+        }
+    }
+}
+
 /// Denotes which scrollbar, if any, is currently being hovered over
 /// by the mouse.
 #[derive(Debug, Copy, Clone)]
@@ -169,6 +207,8 @@ pub struct ScrollComponent {
     pub held: BarHeldState,
     /// Which scrollbars are enabled
     pub enabled: ScrollbarsEnabled,
+    /// When the scrollbars are shown
+    pub behavior: ScrollbarBehavior,
 }
 
 impl Default for ScrollComponent {
@@ -179,6 +219,7 @@ impl Default for ScrollComponent {
             hovered: BarHoveredState::None,
             held: BarHeldState::None,
             enabled: ScrollbarsEnabled::Both,
+            behavior: ScrollbarBehavior::WhenScrolling,
         }
     }
 }
@@ -200,11 +241,23 @@ impl ScrollComponent {
         F: FnOnce(Duration) -> TimerToken,
     {
         self.opacity = env.get(theme::SCROLLBAR_MAX_OPACITY);
+        if self.behavior == ScrollbarBehavior::Always {
+            return;
+        }
         let fade_delay = env.get(theme::SCROLLBAR_FADE_DELAY);
         let deadline = Duration::from_millis(fade_delay);
         self.timer_id = request_timer(deadline);
     }
 
+    /// The opacity the scrollbars are currently drawn with.
+    fn current_opacity(&self, env: &Env) -> f64 {
+        if self.behavior == ScrollbarBehavior::Always {
+            env.get(theme::SCROLLBAR_MAX_OPACITY)
+        } else {
+            self.opacity
+        }
+    }
+
     /// Calculates the paint rect of the vertical scrollbar, or `None` if the vertical scrollbar is
     /// not visible.
     pub fn calc_vertical_bar_bounds(&self, port: &Viewport, env: &Env) -> Option<Rect> {
@@ -217,24 +270,16 @@ impl ScrollComponent {
         self.calc_bar_bounds(Axis::Horizontal, port, env)
     }
 
-    fn calc_bar_bounds(&self, axis: Axis, port: &Viewport, env: &Env) -> Option<Rect> {
-        let viewport_size = port.view_size;
-        let content_size = port.content_size;
-        let scroll_offset = port.view_origin.to_vec2();
-
-        let viewport_major = axis.major(viewport_size);
-        let content_major = axis.major(content_size);
-
-        if viewport_major >= content_major {
-            return None;
-        }
+    /// The space the scrollbar on `axis` moves in, and its length.
+    fn bar_extent(&self, axis: Axis, port: &Viewport, env: &Env) -> (f64, f64) {
+        let viewport_major = axis.major(port.view_size);
+        let content_major = axis.major(port.content_size);
 
         let bar_width = env.get(theme::SCROLLBAR_WIDTH);
         let bar_pad = env.get(theme::SCROLLBAR_PAD);
         let bar_min_size = env.get(theme::SCROLLBAR_MIN_SIZE);
 
         let percent_visible = viewport_major / content_major;
-        let percent_scrolled = axis.major_vec(scroll_offset) / (content_major - viewport_major);
 
         let major_padding = if self.enabled.is_enabled(axis.cross()) {
             bar_pad + bar_pad + bar_width
@@ -245,16 +290,59 @@ impl ScrollComponent {
 
         let length = (percent_visible * viewport_major).ceil();
         let length = length.max(bar_min_size).min(usable_space);
+        (usable_space, length)
+    }
+
+    /// How far the content scrolls when the scrollbar on `axis` is dragged by one pixel.
+    ///
+    /// This accounts for the thumb being kept at its minimum size, so the
+    /// thumb stays under the mouse all the way to the ends of the track.
+    fn content_per_bar_pixel(&self, axis: Axis, port: &Viewport, env: &Env) -> f64 {
+        let (usable_space, length) = self.bar_extent(axis, port, env);
+        let travel = usable_space - length;
+        let content_travel = axis.major(port.content_size) - axis.major(port.view_size);
+        if travel > 0.0 {
+            content_travel / travel
+        } else {
+            0.0
+        }
+    }
+
+    fn calc_bar_bounds(&self, axis: Axis, port: &Viewport, env: &Env) -> Option<Rect> {
+        let viewport_size = port.view_size;
+        let content_size = port.content_size;
+        let scroll_offset = port.view_origin.to_vec2();
 
-        let left_x_offset = bar_pad + ((usable_space - length) * percent_scrolled).ceil();
+        let viewport_major = axis.major(viewport_size);
+        let content_major = axis.major(content_size);
+
+        if viewport_major >= content_major {
+            return None;
+        }
+
+        let bar_width = env.get(theme::SCROLLBAR_WIDTH);
+        let bar_pad = env.get(theme::SCROLLBAR_PAD);
+
+        let percent_scrolled = axis.major_vec(scroll_offset) / (content_major - viewport_major);
+        let (usable_space, length) = self.bar_extent(axis, port, env);
+
+        // In a right-to-left layout the vertical bar is on the left, so the
+        // horizontal bar makes room for it there.
+        let rtl = env.get(theme::LAYOUT_DIRECTION).is_rtl();
+        let mut left_x_offset = bar_pad + ((usable_space - length) * percent_scrolled).ceil();
+        if rtl && axis == Axis::Horizontal && self.enabled.is_enabled(Axis::Vertical) {
+            left_x_offset += bar_width;
+        }
         let right_x_offset = left_x_offset + length;
 
-        let (x0, y0) = axis.pack(
-            left_x_offset,
-            axis.minor(viewport_size) - bar_width - bar_pad,
-        );
+        let minor_offset = if rtl && axis == Axis::Vertical {
+            bar_pad + bar_width
+        } else {
+            axis.minor(viewport_size) - bar_pad
+        };
 
-        let (x1, y1) = axis.pack(right_x_offset, axis.minor(viewport_size) - bar_pad);
+        let (x0, y0) = axis.pack(left_x_offset, minor_offset - bar_width);
+        let (x1, y1) = axis.pack(right_x_offset, minor_offset);
 
         if x0 >= x1 || y0 >= y1 {
             return None;
@@ -267,17 +355,17 @@ impl ScrollComponent {
     pub fn draw_bars(&self, ctx: &mut PaintCtx, port: &Viewport, env: &Env) {
         let scroll_offset = port.view_origin.to_vec2();
 
-        if self.enabled.is_none() || self.opacity <= 0.0 {
+        let opacity = self.current_opacity(env);
+        if self.enabled.is_none() || opacity <= 0.0 {
             return;
         }
 
         let brush = ctx
             .render_ctx
-            .solid_brush(env.get(theme::SCROLLBAR_COLOR).with_alpha(self.opacity));
-        let border_brush = ctx.render_ctx.solid_brush(
-            env.get(theme::SCROLLBAR_BORDER_COLOR)
-                .with_alpha(self.opacity),
-        );
+            .solid_brush(env.get(theme::SCROLLBAR_COLOR).with_alpha(opacity));
+        let border_brush = ctx
+            .render_ctx
+            .solid_brush(env.get(theme::SCROLLBAR_BORDER_COLOR).with_alpha(opacity));
 
         let radius = env.get(theme::SCROLLBAR_RADIUS);
         let edge_width = env.get(theme::SCROLLBAR_EDGE_WIDTH);
@@ -317,7 +405,11 @@ impl ScrollComponent {
 
         if let Some(mut bounds) = self.calc_vertical_bar_bounds(port, env) {
             // Stretch hitbox to edge of widget
-            bounds.x1 = scroll_offset.x + viewport_size.width;
+            if env.get(theme::LAYOUT_DIRECTION).is_rtl() {
+                bounds.x0 = scroll_offset.x;
+            } else {
+                bounds.x1 = scroll_offset.x + viewport_size.width;
+            }
             bounds.contains(pos)
         } else {
             false
@@ -343,12 +435,52 @@ impl ScrollComponent {
         }
     }
 
+    /// Tests if the specified point is on the track of a scrollbar, and returns its axis.
+    ///
+    /// The track is the whole length of the scroll area along the scrollbar,
+    /// including the scrollbar itself. Returns `None` if no scrollbar is visible
+    /// there.
+    pub fn point_hits_track(&self, port: &Viewport, pos: Point, env: &Env) -> Option<Axis> {
+        let view_rect = port.view_rect();
+        let hits = |axis: Axis, bounds: Option<Rect>| {
+            let mut track = match bounds {
+                Some(bounds) if self.enabled.is_enabled(axis) => bounds,
+                _ => return false,
+            };
+            // Stretch the hitbox to the edge of the widget like for the bar,
+            // and along the whole scroll area.
+            match axis {
+                Axis::Vertical => {
+                    if env.get(theme::LAYOUT_DIRECTION).is_rtl() {
+                        track.x0 = view_rect.x0;
+                    } else {
+                        track.x1 = view_rect.x1;
+                    }
+                    track.y0 = view_rect.y0;
+                    track.y1 = view_rect.y1;
+                }
+                Axis::Horizontal => {
+                    track.y1 = view_rect.y1;
+                    track.x0 = view_rect.x0;
+                    track.x1 = view_rect.x1;
+                }
+            }
+            track.contains(pos)
+        };
+        if hits(Axis::Vertical, self.calc_vertical_bar_bounds(port, env)) {
+            Some(Axis::Vertical)
+        } else if hits(Axis::Horizontal, self.calc_horizontal_bar_bounds(port, env)) {
+            Some(Axis::Horizontal)
+        } else {
+            None
+        }
+    }
+
     /// Checks if the event applies to the scroll behavior, uses it, and marks it handled
     ///
     /// Make sure to call on every event
     pub fn event(&mut self, port: &mut Viewport, ctx: &mut EventCtx, event: &Event, env: &Env) {
         let viewport_size = port.view_size;
-        let content_size = port.content_size;
         let scroll_offset = port.view_origin.to_vec2();
 
         let scrollbar_is_hovered = match event {
@@ -366,23 +498,23 @@ impl ScrollComponent {
                 Event::MouseMove(event) => {
                     match self.held {
                         BarHeldState::Vertical(offset) => {
-                            let scale_y = viewport_size.height / content_size.height;
+                            let scale_y = self.content_per_bar_pixel(Axis::Vertical, port, env);
                             let bounds = self
                                 .calc_vertical_bar_bounds(port, env)
                                 .unwrap_or(Rect::ZERO);
                             let mouse_y = event.pos.y + scroll_offset.y;
                             let delta = mouse_y - bounds.y0 - offset;
-                            port.pan_by(Vec2::new(0f64, (delta / scale_y).ceil()));
+                            port.pan_by(Vec2::new(0f64, (delta * scale_y).ceil()));
                             ctx.set_handled();
                         }
                         BarHeldState::Horizontal(offset) => {
-                            let scale_x = viewport_size.width / content_size.width;
+                            let scale_x = self.content_per_bar_pixel(Axis::Horizontal, port, env);
                             let bounds = self
                                 .calc_horizontal_bar_bounds(port, env)
                                 .unwrap_or(Rect::ZERO);
                             let mouse_x = event.pos.x + scroll_offset.x;
                             let delta = mouse_x - bounds.x0 - offset;
-                            port.pan_by(Vec2::new((delta / scale_x).ceil(), 0f64));
+                            port.pan_by(Vec2::new((delta * scale_x).ceil(), 0f64));
                             ctx.set_handled();
                         }
                         _ => (),
@@ -454,16 +586,36 @@ impl ScrollComponent {
                         self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
                     }
                 }
+                Event::MouseDown(event) if self.current_opacity(env) > 0.0 => {
+                    // Clicking the track next to the bar scrolls by a page towards the click.
+                    let pos = event.pos + scroll_offset;
+                    if let Some(axis) = self.point_hits_track(port, pos, env) {
+                        let bounds = self.calc_bar_bounds(axis, port, env).unwrap_or(Rect::ZERO);
+                        let page = axis.major(viewport_size);
+                        let delta = if axis.major_pos(pos) < axis.major_pos(bounds.origin()) {
+                            -page
+                        } else {
+                            page
+                        };
+                        let (x, y) = axis.pack(delta, 0.0);
+                        port.pan_by(Vec2::new(x, y));
+                        self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+                        ctx.request_paint();
+                        ctx.set_handled();
+                    }
+                }
                 Event::Timer(id) if *id == self.timer_id => {
-                    // Schedule scroll bars animation
-                    ctx.request_anim_frame();
+                    // Schedule scroll bars animation, unless they should stay visible
+                    if self.behavior.fades(ctx.is_hot()) {
+                        ctx.request_anim_frame();
+                    }
                     self.timer_id = TimerToken::INVALID;
                     ctx.set_handled();
                 }
                 Event::AnimFrame(interval) => {
                     // Guard by the timer id being invalid, otherwise the scroll bars would fade
                     // immediately if some other widget started animating.
-                    if self.timer_id == TimerToken::INVALID {
+                    if self.timer_id == TimerToken::INVALID && self.behavior.fades(ctx.is_hot()) {
                         // Animate scroll bars opacity
                         let diff = 2.0 * (*interval as f64) * 1e-9;
                         self.opacity -= diff;
@@ -508,9 +660,18 @@ impl ScrollComponent {
     ///
     /// Make sure to call on every lifecycle event
     pub fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, env: &Env) {
-        if let LifeCycle::Size(_) = event {
+        match event {
             // Show the scrollbars any time our size changes
-            self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+            LifeCycle::Size(_) => self.reset_scrollbar_fade(|d| ctx.request_timer(d), env),
+            LifeCycle::HotChanged(hot) if self.behavior == ScrollbarBehavior::OnHover => {
+                self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+                if *hot {
+                    // Cancel any fade out in progress
+                    self.timer_id = TimerToken::INVALID;
+                }
+                ctx.request_paint();
+            }
+            _ => (),
         }
     }
 }
@@ -521,6 +682,7 @@ mod tests {
 
     use super::*;
     use crate::kurbo::Size;
+    use crate::widget::LayoutDirection;
 
     const TEST_SCROLLBAR_WIDTH: f64 = 11.0;
     const TEST_SCROLLBAR_PAD: f64 = 3.0;
@@ -580,6 +742,26 @@ mod tests {
         assert_eq!(scrollbar_rect, Rect::new(86.0, 3.0, 97.0, 28.0));
     }
 
+    #[test]
+    fn rtl_vertical_bar_is_on_the_left() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.enabled = ScrollbarsEnabled::Vertical;
+        let viewport = Viewport {
+            content_size: Size::new(100.0, 100.0),
+            view_origin: Point::ZERO,
+            view_size: (100.0, 50.0).into(),
+        };
+        let env = test_env().adding(theme::LAYOUT_DIRECTION, LayoutDirection::Rtl);
+
+        let scrollbar_rect = scroll_component
+            .calc_vertical_bar_bounds(&viewport, &env)
+            .unwrap();
+        assert_eq!(scrollbar_rect, Rect::new(3.0, 3.0, 14.0, 28.0));
+        // The hitbox stretches to the left edge.
+        assert!(scroll_component.point_hits_vertical_bar(&viewport, Point::new(1.0, 10.0), &env));
+        assert!(!scroll_component.point_hits_vertical_bar(&viewport, Point::new(90.0, 10.0), &env));
+    }
+
     #[test]
     fn scrollbar_layout_at_end() {
         let mut scroll_component = ScrollComponent::new();
@@ -691,6 +873,23 @@ mod tests {
         assert_eq!(scrollbar_rect, Rect::new(86.0, 29.0, 97.0, 46.0));
     }
 
+    #[test]
+    fn drag_maps_bar_travel_to_content() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.enabled = ScrollbarsEnabled::Vertical;
+        let viewport = Viewport {
+            content_size: Size::new(100.0, 1000.0),
+            view_origin: Point::ZERO,
+            view_size: (100.0, 50.0).into(),
+        };
+
+        // The bar is kept at its minimum size, so it moves 50 - 2 * 3 - 17 = 27px
+        // for the 950px that the content scrolls.
+        let env = test_env();
+        let ratio = scroll_component.content_per_bar_pixel(Axis::Vertical, &viewport, &env);
+        approx_eq!(f64, ratio, 950.0 / 27.0);
+    }
+
     #[test]
     fn scrollbar_layout_viewport_too_small_for_min_bar_size() {
         let mut scroll_component = ScrollComponent::new();
@@ -752,5 +951,6 @@ mod tests {
             .adding(theme::SCROLLBAR_WIDTH, TEST_SCROLLBAR_WIDTH)
             .adding(theme::SCROLLBAR_PAD, TEST_SCROLLBAR_PAD)
             .adding(theme::SCROLLBAR_MIN_SIZE, TEST_SCROLLBAR_MIN_SIZE)
+            .adding(theme::LAYOUT_DIRECTION, LayoutDirection::Ltr)
     }
 }
@@ -13,13 +13,59 @@
 // limitations under the License.
 
 //! Tools and infrastructure for testing widgets.
-
+//!
+//! The [`Harness`] runs a widget without a real window, so that you can send it
+//! synthetic events, drive layout and paint, and check the results.
+//!
+//! # Examples
+//!
+//! ```
+//! use druid::tests::{harness::Harness, move_mouse};
+//! use druid::widget::Button;
+//! use druid::{Color, Event, MouseButton, Selector, Size, WidgetExt, WidgetId};
+//!
+//! const CLICKED: Selector = Selector::new("my-app.clicked");
+//!
+//! let id = WidgetId::next();
+//! let button = Button::new("Click")
+//!     .on_click(|ctx, _, _| ctx.submit_command(CLICKED))
+//!     .with_id(id)
+//!     .center()
+//!     .background(Color::WHITE);
+//!
+//! Harness::create_with_render(
+//!     (),
+//!     button,
+//!     Size::new(100.0, 40.0),
+//!     |harness| {
+//!         harness.send_initial_events();
+//!         harness.just_layout();
+//!         let state = harness.get_state(id);
+//!         let center = state.window_origin() + state.size().to_vec2() / 2.0;
+//!         let mut mouse = move_mouse(center);
+//!         mouse.button = MouseButton::Left;
+//!         mouse.count = 1;
+//!         harness.event(Event::MouseDown(mouse.clone()));
+//!         assert!(harness.get_state(id).is_active());
+//!         harness.event(Event::MouseUp(mouse));
+//!         assert!(harness.take_commands().iter().any(|cmd| cmd.is(CLICKED)));
+//!         harness.paint();
+//!     },
+//!     |target| {
+//!         let image = target.into_image_buf();
+//!         assert_eq!(image.width(), 100);
+//!     },
+//! );
+//! ```
+
+use std::any::TypeId;
 use std::path::Path;
 use std::sync::Arc;
 
+use crate::accessibility::{AccessNode, AccessUpdate};
 use crate::app::PendingWindow;
 use crate::core::{CommandQueue, WidgetState};
-use crate::ext_event::ExtEventHost;
+use crate::ext_event::{ExtEvent, ExtEventHost};
 use crate::piet::{BitmapTarget, Device, Error, ImageFormat, Piet};
 use crate::*;
 
@@ -60,6 +106,11 @@ struct MockAppState<T> {
     env: Env,
     window: Window<T>,
     cmds: CommandQueue,
+    /// Every command that was dispatched, for [`Harness::take_commands`].
+    submitted: Vec<Command>,
+    ext_host: ExtEventHost,
+    delegate: Option<Box<dyn AppDelegate<T>>>,
+    keymap: KeyMap,
 }
 
 /// A way to clean up resources when our target goes out of scope.
@@ -68,6 +119,13 @@ struct MockAppState<T> {
 pub struct TargetGuard<'a>(Option<BitmapTarget<'a>>);
 
 impl<'a> TargetGuard<'a> {
+    /// Turns the TargetGuard into an [`ImageBuf`], for instance to compare
+    /// with a golden image.
+    pub fn into_image_buf(mut self) -> ImageBuf {
+        let mut raw_target = self.0.take().unwrap();
+        raw_target.to_image_buf(ImageFormat::RgbaPremul).unwrap()
+    }
+
     /// Turns the TargetGuard into a array of pixels
     #[allow(dead_code)]
     pub fn into_raw(mut self) -> Arc<[u8]> {
@@ -159,6 +217,10 @@ impl<T: Data> Harness<'_, T> {
                 env: Env::with_default_i10n(),
                 window,
                 cmds: Default::default(),
+                submitted: Vec::new(),
+                ext_host,
+                delegate: None,
+                keymap: KeyMap::default(),
             };
 
             let mut harness = Harness {
@@ -186,11 +248,32 @@ impl<T: Data> Harness<'_, T> {
         &mut self.mock_app.window
     }
 
+    /// The current data.
     #[allow(dead_code)]
     pub fn data(&self) -> &T {
         &self.mock_app.data
     }
 
+    /// Change the data, and `update` the widget with the result.
+    pub fn update_data(&mut self, f: impl FnOnce(&mut T)) {
+        f(&mut self.mock_app.data);
+        self.update();
+    }
+
+    /// The region of the window that needs to be repainted, because of calls
+    /// to `request_paint` or `request_paint_rect`.
+    pub fn invalid_region(&self) -> &Region {
+        self.mock_app.window.invalid()
+    }
+
+    /// Returns the commands that widgets have submitted since the last call,
+    /// in the order that they were dispatched.
+    ///
+    /// Commands are still delivered to their targets as they would be in an app.
+    pub fn take_commands(&mut self) -> Vec<Command> {
+        std::mem::take(&mut self.mock_app.submitted)
+    }
+
     /// Retrieve a copy of this widget's `WidgetState`, or die trying.
     pub fn get_state(&mut self, widget: WidgetId) -> WidgetState {
         match self.try_get_state(widget) {
@@ -246,11 +329,49 @@ impl<T: Data> Harness<'_, T> {
         )))
     }
 
+    /// Collect the accessibility tree of the window.
+    pub fn access_tree(&mut self) -> AccessNode {
+        let app = &mut self.mock_app;
+        app.window.access_tree(&mut app.cmds, &app.data, &app.env)
+    }
+
+    /// The accessibility update an application would receive now, if the tree
+    /// may have changed since the previous call.
+    pub fn accessibility_update(&mut self) -> Option<AccessUpdate> {
+        let app = &mut self.mock_app;
+        app.window
+            .accessibility_update(&mut app.cmds, &app.data, &app.env)
+    }
+
+    /// Pass commands to `delegate` before the widget sees them.
+    ///
+    /// As in an app, each command goes through [`AppDelegate::filter_command`]
+    /// and then [`AppDelegate::command`]; other delegate methods aren't called.
+    pub fn set_delegate(&mut self, delegate: impl AppDelegate<T> + 'static) {
+        self.mock_app.delegate = Some(Box::new(delegate));
+    }
+
     /// Send a command to a target.
     pub fn submit_command(&mut self, cmd: impl Into<Command>) {
         let command = cmd.into().default_to(self.mock_app.window.id.into());
-        let event = Event::Internal(InternalEvent::TargetedCommand(command));
-        self.event(event);
+        if let Some(command) = self.mock_app.delegate_command(command) {
+            self.event(Event::Internal(InternalEvent::TargetedCommand(command)));
+        }
+    }
+
+    /// Handle the commands and callbacks that have been submitted through an
+    /// [`ExtEventSink`] so far.
+    ///
+    /// [`ExtEventSink`]: crate::ExtEventSink
+    pub fn process_ext_events(&mut self) {
+        while let Some(ext_event) = self.mock_app.ext_host.recv() {
+            match ext_event {
+                ExtEvent::Command((selector, payload, target)) => {
+                    self.submit_command(Command::from_ext(selector, payload, target))
+                }
+                ExtEvent::Callback(callback) => self.update_data(|data| callback(data)),
+            }
+        }
     }
 
     /// Send the events that would normally be sent when the app starts.
@@ -276,13 +397,19 @@ impl<T: Data> Harness<'_, T> {
         loop {
             let cmd = self.mock_app.cmds.pop_front();
             match cmd {
-                Some(cmd) => self.event(Event::Internal(InternalEvent::TargetedCommand(cmd))),
+                Some(cmd) => {
+                    self.mock_app.submitted.push(cmd.clone());
+                    if let Some(cmd) = self.mock_app.delegate_command(cmd) {
+                        self.event(Event::Internal(InternalEvent::TargetedCommand(cmd)))
+                    }
+                }
                 None => break,
             }
         }
     }
 
-    pub(crate) fn lifecycle(&mut self, event: LifeCycle) {
+    /// Send a lifecycle event to the widget.
+    pub fn lifecycle(&mut self, event: LifeCycle) {
         self.mock_app.lifecycle(event)
     }
 
@@ -317,6 +444,16 @@ impl<T: Data> Harness<'_, T> {
     pub fn root_debug_state(&self) -> DebugState {
         self.mock_app.root_debug_state()
     }
+
+    /// Render the window, or `region` of it, into an image, as the
+    /// [`CAPTURE_WINDOW`] command does.
+    ///
+    /// [`CAPTURE_WINDOW`]: crate::commands::CAPTURE_WINDOW
+    pub fn capture(&mut self, region: Option<Rect>) -> Result<ImageBuf, CaptureError> {
+        let app = &mut self.mock_app;
+        app.window
+            .capture(region, &mut app.cmds, &app.data, &app.env)
+    }
 }
 
 impl<T: Data> MockAppState<T> {
@@ -325,6 +462,35 @@ impl<T: Data> MockAppState<T> {
             .event(&mut self.cmds, event, &mut self.data, &self.env);
     }
 
+    /// Run `cmd` through the delegate, if there is one. Returns the command
+    /// that should be sent to the window.
+    fn delegate_command(&mut self, cmd: Command) -> Option<Command> {
+        let MockAppState {
+            delegate,
+            cmds,
+            data,
+            env,
+            ext_host,
+            keymap,
+            ..
+        } = self;
+        let delegate = match delegate {
+            Some(delegate) => delegate,
+            None => return Some(cmd),
+        };
+        let mut ctx = DelegateCtx {
+            command_queue: cmds,
+            ext_event_host: ext_host,
+            keymap,
+            app_data_type: TypeId::of::<T>(),
+        };
+        let cmd = delegate.filter_command(&mut ctx, cmd, data, env)?;
+        match delegate.command(&mut ctx, cmd.target(), &cmd, data, env) {
+            Handled::Yes => None,
+            Handled::No => Some(cmd),
+        }
+    }
+
     fn lifecycle(&mut self, event: LifeCycle) {
         self.window
             .lifecycle(&mut self.cmds, &event, &self.data, &self.env, false);
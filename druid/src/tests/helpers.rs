@@ -25,6 +25,8 @@ use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::rc::Rc;
 
+use crate::tests::harness::Harness;
+use crate::tests::move_mouse;
 use crate::*;
 
 pub type EventFn<S, T> = dyn FnMut(&mut S, &mut EventCtx, &Event, &mut T, &Env);
@@ -34,6 +36,7 @@ pub type LayoutFn<S, T> = dyn FnMut(&mut S, &mut LayoutCtx, &BoxConstraints, &T,
 pub type PaintFn<S, T> = dyn FnMut(&mut S, &mut PaintCtx, &T, &Env);
 
 pub const REPLACE_CHILD: Selector = Selector::new("druid-test.replace-child");
+pub const REQUEST_FOCUS: Selector = Selector::new("druid-test.request-focus");
 
 /// A widget that can be constructed from individual functions, builder-style.
 ///
@@ -53,6 +56,9 @@ pub struct ReplaceChild<T> {
     replacer: Box<dyn Fn() -> Box<dyn Widget<T>>>,
 }
 
+/// A controller that requests focus for its child when it receives [`REQUEST_FOCUS`].
+pub struct FocusOnCommand;
+
 /// A widget that records each time one of its methods is called.
 ///
 /// Make one like this:
@@ -239,6 +245,15 @@ impl<T: Data> Widget<T> for ReplaceChild<T> {
 }
 
 #[allow(dead_code)]
+impl<T, W: Widget<T>> widget::Controller<T, W> for FocusOnCommand {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(REQUEST_FOCUS) => ctx.request_focus(),
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
 impl Recording {
     pub fn is_empty(&self) -> bool {
         self.0.borrow().is_empty()
@@ -321,3 +336,23 @@ pub fn widget_ids<const N: usize>() -> [WidgetId; N] {
 
     ids
 }
+
+/// Click the left mouse button at `pos`, moving the mouse there first.
+pub fn click<T: Data>(harness: &mut Harness<T>, pos: impl Into<Point>) {
+    let press = MouseEvent {
+        button: MouseButton::Left,
+        count: 1,
+        ..move_mouse(pos)
+    };
+    harness.event(Event::MouseMove(press.clone()));
+    harness.event(Event::MouseDown(press.clone()));
+    harness.event(Event::MouseUp(press));
+}
+
+/// Press `key` without any modifiers.
+pub fn press_key<T: Data>(harness: &mut Harness<T>, key: KbKey) {
+    harness.event(Event::KeyDown(KeyEvent::for_test(
+        Modifiers::default(),
+        key,
+    )));
+}
@@ -122,4 +122,53 @@ fn invalidate_scroll() {
     });
 }
 
+#[test]
+fn paint_only_invalid_rect() {
+    const INVALIDATE: Selector = Selector::new("druid-test.invalidate-corner");
+
+    // the widget always paints all of itself, in the color of the data
+    let widget = ModularWidget::new(())
+        .event_fn(|_, ctx, event, _, _| {
+            if matches!(event, Event::Command(cmd) if cmd.is(INVALIDATE)) {
+                ctx.request_paint_rect(Rect::new(0., 0., 2., 2.));
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.max())
+        .paint_fn(|_, ctx, data: &u32, _| {
+            let color = if *data == 0 {
+                Color::rgb8(0xff, 0, 0)
+            } else {
+                Color::rgb8(0, 0, 0xff)
+            };
+            let rect = ctx.size().to_rect();
+            ctx.fill(rect, &color);
+        });
+
+    Harness::create_with_render(
+        0u32,
+        widget,
+        Size::new(4., 4.),
+        |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            harness.update_data(|data| *data = 1);
+            harness.submit_command(INVALIDATE);
+            assert_eq!(
+                harness.invalid_region().rects(),
+                &[Rect::new(0., 0., 2., 2.)]
+            );
+            harness.paint_invalid();
+        },
+        |target| {
+            let image = target.into_image_buf();
+            let pixel = |x: usize, y: usize| &image.raw_pixels()[(y * 4 + x) * 4..][..4];
+            assert_eq!(pixel(1, 1), &[0, 0, 0xff, 0xff]);
+            // outside of the invalid rect, the old paint is kept
+            assert_eq!(pixel(2, 1), &[0xff, 0, 0, 0xff]);
+            assert_eq!(pixel(3, 3), &[0xff, 0, 0, 0xff]);
+        },
+    );
+}
+
 // TODO: one with scroll
@@ -273,3 +273,78 @@ fn aspect_ratio() {
         assert_eq!(state.layout_rect().size(), Size::new(1000., 500.));
     });
 }
+
+#[test]
+fn flex_gap_tight_constraints() {
+    let [row_id, id1, id2, id3] = widget_ids();
+    let widget = Flex::row()
+        .with_main_axis_gap(10.0)
+        .with_child(SizedBox::empty().width(50.).height(10.).with_id(id1))
+        .with_flex_child(SizedBox::empty().expand_width().with_id(id2), 1.0)
+        .with_child(SizedBox::empty().width(30.).height(10.).with_id(id3))
+        .with_id(row_id);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.get_state(row_id).layout_rect().width(), 400.);
+        assert_eq!(harness.get_state(id1).layout_rect().x0, 0.);
+        // the flex child gets what is left after the fixed children and both gaps
+        let flex_rect = harness.get_state(id2).layout_rect();
+        assert_eq!(flex_rect.x0, 60.);
+        assert_eq!(flex_rect.width(), 300.);
+        // no gap after the last child
+        assert_eq!(harness.get_state(id3).layout_rect().x1, 400.);
+    })
+}
+
+#[test]
+fn flex_gap_loose_constraints() {
+    let [row_id, id1, id2, id3] = widget_ids();
+    let widget = Flex::column()
+        .with_main_axis_gap(10.0)
+        .with_child(SizedBox::empty().width(10.).height(50.).with_id(id1))
+        .with_child(SizedBox::empty().width(10.).height(20.).with_id(id2))
+        .with_child(SizedBox::empty().width(10.).height(30.).with_id(id3))
+        .with_id(row_id)
+        .center();
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // the reported size includes the gaps, but nothing after the last child
+        assert_eq!(
+            harness.get_state(row_id).layout_rect().size(),
+            Size::new(10., 120.)
+        );
+        assert_eq!(harness.get_state(id1).layout_rect().y0, 0.);
+        assert_eq!(harness.get_state(id2).layout_rect().y0, 60.);
+        assert_eq!(harness.get_state(id3).layout_rect().y0, 90.);
+    })
+}
+
+#[test]
+fn flex_gap_with_flex_children_and_alignment() {
+    let [id1, id2, id3] = widget_ids();
+    let widget = Flex::row()
+        .main_axis_alignment(MainAxisAlignment::SpaceEvenly)
+        .with_main_axis_gap(20.0)
+        .with_flex_child(SizedBox::empty().expand_width().with_id(id1), 1.0)
+        .with_child(SizedBox::empty().width(40.).height(10.).with_id(id2))
+        .with_flex_child(SizedBox::empty().expand_width().with_id(id3), 3.0)
+        .center();
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // 400 - 40 (fixed) - 2 * 20 (gaps) = 320, divided 1:3
+        let first = harness.get_state(id1).layout_rect();
+        let fixed = harness.get_state(id2).layout_rect();
+        let last = harness.get_state(id3).layout_rect();
+        assert_eq!(first.x0, 0.);
+        assert_eq!(first.width(), 80.);
+        assert_eq!(fixed.x0, 100.);
+        assert_eq!(last.x0, 160.);
+        assert_eq!(last.width(), 240.);
+    })
+}
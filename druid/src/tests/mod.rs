@@ -188,6 +188,69 @@ fn propagate_hot() {
         assert!(root_rec.is_empty() && padding_rec.is_empty() && button_rec.is_empty());
     });
 }
+#[test]
+fn file_drop_events() {
+    let [target, empty] = widget_ids();
+    let target_rec = Recording::default();
+
+    let widget = Split::columns(
+        SizedBox::empty().with_id(empty),
+        SizedBox::empty()
+            .expand()
+            .record(&target_rec)
+            .with_id(target)
+            .padding(50.),
+    );
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        target_rec.clear();
+
+        let drop_event = |pos: Point| FileDropEvent {
+            pos,
+            window_pos: pos,
+            paths: vec!["photos/cat.png".into()],
+        };
+        let origin = harness.get_state(target).window_origin();
+
+        harness.event(Event::FileDragMove(drop_event(origin + (10., 20.))));
+        assert!(harness.get_state(target).is_hot);
+        assert!(matches!(
+            target_rec.next(),
+            Record::L(LifeCycle::HotChanged(true))
+        ));
+        match target_rec.next() {
+            Record::E(Event::FileDragMove(event)) => {
+                assert_eq!(event.pos, Point::new(10., 20.));
+                assert!(event.matches(&[FileSpec::JPG, FileSpec::PNG]));
+                assert!(!event.matches(&[FileSpec::TEXT]));
+            }
+            other => panic!("unexpected record {:?}", other),
+        }
+
+        harness.event(Event::FileDragLeave);
+        assert!(!harness.get_state(target).is_hot);
+        assert!(matches!(
+            target_rec.next(),
+            Record::L(LifeCycle::HotChanged(false))
+        ));
+        assert!(matches!(target_rec.next(), Record::E(Event::FileDragLeave)));
+
+        // Drops outside of the widget don't reach it.
+        harness.event(Event::DropFiles(drop_event(Point::new(10., 10.))));
+        assert!(harness.get_state(empty).is_hot);
+        assert!(target_rec.is_empty());
+
+        harness.event(Event::DropFiles(drop_event(origin + (5., 5.))));
+        assert!(matches!(
+            target_rec.next(),
+            Record::L(LifeCycle::HotChanged(true))
+        ));
+        assert!(matches!(target_rec.next(), Record::E(Event::DropFiles(_))));
+    });
+}
+
 #[test]
 fn take_focus() {
     const TAKE_FOCUS: Selector = Selector::new("druid-tests.take-focus");
@@ -745,6 +808,64 @@ fn disable_tree() {
     })
 }
 
+#[test]
+fn disabled_controls_ignore_input() {
+    #[derive(Clone, Data, Lens)]
+    struct Form {
+        locked: bool,
+        checked: bool,
+        value: f64,
+        text: String,
+    }
+
+    let [checkbox, slider, textbox] = widget_ids();
+    let form = Flex::column()
+        .with_child(Checkbox::new("check").lens(Form::checked).with_id(checkbox))
+        .with_child(Slider::new().lens(Form::value).with_id(slider))
+        .with_child(TextBox::new().lens(Form::text).with_id(textbox))
+        .disabled_if(|form: &Form, _| form.locked);
+    let data = Form {
+        locked: true,
+        checked: false,
+        value: 0.5,
+        text: String::new(),
+    };
+
+    Harness::create_simple(data, form, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.paint();
+        assert!(harness.get_state(checkbox).is_disabled());
+        assert_eq!(harness.window().focus_chain(), &[]);
+
+        let click = |harness: &mut Harness<Form>, id| {
+            let rect = harness.get_state(id).layout_rect();
+            let press = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse((rect.x0 + 5.0, rect.center().y))
+            };
+            harness.event(Event::MouseMove(press.clone()));
+            harness.event(Event::MouseDown(press.clone()));
+            harness.event(Event::MouseUp(press));
+        };
+        click(harness, checkbox);
+        click(harness, slider);
+        click(harness, textbox);
+        assert!(!harness.data().checked);
+        assert_eq!(harness.data().value, 0.5);
+        assert_eq!(harness.window().focus, None);
+
+        harness.update_data(|form| form.locked = false);
+        harness.paint();
+        assert_eq!(harness.window().focus_chain(), &[checkbox, slider, textbox]);
+        click(harness, checkbox);
+        assert!(harness.data().checked);
+        click(harness, slider);
+        assert!(harness.data().value < 0.5);
+    })
+}
+
 #[test]
 fn simple_lifecyle() {
     let record = Recording::default();
@@ -961,3 +1082,81 @@ fn notifications() {
         assert!(saw_notification(&grandparent_rec));
     });
 }
+
+#[test]
+/// Test the harness' view of requests, data changes and the painted output.
+fn harness_reports_requests_and_output() {
+    let id = WidgetId::next();
+    let widget = ModularWidget::new(())
+        .update_fn(|_, ctx, old_data: &u32, data, _| {
+            if old_data != data {
+                ctx.request_layout();
+                ctx.request_paint();
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.max())
+        .paint_fn(|_, ctx, _, _| {
+            let rect = ctx.size().to_rect();
+            ctx.fill(rect, &Color::rgb8(0xff, 0, 0));
+        })
+        .with_id(id);
+
+    Harness::create_with_render(
+        0u32,
+        widget,
+        Size::new(4., 4.),
+        |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(harness.get_state(id).size(), Size::new(4., 4.));
+            assert!(!harness.get_state(id).needs_layout());
+
+            harness.update_data(|data| *data = 1);
+            assert!(harness.get_state(id).needs_layout());
+            assert!(!harness.invalid_region().is_empty());
+            harness.just_layout();
+            harness.paint();
+            assert!(harness.invalid_region().is_empty());
+        },
+        |target| {
+            let image = target.into_image_buf();
+            assert_eq!((image.width(), image.height()), (4, 4));
+            assert_eq!(&image.raw_pixels()[..4], &[0xff, 0, 0, 0xff]);
+        },
+    );
+}
+
+#[test]
+fn capture_window() {
+    let widget = Flex::row()
+        .with_child(
+            SizedBox::empty()
+                .width(2.)
+                .height(4.)
+                .background(Color::WHITE),
+        )
+        .with_child(
+            SizedBox::empty()
+                .width(2.)
+                .height(4.)
+                .background(Color::BLACK),
+        );
+
+    Harness::create_simple((), widget, |harness| {
+        harness.set_initial_size(Size::new(4., 4.));
+        harness.send_initial_events();
+        assert_eq!(harness.capture(None).err(), Some(CaptureError::NotPainted));
+        harness.paint();
+
+        let image = harness.capture(None).unwrap();
+        assert_eq!((image.width(), image.height()), (4, 4));
+        // The region is relative to the window.
+        let image = harness.capture(Some(Rect::new(2., 0., 4., 1.))).unwrap();
+        assert_eq!((image.width(), image.height()), (2, 1));
+        assert_eq!(&image.raw_pixels()[..4], &[0, 0, 0, 0xff]);
+        assert_eq!(
+            harness.capture(Some(Rect::new(10., 10., 20., 20.))).err(),
+            Some(CaptureError::EmptyRegion)
+        );
+    });
+}
@@ -0,0 +1,149 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ranges of text decorated with an underline, such as spelling errors.
+
+use std::ops::Range;
+
+use crate::{theme, Color, Data, Key, Point, WidgetId};
+
+/// How a [`TextAnnotation`] is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum AnnotationStyle {
+    /// A squiggly underline in [`theme::ANNOTATION_ERROR_COLOR`].
+    Error,
+    /// A squiggly underline in [`theme::ANNOTATION_WARNING_COLOR`].
+    Warning,
+    /// A squiggly underline in [`theme::ANNOTATION_INFO_COLOR`].
+    Info,
+}
+
+/// A range of text that is underlined, such as a misspelled word.
+#[derive(Debug, Clone, PartialEq, Eq, Data)]
+pub struct TextAnnotation {
+    /// The annotated range, in utf-8 code units.
+    pub range: Range<usize>,
+    /// How the range is drawn.
+    pub style: AnnotationStyle,
+}
+
+/// The payload of [`TextBox::ANNOTATION_CLICKED`].
+///
+/// [`TextBox::ANNOTATION_CLICKED`]: crate::widget::TextBox::ANNOTATION_CLICKED
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotationClick {
+    /// The annotation that was clicked.
+    pub annotation: TextAnnotation,
+    /// The text box that was clicked.
+    pub widget: WidgetId,
+    /// The position of the click, in window coordinates; a good place for a
+    /// context menu.
+    pub window_pos: Point,
+}
+
+impl AnnotationStyle {
+    /// The theme key of the underline color.
+    pub fn color_key(self) -> Key<Color> {
+        match self {
+            AnnotationStyle::Error => theme::ANNOTATION_ERROR_COLOR,
+            AnnotationStyle::Warning => theme::ANNOTATION_WARNING_COLOR,
+            AnnotationStyle::Info => theme::ANNOTATION_INFO_COLOR,
+        }
+    }
+}
+
+impl TextAnnotation {
+    /// Create a new annotation.
+    pub fn new(range: Range<usize>, style: AnnotationStyle) -> Self {
+        TextAnnotation { range, style }
+    }
+
+    /// The annotation after the text changed from `old` to `new`.
+    ///
+    /// Annotations before or after the edited text keep marking the same text;
+    /// annotations overlapping the edit are dropped.
+    pub fn after_edit(&self, old: &str, new: &str) -> Option<TextAnnotation> {
+        let (edit, inserted) = edited_range(old, new);
+        if self.range.end <= edit.start {
+            Some(self.clone())
+        } else if self.range.start >= edit.end {
+            let shift = |idx: usize| idx - edit.len() + inserted;
+            Some(TextAnnotation::new(
+                shift(self.range.start)..shift(self.range.end),
+                self.style,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// The range of `old` that was replaced to get `new`, and the length of its
+/// replacement.
+fn edited_range(old: &str, new: &str) -> (Range<usize>, usize) {
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((idx, _), _)| idx)
+        .unwrap_or_else(|| old.len().min(new.len()));
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .scan(0, |len, c| {
+            *len += c;
+            Some(*len)
+        })
+        .take_while(|len| *len <= max_suffix)
+        .last()
+        .unwrap_or(0);
+    (prefix..old.len() - suffix, new.len() - suffix - prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn annotations_follow_edits() {
+        let word = TextAnnotation::new(4..9, AnnotationStyle::Error);
+        // typing before the annotation moves it
+        assert_eq!(
+            word.after_edit("the qiuck fox", "so the qiuck fox"),
+            Some(TextAnnotation::new(7..12, AnnotationStyle::Error))
+        );
+        // typing after it doesn't
+        assert_eq!(
+            word.after_edit("the qiuck fox", "the qiuck brown fox"),
+            Some(word.clone())
+        );
+        // fixing the word removes it
+        assert_eq!(word.after_edit("the qiuck fox", "the quick fox"), None);
+        // no change at all keeps it
+        assert_eq!(
+            word.after_edit("the qiuck fox", "the qiuck fox"),
+            Some(word.clone())
+        );
+        // multi-byte characters are measured in utf-8
+        assert_eq!(
+            word.after_edit("the qiuck fox", "\u{e9} the qiuck fox"),
+            Some(TextAnnotation::new(7..12, AnnotationStyle::Error))
+        );
+    }
+}
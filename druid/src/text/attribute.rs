@@ -17,7 +17,7 @@
 use std::ops::Range;
 
 use crate::piet::{Color, FontFamily, FontStyle, FontWeight, TextAttribute as PietAttr};
-use crate::{Command, Env, FontDescriptor, KeyOrValue};
+use crate::{theme, Command, Env, FontDescriptor, KeyOrValue};
 
 use super::EnvUpdateCtx;
 
@@ -30,6 +30,25 @@ pub struct Link {
     pub command: Command,
 }
 
+/// The interaction state of a [`Link`], which decides how it is styled.
+///
+/// Links are drawn with the [`LINK_COLOR`], [`LINK_VISITED_COLOR`] or
+/// [`LINK_HOVER_COLOR`] matching their state, and hovered links are also
+/// underlined. Attributes set explicitly on the text of a link take precedence.
+///
+/// [`LINK_COLOR`]: crate::theme::LINK_COLOR
+/// [`LINK_VISITED_COLOR`]: crate::theme::LINK_VISITED_COLOR
+/// [`LINK_HOVER_COLOR`]: crate::theme::LINK_HOVER_COLOR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkState {
+    /// The link has not been clicked yet.
+    Normal,
+    /// The link has been clicked before.
+    Visited,
+    /// The pointer is over the link.
+    Hovered,
+}
+
 /// A collection of spans of attributes of various kinds.
 #[derive(Debug, Clone, Default)]
 pub struct AttributeSpans {
@@ -119,6 +138,19 @@ impl Link {
     }
 }
 
+impl LinkState {
+    /// The attributes used to draw a link in this state.
+    pub(crate) fn attributes(self) -> impl Iterator<Item = Attribute> {
+        let color = match self {
+            LinkState::Normal => theme::LINK_COLOR,
+            LinkState::Visited => theme::LINK_VISITED_COLOR,
+            LinkState::Hovered => theme::LINK_HOVER_COLOR,
+        };
+        let underline = (self == LinkState::Hovered).then(|| Attribute::underline(true));
+        std::iter::once(Attribute::text_color(color)).chain(underline)
+    }
+}
+
 impl AttributeSpans {
     /// Create a new, empty `AttributeSpans`.
     pub fn new() -> Self {
@@ -139,6 +171,19 @@ impl AttributeSpans {
         }
     }
 
+    /// Returns a copy of `base` with these spans added on top of it.
+    pub(crate) fn layered_over(&self, mut base: AttributeSpans) -> AttributeSpans {
+        base.family.extend(&self.family);
+        base.size.extend(&self.size);
+        base.weight.extend(&self.weight);
+        base.fg_color.extend(&self.fg_color);
+        base.style.extend(&self.style);
+        base.underline.extend(&self.underline);
+        base.strikethrough.extend(&self.strikethrough);
+        base.font_descriptor.extend(&self.font_descriptor);
+        base
+    }
+
     pub(crate) fn to_piet_attrs(&self, env: &Env) -> Vec<(Range<usize>, PietAttr)> {
         let mut items = Vec::new();
         for Span { range, attr } in self.font_descriptor.iter() {
@@ -254,6 +299,13 @@ impl<T: Clone> SpanSet<T> {
         self.spans.retain(|span| !span.is_empty());
     }
 
+    /// Add all of the spans of `other`, on top of the existing ones.
+    fn extend(&mut self, other: &SpanSet<T>) {
+        for span in other.iter() {
+            self.add(span.clone());
+        }
+    }
+
     /// Edit the spans, inserting empty space into the changed region if needed.
     ///
     /// This is used to keep the spans up to date as edits occur in the buffer.
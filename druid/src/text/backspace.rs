@@ -195,12 +195,41 @@ fn backspace_offset(text: &impl EditableText, start: usize) -> usize {
 /// Calculate resulting offset for a backwards delete.
 ///
 /// This involves complicated logic to handle various special cases that
-/// are unique to backspace.
+/// are unique to backspace. At least the whole grapheme cluster before the
+/// caret is deleted, so that a combining accent is deleted with its base.
 #[allow(clippy::trivially_copy_pass_by_ref)]
 pub fn offset_for_delete_backwards(region: &Selection, text: &impl EditableText) -> usize {
     if !region.is_caret() {
         region.min()
     } else {
-        backspace_offset(text, region.active)
+        let grapheme_start = text.prev_grapheme_offset(region.active).unwrap_or(0);
+        backspace_offset(text, region.active).min(grapheme_start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAMILY: &str = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+
+    fn backspace(text: &str) -> String {
+        let offset = offset_for_delete_backwards(&Selection::caret(text.len()), &text.to_string());
+        text[..offset].to_string()
+    }
+
+    #[test]
+    fn backspace_deletes_graphemes() {
+        assert_eq!(backspace("abc"), "ab");
+        assert_eq!(backspace("cafe\u{301}"), "caf");
+        assert_eq!(backspace("a\r\n"), "a");
+        assert_eq!(backspace(&format!("a{}", FAMILY)), "a");
+        // A skin tone modifier belongs to the emoji before it.
+        assert_eq!(backspace("a\u{1F44B}\u{1F3FD}"), "a");
+        // Regional indicators are deleted in pairs.
+        assert_eq!(
+            backspace("\u{1F1E9}\u{1F1EA}\u{1F1EB}\u{1F1F7}"),
+            "\u{1F1E9}\u{1F1EA}"
+        );
     }
 }
@@ -0,0 +1,267 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Searching text, for find and replace.
+
+use std::ops::Range;
+
+use crate::{Data, Lens};
+
+/// What to search for in a text, and what to replace it with.
+///
+/// This is the state of a find bar; see [`TextBox::with_find`].
+///
+/// [`TextBox::with_find`]: crate::widget::TextBox::with_find
+#[derive(Debug, Clone, Default, PartialEq, Eq, Data, Lens)]
+pub struct FindQuery {
+    /// The text to search for, or a regular expression if `regex` is set.
+    pub query: String,
+    /// The text that replaces a match.
+    ///
+    /// In regex mode, `$1` or `${name}` insert the text of a capture group.
+    pub replacement: String,
+    /// Whether upper and lower case letters are different.
+    pub case_sensitive: bool,
+    /// Whether a match must not be next to letters, digits or underscores.
+    pub whole_word: bool,
+    /// Whether `query` is a regular expression.
+    ///
+    /// This requires the `regex` feature.
+    pub regex: bool,
+}
+
+/// A compiled [`FindQuery`].
+#[derive(Debug, Clone)]
+pub struct Finder {
+    query: FindQuery,
+    #[cfg(feature = "regex")]
+    regex: Option<regex::Regex>,
+}
+
+/// The reason a [`FindQuery`] can't be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindError {
+    /// The query is not a valid regular expression.
+    InvalidRegex(String),
+    /// The query is a regular expression, but the `regex` feature is disabled.
+    RegexUnsupported,
+}
+
+impl FindQuery {
+    /// Create a case-insensitive plain text query.
+    pub fn new(query: impl Into<String>) -> Self {
+        FindQuery {
+            query: query.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Prepare the query for searching.
+    pub fn compile(&self) -> Result<Finder, FindError> {
+        #[cfg(not(feature = "regex"))]
+        if self.regex {
+            return Err(FindError::RegexUnsupported);
+        }
+        Ok(Finder {
+            #[cfg(feature = "regex")]
+            regex: if self.regex {
+                let regex = regex::RegexBuilder::new(&self.query)
+                    .case_insensitive(!self.case_sensitive)
+                    .multi_line(true)
+                    .build()
+                    .map_err(|err| FindError::InvalidRegex(err.to_string()))?;
+                Some(regex)
+            } else {
+                None
+            },
+            query: self.clone(),
+        })
+    }
+}
+
+impl Finder {
+    /// The query this was compiled from.
+    pub fn query(&self) -> &FindQuery {
+        &self.query
+    }
+
+    /// The ranges of all matches in `text`, in order.
+    ///
+    /// Matches don't overlap; after a match, the search continues at its end.
+    /// A regular expression can have empty matches, such as `^` at the start
+    /// of each line, but never two at the same position.
+    pub fn matches(&self, text: &str) -> Vec<Range<usize>> {
+        let whole_word =
+            |range: &Range<usize>| !self.query.whole_word || is_whole_word(text, range);
+        #[cfg(feature = "regex")]
+        if let Some(regex) = &self.regex {
+            return regex
+                .find_iter(text)
+                .map(|found| found.range())
+                .filter(whole_word)
+                .collect();
+        }
+        if self.query.query.is_empty() {
+            return Vec::new();
+        }
+        let mut matches = Vec::new();
+        let mut start = 0;
+        while let Some(c) = text[start..].chars().next() {
+            match self.plain_match_len(&text[start..]) {
+                Some(len) if whole_word(&(start..start + len)) => {
+                    matches.push(start..start + len);
+                    start += len;
+                }
+                _ => start += c.len_utf8(),
+            }
+        }
+        matches
+    }
+
+    /// The text that replaces the match at `range`, one of [`matches`].
+    ///
+    /// [`matches`]: Finder::matches
+    #[cfg_attr(not(feature = "regex"), allow(unused_variables))]
+    pub fn replacement(&self, text: &str, range: Range<usize>) -> String {
+        #[cfg(feature = "regex")]
+        if let Some(regex) = &self.regex {
+            if let Some(captures) = regex.captures_at(text, range.start) {
+                if captures.get(0).map(|found| found.range()) == Some(range) {
+                    let mut replacement = String::new();
+                    captures.expand(&self.query.replacement, &mut replacement);
+                    return replacement;
+                }
+            }
+        }
+        self.query.replacement.clone()
+    }
+
+    /// `text` with every match replaced.
+    pub fn replace_all(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut end = 0;
+        for range in self.matches(text) {
+            result.push_str(&text[end..range.start]);
+            result.push_str(&self.replacement(text, range.clone()));
+            end = range.end;
+        }
+        result.push_str(&text[end..]);
+        result
+    }
+
+    /// The length of the match of a plain query at the start of `text`.
+    fn plain_match_len(&self, text: &str) -> Option<usize> {
+        let mut chars = text.char_indices();
+        for expected in self.query.query.chars() {
+            let (_, c) = chars.next()?;
+            let same = c == expected
+                || (!self.query.case_sensitive && c.to_lowercase().eq(expected.to_lowercase()));
+            if !same {
+                return None;
+            }
+        }
+        Some(chars.next().map(|(idx, _)| idx).unwrap_or(text.len()))
+    }
+}
+
+/// `true` if `range` isn't next to a letter, digit or underscore.
+fn is_whole_word(text: &str, range: &Range<usize>) -> bool {
+    let is_word = |c: Option<char>| matches!(c, Some(c) if c.is_alphanumeric() || c == '_');
+    !is_word(text[..range.start].chars().next_back()) && !is_word(text[range.end..].chars().next())
+}
+
+impl std::fmt::Display for FindError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FindError::InvalidRegex(err) => write!(f, "invalid regular expression: {}", err),
+            FindError::RegexUnsupported => {
+                write!(f, "regular expressions require the 'regex' feature")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FindError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    fn find(query: FindQuery, text: &str) -> Vec<Range<usize>> {
+        query.compile().unwrap().matches(text)
+    }
+
+    #[test]
+    fn plain_matches() {
+        assert_eq!(find(FindQuery::new("ab"), "ab xAb"), [0..2, 4..6]);
+        let case_sensitive = FindQuery {
+            case_sensitive: true,
+            ..FindQuery::new("ab")
+        };
+        assert_eq!(find(case_sensitive, "ab xAb ab"), [0..2, 7..9]);
+        // matches don't overlap
+        assert_eq!(find(FindQuery::new("aa"), "aaaa a"), [0..2, 2..4]);
+        // a rejected match doesn't hide a later one
+        let whole_word = FindQuery {
+            whole_word: true,
+            ..FindQuery::new("aa")
+        };
+        assert_eq!(find(whole_word, "aaa aa_ aa aa"), [8..10, 11..13]);
+        assert_eq!(
+            find(
+                FindQuery::new("\u{c9}t\u{e9}"),
+                "l'\u{e9}T\u{c9} \u{e9}t\u{e9}"
+            ),
+            [2..7, 8..13]
+        );
+        assert!(find(FindQuery::new(""), "abc").is_empty());
+
+        let finder = FindQuery {
+            replacement: "cd".into(),
+            ..FindQuery::new("ab")
+        };
+        assert_eq!(finder.compile().unwrap().replace_all("ab xAb"), "cd xcd");
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn regex_matches() {
+        let regex = |query: &str| FindQuery {
+            regex: true,
+            ..FindQuery::new(query)
+        };
+        assert_eq!(find(regex("a+"), "baa AAA"), [1..3, 4..7]);
+        // empty matches are at distinct positions, and not right after a match
+        assert_eq!(find(regex("^"), "ab\ncd"), [0..0, 3..3]);
+        assert_eq!(find(regex("x*"), "xxa"), [0..2, 3..3]);
+        let swap = FindQuery {
+            replacement: "$2 $1".into(),
+            ..regex(r"(\w+) (\w+)")
+        };
+        assert_eq!(
+            swap.compile().unwrap().replace_all("ab cd, ef gh"),
+            "cd ab, gh ef"
+        );
+        let quote = FindQuery {
+            replacement: "> ".into(),
+            ..regex("^")
+        };
+        assert_eq!(quote.compile().unwrap().replace_all("ab\ncd"), "> ab\n> cd");
+        assert!(matches!(
+            regex("(").compile(),
+            Err(FindError::InvalidRegex(_))
+        ));
+    }
+}
@@ -118,6 +118,37 @@ pub struct ParseFormatter<T> {
     fmt_fn: Box<dyn Fn(&T) -> String>,
 }
 
+/// A [`Formatter`] that arranges input according to a mask, such as
+/// `(###) ###-####` for a phone number.
+///
+/// Each `#` in the mask is a slot that accepts a single digit; every other
+/// character is a literal separator that is inserted automatically as the
+/// user types. Characters that don't fit the next slot are rejected, and
+/// deleting a separator also deletes the digit in front of it.
+///
+/// By default the value is the masked text, including separators; use
+/// [`MaskFormatter::with_masked_value`] to only keep the digits. Partially
+/// filled masks are valid values.
+///
+/// [`Formatter`]: Formatter
+#[derive(Debug, Clone)]
+pub struct MaskFormatter {
+    mask: Vec<char>,
+    masked_value: bool,
+}
+
+/// The character in a mask that accepts a digit.
+const MASK_DIGIT: char = '#';
+
+/// An error returned by a [`MaskFormatter`] for input that does not fit the mask.
+#[derive(Debug, Clone)]
+enum MaskError {
+    /// The character does not fit the next slot.
+    Invalid(char),
+    /// There are more characters than slots.
+    TooLong,
+}
+
 impl Validation {
     /// Create a `Validation` indicating succes.
     pub fn success() -> Self {
@@ -208,6 +239,173 @@ where
     }
 }
 
+impl MaskFormatter {
+    /// Create a new `MaskFormatter` with the given mask.
+    pub fn new(mask: &str) -> Self {
+        MaskFormatter {
+            mask: mask.chars().collect(),
+            masked_value: true,
+        }
+    }
+
+    /// Builder-style method to set whether the value includes the separators.
+    ///
+    /// If `true` (the default) the value is the text as displayed; if `false`
+    /// it only contains the characters that were entered into slots.
+    pub fn with_masked_value(mut self, masked: bool) -> Self {
+        self.masked_value = masked;
+        self
+    }
+
+    fn is_literal(&self, c: char) -> bool {
+        c != MASK_DIGIT && self.mask.contains(&c)
+    }
+
+    /// Walk the input along the mask, returning the characters that fill slots,
+    /// along with the number of them that appear before each char position in
+    /// the input.
+    ///
+    /// Separators that are missing from the input are skipped over, so that
+    /// typing a digit where a separator is expected works as expected.
+    fn parse(&self, input: &str) -> Result<(Vec<char>, Vec<usize>), MaskError> {
+        let mut slots = Vec::new();
+        let mut slots_before = vec![0];
+        let mut mask = self.mask.iter().copied().peekable();
+        for c in input.chars() {
+            loop {
+                match mask.peek() {
+                    None => return Err(MaskError::TooLong),
+                    Some(&MASK_DIGIT) if c.is_ascii_digit() => {
+                        slots.push(c);
+                        mask.next();
+                        break;
+                    }
+                    // A separator typed in the wrong place; drop it.
+                    Some(&MASK_DIGIT) if self.is_literal(c) => break,
+                    Some(&MASK_DIGIT) => return Err(MaskError::Invalid(c)),
+                    Some(&literal) => {
+                        mask.next();
+                        if literal == c {
+                            break;
+                        }
+                    }
+                }
+            }
+            slots_before.push(slots.len());
+        }
+        Ok((slots, slots_before))
+    }
+
+    /// Lay out the slot characters according to the mask.
+    ///
+    /// Separators are only emitted when followed by a slot character, so that
+    /// deleting the last character also removes any separators before it.
+    fn apply(&self, slots: &[char]) -> String {
+        let mut out = String::new();
+        let mut slots = slots.iter();
+        let mut remaining = slots.len();
+        for &m in &self.mask {
+            if remaining == 0 {
+                break;
+            }
+            if m == MASK_DIGIT {
+                out.push(*slots.next().unwrap());
+                remaining -= 1;
+            } else {
+                out.push(m);
+            }
+        }
+        out
+    }
+
+    /// The byte offset in `text` (produced by [`apply`]) just after the `n`th slot.
+    ///
+    /// [`apply`]: MaskFormatter::apply
+    fn offset_after_slots(&self, text: &str, n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+        let mut seen = 0;
+        for ((offset, c), m) in text.char_indices().zip(self.mask.iter().copied()) {
+            if m == MASK_DIGIT {
+                seen += 1;
+                if seen == n {
+                    return offset + c.len_utf8();
+                }
+            }
+        }
+        text.len()
+    }
+}
+
+impl Formatter<String> for MaskFormatter {
+    fn format(&self, value: &String) -> String {
+        match self.parse(value) {
+            Ok((slots, _)) => self.apply(&slots),
+            Err(_) => value.clone(),
+        }
+    }
+
+    fn validate_partial_input(&self, input: &str, sel: &Selection) -> Validation {
+        let (mut slots, slots_before) = match self.parse(input) {
+            Ok(parsed) => parsed,
+            Err(e) => return Validation::failure(e),
+        };
+        let char_pos = |offset: usize| input[..offset.min(input.len())].chars().count();
+        let mut anchor = slots_before[char_pos(sel.anchor)];
+        let mut active = slots_before[char_pos(sel.active)];
+        let mut text = self.apply(&slots);
+
+        // If a separator was just deleted, it would come straight back;
+        // delete the slot character in front of it instead.
+        if sel.is_caret() && active > 0 && text.len() > input.len() {
+            let (before, after) = input.split_at(sel.active);
+            if let Some(removed) = text
+                .strip_prefix(before)
+                .and_then(|rest| rest.strip_suffix(after))
+            {
+                if removed.chars().count() == 1 && removed.chars().all(|c| self.is_literal(c)) {
+                    slots.remove(active - 1);
+                    active -= 1;
+                    anchor = active;
+                    text = self.apply(&slots);
+                }
+            }
+        }
+
+        if text == input {
+            return Validation::success();
+        }
+        let selection = Selection::new(
+            self.offset_after_slots(&text, anchor),
+            self.offset_after_slots(&text, active),
+        );
+        Validation::success()
+            .change_text(text)
+            .change_selection(selection)
+    }
+
+    fn value(&self, input: &str) -> Result<String, ValidationError> {
+        let (slots, _) = self.parse(input).map_err(ValidationError::new)?;
+        if self.masked_value {
+            Ok(self.apply(&slots))
+        } else {
+            Ok(slots.into_iter().collect())
+        }
+    }
+}
+
+impl std::fmt::Display for MaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MaskError::Invalid(c) => write!(f, "'{}' does not fit the mask", c),
+            MaskError::TooLong => write!(f, "input is longer than the mask"),
+        }
+    }
+}
+
+impl std::error::Error for MaskError {}
+
 impl std::fmt::Display for ValidationError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}", &self.inner)
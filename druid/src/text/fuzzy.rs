@@ -0,0 +1,187 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fuzzy matching, for filtering lists as the user types.
+//!
+//! A query matches a candidate if the grapheme clusters of the query appear
+//! in the candidate in the same order, but not necessarily next to each
+//! other, ignoring case; `"opf"` matches `"Open File"`. A match gets a higher
+//! score when its clusters are consecutive, or at the start of words, and a
+//! lower one when there are many characters between them.
+//!
+//! The score only depends on the query and the candidate, so sorting by
+//! score with a stable sort keeps candidates that match equally well in
+//! their original order.
+//!
+//! # Examples
+//!
+//! ```
+//! use druid::text::fuzzy::fuzzy_match;
+//!
+//! let commands = ["Toggle Fullscreen", "Files", "Format Selection"];
+//! let mut matches: Vec<_> = commands
+//!     .iter()
+//!     .filter_map(|command| Some((fuzzy_match("fs", command)?.0, *command)))
+//!     .collect();
+//! matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+//! let sorted: Vec<_> = matches.iter().map(|(_, command)| *command).collect();
+//! assert_eq!(sorted, ["Format Selection", "Files", "Toggle Fullscreen"]);
+//! ```
+
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The score of each matched grapheme cluster.
+const MATCH_SCORE: i64 = 16;
+/// The bonus for a match at the start of a word.
+const WORD_START_BONUS: i64 = 12;
+/// The bonus for a match right after the previous one.
+const CONSECUTIVE_BONUS: i64 = 10;
+/// The most that is taken off for the clusters before the first match.
+const MAX_LEADING_PENALTY: i64 = 10;
+
+/// Match `query` against `candidate`.
+///
+/// Returns the score of the best match, higher for better matches, and the
+/// byte offsets in `candidate` of the grapheme clusters that matched, in
+/// order. Every candidate matches an empty query, with a score of zero.
+///
+/// Use [`match_ranges`] to turn the offsets into ranges, for instance to
+/// show the matched text in bold.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<&str> = query.graphemes(true).collect();
+    let text: Vec<(usize, &str)> = candidate.grapheme_indices(true).collect();
+    let (m, n) = (query.len(), text.len());
+    if m == 0 {
+        return Some((0, Vec::new()));
+    }
+    if m > n {
+        return None;
+    }
+    let same = |a: &str, b: &str| a == b || a.to_lowercase() == b.to_lowercase();
+    let bonus = |j: usize| {
+        let first = |cluster: &str| cluster.chars().next().unwrap_or(' ');
+        let current = first(text[j].1);
+        match j.checked_sub(1).map(|prev| first(text[prev].1)) {
+            None => WORD_START_BONUS,
+            Some(prev) if !prev.is_alphanumeric() => WORD_START_BONUS,
+            Some(prev) if prev.is_lowercase() && current.is_uppercase() => WORD_START_BONUS,
+            Some(_) => 0,
+        }
+    };
+
+    // best[i][j] is the best score with query[i] matched at text[j], and
+    // from[i][j] is where query[i - 1] was matched for that score.
+    let mut best = vec![vec![None; n]; m];
+    let mut from = vec![vec![0; n]; m];
+    for j in 0..n {
+        if same(query[0], text[j].1) {
+            let leading = (j as i64).min(MAX_LEADING_PENALTY);
+            best[0][j] = Some(MATCH_SCORE + bonus(j) - leading);
+        }
+    }
+    for i in 1..m {
+        for j in i..n {
+            if !same(query[i], text[j].1) {
+                continue;
+            }
+            for k in i - 1..j {
+                if let Some(previous) = best[i - 1][k] {
+                    let gap = if k + 1 == j {
+                        CONSECUTIVE_BONUS
+                    } else {
+                        -((j - k - 1) as i64)
+                    };
+                    let score = previous + MATCH_SCORE + bonus(j) + gap;
+                    if matches!(best[i][j], Some(current) if current >= score) {
+                        continue;
+                    }
+                    best[i][j] = Some(score);
+                    from[i][j] = k;
+                }
+            }
+        }
+    }
+
+    // the first of the best ends, so that ties favor earlier matches
+    let (mut j, score) = best[m - 1]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .fold(
+            None,
+            |found: Option<(usize, i64)>, (j, score)| match found {
+                Some((_, best)) if best >= score => found,
+                _ => Some((j, score)),
+            },
+        )?;
+    let mut positions = vec![j];
+    for i in (1..m).rev() {
+        j = from[i][j];
+        positions.push(j);
+    }
+    let offsets = positions.into_iter().rev().map(|j| text[j].0).collect();
+    Some((score, offsets))
+}
+
+/// The ranges of `candidate` covered by the grapheme clusters at `offsets`,
+/// as returned by [`fuzzy_match`], with adjacent clusters joined.
+pub fn match_ranges(candidate: &str, offsets: &[usize]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for &start in offsets {
+        let len = candidate
+            .get(start..)
+            .and_then(|rest| rest.graphemes(true).next())
+            .map_or(0, str::len);
+        let end = start + len;
+        match ranges.last_mut() {
+            Some(last) if last.end == start => last.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(query: &str, candidate: &str) -> Vec<Range<usize>> {
+        let (_, offsets) = fuzzy_match(query, candidate).unwrap();
+        match_ranges(candidate, &offsets)
+    }
+
+    #[test]
+    fn matches_and_ranges() {
+        assert_eq!(fuzzy_match("", "Open"), Some((0, vec![])));
+        assert_eq!(fuzzy_match("xo", "Open"), None);
+        assert_eq!(fuzzy_match("OPEf", "Open File").unwrap().1, [0, 1, 2, 5]);
+        assert_eq!(ranges("OPEf", "Open File"), [0..3, 5..6]);
+        assert_eq!(ranges("fs", "Format Selection"), [0..1, 7..8]);
+        // the word start is preferred over the first occurrence
+        assert_eq!(ranges("br", "abc Bar"), [4..5, 6..7]);
+        assert_eq!(ranges("bs", "fooBarBaz Size"), [3..4, 10..11]);
+    }
+
+    #[test]
+    fn unicode() {
+        assert_eq!(ranges("\u{e9}\u{c9}", "\u{c9}T\u{c9}"), [0..2, 3..5]);
+        // a cluster with a combining accent is matched as a whole
+        let combining = "e\u{301}t\u{e9}";
+        assert_eq!(fuzzy_match("e", combining), None);
+        assert_eq!(ranges("e\u{301}\u{e9}", combining), [0..3, 4..6]);
+        assert_eq!(ranges("\u{c9}\u{e9}", "\u{c9}x\u{e9}"), [0..2, 3..5]);
+    }
+}
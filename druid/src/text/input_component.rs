@@ -436,7 +436,8 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
         } else {
             (size.width - width).max(0.0)
         };
-        self.borrow_mut().update_alignment_offset(extra_width);
+        let rtl = env.get(theme::LAYOUT_DIRECTION).is_rtl();
+        self.borrow_mut().update_alignment_offset(extra_width, rtl);
         let baseline_off = metrics.size.height - metrics.first_baseline;
         ctx.set_baseline_offset(baseline_off);
         size
@@ -461,8 +462,8 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
         let composition = self.borrow().composition_range();
         let sel_rects = self.borrow().layout.rects_for_range(selection.range());
         if let Some(composition) = composition {
-            // I believe selection should always be contained in composition range while composing?
-            assert!(composition.start <= selection.anchor && composition.end >= selection.active);
+            // The selection is usually inside the composition, but some IMEs place the caret
+            // just outside of it; both are drawn as underlines either way.
             let comp_rects = self.borrow().layout.rects_for_range(composition);
             for region in comp_rects {
                 let y = region.max_y().floor();
@@ -537,6 +538,11 @@ impl<T> EditSession<T> {
         self.pending_ime_invalidation.take()
     }
 
+    /// The offset from the window origin to the origin of the text layout.
+    fn text_origin(&self) -> Vec2 {
+        self.origin.to_vec2() + Vec2::new(self.alignment_offset, 0.0)
+    }
+
     fn take_external_text_change(&mut self) -> Option<T> {
         self.external_text_change.take()
     }
@@ -570,8 +576,13 @@ impl<T> EditSession<T> {
         }
     }
 
-    fn update_alignment_offset(&mut self, extra_width: f64) {
-        self.alignment_offset = match self.alignment {
+    fn update_alignment_offset(&mut self, extra_width: f64, rtl: bool) {
+        let alignment = if rtl {
+            text::mirror_alignment(self.alignment)
+        } else {
+            self.alignment
+        };
+        self.alignment_offset = match alignment {
             TextAlignment::Start | TextAlignment::Justified => 0.0,
             TextAlignment::End => extra_width,
             TextAlignment::Center => extra_width / 2.0,
@@ -596,8 +607,13 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     /// Sets the clipboard to the contents of the current selection.
     ///
     /// Returns `true` if the clipboard was set, and `false` if not (indicating)
-    /// that the selection was empty.)
+    /// that the selection was empty, or that the text is [masked].)
+    ///
+    /// [masked]: TextLayout::set_mask
     pub fn set_clipboard(&self) -> bool {
+        if self.layout.mask().is_some() {
+            return false;
+        }
         if let Some(text) = self
             .layout
             .text()
@@ -774,6 +790,17 @@ impl<T: TextStorage + EditableText> EditSession<T> {
         self.scroll_to_selection_end(false);
     }
 
+    /// The rects covering the text in `range`, relative to the origin of the
+    /// component.
+    pub fn rects_for_range(&self, range: Range<usize>) -> Vec<Rect> {
+        let offset = Vec2::new(self.alignment_offset, 0.0);
+        self.layout
+            .rects_for_range(range)
+            .into_iter()
+            .map(|rect| rect + offset)
+            .collect()
+    }
+
     /// Returns a line suitable for drawing a standard cursor.
     pub fn cursor_line_for_text_position(&self, pos: usize) -> Line {
         let line = self.layout.cursor_line_for_text_position(pos);
@@ -801,6 +828,10 @@ impl<T: TextStorage + EditableText> EditSession<T> {
             Some(layout) => layout,
             None => return pos..pos,
         };
+        // Masked text doesn't reveal its word boundaries; it's a single word.
+        if self.layout.mask().is_some() {
+            return 0..self.layout.text().map(|text| text.len()).unwrap_or(pos);
+        }
 
         let line_n = layout.hit_test_text_position(pos).line;
         let lm = layout.line_metric(line_n).unwrap();
@@ -822,6 +853,8 @@ impl<T: TextStorage + EditableText> EditSession<T> {
         {
             self.update_pending_invalidation(ImeInvalidation::Reset);
             self.layout.set_text(new_data.clone());
+            // the composition doesn't survive the text being changed by someone else
+            self.composition_range = None;
         }
         if self.layout.needs_rebuild_after_update(ctx) {
             ctx.request_layout();
@@ -844,7 +877,9 @@ impl<T: TextStorage> EditSessionHandle<T> {
 
 impl<T: TextStorage + EditableText> InputHandler for EditSessionHandle<T> {
     fn selection(&self) -> Selection {
-        self.inner.borrow().selection
+        // changes made while the lock is held are only applied when it is released
+        let inner = self.inner.borrow();
+        inner.external_selection_change.unwrap_or(inner.selection)
     }
 
     fn set_selection(&mut self, selection: Selection) {
@@ -873,46 +908,64 @@ impl<T: TextStorage + EditableText> InputHandler for EditSessionHandle<T> {
     }
 
     fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        let selection = self.selection();
+        let new_selection = if selection.max() <= range.start {
+            selection
+        } else if selection.min() >= range.end {
+            let shift = |idx: usize| idx - range.len() + text.len();
+            Selection::new(shift(selection.anchor), shift(selection.active))
+        } else {
+            Selection::caret(range.start + text.len())
+        };
         self.text.edit(range, text);
-        self.inner.borrow_mut().external_text_change = Some(self.text.clone());
+        let mut inner = self.inner.borrow_mut();
+        inner.external_text_change = Some(self.text.clone());
+        inner.external_selection_change = Some(new_selection);
+        inner.composition_range = None;
     }
 
     fn hit_test_point(&self, point: Point) -> crate::piet::HitTestPoint {
-        self.inner
-            .borrow()
+        let inner = self.inner.borrow();
+        let point = point - inner.text_origin();
+        inner
             .layout
             .layout()
-            .map(|layout| layout.hit_test_point(point))
+            .map(|layout| {
+                let mut hit = layout.hit_test_point(point);
+                hit.idx = inner.layout.text_position_for_point(point);
+                hit
+            })
             .unwrap_or_default()
     }
 
     fn line_range(&self, index: usize, _affinity: druid_shell::text::Affinity) -> Range<usize> {
         let inner = self.inner.borrow();
         let layout = inner.layout.layout().unwrap();
-        let hit = layout.hit_test_text_position(index);
-        let metric = layout.line_metric(hit.line).unwrap();
-        metric.range()
+        let hit = layout.hit_test_text_position(inner.layout.layout_offset(index));
+        let range = layout.line_metric(hit.line).unwrap().range();
+        inner.layout.text_offset(range.start)..inner.layout.text_offset(range.end)
     }
 
     fn bounding_box(&self) -> Option<Rect> {
-        let size = self.inner.borrow().layout.size();
-        Some(Rect::from_origin_size(self.inner.borrow().origin, size))
+        let inner = self.inner.borrow();
+        Some(Rect::from_origin_size(inner.origin, inner.layout.size()))
     }
 
     fn slice_bounding_box(&self, range: Range<usize>) -> Option<Rect> {
-        let origin = self.inner.borrow().origin;
-        let layout = &self.inner.borrow().layout;
+        let inner = self.inner.borrow();
+        let origin = inner.text_origin();
+        let layout = &inner.layout;
         if range.is_empty() {
             let hit = layout
                 .layout()
-                .map(|l| l.hit_test_text_position(range.start))?;
+                .map(|l| l.hit_test_text_position(layout.layout_offset(range.start)))?;
             let line = layout.layout().and_then(|l| l.line_metric(hit.line))?;
             let x = hit.point.x;
             Some(Rect::new(x, line.y_offset, x, line.y_offset + line.height))
         } else {
             layout.rects_for_range(range).first().copied()
         }
-        .map(|rect| rect + origin.to_vec2())
+        .map(|rect| rect + origin)
     }
 
     fn handle_action(&mut self, action: TextAction) {
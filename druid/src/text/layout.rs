@@ -17,13 +17,15 @@
 use std::ops::Range;
 use std::rc::Rc;
 
-use super::{EnvUpdateCtx, Link, TextStorage};
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+use super::{EnvUpdateCtx, Link, LinkState, TextStorage};
 use crate::kurbo::{Line, Point, Rect, Size};
 use crate::piet::{
     Color, PietText, PietTextLayout, Text as _, TextAlignment, TextAttribute, TextLayout as _,
     TextLayoutBuilder as _,
 };
-use crate::{Env, FontDescriptor, KeyOrValue, PaintCtx, RenderContext, UpdateCtx};
+use crate::{theme, Env, FontDescriptor, KeyOrValue, PaintCtx, RenderContext, UpdateCtx};
 
 /// A component for displaying text on screen.
 ///
@@ -57,7 +59,11 @@ pub struct TextLayout<T> {
     wrap_width: f64,
     alignment: TextAlignment,
     links: Rc<[(Rect, usize)]>,
+    hovered_link: Option<usize>,
+    visited_links: Vec<usize>,
     text_is_rtl: bool,
+    /// If set, each grapheme of the text is displayed as this character.
+    mask: Option<char>,
 }
 
 /// Metrics describing the layout text.
@@ -88,7 +94,10 @@ impl<T> TextLayout<T> {
             wrap_width: f64::INFINITY,
             alignment: Default::default(),
             links: Rc::new([]),
+            hovered_link: None,
+            visited_links: Vec::new(),
             text_is_rtl: false,
+            mask: None,
         }
     }
 
@@ -155,6 +164,66 @@ impl<T> TextLayout<T> {
         }
     }
 
+    /// Set a character to display in place of each grapheme of the text.
+    ///
+    /// This is used to hide the contents of password fields. Text positions
+    /// passed to and returned from this layout still refer to the underlying
+    /// text; they are translated to and from the masked text internally.
+    pub fn set_mask(&mut self, mask: Option<char>) {
+        if mask != self.mask {
+            self.mask = mask;
+            self.layout = None;
+        }
+    }
+
+    /// The character displayed in place of the text, if it is masked.
+    pub fn mask(&self) -> Option<char> {
+        self.mask
+    }
+
+    /// Set the link that the pointer is over, as an index into the text's
+    /// [`links`].
+    ///
+    /// The hovered link is drawn with its [`LinkState::Hovered`] style; this
+    /// requires the layout to be rebuilt, which is why this returns `true`
+    /// if the hovered link changed.
+    ///
+    /// [`links`]: super::TextStorage::links
+    pub fn set_hovered_link(&mut self, link: Option<usize>) -> bool {
+        if self.hovered_link == link {
+            return false;
+        }
+        self.hovered_link = link;
+        self.layout = None;
+        true
+    }
+
+    /// Mark a link as visited, using its index into the text's [`links`].
+    ///
+    /// Visited links are drawn with their [`LinkState::Visited`] style, until
+    /// the text changes. Returns `true` if the layout needs to be rebuilt.
+    ///
+    /// [`links`]: super::TextStorage::links
+    pub fn set_link_visited(&mut self, link: usize) -> bool {
+        if self.visited_links.contains(&link) {
+            return false;
+        }
+        self.visited_links.push(link);
+        self.layout = None;
+        true
+    }
+
+    /// Returns the [`LinkState`] of the link with the given index.
+    pub fn link_state(&self, link: usize) -> LinkState {
+        if self.hovered_link == Some(link) {
+            LinkState::Hovered
+        } else if self.visited_links.contains(&link) {
+            LinkState::Visited
+        } else {
+            LinkState::Normal
+        }
+    }
+
     /// Returns `true` if this layout's text appears to be right-to-left.
     ///
     /// See [`piet::util::first_strong_rtl`] for more information.
@@ -191,6 +260,8 @@ impl<T: TextStorage> TextLayout<T> {
             self.text_is_rtl = crate::piet::util::first_strong_rtl(text.as_str());
             self.text = Some(text);
             self.layout = None;
+            self.hovered_link = None;
+            self.visited_links.clear();
         }
     }
 
@@ -251,10 +322,42 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn text_position_for_point(&self, point: Point) -> usize {
         self.layout
             .as_ref()
-            .map(|layout| layout.hit_test_point(point).idx)
+            .map(|layout| {
+                let pos = self.text_offset(layout.hit_test_point(point).idx);
+                self.snap_to_grapheme(pos, point)
+            })
             .unwrap_or_default()
     }
 
+    /// If `pos` is inside a grapheme cluster, move it to the boundary of the
+    /// cluster that is closest to `point`.
+    ///
+    /// Depending on the platform, hit testing may return positions inside
+    /// clusters such as emoji sequences, where the caret must not be placed.
+    fn snap_to_grapheme(&self, pos: usize, point: Point) -> usize {
+        let text = match &self.text {
+            Some(text) => text.as_str(),
+            None => return pos,
+        };
+        let mut cursor = GraphemeCursor::new(pos, text.len(), true);
+        if cursor.is_boundary(text, 0).unwrap_or(true) {
+            return pos;
+        }
+        let prev = cursor.prev_boundary(text, 0).ok().flatten().unwrap_or(0);
+        let mut cursor = GraphemeCursor::new(pos, text.len(), true);
+        let next = cursor
+            .next_boundary(text, 0)
+            .ok()
+            .flatten()
+            .unwrap_or(text.len());
+        let distance = |pos| (self.point_for_text_position(pos).x - point.x).abs();
+        if distance(next) < distance(prev) {
+            next
+        } else {
+            prev
+        }
+    }
+
     /// Given the utf-8 position of a character boundary in the underlying text,
     /// return the `Point` (relative to this object's origin) representing the
     /// boundary of the containing grapheme.
@@ -265,7 +368,11 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn point_for_text_position(&self, text_pos: usize) -> Point {
         self.layout
             .as_ref()
-            .map(|layout| layout.hit_test_text_position(text_pos).point)
+            .map(|layout| {
+                layout
+                    .hit_test_text_position(self.layout_offset(text_pos))
+                    .point
+            })
             .unwrap_or_default()
     }
 
@@ -278,7 +385,10 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn rects_for_range(&self, range: Range<usize>) -> Vec<Rect> {
         self.layout
             .as_ref()
-            .map(|layout| layout.rects_for_range(range))
+            .map(|layout| {
+                layout
+                    .rects_for_range(self.layout_offset(range.start)..self.layout_offset(range.end))
+            })
             .unwrap_or_default()
     }
 
@@ -292,8 +402,8 @@ impl<T: TextStorage> TextLayout<T> {
         self.layout
             .as_ref()
             .map(|layout| {
-                let p1 = layout.hit_test_text_position(range.start);
-                let p2 = layout.hit_test_text_position(range.end);
+                let p1 = layout.hit_test_text_position(self.layout_offset(range.start));
+                let p2 = layout.hit_test_text_position(self.layout_offset(range.end));
                 let line_metric = layout.line_metric(p1.line).unwrap();
                 // heuristic; 1/5 of height is a rough guess at the descender pos?
                 let y_pos = line_metric.baseline + (line_metric.height / 5.0);
@@ -308,7 +418,7 @@ impl<T: TextStorage> TextLayout<T> {
         self.layout
             .as_ref()
             .map(|layout| {
-                let pos = layout.hit_test_text_position(text_pos);
+                let pos = layout.hit_test_text_position(self.layout_offset(text_pos));
                 let line_metrics = layout.line_metric(pos.line).unwrap();
                 let p1 = (pos.point.x, line_metrics.y_offset);
                 let p2 = (pos.point.x, (line_metrics.y_offset + line_metrics.height));
@@ -325,13 +435,23 @@ impl<T: TextStorage> TextLayout<T> {
     ///
     /// [`Link`]: super::attribute::Link
     pub fn link_for_pos(&self, pos: Point) -> Option<&Link> {
-        let (_, i) = self
-            .links
-            .iter()
-            .rfind(|(hit_box, _)| hit_box.contains(pos))?;
-
+        let i = self.link_index_for_pos(pos)?;
         let text = self.text()?;
-        text.links().get(*i)
+        text.links().get(i)
+    }
+
+    /// Returns the index into the text's [`links`] of the link at the provided
+    /// point (relative to the layout's origin), if there is one.
+    ///
+    /// The point is mapped to the ranges of the links using the boxes of their
+    /// text in the layout.
+    ///
+    /// [`links`]: super::TextStorage::links
+    pub fn link_index_for_pos(&self, pos: Point) -> Option<usize> {
+        self.links
+            .iter()
+            .rfind(|(hit_box, _)| hit_box.contains(pos))
+            .map(|(_, i)| *i)
     }
 
     /// Called during the containing widgets `update` method; this text object
@@ -343,6 +463,7 @@ impl<T: TextStorage> TextLayout<T> {
         if ctx.env_changed() && self.layout.is_some() {
             let rebuild = ctx.env_key_changed(&self.font)
                 || ctx.env_key_changed(&self.text_color)
+                || ctx.env_key_changed(&theme::LAYOUT_DIRECTION)
                 || self
                     .text_size_override
                     .as_ref()
@@ -385,18 +506,42 @@ impl<T: TextStorage> TextLayout<T> {
                     font
                 };
 
-                let builder = factory
-                    .new_text_layout(text.clone())
+                let builder = match self.mask {
+                    Some(mask) => {
+                        let graphemes = text.as_str().graphemes(true).count();
+                        factory.new_text_layout(mask.to_string().repeat(graphemes))
+                    }
+                    None => factory.new_text_layout(text.clone()),
+                };
+                // The platform aligns text relative to its own direction, so
+                // left-to-right text in a right-to-left layout is mirrored.
+                let alignment = if env.get(theme::LAYOUT_DIRECTION).is_rtl() && !self.text_is_rtl {
+                    mirror_alignment(self.alignment)
+                } else {
+                    self.alignment
+                };
+                let builder = builder
                     .max_width(self.wrap_width)
-                    .alignment(self.alignment)
+                    .alignment(alignment)
                     .font(descriptor.family.clone(), descriptor.size)
                     .default_attribute(descriptor.weight)
                     .default_attribute(descriptor.style)
                     .default_attribute(TextAttribute::TextColor(color));
-                let layout = text.add_attributes(builder, env).build().unwrap();
+                // The attributes and links of the text don't apply to the mask.
+                let layout = if self.mask.is_some() {
+                    builder.build().unwrap()
+                } else {
+                    text.add_attributes_with_links(builder, env, &|i| self.link_state(i))
+                        .build()
+                        .unwrap()
+                };
 
-                self.links = text
-                    .links()
+                let links = if self.mask.is_some() {
+                    &[][..]
+                } else {
+                    text.links()
+                };
+                self.links = links
                     .iter()
                     .enumerate()
                     .flat_map(|(i, link)| {
@@ -412,6 +557,38 @@ impl<T: TextStorage> TextLayout<T> {
         }
     }
 
+    /// Translate a position in the text to the corresponding position in the
+    /// inner layout, which differ if the text is [masked].
+    ///
+    /// [masked]: TextLayout::set_mask
+    pub(crate) fn layout_offset(&self, text_pos: usize) -> usize {
+        match (self.mask, &self.text) {
+            (Some(mask), Some(text)) => {
+                let text = text.as_str();
+                let graphemes = text[..text_pos.min(text.len())].graphemes(true).count();
+                graphemes * mask.len_utf8()
+            }
+            _ => text_pos,
+        }
+    }
+
+    /// Translate a position in the inner layout to the corresponding position
+    /// in the text; the inverse of [`layout_offset`].
+    ///
+    /// [`layout_offset`]: TextLayout::layout_offset
+    pub(crate) fn text_offset(&self, layout_pos: usize) -> usize {
+        match (self.mask, &self.text) {
+            (Some(mask), Some(text)) => {
+                let text = text.as_str();
+                text.grapheme_indices(true)
+                    .nth(layout_pos / mask.len_utf8())
+                    .map(|(i, _)| i)
+                    .unwrap_or(text.len())
+            }
+            _ => layout_pos,
+        }
+    }
+
     ///  Draw the layout at the provided `Point`.
     ///
     ///  The origin of the layout is the top-left corner.
@@ -458,3 +635,110 @@ impl<T: TextStorage> Default for TextLayout<T> {
         Self::new()
     }
 }
+
+/// Swap the [`Start`] and [`End`] alignments, which is how they apply in a
+/// right-to-left layout.
+///
+/// [`Start`]: TextAlignment::Start
+/// [`End`]: TextAlignment::End
+pub(crate) fn mirror_alignment(alignment: TextAlignment) -> TextAlignment {
+    match alignment {
+        TextAlignment::Start => TextAlignment::End,
+        TextAlignment::End => TextAlignment::Start,
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use std::cell::Cell;
+    use test_log::test;
+
+    #[test]
+    fn masked_positions() {
+        let mut layout = TextLayout::<String>::from_text("ae\u{301}z");
+        layout.set_mask(Some('*'));
+        // The combining accent is part of the second grapheme.
+        assert_eq!(layout.layout_offset(1), 1);
+        assert_eq!(layout.layout_offset(4), 2);
+        assert_eq!(layout.text_offset(2), 4);
+        assert_eq!(layout.text_offset(3), 5);
+
+        layout.set_mask(Some('\u{2022}'));
+        assert_eq!(layout.layout_offset(5), 9);
+        assert_eq!(layout.text_offset(6), 4);
+
+        layout.set_mask(None);
+        assert_eq!(layout.layout_offset(4), 4);
+    }
+
+    #[test]
+    fn masked_text_is_measured_by_mask() {
+        let widths = Rc::new(Cell::new((0.0, 0.0, 0.0)));
+        let widths_ = widths.clone();
+        let widget = ModularWidget::new(()).layout_fn(move |_, ctx, bc, _: &(), env| {
+            let mut width = |text: &str, mask| {
+                let mut layout = TextLayout::<String>::from_text(text);
+                layout.set_mask(mask);
+                layout.rebuild_if_needed(ctx.text(), env);
+                layout.point_for_text_position(text.len()).x
+            };
+            widths_.set((
+                width("iii", None),
+                width("iii", Some('\u{2022}')),
+                width("WWW", Some('\u{2022}')),
+            ));
+            bc.min()
+        });
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let (plain, masked_narrow, masked_wide) = widths.get();
+            assert_eq!(masked_narrow, masked_wide);
+            assert_ne!(plain, masked_narrow);
+        });
+    }
+
+    #[test]
+    fn carets_stay_on_grapheme_boundaries() {
+        use crate::text::{movement, Direction, Movement, Selection};
+
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let text = format!("a{}e\u{301}z", family);
+        let boundaries: Vec<usize> = text
+            .grapheme_indices(true)
+            .map(|(i, _)| i)
+            .chain(Some(text.len()))
+            .collect();
+
+        let widget = ModularWidget::new(()).layout_fn(move |_, ctx, bc, _: &(), env| {
+            let mut layout = TextLayout::<String>::from_text(text.clone());
+            layout.rebuild_if_needed(ctx.text(), env);
+
+            let width = layout.size().width;
+            for x in 0..=(width.ceil() as usize) {
+                let pos = layout.text_position_for_point(Point::new(x as f64, 5.0));
+                assert!(boundaries.contains(&pos), "{} is inside a grapheme", pos);
+            }
+
+            let right = Movement::Grapheme(Direction::Right);
+            let mut selection = Selection::caret(0);
+            let mut carets = Vec::new();
+            for _ in 0..4 {
+                selection = movement(right, selection, &layout, false);
+                carets.push(selection.active);
+            }
+            assert_eq!(carets, &boundaries[1..]);
+            bc.min()
+        });
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+        });
+    }
+}
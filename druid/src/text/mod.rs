@@ -14,9 +14,11 @@
 
 //! Editing and displaying text.
 
+mod annotation;
 mod attribute;
 mod backspace;
 mod editable_text;
+mod find;
 mod font_descriptor;
 
 #[deprecated(since = "0.8.0", note = "use types from druid::text module instead")]
@@ -26,12 +28,14 @@ pub mod format;
 // format private
 #[path = "format.rs"]
 mod format_priv;
+pub mod fuzzy;
 mod input_component;
 mod input_methods;
 mod layout;
 mod movement;
 mod rich_text;
 mod storage;
+mod undo;
 
 pub use crate::piet::{FontFamily, FontStyle, FontWeight, TextAlignment};
 pub use druid_shell::text::{
@@ -39,11 +43,15 @@ pub use druid_shell::text::{
     Selection, VerticalMovement, WritingDirection,
 };
 
-pub use self::attribute::{Attribute, AttributeSpans, Link};
+pub use self::annotation::{AnnotationClick, AnnotationStyle, TextAnnotation};
+pub use self::attribute::{Attribute, AttributeSpans, Link, LinkState};
 pub use self::backspace::offset_for_delete_backwards;
 pub use self::editable_text::{EditableText, EditableTextCursor, StringCursor};
+pub use self::find::{FindError, FindQuery, Finder};
 pub use self::font_descriptor::FontDescriptor;
-pub use self::format_priv::{Formatter, ParseFormatter, Validation, ValidationError};
+pub use self::format_priv::{
+    Formatter, MaskFormatter, ParseFormatter, Validation, ValidationError,
+};
 pub use self::layout::{LayoutMetrics, TextLayout};
 pub use self::movement::movement;
 pub use input_component::{EditSession, TextComponent};
@@ -52,3 +60,5 @@ pub use rich_text::{AttributesAdder, RichText, RichTextBuilder};
 pub use storage::{ArcStr, EnvUpdateCtx, TextStorage};
 
 pub(crate) use input_methods::TextFieldRegistration;
+pub(crate) use layout::mirror_alignment;
+pub(crate) use undo::{Snapshot, UndoHistory};
@@ -37,6 +37,8 @@ pub fn movement<T: EditableText + TextStorage>(
     layout: &TextLayout<T>,
     modify: bool,
 ) -> Selection {
+    // Positions in the inner layout differ from those in the text if the text is masked.
+    let text_layout = layout;
     let (text, layout) = match (layout.text(), layout.layout()) {
         (Some(text), Some(layout)) => (text, layout),
         _ => {
@@ -71,7 +73,7 @@ pub fn movement<T: EditableText + TextStorage>(
             }
         }
         Movement::Vertical(VerticalMovement::LineUp) => {
-            let cur_pos = layout.hit_test_text_position(s.active);
+            let cur_pos = layout.hit_test_text_position(text_layout.layout_offset(s.active));
             let h_pos = s.h_pos.unwrap_or(cur_pos.point.x);
             if cur_pos.line == 0 {
                 (0, Some(h_pos))
@@ -80,7 +82,10 @@ pub fn movement<T: EditableText + TextStorage>(
                 let point_above = Point::new(h_pos, cur_pos.point.y - lm.height);
                 let up_pos = layout.hit_test_point(point_above);
                 if up_pos.is_inside {
-                    (up_pos.idx, Some(h_pos))
+                    (
+                        text_layout.text_position_for_point(point_above),
+                        Some(h_pos),
+                    )
                 } else {
                     // because we can't specify affinity, moving up when h_pos
                     // is wider than both the current line and the previous line
@@ -88,12 +93,12 @@ pub fn movement<T: EditableText + TextStorage>(
                     // current line; so we handle this as a special-case.
                     let lm_prev = layout.line_metric(cur_pos.line.saturating_sub(1)).unwrap();
                     let up_pos = lm_prev.end_offset - lm_prev.trailing_whitespace;
-                    (up_pos, Some(h_pos))
+                    (text_layout.text_offset(up_pos), Some(h_pos))
                 }
             }
         }
         Movement::Vertical(VerticalMovement::LineDown) => {
-            let cur_pos = layout.hit_test_text_position(s.active);
+            let cur_pos = layout.hit_test_text_position(text_layout.layout_offset(s.active));
             let h_pos = s.h_pos.unwrap_or(cur_pos.point.x);
             if cur_pos.line == layout.line_count() - 1 {
                 (text.len(), Some(h_pos))
@@ -102,8 +107,10 @@ pub fn movement<T: EditableText + TextStorage>(
                 // may not work correctly for point sizes below 1.0
                 let y_below = lm.y_offset + lm.height + 1.0;
                 let point_below = Point::new(h_pos, y_below);
-                let up_pos = layout.hit_test_point(point_below);
-                (up_pos.idx, Some(point_below.x))
+                (
+                    text_layout.text_position_for_point(point_below),
+                    Some(point_below.x),
+                )
             }
         }
         Movement::Vertical(VerticalMovement::DocumentStart) => (0, None),
@@ -113,17 +120,27 @@ pub fn movement<T: EditableText + TextStorage>(
         Movement::ParagraphEnd => (text.next_line_break(s.active), None),
 
         Movement::Line(d) => {
-            let hit = layout.hit_test_text_position(s.active);
+            let hit = layout.hit_test_text_position(text_layout.layout_offset(s.active));
             let lm = layout.line_metric(hit.line).unwrap();
-            let offset = if d.is_upstream_for_direction(writing_direction) {
-                lm.start_offset
+            if d.is_upstream_for_direction(writing_direction) {
+                (text_layout.text_offset(lm.start_offset), None)
             } else {
-                lm.end_offset - lm.trailing_whitespace
-            };
-            (offset, None)
+                let offset = text_layout.text_offset(lm.end_offset - lm.trailing_whitespace);
+                // A line wrapped inside a word ends where the next line starts,
+                // and a caret there would be drawn on the next line; so we stop
+                // before the last grapheme instead.
+                let wraps_here = lm.trailing_whitespace == 0 && hit.line + 1 < layout.line_count();
+                match text.prev_grapheme_offset(offset) {
+                    Some(before) if wraps_here => (before, None),
+                    _ => (offset, None),
+                }
+            }
         }
         Movement::Word(d) if d.is_upstream_for_direction(writing_direction) => {
-            let offset = if s.is_caret() || modify {
+            let offset = if text_layout.mask().is_some() {
+                // Masked text doesn't reveal its word boundaries.
+                0
+            } else if s.is_caret() || modify {
                 text.prev_word_offset(s.active).unwrap_or(0)
             } else {
                 s.min()
@@ -131,7 +148,9 @@ pub fn movement<T: EditableText + TextStorage>(
             (offset, None)
         }
         Movement::Word(_) => {
-            let offset = if s.is_caret() || modify {
+            let offset = if text_layout.mask().is_some() {
+                text.len()
+            } else if s.is_caret() || modify {
                 text.next_word_offset(s.active).unwrap_or(s.active)
             } else {
                 s.max()
@@ -17,13 +17,13 @@
 use std::ops::{Range, RangeBounds};
 use std::sync::Arc;
 
-use super::attribute::Link;
+use super::attribute::{Link, LinkState};
 use super::{Attribute, AttributeSpans, EnvUpdateCtx, TextStorage};
 use crate::piet::{
     util, Color, FontFamily, FontStyle, FontWeight, PietTextLayoutBuilder, TextLayoutBuilder,
     TextStorage as PietTextStorage,
 };
-use crate::{ArcStr, Command, Data, Env, FontDescriptor, KeyOrValue};
+use crate::{theme, ArcStr, Command, Data, Env, FontDescriptor, KeyOrValue};
 
 /// Text with optional style spans.
 #[derive(Clone, Debug, Data)]
@@ -73,6 +73,17 @@ impl RichText {
         let range = util::resolve_range(range, self.buffer.len());
         Arc::make_mut(&mut self.attrs).add(range, attr);
     }
+
+    /// The attribute spans with the styles of the links added underneath.
+    fn link_styled_attrs(&self, link_state: &dyn Fn(usize) -> LinkState) -> AttributeSpans {
+        let mut link_attrs = AttributeSpans::new();
+        for (i, link) in self.links.iter().enumerate() {
+            for attr in link_state(i).attributes() {
+                link_attrs.add(link.range(), attr);
+            }
+        }
+        self.attrs.layered_over(link_attrs)
+    }
 }
 
 impl PietTextStorage for RichText {
@@ -82,24 +93,38 @@ impl PietTextStorage for RichText {
 }
 
 impl TextStorage for RichText {
-    fn add_attributes(
-        &self,
-        mut builder: PietTextLayoutBuilder,
-        env: &Env,
-    ) -> PietTextLayoutBuilder {
-        for (range, attr) in self.attrs.to_piet_attrs(env) {
-            builder = builder.range_attribute(range, attr);
-        }
-        builder
+    fn add_attributes(&self, builder: PietTextLayoutBuilder, env: &Env) -> PietTextLayoutBuilder {
+        self.add_attributes_with_links(builder, env, &|_| LinkState::Normal)
     }
 
     fn env_update(&self, ctx: &EnvUpdateCtx) -> bool {
         self.attrs.env_update(ctx)
+            || !self.links.is_empty()
+                && (ctx.env_key_changed(&theme::LINK_COLOR)
+                    || ctx.env_key_changed(&theme::LINK_VISITED_COLOR)
+                    || ctx.env_key_changed(&theme::LINK_HOVER_COLOR))
     }
 
     fn links(&self) -> &[Link] {
         &self.links
     }
+
+    fn add_attributes_with_links(
+        &self,
+        mut builder: PietTextLayoutBuilder,
+        env: &Env,
+        link_state: &dyn Fn(usize) -> LinkState,
+    ) -> PietTextLayoutBuilder {
+        let attrs = if self.links.is_empty() {
+            self.attrs.to_piet_attrs(env)
+        } else {
+            self.link_styled_attrs(link_state).to_piet_attrs(env)
+        };
+        for (range, attr) in attrs {
+            builder = builder.range_attribute(range, attr);
+        }
+        builder
+    }
 }
 
 /// A builder for creating [`RichText`] objects.
@@ -246,7 +271,11 @@ impl AttributesAdder<'_> {
 
     /// Add a [`Link`] attribute.
     ///
+    /// The link is styled according to its [`LinkState`], unless other
+    /// attributes are set on the same text.
+    ///
     /// [`Link`]: super::attribute::Link
+    /// [`LinkState`]: super::attribute::LinkState
     pub fn link(&mut self, command: impl Into<Command>) -> &mut Self {
         self.rich_text_builder
             .links
@@ -254,3 +283,44 @@ impl AttributesAdder<'_> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piet::TextAttribute;
+    use crate::Selector;
+    use test_log::test;
+
+    fn color_at(attrs: &[(Range<usize>, TextAttribute)], pos: usize) -> Option<Color> {
+        attrs.iter().rev().find_map(|(range, attr)| match attr {
+            TextAttribute::TextColor(color) if range.contains(&pos) => Some(color.clone()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn links_are_styled_by_state() {
+        let env = theme::add_to_env(Env::empty());
+        let mut builder = RichTextBuilder::new();
+        builder.push("go ");
+        builder.push("here").link(Selector::NOOP);
+        builder.push(" or ");
+        builder
+            .push("there")
+            .text_color(Color::RED)
+            .link(Selector::NOOP);
+        let text = builder.build();
+
+        let attrs = text
+            .link_styled_attrs(&|i| [LinkState::Hovered, LinkState::Visited][i])
+            .to_piet_attrs(&env);
+        assert_eq!(color_at(&attrs, 0), None);
+        assert_eq!(color_at(&attrs, 4), Some(env.get(theme::LINK_HOVER_COLOR)));
+        assert!(attrs.iter().any(
+            |(range, attr)| *range == (3..7) && matches!(attr, TextAttribute::Underline(true))
+        ));
+        // Explicit attributes win over the link style.
+        assert_eq!(color_at(&attrs, 12), Some(Color::RED));
+        assert!(attrs.windows(2).all(|w| w[0].0.start <= w[1].0.start));
+    }
+}
@@ -20,7 +20,7 @@ use crate::env::KeyLike;
 use crate::piet::{PietTextLayoutBuilder, TextStorage as PietTextStorage};
 use crate::{Data, Env};
 
-use super::attribute::Link;
+use super::attribute::{Link, LinkState};
 use crate::UpdateCtx;
 
 /// A type that represents text that can be displayed.
@@ -52,6 +52,24 @@ pub trait TextStorage: PietTextStorage + Data {
     fn links(&self) -> &[Link] {
         &[]
     }
+
+    /// Like [`add_attributes`], but also styles each of this text's [`links`]
+    /// according to its [`LinkState`].
+    ///
+    /// `link_state` is called with the index of each link. The default
+    /// implementation ignores the links and calls [`add_attributes`].
+    ///
+    /// [`add_attributes`]: TextStorage::add_attributes
+    /// [`links`]: TextStorage::links
+    #[allow(unused_variables)]
+    fn add_attributes_with_links(
+        &self,
+        builder: PietTextLayoutBuilder,
+        env: &Env,
+        link_state: &dyn Fn(usize) -> LinkState,
+    ) -> PietTextLayoutBuilder {
+        self.add_attributes(builder, env)
+    }
 }
 
 /// Provides information about keys change for more fine grained invalidation
@@ -0,0 +1,176 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Undo and redo for editable text.
+
+use std::collections::VecDeque;
+
+use super::{Selection, TextStorage};
+
+/// The number of edits an [`UndoHistory`] remembers.
+const MAX_UNDO_STEPS: usize = 100;
+
+/// The state of some text at one point in its history.
+#[derive(Debug, Clone)]
+pub(crate) struct Snapshot<T> {
+    pub text: T,
+    pub selection: Selection,
+}
+
+/// The edit history of a text field.
+///
+/// The owner records a [`Snapshot`] of the text before each edit; undoing
+/// restores it. Consecutive typing is coalesced into a single step, which is
+/// ended by anything other than inserting text at the caret, such as moving
+/// the caret or deleting.
+#[derive(Debug, Clone)]
+pub(crate) struct UndoHistory<T> {
+    undo: VecDeque<Snapshot<T>>,
+    redo: Vec<Snapshot<T>>,
+    /// While typing is being coalesced, the caret position after the last edit.
+    typing_caret: Option<usize>,
+    /// The text after the last edit or restore, used to notice external changes.
+    current: Option<T>,
+}
+
+impl<T: TextStorage> UndoHistory<T> {
+    pub fn new() -> Self {
+        UndoHistory {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            typing_caret: None,
+            current: None,
+        }
+    }
+
+    /// Record an edit from `before` to the text `after`, with the selection `selection`.
+    pub fn record(&mut self, before: Snapshot<T>, after: &T, selection: Selection) {
+        let inserted = after.as_str().len() as isize - before.text.as_str().len() as isize;
+        let is_typing = before.selection.is_caret()
+            && selection.is_caret()
+            && inserted > 0
+            && selection.active as isize == before.selection.active as isize + inserted;
+
+        let coalesce = is_typing && self.typing_caret == Some(before.selection.active);
+        if !coalesce {
+            if self.undo.len() == MAX_UNDO_STEPS {
+                self.undo.pop_front();
+            }
+            self.undo.push_back(before);
+        }
+        self.redo.clear();
+        self.typing_caret = if is_typing {
+            Some(selection.active)
+        } else {
+            None
+        };
+        self.current = Some(after.clone());
+    }
+
+    /// End the current group of typing, for instance because the caret moved.
+    pub fn break_coalescing(&mut self) {
+        self.typing_caret = None;
+    }
+
+    /// Forget the history if `text` isn't the text it last saw, because it was
+    /// changed by someone else.
+    pub fn sync(&mut self, text: &T) {
+        if let Some(current) = &self.current {
+            if !current.same(text) {
+                self.undo.clear();
+                self.redo.clear();
+                self.typing_caret = None;
+                self.current = None;
+            }
+        }
+    }
+
+    /// Undo the last edit, returning the state to restore.
+    ///
+    /// `current` is the present state, which is restored by a later [`redo`].
+    ///
+    /// [`redo`]: UndoHistory::redo
+    pub fn undo(&mut self, current: Snapshot<T>) -> Option<Snapshot<T>> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        self.restored(previous)
+    }
+
+    /// Redo the last undone edit, returning the state to restore.
+    pub fn redo(&mut self, current: Snapshot<T>) -> Option<Snapshot<T>> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+        self.restored(next)
+    }
+
+    fn restored(&mut self, snapshot: Snapshot<T>) -> Option<Snapshot<T>> {
+        self.typing_caret = None;
+        self.current = Some(snapshot.text.clone());
+        Some(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(text: &str, caret: usize) -> Snapshot<String> {
+        Snapshot {
+            text: text.to_string(),
+            selection: Selection::caret(caret),
+        }
+    }
+
+    #[test]
+    fn typing_is_coalesced() {
+        let mut history = UndoHistory::new();
+        history.record(snapshot("", 0), &"a".into(), Selection::caret(1));
+        history.record(snapshot("a", 1), &"ab".into(), Selection::caret(2));
+        // Deleting ends the group, and is a step of its own.
+        history.record(snapshot("ab", 2), &"a".into(), Selection::caret(1));
+        history.record(snapshot("a", 1), &"ac".into(), Selection::caret(2));
+        history.break_coalescing();
+        history.record(snapshot("ac", 2), &"acd".into(), Selection::caret(3));
+
+        let mut current = snapshot("acd", 3);
+        let mut texts = Vec::new();
+        while let Some(previous) = history.undo(current.clone()) {
+            texts.push(previous.text.clone());
+            current = previous;
+        }
+        assert_eq!(texts, ["ac", "a", "ab", ""]);
+
+        let restored = history.redo(current).unwrap();
+        assert_eq!(restored.text, "ab");
+        assert_eq!(restored.selection, Selection::caret(2));
+    }
+
+    #[test]
+    fn history_is_bounded_and_synced() {
+        let mut history = UndoHistory::new();
+        let mut text = String::new();
+        for i in 0..MAX_UNDO_STEPS + 10 {
+            history.break_coalescing();
+            let before = snapshot(&text, i);
+            text.push('x');
+            history.record(before, &text, Selection::caret(i + 1));
+        }
+        assert_eq!(history.undo.len(), MAX_UNDO_STEPS);
+
+        history.sync(&text);
+        assert_eq!(history.undo.len(), MAX_UNDO_STEPS);
+        history.sync(&"changed elsewhere".to_string());
+        assert!(history.undo(snapshot("", 0)).is_none());
+    }
+}
@@ -20,7 +20,11 @@ use crate::kurbo::RoundedRectRadii;
 
 use crate::piet::Color;
 
-use crate::{Env, FontDescriptor, FontFamily, FontStyle, FontWeight, Insets, Key};
+use crate::widget::LayoutDirection;
+use crate::{
+    ColorScheme, Data, Env, FontDescriptor, FontFamily, FontStyle, FontWeight, Insets, Key,
+    ValueType,
+};
 
 pub const WINDOW_BACKGROUND_COLOR: Key<Color> =
     Key::new("org.linebender.druid.theme.window_background_color");
@@ -38,6 +42,10 @@ pub const PROGRESS_BAR_RADIUS: Key<RoundedRectRadii> =
     Key::new("org.linebender.druid.theme.progress_bar_radius");
 pub const BACKGROUND_LIGHT: Key<Color> = Key::new("org.linebender.druid.theme.background_light");
 pub const BACKGROUND_DARK: Key<Color> = Key::new("org.linebender.druid.theme.background_dark");
+pub const DISABLED_BACKGROUND_LIGHT: Key<Color> =
+    Key::new("org.linebender.druid.theme.disabled_background_light");
+pub const DISABLED_BACKGROUND_DARK: Key<Color> =
+    Key::new("org.linebender.druid.theme.disabled_background_dark");
 pub const FOREGROUND_LIGHT: Key<Color> = Key::new("org.linebender.druid.theme.foreground_light");
 pub const FOREGROUND_DARK: Key<Color> = Key::new("org.linebender.druid.theme.foreground_dark");
 pub const DISABLED_FOREGROUND_LIGHT: Key<Color> =
@@ -64,6 +72,11 @@ pub const SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR: Key<Color> =
     Key::new("org.linebender.druid.theme.selection_color_inactive");
 pub const SELECTION_TEXT_COLOR: Key<Color> =
     Key::new("org.linebender.druid.theme.selection_text_color");
+/// The background color of selected items in a [`SelectableList`].
+///
+/// [`SelectableList`]: crate::widget::SelectableList
+pub const SELECTED_ITEM_BACKGROUND_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.selected_item_background_color");
 pub const CURSOR_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.cursor_color");
 
 pub const TEXT_SIZE_NORMAL: Key<f64> = Key::new("org.linebender.druid.theme.text_size_normal");
@@ -91,6 +104,86 @@ pub const TEXTBOX_BORDER_RADIUS: Key<RoundedRectRadii> =
 pub const TEXTBOX_BORDER_WIDTH: Key<f64> =
     Key::new("org.linebender.druid.theme.textbox_border_width");
 pub const TEXTBOX_INSETS: Key<Insets> = Key::new("org.linebender.druid.theme.textbox_insets");
+/// The border color of a text box whose contents are invalid.
+pub const TEXTBOX_ERROR_BORDER_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.textbox_error_border_color");
+/// The background of the line-number gutter of a [`TextBox::with_gutter`].
+///
+/// [`TextBox::with_gutter`]: crate::widget::TextBox::with_gutter
+pub const TEXTBOX_GUTTER_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.textbox_gutter_color");
+/// The color of the line numbers in the gutter of a text box.
+pub const TEXTBOX_LINE_NUMBER_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.textbox_line_number_color");
+/// The highlight behind the line containing the cursor, in a text box with a gutter.
+pub const TEXTBOX_CURRENT_LINE_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.textbox_current_line_color");
+/// The highlight behind the [matches] of a find query in a text box.
+///
+/// [matches]: crate::widget::TextBox::set_highlights
+pub const TEXTBOX_MATCH_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.textbox_match_color");
+/// The highlight behind the current match of a find query in a text box.
+pub const TEXTBOX_CURRENT_MATCH_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.textbox_current_match_color");
+
+/// The color of links in text.
+pub const LINK_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.link_color");
+/// The color of links in text that have been clicked before.
+pub const LINK_VISITED_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.link_visited_color");
+/// The color of links in text while the pointer is over them.
+pub const LINK_HOVER_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.link_hover_color");
+
+/// The font of source code, in a [`CodeView`].
+///
+/// [`CodeView`]: crate::widget::CodeView
+pub const CODE_FONT: Key<FontDescriptor> = Key::new("org.linebender.druid.theme.code_font");
+/// The color of keywords in source code.
+pub const CODE_KEYWORD_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.code_keyword_color");
+/// The color of type names in source code.
+pub const CODE_TYPE_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.code_type_color");
+/// The color of function names in source code.
+pub const CODE_FUNCTION_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.code_function_color");
+/// The color of string literals in source code.
+pub const CODE_STRING_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.code_string_color");
+/// The color of numeric literals in source code.
+pub const CODE_NUMBER_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.code_number_color");
+/// The color of comments in source code.
+pub const CODE_COMMENT_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.code_comment_color");
+/// The color of the line numbers next to source code.
+pub const CODE_LINE_NUMBER_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.code_line_number_color");
+/// The color of the series of a [`Chart`].
+///
+/// [`Chart`]: crate::widget::Chart
+pub const CHART_SERIES_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.chart_series_color");
+/// The color of the gridlines of a [`Chart`].
+///
+/// [`Chart`]: crate::widget::Chart
+pub const CHART_GRID_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.chart_grid_color");
+/// The background of lines that were added, in a [`DiffView`].
+///
+/// [`DiffView`]: crate::widget::DiffView
+pub const DIFF_ADDED_BACKGROUND_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.diff_added_background_color");
+/// The background of lines that were removed, in a [`DiffView`].
+///
+/// [`DiffView`]: crate::widget::DiffView
+pub const DIFF_REMOVED_BACKGROUND_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.diff_removed_background_color");
+
+/// The color of the ring drawn around a widget that has keyboard focus.
+pub const FOCUS_RING_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.focus_ring_color");
+
+/// The color of the line drawn by a [`Divider`].
+///
+/// [`Divider`]: crate::widget::Divider
+pub const DIVIDER_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.divider_color");
 
 /// The default horizontal spacing between widgets.
 pub const WIDGET_PADDING_HORIZONTAL: Key<f64> =
@@ -120,6 +213,232 @@ pub const SCROLLBAR_EDGE_WIDTH: Key<f64> =
 /// scrollbar's primary axis.
 pub const SCROLLBAR_MIN_SIZE: Key<f64> = Key::new("org.linebender.theme.scrollbar_min_size");
 
+/// How long, in milliseconds, the pointer has to rest on a widget before its
+/// tooltip is shown.
+pub const TOOLTIP_DELAY: Key<u64> = Key::new("org.linebender.druid.theme.tooltip_delay");
+/// The background color of tooltips.
+pub const TOOLTIP_BACKGROUND_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.tooltip_background_color");
+
+/// The background color of informational toasts.
+pub const TOAST_INFO_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.toast_info_color");
+/// The background color of warning toasts.
+pub const TOAST_WARNING_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.toast_warning_color");
+/// The background color of error toasts.
+pub const TOAST_ERROR_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.toast_error_color");
+
+/// The background color of the count of a [`Badge`].
+///
+/// [`Badge`]: crate::widget::Badge
+pub const BADGE_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.badge_color");
+/// The text color of the count of a [`Badge`].
+///
+/// [`Badge`]: crate::widget::Badge
+pub const BADGE_TEXT_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.badge_text_color");
+
+/// The color of a [`Wire`].
+///
+/// [`Wire`]: crate::widget::Wire
+pub const WIRE_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.wire_color");
+/// How far the ends of a [`Wire`] bend out horizontally, as a fraction of the
+/// horizontal distance between them; 0 is a straight line.
+///
+/// [`Wire`]: crate::widget::Wire
+pub const WIRE_CURVATURE: Key<f64> = Key::new("org.linebender.druid.theme.wire_curvature");
+
+/// The underline color of an [`AnnotationStyle::Error`] text annotation.
+///
+/// [`AnnotationStyle::Error`]: crate::text::AnnotationStyle::Error
+pub const ANNOTATION_ERROR_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.annotation_error_color");
+/// The underline color of an [`AnnotationStyle::Warning`] text annotation.
+///
+/// [`AnnotationStyle::Warning`]: crate::text::AnnotationStyle::Warning
+pub const ANNOTATION_WARNING_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.annotation_warning_color");
+/// The underline color of an [`AnnotationStyle::Info`] text annotation.
+///
+/// [`AnnotationStyle::Info`]: crate::text::AnnotationStyle::Info
+pub const ANNOTATION_INFO_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.annotation_info_color");
+
+/// Whether content is laid out left-to-right or right-to-left.
+pub const LAYOUT_DIRECTION: Key<LayoutDirection> =
+    Key::new("org.linebender.druid.theme.layout_direction");
+
+/// A set of values for theme keys.
+///
+/// A theme can be applied to the root [`Env`] before launch, with
+/// [`AppLauncher::with_theme`], or while the application runs, by submitting
+/// the [`SET_THEME`] command. In the second case every widget receives an
+/// `update` with the new `Env`, and every window is repainted; widgets that
+/// cache values derived from theme keys should check
+/// [`UpdateCtx::env_key_changed`] to know when to recompute them.
+///
+/// [`Theme::dark`] contains all the standard keys in this module, and is the
+/// theme used by default. A theme doesn't have to set every key: the values
+/// it doesn't set are left unchanged when it is applied.
+///
+/// # Examples
+///
+/// ```
+/// use druid::theme::{self, Theme};
+/// use druid::Color;
+///
+/// let theme = Theme::light().with(theme::PRIMARY_DARK, Color::rgb8(0x80, 0x30, 0xb0));
+/// ```
+///
+/// [`AppLauncher::with_theme`]: crate::AppLauncher::with_theme
+/// [`SET_THEME`]: crate::commands::SET_THEME
+/// [`UpdateCtx::env_key_changed`]: crate::UpdateCtx::env_key_changed
+#[derive(Clone, Debug, Data)]
+pub struct Theme {
+    values: Env,
+}
+
+impl Theme {
+    /// Create a theme that doesn't set any keys.
+    pub fn empty() -> Theme {
+        Theme {
+            values: Env::empty(),
+        }
+    }
+
+    /// The default dark theme, with values for all the standard keys.
+    pub fn dark() -> Theme {
+        Theme {
+            values: add_to_env(Env::empty()),
+        }
+    }
+
+    /// A light theme, with values for all the standard keys.
+    pub fn light() -> Theme {
+        Theme::dark()
+            .with(WINDOW_BACKGROUND_COLOR, Color::grey8(0xee))
+            .with(TEXT_COLOR, Color::grey8(0x20))
+            .with(DISABLED_TEXT_COLOR, Color::grey8(0x8a))
+            .with(PLACEHOLDER_COLOR, Color::grey8(0x90))
+            .with(BACKGROUND_LIGHT, Color::WHITE)
+            .with(BACKGROUND_DARK, Color::grey8(0xf4))
+            .with(DISABLED_BACKGROUND_LIGHT, Color::grey8(0xf0))
+            .with(DISABLED_BACKGROUND_DARK, Color::grey8(0xe8))
+            .with(FOREGROUND_LIGHT, Color::grey8(0x28))
+            .with(FOREGROUND_DARK, Color::grey8(0x5a))
+            .with(DISABLED_FOREGROUND_LIGHT, Color::grey8(0xa0))
+            .with(DISABLED_FOREGROUND_DARK, Color::grey8(0xb8))
+            .with(BUTTON_DARK, Color::grey8(0xd8))
+            .with(BUTTON_LIGHT, Color::grey8(0xfa))
+            .with(DISABLED_BUTTON_DARK, Color::grey8(0xe4))
+            .with(DISABLED_BUTTON_LIGHT, Color::grey8(0xf2))
+            .with(BORDER_DARK, Color::grey8(0xc4))
+            .with(BORDER_LIGHT, Color::grey8(0x80))
+            .with(
+                SELECTED_TEXT_BACKGROUND_COLOR,
+                Color::rgb8(0xa8, 0xc8, 0xf0),
+            )
+            .with(SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR, Color::grey8(0xd0))
+            .with(SELECTION_TEXT_COLOR, Color::BLACK)
+            .with(
+                SELECTED_ITEM_BACKGROUND_COLOR,
+                Color::rgb8(0xcf, 0xe0, 0xf7),
+            )
+            .with(CURSOR_COLOR, Color::BLACK)
+            .with(DIVIDER_COLOR, Color::grey8(0xc8))
+            .with(LINK_COLOR, Color::rgb8(0x00, 0x66, 0xcc))
+            .with(LINK_VISITED_COLOR, Color::rgb8(0x7a, 0x4f, 0xc0))
+            .with(LINK_HOVER_COLOR, Color::rgb8(0x00, 0x88, 0xee))
+            .with(CODE_KEYWORD_COLOR, Color::rgb8(0xa6, 0x26, 0xa4))
+            .with(CODE_TYPE_COLOR, Color::rgb8(0xc1, 0x84, 0x01))
+            .with(CODE_FUNCTION_COLOR, Color::rgb8(0x40, 0x78, 0xf2))
+            .with(CODE_STRING_COLOR, Color::rgb8(0x50, 0xa1, 0x4f))
+            .with(CODE_NUMBER_COLOR, Color::rgb8(0x98, 0x68, 0x01))
+            .with(CODE_COMMENT_COLOR, Color::rgb8(0xa0, 0xa1, 0xa7))
+            .with(CODE_LINE_NUMBER_COLOR, Color::grey8(0x90))
+            .with(CHART_SERIES_COLOR, Color::rgb8(0x1f, 0x77, 0xb4))
+            .with(CHART_GRID_COLOR, Color::grey8(0xe0))
+            .with(DIFF_ADDED_BACKGROUND_COLOR, Color::rgb8(0xe6, 0xff, 0xec))
+            .with(DIFF_REMOVED_BACKGROUND_COLOR, Color::rgb8(0xff, 0xeb, 0xe9))
+            .with(SCROLLBAR_COLOR, Color::grey8(0x40))
+            .with(SCROLLBAR_BORDER_COLOR, Color::grey8(0xc0))
+            .with(TOOLTIP_BACKGROUND_COLOR, Color::rgb8(0xff, 0xfb, 0xe6))
+            .with(TOAST_INFO_COLOR, Color::rgb8(0xd4, 0xe5, 0xf6))
+            .with(TOAST_WARNING_COLOR, Color::rgb8(0xf8, 0xe8, 0xc0))
+            .with(TOAST_ERROR_COLOR, Color::rgb8(0xf6, 0xd2, 0xd2))
+            .with(BADGE_COLOR, Color::rgb8(0xd9, 0x30, 0x25))
+            .with(BADGE_TEXT_COLOR, Color::WHITE)
+            .with(WIRE_COLOR, Color::grey8(0x60))
+            .with(ANNOTATION_ERROR_COLOR, Color::rgb8(0xd9, 0x30, 0x25))
+            .with(ANNOTATION_WARNING_COLOR, Color::rgb8(0xc7, 0x8a, 0x00))
+            .with(ANNOTATION_INFO_COLOR, Color::rgb8(0x2a, 0x6f, 0xd6))
+            .with(TEXTBOX_GUTTER_COLOR, Color::grey8(0xf4))
+            .with(TEXTBOX_LINE_NUMBER_COLOR, Color::grey8(0x90))
+            .with(
+                TEXTBOX_CURRENT_LINE_COLOR,
+                Color::rgba8(0x00, 0x00, 0x00, 0x0a),
+            )
+            .with(TEXTBOX_MATCH_COLOR, Color::rgba8(0xff, 0xd0, 0x00, 0x60))
+            .with(
+                TEXTBOX_CURRENT_MATCH_COLOR,
+                Color::rgba8(0xff, 0x96, 0x00, 0xa0),
+            )
+    }
+
+    /// The light or dark theme, matching a [`ColorScheme`].
+    ///
+    /// Without a preference this is the dark theme, as the default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use druid::theme::Theme;
+    /// use druid::widget::Label;
+    /// use druid::{AppLauncher, Application, WindowDesc};
+    ///
+    /// let window = WindowDesc::new(Label::new("Hello"));
+    /// AppLauncher::with_window(window)
+    ///     .with_theme(Theme::for_color_scheme(Application::get_color_scheme()))
+    ///     .launch(())
+    ///     .expect("launch failed");
+    /// ```
+    pub fn for_color_scheme(scheme: ColorScheme) -> Theme {
+        match scheme {
+            ColorScheme::Light => Theme::light(),
+            ColorScheme::Dark | ColorScheme::NoPreference => Theme::dark(),
+        }
+    }
+
+    /// Builder-style method to set the value for a key.
+    pub fn with<V: ValueType>(mut self, key: Key<V>, value: impl Into<V>) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    /// Set the value for a key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the theme already has a value of a different type for the key.
+    pub fn set<V: ValueType>(&mut self, key: Key<V>, value: impl Into<V>) {
+        self.values.set(key, value);
+    }
+
+    /// Set all the values of this theme in `env`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `env` has a value of a different type for one of the keys.
+    pub fn apply(&self, env: &mut Env) {
+        env.set_all(&self.values);
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
 /// An initial theme.
 pub(crate) fn add_to_env(env: Env) -> Env {
     env.adding(WINDOW_BACKGROUND_COLOR, Color::rgb8(0x29, 0x29, 0x29))
@@ -131,6 +450,8 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(PROGRESS_BAR_RADIUS, 4.)
         .adding(BACKGROUND_LIGHT, Color::rgb8(0x3a, 0x3a, 0x3a))
         .adding(BACKGROUND_DARK, Color::rgb8(0x31, 0x31, 0x31))
+        .adding(DISABLED_BACKGROUND_LIGHT, Color::rgb8(0x2c, 0x2c, 0x2c))
+        .adding(DISABLED_BACKGROUND_DARK, Color::rgb8(0x28, 0x28, 0x28))
         .adding(FOREGROUND_LIGHT, Color::rgb8(0xf9, 0xf9, 0xf9))
         .adding(FOREGROUND_DARK, Color::rgb8(0xbf, 0xbf, 0xbf))
         .adding(DISABLED_FOREGROUND_LIGHT, Color::rgb8(0x89, 0x89, 0x89))
@@ -149,6 +470,10 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         )
         .adding(SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR, Color::grey8(0x74))
         .adding(SELECTION_TEXT_COLOR, Color::rgb8(0x00, 0x00, 0x00))
+        .adding(
+            SELECTED_ITEM_BACKGROUND_COLOR,
+            Color::rgb8(0x2e, 0x4f, 0x78),
+        )
         .adding(CURSOR_COLOR, Color::WHITE)
         .adding(TEXT_SIZE_NORMAL, 15.0)
         .adding(TEXT_SIZE_LARGE, 24.0)
@@ -158,6 +483,44 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(TEXTBOX_BORDER_RADIUS, 2.)
         .adding(TEXTBOX_BORDER_WIDTH, 1.)
         .adding(TEXTBOX_INSETS, Insets::new(4.0, 4.0, 4.0, 4.0))
+        .adding(TEXTBOX_ERROR_BORDER_COLOR, Color::rgb8(0xe0, 0x4b, 0x4b))
+        .adding(TEXTBOX_GUTTER_COLOR, Color::rgb8(0x33, 0x33, 0x33))
+        .adding(TEXTBOX_LINE_NUMBER_COLOR, Color::rgb8(0x80, 0x80, 0x80))
+        .adding(
+            TEXTBOX_CURRENT_LINE_COLOR,
+            Color::rgba8(0xff, 0xff, 0xff, 0x10),
+        )
+        .adding(TEXTBOX_MATCH_COLOR, Color::rgba8(0xff, 0xd0, 0x00, 0x40))
+        .adding(
+            TEXTBOX_CURRENT_MATCH_COLOR,
+            Color::rgba8(0xff, 0x96, 0x00, 0x80),
+        )
+        .adding(FOCUS_RING_COLOR, Color::rgb8(0x5c, 0xc4, 0xff))
+        .adding(DIVIDER_COLOR, Color::rgb8(0x5a, 0x5a, 0x5a))
+        .adding(LINK_COLOR, Color::rgb8(0x5c, 0xc4, 0xff))
+        .adding(LINK_VISITED_COLOR, Color::rgb8(0xb4, 0x8e, 0xf0))
+        .adding(LINK_HOVER_COLOR, Color::rgb8(0x8f, 0xd8, 0xff))
+        .adding(
+            CODE_FONT,
+            FontDescriptor::new(FontFamily::MONOSPACE).with_size(14.0),
+        )
+        .adding(CODE_KEYWORD_COLOR, Color::rgb8(0xc6, 0x78, 0xdd))
+        .adding(CODE_TYPE_COLOR, Color::rgb8(0xe5, 0xc0, 0x7b))
+        .adding(CODE_FUNCTION_COLOR, Color::rgb8(0x61, 0xaf, 0xef))
+        .adding(CODE_STRING_COLOR, Color::rgb8(0x98, 0xc3, 0x79))
+        .adding(CODE_NUMBER_COLOR, Color::rgb8(0xd1, 0x9a, 0x66))
+        .adding(CODE_COMMENT_COLOR, Color::rgb8(0x7f, 0x84, 0x8e))
+        .adding(CODE_LINE_NUMBER_COLOR, Color::rgb8(0x80, 0x80, 0x80))
+        .adding(CHART_SERIES_COLOR, Color::rgb8(0x5a, 0xa9, 0xe6))
+        .adding(CHART_GRID_COLOR, Color::grey8(0x48))
+        .adding(
+            DIFF_ADDED_BACKGROUND_COLOR,
+            Color::rgba8(0x2e, 0xa0, 0x43, 0x40),
+        )
+        .adding(
+            DIFF_REMOVED_BACKGROUND_COLOR,
+            Color::rgba8(0xf8, 0x51, 0x49, 0x40),
+        )
         .adding(SCROLLBAR_COLOR, Color::rgb8(0xff, 0xff, 0xff))
         .adding(SCROLLBAR_BORDER_COLOR, Color::rgb8(0x77, 0x77, 0x77))
         .adding(SCROLLBAR_MAX_OPACITY, 0.7)
@@ -167,6 +530,19 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(SCROLLBAR_MIN_SIZE, 45.)
         .adding(SCROLLBAR_RADIUS, 5.)
         .adding(SCROLLBAR_EDGE_WIDTH, 1.)
+        .adding(TOOLTIP_DELAY, 600u64)
+        .adding(TOOLTIP_BACKGROUND_COLOR, Color::rgb8(0x4a, 0x4a, 0x4a))
+        .adding(TOAST_INFO_COLOR, Color::rgb8(0x2b, 0x57, 0x80))
+        .adding(TOAST_WARNING_COLOR, Color::rgb8(0x85, 0x65, 0x1a))
+        .adding(TOAST_ERROR_COLOR, Color::rgb8(0x9a, 0x2e, 0x2e))
+        .adding(BADGE_COLOR, Color::rgb8(0xe5, 0x48, 0x3d))
+        .adding(BADGE_TEXT_COLOR, Color::WHITE)
+        .adding(WIRE_COLOR, Color::grey8(0xb0))
+        .adding(WIRE_CURVATURE, 0.5)
+        .adding(ANNOTATION_ERROR_COLOR, Color::rgb8(0xf0, 0x5a, 0x50))
+        .adding(ANNOTATION_WARNING_COLOR, Color::rgb8(0xe8, 0xb3, 0x3a))
+        .adding(ANNOTATION_INFO_COLOR, Color::rgb8(0x5c, 0x9c, 0xf5))
+        .adding(LAYOUT_DIRECTION, LayoutDirection::Ltr)
         .adding(WIDGET_PADDING_VERTICAL, 10.0)
         .adding(WIDGET_PADDING_HORIZONTAL, 8.0)
         .adding(WIDGET_CONTROL_COMPONENT_PADDING, 4.0)
@@ -0,0 +1,93 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An icon in the system tray.
+
+use crate::menu::MenuManager;
+use crate::{Data, Env, ImageBuf, Menu, WindowId};
+
+/// A description of an icon that lives in the system tray, for as long as
+/// the application runs.
+///
+/// The icon is added with [`AppLauncher::tray_icon`]. Clicks on the icon are
+/// reported with the [`TRAY_ICON_CLICKED`] and [`TRAY_ICON_DOUBLE_CLICKED`]
+/// commands, which are sent to the [`AppDelegate`] and to all windows. The
+/// icon can have a [`Menu`], which is shown when it is right-clicked; the menu
+/// is kept up to date with the application data, just like a window's menu.
+///
+/// The image and tooltip can be changed with the [`SET_TRAY_ICON`] and
+/// [`SET_TRAY_TOOLTIP`] commands. The icon is removed when the application
+/// quits. While it exists, closing the last window does not quit the
+/// application, so the menu should provide a way to do that, for instance
+/// with an item that submits [`QUIT_APP`].
+///
+/// Tray icons are currently supported on Windows and macOS.
+///
+/// ```no_run
+/// # use druid::{commands, AppLauncher, ImageBuf, Menu, MenuItem, TrayIcon, WindowDesc};
+/// # use druid::widget::Label;
+/// # let image = ImageBuf::empty();
+/// let tray = TrayIcon::new(image)
+///     .tooltip("My app")
+///     .menu(|_, _, _| Menu::empty().entry(MenuItem::new("Quit").command(commands::QUIT_APP)));
+/// AppLauncher::with_window(WindowDesc::new(Label::new("Hello")))
+///     .tray_icon(tray)
+///     .launch(())
+///     .unwrap();
+/// ```
+///
+/// [`AppLauncher::tray_icon`]: crate::AppLauncher::tray_icon
+/// [`AppDelegate`]: crate::AppDelegate
+/// [`TRAY_ICON_CLICKED`]: crate::commands::TRAY_ICON_CLICKED
+/// [`TRAY_ICON_DOUBLE_CLICKED`]: crate::commands::TRAY_ICON_DOUBLE_CLICKED
+/// [`SET_TRAY_ICON`]: crate::commands::SET_TRAY_ICON
+/// [`SET_TRAY_TOOLTIP`]: crate::commands::SET_TRAY_TOOLTIP
+/// [`QUIT_APP`]: crate::commands::QUIT_APP
+pub struct TrayIcon<T> {
+    pub(crate) icon: ImageBuf,
+    pub(crate) tooltip: String,
+    pub(crate) menu: Option<MenuManager<T>>,
+}
+
+impl<T: Data> TrayIcon<T> {
+    /// Create a new tray icon showing `icon`.
+    ///
+    /// The image should be small and roughly square; it is scaled to fit the
+    /// tray by the platform.
+    pub fn new(icon: ImageBuf) -> TrayIcon<T> {
+        TrayIcon {
+            icon,
+            tooltip: String::new(),
+            menu: None,
+        }
+    }
+
+    /// Builder-style method to set the text shown when hovering over the icon.
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = tooltip.into();
+        self
+    }
+
+    /// Builder-style method to set the menu shown when the icon is right-clicked.
+    ///
+    /// The closure is called with `None` as the window id, since the menu
+    /// doesn't belong to any window.
+    pub fn menu(
+        mut self,
+        menu: impl FnMut(Option<WindowId>, &T, &Env) -> Menu<T> + 'static,
+    ) -> Self {
+        self.menu = Some(MenuManager::new_for_tray(menu));
+        self
+    }
+}
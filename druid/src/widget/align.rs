@@ -16,7 +16,7 @@
 
 use crate::debug_state::DebugState;
 use crate::widget::prelude::*;
-use crate::{Data, Rect, Size, UnitPoint, WidgetPod};
+use crate::{theme, Data, Rect, Size, UnitPoint, WidgetPod};
 use tracing::{instrument, trace};
 
 /// A widget that aligns its child.
@@ -121,10 +121,13 @@ impl<T: Data> Widget<T> for Align<T> {
         my_size = bc.constrain(my_size);
         let extra_width = (my_size.width - size.width).max(0.);
         let extra_height = (my_size.height - size.height).max(0.);
-        let origin = self
+        let mut origin = self
             .align
-            .resolve(Rect::new(0., 0., extra_width, extra_height))
-            .expand();
+            .resolve(Rect::new(0., 0., extra_width, extra_height));
+        if env.get(theme::LAYOUT_DIRECTION).is_rtl() {
+            origin.x = extra_width - origin.x;
+        }
+        let origin = origin.expand();
         self.child.set_origin(ctx, data, env, origin);
 
         let my_insets = self.child.compute_parent_paint_insets(my_size);
@@ -0,0 +1,420 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that switches between two children with an animation.
+
+use std::time::Duration;
+
+use crate::debug_state::DebugState;
+use crate::kurbo::Vec2;
+use crate::widget::prelude::*;
+use crate::widget::Axis;
+use crate::{Data, Point, WidgetPod};
+use tracing::{instrument, trace};
+
+type ChildPod<T> = WidgetPod<T, Box<dyn Widget<T>>>;
+type BranchFn<T> = dyn Fn(&T, &Env) -> bool;
+
+/// The default duration of an [`AnimatedSwitcher`] transition.
+const DEFAULT_DURATION: Duration = Duration::from_millis(250);
+
+/// How an [`AnimatedSwitcher`] goes from one child to the other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SwitcherTransition {
+    /// Cross-fade: the old child fades out while the new one fades in.
+    ///
+    /// Both children are painted with [`PaintCtx::with_opacity`], so whatever
+    /// is behind the switcher shows through them.
+    Fade,
+    /// Slide the new child in along the given axis, pushing the old one out.
+    ///
+    /// The true branch comes in from the right (or bottom), the false branch
+    /// from the left (or top).
+    Slide(Axis),
+}
+
+/// A widget that switches between two possible child views, with an animation.
+///
+/// This is like [`Either`], except that when the closure changes its mind,
+/// the old child is replaced by the new one over a short [`transition`]
+/// instead of instantly. If the closure changes again before the transition is
+/// done, it is reversed from where it is.
+///
+/// While the transition runs both children are laid out, and the widget is as
+/// large as the larger of the two, unless [`animate_size`] is set. Only the new
+/// child receives pointer events.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::widget::{AnimatedSwitcher, Axis, Label, SwitcherTransition};
+/// use druid::Widget;
+///
+/// fn status() -> impl Widget<bool> {
+///     AnimatedSwitcher::new(
+///         |online: &bool, _| *online,
+///         Label::new("Online"),
+///         Label::new("Offline"),
+///     )
+///     .with_transition(SwitcherTransition::Slide(Axis::Vertical))
+///     .with_duration(Duration::from_millis(150))
+/// }
+/// ```
+///
+/// [`Either`]: crate::widget::Either
+/// [`transition`]: AnimatedSwitcher::with_transition
+/// [`animate_size`]: AnimatedSwitcher::animate_size
+pub struct AnimatedSwitcher<T> {
+    closure: Box<BranchFn<T>>,
+    true_branch: ChildPod<T>,
+    false_branch: ChildPod<T>,
+    current: bool,
+    transition: SwitcherTransition,
+    duration: Duration,
+    animate_size: bool,
+    /// How far the running transition has got, if there is one.
+    elapsed: Option<Duration>,
+}
+
+impl<T> AnimatedSwitcher<T> {
+    /// Create a new widget that switches between two views.
+    ///
+    /// The given closure is evaluated on data change. If its value is `true`, then
+    /// the `true_branch` widget is shown, otherwise `false_branch`.
+    pub fn new(
+        closure: impl Fn(&T, &Env) -> bool + 'static,
+        true_branch: impl Widget<T> + 'static,
+        false_branch: impl Widget<T> + 'static,
+    ) -> AnimatedSwitcher<T> {
+        AnimatedSwitcher {
+            closure: Box::new(closure),
+            true_branch: WidgetPod::new(true_branch).boxed(),
+            false_branch: WidgetPod::new(false_branch).boxed(),
+            current: false,
+            transition: SwitcherTransition::Fade,
+            duration: DEFAULT_DURATION,
+            animate_size: false,
+            elapsed: None,
+        }
+    }
+
+    /// Builder-style method to set the transition. The default is [`SwitcherTransition::Fade`].
+    pub fn with_transition(mut self, transition: SwitcherTransition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Builder-style method to set how long a transition takes. The default is 250ms.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Builder-style method to animate the size of the widget from the size of
+    /// the old child to the size of the new one.
+    pub fn animate_size(mut self, animate: bool) -> Self {
+        self.animate_size = animate;
+        self
+    }
+
+    /// Returns `true` while a transition is running.
+    pub fn is_animating(&self) -> bool {
+        self.elapsed.is_some()
+    }
+
+    /// The eased progress of the transition, from 0 (old child) to 1 (new child).
+    fn progress(&self) -> f64 {
+        let t = match self.elapsed {
+            Some(elapsed) if !self.duration.is_zero() => {
+                (elapsed.as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+            }
+            _ => 1.0,
+        };
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    fn current_widget(&mut self) -> &mut ChildPod<T> {
+        if self.current {
+            &mut self.true_branch
+        } else {
+            &mut self.false_branch
+        }
+    }
+
+    /// Returns the new and the old child.
+    fn both_widgets(&mut self) -> (&mut ChildPod<T>, &mut ChildPod<T>) {
+        if self.current {
+            (&mut self.true_branch, &mut self.false_branch)
+        } else {
+            (&mut self.false_branch, &mut self.true_branch)
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for AnimatedSwitcher<T> {
+    #[instrument(name = "AnimatedSwitcher", level = "trace", skip(self, ctx, event, data, env), fields(branch = self.current))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let (Some(elapsed), Event::AnimFrame(interval)) = (self.elapsed, event) {
+            // The first frame can have a large interval from earlier animations.
+            let interval = if elapsed.is_zero() {
+                Duration::from_millis(1)
+            } else {
+                Duration::from_nanos(*interval)
+            };
+            let elapsed = elapsed + interval;
+            if elapsed < self.duration {
+                self.elapsed = Some(elapsed);
+                ctx.request_anim_frame();
+            } else {
+                trace!("AnimatedSwitcher transition finished");
+                self.elapsed = None;
+            }
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+
+        if event.should_propagate_to_hidden() {
+            self.true_branch.event(ctx, event, data, env);
+            self.false_branch.event(ctx, event, data, env);
+        } else {
+            self.current_widget().event(ctx, event, data, env)
+        }
+    }
+
+    #[instrument(name = "AnimatedSwitcher", level = "trace", skip(self, ctx, event, data, env), fields(branch = self.current))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.current = (self.closure)(data, env);
+            self.elapsed = None;
+        }
+
+        if event.should_propagate_to_hidden() {
+            self.true_branch.lifecycle(ctx, event, data, env);
+            self.false_branch.lifecycle(ctx, event, data, env);
+        } else {
+            self.current_widget().lifecycle(ctx, event, data, env)
+        }
+    }
+
+    #[instrument(name = "AnimatedSwitcher", level = "trace", skip(self, ctx, _old_data, data, env), fields(branch = self.current))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let current = (self.closure)(data, env);
+        if current != self.current {
+            self.current = current;
+            // Reverse a running transition from where it is, so that nothing jumps.
+            self.elapsed = Some(match self.elapsed {
+                Some(elapsed) => self.duration.saturating_sub(elapsed),
+                None => Duration::ZERO,
+            });
+            ctx.request_anim_frame();
+            ctx.children_changed();
+        }
+
+        if self.is_animating() {
+            self.true_branch.update(ctx, data, env);
+            self.false_branch.update(ctx, data, env);
+        } else {
+            self.current_widget().update(ctx, data, env)
+        }
+    }
+
+    #[instrument(name = "AnimatedSwitcher", level = "trace", skip(self, ctx, bc, data, env), fields(branch = self.current))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("AnimatedSwitcher");
+
+        if !self.is_animating() {
+            let current_widget = self.current_widget();
+            let size = current_widget.layout(ctx, bc, data, env);
+            current_widget.set_origin(ctx, data, env, Point::ORIGIN);
+            ctx.set_paint_insets(current_widget.paint_insets());
+            return size;
+        }
+
+        let progress = self.progress();
+        let (animate_size, transition, current) =
+            (self.animate_size, self.transition, self.current);
+        let (new, old) = self.both_widgets();
+        let new_size = new.layout(ctx, bc, data, env);
+        let old_size = old.layout(ctx, bc, data, env);
+        let size = if animate_size {
+            old_size
+                .to_vec2()
+                .lerp(new_size.to_vec2(), progress)
+                .to_size()
+        } else {
+            Size::new(
+                new_size.width.max(old_size.width),
+                new_size.height.max(old_size.height),
+            )
+        };
+        let size = bc.constrain(size);
+
+        let (new_origin, old_origin) = match transition {
+            SwitcherTransition::Fade => (Point::ORIGIN, Point::ORIGIN),
+            SwitcherTransition::Slide(axis) => {
+                let extent = axis.major(size);
+                let direction = if current { 1.0 } else { -1.0 };
+                let new_offset: Vec2 = axis.pack(direction * (1.0 - progress) * extent, 0.0).into();
+                let old_offset: Vec2 = axis.pack(-direction * progress * extent, 0.0).into();
+                (new_offset.to_point(), old_offset.to_point())
+            }
+        };
+        new.set_origin(ctx, data, env, new_origin);
+        old.set_origin(ctx, data, env, old_origin);
+        trace!("Computed layout: size={}, progress={}", size, progress);
+        size
+    }
+
+    #[instrument(name = "AnimatedSwitcher", level = "trace", skip(self, ctx, data, env), fields(branch = self.current))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if !self.is_animating() {
+            return self.current_widget().paint(ctx, data, env);
+        }
+
+        let progress = self.progress();
+        let transition = self.transition;
+        let (new, old) = self.both_widgets();
+        let bounds = ctx.size().to_rect();
+        ctx.with_save(|ctx| {
+            ctx.clip(bounds);
+            match transition {
+                SwitcherTransition::Fade => {
+                    ctx.with_opacity(1.0 - progress, |ctx| old.paint(ctx, data, env));
+                    ctx.with_opacity(progress, |ctx| new.paint(ctx, data, env));
+                }
+                SwitcherTransition::Slide(_) => {
+                    old.paint(ctx, data, env);
+                    new.paint(ctx, data, env);
+                }
+            }
+        });
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let current_widget = if self.current {
+            &self.true_branch
+        } else {
+            &self.false_branch
+        };
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![current_widget.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::widget::{Painter, SizedBox};
+    use crate::{Color, RenderContext, WidgetExt};
+    use test_log::test;
+
+    const FRAME: u64 = 10_000_000;
+    #[test]
+    fn fade_paints_both_children() {
+        let fill = |color: Color| {
+            Painter::new(move |ctx, _: &bool, _| {
+                let rect = ctx.size().to_rect();
+                ctx.fill(rect, &color);
+            })
+        };
+        let widget = AnimatedSwitcher::new(
+            |data: &bool, _| *data,
+            fill(Color::rgb8(255, 0, 0)),
+            fill(Color::rgb8(0, 0, 255)),
+        )
+        .with_duration(Duration::from_millis(100));
+
+        Harness::create_with_render(
+            false,
+            widget,
+            Size::new(10.0, 10.0),
+            |harness| {
+                harness.send_initial_events();
+                harness.update_data(|data| *data = true);
+                for _ in 0..6 {
+                    harness.event(Event::AnimFrame(FRAME));
+                }
+                harness.just_layout();
+                harness.paint();
+            },
+            |target| {
+                let pixels = target.into_raw();
+                let (r, g, b) = (pixels[0], pixels[1], pixels[2]);
+                assert!(
+                    r > 100 && b > 60 && r > b && g < 30,
+                    "got {:?}",
+                    &pixels[..4]
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn transition_settles() {
+        let id = WidgetId::next();
+        let widget = AnimatedSwitcher::new(
+            |data: &bool, _| *data,
+            SizedBox::empty().fix_size(100.0, 20.0),
+            SizedBox::empty().fix_size(50.0, 40.0),
+        )
+        .with_transition(SwitcherTransition::Slide(Axis::Horizontal))
+        .with_duration(Duration::from_millis(100))
+        .with_id(id)
+        .center();
+
+        Harness::create_simple(false, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(50.0, 40.0)
+            );
+            assert!(!harness.window().wants_animation_frame());
+
+            harness.update_data(|data| *data = true);
+            assert!(harness.window().wants_animation_frame());
+            harness.event(Event::AnimFrame(FRAME));
+            harness.just_layout();
+            // Both children are laid out, so we get the larger of the two.
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(100.0, 40.0)
+            );
+
+            for _ in 0..5 {
+                harness.event(Event::AnimFrame(FRAME));
+            }
+            // Flip back halfway through; this reverses the running transition.
+            harness.update_data(|data| *data = false);
+            let mut frames = 0;
+            while harness.window().wants_animation_frame() {
+                harness.event(Event::AnimFrame(FRAME));
+                frames += 1;
+                assert!(frames < 100, "transition never settled");
+            }
+            assert_eq!(frames, 6);
+
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(50.0, 40.0)
+            );
+        });
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A text box whose text is decorated with annotations from the app data.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use tracing::instrument;
+
+use crate::debug_state::DebugState;
+use crate::text::{EditableText, TextAnnotation, TextStorage};
+use crate::widget::prelude::*;
+use crate::widget::TextBox;
+use crate::{Data, Lens};
+
+/// A [`TextBox`] that underlines ranges of its text, such as spelling errors.
+///
+/// The text and its annotations both live in the app data, and are selected
+/// with two lenses. When the text is edited in the text box, the annotations
+/// are updated to match: annotations before or after the edit move with their
+/// text, and annotations touched by the edit are removed.
+///
+/// Right-clicking an annotation sends [`TextBox::ANNOTATION_CLICKED`].
+///
+/// Create one with [`TextBox::with_annotations`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::text::TextAnnotation;
+/// use druid::widget::TextBox;
+/// use druid::{Data, Lens, Widget};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Document {
+///     text: String,
+///     spelling_errors: Arc<Vec<TextAnnotation>>,
+/// }
+///
+/// fn editor() -> impl Widget<Document> {
+///     TextBox::multiline().with_annotations(Document::text, Document::spelling_errors)
+/// }
+/// ```
+pub struct AnnotatedTextBox<T, U, LT, LA> {
+    textbox: TextBox<U>,
+    text: LT,
+    annotations: LA,
+    phantom: PhantomData<T>,
+}
+
+impl<T, U, LT, LA> AnnotatedTextBox<T, U, LT, LA> {
+    /// Decorate `textbox` with annotations.
+    ///
+    /// `text` selects the text of the text box, and `annotations` its annotations.
+    pub fn new(textbox: TextBox<U>, text: LT, annotations: LA) -> Self {
+        AnnotatedTextBox {
+            textbox,
+            text,
+            annotations,
+            phantom: PhantomData,
+        }
+    }
+
+    /// A reference to the inner [`TextBox`].
+    pub fn textbox(&self) -> &TextBox<U> {
+        &self.textbox
+    }
+
+    /// A mutable reference to the inner [`TextBox`].
+    pub fn textbox_mut(&mut self) -> &mut TextBox<U> {
+        &mut self.textbox
+    }
+}
+
+impl<T, U, LT, LA> Widget<T> for AnnotatedTextBox<T, U, LT, LA>
+where
+    T: Data,
+    U: TextStorage + EditableText,
+    LT: Lens<T, U>,
+    LA: Lens<T, Arc<Vec<TextAnnotation>>>,
+{
+    #[instrument(
+        name = "AnnotatedTextBox",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        // Only these events can change the text, so we don't copy it for other events.
+        let may_edit = matches!(
+            event,
+            Event::ImeStateChange | Event::Paste(_) | Event::Command(_)
+        );
+        let textbox = &mut self.textbox;
+        let edit = self.text.with_mut(data, |text| {
+            let old = may_edit.then(|| text.clone());
+            textbox.event(ctx, event, text, env);
+            old.filter(|old| !old.same(text))
+                .map(|old| (old, text.clone()))
+        });
+        if let Some((old, new)) = edit {
+            self.annotations.with_mut(data, |annotations| {
+                let moved: Vec<_> = annotations
+                    .iter()
+                    .filter_map(|annotation| annotation.after_edit(old.as_str(), new.as_str()))
+                    .collect();
+                if moved != **annotations {
+                    *annotations = Arc::new(moved);
+                }
+            });
+        }
+    }
+
+    #[instrument(
+        name = "AnnotatedTextBox",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let annotations = self.annotations.with(data, Arc::clone);
+            self.textbox.set_annotations(annotations);
+        }
+        let textbox = &mut self.textbox;
+        self.text
+            .with(data, |text| textbox.lifecycle(ctx, event, text, env));
+    }
+
+    #[instrument(
+        name = "AnnotatedTextBox",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let annotations = self.annotations.with(data, Arc::clone);
+        if !annotations.same(self.textbox.annotations()) {
+            self.textbox.set_annotations(annotations);
+            ctx.request_paint();
+        }
+        let textbox = &mut self.textbox;
+        let lens = &self.text;
+        lens.with(old_data, |old_text| {
+            lens.with(data, |text| {
+                if ctx.has_requested_update() || !old_text.same(text) || ctx.env_changed() {
+                    textbox.update(ctx, old_text, text, env);
+                }
+            })
+        })
+    }
+
+    #[instrument(
+        name = "AnnotatedTextBox",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let textbox = &mut self.textbox;
+        self.text
+            .with(data, |text| textbox.layout(ctx, bc, text, env))
+    }
+
+    #[instrument(name = "AnnotatedTextBox", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let textbox = &mut self.textbox;
+        self.text.with(data, |text| textbox.paint(ctx, text, env));
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let child = self.text.with(data, |text| self.textbox.debug_state(text));
+        DebugState {
+            display_name: "AnnotatedTextBox".to_string(),
+            children: vec![child],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::tests::move_mouse;
+    use crate::text::{AnnotationClick, AnnotationStyle, Selection};
+    use crate::{InternalEvent, MouseButton, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    type Doc = (String, Arc<Vec<TextAnnotation>>);
+
+    #[test]
+    fn right_click_and_edit() {
+        let [id] = widget_ids();
+        let textbox = TextBox::new()
+            .with_annotations(lens!(Doc, 0), lens!(Doc, 1))
+            .with_id(id)
+            .center();
+        let typo = TextAnnotation::new(4..9, AnnotationStyle::Error);
+        let data = ("the qiuck fox".to_string(), Arc::new(vec![typo.clone()]));
+
+        Harness::create_simple(data, textbox, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            let rect = harness.get_state(id).layout_rect();
+            let press = |x: f64, button| MouseEvent {
+                button,
+                count: 1,
+                ..move_mouse((rect.x0 + x, rect.center().y))
+            };
+
+            // the typo is roughly in the middle of the first 15 characters
+            let mut clicks = Vec::new();
+            for x in (4..120).step_by(4) {
+                harness.take_commands();
+                harness.event(Event::MouseDown(press(x as f64, MouseButton::Right)));
+                harness.event(Event::MouseUp(press(x as f64, MouseButton::Right)));
+                let hit = harness
+                    .take_commands()
+                    .iter()
+                    .filter_map(|cmd| cmd.get(TextBox::ANNOTATION_CLICKED))
+                    .map(|click: &AnnotationClick| click.annotation.clone())
+                    .next();
+                clicks.push(hit.is_some());
+                if let Some(annotation) = hit {
+                    assert_eq!(annotation, typo);
+                }
+            }
+            let first = clicks.iter().position(|hit| *hit).unwrap();
+            let count = clicks.iter().filter(|hit| **hit).count();
+            // not the leading "the ", and a contiguous run
+            assert!(first > 0);
+            assert!(clicks[first..first + count].iter().all(|hit| *hit));
+
+            // typing before the typo moves it
+            harness.event(Event::MouseDown(press(4.0, MouseButton::Left)));
+            harness.event(Event::MouseUp(press(4.0, MouseButton::Left)));
+            let token = harness.window().ime_handlers[0].0;
+            let mut handler = harness.window_mut().get_ime_handler(token, true);
+            handler.replace_range(0..0, "so ");
+            handler.set_selection(Selection::caret(3));
+            drop(handler);
+            let widget = harness.window_mut().release_ime_lock(token).unwrap();
+            harness.event(Event::Internal(InternalEvent::RouteImeStateChange(widget)));
+            assert_eq!(harness.data().0, "so the qiuck fox");
+            assert_eq!(
+                *harness.data().1,
+                vec![TextAnnotation::new(7..12, AnnotationStyle::Error)]
+            );
+        });
+    }
+}
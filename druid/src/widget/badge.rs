@@ -0,0 +1,328 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows a count over the corner of its child.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::text::TextLayout;
+use crate::widget::prelude::*;
+use crate::widget::ZStack;
+use crate::{theme, ArcStr, Color, Data, KeyOrValue, Lens, Rect, UnitPoint, Vec2};
+
+/// The height of the count, which is a circle for a single digit.
+const BADGE_HEIGHT: f64 = 16.0;
+const BADGE_TEXT_SIZE: f64 = 11.0;
+const BADGE_PADDING: f64 = 4.0;
+
+/// A widget that shows a count, like the number of unread messages, in a
+/// small bubble over a corner of its child.
+///
+/// The bubble is centered on the corner, and drawn over the child without
+/// changing its layout; the `Badge` has the size of the child. It is hidden
+/// while the count is zero, and counts above the maximum, 99 by default, are
+/// shown as "99+".
+///
+/// The bubble is drawn with the [`BADGE_COLOR`] and [`BADGE_TEXT_COLOR`],
+/// unless other colors are set with [`with_color`] and [`with_text_color`].
+///
+/// This is more conveniently used through [`WidgetExt::badge`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::Label;
+/// use druid::{Data, Lens, UnitPoint, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Inbox {
+///     unread: u32,
+/// }
+///
+/// fn inbox_icon() -> impl Widget<Inbox> {
+///     Label::new("Inbox")
+///         .padding(8.0)
+///         .badge(Inbox::unread)
+///         .with_corner(UnitPoint::TOP_LEFT)
+///         .with_max_count(9)
+/// }
+/// ```
+///
+/// [`BADGE_COLOR`]: theme::BADGE_COLOR
+/// [`BADGE_TEXT_COLOR`]: theme::BADGE_TEXT_COLOR
+/// [`with_color`]: Badge::with_color
+/// [`with_text_color`]: Badge::with_text_color
+/// [`WidgetExt::badge`]: crate::WidgetExt::badge
+pub struct Badge<T> {
+    stack: ZStack<T>,
+    style: Rc<RefCell<BadgeStyle>>,
+}
+
+/// How the count of a [`Badge`] is drawn, shared with the [`BadgeCount`].
+struct BadgeStyle {
+    color: KeyOrValue<Color>,
+    text_color: KeyOrValue<Color>,
+    max_count: u32,
+}
+
+/// The bubble with the count.
+struct BadgeCount<T> {
+    count: Box<dyn Fn(&T) -> u32>,
+    style: Rc<RefCell<BadgeStyle>>,
+    text: TextLayout<ArcStr>,
+    shown: bool,
+}
+
+impl<T: Data> Badge<T> {
+    /// Create a new `Badge` that shows the count selected by `lens` over the
+    /// top right corner of `child`.
+    pub fn new(child: impl Widget<T> + 'static, lens: impl Lens<T, u32> + 'static) -> Self {
+        let style = Rc::new(RefCell::new(BadgeStyle {
+            color: theme::BADGE_COLOR.into(),
+            text_color: theme::BADGE_TEXT_COLOR.into(),
+            max_count: 99,
+        }));
+        let mut text = TextLayout::new();
+        text.set_text_size(BADGE_TEXT_SIZE);
+        let count = BadgeCount {
+            count: Box::new(move |data| lens.with(data, |count| *count)),
+            style: style.clone(),
+            text,
+            shown: false,
+        };
+        let corner = UnitPoint::TOP_RIGHT;
+        let mut stack = ZStack::new(child).with_child(count, corner, corner_offset(corner));
+        // clicks on the count are meant for the child
+        stack.set_pointer_passes_through(0);
+        Badge { stack, style }
+    }
+
+    /// Builder-style method for setting the corner of the child that the
+    /// count is centered on.
+    ///
+    /// Other points than the corners work too; [`UnitPoint::BOTTOM`] centers
+    /// the count on the bottom edge.
+    pub fn with_corner(mut self, corner: UnitPoint) -> Self {
+        self.stack
+            .set_child_position(0, corner, corner_offset(corner));
+        self
+    }
+
+    /// Builder-style method for setting the largest count that is shown;
+    /// larger counts are shown as this count followed by a "+".
+    pub fn with_max_count(self, max_count: u32) -> Self {
+        self.style.borrow_mut().max_count = max_count;
+        self
+    }
+
+    /// Builder-style method for setting the background color of the count.
+    pub fn with_color(self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.style.borrow_mut().color = color.into();
+        self
+    }
+
+    /// Builder-style method for setting the text color of the count.
+    pub fn with_text_color(self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.style.borrow_mut().text_color = color.into();
+        self
+    }
+}
+
+/// The offset that centers a count at `corner` on that point.
+fn corner_offset(corner: UnitPoint) -> Vec2 {
+    let outwards = corner.resolve(Rect::new(-1.0, -1.0, 1.0, 1.0));
+    outwards.to_vec2() * (BADGE_HEIGHT / 2.0)
+}
+
+impl<T> BadgeCount<T> {
+    /// The text for `count`, or `None` if nothing is shown.
+    fn label(&self, count: u32) -> Option<ArcStr> {
+        let max_count = self.style.borrow().max_count;
+        match count {
+            0 => None,
+            count if count > max_count => Some(format!("{}+", max_count).into()),
+            count => Some(count.to_string().into()),
+        }
+    }
+
+    fn set_count(&mut self, count: u32) {
+        let label = self.label(count);
+        self.shown = label.is_some();
+        self.text.set_text(label.unwrap_or_else(|| "".into()));
+    }
+}
+
+impl<T: Data> Widget<T> for BadgeCount<T> {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let style = self.style.borrow();
+            self.text.set_text_color(style.text_color.clone());
+            drop(style);
+            self.set_count((self.count)(data));
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        let count = (self.count)(data);
+        if self.label(count) != self.label((self.count)(old_data)) {
+            self.set_count(count);
+            ctx.request_layout();
+        }
+        if self.text.needs_rebuild_after_update(ctx) {
+            ctx.request_layout();
+        }
+        if ctx.env_key_changed(&self.style.borrow().color) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("BadgeCount");
+        if !self.shown {
+            return bc.min();
+        }
+        self.text.rebuild_if_needed(ctx.text(), env);
+        let width = self.text.size().width + 2.0 * BADGE_PADDING;
+        bc.constrain((width.max(BADGE_HEIGHT), BADGE_HEIGHT))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        if !self.shown {
+            return;
+        }
+        let size = ctx.size();
+        let bubble = size.to_rounded_rect(size.height / 2.0);
+        ctx.fill(bubble, &self.style.borrow().color.resolve(env));
+        let origin = (size.to_vec2() - self.text.size().to_vec2()) / 2.0;
+        self.text.draw(ctx, origin.to_point());
+    }
+
+    fn debug_state(&self, _data: &T) -> DebugState {
+        DebugState {
+            display_name: "BadgeCount".to_string(),
+            main_value: self
+                .text
+                .text()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for Badge<T> {
+    #[instrument(name = "Badge", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.stack.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Badge", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.stack.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Badge", level = "trace", skip(self, ctx, old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.stack.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "Badge", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.stack.layout(ctx, bc, data, env);
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "Badge", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.stack.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "Badge".to_string(),
+            children: vec![self.stack.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::SizedBox;
+    use crate::{MouseButton, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    #[derive(Clone, Data, Lens)]
+    struct Inbox {
+        unread: u32,
+        opened: u32,
+    }
+
+    fn shown_count(harness: &Harness<Inbox>) -> String {
+        let stack = &harness.root_debug_state().children[0];
+        stack.children[1].main_value.clone()
+    }
+
+    #[test]
+    fn count_and_clicks() {
+        let id = WidgetId::next();
+        let widget = SizedBox::empty()
+            .on_click(|_, inbox: &mut Inbox, _| inbox.opened += 1)
+            .with_id(id)
+            .badge(Inbox::unread)
+            .with_max_count(9);
+        let inbox = Inbox {
+            unread: 0,
+            opened: 0,
+        };
+
+        Harness::create_simple(inbox, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(shown_count(harness), "");
+
+            harness.update_data(|inbox| inbox.unread = 3);
+            assert_eq!(shown_count(harness), "3");
+            harness.update_data(|inbox| inbox.unread = 12);
+            assert_eq!(shown_count(harness), "9+");
+            harness.just_layout();
+            // the child keeps its layout
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(400., 400.)
+            );
+
+            // a click on the count goes to the child
+            let press = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse((396., 2.))
+            };
+            harness.event(Event::MouseMove(press.clone()));
+            harness.event(Event::MouseDown(press.clone()));
+            harness.event(Event::MouseUp(press));
+            assert_eq!(harness.data().opened, 1);
+        });
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Widgets that blur their content, or the content behind them.
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{Data, Insets, InternalEvent, KeyOrValue, Point, Rect, UnitPoint, WidgetPod};
+use tracing::{instrument, trace};
+
+/// A widget that paints its child with a Gaussian blur.
+///
+/// The radius is the standard deviation of the blur, in display points; the
+/// blur spreads about three times as far. The child is painted into a
+/// separate layer and blurred in software, so large radii over large areas
+/// are expensive. If the layer can't be created, the child is painted
+/// without a blur.
+///
+/// To blur what is behind a widget instead, use a [`Backdrop`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Blur, Label};
+///
+/// let blurred: Blur<(), _> = Blur::new(Label::new("Out of focus"), 2.0);
+/// ```
+pub struct Blur<T, W> {
+    child: WidgetPod<T, W>,
+    radius: KeyOrValue<f64>,
+}
+
+impl<T: Data, W: Widget<T>> Blur<T, W> {
+    /// Create a new `Blur` with a fixed radius, or a [`Key`] referring to one
+    /// in the [`Env`].
+    ///
+    /// [`Key`]: crate::Key
+    pub fn new(child: W, radius: impl Into<KeyOrValue<f64>>) -> Self {
+        Blur {
+            child: WidgetPod::new(child),
+            radius: radius.into(),
+        }
+    }
+
+    /// Set the radius of the blur.
+    pub fn set_radius(&mut self, radius: impl Into<KeyOrValue<f64>>) {
+        self.radius = radius.into();
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Blur<T, W> {
+    #[instrument(name = "Blur", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Blur", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Blur", level = "trace", skip(self, ctx, _old, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        if ctx.env_key_changed(&self.radius) {
+            ctx.request_layout();
+        }
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Blur", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+        let spread = self.radius.resolve(env).max(0.0) * 3.0;
+        let insets = self.child.paint_insets();
+        ctx.set_paint_insets(Insets::new(
+            insets.x0.max(spread),
+            insets.y0.max(spread),
+            insets.x1.max(spread),
+            insets.y1.max(spread),
+        ));
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "Blur", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let radius = self.radius.resolve(env);
+        let child = &mut self.child;
+        ctx.with_blur(radius, |ctx| child.paint(ctx, data, env));
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+/// A panel on top of a background, which blurs the part of the background
+/// behind it, like frosted glass.
+///
+/// The background fills the `Backdrop`, and the panel is laid out on top of
+/// it, at most as large as the background, and placed according to its
+/// [alignment]. Only the background is blurred: other widgets painted before
+/// the `Backdrop` are not, as the blur is computed by painting the background
+/// a second time into a separate layer. If that layer can't be created, the
+/// background is shown without a blur.
+///
+/// The panel should usually be partially transparent, for instance with a
+/// translucent [`background`], or the blur won't be visible.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Backdrop, Flex, Label};
+/// use druid::{Color, WidgetExt};
+///
+/// let content = Flex::column().with_child(Label::new("Behind the dialog"));
+/// let dialog = Label::new("Are you sure?")
+///     .padding(20.0)
+///     .background(Color::grey8(0x20).with_alpha(0.5));
+/// let _: Backdrop<()> = Backdrop::new(content, dialog, 8.0);
+/// ```
+///
+/// [alignment]: Backdrop::panel_alignment
+/// [`background`]: crate::WidgetExt::background
+pub struct Backdrop<T> {
+    background: WidgetPod<T, Box<dyn Widget<T>>>,
+    panel: WidgetPod<T, Box<dyn Widget<T>>>,
+    radius: KeyOrValue<f64>,
+    alignment: UnitPoint,
+}
+
+impl<T: Data> Backdrop<T> {
+    /// Create a new `Backdrop`, blurring `background` behind `panel` with the
+    /// given radius, in display points.
+    pub fn new(
+        background: impl Widget<T> + 'static,
+        panel: impl Widget<T> + 'static,
+        radius: impl Into<KeyOrValue<f64>>,
+    ) -> Self {
+        Backdrop {
+            background: WidgetPod::new(background).boxed(),
+            panel: WidgetPod::new(panel).boxed(),
+            radius: radius.into(),
+            alignment: UnitPoint::CENTER,
+        }
+    }
+
+    /// Builder-style method to set where the panel is placed over the
+    /// background.
+    ///
+    /// The default is [`UnitPoint::CENTER`].
+    pub fn panel_alignment(mut self, alignment: UnitPoint) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Set the radius of the blur.
+    pub fn set_radius(&mut self, radius: impl Into<KeyOrValue<f64>>) {
+        self.radius = radius.into();
+    }
+}
+
+impl<T: Data> Widget<T> for Backdrop<T> {
+    #[instrument(name = "Backdrop", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let is_pointer_event = matches!(
+            event,
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::MouseMove(_) | Event::Wheel(_)
+        );
+        self.panel.event(ctx, event, data, env);
+        // The panel covers the background, unless the background is active.
+        let covered = is_pointer_event && self.panel.is_hot() && !self.background.has_active();
+        if !covered {
+            self.background.event(ctx, event, data, env);
+        } else if self.background.is_hot() {
+            let leave = Event::Internal(InternalEvent::MouseLeave);
+            self.background.event(ctx, &leave, data, env);
+        }
+    }
+
+    #[instrument(name = "Backdrop", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.background.lifecycle(ctx, event, data, env);
+        self.panel.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Backdrop", level = "trace", skip(self, ctx, _old, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        if ctx.env_key_changed(&self.radius) {
+            ctx.request_paint();
+        }
+        self.background.update(ctx, data, env);
+        self.panel.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Backdrop", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Backdrop");
+
+        let size = self.background.layout(ctx, bc, data, env);
+        self.background.set_origin(ctx, data, env, Point::ORIGIN);
+
+        let panel_bc = BoxConstraints::new(Size::ZERO, size);
+        let panel_size = self.panel.layout(ctx, &panel_bc, data, env);
+        let origin = self.alignment.resolve((size - panel_size).to_rect());
+        self.panel.set_origin(ctx, data, env, origin);
+
+        let paint_rect = self.background.paint_rect().union(self.panel.paint_rect());
+        let insets = paint_rect - Rect::ZERO.with_size(size);
+        ctx.set_paint_insets(insets);
+        ctx.set_baseline_offset(self.background.baseline_offset());
+        trace!(
+            "Computed layout: size={}, panel={}",
+            size,
+            self.panel.layout_rect()
+        );
+        size
+    }
+
+    #[instrument(name = "Backdrop", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.background.paint(ctx, data, env);
+
+        let radius = self.radius.resolve(env);
+        let panel_rect = self.panel.layout_rect();
+        if radius > 0.0 && ctx.region().intersects(panel_rect) {
+            let background = &mut self.background;
+            ctx.with_save(|ctx| {
+                ctx.clip(panel_rect);
+                let spread = radius * 3.0;
+                let rect = panel_rect.inflate(spread, spread);
+                ctx.blur_rect(rect, radius, |ctx| background.paint(ctx, data, env));
+            });
+        }
+
+        self.panel.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![
+                self.background.widget().debug_state(data),
+                self.panel.widget().debug_state(data),
+            ],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::widget::{Painter, SizedBox};
+    use crate::{Color, RenderContext, WidgetExt};
+
+    /// A 20x20 widget, black on the left half and white on the right.
+    fn split() -> impl Widget<()> {
+        Painter::new(|ctx, _, _| {
+            let size = ctx.size();
+            ctx.fill(size.to_rect(), &Color::BLACK);
+            ctx.fill(
+                Rect::new(size.width / 2.0, 0.0, size.width, size.height),
+                &Color::WHITE,
+            );
+        })
+        .fix_size(20.0, 20.0)
+    }
+
+    /// The red channel of the pixel at `(x, y)` of a 20 pixel wide image.
+    fn red(pixels: &[u8], x: usize, y: usize) -> u8 {
+        pixels[(y * 20 + x) * 4]
+    }
+
+    #[test]
+    fn blur_softens_edges() {
+        Harness::create_with_render(
+            (),
+            Blur::new(split(), 2.0),
+            Size::new(20.0, 20.0),
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                harness.paint();
+            },
+            |target| {
+                let pixels = target.into_raw();
+                let (left, right) = (red(&pixels, 9, 10), red(&pixels, 10, 10));
+                assert!(left > 40 && left < 128, "left of the edge: {}", left);
+                assert!(right > 128 && right < 215, "right of the edge: {}", right);
+                // away from the edge, the colors are almost unchanged
+                assert!(red(&pixels, 4, 10) < 10);
+                assert!(red(&pixels, 15, 10) > 240);
+            },
+        );
+    }
+
+    #[test]
+    fn backdrop_only_blurs_behind_panel() {
+        let panel = SizedBox::empty().fix_size(20.0, 10.0);
+        let widget = Backdrop::new(split(), panel, 2.0).panel_alignment(UnitPoint::TOP);
+        Harness::create_with_render(
+            (),
+            widget,
+            Size::new(20.0, 20.0),
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                harness.paint();
+            },
+            |target| {
+                let pixels = target.into_raw();
+                // the top half is behind the panel
+                let blurred = red(&pixels, 9, 2);
+                assert!(blurred > 40 && blurred < 128, "behind panel: {}", blurred);
+                assert!(red(&pixels, 9, 17) < 5);
+                assert!(red(&pixels, 10, 17) > 250);
+            },
+        );
+    }
+}
@@ -14,21 +14,34 @@
 
 //! A button widget.
 
+use crate::accessibility::Role;
+use crate::commands::{ADD_KEY_BINDING, CLICK};
 use crate::debug_state::DebugState;
 use crate::widget::prelude::*;
 use crate::widget::{Click, ControllerHost, Label, LabelText};
-use crate::{theme, Affine, Data, Insets, LinearGradient, UnitPoint};
+use crate::{theme, Affine, Data, HotKey, Insets, LinearGradient, Point, UnitPoint};
 use tracing::{instrument, trace};
 
 // the minimum padding added to a button.
 // NOTE: these values are chosen to match the existing look of TextBox; these
 // should be reevaluated at some point.
 const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
+// the space between the label and the hotkey.
+const HOTKEY_SPACING: f64 = 16.0;
 
 /// A button with a text label.
 pub struct Button<T> {
     label: Label<T>,
     label_size: Size,
+    hotkey: Option<HotkeyLabel<T>>,
+    trigger_on_hotkey: bool,
+}
+
+/// The hotkey shown after the label of a [`Button`].
+struct HotkeyLabel<T> {
+    hotkey: HotKey,
+    label: Label<T>,
+    size: Size,
 }
 
 impl<T: Data> Button<T> {
@@ -74,6 +87,8 @@ impl<T: Data> Button<T> {
         Button {
             label,
             label_size: Size::ZERO,
+            hotkey: None,
+            trigger_on_hotkey: false,
         }
     }
 
@@ -102,6 +117,55 @@ impl<T: Data> Button<T> {
         Button::new(text)
     }
 
+    /// Builder-style method for showing a keyboard shortcut after the label,
+    /// like the shortcuts in a menu.
+    ///
+    /// The hotkey is formatted the way the platform shows shortcuts, for
+    /// example as `Ctrl+S` on Windows and Linux, and as `⌘S` on macOS, and
+    /// drawn with the [`PLACEHOLDER_COLOR`]. To also click the button when the
+    /// hotkey is pressed, use [`trigger_on_hotkey`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::Button;
+    /// use druid::{HotKey, SysMods};
+    ///
+    /// let save = Button::new("Save")
+    ///     .with_hotkey_label(HotKey::new(SysMods::Cmd, "s"))
+    ///     .trigger_on_hotkey(true)
+    ///     .on_click(|_ctx, saved: &mut bool, _env| *saved = true);
+    /// ```
+    ///
+    /// [`PLACEHOLDER_COLOR`]: theme::PLACEHOLDER_COLOR
+    /// [`trigger_on_hotkey`]: Button::trigger_on_hotkey
+    pub fn with_hotkey_label(mut self, hotkey: HotKey) -> Self {
+        let label = Label::new(hotkey.to_string()).with_text_color(theme::PLACEHOLDER_COLOR);
+        self.hotkey = Some(HotkeyLabel {
+            hotkey,
+            label,
+            size: Size::ZERO,
+        });
+        self
+    }
+
+    /// Builder-style method for clicking the button when the hotkey set with
+    /// [`with_hotkey_label`] is pressed.
+    ///
+    /// When the button is added to a window, the hotkey is bound in the
+    /// window's [`KeyMap`], replacing an existing binding of the same hotkey.
+    /// Like the other bindings, it is only triggered if the focused widget
+    /// doesn't handle the key press. Pressing the hotkey runs the closure
+    /// passed to [`on_click`], unless the button is disabled.
+    ///
+    /// [`with_hotkey_label`]: Button::with_hotkey_label
+    /// [`KeyMap`]: crate::KeyMap
+    /// [`on_click`]: Button::on_click
+    pub fn trigger_on_hotkey(mut self, trigger: bool) -> Self {
+        self.trigger_on_hotkey = trigger;
+        self
+    }
+
     /// Provide a closure to be called when this button is clicked.
     pub fn on_click(
         self,
@@ -111,6 +175,38 @@ impl<T: Data> Button<T> {
     }
 }
 
+impl<T: Data> Button<T> {
+    /// The size of the label and the hotkey, side by side.
+    fn content_size(&self) -> Size {
+        let hotkey_width = match &self.hotkey {
+            Some(hotkey) => hotkey.size.width + HOTKEY_SPACING,
+            None => 0.0,
+        };
+        Size::new(
+            self.label_size.width + hotkey_width,
+            self.content_ascent() + self.content_descent(),
+        )
+    }
+
+    /// The height of the label and the hotkey above their common baseline.
+    fn content_ascent(&self) -> f64 {
+        let label_ascent = self.label_size.height - self.label.baseline_offset();
+        match &self.hotkey {
+            Some(hotkey) => label_ascent.max(hotkey.size.height - hotkey.label.baseline_offset()),
+            None => label_ascent,
+        }
+    }
+
+    /// The height of the label and the hotkey below their common baseline.
+    fn content_descent(&self) -> f64 {
+        let label_descent = self.label.baseline_offset();
+        match &self.hotkey {
+            Some(hotkey) => label_descent.max(hotkey.label.baseline_offset()),
+            None => label_descent,
+        }
+    }
+}
+
 impl<T: Data> Widget<T> for Button<T> {
     #[instrument(name = "Button", level = "trace", skip(self, ctx, event, _data, _env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
@@ -135,32 +231,57 @@ impl<T: Data> Widget<T> for Button<T> {
 
     #[instrument(name = "Button", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
-        if let LifeCycle::HotChanged(_) | LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::HotChanged(_) | LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            LifeCycle::WidgetAdded if self.trigger_on_hotkey => {
+                if let Some(hotkey) = &self.hotkey {
+                    let click = CLICK.to(ctx.widget_id());
+                    let binding = ADD_KEY_BINDING.with((hotkey.hotkey.clone(), click));
+                    ctx.submit_command(binding.to(ctx.window_id()));
+                }
+            }
+            _ => (),
+        }
+        self.label.lifecycle(ctx, event, data, env);
+        if let Some(hotkey) = &mut self.hotkey {
+            hotkey.label.lifecycle(ctx, event, data, env);
+        }
+        // set after the labels, which describe themselves using this widget's context
+        if let LifeCycle::WidgetAdded = event {
+            ctx.set_access_role(Role::Button);
+            ctx.set_access_name(self.label.text().to_string());
         }
-        self.label.lifecycle(ctx, event, data, env)
     }
 
     #[instrument(name = "Button", level = "trace", skip(self, ctx, old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
-        self.label.update(ctx, old_data, data, env)
+        self.label.update(ctx, old_data, data, env);
+        if let Some(hotkey) = &mut self.hotkey {
+            hotkey.label.update(ctx, old_data, data, env);
+        }
+        ctx.set_access_name(self.label.text().to_string());
     }
 
     #[instrument(name = "Button", level = "trace", skip(self, ctx, bc, data, env))]
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         bc.debug_check("Button");
         let padding = Size::new(LABEL_INSETS.x_value(), LABEL_INSETS.y_value());
-        let label_bc = bc.shrink(padding).loosen();
+        let mut label_bc = bc.shrink(padding).loosen();
+        // the hotkey is laid out first, so that the label gets the remaining space
+        if let Some(hotkey) = &mut self.hotkey {
+            hotkey.size = hotkey.label.layout(ctx, &label_bc, data, env);
+            label_bc = label_bc.shrink((hotkey.size.width + HOTKEY_SPACING, 0.0));
+        }
         self.label_size = self.label.layout(ctx, &label_bc, data, env);
+        let content_size = self.content_size();
         // HACK: to make sure we look okay at default sizes when beside a textbox,
         // we make sure we will have at least the same height as the default textbox.
         let min_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
-        let baseline = self.label.baseline_offset();
-        ctx.set_baseline_offset(baseline + LABEL_INSETS.y1);
+        ctx.set_baseline_offset(self.content_descent() + LABEL_INSETS.y1);
 
         let button_size = bc.constrain(Size::new(
-            self.label_size.width + padding.width,
-            (self.label_size.height + padding.height).max(min_height),
+            content_size.width + padding.width,
+            (content_size.height + padding.height).max(min_height),
         ));
         trace!("Computed button size: {}", button_size);
         button_size
@@ -211,12 +332,28 @@ impl<T: Data> Widget<T> for Button<T> {
 
         ctx.fill(rounded_rect, &bg_gradient);
 
-        let label_offset = (size.to_vec2() - self.label_size.to_vec2()) / 2.0;
+        // the label and the hotkey are centered together, on a common baseline
+        let ascent = self.content_ascent();
+        let content_size = self.content_size();
+        let content_origin = ((size - content_size).to_vec2() / 2.0).to_point();
+        let label_ascent = self.label_size.height - self.label.baseline_offset();
+        let label_offset = content_origin + (0.0, ascent - label_ascent);
 
         ctx.with_save(|ctx| {
-            ctx.transform(Affine::translate(label_offset));
+            ctx.transform(Affine::translate(label_offset.to_vec2()));
             self.label.paint(ctx, data, env);
         });
+        if let Some(hotkey) = &mut self.hotkey {
+            let hotkey_ascent = hotkey.size.height - hotkey.label.baseline_offset();
+            let hotkey_offset = Point::new(
+                content_origin.x + self.label_size.width + HOTKEY_SPACING,
+                content_origin.y + ascent - hotkey_ascent,
+            );
+            ctx.with_save(|ctx| {
+                ctx.transform(Affine::translate(hotkey_offset.to_vec2()));
+                hotkey.label.paint(ctx, data, env);
+            });
+        }
     }
 
     fn debug_state(&self, _data: &T) -> DebugState {
@@ -227,3 +364,54 @@ impl<T: Data> Widget<T> for Button<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::{Event, KbKey, KeyEvent, Modifiers, SysMods, WidgetExt};
+    use test_log::test;
+
+    fn save_key() -> KeyEvent {
+        let mods = if cfg!(target_os = "macos") {
+            Modifiers::META
+        } else {
+            Modifiers::CONTROL
+        };
+        KeyEvent::for_test(mods, KbKey::Character("s".into()))
+    }
+
+    #[test]
+    fn hotkey_label_and_trigger() {
+        let id = WidgetId::next();
+        let mut plain = Size::ZERO;
+        let widget = Button::new("Save").with_id(id).center();
+        Harness::create_simple(0u32, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            plain = harness.get_state(id).layout_rect().size();
+        });
+
+        let widget = Button::new("Save")
+            .with_hotkey_label(HotKey::new(SysMods::Cmd, "s"))
+            .trigger_on_hotkey(true)
+            .on_click(|_, count: &mut u32, _| *count += 1)
+            .with_id(id)
+            .center();
+        Harness::create_simple(0u32, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let size = harness.get_state(id).layout_rect().size();
+            assert!(size.width > plain.width + HOTKEY_SPACING);
+            assert_eq!(size.height, plain.height);
+
+            harness.event(Event::KeyDown(save_key()));
+            assert_eq!(*harness.data(), 1);
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::default(),
+                "s",
+            )));
+            assert_eq!(*harness.data(), 1);
+        });
+    }
+}
@@ -0,0 +1,367 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A container that places its children at arbitrary points.
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{Data, InternalEvent, Point, Rect, WidgetPod};
+
+/// A container that places each child at a point, like the nodes of a node
+/// editor or the shapes of a diagram.
+///
+/// Every child is laid out at its preferred size, without constraints, with
+/// its top left corner at a fixed point or at a point computed from the
+/// data. Children are painted in the order they were added, so later
+/// children are drawn on top, and pointer events go to the topmost child
+/// under the pointer.
+///
+/// The `Canvas` is as large as needed to reach the right and bottom edges
+/// of its children, so that it can be put in a [`Scroll`] or a [`PanZoom`];
+/// children at negative points are still painted, but can't be scrolled to.
+/// The area covered by the children is available from [`content_bounds`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Canvas, Label, PanZoom, ViewTransform};
+/// use druid::{Color, Data, Lens, Point, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Diagram {
+///     node: Point,
+///     view: ViewTransform,
+/// }
+///
+/// fn diagram() -> impl Widget<Diagram> {
+///     let canvas = Canvas::new()
+///         .with_child(Label::new("Start").border(Color::WHITE, 1.0), (20.0, 20.0))
+///         .with_dynamic_child(Label::new("Node").padding(4.0), |diagram: &Diagram| {
+///             diagram.node
+///         });
+///     PanZoom::new(canvas, Diagram::view)
+/// }
+/// ```
+///
+/// [`Scroll`]: crate::widget::Scroll
+/// [`PanZoom`]: crate::widget::PanZoom
+/// [`content_bounds`]: Canvas::content_bounds
+pub struct Canvas<T> {
+    children: Vec<CanvasChild<T>>,
+    content_bounds: Rect,
+}
+
+/// A child of a [`Canvas`] and its position.
+struct CanvasChild<T> {
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    position: Position<T>,
+    /// Whether the children below get the pointer, even when it is over this child.
+    pointer_passes_through: bool,
+}
+
+enum Position<T> {
+    Fixed(Point),
+    Dynamic(Box<dyn Fn(&T) -> Point>),
+}
+
+impl<T> Position<T> {
+    fn resolve(&self, data: &T) -> Point {
+        match self {
+            Position::Fixed(point) => *point,
+            Position::Dynamic(f) => f(data),
+        }
+    }
+}
+
+impl<T: Data> Canvas<T> {
+    /// Create a new, empty `Canvas`.
+    pub fn new() -> Self {
+        Canvas {
+            children: Vec::new(),
+            content_bounds: Rect::ZERO,
+        }
+    }
+
+    /// Builder-style method to add a child with its top left corner at
+    /// `position`.
+    pub fn with_child(
+        mut self,
+        child: impl Widget<T> + 'static,
+        position: impl Into<Point>,
+    ) -> Self {
+        self.add_child(child, position);
+        self
+    }
+
+    /// Builder-style method to add a child with its top left corner at the
+    /// point computed from the data by `position`.
+    ///
+    /// The child is moved whenever the point changes.
+    pub fn with_dynamic_child(
+        mut self,
+        child: impl Widget<T> + 'static,
+        position: impl Fn(&T) -> Point + 'static,
+    ) -> Self {
+        self.add_dynamic_child(child, position);
+        self
+    }
+
+    /// Add a child with its top left corner at `position`.
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static, position: impl Into<Point>) {
+        self.push(child, Position::Fixed(position.into()));
+    }
+
+    /// Add a child with its top left corner at the point computed from the
+    /// data by `position`.
+    ///
+    /// See [`with_dynamic_child`] for more details.
+    ///
+    /// [`with_dynamic_child`]: Canvas::with_dynamic_child
+    pub fn add_dynamic_child(
+        &mut self,
+        child: impl Widget<T> + 'static,
+        position: impl Fn(&T) -> Point + 'static,
+    ) {
+        self.push(child, Position::Dynamic(Box::new(position)));
+    }
+
+    /// Builder-style method to add a child that connects other children,
+    /// like a [`Wire`].
+    ///
+    /// The child is placed at the origin, so that it can draw in the
+    /// coordinates of the canvas. As its layout rect covers everything it
+    /// connects, the pointer passes through it to the children below; it
+    /// still gets pointer events and can handle them where it is actually
+    /// drawn, which keeps them from the children below.
+    ///
+    /// [`Wire`]: crate::widget::Wire
+    pub fn with_connection(mut self, child: impl Widget<T> + 'static) -> Self {
+        self.add_connection(child);
+        self
+    }
+
+    /// Add a child that connects other children, like a [`Wire`].
+    ///
+    /// See [`with_connection`] for more details.
+    ///
+    /// [`Wire`]: crate::widget::Wire
+    /// [`with_connection`]: Canvas::with_connection
+    pub fn add_connection(&mut self, child: impl Widget<T> + 'static) {
+        self.push(child, Position::Fixed(Point::ORIGIN));
+        self.children.last_mut().unwrap().pointer_passes_through = true;
+    }
+
+    fn push(&mut self, child: impl Widget<T> + 'static, position: Position<T>) {
+        self.children.push(CanvasChild {
+            widget: WidgetPod::new(child).boxed(),
+            position,
+            pointer_passes_through: false,
+        });
+    }
+
+    /// The smallest rectangle that contains all of the children, apart from
+    /// the connections, as of the last layout.
+    ///
+    /// This is [`Rect::ZERO`] if there are no children.
+    pub fn content_bounds(&self) -> Rect {
+        self.content_bounds
+    }
+}
+
+impl<T: Data> Default for Canvas<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Widget<T> for Canvas<T> {
+    #[instrument(name = "Canvas", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let is_pointer_event = matches!(
+            event,
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::MouseMove(_) | Event::Wheel(_)
+        );
+        // While a child is active it gets the pointer, whatever is on top of it.
+        let has_active = self.children.iter().any(|child| child.widget.has_active());
+
+        let mut covered = false;
+        for child in self.children.iter_mut().rev() {
+            let passes_through = child.pointer_passes_through;
+            let child = &mut child.widget;
+            if is_pointer_event && covered && !has_active {
+                if child.is_hot() {
+                    child.event(ctx, &Event::Internal(InternalEvent::MouseLeave), data, env);
+                }
+            } else {
+                child.event(ctx, event, data, env);
+                covered |= child.is_hot() && !passes_through;
+            }
+        }
+    }
+
+    #[instrument(name = "Canvas", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Canvas", level = "trace", skip(self, ctx, old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.update(ctx, data, env);
+            if let Position::Dynamic(_) = child.position {
+                if child.position.resolve(old_data) != child.position.resolve(data) {
+                    ctx.request_layout();
+                }
+            }
+        }
+    }
+
+    #[instrument(name = "Canvas", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Canvas");
+
+        let mut bounds: Option<Rect> = None;
+        let mut paint_rect = Rect::ZERO;
+        for child in &mut self.children {
+            child
+                .widget
+                .layout(ctx, &BoxConstraints::UNBOUNDED, data, env);
+            let origin = child.position.resolve(data);
+            child.widget.set_origin(ctx, data, env, origin);
+            // connections start at the origin, whatever they connect
+            if !child.pointer_passes_through {
+                let rect = child.widget.layout_rect();
+                bounds = Some(bounds.map_or(rect, |bounds| bounds.union(rect)));
+            }
+            paint_rect = paint_rect.union(child.widget.paint_rect());
+        }
+        self.content_bounds = bounds.unwrap_or(Rect::ZERO);
+
+        let size = bc.constrain(Size::new(
+            self.content_bounds.x1.max(0.0),
+            self.content_bounds.y1.max(0.0),
+        ));
+        let insets = paint_rect - size.to_rect();
+        ctx.set_paint_insets(insets);
+        trace!(
+            "Computed layout: size={}, content_bounds={}",
+            size,
+            self.content_bounds
+        );
+        size
+    }
+
+    #[instrument(name = "Canvas", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "Canvas".to_string(),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.widget.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::SizedBox;
+    use crate::{Lens, MouseButton, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    #[derive(Clone, Data, Lens)]
+    struct Diagram {
+        node: Point,
+        clicked: Option<&'static str>,
+    }
+
+    fn node(name: &'static str, id: WidgetId) -> impl Widget<Diagram> {
+        SizedBox::empty()
+            .fix_size(50., 20.)
+            .on_click(move |_, diagram: &mut Diagram, _| diagram.clicked = Some(name))
+            .with_id(id)
+    }
+
+    fn click(harness: &mut Harness<Diagram>, pos: impl Into<Point>) {
+        let press = MouseEvent {
+            button: MouseButton::Left,
+            count: 1,
+            ..move_mouse(pos)
+        };
+        harness.event(Event::MouseMove(press.clone()));
+        harness.event(Event::MouseDown(press.clone()));
+        harness.event(Event::MouseUp(press));
+    }
+
+    #[test]
+    fn positions_and_clicks() {
+        let (fixed, dynamic) = (WidgetId::next(), WidgetId::next());
+        let canvas_id = WidgetId::next();
+        let canvas = Canvas::new()
+            .with_child(node("fixed", fixed), (10., 10.))
+            .with_dynamic_child(node("dynamic", dynamic), |diagram: &Diagram| diagram.node)
+            .with_id(canvas_id)
+            .center();
+        let diagram = Diagram {
+            node: Point::new(40., 20.),
+            clicked: None,
+        };
+
+        Harness::create_simple(diagram, canvas, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let size = harness.get_state(canvas_id).layout_rect().size();
+            assert_eq!(size, Size::new(90., 40.));
+            assert_eq!(
+                harness.get_state(dynamic).layout_rect().size(),
+                Size::new(50., 20.)
+            );
+
+            // the canvas is centered in the window, at (155, 180)
+            // the dynamic child is on top where both overlap
+            click(harness, (155. + 45., 180. + 25.));
+            assert_eq!(harness.data().clicked, Some("dynamic"));
+            click(harness, (155. + 15., 180. + 15.));
+            assert_eq!(harness.data().clicked, Some("fixed"));
+
+            harness.update_data(|diagram| diagram.node = Point::new(100., 50.));
+            harness.just_layout();
+            let origin = harness.get_state(canvas_id).layout_rect().origin();
+            assert_eq!(
+                harness.get_state(dynamic).layout_rect(),
+                Rect::new(100., 50., 150., 70.)
+            );
+            assert_eq!(
+                harness.get_state(canvas_id).layout_rect().size(),
+                Size::new(150., 70.)
+            );
+            assert_eq!(origin, Point::new(125., 165.));
+        });
+    }
+}
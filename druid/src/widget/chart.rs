@@ -0,0 +1,570 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that draws a line or bar chart.
+
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, Circle, Line};
+use crate::piet::RenderContext;
+use crate::text::TextLayout;
+use crate::widget::prelude::*;
+use crate::{theme, ArcStr, Point, Rect, Selector};
+
+/// The size of a chart that isn't constrained.
+const DEFAULT_SIZE: Size = Size::new(300.0, 200.0);
+/// The space between the labels and the plot, and around the chart.
+const LABEL_PADDING: f64 = 4.0;
+/// About how many ticks an axis gets.
+const TARGET_TICKS: usize = 5;
+/// How close the pointer has to be to a point of a line chart to hover it.
+const HOVER_RADIUS: f64 = 8.0;
+/// The part of the space between bars that they fill.
+const BAR_FILL: f64 = 0.8;
+const LINE_WIDTH: f64 = 2.0;
+const POINT_RADIUS: f64 = 4.0;
+
+/// The data of a [`Chart`]: a series of `(x, y)` points.
+pub type Series = Arc<Vec<(f64, f64)>>;
+
+/// How a [`Chart`] draws its series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    /// A line through the points, in the order of the series.
+    Line,
+    /// A bar from zero to each point.
+    Bar,
+}
+
+/// A widget that draws a series of points as a line or bar chart.
+///
+/// The axes are scaled to fit the data, rounded out to "nice" tick values
+/// like 0, 20, 40, with gridlines and labels at every tick. A bar chart
+/// always includes zero. An empty series shows empty axes from 0 to 1, and
+/// a constant one is given some room above and below.
+///
+/// The series is drawn in the [`CHART_SERIES_COLOR`] and the gridlines in
+/// the [`CHART_GRID_COLOR`].
+///
+/// When the pointer moves onto a point of a line chart, or over a bar, the
+/// chart highlights it and submits a [`Chart::POINT_HOVERED`] notification,
+/// for instance to show the value in a tooltip.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use druid::widget::{Chart, ChartKind};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Dashboard {
+///     requests: Arc<Vec<(f64, f64)>>,
+/// }
+///
+/// fn requests_chart() -> impl Widget<Dashboard> {
+///     Chart::new(ChartKind::Bar).lens(Dashboard::requests)
+/// }
+/// ```
+///
+/// [`CHART_SERIES_COLOR`]: theme::CHART_SERIES_COLOR
+/// [`CHART_GRID_COLOR`]: theme::CHART_GRID_COLOR
+pub struct Chart {
+    kind: ChartKind,
+    x_axis: Axis,
+    y_axis: Axis,
+    /// The area inside the axes.
+    plot: Rect,
+    /// The width of a bar, in data units.
+    bar_width: f64,
+    hovered: Option<usize>,
+}
+
+/// The ticks and labels of one axis of a [`Chart`].
+struct Axis {
+    ticks: Ticks,
+    labels: Vec<TextLayout<ArcStr>>,
+}
+
+/// Evenly spaced tick values covering a range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Ticks {
+    /// The first tick, which is where the axis starts.
+    min: f64,
+    /// The last tick, which is where the axis ends.
+    max: f64,
+    step: f64,
+}
+
+impl Chart {
+    /// The notification submitted when the hovered point changes, with the
+    /// index of the point in the series and its value, or `None` when the
+    /// pointer moves away from it.
+    pub const POINT_HOVERED: Selector<Option<(usize, (f64, f64))>> =
+        Selector::new("druid-builtin.chart-point-hovered");
+
+    /// Create a new chart of the given kind.
+    pub fn new(kind: ChartKind) -> Self {
+        Chart {
+            kind,
+            x_axis: Axis::new(),
+            y_axis: Axis::new(),
+            plot: Rect::ZERO,
+            bar_width: 0.0,
+            hovered: None,
+        }
+    }
+
+    /// Returns the index of the hovered point, if any.
+    pub fn hovered(&self) -> Option<usize> {
+        self.hovered
+    }
+
+    /// Compute the ranges of the axes for `series`.
+    fn set_series(&mut self, series: &[(f64, f64)]) {
+        let points = || {
+            series
+                .iter()
+                .filter(|(x, y)| x.is_finite() && y.is_finite())
+        };
+        let (mut x_min, mut x_max) = min_max(points().map(|&(x, _)| x));
+        let (mut y_min, mut y_max) = min_max(points().map(|&(_, y)| y));
+        self.bar_width = 0.0;
+        if self.kind == ChartKind::Bar && x_min <= x_max {
+            // The bars are as wide as the smallest gap between them allows, and
+            // the axis leaves room for the outer halves of the first and last.
+            let mut xs: Vec<f64> = points().map(|&(x, _)| x).collect();
+            xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let spacing = xs
+                .windows(2)
+                .map(|pair| pair[1] - pair[0])
+                .filter(|gap| *gap > 0.0)
+                .fold(f64::INFINITY, f64::min);
+            let spacing = if spacing.is_finite() { spacing } else { 1.0 };
+            self.bar_width = spacing * BAR_FILL;
+            x_min -= spacing / 2.0;
+            x_max += spacing / 2.0;
+            y_min = y_min.min(0.0);
+            y_max = y_max.max(0.0);
+        }
+        self.x_axis
+            .set_ticks(Ticks::new(x_min, x_max, TARGET_TICKS));
+        self.y_axis
+            .set_ticks(Ticks::new(y_min, y_max, TARGET_TICKS));
+        trace!("Chart axes {:?} {:?}", self.x_axis.ticks, self.y_axis.ticks);
+    }
+
+    /// The position of a value in the widget.
+    fn to_point(&self, (x, y): (f64, f64)) -> Point {
+        Point::new(
+            self.plot.x0 + self.x_axis.ticks.fraction(x) * self.plot.width(),
+            self.plot.y1 - self.y_axis.ticks.fraction(y) * self.plot.height(),
+        )
+    }
+
+    /// The rectangle of the bar of a value.
+    fn bar_rect(&self, (x, y): (f64, f64)) -> Rect {
+        let half = self.bar_width / 2.0;
+        Rect::from_points(self.to_point((x - half, 0.0)), self.to_point((x + half, y)))
+    }
+
+    /// The point under `pos`, if any.
+    fn point_at(&self, pos: Point, series: &[(f64, f64)]) -> Option<usize> {
+        let points = series
+            .iter()
+            .enumerate()
+            .filter(|(_, (x, y))| x.is_finite() && y.is_finite());
+        match self.kind {
+            ChartKind::Line => points
+                .map(|(i, &value)| (i, self.to_point(value).distance(pos)))
+                .filter(|(_, distance)| *distance <= HOVER_RADIUS)
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .map(|(i, _)| i),
+            ChartKind::Bar => points
+                .filter(|(_, &value)| {
+                    // anywhere in the column of the bar
+                    let bar = self.bar_rect(value);
+                    pos.x >= bar.x0 && pos.x <= bar.x1 && self.plot.contains(pos)
+                })
+                .map(|(i, _)| i)
+                .next_back(),
+        }
+    }
+
+    fn set_hovered(&mut self, ctx: &mut EventCtx, hovered: Option<usize>, series: &[(f64, f64)]) {
+        if hovered != self.hovered {
+            self.hovered = hovered;
+            let value = hovered.map(|index| (index, series[index]));
+            ctx.submit_notification(Chart::POINT_HOVERED.with(value));
+            ctx.request_paint();
+        }
+    }
+
+    fn paint_grid(&self, ctx: &mut PaintCtx, env: &Env) {
+        let grid_color = env.get(theme::CHART_GRID_COLOR);
+        let axis_color = env.get(theme::BORDER_DARK);
+        for (i, value) in self.x_axis.ticks.values().enumerate() {
+            let x = self.to_point((value, 0.0)).x;
+            let color = if i == 0 { &axis_color } else { &grid_color };
+            ctx.stroke(Line::new((x, self.plot.y0), (x, self.plot.y1)), color, 1.0);
+            let label = &self.x_axis.labels[i];
+            let origin = (x - label.size().width / 2.0, self.plot.y1 + LABEL_PADDING);
+            label.draw(ctx, origin);
+        }
+        for (i, value) in self.y_axis.ticks.values().enumerate() {
+            let y = self.to_point((0.0, value)).y;
+            let color = if i == 0 { &axis_color } else { &grid_color };
+            ctx.stroke(Line::new((self.plot.x0, y), (self.plot.x1, y)), color, 1.0);
+            let label = &self.y_axis.labels[i];
+            let size = label.size();
+            let origin = (
+                self.plot.x0 - LABEL_PADDING - size.width,
+                y - size.height / 2.0,
+            );
+            label.draw(ctx, origin);
+        }
+    }
+}
+
+impl Axis {
+    fn new() -> Self {
+        Axis {
+            ticks: Ticks::new(0.0, 1.0, TARGET_TICKS),
+            labels: Vec::new(),
+        }
+    }
+
+    fn set_ticks(&mut self, ticks: Ticks) {
+        if ticks != self.ticks || self.labels.is_empty() {
+            self.ticks = ticks;
+            self.labels = ticks
+                .values()
+                .map(|value| TextLayout::from_text(ticks.label(value)))
+                .collect();
+        }
+    }
+
+    /// Build the labels, returning the size of the largest one.
+    fn layout_labels(&mut self, ctx: &mut LayoutCtx, env: &Env) -> Size {
+        let mut max = Size::ZERO;
+        for label in &mut self.labels {
+            label.rebuild_if_needed(ctx.text(), env);
+            let size = label.size();
+            max = Size::new(max.width.max(size.width), max.height.max(size.height));
+        }
+        max
+    }
+}
+
+impl Ticks {
+    /// Ticks with a "nice" step, for about `target` intervals, that cover
+    /// `min` to `max`.
+    ///
+    /// An empty range (`min > max`) gets the ticks of `0..1`, and a single
+    /// value some room on both sides.
+    fn new(min: f64, max: f64, target: usize) -> Self {
+        let (min, max) = if min > max {
+            (0.0, 1.0)
+        } else if min == max {
+            let pad = if min == 0.0 { 1.0 } else { min.abs() / 10.0 };
+            (min - pad, max + pad)
+        } else {
+            (min, max)
+        };
+        let step = nice_step((max - min) / target.max(1) as f64);
+        Ticks {
+            min: (min / step).floor() * step,
+            max: (max / step).ceil() * step,
+            step,
+        }
+    }
+
+    /// The values of the ticks.
+    fn values(self) -> impl Iterator<Item = f64> {
+        let count = ((self.max - self.min) / self.step).round() as usize;
+        (0..=count).map(move |i| self.min + i as f64 * self.step)
+    }
+
+    /// Where `value` is between the first and the last tick, from 0 to 1.
+    fn fraction(self, value: f64) -> f64 {
+        (value - self.min) / (self.max - self.min)
+    }
+
+    /// The label of a tick, with as many decimals as the step needs.
+    fn label(self, value: f64) -> ArcStr {
+        let decimals = (-self.step.log10().floor()).max(0.0) as usize;
+        // avoid "-0"
+        let value = if value.abs() < self.step / 1e6 {
+            0.0
+        } else {
+            value
+        };
+        format!("{:.*}", decimals, value).into()
+    }
+}
+
+/// The smallest of 1, 2 and 5 times a power of ten that is at least `step`.
+fn nice_step(step: f64) -> f64 {
+    let magnitude = 10f64.powf(step.log10().floor());
+    let fraction = step / magnitude;
+    let nice = if fraction <= 1.0 {
+        1.0
+    } else if fraction <= 2.0 {
+        2.0
+    } else if fraction <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+/// The smallest and the largest value; the first is larger if there are none.
+fn min_max(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+        (min.min(value), max.max(value))
+    })
+}
+
+impl Widget<Series> for Chart {
+    #[instrument(name = "Chart", level = "trace", skip(self, ctx, event, data, _env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Series, _env: &Env) {
+        if let Event::MouseMove(mouse) = event {
+            let hovered = if ctx.is_hot() {
+                self.point_at(mouse.pos, data)
+            } else {
+                None
+            };
+            self.set_hovered(ctx, hovered, data);
+        }
+    }
+
+    #[instrument(name = "Chart", level = "trace", skip(self, _ctx, event, data, _env))]
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Series, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.set_series(data);
+        }
+    }
+
+    #[instrument(name = "Chart", level = "trace", skip(self, ctx, old_data, data, _env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &Series, data: &Series, _env: &Env) {
+        if !old_data.same(data) {
+            self.set_series(data);
+            if matches!(self.hovered, Some(index) if index >= data.len()) {
+                self.hovered = None;
+            }
+            ctx.request_layout();
+        }
+        for label in self
+            .x_axis
+            .labels
+            .iter_mut()
+            .chain(self.y_axis.labels.iter_mut())
+        {
+            if label.needs_rebuild_after_update(ctx) {
+                ctx.request_layout();
+            }
+        }
+        if ctx.env_key_changed(&theme::CHART_SERIES_COLOR)
+            || ctx.env_key_changed(&theme::CHART_GRID_COLOR)
+        {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Chart", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &Series,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("Chart");
+        let size = bc.constrain(DEFAULT_SIZE);
+        let x_labels = self.x_axis.layout_labels(ctx, env);
+        let y_labels = self.y_axis.layout_labels(ctx, env);
+        // Leave room for half of the outermost labels, which are centered on the ends of the axes.
+        self.plot = Rect::new(
+            LABEL_PADDING + y_labels.width + LABEL_PADDING,
+            LABEL_PADDING + y_labels.height / 2.0,
+            size.width - LABEL_PADDING - x_labels.width / 2.0,
+            size.height - LABEL_PADDING - x_labels.height - LABEL_PADDING,
+        );
+        if self.plot.width() <= 0.0 || self.plot.height() <= 0.0 {
+            self.plot = size.to_rect();
+        }
+        size
+    }
+
+    #[instrument(name = "Chart", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Series, env: &Env) {
+        self.paint_grid(ctx, env);
+
+        let color = env.get(theme::CHART_SERIES_COLOR);
+        let points = data.iter().filter(|(x, y)| x.is_finite() && y.is_finite());
+        match self.kind {
+            ChartKind::Line => {
+                let mut path = BezPath::new();
+                for (i, &value) in points.enumerate() {
+                    if i == 0 {
+                        path.move_to(self.to_point(value));
+                    } else {
+                        path.line_to(self.to_point(value));
+                    }
+                }
+                ctx.stroke(path, &color, LINE_WIDTH);
+                if let Some(index) = self.hovered {
+                    let circle = Circle::new(self.to_point(data[index]), POINT_RADIUS);
+                    ctx.fill(circle, &color);
+                }
+            }
+            ChartKind::Bar => {
+                for &value in points {
+                    ctx.fill(self.bar_rect(value), &color);
+                }
+                if let Some(index) = self.hovered {
+                    let bar = self.bar_rect(data[index]).inset(-1.0);
+                    ctx.stroke(bar, &env.get(theme::TEXT_COLOR), 1.0);
+                }
+            }
+        }
+    }
+
+    fn debug_state(&self, data: &Series) -> DebugState {
+        let mut state = DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!("{:?}", data),
+            ..Default::default()
+        };
+        let labels = |axis: &Axis| {
+            let ticks = axis.ticks;
+            ticks
+                .values()
+                .map(|value| ticks.label(value).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        state.other_values.insert("x".into(), labels(&self.x_axis));
+        state.other_values.insert("y".into(), labels(&self.y_axis));
+        if let Some(index) = self.hovered {
+            state
+                .other_values
+                .insert("hovered".into(), index.to_string());
+        }
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{Controller, Flex};
+    use crate::WidgetExt;
+    use test_log::test;
+
+    fn labels(min: f64, max: f64) -> Vec<String> {
+        let ticks = Ticks::new(min, max, TARGET_TICKS);
+        ticks
+            .values()
+            .map(|value| ticks.label(value).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn nice_ticks() {
+        assert_eq!(labels(0.0, 100.0), ["0", "20", "40", "60", "80", "100"]);
+        assert_eq!(labels(3.0, 97.0), ["0", "20", "40", "60", "80", "100"]);
+        assert_eq!(
+            labels(-0.3, 0.45),
+            ["-0.4", "-0.2", "0.0", "0.2", "0.4", "0.6"]
+        );
+        assert_eq!(labels(1000.0, 1012.0), ["1000", "1005", "1010", "1015"]);
+        // empty
+        assert_eq!(labels(f64::INFINITY, f64::NEG_INFINITY), labels(0.0, 1.0));
+        assert_eq!(labels(0.0, 1.0), ["0.0", "0.2", "0.4", "0.6", "0.8", "1.0"]);
+        // constant
+        assert_eq!(
+            labels(5.0, 5.0),
+            ["4.4", "4.6", "4.8", "5.0", "5.2", "5.4", "5.6"]
+        );
+        assert_eq!(labels(0.0, 0.0), ["-1.0", "-0.5", "0.0", "0.5", "1.0"]);
+    }
+
+    /// Records the hover notifications of its child.
+    struct Hovers(Rc<RefCell<Vec<Option<usize>>>>);
+
+    impl<W: Widget<Series>> Controller<Series, W> for Hovers {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut Series,
+            env: &Env,
+        ) {
+            if let Event::Notification(note) = event {
+                if let Some(hovered) = note.get(Chart::POINT_HOVERED) {
+                    self.0.borrow_mut().push(hovered.map(|(index, _)| index));
+                    ctx.set_handled();
+                }
+            }
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[test]
+    fn hover_points() {
+        let hovers = Rc::new(RefCell::new(Vec::new()));
+        let series: Series = Arc::new(vec![(0.0, 0.0), (1.0, 10.0), (2.0, 5.0)]);
+        let widget = Flex::column()
+            .with_child(Chart::new(ChartKind::Bar).fix_size(400.0, 400.0))
+            .controller(Hovers(hovers.clone()));
+
+        Harness::create_simple(series, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            let mut state = harness.root_debug_state();
+            while state.display_name != "Chart" {
+                state = state.children.remove(0);
+            }
+            assert_eq!(state.other_values["x"], "-1 0 1 2 3");
+            assert_eq!(state.other_values["y"], "0 2 4 6 8 10");
+
+            // The middle bar is in the middle of the chart; hover above it.
+            harness.event(Event::MouseMove(move_mouse((210.0, 50.0))));
+            harness.event(Event::MouseMove(move_mouse((212.0, 60.0))));
+            harness.event(Event::MouseMove(move_mouse((300.0, 50.0))));
+            harness.event(Event::MouseMove(move_mouse((399.0, 50.0))));
+            harness.event(Event::MouseMove(move_mouse((500.0, 50.0))));
+            assert_eq!(*hovers.borrow(), [Some(1), Some(2), None]);
+
+            // An empty series has no points to hover.
+            harness.update_data(|series| *series = Arc::new(Vec::new()));
+            harness.event(Event::MouseMove(move_mouse((210.0, 50.0))));
+            assert_eq!(hovers.borrow().len(), 3);
+            harness.paint();
+        });
+    }
+}
@@ -14,14 +14,25 @@
 
 //! A checkbox widget.
 
+use crate::accessibility::Role;
 use crate::debug_state::DebugState;
 use crate::kurbo::{BezPath, Size};
 use crate::piet::{LineCap, LineJoin, LinearGradient, RenderContext, StrokeStyle, UnitPoint};
 use crate::theme;
 use crate::widget::{prelude::*, Label, LabelText};
+use crate::KbKey;
 use tracing::{instrument, trace};
 
+/// How far the focus ring is drawn outside of the box.
+const FOCUS_RING_INSET: f64 = 2.0;
+
 /// A checkbox that toggles a `bool`.
+///
+/// The checkbox can be focused with the keyboard; while it has focus, it is
+/// drawn with a [`FOCUS_RING_COLOR`] ring, and pressing space or enter toggles
+/// it just like a click.
+///
+/// [`FOCUS_RING_COLOR`]: crate::theme::FOCUS_RING_COLOR
 pub struct Checkbox {
     child_label: Label<bool>,
 }
@@ -50,6 +61,7 @@ impl Widget<bool> for Checkbox {
             Event::MouseDown(_) => {
                 if !ctx.is_disabled() {
                     ctx.set_active(true);
+                    ctx.request_focus();
                     ctx.request_paint();
                     trace!("Checkbox {:?} pressed", ctx.widget_id());
                 }
@@ -69,6 +81,14 @@ impl Widget<bool> for Checkbox {
                 }
                 ctx.set_active(false);
             }
+            Event::KeyDown(key)
+                if ctx.is_focused() && !ctx.is_disabled() && is_toggle_key(&key.key) =>
+            {
+                *data = !*data;
+                trace!("Checkbox {:?} toggled with the keyboard", ctx.widget_id());
+                ctx.request_paint();
+                ctx.set_handled();
+            }
             _ => (),
         }
     }
@@ -76,8 +96,17 @@ impl Widget<bool> for Checkbox {
     #[instrument(name = "CheckBox", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &bool, env: &Env) {
         self.child_label.lifecycle(ctx, event, data, env);
-        if let LifeCycle::HotChanged(_) | LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::WidgetAdded => {
+                // the label has already set our name
+                ctx.set_access_role(Role::CheckBox);
+                ctx.set_access_checked(*data);
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_)
+            | LifeCycle::DisabledChanged(_)
+            | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
     }
 
@@ -88,6 +117,7 @@ impl Widget<bool> for Checkbox {
     )]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &bool, data: &bool, env: &Env) {
         self.child_label.update(ctx, old_data, data, env);
+        ctx.set_access_checked(*data);
         ctx.request_paint();
     }
 
@@ -98,6 +128,9 @@ impl Widget<bool> for Checkbox {
         let check_size = env.get(theme::BASIC_WIDGET_HEIGHT);
         let label_size = self.child_label.layout(ctx, bc, data, env);
 
+        // Leave room for the focus ring around the box.
+        ctx.set_paint_insets(FOCUS_RING_INSET);
+
         let desired_size = Size::new(
             check_size + x_padding + label_size.width,
             check_size.max(label_size.height),
@@ -125,8 +158,12 @@ impl Widget<bool> for Checkbox {
             UnitPoint::TOP,
             UnitPoint::BOTTOM,
             (
-                env.get(theme::BACKGROUND_LIGHT),
-                env.get(theme::BACKGROUND_DARK),
+                ctx.resolve_disabled(
+                    env,
+                    theme::BACKGROUND_LIGHT,
+                    theme::DISABLED_BACKGROUND_LIGHT,
+                ),
+                ctx.resolve_disabled(env, theme::BACKGROUND_DARK, theme::DISABLED_BACKGROUND_DARK),
             ),
         );
 
@@ -140,6 +177,14 @@ impl Widget<bool> for Checkbox {
 
         ctx.stroke(rect, &border_color, border_width);
 
+        if ctx.is_focused() && !ctx.is_disabled() {
+            let ring = Size::new(size, size)
+                .to_rect()
+                .inset(FOCUS_RING_INSET - 0.5)
+                .to_rounded_rect(3.5);
+            ctx.stroke(ring, &env.get(theme::FOCUS_RING_COLOR), 1.0);
+        }
+
         if *data {
             // Paint the checkmark
             let x_offset = (rect.width() - 10.0) / 2.0;
@@ -179,3 +224,48 @@ impl Widget<bool> for Checkbox {
         }
     }
 }
+
+/// Returns `true` for the keys that toggle a focused [`Checkbox`] or [`Switch`].
+///
+/// [`Switch`]: super::Switch
+pub(crate) fn is_toggle_key(key: &KbKey) -> bool {
+    match key {
+        KbKey::Enter => true,
+        KbKey::Character(c) => c == " ",
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{press_key, widget_ids, FocusOnCommand, REQUEST_FOCUS};
+    use crate::WidgetExt;
+    use test_log::test;
+
+    #[test]
+    fn toggles_with_keyboard() {
+        let [id] = widget_ids();
+        let checkbox = Checkbox::new("check")
+            .controller(FocusOnCommand)
+            .with_id(id)
+            .center();
+
+        Harness::create_simple(false, checkbox, |harness| {
+            harness.send_initial_events();
+            // Not focused yet, so keys are ignored.
+            press_key(harness, KbKey::Character(" ".into()));
+            assert!(!*harness.data());
+
+            harness.submit_command(REQUEST_FOCUS.to(id));
+            assert!(harness.get_state(id).has_focus);
+            press_key(harness, KbKey::Character(" ".into()));
+            assert!(*harness.data());
+            press_key(harness, KbKey::Enter);
+            assert!(!*harness.data());
+            press_key(harness, KbKey::Character("a".into()));
+            assert!(!*harness.data());
+        });
+    }
+}
@@ -16,6 +16,7 @@
 //!
 //! [`Controller`]: struct.Controller.html
 
+use crate::commands::CLICK;
 use crate::widget::Controller;
 use crate::{Data, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, MouseButton, Widget};
 use tracing::{instrument, trace};
@@ -75,6 +76,13 @@ impl<T: Data, W: Widget<T>> Controller<T, W> for Click<T> {
                     trace!("Widget {:?} released", ctx.widget_id());
                 }
             }
+            Event::Command(cmd) if cmd.is(CLICK) => {
+                if !ctx.is_disabled() {
+                    (self.action)(ctx, data, env);
+                }
+                ctx.set_handled();
+                return;
+            }
             _ => {}
         }
 
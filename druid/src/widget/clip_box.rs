@@ -12,12 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::commands::SCROLL_TO_VIEW;
+use crate::commands::{SCROLL_TO_VIEW, VIEWPORT_CHANGED, WATCH_VIEWPORT};
 use crate::debug_state::DebugState;
 use crate::kurbo::{Affine, Point, Rect, Size, Vec2};
 use crate::widget::prelude::*;
 use crate::widget::Axis;
-use crate::{Data, WidgetPod};
+use crate::{Data, WidgetId, WidgetPod};
 use tracing::{instrument, trace};
 
 /// Represents the size and position of a rectangular "viewport" into a larger area.
@@ -133,6 +133,10 @@ pub struct ClipBox<T, W> {
     constrain_horizontal: bool,
     constrain_vertical: bool,
     must_fill: bool,
+    /// The widgets that asked to be told about viewport changes.
+    viewport_watchers: Vec<WidgetId>,
+    /// The viewport that was last sent to the watchers.
+    notified_viewport: Option<Rect>,
 }
 
 impl<T, W> ClipBox<T, W> {
@@ -244,6 +248,8 @@ impl<T, W: Widget<T>> ClipBox<T, W> {
             constrain_horizontal: false,
             constrain_vertical: false,
             must_fill: false,
+            viewport_watchers: Vec::new(),
+            notified_viewport: None,
         }
     }
 
@@ -343,9 +349,50 @@ impl<T, W: Widget<T>> ClipBox<T, W> {
     }
 }
 
+impl<T: Data, W: Widget<T>> ClipBox<T, W> {
+    /// Send [`VIEWPORT_CHANGED`] to the widgets that asked for it, if the viewport
+    /// has changed since it was last sent.
+    ///
+    /// The `ClipBox` does this by itself at the end of `event` and `layout`;
+    /// widgets that contain a `ClipBox` and move its viewport after passing it an
+    /// event should call this when they are done.
+    ///
+    /// [`VIEWPORT_CHANGED`]: crate::commands::VIEWPORT_CHANGED
+    pub fn notify_viewport_change(&mut self, ctx: &mut EventCtx) {
+        if let Some(view_rect) = self.changed_viewport() {
+            for id in &self.viewport_watchers {
+                ctx.submit_command(VIEWPORT_CHANGED.with(view_rect).to(*id));
+            }
+        }
+    }
+
+    /// Returns the viewport if it has to be sent to the watchers.
+    fn changed_viewport(&mut self) -> Option<Rect> {
+        let view_rect = self.port.view_rect();
+        if self.viewport_watchers.is_empty() || self.notified_viewport == Some(view_rect) {
+            return None;
+        }
+        trace!("Viewport changed: {}", view_rect);
+        self.notified_viewport = Some(view_rect);
+        Some(view_rect)
+    }
+}
+
 impl<T: Data, W: Widget<T>> Widget<T> for ClipBox<T, W> {
     #[instrument(name = "ClipBox", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Notification(notification) = event {
+            if notification.is(WATCH_VIEWPORT) {
+                ctx.set_handled();
+                let id = notification.source();
+                if !self.viewport_watchers.contains(&id) {
+                    self.viewport_watchers.push(id);
+                }
+                let view_rect = self.port.view_rect();
+                ctx.submit_command(VIEWPORT_CHANGED.with(view_rect).to(id));
+                return;
+            }
+        }
         let viewport = ctx.size().to_rect();
         let force_event = self.child.is_hot() || self.child.has_active();
         if let Some(child_event) =
@@ -353,6 +400,7 @@ impl<T: Data, W: Widget<T>> Widget<T> for ClipBox<T, W> {
         {
             self.child.event(ctx, &child_event, data, env);
         }
+        self.notify_viewport_change(ctx);
     }
 
     #[instrument(name = "ClipBox", level = "trace", skip(self, ctx, event, data, env))]
@@ -394,6 +442,11 @@ impl<T: Data, W: Widget<T>> Widget<T> for ClipBox<T, W> {
         self.port.view_size = bc.constrain(content_size);
         let new_offset = self.port.clamp_view_origin(self.viewport_origin());
         self.pan_to(new_offset);
+        if let Some(view_rect) = self.changed_viewport() {
+            for id in &self.viewport_watchers {
+                ctx.submit_command(VIEWPORT_CHANGED.with(view_rect).to(*id));
+            }
+        }
         trace!("Computed sized: {}", self.viewport_size());
         self.viewport_size()
     }
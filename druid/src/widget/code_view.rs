@@ -0,0 +1,892 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows syntax-highlighted source code.
+
+use std::ops::Range;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::piet::{PietText, RenderContext};
+use crate::text::{Attribute, RichText, TextLayout};
+use crate::widget::prelude::*;
+use crate::widget::Scroll;
+use crate::{
+    commands, theme, Application, ArcStr, Color, Cursor, HotKey, Key, Point, Rect, SysMods, Vec2,
+    WidgetPod,
+};
+
+/// The space between the text and the edges of the code.
+const TEXT_PADDING: f64 = 4.0;
+/// The space on each side of the line numbers.
+const GUTTER_PADDING: f64 = 8.0;
+/// How many columns a tab is counted as, to estimate the width of the code.
+const TAB_COLUMNS: usize = 4;
+
+/// The kind of a highlighted piece of source code.
+///
+/// Each kind is drawn with the color of one of the `CODE_*` keys in
+/// [`theme`]; text that isn't part of a token has the [`TEXT_COLOR`].
+///
+/// [`TEXT_COLOR`]: theme::TEXT_COLOR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// A keyword, such as `fn` or `if`.
+    Keyword,
+    /// The name of a type.
+    Type,
+    /// The name of a function or macro.
+    Function,
+    /// A string or character literal.
+    String,
+    /// A numeric literal.
+    Number,
+    /// A comment.
+    Comment,
+}
+
+impl TokenKind {
+    /// The key of the color this kind of token is drawn with.
+    pub fn color_key(self) -> Key<Color> {
+        match self {
+            TokenKind::Keyword => theme::CODE_KEYWORD_COLOR,
+            TokenKind::Type => theme::CODE_TYPE_COLOR,
+            TokenKind::Function => theme::CODE_FUNCTION_COLOR,
+            TokenKind::String => theme::CODE_STRING_COLOR,
+            TokenKind::Number => theme::CODE_NUMBER_COLOR,
+            TokenKind::Comment => theme::CODE_COMMENT_COLOR,
+        }
+    }
+}
+
+/// Something that finds the tokens of source code, for a [`CodeView`].
+///
+/// Code is highlighted one line at a time. The `State` is what the
+/// highlighter needs to know about the lines before the current one, like
+/// being inside a block comment; the first line starts with the default
+/// state, and each line starts with the state the previous one ended with.
+///
+/// When the code changes, a [`CodeView`] only highlights the lines that
+/// changed, and the following lines until one of them starts with the same
+/// state as before, so states should compare equal whenever they would lead
+/// to the same tokens.
+///
+/// This is the extension point for highlighting libraries such as [syntect];
+/// their parse state can be used as the `State`.
+///
+/// # Examples
+///
+/// ```
+/// use std::ops::Range;
+///
+/// use druid::widget::{Highlighter, TokenKind};
+///
+/// /// Highlights `#` comments, and nothing else.
+/// struct Comments;
+///
+/// impl Highlighter for Comments {
+///     type State = ();
+///
+///     fn highlight_line(
+///         &self,
+///         _language: &str,
+///         line: &str,
+///         _state: &mut (),
+///     ) -> Vec<(Range<usize>, TokenKind)> {
+///         match line.find('#') {
+///             Some(start) => vec![(start..line.len(), TokenKind::Comment)],
+///             None => Vec::new(),
+///         }
+///     }
+/// }
+/// ```
+///
+/// [syntect]: https://crates.io/crates/syntect
+pub trait Highlighter {
+    /// What is carried over from one line to the next.
+    type State: Clone + PartialEq + Default;
+
+    /// Find the tokens of a line of code in `language`, updating `state` to
+    /// the state at the end of the line.
+    ///
+    /// The ranges are byte ranges into `line`, which doesn't include the line
+    /// break. They shouldn't overlap.
+    fn highlight_line(
+        &self,
+        language: &str,
+        line: &str,
+        state: &mut Self::State,
+    ) -> Vec<(Range<usize>, TokenKind)>;
+}
+
+/// A [`Highlighter`] that doesn't highlight anything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainText;
+
+impl Highlighter for PlainText {
+    type State = ();
+
+    fn highlight_line(&self, _: &str, _: &str, _: &mut ()) -> Vec<(Range<usize>, TokenKind)> {
+        Vec::new()
+    }
+}
+
+/// A widget that shows syntax-highlighted source code.
+///
+/// The code is the widget's data, and is highlighted by a [`Highlighter`]
+/// for a language identifier, such as `"rust"`; what identifiers mean is up
+/// to the highlighter. It is drawn in [`CODE_FONT`], with the token colors of
+/// the `CODE_*` keys in [`theme`], and scrolls in both directions. Line
+/// numbers can be shown in a gutter to the left of the code, with
+/// [`with_line_numbers`].
+///
+/// The code can't be edited, but it can be selected with the mouse, and
+/// copied with Ctrl+C (Cmd+C on macOS) or the [`COPY`] command, like a
+/// [selectable label].
+///
+/// Large files are handled by only laying out the lines that are shown, and
+/// by only highlighting the lines that changed when the data changes.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::CodeView;
+/// use druid::Widget;
+///
+/// fn source_view() -> impl Widget<String> {
+///     CodeView::new("rust").with_line_numbers(true)
+/// }
+/// ```
+///
+/// [`CODE_FONT`]: theme::CODE_FONT
+/// [`with_line_numbers`]: CodeView::with_line_numbers
+/// [`COPY`]: crate::commands::COPY
+/// [selectable label]: super::Label::selectable
+pub struct CodeView<T, H: Highlighter = PlainText> {
+    code: WidgetPod<T, Scroll<T, CodeText<H>>>,
+    line_numbers: bool,
+    /// The layouts of the line numbers, built as they are shown.
+    numbers: Vec<Option<TextLayout<ArcStr>>>,
+    gutter_width: f64,
+    /// The scroll offset the gutter was last painted with.
+    offset: Vec2,
+}
+
+impl<T: Data + AsRef<str>> CodeView<T> {
+    /// Create a widget showing code in `language`, without highlighting it.
+    ///
+    /// Use [`with_highlighter`] to highlight it.
+    ///
+    /// [`with_highlighter`]: CodeView::with_highlighter
+    pub fn new(language: impl Into<String>) -> Self {
+        CodeView::with_highlighter(language, PlainText)
+    }
+}
+
+impl<T: Data + AsRef<str>, H: Highlighter> CodeView<T, H> {
+    /// Create a widget showing code in `language`, highlighted by
+    /// `highlighter`.
+    pub fn with_highlighter(language: impl Into<String>, highlighter: H) -> Self {
+        CodeView {
+            code: WidgetPod::new(
+                Scroll::new(CodeText::new(language.into(), highlighter)).content_must_fill(true),
+            ),
+            line_numbers: false,
+            numbers: Vec::new(),
+            gutter_width: 0.0,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    /// Builder-style method to set whether line numbers are shown.
+    pub fn with_line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// Set the language of the code.
+    ///
+    /// The code has to be highlighted again, and laid out; you are
+    /// responsible for calling [`request_layout`].
+    ///
+    /// [`request_layout`]: crate::EventCtx::request_layout
+    pub fn set_language(&mut self, language: impl Into<String>) {
+        self.text_mut().set_language(language.into());
+    }
+
+    /// Returns the selected code, if any.
+    pub fn selected_text(&self) -> Option<String> {
+        self.text().selected_text()
+    }
+
+    fn text(&self) -> &CodeText<H> {
+        self.code.widget().child()
+    }
+
+    fn text_mut(&mut self) -> &mut CodeText<H> {
+        self.code.widget_mut().child_mut()
+    }
+
+    /// Request a paint if the code scrolled, to move the line numbers too.
+    fn check_offset(&mut self, ctx: &mut EventCtx) {
+        let offset = self.code.widget().offset();
+        if self.line_numbers && offset != self.offset {
+            self.offset = offset;
+            ctx.request_paint();
+        }
+    }
+
+    fn paint_gutter(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        let line_height = self.text().line_height;
+        let lines = self.text().lines.len();
+        self.offset = self.code.widget().offset();
+        if self.numbers.len() != lines {
+            self.numbers.resize_with(lines, || None);
+        }
+        let gutter = Rect::new(0.0, 0.0, self.gutter_width, ctx.size().height);
+        let region = ctx.region().bounding_box().intersect(gutter);
+        let visible = visible_lines(
+            region.y0 + self.offset.y,
+            region.y1 + self.offset.y,
+            line_height,
+            lines,
+        );
+
+        ctx.with_save(|ctx| {
+            ctx.clip(gutter);
+            for line in visible {
+                let layout = self.numbers[line].get_or_insert_with(|| {
+                    let mut layout = TextLayout::from_text((line + 1).to_string());
+                    layout.set_font(theme::CODE_FONT);
+                    layout.set_text_color(theme::CODE_LINE_NUMBER_COLOR);
+                    layout
+                });
+                layout.rebuild_if_needed(ctx.text(), env);
+                let x = self.gutter_width - GUTTER_PADDING - layout.size().width;
+                let y = line as f64 * line_height - self.offset.y;
+                layout.draw(ctx, (x, y));
+            }
+        });
+    }
+}
+
+impl<T: Data + AsRef<str>, H: Highlighter> Widget<T> for CodeView<T, H> {
+    #[instrument(name = "CodeView", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.code.event(ctx, event, data, env);
+        self.check_offset(ctx);
+    }
+
+    #[instrument(name = "CodeView", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.code.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "CodeView",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if ctx.env_key_changed(&theme::CODE_FONT)
+            || ctx.env_key_changed(&theme::CODE_LINE_NUMBER_COLOR)
+        {
+            self.numbers.clear();
+            ctx.request_layout();
+        }
+        self.code.update(ctx, data, env);
+    }
+
+    #[instrument(name = "CodeView", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("CodeView");
+        self.gutter_width = if self.line_numbers {
+            let text = self.text();
+            let digits = text.lines.len().max(10).to_string().len();
+            digits as f64 * text.advance + 2.0 * GUTTER_PADDING
+        } else {
+            0.0
+        };
+        let code_bc = bc.shrink((self.gutter_width, 0.0));
+        let code_size = self.code.layout(ctx, &code_bc, data, env);
+        self.code
+            .set_origin(ctx, data, env, Point::new(self.gutter_width, 0.0));
+        bc.constrain(Size::new(
+            code_size.width + self.gutter_width,
+            code_size.height,
+        ))
+    }
+
+    #[instrument(name = "CodeView", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if self.line_numbers {
+            self.paint_gutter(ctx, env);
+        }
+        self.code.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut state = DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.as_ref().to_string(),
+            ..Default::default()
+        };
+        if let Some(selection) = self.selected_text() {
+            state.other_values.insert("selection".into(), selection);
+        }
+        state
+    }
+}
+
+/// A position in the code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct TextPos {
+    line: usize,
+    /// The byte offset in the line.
+    offset: usize,
+}
+
+/// A highlighted line of code.
+struct CodeLine<S> {
+    text: ArcStr,
+    /// The highlighter state at the end of the line.
+    end: S,
+    tokens: Vec<(Range<usize>, TokenKind)>,
+    /// The width of the line in columns.
+    columns: usize,
+    /// The layout of the line, built when it is first shown.
+    layout: Option<TextLayout<RichText>>,
+}
+
+/// The scrolled content of a [`CodeView`], also used by [`DiffView`].
+///
+/// [`DiffView`]: super::DiffView
+pub(crate) struct CodeText<H: Highlighter> {
+    highlighter: H,
+    language: String,
+    lines: Vec<CodeLine<H::State>>,
+    /// The widest line, in columns.
+    columns: usize,
+    line_height: f64,
+    /// The width of a column.
+    advance: f64,
+    anchor: TextPos,
+    active: TextPos,
+    /// The background colors of the lines, if they have one.
+    backgrounds: Vec<Option<Key<Color>>>,
+}
+
+impl<H: Highlighter> CodeText<H> {
+    pub(crate) fn new(language: String, highlighter: H) -> Self {
+        CodeText {
+            highlighter,
+            language,
+            lines: Vec::new(),
+            columns: 0,
+            line_height: 0.0,
+            advance: 0.0,
+            anchor: TextPos::default(),
+            active: TextPos::default(),
+            backgrounds: Vec::new(),
+        }
+    }
+
+    /// Set the background colors of the lines.
+    ///
+    /// The backgrounds cover the whole width of the code; lines past the end
+    /// of `backgrounds` have none.
+    pub(crate) fn set_backgrounds(&mut self, backgrounds: Vec<Option<Key<Color>>>) {
+        self.backgrounds = backgrounds;
+    }
+
+    fn set_language(&mut self, language: String) {
+        if language != self.language {
+            self.language = language;
+            let mut state = H::State::default();
+            for line in &mut self.lines {
+                line.tokens =
+                    self.highlighter
+                        .highlight_line(&self.language, &line.text, &mut state);
+                line.end = state.clone();
+                line.layout = None;
+            }
+        }
+    }
+
+    fn highlight(&self, text: &str, state: &mut H::State) -> CodeLine<H::State> {
+        let tokens = self.highlighter.highlight_line(&self.language, text, state);
+        let columns = text
+            .chars()
+            .map(|c| if c == '\t' { TAB_COLUMNS } else { 1 })
+            .sum();
+        CodeLine {
+            text: text.into(),
+            end: state.clone(),
+            tokens,
+            columns,
+            layout: None,
+        }
+    }
+
+    /// Replace the code, only highlighting the lines that changed, and the lines
+    /// after them that don't start with the same state as before.
+    fn set_source(&mut self, source: &str) {
+        let new: Vec<&str> = source
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect();
+        let mut lines = std::mem::take(&mut self.lines);
+        let prefix = lines
+            .iter()
+            .zip(&new)
+            .take_while(|(old, new)| &*old.text == **new)
+            .count();
+        let suffix = lines[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(old, new)| &*old.text == **new)
+            .count();
+
+        let old_suffix = lines.split_off(lines.len() - suffix);
+        // the state the first line of the suffix used to start with
+        let mut old_state = lines
+            .last()
+            .map(|line| line.end.clone())
+            .unwrap_or_default();
+        lines.truncate(prefix);
+        let mut state = lines
+            .last()
+            .map(|line| line.end.clone())
+            .unwrap_or_default();
+        let changed = &new[prefix..new.len() - suffix];
+        for text in changed {
+            lines.push(self.highlight(text, &mut state));
+        }
+        let mut highlighted = changed.len();
+        let mut old_suffix = old_suffix.into_iter();
+        for line in old_suffix.by_ref() {
+            if state == old_state {
+                lines.push(line);
+                break;
+            }
+            old_state = line.end.clone();
+            lines.push(self.highlight(&line.text, &mut state));
+            highlighted += 1;
+        }
+        lines.extend(old_suffix);
+        trace!("Highlighted {} of {} lines", highlighted, lines.len());
+
+        self.columns = lines.iter().map(|line| line.columns).max().unwrap_or(0);
+        self.lines = lines;
+        self.anchor = TextPos::default();
+        self.active = TextPos::default();
+    }
+
+    /// Measure the code font.
+    fn measure(&mut self, factory: &mut PietText, env: &Env) {
+        let mut layout = TextLayout::<ArcStr>::from_text("0");
+        layout.set_font(theme::CODE_FONT);
+        layout.rebuild_if_needed(factory, env);
+        let size = layout.size();
+        self.line_height = size.height;
+        self.advance = size.width;
+    }
+
+    /// The layout of a line, built if needed.
+    fn line_layout(
+        &mut self,
+        line: usize,
+        factory: &mut PietText,
+        env: &Env,
+    ) -> &TextLayout<RichText> {
+        let CodeLine {
+            text,
+            tokens,
+            layout,
+            ..
+        } = &mut self.lines[line];
+        let layout = layout.get_or_insert_with(|| {
+            let mut text = RichText::new(text.clone());
+            for (range, kind) in tokens.iter() {
+                text.add_attribute(range.clone(), Attribute::text_color(kind.color_key()));
+            }
+            let mut layout = TextLayout::from_text(text);
+            layout.set_font(theme::CODE_FONT);
+            layout
+        });
+        layout.rebuild_if_needed(factory, env);
+        layout
+    }
+
+    fn position_for_point(&mut self, point: Point, factory: &mut PietText, env: &Env) -> TextPos {
+        let line = (point.y / self.line_height).floor().max(0.0) as usize;
+        let line = line.min(self.lines.len().saturating_sub(1));
+        let point = Point::new(point.x - TEXT_PADDING, self.line_height / 2.0);
+        let offset = self
+            .line_layout(line, factory, env)
+            .text_position_for_point(point);
+        TextPos { line, offset }
+    }
+
+    /// The start and end of the selection.
+    fn selection(&self) -> (TextPos, TextPos) {
+        (self.anchor.min(self.active), self.anchor.max(self.active))
+    }
+
+    pub(crate) fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection();
+        if start == end {
+            return None;
+        }
+        let mut text = String::new();
+        for line in start.line..=end.line {
+            let line_text = &self.lines[line].text;
+            let from = if line == start.line { start.offset } else { 0 };
+            let to = if line == end.line {
+                end.offset
+            } else {
+                line_text.len()
+            };
+            if line > start.line {
+                text.push('\n');
+            }
+            text.push_str(&line_text[from..to]);
+        }
+        Some(text)
+    }
+
+    /// Set the selection, returning `true` if it changed.
+    fn set_selection(&mut self, anchor: TextPos, active: TextPos) -> bool {
+        let changed = (anchor, active) != (self.anchor, self.active);
+        self.anchor = anchor;
+        self.active = active;
+        changed
+    }
+
+    fn select_all(&mut self) -> bool {
+        let end = match self.lines.last() {
+            Some(line) => TextPos {
+                line: self.lines.len() - 1,
+                offset: line.text.len(),
+            },
+            None => TextPos::default(),
+        };
+        self.set_selection(TextPos::default(), end)
+    }
+
+    fn copy_selection(&self) {
+        if let Some(text) = self.selected_text() {
+            Application::global().clipboard().put_string(text);
+        }
+    }
+}
+
+/// The lines that are at least partly between `y0` and `y1`.
+fn visible_lines(y0: f64, y1: f64, line_height: f64, lines: usize) -> Range<usize> {
+    if line_height <= 0.0 {
+        return 0..0;
+    }
+    let first = (y0 / line_height).floor().max(0.0) as usize;
+    let last = (y1 / line_height).ceil().max(0.0) as usize;
+    first.min(lines)..last.min(lines)
+}
+
+impl<T: Data + AsRef<str>, H: Highlighter> Widget<T> for CodeText<H> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                let pos = self.position_for_point(mouse.pos, ctx.text(), env);
+                let anchor = if mouse.mods.shift() { self.anchor } else { pos };
+                if self.set_selection(anchor, pos) {
+                    ctx.request_paint();
+                }
+                ctx.set_active(true);
+                ctx.request_focus();
+            }
+            Event::MouseMove(mouse) => {
+                ctx.set_cursor(&Cursor::IBeam);
+                if ctx.is_active() {
+                    let pos = self.position_for_point(mouse.pos, ctx.text(), env);
+                    if self.set_selection(self.anchor, pos) {
+                        ctx.request_paint();
+                    }
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => ctx.set_active(false),
+            Event::KeyDown(key) if ctx.has_focus() => {
+                if HotKey::new(SysMods::Cmd, "a").matches(key) {
+                    if self.select_all() {
+                        ctx.request_paint();
+                    }
+                    ctx.set_handled();
+                } else if HotKey::new(SysMods::Cmd, "c").matches(key) {
+                    self.copy_selection();
+                    ctx.set_handled();
+                }
+            }
+            Event::Command(cmd) if ctx.is_focused() && cmd.is(commands::COPY) => {
+                self.copy_selection();
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if ctx.is_focused() && cmd.is(commands::SELECT_ALL) => {
+                if self.select_all() {
+                    ctx.request_paint();
+                }
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.set_source(data.as_ref());
+                self.measure(ctx.text(), env);
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(false) if self.anchor != self.active => {
+                self.anchor = self.active;
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) {
+            self.set_source(data.as_ref());
+            ctx.request_layout();
+        }
+        if ctx.env_key_changed(&theme::CODE_FONT) {
+            self.measure(ctx.text(), env);
+            ctx.request_layout();
+        }
+        let colors = [
+            theme::TEXT_COLOR,
+            theme::CODE_KEYWORD_COLOR,
+            theme::CODE_TYPE_COLOR,
+            theme::CODE_FUNCTION_COLOR,
+            theme::CODE_STRING_COLOR,
+            theme::CODE_NUMBER_COLOR,
+            theme::CODE_COMMENT_COLOR,
+        ];
+        if ctx.env_key_changed(&theme::CODE_FONT)
+            || colors.iter().any(|key| ctx.env_key_changed(key))
+        {
+            for line in &mut self.lines {
+                line.layout = None;
+            }
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &T, _: &Env) -> Size {
+        let width = self.columns as f64 * self.advance + 2.0 * TEXT_PADDING;
+        let height = self.lines.len() as f64 * self.line_height;
+        bc.constrain(Size::new(width, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _: &T, env: &Env) {
+        let region = ctx.region().bounding_box();
+        let (start, end) = self.selection();
+        let selection_color = env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR);
+        let width = ctx.size().width;
+        for line in visible_lines(region.y0, region.y1, self.line_height, self.lines.len()) {
+            let y = line as f64 * self.line_height;
+            if let Some(Some(key)) = self.backgrounds.get(line) {
+                let rect = Rect::new(0.0, y, width, y + self.line_height);
+                ctx.fill(rect, &env.get(key));
+            }
+            let len = self.lines[line].text.len();
+            let layout = self.line_layout(line, ctx.text(), env);
+
+            if start != end && (start.line..=end.line).contains(&line) {
+                let from = if line == start.line { start.offset } else { 0 };
+                let to = if line == end.line { end.offset } else { len };
+                let mut rects = layout.rects_for_range(from..to);
+                if line < end.line {
+                    // show that the line break is selected
+                    let x = layout.point_for_text_position(len).x;
+                    rects.push(Rect::new(x, 0.0, x + self.advance, self.line_height));
+                }
+                for rect in rects {
+                    let rect = rect.with_origin(rect.origin() + (TEXT_PADDING, y));
+                    ctx.fill(rect, &selection_color);
+                }
+            }
+            let layout = self.lines[line].layout.as_ref().unwrap();
+            layout.draw(ctx, (TEXT_PADDING, y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::tests::move_mouse;
+    use crate::{KeyEvent, Modifiers, MouseButton, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    /// Highlights `/* */` comments, and records the lines it highlights.
+    #[derive(Default)]
+    struct Comments {
+        highlighted: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Highlighter for Comments {
+        type State = bool;
+
+        fn highlight_line(
+            &self,
+            _: &str,
+            line: &str,
+            in_comment: &mut bool,
+        ) -> Vec<(Range<usize>, TokenKind)> {
+            self.highlighted.borrow_mut().push(line.to_string());
+            let mut tokens = Vec::new();
+            let mut start = 0;
+            while start < line.len() {
+                if *in_comment {
+                    let end = line[start..].find("*/").map(|i| start + i + 2);
+                    tokens.push((start..end.unwrap_or(line.len()), TokenKind::Comment));
+                    match end {
+                        Some(end) => {
+                            *in_comment = false;
+                            start = end;
+                        }
+                        None => break,
+                    }
+                } else {
+                    match line[start..].find("/*") {
+                        Some(i) => {
+                            *in_comment = true;
+                            start += i;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            tokens
+        }
+    }
+
+    #[test]
+    fn highlights_changed_lines() {
+        let highlighted = Rc::new(RefCell::new(Vec::new()));
+        let highlighter = Comments {
+            highlighted: highlighted.clone(),
+        };
+        let lines: Vec<String> = (0..100).map(|i| format!("line {}", i)).collect();
+        let code = lines.join("\n");
+        let widget = CodeView::with_highlighter("c", highlighter).with_line_numbers(true);
+
+        Harness::create_simple(code, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(highlighted.borrow().len(), 100);
+
+            // Editing a line only highlights that line.
+            highlighted.borrow_mut().clear();
+            harness.update_data(|code| *code = code.replace("line 50", "line fifty"));
+            assert_eq!(*highlighted.borrow(), vec!["line fifty"]);
+
+            // Opening a comment highlights the rest of the code.
+            highlighted.borrow_mut().clear();
+            harness.update_data(|code| *code = code.replace("line 90", "/* line 90"));
+            assert_eq!(highlighted.borrow().len(), 10);
+
+            // And closing it only the lines up to where the state is the same again.
+            highlighted.borrow_mut().clear();
+            harness.update_data(|code| *code = code.replace("line 95", "line 95 */"));
+            assert_eq!(
+                *highlighted.borrow(),
+                vec!["line 95 */", "line 96", "line 97", "line 98", "line 99"]
+            );
+            harness.paint();
+        });
+    }
+
+    #[test]
+    fn tokens() {
+        let highlighter = Comments::default();
+        let mut state = false;
+        assert_eq!(
+            highlighter.highlight_line("c", "a /* b */ c /* d", &mut state),
+            vec![(2..9, TokenKind::Comment), (12..16, TokenKind::Comment)]
+        );
+        assert!(state);
+        assert_eq!(
+            highlighter.highlight_line("c", "e */ f", &mut state),
+            vec![(0..4, TokenKind::Comment)]
+        );
+        assert!(!state);
+    }
+
+    #[test]
+    fn selecting_code() {
+        let [id] = widget_ids();
+        let code = String::from("fn main() {\n    run();\n}");
+        let widget = CodeView::new("rust").with_line_numbers(true).with_id(id);
+        let mouse = |pos: Point| MouseEvent {
+            button: MouseButton::Left,
+            count: 1,
+            ..move_mouse(pos)
+        };
+        let selection = |harness: &mut Harness<String>| {
+            let mut state = harness.get_debug_state(id).children.remove(0);
+            state.other_values.remove("selection")
+        };
+
+        Harness::create_simple(code.clone(), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+
+            // Drag from past the end of the code to its start, over the line numbers.
+            let origin = harness.get_state(id).window_origin();
+            let (start, end) = (origin + (390.0, 390.0), origin + (1.0, 1.0));
+            harness.event(Event::MouseDown(mouse(start)));
+            harness.event(Event::MouseMove(move_mouse(end)));
+            harness.event(Event::MouseUp(mouse(end)));
+            assert_eq!(selection(harness).as_deref(), Some(code.as_str()));
+            assert!(harness.window().focus.is_some());
+
+            // A click collapses the selection, and the keyboard selects all again.
+            harness.event(Event::MouseDown(mouse(start)));
+            harness.event(Event::MouseUp(mouse(start)));
+            assert_eq!(selection(harness), None);
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                if cfg!(target_os = "macos") {
+                    Modifiers::META
+                } else {
+                    Modifiers::CONTROL
+                },
+                "a",
+            )));
+            assert_eq!(selection(harness).as_deref(), Some(code.as_str()));
+
+            // Changing the code clears the selection.
+            harness.update_data(|code| code.push('\n'));
+            assert_eq!(selection(harness), None);
+        });
+    }
+}
@@ -0,0 +1,691 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget for picking a color.
+
+use std::iter;
+
+use tracing::{instrument, trace};
+
+use super::{TextBox, ValueTextBox};
+use crate::debug_state::DebugState;
+use crate::kurbo::{Circle, Shape};
+use crate::lens::Map;
+use crate::piet::ColorParseError;
+use crate::text::{Formatter, Selection, Validation, ValidationError};
+use crate::widget::prelude::*;
+use crate::{
+    theme, Color, Data, Lens, LinearGradient, Point, Rect, RenderContext, UnitPoint, WidgetExt,
+    WidgetPod,
+};
+
+const DEFAULT_WIDTH: f64 = 200.0;
+/// The height of the saturation/value area, relative to its width.
+const AREA_ASPECT_RATIO: f64 = 0.75;
+const STRIP_HEIGHT: f64 = 16.0;
+const SPACING: f64 = 8.0;
+const KNOB_RADIUS: f64 = 5.0;
+const CHECKER_SIZE: f64 = 4.0;
+
+/// A color in the HSV color space, with an alpha channel.
+///
+/// This is the state of a [`ColorPicker`]. Unlike a [`Color`], it keeps its
+/// hue when the saturation or value are zero, which is what lets the picker
+/// go from a color to grey and back without losing the hue.
+#[derive(Debug, Clone, Copy, PartialEq, Data, Lens)]
+pub struct HsvColor {
+    /// The hue, in degrees, in the range `0.0..=360.0`.
+    pub hue: f64,
+    /// The saturation, in the range `0.0..=1.0`.
+    pub saturation: f64,
+    /// The value, or brightness, in the range `0.0..=1.0`.
+    pub value: f64,
+    /// The opacity, in the range `0.0..=1.0`.
+    pub alpha: f64,
+}
+
+impl HsvColor {
+    /// Create a new `HsvColor`, clamping each component into its range.
+    pub fn new(hue: f64, saturation: f64, value: f64, alpha: f64) -> Self {
+        HsvColor {
+            hue: hue.clamp(0.0, 360.0),
+            saturation: saturation.clamp(0.0, 1.0),
+            value: value.clamp(0.0, 1.0),
+            alpha: alpha.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Convert a [`Color`].
+    ///
+    /// Greys have a hue of zero.
+    pub fn from_color(color: &Color) -> Self {
+        HsvColor::new(0.0, 0.0, 0.0, 1.0).with_color(color)
+    }
+
+    /// Returns `color` converted to HSV, keeping the hue of `self` if `color`
+    /// is a grey, and also its saturation if `color` is black.
+    pub fn with_color(self, color: &Color) -> Self {
+        let (r, g, b, alpha) = color.as_rgba();
+        let max = r.max(g).max(b);
+        let delta = max - r.min(g).min(b);
+        let hue = if delta <= 0.0 {
+            self.hue
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let saturation = if max <= 0.0 {
+            self.saturation
+        } else {
+            delta / max
+        };
+        HsvColor::new(hue, saturation, max, alpha)
+    }
+
+    /// Convert to a [`Color`].
+    pub fn to_color(&self) -> Color {
+        let hue = (self.hue / 60.0).rem_euclid(6.0);
+        let chroma = self.value * self.saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = self.value - chroma;
+        Color::rgba(r + m, g + m, b + m, self.alpha)
+    }
+}
+
+impl From<Color> for HsvColor {
+    fn from(color: Color) -> Self {
+        HsvColor::from_color(&color)
+    }
+}
+
+impl From<HsvColor> for Color {
+    fn from(color: HsvColor) -> Self {
+        color.to_color()
+    }
+}
+
+type Child = WidgetPod<HsvColor, Box<dyn Widget<HsvColor>>>;
+
+/// A widget for picking a [`Color`].
+///
+/// The picker has an area for choosing the saturation and value of the color,
+/// a strip below it for choosing the hue, and optionally a strip for choosing
+/// the opacity and a text box for entering the color as a hex code such as
+/// `#ff8800`. The text box accepts three, four, six or eight hex digits, with
+/// or without a leading `#`; the edit is committed when enter is pressed or the
+/// text box loses focus.
+///
+/// The picker keeps the color in HSV internally, see [`HsvColor`], so that
+/// the hue doesn't jump around while dragging through greys. When the data is
+/// changed from outside, all the parts of the picker are updated to match.
+///
+/// The picker is [`ColorPicker::DEFAULT_WIDTH`] wide unless its constraints require
+/// otherwise; use [`WidgetExt::expand_width`] to make it fill the available
+/// width.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::ColorPicker;
+/// use druid::{Color, Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Theme {
+///     accent: Color,
+/// }
+///
+/// fn accent_picker() -> impl Widget<Theme> {
+///     ColorPicker::new().with_alpha(true).lens(Theme::accent)
+/// }
+/// ```
+pub struct ColorPicker {
+    hsv: HsvColor,
+    area: Child,
+    hue: Child,
+    alpha: Option<Child>,
+    hex: Option<Child>,
+}
+
+impl ColorPicker {
+    /// The width of the picker, if its constraints allow it.
+    pub const DEFAULT_WIDTH: f64 = DEFAULT_WIDTH;
+
+    /// Create a new `ColorPicker`, with a hex text box but without an alpha
+    /// strip.
+    pub fn new() -> Self {
+        ColorPicker {
+            hsv: HsvColor::new(0.0, 0.0, 0.0, 1.0),
+            area: WidgetPod::new(SaturationValueArea).boxed(),
+            hue: WidgetPod::new(Strip::Hue).boxed(),
+            alpha: None,
+            hex: Some(Self::hex_box(false)),
+        }
+    }
+
+    /// Builder-style method for showing or hiding the strip for choosing the
+    /// opacity.
+    ///
+    /// While the strip is shown, the hex text box also shows the alpha
+    /// channel.
+    pub fn with_alpha(mut self, alpha: bool) -> Self {
+        self.alpha = if alpha {
+            Some(WidgetPod::new(Strip::Alpha).boxed())
+        } else {
+            None
+        };
+        if self.hex.is_some() {
+            self.hex = Some(Self::hex_box(alpha));
+        }
+        self
+    }
+
+    /// Builder-style method for showing or hiding the hex text box.
+    pub fn with_hex_input(mut self, hex_input: bool) -> Self {
+        self.hex = if hex_input {
+            Some(Self::hex_box(self.alpha.is_some()))
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Returns the color being edited, in HSV.
+    pub fn hsv(&self) -> HsvColor {
+        self.hsv
+    }
+
+    fn hex_box(alpha: bool) -> Child {
+        let text_box = ValueTextBox::new(TextBox::new(), HexFormatter { alpha }).lens(Map::new(
+            |hsv: &HsvColor| hsv.to_color(),
+            |hsv: &mut HsvColor, color: Color| {
+                if !color.same(&hsv.to_color()) {
+                    *hsv = hsv.with_color(&color);
+                }
+            },
+        ));
+        WidgetPod::new(text_box).boxed()
+    }
+
+    fn children(&mut self) -> impl Iterator<Item = &mut Child> {
+        iter::once(&mut self.area)
+            .chain(iter::once(&mut self.hue))
+            .chain(self.alpha.as_mut())
+            .chain(self.hex.as_mut())
+    }
+}
+
+impl Default for ColorPicker {
+    fn default() -> Self {
+        ColorPicker::new()
+    }
+}
+
+impl Widget<Color> for ColorPicker {
+    #[instrument(
+        name = "ColorPicker",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Color, env: &Env) {
+        let mut hsv = self.hsv;
+        for child in self.children() {
+            child.event(ctx, event, &mut hsv, env);
+        }
+        if !hsv.same(&self.hsv) {
+            self.hsv = hsv;
+            let color = hsv.to_color();
+            if !color.same(data) {
+                *data = color;
+            }
+            // The children need to be updated even if the color didn't change,
+            // for instance when changing the hue of a grey.
+            ctx.request_update();
+        }
+    }
+
+    #[instrument(
+        name = "ColorPicker",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Color, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.hsv = HsvColor::from_color(data);
+        }
+        let hsv = self.hsv;
+        for child in self.children() {
+            child.lifecycle(ctx, event, &hsv, env);
+        }
+    }
+
+    #[instrument(
+        name = "ColorPicker",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &Color, data: &Color, env: &Env) {
+        if !data.same(&self.hsv.to_color()) {
+            trace!(
+                "ColorPicker {:?}: color changed externally",
+                ctx.widget_id()
+            );
+            self.hsv = self.hsv.with_color(data);
+        }
+        let hsv = self.hsv;
+        for child in self.children() {
+            child.update(ctx, &hsv, env);
+        }
+    }
+
+    #[instrument(name = "ColorPicker", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &Color,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("ColorPicker");
+        let hsv = self.hsv;
+        let width = DEFAULT_WIDTH.clamp(bc.min().width, bc.max().width);
+
+        // The strips and the text box get their size first, and the area gets
+        // what is left.
+        let strip_bc = BoxConstraints::tight(Size::new(width, STRIP_HEIGHT));
+        let mut below = Vec::new();
+        for child in iter::once(&mut self.hue).chain(self.alpha.as_mut()) {
+            let size = child.layout(ctx, &strip_bc, &hsv, env);
+            below.push((child, size.height));
+        }
+        if let Some(hex) = &mut self.hex {
+            let hex_bc =
+                BoxConstraints::new(Size::new(width, 0.0), Size::new(width, f64::INFINITY));
+            let size = hex.layout(ctx, &hex_bc, &hsv, env);
+            below.push((hex, size.height));
+        }
+        let below_height: f64 = below.iter().map(|(_, height)| height + SPACING).sum();
+
+        let area_height = if bc.is_height_bounded() {
+            (width * AREA_ASPECT_RATIO).min(bc.max().height - below_height)
+        } else {
+            width * AREA_ASPECT_RATIO
+        }
+        .max(bc.min().height - below_height)
+        .max(0.0);
+
+        let mut y = area_height + SPACING;
+        for (child, height) in below {
+            child.set_origin(ctx, &hsv, env, Point::new(0.0, y));
+            y += height + SPACING;
+        }
+        let area_bc = BoxConstraints::tight(Size::new(width, area_height));
+        self.area.layout(ctx, &area_bc, &hsv, env);
+        self.area.set_origin(ctx, &hsv, env, Point::ORIGIN);
+
+        let size = bc.constrain(Size::new(width, y - SPACING));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "ColorPicker", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &Color, env: &Env) {
+        let hsv = self.hsv;
+        for child in self.children() {
+            child.paint(ctx, &hsv, env);
+        }
+    }
+
+    fn debug_state(&self, data: &Color) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: HexFormatter { alpha: true }.format(data),
+            ..Default::default()
+        }
+    }
+}
+
+/// The area for choosing the saturation, from left to right, and the value,
+/// from bottom to top.
+struct SaturationValueArea;
+
+impl SaturationValueArea {
+    fn set_from_mouse(ctx: &mut EventCtx, pos: Point, data: &mut HsvColor) {
+        let size = ctx.size();
+        data.saturation = fraction(pos.x, size.width);
+        data.value = 1.0 - fraction(pos.y, size.height);
+        ctx.request_paint();
+    }
+}
+
+impl Widget<HsvColor> for SaturationValueArea {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut HsvColor, _env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                ctx.set_active(true);
+                Self::set_from_mouse(ctx, mouse.pos, data);
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                Self::set_from_mouse(ctx, mouse.pos, data);
+            }
+            Event::MouseUp(mouse) if ctx.is_active() && mouse.button.is_left() => {
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &HsvColor, _: &Env) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &HsvColor, data: &HsvColor, _: &Env) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &HsvColor, _: &Env) -> Size {
+        bc.debug_check("SaturationValueArea");
+        bc.max()
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &HsvColor, _env: &Env) {
+        let rect = ctx.size().to_rect();
+        let pure = HsvColor::new(data.hue, 1.0, 1.0, 1.0).to_color();
+        ctx.fill(rect, &pure);
+        let white = LinearGradient::new(
+            UnitPoint::LEFT,
+            UnitPoint::RIGHT,
+            (Color::WHITE, Color::WHITE.with_alpha(0.0)),
+        );
+        ctx.fill(rect, &white);
+        let black = LinearGradient::new(
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+            (Color::BLACK.with_alpha(0.0), Color::BLACK),
+        );
+        ctx.fill(rect, &black);
+
+        let center = Point::new(
+            data.saturation * rect.width(),
+            (1.0 - data.value) * rect.height(),
+        );
+        ctx.with_save(|ctx| {
+            ctx.clip(rect);
+            paint_knob(ctx, Circle::new(center, KNOB_RADIUS));
+        });
+    }
+}
+
+/// A horizontal strip for choosing one channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Strip {
+    Hue,
+    Alpha,
+}
+
+impl Strip {
+    fn get(self, data: &HsvColor) -> f64 {
+        match self {
+            Strip::Hue => data.hue / 360.0,
+            Strip::Alpha => data.alpha,
+        }
+    }
+
+    fn set_from_mouse(self, ctx: &mut EventCtx, pos: Point, data: &mut HsvColor) {
+        let fraction = fraction(pos.x, ctx.size().width);
+        match self {
+            Strip::Hue => data.hue = fraction * 360.0,
+            Strip::Alpha => data.alpha = fraction,
+        }
+        ctx.request_paint();
+    }
+}
+
+impl Widget<HsvColor> for Strip {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut HsvColor, _env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                ctx.set_active(true);
+                self.set_from_mouse(ctx, mouse.pos, data);
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                self.set_from_mouse(ctx, mouse.pos, data);
+            }
+            Event::MouseUp(mouse) if ctx.is_active() && mouse.button.is_left() => {
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &HsvColor, _: &Env) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &HsvColor, data: &HsvColor, _: &Env) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &HsvColor, _: &Env) -> Size {
+        bc.debug_check("Strip");
+        bc.max()
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &HsvColor, env: &Env) {
+        let rect = ctx.size().to_rect();
+        match self {
+            Strip::Hue => {
+                let stops: Vec<Color> = (0..=6)
+                    .map(|i| HsvColor::new(i as f64 * 60.0, 1.0, 1.0, 1.0).to_color())
+                    .collect();
+                let gradient = LinearGradient::new(UnitPoint::LEFT, UnitPoint::RIGHT, &*stops);
+                ctx.fill(rect, &gradient);
+            }
+            Strip::Alpha => {
+                paint_checkerboard(ctx, rect, env);
+                let opaque = HsvColor {
+                    alpha: 1.0,
+                    ..*data
+                }
+                .to_color();
+                let gradient = LinearGradient::new(
+                    UnitPoint::LEFT,
+                    UnitPoint::RIGHT,
+                    (opaque.clone().with_alpha(0.0), opaque),
+                );
+                ctx.fill(rect, &gradient);
+            }
+        }
+        let x = self.get(data) * rect.width();
+        let knob = Rect::new(x - 2.0, 0.0, x + 2.0, rect.height()).to_rounded_rect(1.0);
+        ctx.with_save(|ctx| {
+            ctx.clip(rect);
+            paint_knob(ctx, knob);
+        });
+    }
+}
+
+/// The position of `pos` along `length`, between zero and one.
+fn fraction(pos: f64, length: f64) -> f64 {
+    if length > 0.0 {
+        (pos / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Paint a knob that is visible on any color.
+fn paint_knob(ctx: &mut PaintCtx, shape: impl Shape + Copy) {
+    ctx.stroke(shape, &Color::BLACK, 3.0);
+    ctx.stroke(shape, &Color::WHITE, 1.5);
+}
+
+/// Paint the checkerboard behind translucent colors.
+fn paint_checkerboard(ctx: &mut PaintCtx, rect: Rect, env: &Env) {
+    ctx.fill(rect, &Color::WHITE);
+    let dark = env.get(theme::DISABLED_FOREGROUND_LIGHT);
+    let columns = (rect.width() / CHECKER_SIZE).ceil() as usize;
+    let rows = (rect.height() / CHECKER_SIZE).ceil() as usize;
+    ctx.with_save(|ctx| {
+        ctx.clip(rect);
+        for row in 0..rows {
+            for column in (row % 2..columns).step_by(2) {
+                let origin = Point::new(column as f64 * CHECKER_SIZE, row as f64 * CHECKER_SIZE);
+                let square = Rect::from_origin_size(origin, (CHECKER_SIZE, CHECKER_SIZE));
+                ctx.fill(square, &dark);
+            }
+        }
+    });
+}
+
+/// A [`Formatter`] for colors as hex codes.
+///
+/// Partial input longer than eight digits or with characters other than hex
+/// digits is rejected.
+struct HexFormatter {
+    /// Whether to always include the alpha channel. It is included anyway if
+    /// the color is translucent.
+    alpha: bool,
+}
+
+impl Formatter<Color> for HexFormatter {
+    fn format(&self, value: &Color) -> String {
+        let (r, g, b, a) = value.as_rgba8();
+        if self.alpha || a != 0xff {
+            format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+        } else {
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        }
+    }
+
+    fn validate_partial_input(&self, input: &str, _sel: &Selection) -> Validation {
+        let digits = input.strip_prefix('#').unwrap_or(input);
+        if digits.len() > 8 {
+            Validation::failure(ColorParseError::WrongSize(digits.len()))
+        } else if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            Validation::failure(ColorParseError::NotHex {
+                idx: input.find(c).unwrap_or_default(),
+                byte: c as u32 as u8,
+            })
+        } else {
+            Validation::success()
+        }
+    }
+
+    fn value(&self, input: &str) -> Result<Color, ValidationError> {
+        Color::from_hex_str(input.trim()).map_err(ValidationError::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{click, widget_ids};
+    use crate::tests::move_mouse;
+    use crate::{MouseButton, MouseEvent};
+    use test_log::test;
+
+    #[test]
+    fn greys_keep_the_hue() {
+        let orange = HsvColor::from_color(&Color::rgb8(0xff, 0x80, 0x00));
+        assert!((orange.hue - 30.1).abs() < 0.1);
+        assert_eq!((orange.saturation, orange.value), (1.0, 1.0));
+        assert!(orange.to_color().same(&Color::rgb8(0xff, 0x80, 0x00)));
+
+        let grey = orange.with_color(&Color::grey8(0x80));
+        assert_eq!(grey.hue, orange.hue);
+        assert_eq!(grey.saturation, 0.0);
+        let black = HsvColor::new(200.0, 0.5, 1.0, 1.0).with_color(&Color::BLACK);
+        assert_eq!(
+            (black.hue, black.saturation, black.value),
+            (200.0, 0.5, 0.0)
+        );
+
+        assert_eq!(HsvColor::new(400.0, 2.0, -1.0, 0.5).hue, 360.0);
+        assert!(HsvColor::new(360.0, 1.0, 1.0, 1.0)
+            .to_color()
+            .same(&Color::rgb8(0xff, 0, 0)));
+    }
+
+    #[test]
+    fn hex_input_is_validated() {
+        let formatter = HexFormatter { alpha: false };
+        let sel = Selection::caret(0);
+        assert_eq!(formatter.format(&Color::rgb8(0xff, 0x88, 0)), "#ff8800");
+        assert_eq!(
+            formatter.format(&Color::rgba8(0x11, 0x22, 0x33, 0x44)),
+            "#11223344"
+        );
+        assert!(!formatter.validate_partial_input("#ff8", &sel).is_err());
+        assert!(formatter.validate_partial_input("#ff8g", &sel).is_err());
+        assert!(formatter
+            .validate_partial_input("#112233445", &sel)
+            .is_err());
+
+        assert!(formatter
+            .value("f80")
+            .unwrap()
+            .same(&Color::rgb8(0xff, 0x88, 0)));
+        assert!(formatter.value("#ff88").is_ok());
+        assert!(formatter.value("#ff88001").is_err());
+    }
+
+    #[test]
+    fn dragging_and_external_changes() {
+        let [id] = widget_ids();
+        let picker = ColorPicker::new().with_alpha(true).with_id(id).center();
+        let mouse = |pos: Point| MouseEvent {
+            button: MouseButton::Left,
+            count: 1,
+            ..move_mouse(pos)
+        };
+
+        Harness::create_simple(Color::rgb8(0xff, 0, 0), picker, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(id).layout_rect().width(),
+                ColorPicker::DEFAULT_WIDTH
+            );
+            let origin = harness.get_state(id).window_origin();
+            let top_right = origin + (199.9, 0.0);
+
+            // Drag to the left edge of the area, which is white.
+            harness.event(Event::MouseDown(mouse(origin + (100.0, 0.0))));
+            harness.event(Event::MouseMove(move_mouse(origin - (10.0, 0.0))));
+            harness.event(Event::MouseUp(mouse(origin - (10.0, 0.0))));
+            assert!(harness.data().same(&Color::WHITE));
+
+            // Back to the right; the hue is still red.
+            click(harness, top_right);
+            assert!(harness.data().same(&Color::rgb8(0xff, 0, 0)));
+
+            // An external change updates the hue as well.
+            harness.update_data(|color| *color = Color::rgb8(0x40, 0x80, 0x40));
+            click(harness, top_right);
+            assert!(harness.data().same(&Color::rgb8(0, 0xff, 0)));
+        });
+    }
+}
@@ -0,0 +1,1285 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Drop-downs for picking from a list of choices.
+
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::commands::{CLOSE_POPUP, DISMISS_POPUP, POPUP_DISMISSED};
+use crate::debug_state::DebugState;
+use crate::kurbo::BezPath;
+use crate::text::{Attribute, FontWeight, RichText};
+use crate::widget::prelude::*;
+use crate::widget::{LazyList, ListIter, Popup, PopupId, Scroll, TextBox};
+use crate::{
+    theme, ArcStr, Data, InternalLifeCycle, KbKey, Lens, LensExt, Point, Rect, Selector,
+    TextLayout, TimerToken, WidgetExt, WidgetPod,
+};
+
+/// Sent by the drop-down to its owner when a choice is picked.
+const SELECT_CHOICE: Selector<usize> = Selector::new("druid-builtin.combo-box-select-choice");
+/// Sent by the drop-down to its owner when the highlighted choice changes.
+const HIGHLIGHT_CHANGED: Selector<usize> =
+    Selector::new("druid-builtin.combo-box-highlight-changed");
+/// Sent to the drop-down by an [`AutoComplete`], to move the highlight with a
+/// key that the text box got.
+const MOVE_HIGHLIGHT: Selector<KbKey> = Selector::new("druid-builtin.combo-box-move-highlight");
+/// Sent to the drop-down by an [`AutoComplete`], to show new suggestions.
+const SET_CHOICES: Selector<ChoiceList> = Selector::new("druid-builtin.combo-box-set-choices");
+/// Sent by a row of the drop-down when the pointer moves over it.
+const HIGHLIGHT_ROW: Selector<usize> = Selector::new("druid-builtin.combo-box-highlight-row");
+/// Sent by a row of the drop-down when it is clicked.
+const CHOOSE_ROW: Selector<usize> = Selector::new("druid-builtin.combo-box-choose-row");
+
+const ROW_HEIGHT: f64 = 24.0;
+const MAX_POPUP_HEIGHT: f64 = 300.0;
+const ARROW_SIZE: f64 = 8.0;
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+const DEFAULT_SUGGESTION_DELAY: Duration = Duration::from_millis(150);
+
+type ChoicesFn<T, V> = dyn Fn(&T, &Env) -> Vec<(ArcStr, V)>;
+type SuggestionsFn = dyn Fn(&str, &Env) -> Vec<String>;
+
+/// Where a [`ComboBox`] gets its choices from.
+trait Choices<T> {
+    /// The labels of the choices.
+    fn labels(&self, data: &T, env: &Env) -> Arc<Vec<ArcStr>>;
+    /// The index of the current choice, if it is one of the choices.
+    fn selected(&self, data: &T, env: &Env) -> Option<usize>;
+    /// Make the choice at `index` the current one.
+    fn select(&self, data: &mut T, env: &Env, index: usize);
+
+    fn selected_label(&self, data: &T, env: &Env) -> Option<ArcStr> {
+        let index = self.selected(data, env)?;
+        self.labels(data, env).get(index).cloned()
+    }
+}
+
+struct StaticChoices<T> {
+    labels: Arc<Vec<ArcStr>>,
+    values: Vec<T>,
+}
+
+struct DynamicChoices<T, V, L> {
+    choices: Box<ChoicesFn<T, V>>,
+    selection: L,
+    phantom: PhantomData<V>,
+}
+
+impl<T: Data + PartialEq> Choices<T> for StaticChoices<T> {
+    fn labels(&self, _data: &T, _env: &Env) -> Arc<Vec<ArcStr>> {
+        self.labels.clone()
+    }
+
+    fn selected(&self, data: &T, _env: &Env) -> Option<usize> {
+        self.values.iter().position(|value| value == data)
+    }
+
+    fn select(&self, data: &mut T, _env: &Env, index: usize) {
+        if let Some(value) = self.values.get(index) {
+            *data = value.clone();
+        }
+    }
+}
+
+impl<T, V: Data + PartialEq, L: Lens<T, V>> Choices<T> for DynamicChoices<T, V, L> {
+    fn labels(&self, data: &T, env: &Env) -> Arc<Vec<ArcStr>> {
+        let choices = (self.choices)(data, env);
+        Arc::new(choices.into_iter().map(|(label, _)| label).collect())
+    }
+
+    fn selected(&self, data: &T, env: &Env) -> Option<usize> {
+        let current = self.selection.get(data);
+        (self.choices)(data, env)
+            .iter()
+            .position(|(_, value)| *value == current)
+    }
+
+    fn select(&self, data: &mut T, env: &Env, index: usize) {
+        if let Some((_, value)) = (self.choices)(data, env).into_iter().nth(index) {
+            self.selection.put(data, value);
+        }
+    }
+}
+
+/// A drop-down for picking one of a list of choices.
+///
+/// The combo box looks like a [`TextBox`] showing the label of the current
+/// choice. Clicking it, or pressing enter, space or the down arrow while it
+/// is focused, opens a list of all the choices below it. In the list, the
+/// arrow keys, home, end, page up and page down move the highlight, typing
+/// jumps to the next choice starting with the typed text, and enter or a
+/// click picks the highlighted choice. The list closes when a choice is
+/// picked, or on escape, a click outside of it, or when it loses focus.
+///
+/// The list is shown in a [`Popup`], so the combo box must be inside an
+/// [`OverlayLayer`]. It only creates widgets for the visible choices, so it
+/// is fine to have thousands of them. The choices are read when the list
+/// opens; it isn't updated if they change while it is open.
+///
+/// The combo box is as wide as the label of the current choice, and the list
+/// is as wide as the combo box; use [`WidgetExt::fix_width`] to keep it from
+/// changing size with the choice.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{ComboBox, Flex};
+/// use druid::{ArcStr, Data, Lens, Widget, WidgetExt};
+/// use std::sync::Arc;
+///
+/// #[derive(Clone, Copy, PartialEq, Data)]
+/// enum Size {
+///     Small,
+///     Large,
+/// }
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Order {
+///     size: Size,
+///     drinks: Arc<Vec<String>>,
+///     drink: String,
+/// }
+///
+/// fn make_form() -> impl Widget<Order> {
+///     let size = ComboBox::new(vec![("Small", Size::Small), ("Large", Size::Large)])
+///         .lens(Order::size);
+///     // The choices can also come from the data.
+///     let drink = ComboBox::dynamic(
+///         |order: &Order, _| {
+///             let drinks = order.drinks.iter();
+///             drinks.map(|drink| (ArcStr::from(drink.as_str()), drink.clone())).collect()
+///         },
+///         Order::drink,
+///     )
+///     .with_placeholder("Pick a drink")
+///     .fix_width(150.0);
+///     Flex::column().with_child(size).with_child(drink)
+/// }
+/// ```
+///
+/// [`TextBox`]: super::TextBox
+/// [`OverlayLayer`]: super::OverlayLayer
+/// [`WidgetExt::fix_width`]: super::WidgetExt::fix_width
+pub struct ComboBox<T> {
+    choices: Box<dyn Choices<T>>,
+    placeholder: ArcStr,
+    layout: TextLayout<ArcStr>,
+    /// Whether the current choice is one of the choices, as of the last layout.
+    has_choice: bool,
+    popup: Option<PopupId>,
+}
+
+impl<T: Data + PartialEq> ComboBox<T> {
+    /// Create a combo box with a fixed list of labels and their values.
+    ///
+    /// The current choice is the one whose value is equal to the data.
+    pub fn new(choices: impl IntoIterator<Item = (impl Into<ArcStr>, T)>) -> Self {
+        let (labels, values): (Vec<ArcStr>, Vec<T>) = choices
+            .into_iter()
+            .map(|(label, value)| (label.into(), value))
+            .unzip();
+        Self::with_choices(StaticChoices {
+            labels: Arc::new(labels),
+            values,
+        })
+    }
+}
+
+impl<T: Data> ComboBox<T> {
+    /// Create a combo box whose labels and values are computed from the data,
+    /// and that shows and changes the value selected by `selection`.
+    pub fn dynamic<V: Data + PartialEq>(
+        choices: impl Fn(&T, &Env) -> Vec<(ArcStr, V)> + 'static,
+        selection: impl Lens<T, V> + 'static,
+    ) -> Self {
+        Self::with_choices(DynamicChoices {
+            choices: Box::new(choices),
+            selection,
+            phantom: PhantomData,
+        })
+    }
+
+    fn with_choices(choices: impl Choices<T> + 'static) -> Self {
+        ComboBox {
+            choices: Box::new(choices),
+            placeholder: "".into(),
+            layout: TextLayout::new(),
+            has_choice: false,
+            popup: None,
+        }
+    }
+
+    /// Builder-style method to set the text shown when the data is none of
+    /// the choices.
+    pub fn with_placeholder(mut self, placeholder: impl Into<ArcStr>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    fn open_popup(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        let list = ChoiceList {
+            labels: self.choices.labels(data, env),
+            selected: self.choices.selected(data, env),
+            highlighted: None,
+            query: "".into(),
+        };
+        let content = ChoicePopup::new(ctx.widget_id(), list, ctx.size().width);
+        let anchor = Rect::from_origin_size(ctx.window_origin(), ctx.size());
+        let popup = Popup::<()>::new(anchor, content).dismiss_on_focus_loss(true);
+        let id = ctx.show_popup(popup);
+        trace!("ComboBox {:?} opened popup {:?}", ctx.widget_id(), id);
+        self.popup = Some(id);
+    }
+}
+
+impl<T: Data> Widget<T> for ComboBox<T> {
+    #[instrument(name = "ComboBox", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(_) if !ctx.is_disabled() => {
+                ctx.set_active(true);
+                ctx.request_focus();
+                ctx.request_paint();
+            }
+            Event::MouseUp(_) => {
+                if ctx.is_active() && !ctx.is_disabled() && ctx.is_hot() {
+                    if let Some(id) = self.popup.take() {
+                        ctx.submit_command(CLOSE_POPUP.with(id));
+                    } else {
+                        self.open_popup(ctx, data, env);
+                    }
+                }
+                ctx.set_active(false);
+                ctx.request_paint();
+            }
+            Event::KeyDown(key) if !ctx.is_disabled() && self.popup.is_none() => match &key.key {
+                KbKey::Enter | KbKey::ArrowDown => {
+                    self.open_popup(ctx, data, env);
+                    ctx.set_handled();
+                }
+                KbKey::Character(c) if c == " " => {
+                    self.open_popup(ctx, data, env);
+                    ctx.set_handled();
+                }
+                _ => (),
+            },
+            Event::Command(cmd) if cmd.is(SELECT_CHOICE) => {
+                let index = *cmd.get_unchecked(SELECT_CHOICE);
+                trace!("ComboBox {:?} picked choice {}", ctx.widget_id(), index);
+                self.choices.select(data, env, index);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(POPUP_DISMISSED) => {
+                if self.popup == Some(*cmd.get_unchecked(POPUP_DISMISSED)) {
+                    self.popup = None;
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "ComboBox",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::DisabledChanged(true) => {
+                if let Some(id) = self.popup.take() {
+                    ctx.submit_command(CLOSE_POPUP.with(id));
+                }
+                ctx.request_paint();
+            }
+            LifeCycle::FocusChanged(_)
+            | LifeCycle::DisabledChanged(false)
+            | LifeCycle::HotChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "ComboBox",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) || ctx.env_changed() {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "ComboBox", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("ComboBox");
+
+        let label = self.choices.selected_label(data, env);
+        self.has_choice = label.is_some();
+        let (text, color) = match label {
+            Some(label) => (label, theme::TEXT_COLOR),
+            None => (self.placeholder.clone(), theme::PLACEHOLDER_COLOR),
+        };
+        self.layout.set_text(text);
+        self.layout.set_text_color(color);
+        self.layout.rebuild_if_needed(ctx.text(), env);
+
+        let insets = env.get(theme::TEXTBOX_INSETS);
+        let text_size = self.layout.size();
+        let height =
+            (text_size.height + insets.y_value()).max(env.get(theme::BORDERED_WIDGET_HEIGHT));
+        let width = text_size.width + insets.x_value() + ARROW_SIZE + insets.x1;
+        let size = bc.constrain(Size::new(width, height));
+
+        let text_metrics = self.layout.layout_metrics();
+        let text_offset = (size.height - text_size.height) / 2.0;
+        ctx.set_baseline_offset(size.height - text_offset - text_metrics.first_baseline);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "ComboBox", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let size = ctx.size();
+        let border_width = env.get(theme::TEXTBOX_BORDER_WIDTH);
+        let insets = env.get(theme::TEXTBOX_INSETS);
+
+        let border_color = if ctx.is_focused() && !ctx.is_disabled() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        let arrow_color = if ctx.is_disabled() {
+            env.get(theme::DISABLED_TEXT_COLOR)
+        } else {
+            env.get(theme::TEXT_COLOR)
+        };
+
+        let rect = size
+            .to_rect()
+            .inset(-border_width / 2.0)
+            .to_rounded_rect(env.get(theme::TEXTBOX_BORDER_RADIUS));
+        ctx.fill(rect, &env.get(theme::BACKGROUND_LIGHT));
+        ctx.stroke(rect, &border_color, border_width);
+
+        let text_y = (size.height - self.layout.size().height) / 2.0;
+        ctx.with_save(|ctx| {
+            ctx.clip(rect);
+            self.layout.draw(ctx, (insets.x0, text_y));
+        });
+
+        let arrow_x = size.width - insets.x1 - ARROW_SIZE;
+        let arrow_y = (size.height - ARROW_SIZE / 2.0) / 2.0;
+        let mut arrow = BezPath::new();
+        arrow.move_to((arrow_x, arrow_y));
+        arrow.line_to((arrow_x + ARROW_SIZE, arrow_y));
+        arrow.line_to((arrow_x + ARROW_SIZE / 2.0, arrow_y + ARROW_SIZE / 2.0));
+        arrow.close_path();
+        ctx.fill(arrow, &arrow_color);
+    }
+
+    fn debug_state(&self, _data: &T) -> DebugState {
+        let text = self.layout.text().map(|text| text.to_string());
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: text.filter(|_| self.has_choice).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A [`TextBox`] that suggests completions for its text.
+///
+/// A short while after the text is edited, the suggestions for it are
+/// computed with a closure and shown in a list below the text box, with
+/// the part of each suggestion that matches the text in bold. The arrow
+/// keys, page up and page down move the highlight in the list, and enter
+/// or a click replaces the text with the highlighted suggestion. Any other
+/// keys go to the text box, so the text isn't limited to the suggestions.
+///
+/// The list closes when a suggestion is picked, when there are no
+/// suggestions, or on escape, a click outside of it, or when the text box
+/// loses focus. Like the list of a [`ComboBox`], it is shown in a [`Popup`],
+/// so the widget must be inside an [`OverlayLayer`], and it only creates
+/// widgets for the visible suggestions.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::AutoComplete;
+/// use druid::Widget;
+///
+/// const FRUITS: [&str; 4] = ["Apple", "Apricot", "Banana", "Cherry"];
+///
+/// fn fruit_input() -> impl Widget<String> {
+///     AutoComplete::new(|text, _env| {
+///         let text = text.to_lowercase();
+///         FRUITS
+///             .iter()
+///             .filter(|fruit| !text.is_empty() && fruit.to_lowercase().contains(&text))
+///             .map(|fruit| fruit.to_string())
+///             .collect()
+///     })
+///     .with_placeholder("Fruit")
+/// }
+/// ```
+///
+/// [`OverlayLayer`]: super::OverlayLayer
+pub struct AutoComplete {
+    textbox: WidgetPod<String, TextBox<String>>,
+    suggest: Box<SuggestionsFn>,
+    delay: Duration,
+    timer: TimerToken,
+    /// The suggestions in the list, if it is open.
+    suggestions: Arc<Vec<ArcStr>>,
+    highlighted: Option<usize>,
+    /// The popup, and the id of its content.
+    popup: Option<(PopupId, WidgetId)>,
+    /// Whether the text was just replaced by a suggestion, which shouldn't
+    /// bring up the suggestions again.
+    accepted: bool,
+}
+
+impl AutoComplete {
+    /// Create a text box with the suggestions computed by `suggest` from the
+    /// text, in the order they are shown.
+    pub fn new(suggest: impl Fn(&str, &Env) -> Vec<String> + 'static) -> Self {
+        AutoComplete {
+            textbox: WidgetPod::new(TextBox::new()),
+            suggest: Box::new(suggest),
+            delay: DEFAULT_SUGGESTION_DELAY,
+            timer: TimerToken::INVALID,
+            suggestions: Arc::new(Vec::new()),
+            highlighted: None,
+            popup: None,
+            accepted: false,
+        }
+    }
+
+    /// Builder-style method to set how long the text has to stay the same
+    /// before the suggestions are computed. The default is 150 milliseconds.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Builder-style method to set the placeholder text of the text box.
+    pub fn with_placeholder(mut self, placeholder: impl Into<ArcStr>) -> Self {
+        self.textbox
+            .widget_mut()
+            .set_placeholder(placeholder.into());
+        self
+    }
+
+    fn show_suggestions(&mut self, ctx: &mut EventCtx, data: &str, env: &Env) {
+        let suggestions: Vec<ArcStr> = (self.suggest)(data, env)
+            .into_iter()
+            .map(ArcStr::from)
+            .collect();
+        trace!("{} suggestions for {:?}", suggestions.len(), data);
+        if suggestions.is_empty() {
+            self.close_popup(ctx);
+            return;
+        }
+        self.suggestions = Arc::new(suggestions);
+        self.highlighted = None;
+        let list = ChoiceList {
+            labels: self.suggestions.clone(),
+            selected: None,
+            highlighted: None,
+            query: data.into(),
+        };
+        match self.popup {
+            Some((_, content)) => ctx.submit_command(SET_CHOICES.with(list).to(content)),
+            None => {
+                let content = ChoicePopup::new(ctx.widget_id(), list, ctx.size().width);
+                let content_id = WidgetId::next();
+                let anchor = Rect::from_origin_size(ctx.window_origin(), ctx.size());
+                let popup = Popup::<()>::new(anchor, content.with_id(content_id)).take_focus(false);
+                self.popup = Some((ctx.show_popup(popup), content_id));
+            }
+        }
+    }
+
+    fn close_popup(&mut self, ctx: &mut EventCtx) {
+        if let Some((id, _)) = self.popup.take() {
+            ctx.submit_command(CLOSE_POPUP.with(id));
+        }
+        self.highlighted = None;
+    }
+
+    /// Replace the text with the suggestion at `index`.
+    fn accept(&mut self, data: &mut String, index: usize) {
+        if let Some(suggestion) = self.suggestions.get(index) {
+            if data.as_str() != &**suggestion {
+                *data = suggestion.to_string();
+                self.accepted = true;
+            }
+        }
+    }
+}
+
+impl Widget<String> for AutoComplete {
+    #[instrument(
+        name = "AutoComplete",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut String, env: &Env) {
+        match event {
+            Event::Timer(token) if *token == self.timer => {
+                self.timer = TimerToken::INVALID;
+                if self.textbox.has_focus() {
+                    self.show_suggestions(ctx, data, env);
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(key) if self.textbox.has_focus() => {
+                if let Some((_, content)) = self.popup {
+                    match &key.key {
+                        KbKey::ArrowDown | KbKey::ArrowUp | KbKey::PageDown | KbKey::PageUp => {
+                            ctx.submit_command(MOVE_HIGHLIGHT.with(key.key.clone()).to(content));
+                            ctx.set_handled();
+                            return;
+                        }
+                        KbKey::Enter => {
+                            if let Some(index) = self.highlighted {
+                                self.accept(data, index);
+                                self.close_popup(ctx);
+                                ctx.set_handled();
+                                return;
+                            }
+                            self.close_popup(ctx);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            Event::Command(cmd) if cmd.is(HIGHLIGHT_CHANGED) => {
+                self.highlighted = Some(*cmd.get_unchecked(HIGHLIGHT_CHANGED));
+                ctx.set_handled();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(SELECT_CHOICE) => {
+                self.accept(data, *cmd.get_unchecked(SELECT_CHOICE));
+                ctx.set_handled();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(POPUP_DISMISSED) => {
+                if matches!(self.popup, Some((id, _)) if id == *cmd.get_unchecked(POPUP_DISMISSED))
+                {
+                    self.popup = None;
+                    self.highlighted = None;
+                }
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+        self.textbox.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "AutoComplete",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &String, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.timer = TimerToken::INVALID;
+        }
+        self.textbox.lifecycle(ctx, event, data, env);
+        if let LifeCycle::Internal(InternalLifeCycle::RouteFocusChanged { .. }) = event {
+            if !self.textbox.has_focus() {
+                if let Some((id, _)) = self.popup.take() {
+                    ctx.submit_command(CLOSE_POPUP.with(id));
+                }
+            }
+        }
+    }
+
+    #[instrument(
+        name = "AutoComplete",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &String, data: &String, env: &Env) {
+        if !old_data.same(data) {
+            if self.accepted {
+                self.accepted = false;
+            } else if self.textbox.has_focus() {
+                // Any previously requested timer is now stale and will be ignored.
+                self.timer = ctx.request_timer(self.delay);
+            }
+        }
+        self.textbox.update(ctx, data, env);
+    }
+
+    #[instrument(name = "AutoComplete", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &String,
+        env: &Env,
+    ) -> Size {
+        let size = self.textbox.layout(ctx, bc, data, env);
+        self.textbox.set_origin(ctx, data, env, Point::ORIGIN);
+        ctx.set_baseline_offset(self.textbox.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "AutoComplete", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &String, env: &Env) {
+        self.textbox.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &String) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.clone(),
+            children: vec![self.textbox.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+/// The choices shown in the drop-down of a [`ComboBox`] or an [`AutoComplete`].
+#[derive(Clone, Data)]
+struct ChoiceList {
+    labels: Arc<Vec<ArcStr>>,
+    selected: Option<usize>,
+    highlighted: Option<usize>,
+    /// The text that is shown in bold in the labels.
+    query: ArcStr,
+}
+
+/// A row of the drop-down.
+#[derive(Clone, Data)]
+struct ChoiceRow {
+    index: usize,
+    label: ArcStr,
+    selected: bool,
+    highlighted: bool,
+    query: ArcStr,
+}
+
+impl ChoiceList {
+    fn row(&self, index: usize) -> ChoiceRow {
+        ChoiceRow {
+            index,
+            label: self.labels[index].clone(),
+            selected: self.selected == Some(index),
+            highlighted: self.highlighted == Some(index),
+            query: self.query.clone(),
+        }
+    }
+}
+
+impl ListIter<ChoiceRow> for ChoiceList {
+    fn for_each(&self, cb: impl FnMut(&ChoiceRow, usize)) {
+        self.for_each_in_range(0..self.labels.len(), cb);
+    }
+
+    fn for_each_mut(&mut self, cb: impl FnMut(&mut ChoiceRow, usize)) {
+        self.for_each_mut_in_range(0..self.labels.len(), cb);
+    }
+
+    fn data_len(&self) -> usize {
+        self.labels.len()
+    }
+
+    fn for_each_in_range(&self, range: Range<usize>, mut cb: impl FnMut(&ChoiceRow, usize)) {
+        for index in range.start..range.end.min(self.labels.len()) {
+            cb(&self.row(index), index);
+        }
+    }
+
+    // The rows can't change the choices; they send notifications instead.
+    fn for_each_mut_in_range(
+        &mut self,
+        range: Range<usize>,
+        mut cb: impl FnMut(&mut ChoiceRow, usize),
+    ) {
+        for index in range.start..range.end.min(self.labels.len()) {
+            cb(&mut self.row(index), index);
+        }
+    }
+}
+
+/// The drop-down of a [`ComboBox`] or an [`AutoComplete`].
+///
+/// This keeps its state to itself, so that it can be shown in an
+/// [`OverlayLayer`] without knowing the type of the layer's data.
+///
+/// [`OverlayLayer`]: super::OverlayLayer
+struct ChoicePopup {
+    owner: WidgetId,
+    list: ChoiceList,
+    rows: WidgetPod<ChoiceList, Scroll<ChoiceList, LazyList<ChoiceRow>>>,
+    width: f64,
+    /// Whether the highlighted row has to be scrolled into view at the next
+    /// layout.
+    scroll_to_highlight: bool,
+    /// The characters typed so far, in lowercase.
+    type_ahead: String,
+    /// The timer that clears `type_ahead`.
+    type_ahead_timer: TimerToken,
+}
+
+impl ChoicePopup {
+    fn new(owner: WidgetId, mut list: ChoiceList, width: f64) -> Self {
+        list.highlighted = list.selected;
+        let rows = LazyList::new(ChoiceItem::new).with_row_height(ROW_HEIGHT);
+        ChoicePopup {
+            owner,
+            list,
+            rows: WidgetPod::new(Scroll::new(rows).vertical()),
+            width,
+            scroll_to_highlight: true,
+            type_ahead: String::new(),
+            type_ahead_timer: TimerToken::INVALID,
+        }
+    }
+
+    fn highlight(&mut self, ctx: &mut EventCtx, index: usize) {
+        if self.list.highlighted != Some(index) {
+            self.list.highlighted = Some(index);
+            ctx.submit_command(HIGHLIGHT_CHANGED.with(index).to(self.owner));
+            ctx.request_update();
+        }
+    }
+
+    /// Move the highlight with the keyboard, and keep it in view.
+    fn move_highlight(&mut self, ctx: &mut EventCtx, index: usize) {
+        self.highlight(ctx, index);
+        self.scroll_to_highlight = true;
+        ctx.request_layout();
+    }
+
+    fn choose(&mut self, ctx: &mut EventCtx, index: usize) {
+        ctx.submit_command(SELECT_CHOICE.with(index).to(self.owner));
+        ctx.submit_notification(DISMISS_POPUP);
+    }
+
+    /// Handle a key press in the list. Returns `true` if the key was used.
+    fn key_down(&mut self, ctx: &mut EventCtx, key: &KbKey) -> bool {
+        let len = self.list.labels.len();
+        if len == 0 {
+            return false;
+        }
+        let page = ((MAX_POPUP_HEIGHT / ROW_HEIGHT) as usize).max(1);
+        let current = self.list.highlighted;
+        let target = match key {
+            KbKey::ArrowDown => current.map_or(0, |i| (i + 1).min(len - 1)),
+            KbKey::ArrowUp => current.map_or(0, |i| i.saturating_sub(1)),
+            KbKey::PageDown => current.map_or(0, |i| (i + page).min(len - 1)),
+            KbKey::PageUp => current.map_or(0, |i| i.saturating_sub(page)),
+            KbKey::Home => 0,
+            KbKey::End => len - 1,
+            KbKey::Enter => {
+                if let Some(index) = current {
+                    self.choose(ctx, index);
+                }
+                return true;
+            }
+            KbKey::Character(c) if c == " " && self.type_ahead.is_empty() => {
+                if let Some(index) = current {
+                    self.choose(ctx, index);
+                }
+                return true;
+            }
+            KbKey::Character(c) if !c.chars().any(char::is_control) => {
+                match self.type_ahead_match(ctx, c) {
+                    Some(index) => index,
+                    None => return true,
+                }
+            }
+            _ => return false,
+        };
+        self.move_highlight(ctx, target);
+        true
+    }
+
+    /// Add `typed` to the type-ahead search, and find the choice it matches.
+    fn type_ahead_match(&mut self, ctx: &mut EventCtx, typed: &str) -> Option<usize> {
+        self.type_ahead.push_str(&typed.to_lowercase());
+        self.type_ahead_timer = ctx.request_timer(TYPE_AHEAD_TIMEOUT);
+        // Continuing a search may keep the current choice; a new search starts after it.
+        let start = match self.list.highlighted {
+            Some(index) if self.type_ahead.chars().count() > 1 => index,
+            Some(index) => index + 1,
+            None => 0,
+        };
+        let labels = &self.list.labels;
+        let found = (0..labels.len())
+            .map(|offset| (start + offset) % labels.len())
+            .find(|&index| labels[index].to_lowercase().starts_with(&self.type_ahead));
+        trace!("type-ahead {:?} matched {:?}", self.type_ahead, found);
+        found
+    }
+}
+
+impl Widget<()> for ChoicePopup {
+    #[instrument(
+        name = "ChoicePopup",
+        level = "trace",
+        skip(self, ctx, event, _data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut (), env: &Env) {
+        match event {
+            Event::Notification(note) => {
+                if let Some(&index) = note.get(HIGHLIGHT_ROW) {
+                    self.highlight(ctx, index);
+                    ctx.set_handled();
+                    return;
+                }
+                if let Some(&index) = note.get(CHOOSE_ROW) {
+                    self.choose(ctx, index);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::Command(cmd) if cmd.is(MOVE_HIGHLIGHT) => {
+                self.key_down(ctx, cmd.get_unchecked(MOVE_HIGHLIGHT));
+                ctx.set_handled();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(SET_CHOICES) => {
+                self.list = cmd.get_unchecked(SET_CHOICES).clone();
+                self.scroll_to_highlight = true;
+                ctx.request_update();
+                ctx.request_layout();
+                ctx.set_handled();
+                return;
+            }
+            Event::Timer(token) if *token == self.type_ahead_timer => {
+                self.type_ahead.clear();
+                self.type_ahead_timer = TimerToken::INVALID;
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(key)
+                if ctx.is_focused()
+                    && !(key.mods.ctrl() || key.mods.alt() || key.mods.meta())
+                    && self.key_down(ctx, &key.key) =>
+            {
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+        self.rows.event(ctx, event, &mut self.list, env);
+    }
+
+    #[instrument(
+        name = "ChoicePopup",
+        level = "trace",
+        skip(self, ctx, event, _data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &(), env: &Env) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+        self.rows.lifecycle(ctx, event, &self.list, env);
+    }
+
+    #[instrument(
+        name = "ChoicePopup",
+        level = "trace",
+        skip(self, ctx, _old, _data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &(), _data: &(), env: &Env) {
+        self.rows.update(ctx, &self.list, env);
+    }
+
+    #[instrument(name = "ChoicePopup", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &(), env: &Env) -> Size {
+        bc.debug_check("ChoicePopup");
+        let rows_height = (self.list.labels.len() as f64 * ROW_HEIGHT).min(MAX_POPUP_HEIGHT);
+        let size = bc.constrain(Size::new(self.width, rows_height + 2.0));
+        let rows_bc = BoxConstraints::tight(Size::new(
+            (size.width - 2.0).max(0.0),
+            (size.height - 2.0).max(0.0),
+        ));
+        self.rows.layout(ctx, &rows_bc, &self.list, env);
+        if self.scroll_to_highlight {
+            self.scroll_to_highlight = false;
+            if let Some(index) = self.list.highlighted {
+                let row = Rect::new(
+                    0.0,
+                    index as f64 * ROW_HEIGHT,
+                    rows_bc.max().width,
+                    (index + 1) as f64 * ROW_HEIGHT,
+                );
+                // Lay out again, for the list to learn about the new viewport.
+                if self.rows.widget_mut().scroll_to(row) {
+                    self.rows.layout(ctx, &rows_bc, &self.list, env);
+                }
+            }
+        }
+        self.rows
+            .set_origin(ctx, &self.list, env, Point::new(1.0, 1.0));
+        size
+    }
+
+    #[instrument(name = "ChoicePopup", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &(), env: &Env) {
+        let size = ctx.size();
+        ctx.fill(size.to_rect(), &env.get(theme::BACKGROUND_LIGHT));
+        self.rows.paint(ctx, &self.list, env);
+        ctx.stroke(
+            size.to_rect().inset(-0.5),
+            &env.get(theme::BORDER_DARK),
+            1.0,
+        );
+    }
+
+    fn debug_state(&self, _data: &()) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!("{:?}", self.list.highlighted),
+            children: vec![self.rows.widget().debug_state(&self.list)],
+            ..Default::default()
+        }
+    }
+}
+
+/// The widget for a row of the drop-down.
+struct ChoiceItem {
+    layout: TextLayout<RichText>,
+}
+
+impl ChoiceItem {
+    fn new() -> Self {
+        ChoiceItem {
+            layout: TextLayout::new(),
+        }
+    }
+}
+
+impl Widget<ChoiceRow> for ChoiceItem {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut ChoiceRow, _env: &Env) {
+        match event {
+            Event::MouseMove(_) if ctx.is_hot() && !data.highlighted => {
+                ctx.submit_notification(HIGHLIGHT_ROW.with(data.index));
+            }
+            Event::MouseDown(_) => ctx.set_active(true),
+            Event::MouseUp(_) => {
+                if ctx.is_active() && ctx.is_hot() {
+                    ctx.submit_notification(CHOOSE_ROW.with(data.index));
+                }
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        _ctx: &mut LifeCycleCtx,
+        _event: &LifeCycle,
+        _data: &ChoiceRow,
+        _env: &Env,
+    ) {
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &ChoiceRow, data: &ChoiceRow, _env: &Env) {
+        if !old_data.label.same(&data.label)
+            || !old_data.query.same(&data.query)
+            || old_data.selected != data.selected
+        {
+            ctx.request_layout();
+        } else if old_data.highlighted != data.highlighted {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &ChoiceRow,
+        env: &Env,
+    ) -> Size {
+        let mut text = RichText::new(data.label.clone());
+        if let Some(range) = find_ignoring_case(&data.label, &data.query) {
+            text.add_attribute(range, Attribute::weight(FontWeight::BOLD));
+        }
+        self.layout.set_text(text);
+        self.layout.set_font(if data.selected {
+            theme::UI_FONT_BOLD
+        } else {
+            theme::UI_FONT
+        });
+        self.layout.rebuild_if_needed(ctx.text(), env);
+        bc.constrain(Size::new(bc.max().width, ROW_HEIGHT))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &ChoiceRow, env: &Env) {
+        let size = ctx.size();
+        if data.highlighted {
+            ctx.fill(
+                size.to_rect(),
+                &env.get(theme::SELECTED_ITEM_BACKGROUND_COLOR),
+            );
+        }
+        let insets = env.get(theme::TEXTBOX_INSETS);
+        let text_y = (size.height - self.layout.size().height) / 2.0;
+        self.layout.draw(ctx, (insets.x0, text_y));
+    }
+
+    fn debug_state(&self, data: &ChoiceRow) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.label.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Returns the range of the first occurrence of `query` in `text`, ignoring
+/// case.
+fn find_ignoring_case(text: &str, query: &str) -> Option<Range<usize>> {
+    if query.is_empty() {
+        return None;
+    }
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    text.char_indices().find_map(|(start, _)| {
+        let mut rest = &query[..];
+        for (offset, c) in text[start..].char_indices() {
+            if rest.is_empty() {
+                return Some(start..start + offset);
+            }
+            let lower: Vec<char> = c.to_lowercase().collect();
+            if !rest.starts_with(&lower) {
+                return None;
+            }
+            rest = &rest[lower.len()..];
+        }
+        if rest.is_empty() {
+            Some(start..text.len())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::press_key;
+    use crate::tests::move_mouse;
+    use crate::widget::{Align, OverlayLayer};
+    use crate::{KeyEvent, Modifiers, MouseButton, MouseEvent, UnitPoint, WidgetExt};
+    use test_log::test;
+
+    fn in_corner(combo_box: ComboBox<u32>) -> impl Widget<u32> {
+        OverlayLayer::new(Align::new(UnitPoint::TOP_LEFT, combo_box.fix_width(100.0)))
+    }
+
+    fn sizes() -> ComboBox<u32> {
+        ComboBox::new(vec![("One", 1), ("Two", 2), ("Three", 3)])
+    }
+
+    fn click(harness: &mut Harness<u32>, pos: (f64, f64)) {
+        let event = MouseEvent {
+            button: MouseButton::Left,
+            count: 1,
+            ..move_mouse(pos)
+        };
+        harness.event(Event::MouseDown(event.clone()));
+        harness.event(Event::MouseUp(event));
+        harness.just_layout();
+    }
+
+    fn popup_count(harness: &Harness<u32>) -> usize {
+        harness.get_root_debug_state().children.len() - 1
+    }
+
+    fn row_count(state: &DebugState) -> usize {
+        let rows = state.children.iter().map(row_count).sum::<usize>();
+        rows + (state.display_name == "ChoiceItem") as usize
+    }
+
+    #[test]
+    fn shows_current_choice() {
+        let combo_box = sizes().with_placeholder("None");
+        Harness::create_simple(2, combo_box, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(harness.get_root_debug_state().main_value, "Two");
+
+            harness.update_data(|data| *data = 7);
+            harness.just_layout();
+            assert_eq!(harness.get_root_debug_state().main_value, "");
+        });
+    }
+
+    #[test]
+    fn keyboard_selection() {
+        let root = in_corner(sizes());
+        Harness::create_simple(1, root, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            click(harness, (10.0, 10.0));
+            assert_eq!(popup_count(harness), 1);
+
+            // The highlight starts at the current choice.
+            press_key(harness, KbKey::ArrowDown);
+            press_key(harness, KbKey::Enter);
+            harness.just_layout();
+            assert_eq!(*harness.data(), 2);
+            assert_eq!(popup_count(harness), 0);
+
+            // Type-ahead, after giving the focus back to the combo box.
+            press_key(harness, KbKey::Enter);
+            harness.just_layout();
+            assert_eq!(popup_count(harness), 1);
+            press_key(harness, KbKey::Character("t".into()));
+            press_key(harness, KbKey::Character("h".into()));
+            press_key(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), 3);
+
+            // Escape closes the list without changing the choice.
+            press_key(harness, KbKey::ArrowDown);
+            press_key(harness, KbKey::Home);
+            press_key(harness, KbKey::Escape);
+            harness.just_layout();
+            assert_eq!(popup_count(harness), 0);
+            assert_eq!(*harness.data(), 3);
+        });
+    }
+
+    #[test]
+    fn many_choices() {
+        let choices = (0..10_000).map(|i| (format!("Item {}", i), i));
+        let root = in_corner(ComboBox::new(choices));
+        Harness::create_simple(5000, root, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            click(harness, (10.0, 10.0));
+            // Only the visible rows, and a few more, have widgets.
+            let rows = row_count(&harness.get_root_debug_state());
+            assert!(rows > 0 && rows < 50, "{} rows", rows);
+            press_key(harness, KbKey::End);
+            harness.just_layout();
+            press_key(harness, KbKey::Enter);
+            assert_eq!(*harness.data(), 9999);
+        });
+    }
+
+    fn fruits() -> impl Widget<String> {
+        const FRUITS: [&str; 4] = ["Apple", "Apricot", "Banana", "Cherry"];
+        let input = AutoComplete::new(|text, _| {
+            let text = text.to_lowercase();
+            FRUITS
+                .iter()
+                .filter(|fruit| !text.is_empty() && fruit.to_lowercase().contains(&text))
+                .map(|fruit| fruit.to_string())
+                .collect()
+        });
+        OverlayLayer::new(Align::new(UnitPoint::TOP_LEFT, input.fix_width(200.0)))
+    }
+
+    /// Edit the text, and wait for the suggestions.
+    fn edit(harness: &mut Harness<String>, text: &str) {
+        harness.update_data(|data| *data = text.to_string());
+        fire_timers(harness);
+    }
+
+    fn fire_timers(harness: &mut Harness<String>) {
+        let timers: Vec<TimerToken> = harness.window().timers.keys().copied().collect();
+        for token in timers {
+            harness.event(Event::Timer(token));
+        }
+        harness.just_layout();
+    }
+
+    fn suggestion_count(harness: &Harness<String>) -> usize {
+        let state = harness.get_root_debug_state();
+        state.children.iter().skip(1).map(row_count).sum()
+    }
+
+    #[test]
+    fn suggestions() {
+        Harness::create_simple(String::new(), fruits(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let mouse = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse((10.0, 10.0))
+            };
+            harness.event(Event::MouseDown(mouse.clone()));
+            harness.event(Event::MouseUp(mouse));
+
+            edit(harness, "ap");
+            assert_eq!(suggestion_count(harness), 2);
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::default(),
+                KbKey::ArrowDown,
+            )));
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::default(),
+                KbKey::ArrowDown,
+            )));
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::default(),
+                KbKey::Enter,
+            )));
+            harness.just_layout();
+            assert_eq!(harness.data(), "Apricot");
+            // Picking a suggestion doesn't bring the list back.
+            fire_timers(harness);
+            assert_eq!(suggestion_count(harness), 0);
+
+            // Enter keeps free-form text, and closes the list.
+            edit(harness, "an");
+            assert_eq!(suggestion_count(harness), 1);
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::default(),
+                KbKey::Enter,
+            )));
+            harness.just_layout();
+            assert_eq!(harness.data(), "an");
+            assert_eq!(suggestion_count(harness), 0);
+
+            // The list is updated while typing, and closes without suggestions.
+            edit(harness, "a");
+            assert_eq!(suggestion_count(harness), 3);
+            edit(harness, "ch");
+            assert_eq!(suggestion_count(harness), 1);
+            edit(harness, "chx");
+            assert_eq!(suggestion_count(harness), 0);
+        });
+    }
+
+    #[test]
+    fn matched_text() {
+        assert_eq!(find_ignoring_case("Apricot", "RIC"), Some(2..5));
+        assert_eq!(find_ignoring_case("\u{c9}cole", "\u{e9}c"), Some(0..3));
+        assert_eq!(find_ignoring_case("Apricot", "cots"), None);
+        assert_eq!(find_ignoring_case("Apricot", ""), None);
+    }
+}
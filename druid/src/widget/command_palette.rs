@@ -0,0 +1,663 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A searchable list of actions, like the command palette of an editor.
+
+use std::ops::Range;
+use std::rc::Rc;
+
+use tracing::{instrument, trace};
+
+use crate::commands::{DISMISS_POPUP, POPUP_DISMISSED};
+use crate::debug_state::DebugState;
+use crate::text::fuzzy::{fuzzy_match, match_ranges};
+use crate::text::{Attribute, FontWeight, RichText};
+use crate::widget::prelude::*;
+use crate::widget::{Controller, LazyList, ListIter, Popup, PopupId, Scroll, TextBox};
+use crate::{theme, ArcStr, Command, Data, KbKey, Point, Rect, Selector, TextLayout, WidgetPod};
+
+/// Sent by a row of the palette when the pointer moves over it.
+const HIGHLIGHT_ROW: Selector<usize> = Selector::new("druid-builtin.command-palette-highlight-row");
+/// Sent by a row of the palette when it is clicked.
+const CHOOSE_ROW: Selector<usize> = Selector::new("druid-builtin.command-palette-choose-row");
+
+const PALETTE_WIDTH: f64 = 400.0;
+const TOP_OFFSET: f64 = 8.0;
+const PADDING: f64 = 6.0;
+const ROW_HEIGHT: f64 = 24.0;
+const MAX_RESULTS_HEIGHT: f64 = 300.0;
+
+type ActionsFn<T> = dyn Fn(&T, &Env) -> Vec<PaletteAction>;
+
+/// An action that can be picked in a [`CommandPalette`].
+#[derive(Debug, Clone, Data)]
+pub struct PaletteAction {
+    /// An identifier for the action, that stays the same when its label is
+    /// translated.
+    pub id: ArcStr,
+    /// The text that is shown, and searched.
+    pub label: ArcStr,
+    /// A description of the keyboard shortcut of the action, such as
+    /// `"Ctrl+S"`, shown next to the label.
+    pub shortcut: Option<ArcStr>,
+    /// The command that is submitted when the action is picked.
+    ///
+    /// If it has no explicit [`Target`], it is sent to the window.
+    ///
+    /// [`Target`]: crate::Target
+    #[data(ignore)]
+    pub command: Command,
+}
+
+/// A [`Controller`] that shows a searchable list of actions on top of the
+/// window, like the command palette of an editor.
+///
+/// The palette opens when the controlled widget, or any widget inside of it,
+/// gets the [`CommandPalette::SHOW`] command; this is usually bound to a
+/// shortcut with a [`KeyMap`], or sent by a menu item. It has a text field
+/// at the top, and lists the actions whose label matches the typed text
+/// below it, best matches first, with the matched characters in bold.
+///
+/// The labels are matched with [`fuzzy_match`]: the typed characters have to
+/// appear in the label in the same order, but not next to each other, and
+/// case is ignored. Actions that match equally well keep their order.
+///
+/// The arrow keys, page up and page down move the highlight, and enter or a
+/// click picks the highlighted action, which submits its command and closes
+/// the palette. It also closes on escape, a click outside of it, or when it
+/// loses focus.
+///
+/// The actions are computed from the data by a closure, when the palette
+/// opens. The palette is shown in a [`Popup`], so the widget must be inside
+/// an [`OverlayLayer`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{CommandPalette, Label, OverlayLayer, PaletteAction};
+/// use druid::{commands, KeyMap, SysMods, Widget, WidgetExt};
+///
+/// fn root() -> impl Widget<u32> {
+///     let palette = CommandPalette::new(|_: &u32, _| {
+///         vec![
+///             PaletteAction::new("new-file", "New file", commands::NEW_FILE)
+///                 .with_shortcut("Ctrl+N"),
+///             PaletteAction::new("quit", "Quit", commands::QUIT_APP),
+///         ]
+///     });
+///     OverlayLayer::new(Label::new("Press Ctrl+Shift+P").controller(palette))
+/// }
+///
+/// // given to `WindowDesc::keymap`
+/// let keymap = KeyMap::new().with_binding(SysMods::CmdShift, "P", CommandPalette::SHOW);
+/// ```
+///
+/// [`KeyMap`]: crate::KeyMap
+/// [`OverlayLayer`]: super::OverlayLayer
+/// [`fuzzy_match`]: crate::text::fuzzy::fuzzy_match
+pub struct CommandPalette<T> {
+    actions: Box<ActionsFn<T>>,
+    placeholder: ArcStr,
+    popup: Option<PopupId>,
+}
+
+impl PaletteAction {
+    /// Create an action that submits `command`.
+    pub fn new(
+        id: impl Into<ArcStr>,
+        label: impl Into<ArcStr>,
+        command: impl Into<Command>,
+    ) -> Self {
+        PaletteAction {
+            id: id.into(),
+            label: label.into(),
+            shortcut: None,
+            command: command.into(),
+        }
+    }
+
+    /// Builder-style method to set the description of the keyboard shortcut.
+    pub fn with_shortcut(mut self, shortcut: impl Into<ArcStr>) -> Self {
+        self.shortcut = Some(shortcut.into());
+        self
+    }
+}
+
+impl CommandPalette<()> {
+    /// A command that opens the [`CommandPalette`] that gets it.
+    pub const SHOW: Selector = Selector::new("druid-builtin.command-palette-show");
+}
+
+impl<T> CommandPalette<T> {
+    /// Create a command palette whose actions are computed from the data by
+    /// `actions`, in the order in which they are shown.
+    pub fn new(actions: impl Fn(&T, &Env) -> Vec<PaletteAction> + 'static) -> Self {
+        CommandPalette {
+            actions: Box::new(actions),
+            placeholder: "".into(),
+            popup: None,
+        }
+    }
+
+    /// Builder-style method to set the placeholder text of the text field.
+    pub fn with_placeholder(mut self, placeholder: impl Into<ArcStr>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    fn open(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        let actions = Rc::new((self.actions)(data, env));
+        let width = PALETTE_WIDTH.min(ctx.size().width);
+        let content = PalettePopup::new(actions, self.placeholder.clone(), width);
+        let origin = ctx.window_origin();
+        let anchor = Point::new(
+            origin.x + (ctx.size().width - width) / 2.0,
+            origin.y + TOP_OFFSET,
+        );
+        let popup = Popup::<()>::new(anchor, content).dismiss_on_focus_loss(true);
+        let id = ctx.show_popup(popup);
+        trace!("CommandPalette {:?} opened popup {:?}", ctx.widget_id(), id);
+        self.popup = Some(id);
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for CommandPalette<T> {
+    #[instrument(
+        name = "CommandPalette",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(CommandPalette::SHOW) => {
+                if self.popup.is_none() {
+                    self.open(ctx, data, env);
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(POPUP_DISMISSED) => {
+                if self.popup == Some(*cmd.get_unchecked(POPUP_DISMISSED)) {
+                    self.popup = None;
+                    ctx.set_handled();
+                } else {
+                    child.event(ctx, event, data, env);
+                }
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+/// The actions that match the query, best first.
+#[derive(Clone, Data)]
+struct ResultList {
+    rows: Rc<Vec<ResultRow>>,
+    highlighted: Option<usize>,
+}
+
+/// A row of the palette.
+#[derive(Clone, Data)]
+struct ResultRow {
+    index: usize,
+    action: PaletteAction,
+    /// The ranges of the label that matched the query.
+    matched: Rc<Vec<Range<usize>>>,
+    highlighted: bool,
+}
+
+impl ResultList {
+    fn row(&self, index: usize) -> ResultRow {
+        ResultRow {
+            highlighted: self.highlighted == Some(index),
+            ..self.rows[index].clone()
+        }
+    }
+}
+
+impl ListIter<ResultRow> for ResultList {
+    fn for_each(&self, cb: impl FnMut(&ResultRow, usize)) {
+        self.for_each_in_range(0..self.rows.len(), cb);
+    }
+
+    fn for_each_mut(&mut self, cb: impl FnMut(&mut ResultRow, usize)) {
+        self.for_each_mut_in_range(0..self.rows.len(), cb);
+    }
+
+    fn data_len(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn for_each_in_range(&self, range: Range<usize>, mut cb: impl FnMut(&ResultRow, usize)) {
+        for index in range.start..range.end.min(self.rows.len()) {
+            cb(&self.row(index), index);
+        }
+    }
+
+    // The rows can't change the actions; they send notifications instead.
+    fn for_each_mut_in_range(
+        &mut self,
+        range: Range<usize>,
+        mut cb: impl FnMut(&mut ResultRow, usize),
+    ) {
+        for index in range.start..range.end.min(self.rows.len()) {
+            cb(&mut self.row(index), index);
+        }
+    }
+}
+
+/// The content of the popup of a [`CommandPalette`].
+///
+/// Like the drop-down of a [`ComboBox`], this keeps its state to itself.
+///
+/// [`ComboBox`]: super::ComboBox
+struct PalettePopup {
+    actions: Rc<Vec<PaletteAction>>,
+    query: String,
+    textbox: WidgetPod<String, TextBox<String>>,
+    results: ResultList,
+    rows: WidgetPod<ResultList, Scroll<ResultList, LazyList<ResultRow>>>,
+    width: f64,
+    /// Whether the highlighted row has to be scrolled into view at the next
+    /// layout.
+    scroll_to_highlight: bool,
+}
+
+impl PalettePopup {
+    fn new(actions: Rc<Vec<PaletteAction>>, placeholder: ArcStr, width: f64) -> Self {
+        let rows = LazyList::new(ActionItem::new).with_row_height(ROW_HEIGHT);
+        let mut popup = PalettePopup {
+            actions,
+            query: String::new(),
+            textbox: WidgetPod::new(TextBox::new().with_placeholder(placeholder)),
+            results: ResultList {
+                rows: Rc::new(Vec::new()),
+                highlighted: None,
+            },
+            rows: WidgetPod::new(Scroll::new(rows).vertical()),
+            width,
+            scroll_to_highlight: false,
+        };
+        popup.filter();
+        popup
+    }
+
+    /// Find and rank the actions that match the query, and highlight the best.
+    fn filter(&mut self) {
+        let mut rows: Vec<(i64, ResultRow)> = self
+            .actions
+            .iter()
+            .filter_map(|action| {
+                let (score, offsets) = fuzzy_match(&self.query, &action.label)?;
+                let row = ResultRow {
+                    index: 0,
+                    action: action.clone(),
+                    matched: Rc::new(match_ranges(&action.label, &offsets)),
+                    highlighted: false,
+                };
+                Some((score, row))
+            })
+            .collect();
+        // a stable sort, so that equal matches keep their order
+        rows.sort_by(|(a, _), (b, _)| b.cmp(a));
+        let rows: Vec<ResultRow> = rows
+            .into_iter()
+            .enumerate()
+            .map(|(index, (_, row))| ResultRow { index, ..row })
+            .collect();
+        trace!("{} actions match {:?}", rows.len(), self.query);
+        self.results = ResultList {
+            highlighted: rows.first().map(|_| 0),
+            rows: Rc::new(rows),
+        };
+        self.scroll_to_highlight = true;
+    }
+
+    /// Move the highlight with the keyboard, and keep it in view.
+    fn move_highlight(&mut self, ctx: &mut EventCtx, index: usize) {
+        self.results.highlighted = Some(index);
+        self.scroll_to_highlight = true;
+        ctx.request_update();
+        ctx.request_layout();
+    }
+
+    fn choose(&mut self, ctx: &mut EventCtx, index: usize) {
+        if let Some(row) = self.results.rows.get(index) {
+            trace!("Picked action {:?}", row.action.id);
+            ctx.submit_command(row.action.command.clone());
+            ctx.submit_notification(DISMISS_POPUP);
+        }
+    }
+
+    /// Handle a key press in the text field. Returns `true` if the key was used.
+    fn key_down(&mut self, ctx: &mut EventCtx, key: &KbKey) -> bool {
+        let len = self.results.rows.len();
+        let page = ((MAX_RESULTS_HEIGHT / ROW_HEIGHT) as usize).max(1);
+        let current = self.results.highlighted;
+        let target = match key {
+            KbKey::Enter => {
+                if let Some(index) = current {
+                    self.choose(ctx, index);
+                }
+                return true;
+            }
+            _ if len == 0 => return false,
+            KbKey::ArrowDown => current.map_or(0, |i| (i + 1).min(len - 1)),
+            KbKey::ArrowUp => current.map_or(0, |i| i.saturating_sub(1)),
+            KbKey::PageDown => current.map_or(0, |i| (i + page).min(len - 1)),
+            KbKey::PageUp => current.map_or(0, |i| i.saturating_sub(page)),
+            _ => return false,
+        };
+        self.move_highlight(ctx, target);
+        true
+    }
+
+    fn results_height(&self) -> f64 {
+        (self.results.rows.len() as f64 * ROW_HEIGHT).min(MAX_RESULTS_HEIGHT)
+    }
+}
+
+impl Widget<()> for PalettePopup {
+    #[instrument(
+        name = "PalettePopup",
+        level = "trace",
+        skip(self, ctx, event, _data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut (), env: &Env) {
+        match event {
+            Event::Notification(note) => {
+                if let Some(&index) = note.get(HIGHLIGHT_ROW) {
+                    if self.results.highlighted != Some(index) {
+                        self.results.highlighted = Some(index);
+                        ctx.request_update();
+                    }
+                    ctx.set_handled();
+                    return;
+                }
+                if let Some(&index) = note.get(CHOOSE_ROW) {
+                    self.choose(ctx, index);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::KeyDown(key)
+                if self.textbox.has_focus()
+                    && !(key.mods.ctrl() || key.mods.alt() || key.mods.meta())
+                    && self.key_down(ctx, &key.key) =>
+            {
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+        let old_query = self.query.clone();
+        self.textbox.event(ctx, event, &mut self.query, env);
+        if self.query != old_query {
+            self.filter();
+            ctx.request_update();
+            ctx.request_layout();
+        }
+        self.rows.event(ctx, event, &mut self.results, env);
+    }
+
+    #[instrument(
+        name = "PalettePopup",
+        level = "trace",
+        skip(self, ctx, event, _data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &(), env: &Env) {
+        self.textbox.lifecycle(ctx, event, &self.query, env);
+        self.rows.lifecycle(ctx, event, &self.results, env);
+    }
+
+    #[instrument(
+        name = "PalettePopup",
+        level = "trace",
+        skip(self, ctx, _old, _data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &(), _data: &(), env: &Env) {
+        self.textbox.update(ctx, &self.query, env);
+        self.rows.update(ctx, &self.results, env);
+    }
+
+    #[instrument(
+        name = "PalettePopup",
+        level = "trace",
+        skip(self, ctx, bc, _data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &(), env: &Env) -> Size {
+        bc.debug_check("PalettePopup");
+        let inner_width = (self.width - 2.0 * PADDING).max(0.0);
+        let textbox_bc = BoxConstraints::new(
+            Size::new(inner_width, 0.0),
+            Size::new(inner_width, f64::INFINITY),
+        );
+        let textbox_size = self.textbox.layout(ctx, &textbox_bc, &self.query, env);
+        self.textbox
+            .set_origin(ctx, &self.query, env, Point::new(PADDING, PADDING));
+
+        let rows_top = PADDING + textbox_size.height + PADDING;
+        let height = rows_top + self.results_height() + 1.0;
+        let size = bc.constrain(Size::new(self.width, height));
+        let rows_bc = BoxConstraints::tight(Size::new(
+            (size.width - 2.0).max(0.0),
+            (size.height - rows_top - 1.0).max(0.0),
+        ));
+        self.rows.layout(ctx, &rows_bc, &self.results, env);
+        if self.scroll_to_highlight {
+            self.scroll_to_highlight = false;
+            if let Some(index) = self.results.highlighted {
+                let row = Rect::new(
+                    0.0,
+                    index as f64 * ROW_HEIGHT,
+                    rows_bc.max().width,
+                    (index + 1) as f64 * ROW_HEIGHT,
+                );
+                // Lay out again, for the list to learn about the new viewport.
+                if self.rows.widget_mut().scroll_to(row) {
+                    self.rows.layout(ctx, &rows_bc, &self.results, env);
+                }
+            }
+        }
+        self.rows
+            .set_origin(ctx, &self.results, env, Point::new(1.0, rows_top));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "PalettePopup", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &(), env: &Env) {
+        let size = ctx.size();
+        ctx.fill(size.to_rect(), &env.get(theme::BACKGROUND_LIGHT));
+        self.textbox.paint(ctx, &self.query, env);
+        self.rows.paint(ctx, &self.results, env);
+        ctx.stroke(
+            size.to_rect().inset(-0.5),
+            &env.get(theme::BORDER_DARK),
+            1.0,
+        );
+    }
+
+    fn debug_state(&self, _data: &()) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: self.query.clone(),
+            children: vec![
+                self.textbox.widget().debug_state(&self.query),
+                self.rows.widget().debug_state(&self.results),
+            ],
+            ..Default::default()
+        }
+    }
+}
+
+/// The widget for a row of the palette.
+struct ActionItem {
+    label: TextLayout<RichText>,
+    shortcut: TextLayout<ArcStr>,
+}
+
+impl ActionItem {
+    fn new() -> Self {
+        let mut shortcut = TextLayout::new();
+        shortcut.set_text_color(theme::PLACEHOLDER_COLOR);
+        ActionItem {
+            label: TextLayout::new(),
+            shortcut,
+        }
+    }
+}
+
+impl Widget<ResultRow> for ActionItem {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut ResultRow, _env: &Env) {
+        match event {
+            Event::MouseMove(_) if ctx.is_hot() && !data.highlighted => {
+                ctx.submit_notification(HIGHLIGHT_ROW.with(data.index));
+            }
+            Event::MouseDown(_) => ctx.set_active(true),
+            Event::MouseUp(_) => {
+                if ctx.is_active() && ctx.is_hot() {
+                    ctx.submit_notification(CHOOSE_ROW.with(data.index));
+                }
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        _ctx: &mut LifeCycleCtx,
+        _event: &LifeCycle,
+        _data: &ResultRow,
+        _env: &Env,
+    ) {
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &ResultRow, data: &ResultRow, _env: &Env) {
+        if !old_data.action.same(&data.action) || !old_data.matched.same(&data.matched) {
+            ctx.request_layout();
+        } else if old_data.highlighted != data.highlighted {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &ResultRow,
+        env: &Env,
+    ) -> Size {
+        let mut text = RichText::new(data.action.label.clone());
+        for range in data.matched.iter() {
+            text.add_attribute(range.clone(), Attribute::weight(FontWeight::BOLD));
+        }
+        self.label.set_text(text);
+        self.label.rebuild_if_needed(ctx.text(), env);
+        self.shortcut
+            .set_text(data.action.shortcut.clone().unwrap_or_default());
+        self.shortcut.rebuild_if_needed(ctx.text(), env);
+        bc.constrain(Size::new(bc.max().width, ROW_HEIGHT))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &ResultRow, env: &Env) {
+        let size = ctx.size();
+        if data.highlighted {
+            ctx.fill(
+                size.to_rect(),
+                &env.get(theme::SELECTED_ITEM_BACKGROUND_COLOR),
+            );
+        }
+        let insets = env.get(theme::TEXTBOX_INSETS);
+        let label_y = (size.height - self.label.size().height) / 2.0;
+        self.label.draw(ctx, (insets.x0, label_y));
+        let shortcut_size = self.shortcut.size();
+        let shortcut_x = size.width - insets.x1 - shortcut_size.width;
+        self.shortcut.draw(
+            ctx,
+            (shortcut_x, (size.height - shortcut_size.height) / 2.0),
+        );
+    }
+
+    fn debug_state(&self, data: &ResultRow) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.action.label.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{press_key, widget_ids};
+    use crate::text::Selection;
+    use crate::widget::{Label, OverlayLayer};
+    use crate::{InternalEvent, WidgetExt};
+    use test_log::test;
+
+    const SAVE: Selector = Selector::new("druid-test.save");
+    const SAVE_AS: Selector = Selector::new("druid-test.save-as");
+
+    #[test]
+    fn filter_and_choose() {
+        let [id] = widget_ids();
+        let palette = CommandPalette::new(|_: &(), _| {
+            vec![
+                PaletteAction::new("open", "Open File", Selector::NOOP),
+                PaletteAction::new("save", "Save", SAVE).with_shortcut("Ctrl+S"),
+                PaletteAction::new("save-as", "Save As", SAVE_AS),
+                PaletteAction::new("sidebar", "Toggle Sidebar", Selector::NOOP),
+            ]
+        });
+        let root = OverlayLayer::new(Label::new("content").controller(palette).with_id(id));
+
+        Harness::create_simple((), root, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(CommandPalette::SHOW.to(id));
+            harness.just_layout();
+            harness.paint();
+            let focus = harness.window().focus;
+            assert!(focus.is_some());
+
+            let token = harness.window().ime_handlers[0].0;
+            let mut handler = harness.window_mut().get_ime_handler(token, true);
+            handler.replace_range(0..0, "sa");
+            handler.set_selection(Selection::caret(2));
+            drop(handler);
+            let widget = harness.window_mut().release_ime_lock(token).unwrap();
+            harness.event(Event::Internal(InternalEvent::RouteImeStateChange(widget)));
+            harness.just_layout();
+            harness.paint();
+            harness.take_commands();
+            press_key(harness, KbKey::ArrowDown);
+            press_key(harness, KbKey::Enter);
+            let chosen: Vec<_> = harness
+                .take_commands()
+                .iter()
+                .filter(|cmd| cmd.is(SAVE) || cmd.is(SAVE_AS))
+                .map(|cmd| cmd.is(SAVE_AS))
+                .collect();
+            assert_eq!(chosen, [true]);
+            assert_eq!(harness.window().focus, None);
+
+            // the palette is closed, and opens again
+            harness.submit_command(CommandPalette::SHOW.to(id));
+            assert!(harness.window().focus.is_some());
+        });
+    }
+}
@@ -74,3 +74,34 @@ impl FillStrat {
         Affine::new([scalex, 0., 0., scaley, origin_x, origin_y])
     }
 }
+
+/// The direction in which content flows horizontally.
+///
+/// This is read from the [`LAYOUT_DIRECTION`] key in the [`Env`], which widgets
+/// such as [`Flex`], [`Padding`], [`Align`] and [`TextBox`] consult to mirror
+/// their layout. Set it once near the root of a window, with an [`EnvScope`]
+/// or [`WidgetExt::env_scope`], to lay out a whole form right-to-left.
+///
+/// [`LAYOUT_DIRECTION`]: crate::theme::LAYOUT_DIRECTION
+/// [`Env`]: crate::Env
+/// [`Flex`]: crate::widget::Flex
+/// [`Padding`]: crate::widget::Padding
+/// [`Align`]: crate::widget::Align
+/// [`TextBox`]: crate::widget::TextBox
+/// [`EnvScope`]: crate::widget::EnvScope
+/// [`WidgetExt::env_scope`]: crate::WidgetExt::env_scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data, Default)]
+pub enum LayoutDirection {
+    /// Content starts at the left edge, as in English.
+    #[default]
+    Ltr,
+    /// Content starts at the right edge, as in Arabic or Hebrew.
+    Rtl,
+}
+
+impl LayoutDirection {
+    /// Returns `true` if this is [`LayoutDirection::Rtl`].
+    pub fn is_rtl(self) -> bool {
+        self == LayoutDirection::Rtl
+    }
+}
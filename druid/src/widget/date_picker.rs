@@ -0,0 +1,997 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A date picker widget with a calendar drop-down.
+
+use std::fmt;
+
+use tracing::{instrument, trace, warn};
+
+use crate::commands::CLOSE_WINDOW;
+use crate::debug_state::DebugState;
+use crate::kurbo::BezPath;
+use crate::piet::{PietTextLayout, Text, TextLayout as _, TextLayoutBuilder};
+use crate::widget::prelude::*;
+use crate::{
+    theme, ArcStr, Color, Command, KbKey, LocalizedString, Point, Rect, Selector, TextLayout,
+    WindowConfig, WindowId, WindowLevel, WindowSizePolicy,
+};
+
+/// Sent by the calendar drop-down to its [`DatePicker`] when it closes, with
+/// whether it closed because it lost focus.
+const POPUP_CLOSED: Selector<bool> = Selector::new("druid-builtin.date-picker-popup-closed");
+
+const MONTH_KEYS: [&str; 12] = [
+    "date-picker-month-1",
+    "date-picker-month-2",
+    "date-picker-month-3",
+    "date-picker-month-4",
+    "date-picker-month-5",
+    "date-picker-month-6",
+    "date-picker-month-7",
+    "date-picker-month-8",
+    "date-picker-month-9",
+    "date-picker-month-10",
+    "date-picker-month-11",
+    "date-picker-month-12",
+];
+
+const WEEKDAY_KEYS: [&str; 7] = [
+    "date-picker-weekday-1",
+    "date-picker-weekday-2",
+    "date-picker-weekday-3",
+    "date-picker-weekday-4",
+    "date-picker-weekday-5",
+    "date-picker-weekday-6",
+    "date-picker-weekday-7",
+];
+
+const CELL_SIZE: Size = Size::new(28.0, 24.0);
+const POPUP_PADDING: f64 = 4.0;
+const ARROW_SIZE: f64 = 8.0;
+
+/// A calendar date, in the proleptic Gregorian calendar.
+///
+/// This is the [`Data`] type edited by a [`DatePicker`]. If the `chrono`
+/// feature is enabled it can be converted to and from a `chrono::NaiveDate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Data)]
+pub struct CalendarDate {
+    /// The year.
+    pub year: i32,
+    /// The month, in the range `1..=12`.
+    pub month: u8,
+    /// The day of the month, starting at `1`.
+    pub day: u8,
+}
+
+impl CalendarDate {
+    /// Create a new `CalendarDate`.
+    ///
+    /// Out of range values are clamped; the month into `1..=12`, and the day
+    /// into the number of days of that month.
+    pub fn new(year: i32, month: u8, day: u8) -> Self {
+        let month = month.clamp(1, 12);
+        let day = day.clamp(1, Self::days_in_month(year, month));
+        CalendarDate { year, month, day }
+    }
+
+    /// Returns `true` if `year` is a leap year.
+    pub fn is_leap_year(year: i32) -> bool {
+        (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+    }
+
+    /// The number of days in the given month of the given year.
+    pub fn days_in_month(year: i32, month: u8) -> u8 {
+        match month {
+            2 if Self::is_leap_year(year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    /// The day of the week, where `0` is Monday and `6` is Sunday.
+    pub fn weekday(self) -> u8 {
+        (self.days_since_epoch() + 3).rem_euclid(7) as u8
+    }
+
+    /// The first day of this date's month.
+    pub fn first_of_month(self) -> Self {
+        CalendarDate { day: 1, ..self }
+    }
+
+    /// Returns this date moved by the given number of days.
+    pub fn add_days(self, days: i64) -> Self {
+        Self::from_days_since_epoch(self.days_since_epoch() + days)
+    }
+
+    /// Returns this date moved by the given number of months.
+    ///
+    /// If the day does not exist in the target month, the last day of that
+    /// month is used instead.
+    pub fn add_months(self, months: i32) -> Self {
+        let index = self.year * 12 + (self.month as i32 - 1) + months;
+        CalendarDate::new(
+            index.div_euclid(12),
+            index.rem_euclid(12) as u8 + 1,
+            self.day,
+        )
+    }
+
+    // Algorithms from http://howardhinnant.github.io/date_algorithms.html
+    fn days_since_epoch(self) -> i64 {
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    fn from_days_since_epoch(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+        CalendarDate {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+}
+
+impl fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for CalendarDate {
+    fn from(date: chrono::NaiveDate) -> Self {
+        use chrono::Datelike;
+        CalendarDate::new(date.year(), date.month() as u8, date.day() as u8)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<CalendarDate> for chrono::NaiveDate {
+    fn from(date: CalendarDate) -> Self {
+        chrono::NaiveDate::from_ymd(date.year, date.month as u32, date.day as u32)
+    }
+}
+
+/// A widget for picking a [`CalendarDate`].
+///
+/// The picker looks like a [`TextBox`] showing the formatted date. Clicking it,
+/// or pressing enter, space or the down arrow while it is focused, opens a
+/// calendar drop-down below it. In the calendar, the arrow keys move between
+/// days, page up and page down move between months, and enter selects the
+/// highlighted day. The drop-down closes when a day is picked, when escape
+/// is pressed, or when it loses focus.
+///
+/// Month and weekday names are localized using the current [`Env`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{CalendarDate, DatePicker};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Booking {
+///     arrival: CalendarDate,
+/// }
+///
+/// fn make_picker() -> impl Widget<Booking> {
+///     DatePicker::new()
+///         .with_range(CalendarDate::new(2021, 1, 1), CalendarDate::new(2021, 12, 31))
+///         .lens(Booking::arrival)
+/// }
+/// ```
+///
+/// [`TextBox`]: super::TextBox
+pub struct DatePicker {
+    min: Option<CalendarDate>,
+    max: Option<CalendarDate>,
+    text: LocalizedString<CalendarDate>,
+    layout: TextLayout<ArcStr>,
+    popup: Option<WindowId>,
+    /// The drop-down just closed because it lost focus, which happens when
+    /// the picker itself is pressed; that press shouldn't open it again.
+    dismissed: bool,
+}
+
+impl DatePicker {
+    /// Create a new `DatePicker`.
+    pub fn new() -> Self {
+        let text = LocalizedString::new("date-picker-date")
+            .with_arg("year", |date: &CalendarDate, _| date.year.into())
+            .with_arg("month", |date: &CalendarDate, env| {
+                month_name(date.month, env).to_string().into()
+            })
+            .with_arg("day", |date: &CalendarDate, _| date.day.into());
+        DatePicker {
+            min: None,
+            max: None,
+            text,
+            layout: TextLayout::new(),
+            popup: None,
+            dismissed: false,
+        }
+    }
+
+    /// Limit the dates that can be picked to `min..=max`.
+    ///
+    /// Dates outside of the range are shown greyed out in the calendar.
+    pub fn with_range(mut self, min: CalendarDate, max: CalendarDate) -> Self {
+        if min > max {
+            warn!(
+                "DatePicker: min ({}) is after max ({}), swapping them",
+                min, max
+            );
+            self.min = Some(max);
+            self.max = Some(min);
+        } else {
+            self.min = Some(min);
+            self.max = Some(max);
+        }
+        self
+    }
+
+    fn open_popup(&mut self, ctx: &mut EventCtx, data: CalendarDate, env: &Env) {
+        let origin = ctx.to_window(Point::new(0.0, ctx.size().height));
+        let popup = CalendarPopup::new(ctx.widget_id(), data, self.min, self.max);
+        let window_id = ctx.new_sub_window(
+            WindowConfig::default()
+                .show_titlebar(false)
+                .resizable(false)
+                .window_size_policy(WindowSizePolicy::Content)
+                .set_level(WindowLevel::DropDown(ctx.window().clone()))
+                .set_position(origin),
+            popup,
+            data,
+            env.clone(),
+        );
+        trace!(
+            "DatePicker {:?} opened popup {:?}",
+            ctx.widget_id(),
+            window_id
+        );
+        self.popup = Some(window_id);
+    }
+
+    /// The command closing the drop-down, if it is open.
+    fn close_popup(&mut self) -> Option<Command> {
+        self.popup
+            .take()
+            .map(|window_id| CLOSE_WINDOW.to(window_id))
+    }
+}
+
+impl Default for DatePicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget<CalendarDate> for DatePicker {
+    #[instrument(
+        name = "DatePicker",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut CalendarDate, env: &Env) {
+        if let Event::KeyDown(_) = event {
+            self.dismissed = false;
+        }
+        match event {
+            Event::MouseDown(_) if !ctx.is_disabled() => {
+                ctx.set_active(true);
+                ctx.request_focus();
+                ctx.request_paint();
+            }
+            Event::MouseUp(_) => {
+                let dismissed = std::mem::take(&mut self.dismissed);
+                if ctx.is_active() && !ctx.is_disabled() && ctx.is_hot() {
+                    if let Some(close) = self.close_popup() {
+                        ctx.submit_command(close);
+                    } else if !dismissed {
+                        self.open_popup(ctx, *data, env);
+                    }
+                }
+                ctx.set_active(false);
+                ctx.request_paint();
+            }
+            Event::KeyDown(key) if !ctx.is_disabled() => match &key.key {
+                KbKey::Enter | KbKey::ArrowDown if self.popup.is_none() => {
+                    self.open_popup(ctx, *data, env);
+                    ctx.set_handled();
+                }
+                KbKey::Character(c) if c == " " && self.popup.is_none() => {
+                    self.open_popup(ctx, *data, env);
+                    ctx.set_handled();
+                }
+                KbKey::Escape => {
+                    if let Some(close) = self.close_popup() {
+                        ctx.submit_command(close);
+                        ctx.set_handled();
+                    }
+                }
+                _ => (),
+            },
+            Event::Command(cmd) if cmd.is(POPUP_CLOSED) => {
+                // if the picker closed it itself, the press is already over
+                if self.popup.take().is_some() {
+                    self.dismissed = *cmd.get_unchecked(POPUP_CLOSED);
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "DatePicker",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &CalendarDate,
+        _env: &Env,
+    ) {
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(false) | LifeCycle::DisabledChanged(true) => {
+                if let Some(close) = self.close_popup() {
+                    ctx.submit_command(close);
+                }
+                ctx.request_paint();
+            }
+            LifeCycle::HotChanged(_) => {
+                // the pointer moved, so the next press isn't the one that
+                // made the drop-down lose focus
+                self.dismissed = false;
+                ctx.request_paint();
+            }
+            LifeCycle::FocusChanged(true) | LifeCycle::DisabledChanged(false) => {
+                ctx.request_paint()
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "DatePicker",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &CalendarDate,
+        data: &CalendarDate,
+        _env: &Env,
+    ) {
+        if !old_data.same(data) || ctx.env_changed() {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "DatePicker", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &CalendarDate,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("DatePicker");
+
+        self.text.resolve(data, env);
+        self.layout.set_text(self.text.localized_str());
+        self.layout.rebuild_if_needed(ctx.text(), env);
+
+        let insets = env.get(theme::TEXTBOX_INSETS);
+        let text_size = self.layout.size();
+        let height =
+            (text_size.height + insets.y_value()).max(env.get(theme::BORDERED_WIDGET_HEIGHT));
+        let width = text_size.width + insets.x_value() + ARROW_SIZE + insets.x1;
+        let size = bc.constrain(Size::new(width, height));
+
+        let text_metrics = self.layout.layout_metrics();
+        let text_offset = (size.height - text_size.height) / 2.0;
+        ctx.set_baseline_offset(size.height - text_offset - text_metrics.first_baseline);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "DatePicker", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &CalendarDate, env: &Env) {
+        let size = ctx.size();
+        let border_width = env.get(theme::TEXTBOX_BORDER_WIDTH);
+        let insets = env.get(theme::TEXTBOX_INSETS);
+
+        let border_color = if ctx.is_focused() && !ctx.is_disabled() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        let text_color = if ctx.is_disabled() {
+            env.get(theme::DISABLED_TEXT_COLOR)
+        } else {
+            env.get(theme::TEXT_COLOR)
+        };
+
+        let rect = size
+            .to_rect()
+            .inset(-border_width / 2.0)
+            .to_rounded_rect(env.get(theme::TEXTBOX_BORDER_RADIUS));
+        ctx.fill(rect, &env.get(theme::BACKGROUND_LIGHT));
+        ctx.stroke(rect, &border_color, border_width);
+
+        let text_y = (size.height - self.layout.size().height) / 2.0;
+        ctx.with_save(|ctx| {
+            ctx.clip(rect);
+            self.layout.draw(ctx, (insets.x0, text_y));
+        });
+
+        let arrow_x = size.width - insets.x1 - ARROW_SIZE;
+        let arrow_y = (size.height - ARROW_SIZE / 2.0) / 2.0;
+        let mut arrow = BezPath::new();
+        arrow.move_to((arrow_x, arrow_y));
+        arrow.line_to((arrow_x + ARROW_SIZE, arrow_y));
+        arrow.line_to((arrow_x + ARROW_SIZE / 2.0, arrow_y + ARROW_SIZE / 2.0));
+        arrow.close_path();
+        ctx.fill(arrow, &text_color);
+    }
+
+    fn debug_state(&self, data: &CalendarDate) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The calendar shown in the drop-down window of a [`DatePicker`].
+struct CalendarPopup {
+    picker: WidgetId,
+    min: Option<CalendarDate>,
+    max: Option<CalendarDate>,
+    /// The highlighted day; the displayed month is the month of this date.
+    cursor: CalendarDate,
+    hot: Option<CalendarDate>,
+    /// The window is closing because it lost focus.
+    lost_focus: bool,
+    title: TextLayout<ArcStr>,
+    weekdays: Vec<TextLayout<ArcStr>>,
+    days: Vec<PietTextLayout>,
+}
+
+impl CalendarPopup {
+    fn new(
+        picker: WidgetId,
+        date: CalendarDate,
+        min: Option<CalendarDate>,
+        max: Option<CalendarDate>,
+    ) -> Self {
+        CalendarPopup {
+            picker,
+            min,
+            max,
+            cursor: date,
+            hot: None,
+            lost_focus: false,
+            title: TextLayout::new(),
+            weekdays: (0..7).map(|_| TextLayout::new()).collect(),
+            days: Vec::new(),
+        }
+    }
+
+    fn in_range(&self, date: CalendarDate) -> bool {
+        self.min.map(|min| date >= min).unwrap_or(true)
+            && self.max.map(|max| date <= max).unwrap_or(true)
+    }
+
+    fn clamp(&self, date: CalendarDate) -> CalendarDate {
+        let date = self.min.map(|min| date.max(min)).unwrap_or(date);
+        self.max.map(|max| date.min(max)).unwrap_or(date)
+    }
+
+    fn move_cursor(&mut self, ctx: &mut EventCtx, date: CalendarDate) {
+        let date = self.clamp(date);
+        if date.year != self.cursor.year || date.month != self.cursor.month {
+            ctx.request_layout();
+        }
+        self.cursor = date;
+        ctx.request_paint();
+    }
+
+    fn grid_origin(&self) -> Point {
+        Point::new(POPUP_PADDING, POPUP_PADDING + CELL_SIZE.height * 2.0)
+    }
+
+    fn cell_rect(&self, date: CalendarDate) -> Rect {
+        let index = (date.first_of_month().weekday() + date.day - 1) as f64;
+        let col = index % 7.0;
+        let row = (index / 7.0).floor();
+        Rect::from_origin_size(
+            self.grid_origin() + (col * CELL_SIZE.width, row * CELL_SIZE.height),
+            CELL_SIZE,
+        )
+    }
+
+    fn date_at(&self, pos: Point) -> Option<CalendarDate> {
+        let rel = pos - self.grid_origin();
+        if rel.x < 0.0 || rel.y < 0.0 || rel.x >= CELL_SIZE.width * 7.0 {
+            return None;
+        }
+        let index = (rel.y / CELL_SIZE.height).floor() as i64 * 7
+            + (rel.x / CELL_SIZE.width).floor() as i64;
+        let first = self.cursor.first_of_month();
+        let day = index - first.weekday() as i64 + 1;
+        let days = CalendarDate::days_in_month(first.year, first.month) as i64;
+        if day >= 1 && day <= days {
+            Some(CalendarDate {
+                day: day as u8,
+                ..first
+            })
+        } else {
+            None
+        }
+    }
+
+    fn header_button_rects(&self) -> (Rect, Rect) {
+        let prev = Rect::from_origin_size((POPUP_PADDING, POPUP_PADDING), CELL_SIZE);
+        let next = prev.with_origin((POPUP_PADDING + CELL_SIZE.width * 6.0, POPUP_PADDING));
+        (prev, next)
+    }
+
+    fn commit(&self, ctx: &mut EventCtx, data: &mut CalendarDate, date: CalendarDate) {
+        if self.in_range(date) {
+            *data = date;
+            ctx.submit_command(CLOSE_WINDOW);
+        }
+    }
+}
+
+impl Widget<CalendarDate> for CalendarPopup {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut CalendarDate, _env: &Env) {
+        match event {
+            Event::WindowConnected => ctx.request_focus(),
+            Event::WindowLostFocus => {
+                self.lost_focus = true;
+                ctx.submit_command(CLOSE_WINDOW);
+            }
+            Event::WindowDisconnected => {
+                ctx.submit_command(POPUP_CLOSED.with(self.lost_focus).to(self.picker))
+            }
+            Event::MouseMove(mouse) => {
+                let hot = self.date_at(mouse.pos);
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) => {
+                let (prev, next) = self.header_button_rects();
+                if prev.contains(mouse.pos) {
+                    self.move_cursor(ctx, self.cursor.add_months(-1));
+                } else if next.contains(mouse.pos) {
+                    self.move_cursor(ctx, self.cursor.add_months(1));
+                } else if let Some(date) = self.date_at(mouse.pos) {
+                    self.commit(ctx, data, date);
+                }
+            }
+            Event::KeyDown(key) => {
+                let cursor = self.cursor;
+                match &key.key {
+                    KbKey::ArrowLeft => self.move_cursor(ctx, cursor.add_days(-1)),
+                    KbKey::ArrowRight => self.move_cursor(ctx, cursor.add_days(1)),
+                    KbKey::ArrowUp => self.move_cursor(ctx, cursor.add_days(-7)),
+                    KbKey::ArrowDown => self.move_cursor(ctx, cursor.add_days(7)),
+                    KbKey::PageUp => self.move_cursor(ctx, cursor.add_months(-1)),
+                    KbKey::PageDown => self.move_cursor(ctx, cursor.add_months(1)),
+                    KbKey::Home => self.move_cursor(ctx, cursor.first_of_month()),
+                    KbKey::End => {
+                        let last = CalendarDate::days_in_month(cursor.year, cursor.month);
+                        self.move_cursor(
+                            ctx,
+                            CalendarDate {
+                                day: last,
+                                ..cursor
+                            },
+                        )
+                    }
+                    KbKey::Enter => self.commit(ctx, data, cursor),
+                    KbKey::Character(c) if c == " " => self.commit(ctx, data, cursor),
+                    KbKey::Escape => ctx.submit_command(CLOSE_WINDOW),
+                    _ => return,
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &CalendarDate,
+        _env: &Env,
+    ) {
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(false) => {
+                self.hot = None;
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &CalendarDate,
+        data: &CalendarDate,
+        _env: &Env,
+    ) {
+        if !old_data.same(data) {
+            self.cursor = *data;
+            ctx.request_layout();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &CalendarDate,
+        env: &Env,
+    ) -> Size {
+        let mut title = LocalizedString::<()>::new("date-picker-month-title")
+            .with_arg("month", {
+                let month = self.cursor.month;
+                move |_, env| month_name(month, env).to_string().into()
+            })
+            .with_arg("year", {
+                let year = self.cursor.year;
+                move |_, _| year.into()
+            });
+        title.resolve(&(), env);
+        self.title.set_text(title.localized_str());
+        self.title.set_font(theme::UI_FONT_BOLD);
+        self.title.rebuild_if_needed(ctx.text(), env);
+
+        for (i, layout) in self.weekdays.iter_mut().enumerate() {
+            let mut name = LocalizedString::<()>::new(WEEKDAY_KEYS[i]);
+            name.resolve(&(), env);
+            layout.set_text(name.localized_str());
+            layout.set_text_color(theme::PLACEHOLDER_COLOR);
+            layout.rebuild_if_needed(ctx.text(), env);
+        }
+
+        let font = env.get(theme::UI_FONT);
+        let days = CalendarDate::days_in_month(self.cursor.year, self.cursor.month);
+        let first = self.cursor.first_of_month();
+        self.days = (1..=days)
+            .map(|day| {
+                let color: Color = if self.in_range(CalendarDate { day, ..first }) {
+                    env.get(theme::TEXT_COLOR)
+                } else {
+                    env.get(theme::DISABLED_TEXT_COLOR)
+                };
+                ctx.text()
+                    .new_text_layout(day.to_string())
+                    .font(font.family.clone(), font.size)
+                    .text_color(color)
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+
+        let size = Size::new(
+            CELL_SIZE.width * 7.0 + POPUP_PADDING * 2.0,
+            CELL_SIZE.height * 8.0 + POPUP_PADDING * 2.0,
+        );
+        bc.constrain(size)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &CalendarDate, env: &Env) {
+        let size = ctx.size();
+        ctx.fill(size.to_rect(), &env.get(theme::BACKGROUND_LIGHT));
+        ctx.stroke(
+            size.to_rect().inset(-0.5),
+            &env.get(theme::BORDER_DARK),
+            1.0,
+        );
+
+        // header: previous month, title, next month
+        let text_color = env.get(theme::TEXT_COLOR);
+        let (prev, next) = self.header_button_rects();
+        let mut arrows = BezPath::new();
+        let (prev_center, next_center) = (prev.center(), next.center());
+        let half = ARROW_SIZE / 2.0;
+        arrows.move_to(prev_center + (half / 2.0, -half));
+        arrows.line_to(prev_center + (-half / 2.0, 0.0));
+        arrows.line_to(prev_center + (half / 2.0, half));
+        arrows.close_path();
+        arrows.move_to(next_center + (-half / 2.0, -half));
+        arrows.line_to(next_center + (half / 2.0, 0.0));
+        arrows.line_to(next_center + (-half / 2.0, half));
+        arrows.close_path();
+        ctx.fill(arrows, &text_color);
+
+        let title_size = self.title.size();
+        self.title.draw(
+            ctx,
+            (
+                (size.width - title_size.width) / 2.0,
+                POPUP_PADDING + (CELL_SIZE.height - title_size.height) / 2.0,
+            ),
+        );
+
+        for (i, layout) in self.weekdays.iter().enumerate() {
+            let layout_size = layout.size();
+            layout.draw(
+                ctx,
+                (
+                    POPUP_PADDING
+                        + CELL_SIZE.width * i as f64
+                        + (CELL_SIZE.width - layout_size.width) / 2.0,
+                    POPUP_PADDING
+                        + CELL_SIZE.height
+                        + (CELL_SIZE.height - layout_size.height) / 2.0,
+                ),
+            );
+        }
+
+        let first = self.cursor.first_of_month();
+        for (i, layout) in self.days.iter().enumerate() {
+            let date = CalendarDate {
+                day: i as u8 + 1,
+                ..first
+            };
+            let rect = self.cell_rect(date);
+            let cell = rect.inset(-1.0).to_rounded_rect(2.0);
+            if date == *data {
+                ctx.fill(cell, &env.get(theme::PRIMARY_DARK));
+            } else if Some(date) == self.hot && self.in_range(date) {
+                ctx.fill(cell, &env.get(theme::BACKGROUND_DARK));
+            }
+            if date == self.cursor {
+                ctx.stroke(cell, &env.get(theme::PRIMARY_LIGHT), 1.0);
+            }
+            let layout_size = layout.size();
+            ctx.draw_text(
+                layout,
+                rect.origin()
+                    + (
+                        (CELL_SIZE.width - layout_size.width) / 2.0,
+                        (CELL_SIZE.height - layout_size.height) / 2.0,
+                    ),
+            );
+        }
+    }
+}
+
+fn month_name(month: u8, env: &Env) -> ArcStr {
+    let mut name = LocalizedString::<()>::new(MONTH_KEYS[(month.clamp(1, 12) - 1) as usize]);
+    name.resolve(&(), env);
+    name.localized_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::NEW_SUB_WINDOW;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{click, press_key, widget_ids};
+    use crate::widget::Flex;
+    use crate::WidgetExt;
+    use test_log::test;
+
+    /// Whether the drop-down was opened and closed since the last call.
+    fn opened_closed(harness: &mut Harness<CalendarDate>) -> (bool, bool) {
+        let commands = harness.take_commands();
+        (
+            commands.iter().any(|cmd| cmd.is(NEW_SUB_WINDOW)),
+            commands.iter().any(|cmd| cmd.is(CLOSE_WINDOW)),
+        )
+    }
+
+    fn two_pickers(id: WidgetId, other: WidgetId) -> impl Widget<CalendarDate> {
+        Flex::row()
+            .with_child(DatePicker::new().with_id(id))
+            .with_child(DatePicker::new().with_id(other))
+    }
+
+    #[test]
+    fn escape_and_focus_loss_close_popup() {
+        let [id, other] = widget_ids();
+        let date = CalendarDate::new(2021, 3, 15);
+        Harness::create_simple(date, two_pickers(id, other), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let center = harness.get_state(id).layout_rect().center();
+
+            click(harness, center);
+            assert_eq!(opened_closed(harness), (true, false));
+            press_key(harness, KbKey::Escape);
+            assert_eq!(opened_closed(harness), (false, true));
+
+            press_key(harness, KbKey::Enter);
+            assert_eq!(opened_closed(harness), (true, false));
+            // moving the focus to the other picker closes the drop-down, and
+            // the other picker opens its own
+            let other_center = harness.get_state(other).layout_rect().center();
+            click(harness, other_center);
+            assert_eq!(opened_closed(harness), (true, true));
+        });
+    }
+
+    #[test]
+    fn press_that_dismissed_popup_doesnt_reopen_it() {
+        let [id, other] = widget_ids();
+        let date = CalendarDate::new(2021, 3, 15);
+        Harness::create_simple(date, two_pickers(id, other), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let center = harness.get_state(id).layout_rect().center();
+
+            click(harness, center);
+            assert_eq!(opened_closed(harness), (true, false));
+            // pressing the picker takes the focus from the drop-down, which
+            // closes before the press is released
+            harness.submit_command(POPUP_CLOSED.with(true).to(id));
+            click(harness, center);
+            assert_eq!(opened_closed(harness), (false, false));
+            // the next press opens it again
+            click(harness, center);
+            assert_eq!(opened_closed(harness), (true, false));
+        });
+    }
+
+    #[test]
+    fn popup_closes_on_escape_and_focus_loss() {
+        let picker = WidgetId::next();
+        let date = CalendarDate::new(2021, 3, 15);
+        let popup = CalendarPopup::new(picker, date, None, None);
+        Harness::create_simple(date, popup, |harness| {
+            harness.send_initial_events();
+            harness.take_commands();
+            press_key(harness, KbKey::Escape);
+            assert!(harness
+                .take_commands()
+                .iter()
+                .any(|cmd| cmd.is(CLOSE_WINDOW)));
+            harness.event(Event::WindowDisconnected);
+            let closed = harness.take_commands();
+            assert_eq!(closed[0].get(POPUP_CLOSED), Some(&false));
+
+            harness.event(Event::WindowLostFocus);
+            assert!(harness
+                .take_commands()
+                .iter()
+                .any(|cmd| cmd.is(CLOSE_WINDOW)));
+            harness.event(Event::WindowDisconnected);
+            let closed = harness.take_commands();
+            assert_eq!(closed[0].get(POPUP_CLOSED), Some(&true));
+        });
+    }
+
+    #[test]
+    fn days_in_month() {
+        assert_eq!(CalendarDate::days_in_month(2021, 1), 31);
+        assert_eq!(CalendarDate::days_in_month(2021, 2), 28);
+        assert_eq!(CalendarDate::days_in_month(2020, 2), 29);
+        assert_eq!(CalendarDate::days_in_month(1900, 2), 28);
+        assert_eq!(CalendarDate::days_in_month(2000, 2), 29);
+        assert_eq!(CalendarDate::days_in_month(2021, 4), 30);
+    }
+
+    #[test]
+    fn weekday() {
+        // 1970-01-01 was a Thursday
+        assert_eq!(CalendarDate::new(1970, 1, 1).weekday(), 3);
+        // 2021-01-01 was a Friday
+        assert_eq!(CalendarDate::new(2021, 1, 1).weekday(), 4);
+        // 1600-03-01 was a Wednesday
+        assert_eq!(CalendarDate::new(1600, 3, 1).weekday(), 2);
+    }
+
+    #[test]
+    fn add_days_round_trips() {
+        let date = CalendarDate::new(2020, 2, 28);
+        assert_eq!(date.add_days(1), CalendarDate::new(2020, 2, 29));
+        assert_eq!(date.add_days(2), CalendarDate::new(2020, 3, 1));
+        assert_eq!(date.add_days(-59), CalendarDate::new(2019, 12, 31));
+        for offset in -1000..1000 {
+            assert_eq!(date.add_days(offset).add_days(-offset), date);
+        }
+    }
+
+    #[test]
+    fn add_months_clamps_day() {
+        let date = CalendarDate::new(2021, 1, 31);
+        assert_eq!(date.add_months(1), CalendarDate::new(2021, 2, 28));
+        assert_eq!(date.add_months(-1), CalendarDate::new(2020, 12, 31));
+        assert_eq!(date.add_months(13), CalendarDate::new(2022, 2, 28));
+        assert_eq!(date.add_months(-13), CalendarDate::new(2019, 12, 31));
+    }
+
+    #[test]
+    fn new_clamps() {
+        assert_eq!(
+            CalendarDate::new(2021, 13, 40),
+            CalendarDate::new(2021, 12, 31)
+        );
+        assert_eq!(CalendarDate::new(2021, 0, 0), CalendarDate::new(2021, 1, 1));
+    }
+
+    #[test]
+    fn popup_hit_testing() {
+        // 2021-03-01 is a Monday, so it is in the first column
+        let popup =
+            CalendarPopup::new(WidgetId::next(), CalendarDate::new(2021, 3, 15), None, None);
+        let first = popup.cell_rect(CalendarDate::new(2021, 3, 1));
+        assert_eq!(first.origin(), popup.grid_origin());
+        assert_eq!(
+            popup.date_at(first.center()),
+            Some(CalendarDate::new(2021, 3, 1))
+        );
+        let last = popup.cell_rect(CalendarDate::new(2021, 3, 31));
+        assert_eq!(
+            popup.date_at(last.center()),
+            Some(CalendarDate::new(2021, 3, 31))
+        );
+        assert_eq!(popup.date_at(last.center() + (CELL_SIZE.width, 0.0)), None);
+    }
+
+    #[test]
+    fn popup_range() {
+        let popup = CalendarPopup::new(
+            WidgetId::next(),
+            CalendarDate::new(2021, 3, 15),
+            Some(CalendarDate::new(2021, 3, 10)),
+            Some(CalendarDate::new(2021, 3, 20)),
+        );
+        assert!(!popup.in_range(CalendarDate::new(2021, 3, 9)));
+        assert!(popup.in_range(CalendarDate::new(2021, 3, 10)));
+        assert_eq!(
+            popup.clamp(CalendarDate::new(2021, 4, 1)),
+            CalendarDate::new(2021, 3, 20)
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that coalesces rapid data changes into a single action.
+//!
+//! [`Controller`]: crate::widget::Controller
+
+use std::time::Duration;
+
+use crate::widget::Controller;
+use crate::{Data, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, TimerToken, UpdateCtx, Widget};
+use tracing::{instrument, trace};
+
+type ActionFn<T> = dyn Fn(&mut EventCtx, &mut T, &Env);
+
+/// A [`Controller`] that waits for the data to stop changing before acting.
+///
+/// Every time the data changes, an internal timer is (re)started. When the
+/// timer fires without any further changes having occurred, the provided
+/// closure is invoked once. This is useful for things like search boxes,
+/// where you want to react to the user's input, but not on every keystroke.
+///
+/// Changes that the closure itself makes to the data don't restart the timer.
+///
+/// Pending timers are discarded when the widget is removed from the tree,
+/// so the closure will never be called for a widget that no longer exists.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::widget::{Debounce, TextBox};
+/// use druid::{Selector, Widget, WidgetExt};
+///
+/// const SEARCH: Selector<String> = Selector::new("my-app.search");
+///
+/// fn search_box() -> impl Widget<String> {
+///     TextBox::new().controller(Debounce::new(
+///         Duration::from_millis(300),
+///         |ctx, query: &mut String, _env| ctx.submit_command(SEARCH.with(query.clone())),
+///     ))
+/// }
+/// ```
+///
+/// [`Controller`]: crate::widget::Controller
+pub struct Debounce<T> {
+    delay: Duration,
+    timer: TimerToken,
+    action: Box<ActionFn<T>>,
+    /// The data as the action left it, so its own changes aren't debounced again.
+    acted_on: Option<T>,
+}
+
+impl<T: Data> Debounce<T> {
+    /// Create a new `Debounce` controller.
+    ///
+    /// The `action` is called once the data has not changed for `delay`.
+    pub fn new(delay: Duration, action: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        Debounce {
+            delay,
+            timer: TimerToken::INVALID,
+            action: Box::new(action),
+            acted_on: None,
+        }
+    }
+
+    /// Returns `true` if a change is waiting for the timer to fire.
+    pub fn is_pending(&self) -> bool {
+        self.timer != TimerToken::INVALID
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Debounce<T> {
+    #[instrument(
+        name = "Debounce",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Timer(token) if *token == self.timer => {
+                trace!("Debounce timer fired");
+                self.timer = TimerToken::INVALID;
+                ctx.set_handled();
+                (self.action)(ctx, data, env);
+                self.acted_on = Some(data.clone());
+            }
+            Event::WindowDisconnected => {
+                self.timer = TimerToken::INVALID;
+                child.event(ctx, event, data, env);
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    #[instrument(
+        name = "Debounce",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            // Don't carry timers over if the widget is moved to a new tree.
+            self.timer = TimerToken::INVALID;
+            self.acted_on = None;
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "Debounce",
+        level = "trace",
+        skip(self, child, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let acted_on = self.acted_on.take();
+        if !old_data.same(data) && !matches!(acted_on, Some(acted_on) if acted_on.same(data)) {
+            // Any previously requested timer is now stale and will be ignored.
+            self.timer = ctx.request_timer(self.delay);
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::WidgetExt;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use test_log::test;
+
+    #[test]
+    fn coalesces_rapid_changes() {
+        let calls = Rc::new(Cell::new(Vec::<u32>::new()));
+        let calls_2 = calls.clone();
+
+        let widget = ModularWidget::<(), u32>::new(()).controller(Debounce::new(
+            Duration::from_millis(100),
+            move |_, data: &mut u32, _| {
+                let mut seen = calls_2.take();
+                seen.push(*data);
+                calls_2.set(seen);
+            },
+        ));
+
+        Harness::create_simple(0u32, widget, |harness| {
+            harness.send_initial_events();
+            harness.update_data(|data| *data = 1);
+            harness.update_data(|data| *data = 2);
+            harness.update_data(|data| *data = 3);
+
+            let tokens: Vec<TimerToken> = harness.window().timers.keys().copied().collect();
+            assert_eq!(tokens.len(), 3);
+            for token in tokens {
+                harness.event(Event::Timer(token));
+            }
+
+            assert_eq!(calls.take(), vec![3]);
+        });
+    }
+
+    #[test]
+    fn action_changes_are_not_debounced() {
+        let calls = Rc::new(Cell::new(0));
+        let calls_2 = calls.clone();
+
+        let widget = ModularWidget::<(), u32>::new(()).controller(Debounce::new(
+            Duration::from_millis(100),
+            move |_, data: &mut u32, _| {
+                calls_2.set(calls_2.get() + 1);
+                *data *= 10;
+            },
+        ));
+
+        Harness::create_simple(0u32, widget, |harness| {
+            harness.send_initial_events();
+            harness.update_data(|data| *data = 1);
+            let token = *harness.window().timers.keys().next().unwrap();
+            harness.event(Event::Timer(token));
+
+            assert_eq!(*harness.data(), 10);
+            assert_eq!(calls.get(), 1);
+            assert!(harness.window().timers.is_empty());
+
+            // Later changes are debounced as usual.
+            harness.update_data(|data| *data = 2);
+            assert_eq!(harness.window().timers.len(), 1);
+        });
+    }
+}
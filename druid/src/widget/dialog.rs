@@ -0,0 +1,367 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Modal dialogs.
+
+use std::any::Any;
+
+use tracing::{instrument, trace, warn};
+
+use crate::commands::{CLOSE_DIALOG, DISMISS_POPUP};
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::widget::{FocusGroup, Popup};
+use crate::{theme, Color, KbKey, KeyOrValue, Point, WidgetPod};
+
+type CloseFn<T, R> = dyn FnOnce(&mut EventCtx, R, &mut T, &Env);
+
+/// The scrim color if none is set: a translucent black.
+const DEFAULT_SCRIM_COLOR: Color = Color::rgba8(0, 0, 0, 0x80);
+
+/// A modal dialog, shown with [`EventCtx::show_dialog`].
+///
+/// The dialog is a [`Popup`] that covers its [`OverlayLayer`] with a dimmed
+/// scrim, with the dialog's widget at the center. The widgets under the scrim
+/// don't get the pointer, and the focus stays in the dialog while it is open,
+/// and goes back to where it was when it closes.
+///
+/// A widget in the dialog closes it with a result of type `R` by calling
+/// [`EventCtx::close_dialog`]; escape and clicks on the scrim close it with
+/// the cancel result. The result is passed to the [`on_close`] callback.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Dialog, Flex, Label};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Document {
+///     text: String,
+/// }
+///
+/// fn confirm_clear() -> Dialog<Document, bool> {
+///     let buttons = Flex::row()
+///         .with_child(Button::new("Cancel").on_click(|ctx, _, _| ctx.close_dialog(false)))
+///         .with_child(Button::new("Clear").on_click(|ctx, _, _| ctx.close_dialog(true)));
+///     let content = Flex::column()
+///         .with_child(Label::new("Clear the document?"))
+///         .with_child(buttons)
+///         .padding(10.0);
+///     Dialog::new(content, false).on_close(|_, clear, doc: &mut Document, _| {
+///         if clear {
+///             doc.text.clear();
+///         }
+///     })
+/// }
+///
+/// fn clear_button() -> impl Widget<Document> {
+///     Button::new("Clear").on_click(|ctx, _, _| {
+///         ctx.show_dialog(confirm_clear());
+///     })
+/// }
+/// ```
+///
+/// [`EventCtx::show_dialog`]: crate::EventCtx::show_dialog
+/// [`EventCtx::close_dialog`]: crate::EventCtx::close_dialog
+/// [`OverlayLayer`]: super::OverlayLayer
+/// [`on_close`]: Dialog::on_close
+pub struct Dialog<T, R> {
+    content: Box<dyn Widget<T>>,
+    cancel: R,
+    on_close: Option<Box<CloseFn<T, R>>>,
+    cancel_on_scrim_click: bool,
+    scrim_color: KeyOrValue<Color>,
+}
+
+impl<T: Data, R: Any> Dialog<T, R> {
+    /// Create a dialog showing `content`, that is closed with the result
+    /// `cancel` by escape and clicks on the scrim.
+    pub fn new(content: impl Widget<T> + 'static, cancel: R) -> Self {
+        Dialog {
+            content: Box::new(content),
+            cancel,
+            on_close: None,
+            cancel_on_scrim_click: true,
+            scrim_color: DEFAULT_SCRIM_COLOR.into(),
+        }
+    }
+
+    /// Builder-style method to set the callback that gets the result when the
+    /// dialog is closed.
+    ///
+    /// The callback isn't called if the popup of the dialog is closed with
+    /// [`CLOSE_POPUP`].
+    ///
+    /// [`CLOSE_POPUP`]: crate::commands::CLOSE_POPUP
+    pub fn on_close(
+        mut self,
+        on_close: impl FnOnce(&mut EventCtx, R, &mut T, &Env) + 'static,
+    ) -> Self {
+        self.on_close = Some(Box::new(on_close));
+        self
+    }
+
+    /// Builder-style method to set whether a click on the scrim closes the
+    /// dialog with the cancel result. This is `true` by default.
+    pub fn cancel_on_scrim_click(mut self, cancel: bool) -> Self {
+        self.cancel_on_scrim_click = cancel;
+        self
+    }
+
+    /// Builder-style method to set the color of the scrim covering the rest of
+    /// the window.
+    pub fn scrim_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.scrim_color = color.into();
+        self
+    }
+
+    /// The popup showing this dialog.
+    pub(crate) fn into_popup(self) -> Popup<T> {
+        let host = DialogHost {
+            content: WidgetPod::new(FocusGroup::new(self.content)),
+            cancel: Some(self.cancel),
+            on_close: self.on_close,
+            cancel_on_scrim_click: self.cancel_on_scrim_click,
+            scrim_color: self.scrim_color,
+        };
+        // The host fills the layer, so it is placed at the layer's origin.
+        Popup::new(Point::ZERO, host)
+            .z_index(i32::MAX)
+            .dismiss_on_click_outside(false)
+            .dismiss_on_escape(false)
+    }
+}
+
+/// The widget of the popup showing a [`Dialog`].
+struct DialogHost<T, R> {
+    content: WidgetPod<T, FocusGroup<T, Box<dyn Widget<T>>>>,
+    /// The cancel result, until the dialog is closed.
+    cancel: Option<R>,
+    on_close: Option<Box<CloseFn<T, R>>>,
+    cancel_on_scrim_click: bool,
+    scrim_color: KeyOrValue<Color>,
+}
+
+impl<T: Data, R: Any> DialogHost<T, R> {
+    /// Close the dialog with `result`, or the cancel result if `result` is
+    /// `None`, unless it is already closed.
+    fn close(&mut self, ctx: &mut EventCtx, result: Option<R>, data: &mut T, env: &Env) {
+        let cancel = match self.cancel.take() {
+            Some(cancel) => cancel,
+            None => return,
+        };
+        trace!("Closing dialog");
+        if let Some(on_close) = self.on_close.take() {
+            on_close(ctx, result.unwrap_or(cancel), data, env);
+        }
+        ctx.submit_notification(DISMISS_POPUP);
+    }
+}
+
+impl<T: Data, R: Any> Widget<T> for DialogHost<T, R> {
+    #[instrument(name = "Dialog", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Notification(note) if note.is(CLOSE_DIALOG) => {
+                let result = note
+                    .get(CLOSE_DIALOG)
+                    .and_then(|result| result.take())
+                    .map(|result| result.downcast::<R>());
+                match result {
+                    Some(Ok(result)) => self.close(ctx, Some(*result), data, env),
+                    _ => warn!(
+                        "{} must carry a result of the type of the dialog's cancel result.",
+                        CLOSE_DIALOG
+                    ),
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseDown(mouse) if !self.content.layout_rect().contains(mouse.pos) => {
+                if self.cancel_on_scrim_click {
+                    self.close(ctx, None, data, env);
+                }
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+        self.content.event(ctx, event, data, env);
+        if let Event::KeyDown(key) = event {
+            if key.key == KbKey::Escape && !ctx.is_handled() {
+                self.close(ctx, None, data, env);
+                ctx.set_handled();
+            }
+        }
+    }
+
+    #[instrument(name = "Dialog", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.content.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Dialog", level = "trace", skip(self, ctx, _old, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        if ctx.env_key_changed(&self.scrim_color) {
+            ctx.request_paint();
+        }
+        self.content.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Dialog", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Dialog");
+        let size = bc.max();
+        let content_size = self.content.layout(ctx, &bc.loosen(), data, env);
+        let origin = ((size - content_size).to_vec2() / 2.0).to_point();
+        self.content.set_origin(ctx, data, env, origin);
+        size
+    }
+
+    #[instrument(name = "Dialog", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let scrim = ctx.size().to_rect();
+        ctx.fill(scrim, &self.scrim_color.resolve(env));
+        let rect = self
+            .content
+            .layout_rect()
+            .to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
+        ctx.fill(rect, &env.get(theme::WINDOW_BACKGROUND_COLOR));
+        ctx.stroke(rect, &env.get(theme::BORDER_DARK), 1.0);
+        self.content.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "Dialog".to_string(),
+            children: vec![self.content.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{press_key, widget_ids, ModularWidget};
+    use crate::tests::move_mouse;
+    use crate::widget::{Flex, OverlayLayer, SizedBox, WidgetExt};
+    use crate::Selector;
+    use test_log::test;
+
+    const OPEN: Selector = Selector::new("druid-test.open-dialog");
+
+    /// A focusable widget that moves the focus on tab, and closes its dialog
+    /// on enter.
+    fn button(id: WidgetId) -> impl Widget<Option<bool>> {
+        ModularWidget::new(())
+            .event_fn(|_, ctx, event, _, _| match event {
+                Event::KeyDown(key) if key.key == KbKey::Tab => ctx.focus_next(),
+                Event::KeyDown(key) if key.key == KbKey::Enter => ctx.close_dialog(true),
+                _ => (),
+            })
+            .lifecycle_fn(|_, ctx, event, _, _| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .layout_fn(|_, _, _, _, _| Size::new(40.0, 20.0))
+            .with_id(id)
+    }
+
+    /// A window with a widget that takes the focus and opens a dialog with
+    /// two buttons on `OPEN`, which stores the result in the data.
+    fn window(opener: WidgetId, a: WidgetId, b: WidgetId) -> impl Widget<Option<bool>> {
+        let opener = ModularWidget::new(())
+            .event_fn(move |_, ctx, event, _, _| match event {
+                Event::Command(cmd) if cmd.is(OPEN) => {
+                    ctx.request_focus();
+                    let content = Flex::row().with_child(button(a)).with_child(button(b));
+                    ctx.show_dialog(
+                        Dialog::new(content, false)
+                            .on_close(|_, result, data: &mut Option<bool>, _| *data = Some(result)),
+                    );
+                }
+                Event::KeyDown(key) if key.key == KbKey::Tab => ctx.focus_next(),
+                _ => (),
+            })
+            .lifecycle_fn(|_, ctx, event, _, _| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .with_id(opener);
+        OverlayLayer::new(
+            Flex::column()
+                .with_child(opener)
+                .with_child(SizedBox::empty()),
+        )
+    }
+
+    fn dialog_open(harness: &Harness<Option<bool>>) -> bool {
+        harness.get_root_debug_state().children.len() > 1
+    }
+
+    #[test]
+    fn escape_and_scrim_cancel() {
+        let [opener, a, b] = widget_ids();
+        Harness::create_simple(None, window(opener, a, b), |harness| {
+            harness.send_initial_events();
+            harness.submit_command(OPEN);
+            harness.just_layout();
+            assert!(dialog_open(harness));
+
+            // The focus stays in the dialog.
+            assert_eq!(harness.window().focus, Some(a));
+            press_key(harness, KbKey::Tab);
+            assert_eq!(harness.window().focus, Some(b));
+            press_key(harness, KbKey::Tab);
+            assert_eq!(harness.window().focus, Some(a));
+
+            press_key(harness, KbKey::Escape);
+            assert!(!dialog_open(harness));
+            assert_eq!(*harness.data(), Some(false));
+            assert_eq!(harness.window().focus, Some(opener));
+
+            harness.update_data(|data| *data = None);
+            harness.submit_command(OPEN);
+            harness.just_layout();
+            harness.event(Event::MouseDown(move_mouse((1.0, 1.0))));
+            assert!(!dialog_open(harness));
+            assert_eq!(*harness.data(), Some(false));
+            assert_eq!(harness.window().focus, Some(opener));
+        });
+    }
+
+    #[test]
+    fn close_with_result() {
+        let [opener, a, b] = widget_ids();
+        Harness::create_simple(None, window(opener, a, b), |harness| {
+            harness.send_initial_events();
+            harness.submit_command(OPEN);
+            harness.just_layout();
+
+            // Clicks on the dialog don't close it.
+            harness.event(Event::MouseDown(move_mouse((170.0, 200.0))));
+            assert!(dialog_open(harness));
+
+            press_key(harness, KbKey::Enter);
+            assert!(!dialog_open(harness));
+            assert_eq!(*harness.data(), Some(true));
+            assert_eq!(harness.window().focus, Some(opener));
+        });
+    }
+}
@@ -0,0 +1,526 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows the differences between two texts.
+
+use std::marker::PhantomData;
+
+use tracing::instrument;
+
+use super::code_view::CodeText;
+use crate::debug_state::DebugState;
+use crate::piet::RenderContext;
+use crate::widget::prelude::*;
+use crate::widget::{PlainText, Scroll};
+use crate::{theme, ArcStr, Color, Key, Point, Rect, Vec2, WidgetPod};
+
+/// The width of the line between the panes of a side-by-side diff.
+const DIVIDER_WIDTH: f64 = 1.0;
+
+/// How a [`DiffView`] arranges the two texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLayout {
+    /// A single pane, with the lines that were removed, marked with `-`,
+    /// before the lines that were added in their place, marked with `+`.
+    Unified,
+    /// The old text on the left and the new text on the right, with the
+    /// unchanged lines next to each other. The two panes scroll together.
+    SideBySide,
+}
+
+/// A widget that shows the differences between two texts, line by line.
+///
+/// The data is a pair of the old and the new text; a tuple of lenses is a
+/// convenient way to get it. Added lines are drawn on the
+/// [`DIFF_ADDED_BACKGROUND_COLOR`], and removed lines on the
+/// [`DIFF_REMOVED_BACKGROUND_COLOR`], in the [`CODE_FONT`].
+///
+/// The diff is computed from the longest common subsequence of the lines
+/// that differ, which needs memory proportional to the product of their
+/// counts; lines that are the same at the start and the end of both texts
+/// are cheap.
+///
+/// Like a [`CodeView`], the panes scroll in both directions, and the text
+/// can be selected and copied.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{DiffLayout, DiffView};
+/// use druid::{ArcStr, Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Review {
+///     old: ArcStr,
+///     new: ArcStr,
+/// }
+///
+/// fn review() -> impl Widget<Review> {
+///     DiffView::new()
+///         .with_layout(DiffLayout::SideBySide)
+///         .lens((Review::old, Review::new))
+/// }
+/// ```
+///
+/// [`DIFF_ADDED_BACKGROUND_COLOR`]: theme::DIFF_ADDED_BACKGROUND_COLOR
+/// [`DIFF_REMOVED_BACKGROUND_COLOR`]: theme::DIFF_REMOVED_BACKGROUND_COLOR
+/// [`CODE_FONT`]: theme::CODE_FONT
+/// [`CodeView`]: super::CodeView
+pub struct DiffView<T> {
+    layout: DiffLayout,
+    /// One pane for a unified diff, or the old and the new pane side by side.
+    panes: Vec<Pane>,
+    phantom: PhantomData<T>,
+}
+
+/// One scrolled text of a [`DiffView`].
+struct Pane {
+    text: ArcStr,
+    scroll: WidgetPod<ArcStr, Scroll<ArcStr, CodeText<PlainText>>>,
+    /// The offset after the last event, to find out which pane was scrolled.
+    offset: Vec2,
+}
+
+/// A line of a line diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    /// A line of both texts, with its index in the old and in the new text.
+    Same(usize, usize),
+    /// A line of the old text.
+    Removed(usize),
+    /// A line of the new text.
+    Added(usize),
+}
+
+impl<T: Data + AsRef<str>> DiffView<T> {
+    /// Create a new unified `DiffView`.
+    pub fn new() -> Self {
+        DiffView {
+            layout: DiffLayout::Unified,
+            panes: vec![Pane::new()],
+            phantom: PhantomData,
+        }
+    }
+
+    /// Builder-style method for choosing how the texts are arranged.
+    pub fn with_layout(mut self, layout: DiffLayout) -> Self {
+        self.layout = layout;
+        let panes = match layout {
+            DiffLayout::Unified => 1,
+            DiffLayout::SideBySide => 2,
+        };
+        self.panes = (0..panes).map(|_| Pane::new()).collect();
+        self
+    }
+
+    /// Returns the selected text, if any.
+    pub fn selected_text(&self) -> Option<String> {
+        self.panes
+            .iter()
+            .find_map(|pane| pane.scroll.widget().child().selected_text())
+    }
+
+    /// Compute the diff, and the text of the panes.
+    fn set_texts(&mut self, old: &str, new: &str) {
+        let old = lines(old);
+        let new = lines(new);
+        let changes = diff_lines(&old, &new);
+        match self.layout {
+            DiffLayout::Unified => {
+                let mut text = Vec::with_capacity(changes.len());
+                let mut backgrounds = Vec::with_capacity(changes.len());
+                for change in changes {
+                    let (line, background) = match change {
+                        Change::Same(i, _) => (format!("  {}", old[i]), None),
+                        Change::Removed(i) => (
+                            format!("- {}", old[i]),
+                            Some(theme::DIFF_REMOVED_BACKGROUND_COLOR),
+                        ),
+                        Change::Added(j) => (
+                            format!("+ {}", new[j]),
+                            Some(theme::DIFF_ADDED_BACKGROUND_COLOR),
+                        ),
+                    };
+                    text.push(line);
+                    backgrounds.push(background);
+                }
+                self.panes[0].set_lines(&text, backgrounds);
+            }
+            DiffLayout::SideBySide => {
+                let mut left = Side::new(&old, theme::DIFF_REMOVED_BACKGROUND_COLOR);
+                let mut right = Side::new(&new, theme::DIFF_ADDED_BACKGROUND_COLOR);
+                // The removed and added lines since the last unchanged one.
+                let mut removed = Vec::new();
+                let mut added = Vec::new();
+                for change in changes.into_iter().map(Some).chain(Some(None)) {
+                    match change {
+                        Some(Change::Removed(i)) => removed.push(i),
+                        Some(Change::Added(j)) => added.push(j),
+                        _ => {
+                            // Put the replaced lines next to their replacements, and fill
+                            // the shorter side with blank lines.
+                            let rows = removed.len().max(added.len());
+                            left.push_changed(&removed, rows);
+                            right.push_changed(&added, rows);
+                            removed.clear();
+                            added.clear();
+                            if let Some(Change::Same(i, j)) = change {
+                                left.push(i, None);
+                                right.push(j, None);
+                            }
+                        }
+                    }
+                }
+                if let [left_pane, right_pane] = &mut self.panes[..] {
+                    left_pane.set_lines(&left.text, left.backgrounds);
+                    right_pane.set_lines(&right.text, right.backgrounds);
+                }
+            }
+        }
+    }
+
+    /// Scroll the other pane of a side-by-side diff to where one of them was scrolled.
+    fn sync_offsets(&mut self, ctx: &mut EventCtx) {
+        if let [left, right] = &mut self.panes[..] {
+            let (left_offset, right_offset) = (left.current_offset(), right.current_offset());
+            let moved = if left_offset != left.offset {
+                right
+                    .scroll
+                    .widget_mut()
+                    .scroll_by(left_offset - right_offset)
+            } else if right_offset != right.offset {
+                left.scroll
+                    .widget_mut()
+                    .scroll_by(right_offset - left_offset)
+            } else {
+                false
+            };
+            if moved {
+                ctx.request_paint();
+            }
+            left.offset = left.current_offset();
+            right.offset = right.current_offset();
+        }
+    }
+}
+
+impl<T: Data + AsRef<str>> Default for DiffView<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pane {
+    fn new() -> Self {
+        let text = CodeText::new(String::new(), PlainText);
+        Pane {
+            text: "".into(),
+            scroll: WidgetPod::new(Scroll::new(text).content_must_fill(true)),
+            offset: Vec2::ZERO,
+        }
+    }
+
+    fn set_lines(&mut self, lines: &[String], backgrounds: Vec<Option<Key<Color>>>) {
+        self.text = lines.join("\n").into();
+        self.scroll
+            .widget_mut()
+            .child_mut()
+            .set_backgrounds(backgrounds);
+    }
+
+    fn current_offset(&self) -> Vec2 {
+        self.scroll.widget().offset()
+    }
+}
+
+/// The lines of one side of a side-by-side diff, as they are built.
+struct Side<'a> {
+    lines: &'a [&'a str],
+    changed: Key<Color>,
+    text: Vec<String>,
+    backgrounds: Vec<Option<Key<Color>>>,
+}
+
+impl<'a> Side<'a> {
+    fn new(lines: &'a [&'a str], changed: Key<Color>) -> Self {
+        Side {
+            lines,
+            changed,
+            text: Vec::new(),
+            backgrounds: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, line: usize, background: Option<Key<Color>>) {
+        self.text.push(self.lines[line].to_string());
+        self.backgrounds.push(background);
+    }
+
+    /// Add the changed `lines`, and blank lines up to `rows` lines.
+    fn push_changed(&mut self, lines: &[usize], rows: usize) {
+        for &line in lines {
+            self.push(line, Some(self.changed.clone()));
+        }
+        for _ in lines.len()..rows {
+            self.text.push(String::new());
+            self.backgrounds.push(None);
+        }
+    }
+}
+
+/// Split text into lines, the same way a [`CodeView`] does.
+///
+/// [`CodeView`]: super::CodeView
+fn lines(text: &str) -> Vec<&str> {
+    text.split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect()
+}
+
+/// Find the changes that turn `old` into `new`.
+///
+/// Within a run of changes, the removed lines come before the added ones.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Change> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_changed = &old[prefix..old.len() - suffix];
+    let new_changed = &new[prefix..new.len() - suffix];
+
+    // `lcs[i * width + j]` is the length of the longest common subsequence of
+    // `old_changed[i..]` and `new_changed[j..]`.
+    let width = new_changed.len() + 1;
+    let mut lcs = vec![0u32; (old_changed.len() + 1) * width];
+    for i in (0..old_changed.len()).rev() {
+        for j in (0..new_changed.len()).rev() {
+            lcs[i * width + j] = if old_changed[i] == new_changed[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut changes: Vec<Change> = (0..prefix).map(|i| Change::Same(i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_changed.len() || j < new_changed.len() {
+        if i < old_changed.len() && j < new_changed.len() && old_changed[i] == new_changed[j] {
+            changes.push(Change::Same(prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if j == new_changed.len()
+            || (i < old_changed.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+        {
+            changes.push(Change::Removed(prefix + i));
+            i += 1;
+        } else {
+            changes.push(Change::Added(prefix + j));
+            j += 1;
+        }
+    }
+    let (old_suffix, new_suffix) = (old.len() - suffix, new.len() - suffix);
+    changes.extend((0..suffix).map(|k| Change::Same(old_suffix + k, new_suffix + k)));
+    changes
+}
+
+impl<T: Data + AsRef<str>> Widget<(T, T)> for DiffView<T> {
+    #[instrument(name = "DiffView", level = "trace", skip(self, ctx, event, _data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut (T, T), env: &Env) {
+        for pane in &mut self.panes {
+            pane.scroll.event(ctx, event, &mut pane.text, env);
+        }
+        self.sync_offsets(ctx);
+    }
+
+    #[instrument(name = "DiffView", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &(T, T), env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.set_texts(data.0.as_ref(), data.1.as_ref());
+        }
+        for pane in &mut self.panes {
+            pane.scroll.lifecycle(ctx, event, &pane.text, env);
+        }
+    }
+
+    #[instrument(
+        name = "DiffView",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &(T, T), data: &(T, T), env: &Env) {
+        if !old_data.same(data) {
+            self.set_texts(data.0.as_ref(), data.1.as_ref());
+            ctx.request_layout();
+        }
+        if ctx.env_key_changed(&theme::DIFF_ADDED_BACKGROUND_COLOR)
+            || ctx.env_key_changed(&theme::DIFF_REMOVED_BACKGROUND_COLOR)
+        {
+            ctx.request_paint();
+        }
+        for pane in &mut self.panes {
+            pane.scroll.update(ctx, &pane.text, env);
+        }
+    }
+
+    #[instrument(name = "DiffView", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &(T, T),
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("DiffView");
+        let panes = self.panes.len() as f64;
+        let dividers = (panes - 1.0) * DIVIDER_WIDTH;
+        let pane_width = |width: f64| ((width - dividers) / panes).max(0.0);
+        let pane_bc = BoxConstraints::new(
+            Size::new(pane_width(bc.min().width), bc.min().height),
+            Size::new(pane_width(bc.max().width), bc.max().height),
+        );
+
+        let mut x = 0.0;
+        let mut height: f64 = 0.0;
+        for pane in &mut self.panes {
+            let size = pane.scroll.layout(ctx, &pane_bc, &pane.text, env);
+            pane.scroll
+                .set_origin(ctx, &pane.text, env, Point::new(x, 0.0));
+            x += size.width + DIVIDER_WIDTH;
+            height = height.max(size.height);
+        }
+        bc.constrain(Size::new(x - DIVIDER_WIDTH, height))
+    }
+
+    #[instrument(name = "DiffView", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &(T, T), env: &Env) {
+        let height = ctx.size().height;
+        let divider_color = env.get(theme::BORDER_DARK);
+        for (i, pane) in self.panes.iter_mut().enumerate() {
+            if i > 0 {
+                let x = pane.scroll.layout_rect().x0;
+                let divider = Rect::new(x - DIVIDER_WIDTH, 0.0, x, height);
+                ctx.fill(divider, &divider_color);
+            }
+            pane.scroll.paint(ctx, &pane.text, env);
+        }
+    }
+
+    fn debug_state(&self, _data: &(T, T)) -> DebugState {
+        let children = self
+            .panes
+            .iter()
+            .map(|pane| {
+                let mut state = DebugState {
+                    display_name: "DiffPane".to_string(),
+                    main_value: pane.text.to_string(),
+                    ..Default::default()
+                };
+                let offset = pane.current_offset();
+                state
+                    .other_values
+                    .insert("offset".into(), format!("{},{}", offset.x, offset.y));
+                if let Some(selection) = pane.scroll.widget().child().selected_text() {
+                    state.other_values.insert("selection".into(), selection);
+                }
+                state
+            })
+            .collect();
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::scroll_mouse;
+    use test_log::test;
+
+    #[test]
+    fn diff() {
+        use Change::*;
+        let old = ["a", "b", "c", "d", "e"];
+        let new = ["a", "c", "x", "d", "y", "e"];
+        assert_eq!(
+            diff_lines(&old, &new),
+            vec![
+                Same(0, 0),
+                Removed(1),
+                Same(2, 1),
+                Added(2),
+                Same(3, 3),
+                Added(4),
+                Same(4, 5)
+            ]
+        );
+        assert_eq!(diff_lines(&["a"], &["b"]), vec![Removed(0), Added(0)]);
+        assert_eq!(diff_lines(&[], &["b"]), vec![Added(0)]);
+    }
+
+    #[test]
+    fn unified() {
+        let texts = (ArcStr::from("a\nb\nc"), ArcStr::from("a\nB\nc\nd"));
+        Harness::create_simple(texts, DiffView::new(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            let state = harness.root_debug_state();
+            assert_eq!(state.children.len(), 1);
+            assert_eq!(state.children[0].main_value, "  a\n- b\n+ B\n  c\n+ d");
+        });
+    }
+
+    #[test]
+    fn side_by_side() {
+        let old: Vec<String> = (0..100).map(|i| format!("line {}", i)).collect();
+        let mut new = old.clone();
+        new[1] = "changed".to_string();
+        new.insert(3, "one".to_string());
+        new.insert(3, "two".to_string());
+        let texts = (ArcStr::from(old.join("\n")), ArcStr::from(new.join("\n")));
+        let widget = DiffView::new().with_layout(DiffLayout::SideBySide);
+        let offsets = |harness: &mut Harness<(ArcStr, ArcStr)>| {
+            let state = harness.root_debug_state();
+            let offset = |pane: &DebugState| pane.other_values["offset"].clone();
+            (offset(&state.children[0]), offset(&state.children[1]))
+        };
+
+        Harness::create_simple(texts, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+
+            let state = harness.root_debug_state();
+            let left: Vec<&str> = state.children[0].main_value.split('\n').collect();
+            let right: Vec<&str> = state.children[1].main_value.split('\n').collect();
+            assert_eq!(left.len(), right.len());
+            assert_eq!(left[..5], ["line 0", "line 1", "line 2", "", ""]);
+            assert_eq!(right[..5], ["line 0", "changed", "line 2", "two", "one"]);
+
+            // Scrolling either pane scrolls the other one too.
+            harness.event(Event::Wheel(scroll_mouse((100., 100.), (0., 120.))));
+            assert_eq!(offsets(harness), ("0,120".into(), "0,120".into()));
+            harness.event(Event::Wheel(scroll_mouse((300., 100.), (0., -20.))));
+            assert_eq!(offsets(harness), ("0,100".into(), "0,100".into()));
+        });
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A thin line that separates other widgets.
+
+use crate::kurbo::Vec2;
+use crate::widget::prelude::*;
+use crate::widget::Axis;
+use crate::{theme, Color, Data, KeyOrValue, Rect, Scale};
+use tracing::{instrument, trace};
+
+/// A horizontal or vertical line, used to visually separate other widgets.
+///
+/// A horizontal divider fills the width it is given and is only as high as
+/// its line and margin; a vertical divider does the same the other way
+/// around. A divider created with [`Divider::new`] picks its direction from
+/// its constraints: in a [`Flex::row`] it is vertical, and otherwise it is
+/// horizontal. If the direction can't be told from the constraints, for
+/// instance in a row that is itself in a column, use [`Divider::vertical`]
+/// or [`Divider::horizontal`] instead.
+///
+/// The line is aligned with the pixel grid of the window, and its thickness
+/// is rounded to a whole number of pixels, so that it stays crisp at
+/// fractional scale factors. It is never thinner than one pixel.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Divider, Flex, Label};
+/// use druid::{theme, Widget};
+///
+/// fn form() -> impl Widget<()> {
+///     Flex::column()
+///         .with_child(Label::new("Name"))
+///         .with_child(Divider::new().with_margin(4.0))
+///         .with_child(
+///             Flex::row()
+///                 .with_child(Label::new("Left"))
+///                 .with_child(Divider::vertical().with_color(theme::BORDER_LIGHT))
+///                 .with_child(Label::new("Right")),
+///         )
+/// }
+/// ```
+///
+/// [`Flex::row`]: crate::widget::Flex::row
+pub struct Divider {
+    axis: Option<Axis>,
+    thickness: KeyOrValue<f64>,
+    color: KeyOrValue<Color>,
+    inset: f64,
+    margin: f64,
+    /// The direction chosen during the last layout.
+    resolved_axis: Axis,
+}
+
+impl Divider {
+    /// Create a new divider that picks its direction from its constraints.
+    pub fn new() -> Self {
+        Divider {
+            axis: None,
+            thickness: 1.0.into(),
+            color: theme::DIVIDER_COLOR.into(),
+            inset: 0.0,
+            margin: 0.0,
+            resolved_axis: Axis::Horizontal,
+        }
+    }
+
+    /// Create a new horizontal divider.
+    pub fn horizontal() -> Self {
+        Divider {
+            axis: Some(Axis::Horizontal),
+            ..Divider::new()
+        }
+    }
+
+    /// Create a new vertical divider.
+    pub fn vertical() -> Self {
+        Divider {
+            axis: Some(Axis::Vertical),
+            ..Divider::new()
+        }
+    }
+
+    /// Builder-style method for setting the thickness of the line.
+    ///
+    /// The thickness is rounded to whole pixels when painting. The default
+    /// is `1.0`.
+    pub fn with_thickness(mut self, thickness: impl Into<KeyOrValue<f64>>) -> Self {
+        self.thickness = thickness.into();
+        self
+    }
+
+    /// Builder-style method for setting the color of the line.
+    ///
+    /// The argument can be either a `Color` or a [`Key<Color>`]. The default
+    /// is [`theme::DIVIDER_COLOR`].
+    ///
+    /// [`Key<Color>`]: crate::Key
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Builder-style method for shortening both ends of the line.
+    pub fn with_inset(mut self, inset: f64) -> Self {
+        self.inset = inset;
+        self
+    }
+
+    /// Builder-style method for adding space on both sides of the line.
+    pub fn with_margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Set the color of the line.
+    pub fn set_color(&mut self, color: impl Into<KeyOrValue<Color>>) {
+        self.color = color.into();
+    }
+
+    /// Set the thickness of the line.
+    pub fn set_thickness(&mut self, thickness: impl Into<KeyOrValue<f64>>) {
+        self.thickness = thickness.into();
+    }
+}
+
+impl Default for Divider {
+    fn default() -> Self {
+        Divider::new()
+    }
+}
+
+/// The direction of a divider without an explicit one: vertical if it is
+/// only unbounded horizontally, as in a row.
+fn axis_for_constraints(bc: &BoxConstraints) -> Axis {
+    if !bc.is_width_bounded() && bc.is_height_bounded() {
+        Axis::Vertical
+    } else {
+        Axis::Horizontal
+    }
+}
+
+/// Round `value`, in display points, to the nearest pixel boundary.
+fn snap(value: f64, scale: f64) -> f64 {
+    (value * scale).round() / scale
+}
+
+/// The rect of the line for a divider of `size`, whose origin is at `origin`
+/// in window coordinates.
+fn line_rect(
+    axis: Axis,
+    size: Size,
+    thickness: f64,
+    inset: f64,
+    origin: Vec2,
+    scale: Scale,
+) -> Rect {
+    let scale = Vec2::new(scale.x(), scale.y());
+    let major_scale = axis.major_vec(scale);
+    let minor_scale = axis.minor_vec(scale);
+    let major_origin = axis.major_vec(origin);
+    let minor_origin = axis.minor_vec(origin);
+
+    let thickness = (thickness * minor_scale).round().max(1.0) / minor_scale;
+    let minor_start = snap(
+        minor_origin + (axis.minor(size) - thickness) / 2.0,
+        minor_scale,
+    );
+    let major_start = snap(major_origin + inset, major_scale);
+    let major_end = snap(major_origin + axis.major(size) - inset, major_scale).max(major_start);
+
+    let (x0, y0) = axis.pack(major_start - major_origin, minor_start - minor_origin);
+    let (x1, y1) = axis.pack(
+        major_end - major_origin,
+        minor_start + thickness - minor_origin,
+    );
+    Rect::new(x0, y0, x1, y1)
+}
+
+impl<T: Data> Widget<T> for Divider {
+    #[instrument(
+        name = "Divider",
+        level = "trace",
+        skip(self, _ctx, _event, _data, _env)
+    )]
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+
+    #[instrument(
+        name = "Divider",
+        level = "trace",
+        skip(self, _ctx, _event, _data, _env)
+    )]
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &T, _env: &Env) {}
+
+    #[instrument(
+        name = "Divider",
+        level = "trace",
+        skip(self, ctx, _old_data, _data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {
+        if ctx.env_key_changed(&self.thickness) {
+            ctx.request_layout();
+        }
+        if ctx.env_key_changed(&self.color) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Divider", level = "trace", skip(self, _ctx, bc, _data, env))]
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("Divider");
+        let axis = self.axis.unwrap_or_else(|| axis_for_constraints(bc));
+        self.resolved_axis = axis;
+
+        let major = match axis {
+            Axis::Horizontal if bc.is_width_bounded() => bc.max().width,
+            Axis::Vertical if bc.is_height_bounded() => bc.max().height,
+            _ => axis.major(bc.min()),
+        };
+        let minor = self.thickness.resolve(env) + self.margin * 2.0;
+        let (width, height) = axis.pack(major, minor);
+        let size = bc.constrain((width, height));
+        trace!("Computed size: {} ({:?})", size, axis);
+        size
+    }
+
+    #[instrument(name = "Divider", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let scale = ctx.window().get_scale().unwrap_or_default();
+        let rect = line_rect(
+            self.resolved_axis,
+            ctx.size(),
+            self.thickness.resolve(env),
+            self.inset,
+            ctx.window_origin().to_vec2(),
+            scale,
+        );
+        let color = self.color.resolve(env);
+        ctx.fill(rect, &color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::widget::{Flex, SizedBox};
+    use crate::WidgetExt;
+    use test_log::test;
+
+    #[test]
+    fn line_is_snapped_to_pixels() {
+        let size = Size::new(100.0, 1.0);
+        let scale = Scale::new(1.5, 1.5);
+        let rect = line_rect(
+            Axis::Horizontal,
+            size,
+            1.0,
+            0.0,
+            Vec2::new(0.0, 10.1),
+            scale,
+        );
+        // One dp is 1.5 pixels, which is rounded to two pixels.
+        let top = (10.1 + rect.y0) * 1.5;
+        let bottom = (10.1 + rect.y1) * 1.5;
+        assert!((top - top.round()).abs() < 1e-9);
+        assert!((bottom - top - 2.0).abs() < 1e-9);
+        assert_eq!(rect.x0, 0.0);
+        assert_eq!(rect.x1, 100.0);
+
+        // Very thin lines are still one pixel thick.
+        let rect = line_rect(Axis::Vertical, size, 0.1, 0.0, Vec2::ZERO, Scale::default());
+        assert_eq!(rect.width(), 1.0);
+    }
+
+    #[test]
+    fn direction_follows_flex() {
+        let [column_id, row_id] = crate::tests::helpers::widget_ids();
+        let widget = Flex::column()
+            .with_child(WidgetExt::<()>::with_id(
+                Divider::new().with_margin(2.0),
+                column_id,
+            ))
+            .with_child(
+                SizedBox::new(Flex::row().with_child(WidgetExt::<()>::with_id(
+                    Divider::new().with_inset(4.0),
+                    row_id,
+                )))
+                .height(30.0),
+            );
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(column_id).layout_rect().size(),
+                Size::new(400.0, 5.0)
+            );
+            assert_eq!(
+                harness.get_state(row_id).layout_rect().size(),
+                Size::new(1.0, 30.0)
+            );
+        });
+    }
+}
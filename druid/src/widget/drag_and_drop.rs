@@ -0,0 +1,348 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Controllers for dragging values from one widget to another.
+
+use std::any::Any;
+use std::marker::PhantomData;
+
+use crate::widget::Controller;
+use crate::{Data, DragGhost, DragPayload, Env, Event, EventCtx, MouseButton, Point, Size, Widget};
+
+type PayloadFn<T> = dyn Fn(&T, &Env) -> DragPayload;
+type GhostFn<T> = dyn Fn(&T, Size, &Env) -> DragGhost;
+type DropFn<T, P> = dyn Fn(&mut EventCtx, &P, Point, &mut T, &Env);
+type AcceptsFn<T, P> = dyn Fn(&P, &T, &Env) -> bool;
+type LeaveFn<T> = dyn Fn(&mut EventCtx, &mut T, &Env);
+
+/// How far, in display points, the pointer has to move before a drag starts.
+const DEFAULT_THRESHOLD: f64 = 3.0;
+
+/// A [`Controller`] that lets the user drag a value out of its child.
+///
+/// When the child is pressed with the left mouse button and the pointer
+/// moves more than a few display points, the drag starts with the value
+/// returned by the payload closure. A [`DropTarget`] for the same type can
+/// then receive it.
+///
+/// This is also available as the `drag_source` method of [`WidgetExt`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{DragSource, DropTarget, Flex, Label};
+/// use druid::{Color, DragGhost, Size, WidgetExt};
+///
+/// let source = Label::new("Drag me").controller(
+///     DragSource::new(|data: &u32, _env| *data).with_ghost(|_data, size, _env| {
+///         DragGhost::new(size, move |ctx, _env| {
+///             use druid::RenderContext;
+///             let rect = size.to_rect();
+///             ctx.fill(rect, &Color::grey(0.5).with_alpha(0.5));
+///         })
+///     }),
+/// );
+/// let target = Label::new("Drop here").controller(
+///     DropTarget::new(|_ctx, value: &u32, _pos, data: &mut u32, _env| *data += *value)
+///         .on_hover(|ctx, _value, _pos, _data, _env| ctx.request_paint()),
+/// );
+/// let row = Flex::row().with_child(source).with_child(target);
+/// ```
+///
+/// [`Controller`]: crate::widget::Controller
+/// [`WidgetExt`]: crate::WidgetExt
+pub struct DragSource<T> {
+    payload: Box<PayloadFn<T>>,
+    ghost: Option<Box<GhostFn<T>>>,
+    threshold: f64,
+    /// Where the child was pressed, until the drag starts or the button is released.
+    press: Option<Point>,
+}
+
+/// A [`Controller`] that accepts values of type `P` dragged onto its child.
+///
+/// Dragged values of other types are ignored, so that nested targets for
+/// different types work as expected. If several nested targets accept a
+/// value, the innermost one gets it.
+///
+/// This is also available as the `drop_target` method of [`WidgetExt`].
+/// See [`DragSource`] for an example.
+///
+/// [`Controller`]: crate::widget::Controller
+/// [`WidgetExt`]: crate::WidgetExt
+pub struct DropTarget<T, P> {
+    on_drop: Box<DropFn<T, P>>,
+    on_hover: Option<Box<DropFn<T, P>>>,
+    on_leave: Option<Box<LeaveFn<T>>>,
+    accepts: Option<Box<AcceptsFn<T, P>>>,
+    hovered: bool,
+    phantom: PhantomData<P>,
+}
+
+impl<T: Data> DragSource<T> {
+    /// Create a new `DragSource`, dragging the value returned by `payload`.
+    pub fn new<P: Any>(payload: impl Fn(&T, &Env) -> P + 'static) -> Self {
+        DragSource {
+            payload: Box::new(move |data, env| DragPayload::new(payload(data, env))),
+            ghost: None,
+            threshold: DEFAULT_THRESHOLD,
+            press: None,
+        }
+    }
+
+    /// Builder-style method for setting the image that follows the pointer.
+    ///
+    /// The closure gets the size of the child, and is called when the drag
+    /// starts. The ghost is moved so that the point where the child was
+    /// pressed stays under the pointer.
+    pub fn with_ghost(mut self, ghost: impl Fn(&T, Size, &Env) -> DragGhost + 'static) -> Self {
+        self.ghost = Some(Box::new(ghost));
+        self
+    }
+
+    /// Builder-style method for setting how far the pointer has to move,
+    /// in display points, before the drag starts.
+    ///
+    /// The default is `3.0`.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for DragSource<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left && !ctx.is_disabled() => {
+                self.press = Some(mouse.pos);
+                ctx.set_active(true);
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                if let Some(press) = self.press {
+                    if (mouse.pos - press).hypot() > self.threshold {
+                        // Only one drag per press; the source stays active until the
+                        // button is released, even if the drag is cancelled.
+                        self.press = None;
+                        let payload = (self.payload)(data, env);
+                        let ghost = self.ghost.as_ref().map(|ghost| {
+                            ghost(data, ctx.size(), env).with_offset(-press.to_vec2())
+                        });
+                        ctx.start_drag(payload, ghost);
+                    }
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left && ctx.is_active() => {
+                self.press = None;
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+        child.event(ctx, event, data, env);
+    }
+}
+
+impl<T: Data, P: Any> DropTarget<T, P> {
+    /// Create a new `DropTarget`, calling `on_drop` when a `P` is dropped.
+    ///
+    /// The closure gets the dropped value and the position of the pointer,
+    /// in the coordinate space of the child.
+    pub fn new(on_drop: impl Fn(&mut EventCtx, &P, Point, &mut T, &Env) + 'static) -> Self {
+        DropTarget {
+            on_drop: Box::new(on_drop),
+            on_hover: None,
+            on_leave: None,
+            accepts: None,
+            hovered: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Builder-style method for choosing which values are accepted.
+    ///
+    /// By default, every `P` is accepted.
+    pub fn accepts(mut self, accepts: impl Fn(&P, &T, &Env) -> bool + 'static) -> Self {
+        self.accepts = Some(Box::new(accepts));
+        self
+    }
+
+    /// Builder-style method for reacting to an accepted value that is dragged
+    /// over the child.
+    ///
+    /// The closure is called whenever the pointer moves, with the same
+    /// arguments as the one passed to [`new`](DropTarget::new).
+    pub fn on_hover(
+        mut self,
+        on_hover: impl Fn(&mut EventCtx, &P, Point, &mut T, &Env) + 'static,
+    ) -> Self {
+        self.on_hover = Some(Box::new(on_hover));
+        self
+    }
+
+    /// Builder-style method for reacting to the end of a hover.
+    ///
+    /// The closure is called when the value leaves the child, when a nested
+    /// target takes it over, and when the drag is cancelled. It is not called
+    /// after a drop.
+    pub fn on_leave(mut self, on_leave: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        self.on_leave = Some(Box::new(on_leave));
+        self
+    }
+
+    /// Returns `true` if an accepted value is currently dragged over the child.
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    fn accepted<'a>(&self, payload: &'a DragPayload, data: &T, env: &Env) -> Option<&'a P> {
+        let value = payload.get::<P>()?;
+        match &self.accepts {
+            Some(accepts) if !accepts(value, data, env) => None,
+            _ => Some(value),
+        }
+    }
+
+    fn leave(&mut self, ctx: &mut EventCtx, data: &mut T, env: &Env) {
+        if self.hovered {
+            self.hovered = false;
+            if let Some(on_leave) = &self.on_leave {
+                on_leave(ctx, data, env);
+            }
+        }
+    }
+}
+
+impl<T: Data, P: Any, W: Widget<T>> Controller<T, W> for DropTarget<T, P> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        child.event(ctx, event, data, env);
+
+        match event {
+            Event::DragEnter(drag) | Event::DragOver(drag) => {
+                if ctx.is_handled() {
+                    self.leave(ctx, data, env);
+                } else if let Some(value) = self.accepted(&drag.payload, data, env) {
+                    self.hovered = true;
+                    if let Some(on_hover) = &self.on_hover {
+                        on_hover(ctx, value, drag.pos, data, env);
+                    }
+                    ctx.set_handled();
+                } else {
+                    self.leave(ctx, data, env);
+                }
+            }
+            Event::DragLeave => self.leave(ctx, data, env),
+            Event::Drop(drag) => {
+                self.hovered = false;
+                if ctx.is_handled() {
+                    return;
+                }
+                if let Some(value) = self.accepted(&drag.payload, data, env) {
+                    (self.on_drop)(ctx, value, drag.pos, data, env);
+                    ctx.set_handled();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{CrossAxisAlignment, Flex, SizedBox};
+    use crate::{KbKey, KeyEvent, Modifiers, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    fn press(pos: impl Into<Point>) -> MouseEvent {
+        MouseEvent {
+            button: MouseButton::Left,
+            count: 1,
+            ..move_mouse(pos)
+        }
+    }
+
+    #[test]
+    fn drag_to_target() {
+        let ghost_paints = Rc::new(Cell::new(0));
+        let paints = ghost_paints.clone();
+        let source = SizedBox::empty().fix_size(100., 100.).controller(
+            DragSource::new(|_: &String, _| 7u32).with_ghost(move |_, size, _| {
+                let paints = paints.clone();
+                DragGhost::new(size, move |_, _| paints.set(paints.get() + 1))
+            }),
+        );
+        let target = SizedBox::empty().fix_size(100., 100.).controller(
+            DropTarget::new(|_, value: &u32, pos, data: &mut String, _| {
+                data.push_str(&format!("drop {} {} {};", value, pos.x, pos.y))
+            })
+            .on_hover(|_, _, pos, data: &mut String, _| {
+                data.push_str(&format!("hover {} {};", pos.x, pos.y))
+            })
+            .on_leave(|_, data: &mut String, _| data.push_str("leave;")),
+        );
+        let other = SizedBox::empty()
+            .fix_size(100., 100.)
+            .drop_target(|_, _: &&str, _, data: &mut String, _| data.push_str("wrong;"));
+        let widget = Flex::row()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(source)
+            .with_child(target)
+            .with_child(other);
+
+        Harness::create_simple(String::new(), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.event(Event::MouseDown(press((50., 50.))));
+            harness.event(Event::MouseMove(move_mouse((52., 50.))));
+            harness.paint();
+            assert_eq!(ghost_paints.get(), 0, "moved less than the threshold");
+
+            harness.event(Event::MouseMove(move_mouse((60., 50.))));
+            harness.event(Event::MouseMove(move_mouse((150., 40.))));
+            harness.paint();
+            assert_eq!(ghost_paints.get(), 1);
+            assert_eq!(harness.data(), "hover 50 40;");
+
+            harness.event(Event::MouseMove(move_mouse((250., 50.))));
+            harness.event(Event::MouseMove(move_mouse((150., 50.))));
+            assert_eq!(harness.data(), "hover 50 40;leave;hover 50 50;");
+
+            // Escape cancels the drag, and the source doesn't start another one.
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::default(),
+                KbKey::Escape,
+            )));
+            harness.event(Event::MouseMove(move_mouse((160., 50.))));
+            harness.event(Event::MouseUp(press((160., 50.))));
+            assert_eq!(harness.data(), "hover 50 40;leave;hover 50 50;leave;");
+            harness.paint();
+            assert_eq!(ghost_paints.get(), 1);
+
+            harness.update_data(|data| data.clear());
+            harness.event(Event::MouseDown(press((50., 50.))));
+            harness.event(Event::MouseMove(move_mouse((150., 60.))));
+            harness.event(Event::MouseUp(press((150., 60.))));
+            assert_eq!(harness.data(), "hover 50 60;drop 7 50 60;");
+
+            // The drag is over, so this is an ordinary mouse move.
+            harness.event(Event::MouseMove(move_mouse((250., 60.))));
+            assert_eq!(harness.data(), "hover 50 60;drop 7 50 60;");
+        });
+    }
+}
@@ -15,6 +15,7 @@
 //! A widget that accepts a closure to update the environment for its child.
 
 use crate::debug_state::DebugState;
+use crate::theme::Theme;
 use crate::widget::prelude::*;
 use crate::widget::WidgetWrapper;
 use crate::{Data, Point, WidgetPod};
@@ -56,6 +57,33 @@ impl<T, W: Widget<T>> EnvScope<T, W> {
             child: WidgetPod::new(child),
         }
     }
+
+    /// Create a widget that sets the values of a [`Theme`] for its descendants.
+    ///
+    /// The type of each value is checked against its [`Key`] when the theme
+    /// is built, so this is a less error-prone way to override many keys.
+    ///
+    /// This is available as [`WidgetExt::env_scope_typed`] for convenience.
+    ///
+    /// # Examples
+    /// ```
+    /// # use druid::{theme, Widget};
+    /// # use druid::piet::Color;
+    /// # use druid::theme::Theme;
+    /// # use druid::widget::{Label, EnvScope};
+    /// # fn build_widget() -> impl Widget<String> {
+    /// let overrides = Theme::empty()
+    ///     .with(theme::TEXT_COLOR, Color::WHITE)
+    ///     .with(theme::TEXT_SIZE_NORMAL, 18.0);
+    /// EnvScope::with_theme(overrides, Label::new("Large white text!"))
+    /// # }
+    /// ```
+    ///
+    /// [`Key`]: crate::Key
+    /// [`WidgetExt::env_scope_typed`]: crate::WidgetExt::env_scope_typed
+    pub fn with_theme(theme: Theme, child: W) -> EnvScope<T, W> {
+        EnvScope::new(move |env, _| theme.apply(env), child)
+    }
 }
 
 impl<T: Data, W: Widget<T>> Widget<T> for EnvScope<T, W> {
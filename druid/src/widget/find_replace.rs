@@ -0,0 +1,468 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A text box that searches its text, and a controller for the search field.
+
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::Arc;
+
+use tracing::instrument;
+
+use crate::debug_state::DebugState;
+use crate::text::{EditableText, FindError, FindQuery, Finder, ImeInvalidation, TextStorage};
+use crate::widget::prelude::*;
+use crate::widget::{Controller, TextBox};
+use crate::{Data, HotKey, KbKey, Lens, RawMods, Target};
+
+/// A [`TextBox`] that highlights the matches of a [`FindQuery`], and can step
+/// through and replace them.
+///
+/// The text and the query both live in the app data, and are selected with
+/// two lenses. Whenever either changes, the matches are searched again. When
+/// the query changes, the first match after the cursor is selected and
+/// scrolled into view, as in the search bar of a browser.
+///
+/// This widget handles these commands:
+///
+/// - [`TextBox::FIND_NEXT`] and [`TextBox::FIND_PREVIOUS`] select the next
+///   or previous match, wrapping around at the end of the text.
+/// - [`TextBox::REPLACE`] replaces the current match with the replacement
+///   of the query, and selects the next match.
+/// - [`TextBox::REPLACE_ALL`] replaces every match.
+///
+/// Replacements can be undone like any other edit.
+///
+/// The query is usually edited in other text boxes; wrap the search field
+/// in a [`FindFieldController`] to step through the matches with
+/// <kbd>Enter</kbd> and <kbd>Shift</kbd>+<kbd>Enter</kbd>.
+///
+/// Create one with [`TextBox::with_find`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::text::FindQuery;
+/// use druid::widget::{Button, FindFieldController, Flex, TextBox};
+/// use druid::{Data, Lens, LensExt, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Document {
+///     text: String,
+///     find: FindQuery,
+/// }
+///
+/// fn editor() -> impl Widget<Document> {
+///     let search = TextBox::new()
+///         .with_placeholder("Find")
+///         .controller(FindFieldController::new())
+///         .lens(Document::find.then(FindQuery::query));
+///     let replace_all = Button::new("Replace all")
+///         .on_click(|ctx, _, _| ctx.submit_command(TextBox::REPLACE_ALL));
+///     Flex::column()
+///         .with_child(Flex::row().with_child(search).with_child(replace_all))
+///         .with_flex_child(
+///             TextBox::multiline().with_find(Document::text, Document::find),
+///             1.0,
+///         )
+/// }
+/// ```
+pub struct FindReplace<T, U, LT, LQ> {
+    textbox: TextBox<U>,
+    text: LT,
+    query: LQ,
+    finder: Result<Finder, FindError>,
+    matches: Arc<Vec<Range<usize>>>,
+    current: Option<usize>,
+    /// After a replacement, the position at which to look for the next match.
+    replaced_until: Option<usize>,
+    phantom: PhantomData<T>,
+}
+
+/// A [`Controller`] for a search field, that sends [`TextBox::FIND_NEXT`]
+/// when <kbd>Enter</kbd> is pressed and [`TextBox::FIND_PREVIOUS`] when
+/// <kbd>Shift</kbd>+<kbd>Enter</kbd> is pressed.
+///
+/// By default the commands are sent to every widget in the window; use
+/// [`with_target`] to send them to a single [`FindReplace`].
+///
+/// [`with_target`]: FindFieldController::with_target
+#[derive(Debug, Default)]
+pub struct FindFieldController {
+    target: Option<WidgetId>,
+}
+
+impl<T, U, LT, LQ> FindReplace<T, U, LT, LQ> {
+    /// Search the text of `textbox`.
+    ///
+    /// `text` selects the text of the text box, and `query` what to search for.
+    pub fn new(textbox: TextBox<U>, text: LT, query: LQ) -> Self {
+        FindReplace {
+            textbox,
+            text,
+            query,
+            finder: FindQuery::default().compile(),
+            matches: Arc::new(Vec::new()),
+            current: None,
+            replaced_until: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// A reference to the inner [`TextBox`].
+    pub fn textbox(&self) -> &TextBox<U> {
+        &self.textbox
+    }
+
+    /// A mutable reference to the inner [`TextBox`].
+    pub fn textbox_mut(&mut self) -> &mut TextBox<U> {
+        &mut self.textbox
+    }
+
+    /// The ranges of all matches of the query, in order.
+    pub fn matches(&self) -> &Arc<Vec<Range<usize>>> {
+        &self.matches
+    }
+
+    /// The index in [`matches`] of the selected match, if any.
+    ///
+    /// [`matches`]: FindReplace::matches
+    pub fn current_match(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Why the query can't be searched for, such as an invalid regular expression.
+    pub fn error(&self) -> Option<&FindError> {
+        self.finder.as_ref().err()
+    }
+
+    /// The first match at or after `pos`, wrapping around to the first match.
+    ///
+    /// If `after_replace` is set, an empty match at `pos` is skipped, so that
+    /// replacing empty matches moves forwards.
+    fn match_from(&self, pos: usize, after_replace: bool) -> Option<usize> {
+        self.matches
+            .iter()
+            .position(|m| m.start > pos || (m.start == pos && !(after_replace && m.is_empty())))
+            .or_else(|| self.matches.first().map(|_| 0))
+    }
+
+    /// The last match before `pos`, wrapping around to the last match.
+    fn match_before(&self, pos: usize) -> Option<usize> {
+        self.matches
+            .iter()
+            .rposition(|m| m.start < pos)
+            .or_else(|| self.matches.len().checked_sub(1))
+    }
+}
+
+impl<T, U: TextStorage + EditableText, LT: Lens<T, U>, LQ> FindReplace<T, U, LT, LQ> {
+    /// Search the text again, and show the matches in the text box.
+    fn search(&mut self, data: &T) {
+        let finder = &self.finder;
+        let matches = self.text.with(data, |text| match finder {
+            Ok(finder) => finder.matches(text.as_str()),
+            Err(_) => Vec::new(),
+        });
+        if matches != *self.matches {
+            self.matches = Arc::new(matches);
+        }
+        self.current = None;
+        self.textbox.set_highlights(self.matches.clone(), None);
+    }
+
+    /// Make `current` the current match, selecting it in the text box.
+    ///
+    /// The caller is responsible for requesting layout, and for passing the
+    /// result to [`invalidate_text_input`].
+    ///
+    /// [`invalidate_text_input`]: crate::EventCtx::invalidate_text_input
+    fn select(&mut self, current: Option<usize>) -> Option<ImeInvalidation> {
+        self.current = current;
+        self.textbox
+            .set_highlights(self.matches.clone(), self.current);
+        let range = self.matches.get(current?)?.clone();
+        self.textbox.reveal_range(range)
+    }
+}
+
+impl FindFieldController {
+    /// Create a new `FindFieldController`.
+    pub fn new() -> Self {
+        FindFieldController { target: None }
+    }
+
+    /// Builder-style method to send the commands only to the widget `target`.
+    pub fn with_target(mut self, target: WidgetId) -> Self {
+        self.target = Some(target);
+        self
+    }
+}
+
+impl<T, U, LT, LQ> Widget<T> for FindReplace<T, U, LT, LQ>
+where
+    T: Data,
+    U: TextStorage + EditableText,
+    LT: Lens<T, U>,
+    LQ: Lens<T, FindQuery>,
+{
+    #[instrument(
+        name = "FindReplace",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            let can_write = self.textbox.text().can_write();
+            if (cmd.is(TextBox::FIND_NEXT) || cmd.is(TextBox::FIND_PREVIOUS)) && can_write {
+                let selection = self.textbox.text().borrow().selection();
+                let len = self.matches.len();
+                let current = match self.current {
+                    _ if len == 0 => None,
+                    Some(i) if cmd.is(TextBox::FIND_NEXT) => Some((i + 1) % len),
+                    Some(i) => Some((i + len - 1) % len),
+                    None if cmd.is(TextBox::FIND_NEXT) => self.match_from(selection.min(), false),
+                    None => self.match_before(selection.min()),
+                };
+                if let Some(invalidation) = self.select(current) {
+                    ctx.invalidate_text_input(invalidation);
+                }
+                ctx.request_layout();
+                ctx.set_handled();
+                return;
+            }
+            if (cmd.is(TextBox::REPLACE) || cmd.is(TextBox::REPLACE_ALL)) && can_write {
+                if let Ok(finder) = &self.finder {
+                    let current = self.current.map(|i| self.matches[i].clone());
+                    let textbox = &mut self.textbox;
+                    let replace_all = cmd.is(TextBox::REPLACE_ALL);
+                    self.replaced_until = self.text.with_mut(data, |text| {
+                        if replace_all {
+                            let replaced = finder.replace_all(text.as_str());
+                            if replaced != text.as_str() {
+                                textbox.replace_range(text, 0..text.len(), &replaced);
+                            }
+                            None
+                        } else {
+                            let range = current?;
+                            let replacement = finder.replacement(text.as_str(), range.clone());
+                            textbox.replace_range(text, range.clone(), &replacement);
+                            Some(range.start + replacement.len())
+                        }
+                    });
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        let textbox = &mut self.textbox;
+        self.text
+            .with_mut(data, |text| textbox.event(ctx, event, text, env));
+    }
+
+    #[instrument(
+        name = "FindReplace",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.finder = self.query.with(data, FindQuery::compile);
+            self.search(data);
+        }
+        let textbox = &mut self.textbox;
+        self.text
+            .with(data, |text| textbox.lifecycle(ctx, event, text, env));
+    }
+
+    #[instrument(
+        name = "FindReplace",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let query_changed = self.query.with(old_data, |old| {
+            self.query.with(data, |query| !old.same(query))
+        });
+        if query_changed {
+            self.finder = self.query.with(data, FindQuery::compile);
+        }
+
+        let textbox = &mut self.textbox;
+        let lens = &self.text;
+        let text_changed = lens.with(old_data, |old_text| {
+            lens.with(data, |text| {
+                let changed = !old_text.same(text);
+                if ctx.has_requested_update() || changed || ctx.env_changed() {
+                    textbox.update(ctx, old_text, text, env);
+                }
+                changed
+            })
+        });
+
+        if query_changed || text_changed {
+            self.search(data);
+            // Don't move the selection while the user is typing in the text box.
+            let from = match self.replaced_until.take() {
+                Some(pos) => Some((pos, true)),
+                None if query_changed => {
+                    Some((self.textbox.text().borrow().selection().min(), false))
+                }
+                None => None,
+            };
+            if let Some((pos, after_replace)) = from.filter(|_| self.textbox.text().can_write()) {
+                if let Some(invalidation) = self.select(self.match_from(pos, after_replace)) {
+                    ctx.invalidate_text_input(invalidation);
+                }
+                ctx.request_layout();
+            }
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "FindReplace", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let textbox = &mut self.textbox;
+        self.text
+            .with(data, |text| textbox.layout(ctx, bc, text, env))
+    }
+
+    #[instrument(name = "FindReplace", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let textbox = &mut self.textbox;
+        self.text.with(data, |text| textbox.paint(ctx, text, env));
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let child = self.text.with(data, |text| self.textbox.debug_state(text));
+        DebugState {
+            display_name: "FindReplace".to_string(),
+            children: vec![child],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, W: Widget<T>> Controller<T, W> for FindFieldController {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::KeyDown(key) = event {
+            let command = if HotKey::new(None, KbKey::Enter).matches(key) {
+                Some(TextBox::FIND_NEXT)
+            } else if HotKey::new(RawMods::Shift, KbKey::Enter).matches(key) {
+                Some(TextBox::FIND_PREVIOUS)
+            } else {
+                None
+            };
+            if let Some(command) = command {
+                let target = self.target.map(Target::Widget).unwrap_or(Target::Auto);
+                ctx.submit_command(command.to(target));
+                ctx.set_handled();
+                return;
+            }
+        }
+        child.event(ctx, event, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::tests::move_mouse;
+    use crate::text::Selection;
+    use crate::widget::Flex;
+    use crate::{KeyEvent, LensExt, Modifiers, MouseButton, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    type Doc = (String, FindQuery);
+
+    fn selection(harness: &mut Harness<Doc>) -> Selection {
+        let token = harness.window().ime_handlers[1].0;
+        let handler = harness.window_mut().get_ime_handler(token, false);
+        let selection = handler.selection();
+        drop(handler);
+        harness.window_mut().release_ime_lock(token);
+        selection
+    }
+
+    fn press(harness: &mut Harness<Doc>, mods: Modifiers) {
+        let key = KeyEvent::for_test(mods, KbKey::Enter);
+        harness.event(Event::KeyDown(key));
+    }
+
+    #[test]
+    fn find_and_replace() {
+        let [search_id, id] = widget_ids();
+        let search = TextBox::new()
+            .controller(FindFieldController::new())
+            .with_id(search_id)
+            .lens(lens!(Doc, 1).then(FindQuery::query));
+        let editor = TextBox::multiline()
+            .with_find(lens!(Doc, 0), lens!(Doc, 1))
+            .with_id(id)
+            .fix_size(300.0, 100.0);
+        let root = Flex::column().with_child(search).with_child(editor);
+        let data = ("one two one two one".to_string(), FindQuery::default());
+
+        Harness::create_simple(data, root, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let rect = harness.get_state(search_id).layout_rect();
+            let click = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse(rect.center())
+            };
+            harness.event(Event::MouseDown(click.clone()));
+            harness.event(Event::MouseUp(click));
+            assert_eq!(harness.window().focus, Some(search_id));
+
+            // changing the query selects the first match
+            harness.update_data(|data| data.1.query = "ONE".into());
+            harness.just_layout();
+            harness.paint();
+            assert_eq!(selection(harness), Selection::new(0, 3));
+
+            // Enter and Shift+Enter cycle through the matches, wrapping around
+            press(harness, Modifiers::default());
+            assert_eq!(selection(harness), Selection::new(8, 11));
+            press(harness, Modifiers::SHIFT);
+            press(harness, Modifiers::SHIFT);
+            assert_eq!(selection(harness), Selection::new(16, 19));
+            assert_eq!(harness.window().focus, Some(search_id));
+
+            harness.update_data(|data| data.1.replacement = "1".into());
+            harness.submit_command(TextBox::REPLACE.to(id));
+            assert_eq!(harness.data().0, "one two one two 1");
+            // the next match is after the replacement, wrapping around
+            assert_eq!(selection(harness), Selection::new(0, 3));
+            harness.submit_command(TextBox::REPLACE.to(id));
+            assert_eq!(harness.data().0, "1 two one two 1");
+            assert_eq!(selection(harness), Selection::new(6, 9));
+
+            // the matches follow edits of the text
+            harness.update_data(|data| data.0.insert_str(0, "one "));
+            harness.submit_command(TextBox::REPLACE_ALL.to(id));
+            assert_eq!(harness.data().0, "1 1 two 1 two 1");
+
+            harness.update_data(|data| {
+                data.1.query = "(".into();
+                data.1.regex = true;
+            });
+            harness.submit_command(TextBox::REPLACE_ALL.to(id));
+            assert_eq!(harness.data().0, "1 1 two 1 two 1");
+        });
+    }
+}
@@ -17,7 +17,7 @@
 use crate::debug_state::DebugState;
 use crate::kurbo::{common::FloatExt, Vec2};
 use crate::widget::prelude::*;
-use crate::{Data, KeyOrValue, Point, Rect, WidgetPod};
+use crate::{theme, Data, KeyOrValue, Point, Rect, WidgetPod};
 use tracing::{instrument, trace};
 
 /// A container with either horizontal or vertical layout.
@@ -96,6 +96,10 @@ use tracing::{instrument, trace};
 /// If this is `true`, then the container must fill the available space on that
 /// axis; otherwise it may be smaller if its children are smaller.
 ///
+/// - [`main_axis_gap`] inserts a fixed amount of space between each pair of
+/// adjacent children, regardless of the [`MainAxisAlignment`]. The gaps are
+/// reserved before any space is distributed to flex children.
+///
 /// Additional options can be set (or overridden) in the [`FlexParams`].
 ///
 /// # Examples
@@ -130,6 +134,7 @@ use tracing::{instrument, trace};
 /// [`MainAxisAlignment`]: enum.MainAxisAlignment.html
 /// [`CrossAxisAlignment`]: enum.CrossAxisAlignment.html
 /// [`must_fill_main_axis`]: struct.Flex.html#method.must_fill_main_axis
+/// [`main_axis_gap`]: struct.Flex.html#method.with_main_axis_gap
 /// [`FlexParams`]: struct.FlexParams.html
 /// [`WidgetExt`]: ../trait.WidgetExt.html
 /// [`expand_height`]: ../trait.WidgetExt.html#method.expand_height
@@ -141,6 +146,7 @@ pub struct Flex<T> {
     cross_alignment: CrossAxisAlignment,
     main_alignment: MainAxisAlignment,
     fill_major_axis: bool,
+    gap: KeyOrValue<f64>,
     children: Vec<Child<T>>,
 }
 
@@ -305,9 +311,12 @@ pub enum CrossAxisAlignment {
     /// Align on the baseline.
     ///
     /// In a horizontal container, widgets are aligned along the calculated
-    /// baseline. In a vertical container, this is equivalent to `End`.
+    /// baseline. Widgets that don't report a baseline, such as images, are
+    /// aligned at the bottom instead. In a vertical container, this is
+    /// equivalent to `Center`.
     ///
-    /// The calculated baseline is the maximum baseline offset of the children.
+    /// The calculated baseline is the maximum baseline offset of the children,
+    /// and is also the baseline of the container, so that nested rows line up.
     Baseline,
     /// Fill the available space.
     ///
@@ -375,6 +384,7 @@ impl<T: Data> Flex<T> {
             cross_alignment: CrossAxisAlignment::Center,
             main_alignment: MainAxisAlignment::Start,
             fill_major_axis: false,
+            gap: KeyOrValue::Concrete(0.0),
         }
     }
 
@@ -428,6 +438,33 @@ impl<T: Data> Flex<T> {
         self
     }
 
+    /// Builder-style method for setting the gap inserted between adjacent children.
+    ///
+    /// The gap is added between every pair of children (including spacers), but
+    /// not before the first or after the last child. It is applied in addition
+    /// to any space introduced by the [`MainAxisAlignment`]; flex children share
+    /// the space that is left over once the gaps have been subtracted.
+    ///
+    /// The default value is `0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::{Flex, Label, Slider};
+    ///
+    /// let my_row = Flex::row()
+    ///     .with_main_axis_gap(8.0)
+    ///     .with_child(Label::new("volume"))
+    ///     .with_flex_child(Slider::new(), 1.0)
+    ///     .with_child(Label::new("max"));
+    /// ```
+    ///
+    /// [`MainAxisAlignment`]: enum.MainAxisAlignment.html
+    pub fn with_main_axis_gap(mut self, gap: impl Into<KeyOrValue<f64>>) -> Self {
+        self.set_main_axis_gap(gap);
+        self
+    }
+
     /// Builder-style variant of `add_child`.
     ///
     /// Convenient for assembling a group of widgets in a single expression.
@@ -517,6 +554,22 @@ impl<T: Data> Flex<T> {
         self.fill_major_axis = fill;
     }
 
+    /// Set the gap inserted between adjacent children.
+    ///
+    /// See [`with_main_axis_gap`] for more details.
+    ///
+    /// [`with_main_axis_gap`]: Flex::with_main_axis_gap
+    pub fn set_main_axis_gap(&mut self, gap: impl Into<KeyOrValue<f64>>) {
+        let mut gap = gap.into();
+        if let KeyOrValue::Concrete(ref mut gap) = gap {
+            if *gap < 0.0 {
+                tracing::warn!("Provided main axis gap was less than 0. Value was: {}", gap);
+            }
+            *gap = gap.clamp(0.0, f64::MAX);
+        }
+        self.gap = gap;
+    }
+
     /// Add a non-flex child widget.
     ///
     /// See also [`with_child`].
@@ -625,6 +678,18 @@ impl<T: Data> Flex<T> {
     }
 }
 
+/// Returns `true` if a child with this alignment and baseline offset is aligned
+/// on its baseline.
+fn aligns_on_baseline(
+    direction: Axis,
+    alignment: CrossAxisAlignment,
+    baseline_offset: f64,
+) -> bool {
+    alignment == CrossAxisAlignment::Baseline
+        && direction == Axis::Horizontal
+        && baseline_offset > 0.0
+}
+
 impl<T: Data> Widget<T> for Flex<T> {
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
@@ -642,6 +707,9 @@ impl<T: Data> Widget<T> for Flex<T> {
 
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, _old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if ctx.env_key_changed(&self.gap) {
+            ctx.request_layout();
+        }
         for child in self.children.iter_mut() {
             match child {
                 Child::Fixed { widget, .. } | Child::Flex { widget, .. } => {
@@ -663,19 +731,24 @@ impl<T: Data> Widget<T> for Flex<T> {
 
         // minor-axis values for all children
         let mut minor = self.direction.minor(bc.min());
-        // these two are calculated but only used if we're baseline aligned
+        // these two are only calculated for the children that are aligned on
+        // their baseline
         let mut max_above_baseline = 0f64;
         let mut max_below_baseline = 0f64;
         let mut any_use_baseline = false;
+        let mut max_child_baseline = 0f64;
+
+        // The gaps between children are reserved up front, like a fixed spacer.
+        let gap = self.gap.resolve(env).max(0.0);
+        let total_gap = gap * self.children.len().saturating_sub(1) as f64;
 
         // Measure non-flex children.
-        let mut major_non_flex = 0.0;
+        let mut major_non_flex = total_gap;
         let mut flex_sum = 0.0;
         for child in &mut self.children {
             match child {
                 Child::Fixed { widget, alignment } => {
                     let alignment = alignment.unwrap_or(self.cross_alignment);
-                    any_use_baseline |= alignment == CrossAxisAlignment::Baseline;
 
                     let child_bc =
                         self.direction
@@ -693,9 +766,13 @@ impl<T: Data> Widget<T> for Flex<T> {
 
                     major_non_flex += self.direction.major(child_size).expand();
                     minor = minor.max(self.direction.minor(child_size).expand());
-                    max_above_baseline =
-                        max_above_baseline.max(child_size.height - baseline_offset);
-                    max_below_baseline = max_below_baseline.max(baseline_offset);
+                    max_child_baseline = max_child_baseline.max(baseline_offset);
+                    if aligns_on_baseline(self.direction, alignment, baseline_offset) {
+                        any_use_baseline = true;
+                        max_above_baseline =
+                            max_above_baseline.max(child_size.height - baseline_offset);
+                        max_below_baseline = max_below_baseline.max(baseline_offset);
+                    }
                 }
                 Child::FixedSpacer(kv, calculated_siz) => {
                     *calculated_siz = kv.resolve(env);
@@ -724,7 +801,6 @@ impl<T: Data> Widget<T> for Flex<T> {
                     alignment,
                 } => {
                     let alignment = alignment.unwrap_or(self.cross_alignment);
-                    any_use_baseline |= alignment == CrossAxisAlignment::Baseline;
 
                     let desired_major = (*flex) * px_per_flex + remainder;
                     let actual_major = desired_major.round();
@@ -736,9 +812,13 @@ impl<T: Data> Widget<T> for Flex<T> {
 
                     major_flex += self.direction.major(child_size).expand();
                     minor = minor.max(self.direction.minor(child_size).expand());
-                    max_above_baseline =
-                        max_above_baseline.max(child_size.height - baseline_offset);
-                    max_below_baseline = max_below_baseline.max(baseline_offset);
+                    max_child_baseline = max_child_baseline.max(baseline_offset);
+                    if aligns_on_baseline(self.direction, alignment, baseline_offset) {
+                        any_use_baseline = true;
+                        max_above_baseline =
+                            max_above_baseline.max(child_size.height - baseline_offset);
+                        max_below_baseline = max_below_baseline.max(baseline_offset);
+                    }
                 }
                 Child::FlexedSpacer(flex, calculated_size) => {
                     let desired_major = (*flex) * px_per_flex + remainder;
@@ -764,16 +844,23 @@ impl<T: Data> Widget<T> for Flex<T> {
         // the actual size needed to tightly fit the children on the minor axis.
         // Unlike the 'minor' var, this ignores the incoming constraints.
         let minor_dim = match self.direction {
-            Axis::Horizontal if any_use_baseline => max_below_baseline + max_above_baseline,
+            Axis::Horizontal if any_use_baseline => {
+                minor.max(max_below_baseline + max_above_baseline)
+            }
             _ => minor,
         };
 
-        let extra_height = minor - minor_dim.min(minor);
+        // The baseline, from the top. If some children are taller than the
+        // ones aligned on their baseline, those are aligned at the bottom.
+        let baseline_y = minor_dim - max_below_baseline;
 
         let mut major = spacing.next().unwrap_or(0.);
         let mut child_paint_rect = Rect::ZERO;
 
-        for child in &mut self.children {
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if i > 0 {
+                major += gap;
+            }
             match child {
                 Child::Fixed { widget, alignment }
                 | Child::Flex {
@@ -782,14 +869,21 @@ impl<T: Data> Widget<T> for Flex<T> {
                     let child_size = widget.layout_rect().size();
                     let alignment = alignment.unwrap_or(self.cross_alignment);
                     let child_minor_offset = match alignment {
-                        // This will ignore baseline alignment if it is overridden on children,
-                        // but is not the default for the container. Is this okay?
                         CrossAxisAlignment::Baseline
-                            if matches!(self.direction, Axis::Horizontal) =>
+                            if aligns_on_baseline(
+                                self.direction,
+                                alignment,
+                                widget.baseline_offset(),
+                            ) =>
                         {
                             let child_baseline = widget.baseline_offset();
-                            let child_above_baseline = child_size.height - child_baseline;
-                            extra_height + (max_above_baseline - child_above_baseline)
+                            baseline_y - (child_size.height - child_baseline)
+                        }
+                        // Widgets without a baseline are bottom aligned.
+                        CrossAxisAlignment::Baseline
+                            if matches!(self.direction, Axis::Horizontal) =>
+                        {
+                            minor_dim - child_size.height
                         }
                         CrossAxisAlignment::Fill => {
                             let fill_size: Size = self
@@ -839,12 +933,30 @@ impl<T: Data> Widget<T> for Flex<T> {
             bc.constrain(my_size)
         };
 
+        // In a right-to-left layout, mirror every child around the middle of the
+        // flex, so that the main axis (or the cross axis, for a column) starts
+        // at the right edge.
+        if env.get(theme::LAYOUT_DIRECTION).is_rtl() {
+            child_paint_rect = Rect::ZERO;
+            for widget in self
+                .children
+                .iter_mut()
+                .filter_map(|child| child.widget_mut())
+            {
+                let rect = widget.layout_rect();
+                let origin = Point::new(my_size.width - rect.x1, rect.y0);
+                widget.set_origin(ctx, data, env, origin);
+                child_paint_rect = child_paint_rect.union(widget.paint_rect());
+            }
+        }
+
         let my_bounds = Rect::ZERO.with_size(my_size);
         let insets = child_paint_rect - my_bounds;
         ctx.set_paint_insets(insets);
 
         let baseline_offset = match self.direction {
-            Axis::Horizontal => max_below_baseline,
+            Axis::Horizontal if any_use_baseline => my_size.height - baseline_y,
+            Axis::Horizontal => max_child_baseline,
             Axis::Vertical => (&self.children)
                 .last()
                 .map(|last| {
@@ -1137,4 +1249,86 @@ mod tests {
         let params = FlexParams::new(-1.0, None);
         approx_eq!(f64, params.flex, 1.0, ulps = 2);
     }
+
+    #[test]
+    fn baseline_alignment() {
+        use crate::tests::harness::Harness;
+        use crate::tests::helpers::widget_ids;
+        use crate::widget::{Label, SizedBox};
+        use crate::WidgetExt;
+
+        let [small_id, big_id, box_id, nested_id, row_id] = widget_ids();
+        let label = |text: &str, size: f64, id| Label::new(text).with_text_size(size).with_id(id);
+        let row = Flex::row()
+            .cross_axis_alignment(CrossAxisAlignment::Baseline)
+            .with_child(label("small", 10.0, small_id))
+            .with_child(label("big", 30.0, big_id))
+            .with_child(SizedBox::empty().width(10.0).height(20.0).with_id(box_id))
+            .with_child(
+                Flex::row()
+                    .cross_axis_alignment(CrossAxisAlignment::Baseline)
+                    .with_child(label("nested", 20.0, nested_id)),
+            )
+            .with_id(row_id)
+            .center();
+
+        Harness::create_simple((), row, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert!(harness.get_state(big_id).baseline_offset > 0.0);
+            let mut baseline = |id| {
+                let state = harness.get_state(id);
+                state.window_origin().y + state.layout_rect().height() - state.baseline_offset
+            };
+            let big = baseline(big_id);
+            assert!((baseline(small_id) - big).abs() < 1e-9);
+            assert!((baseline(nested_id) - big).abs() < 1e-9);
+            assert!((baseline(row_id) - big).abs() < 1e-9);
+            // The box has no baseline, so it is at the bottom of the row.
+            let row = harness.get_state(row_id);
+            let bottom = row.window_origin().y + row.layout_rect().height();
+            let box_state = harness.get_state(box_id);
+            assert_eq!(
+                box_state.window_origin().y + box_state.layout_rect().height(),
+                bottom
+            );
+        });
+    }
+
+    #[test]
+    fn rtl_layout_is_mirrored() {
+        use crate::tests::harness::Harness;
+        use crate::tests::helpers::widget_ids;
+        use crate::widget::{LayoutDirection, SizedBox};
+        use crate::WidgetExt;
+
+        let [first_id, second_id, row_id] = widget_ids();
+        let row = Flex::row()
+            .with_child(SizedBox::empty().width(10.0).height(10.0).with_id(first_id))
+            .with_spacer(5.0)
+            .with_child(
+                SizedBox::empty()
+                    .width(20.0)
+                    .height(10.0)
+                    .with_id(second_id),
+            )
+            .with_id(row_id)
+            // The leading inset is on the left, which is the trailing edge.
+            .padding((1.0, 0.0, 3.0, 0.0))
+            .env_scope(|env, _| env.set(theme::LAYOUT_DIRECTION, LayoutDirection::Rtl))
+            .center();
+
+        Harness::create_simple((), row, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let row = harness.get_state(row_id).layout_rect();
+            assert_eq!(row.x0, 3.0);
+            assert_eq!(row.width(), 35.0);
+            // The first child starts at the right edge of the row.
+            let first = harness.get_state(first_id).layout_rect();
+            assert_eq!((first.x0, first.x1), (25.0, 35.0));
+            let second = harness.get_state(second_id).layout_rect();
+            assert_eq!((second.x0, second.x1), (0.0, 20.0));
+        });
+    }
 }
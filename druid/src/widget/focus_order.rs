@@ -0,0 +1,284 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Widgets that change the order in which tab moves the focus.
+
+use crate::core::FocusEntry;
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{Point, WidgetPod};
+
+/// A widget that sets where its descendants are in the focus order.
+///
+/// Tab and [`EventCtx::focus_next`] visit the focusable widgets of a window
+/// by increasing order, and the widgets with the same order in the order
+/// they registered for focus, which is usually the order in the widget tree.
+/// Widgets that aren't inside a `FocusOrder` have the order `0`, so a
+/// negative order puts widgets before them, and a positive one after them.
+/// The order of the closest `FocusOrder` ancestor wins.
+///
+/// Disabled and hidden widgets are skipped, and the focus wraps around from
+/// the last widget to the first one.
+///
+/// This is more conveniently used through [`WidgetExt::focus_order`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Flex, TextBox};
+/// use druid::{Widget, WidgetExt};
+///
+/// // Tab goes to the second text box first.
+/// fn form() -> impl Widget<String> {
+///     Flex::row()
+///         .with_child(TextBox::new().focus_order(1))
+///         .with_child(TextBox::new())
+/// }
+/// ```
+///
+/// [`EventCtx::focus_next`]: crate::EventCtx::focus_next
+/// [`WidgetExt::focus_order`]: super::WidgetExt::focus_order
+pub struct FocusOrder<T, W> {
+    child: WidgetPod<T, W>,
+    order: i32,
+}
+
+/// A widget that keeps the focus among its descendants.
+///
+/// When the focus is in the group, tab and [`EventCtx::focus_next`] only
+/// visit the focusable widgets in the group, wrapping around at its ends.
+/// This is meant for modal content, such as a dialog in a [`Popup`]. Groups
+/// don't nest; a widget belongs to its closest `FocusGroup` ancestor.
+///
+/// This is more conveniently used through [`WidgetExt::focus_group`].
+///
+/// [`EventCtx::focus_next`]: crate::EventCtx::focus_next
+/// [`Popup`]: super::Popup
+/// [`WidgetExt::focus_group`]: super::WidgetExt::focus_group
+pub struct FocusGroup<T, W> {
+    child: WidgetPod<T, W>,
+    group: WidgetId,
+}
+
+impl<T, W: Widget<T>> FocusOrder<T, W> {
+    /// Create a widget that gives the focusable widgets in `child` the focus
+    /// `order`.
+    pub fn new(child: W, order: i32) -> Self {
+        FocusOrder {
+            child: WidgetPod::new(child),
+            order,
+        }
+    }
+}
+
+impl<T, W: Widget<T>> FocusGroup<T, W> {
+    /// Create a widget that keeps the focus among the focusable widgets in
+    /// `child`.
+    pub fn new(child: W) -> Self {
+        FocusGroup {
+            child: WidgetPod::new(child),
+            group: WidgetId::next(),
+        }
+    }
+}
+
+/// Pass `event` to `child`, and then change the focus chain entries it added.
+fn lifecycle_with_entries<T: Data, W: Widget<T>>(
+    child: &mut WidgetPod<T, W>,
+    ctx: &mut LifeCycleCtx,
+    event: &LifeCycle,
+    data: &T,
+    env: &Env,
+    change: impl Fn(&mut FocusEntry),
+) {
+    let start = ctx.widget_state.focus_chain.len();
+    child.lifecycle(ctx, event, data, env);
+    if let LifeCycle::BuildFocusChain = event {
+        ctx.widget_state.focus_chain[start..]
+            .iter_mut()
+            .for_each(change);
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for FocusOrder<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let order = self.order;
+        lifecycle_with_entries(&mut self.child, ctx, event, data, env, |entry| {
+            entry.order.get_or_insert(order);
+        });
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ZERO);
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: self.order.to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for FocusGroup<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let group = self.group;
+        lifecycle_with_entries(&mut self.child, ctx, event, data, env, |entry| {
+            entry.group.get_or_insert(group);
+        });
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ZERO);
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::widget::{Flex, WidgetExt};
+    use crate::{KbKey, KeyEvent, Modifiers, Selector};
+    use test_log::test;
+
+    const FOCUS: Selector = Selector::new("druid-test.focus");
+
+    /// A focusable widget that moves the focus on tab and shift-tab.
+    fn focusable(id: WidgetId) -> impl Widget<bool> {
+        ModularWidget::new(())
+            .event_fn(|_, ctx, event, _, _| match event {
+                Event::Command(cmd) if cmd.is(FOCUS) => ctx.request_focus(),
+                Event::KeyDown(key) if key.key == KbKey::Tab && key.mods.shift() => {
+                    ctx.focus_prev()
+                }
+                Event::KeyDown(key) if key.key == KbKey::Tab => ctx.focus_next(),
+                _ => (),
+            })
+            .lifecycle_fn(|_, ctx, event, _, _| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .with_id(id)
+    }
+
+    /// Press tab, and return the newly focused widget.
+    fn tab(harness: &mut Harness<bool>, shift: bool) -> Option<WidgetId> {
+        let mods = if shift {
+            Modifiers::SHIFT
+        } else {
+            Modifiers::default()
+        };
+        harness.event(Event::KeyDown(KeyEvent::for_test(mods, KbKey::Tab)));
+        harness.window().focus
+    }
+
+    #[test]
+    fn custom_order() {
+        let [a, b, c, d] = widget_ids();
+        let widget = Flex::column()
+            .with_child(focusable(a).focus_order(1))
+            .with_child(focusable(b))
+            .with_child(
+                Flex::column()
+                    .with_child(focusable(c).focus_order(-1))
+                    .with_child(focusable(d))
+                    .focus_order(1),
+            );
+        Harness::create_simple(true, widget, |harness| {
+            harness.send_initial_events();
+            // The closest order wins, and equal orders keep the tree order.
+            assert_eq!(harness.window().focus_chain(), &[c, b, a, d]);
+
+            harness.submit_command(FOCUS.to(c));
+            assert_eq!(tab(harness, false), Some(b));
+            assert_eq!(tab(harness, false), Some(a));
+            assert_eq!(tab(harness, false), Some(d));
+            assert_eq!(tab(harness, false), Some(c));
+            assert_eq!(tab(harness, true), Some(d));
+        });
+    }
+
+    #[test]
+    fn groups_keep_focus() {
+        let [a, b, c, d, e] = widget_ids();
+        let widget = Flex::column()
+            .with_child(focusable(a))
+            .with_child(
+                Flex::column()
+                    .with_child(focusable(b))
+                    .with_child(focusable(c))
+                    .with_child(focusable(d).disabled_if(|data: &bool, _| *data))
+                    .focus_group(),
+            )
+            .with_child(focusable(e));
+        Harness::create_simple(true, widget, |harness| {
+            harness.send_initial_events();
+            assert_eq!(harness.window().focus_chain(), &[a, b, c, e]);
+
+            // Outside of the group, tab goes through all the widgets.
+            harness.submit_command(FOCUS.to(a));
+            assert_eq!(tab(harness, false), Some(b));
+            // Inside, it wraps around in the group, skipping the disabled widget.
+            assert_eq!(tab(harness, false), Some(c));
+            assert_eq!(tab(harness, false), Some(b));
+            assert_eq!(tab(harness, true), Some(c));
+
+            harness.update_data(|data| *data = false);
+            assert_eq!(tab(harness, false), Some(d));
+            assert_eq!(tab(harness, false), Some(b));
+        });
+    }
+}
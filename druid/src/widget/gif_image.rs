@@ -0,0 +1,484 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that plays animated GIFs.
+
+use std::error::Error;
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::image::codecs::gif::GifDecoder;
+use crate::image::AnimationDecoder;
+use crate::piet::{ImageBuf, ImageFormat, InterpolationMode};
+use crate::widget::prelude::*;
+use crate::widget::{FillStrat, Image};
+use crate::{Data, Lens, TimerToken};
+use tracing::{instrument, trace, warn};
+
+/// The most memory that is used to keep decoded frames around. The frames of
+/// larger GIFs are decoded again every time they are shown.
+const MAX_CACHED_BYTES: usize = 32 * 1024 * 1024;
+/// Frames with shorter delays are shown for [`DEFAULT_DELAY`], like browsers do.
+const MIN_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+/// How often a hidden GIF checks whether it is visible again.
+const HIDDEN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A widget that plays an animated GIF.
+///
+/// The frames are shown for as long as the GIF asks for, and the animation
+/// either loops forever or stops on its last frame, see [`looping`]. Frames
+/// are decoded as they are needed; they are kept in memory for the next loop
+/// only if the decoded GIF is reasonably small.
+///
+/// The animation is paused while the widget is not painted, for instance
+/// because it is in a hidden tab, and resumes when it is shown again. It can
+/// also be paused and resumed from the data with [`playing`].
+///
+/// Like [`Image`], which is used to paint the current frame, a `GifImage` can
+/// be configured with a [`FillStrat`] and an [`InterpolationMode`].
+///
+/// This requires both the `image` and the `gif` features.
+///
+/// # Example
+///
+/// ```no_run
+/// use druid::widget::{Flex, GifImage, Switch};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct AppState {
+///     playing: bool,
+/// }
+///
+/// fn spinner(gif_data: Vec<u8>) -> impl Widget<AppState> {
+///     let gif = GifImage::from_data(gif_data)
+///         .unwrap()
+///         .playing(AppState::playing);
+///     Flex::column()
+///         .with_child(gif.fix_size(64.0, 64.0))
+///         .with_child(Switch::new().lens(AppState::playing))
+/// }
+/// ```
+///
+/// [`looping`]: GifImage::looping
+/// [`playing`]: GifImage::playing
+pub struct GifImage<T> {
+    image: Image,
+    frames: Frames,
+    /// How long the current frame is shown.
+    delay: Duration,
+    /// How long the current frame has been shown.
+    elapsed: Duration,
+    looping: bool,
+    is_playing: Option<Box<dyn Fn(&T) -> bool>>,
+    state: Playback,
+}
+
+/// Where a [`GifImage`] is in its animation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Playback {
+    /// Waiting for animation frames.
+    Running,
+    /// A new frame was shown, but not painted yet.
+    NeedsPaint,
+    /// The widget was not painted, so it is probably hidden.
+    Hidden(TimerToken),
+    /// Paused by the data.
+    Paused,
+    /// The last frame of a GIF that doesn't loop is shown.
+    Finished,
+}
+
+/// A single frame of a GIF.
+#[derive(Clone)]
+struct Frame {
+    image: ImageBuf,
+    delay: Duration,
+}
+
+/// The frames of a GIF, decoded as they are needed.
+struct Frames {
+    data: Arc<[u8]>,
+    decoder: Option<crate::image::Frames<'static>>,
+    /// The frames decoded so far, unless they take up too much memory.
+    cache: Option<Vec<Frame>>,
+    /// `true` once `cache` contains every frame.
+    complete: bool,
+    index: usize,
+}
+
+impl Frames {
+    /// Decode the first frame of a GIF.
+    fn new(data: Arc<[u8]>) -> Result<(Self, Frame), Box<dyn Error + Send + Sync>> {
+        let mut frames = Frames {
+            data,
+            decoder: None,
+            cache: Some(Vec::new()),
+            complete: false,
+            index: 0,
+        };
+        let first = frames.restart()?;
+        Ok((frames, first))
+    }
+
+    /// Start again from the first frame.
+    fn restart(&mut self) -> Result<Frame, Box<dyn Error + Send + Sync>> {
+        self.index = 0;
+        if self.complete {
+            if let Some(first) = self.cache.as_ref().and_then(|cache| cache.first()) {
+                return Ok(first.clone());
+            }
+        }
+        let decoder = GifDecoder::new(Cursor::new(self.data.clone()))?;
+        let mut decoder = decoder.into_frames();
+        let first = match decoder.next() {
+            Some(frame) => to_frame(frame?),
+            None => return Err("the GIF has no frames".into()),
+        };
+        self.decoder = Some(decoder);
+        if let Some(cache) = &mut self.cache {
+            cache.clear();
+            cache.push(first.clone());
+        }
+        Ok(first)
+    }
+
+    /// Returns the frame after the current one.
+    ///
+    /// After the last frame, this returns the first one if `looping` is `true`,
+    /// and `None` otherwise. A GIF with a single frame is never repeated.
+    fn next(&mut self, looping: bool) -> Option<Frame> {
+        if !self.complete {
+            match self.decoder.as_mut().and_then(Iterator::next) {
+                Some(Ok(frame)) => {
+                    let frame = to_frame(frame);
+                    self.index += 1;
+                    self.cache_frame(&frame);
+                    return Some(frame);
+                }
+                Some(Err(e)) => warn!("failed to decode GIF frame: {}", e),
+                None => (),
+            }
+            self.decoder = None;
+            self.complete = self.cache.is_some();
+        }
+        if let Some(cache) = self.cache.as_ref().filter(|_| self.complete) {
+            if let Some(frame) = cache.get(self.index + 1) {
+                self.index += 1;
+                return Some(frame.clone());
+            }
+            if cache.len() < 2 {
+                return None;
+            }
+        } else if self.index == 0 {
+            return None;
+        }
+        if !looping {
+            return None;
+        }
+        match self.restart() {
+            Ok(frame) => Some(frame),
+            Err(e) => {
+                warn!("failed to decode GIF: {}", e);
+                None
+            }
+        }
+    }
+
+    fn cache_frame(&mut self, frame: &Frame) {
+        if let Some(cache) = &mut self.cache {
+            let frame_bytes = frame.image.raw_pixels().len();
+            if (cache.len() + 1) * frame_bytes > MAX_CACHED_BYTES {
+                trace!("GIF is too large to cache, decoding every loop");
+                self.cache = None;
+            } else {
+                cache.push(frame.clone());
+            }
+        }
+    }
+}
+
+fn to_frame(frame: crate::image::Frame) -> Frame {
+    let (numer, denom) = frame.delay().numer_denom_ms();
+    let delay = Duration::from_micros(numer as u64 * 1000 / denom.max(1) as u64);
+    let buffer = frame.into_buffer();
+    let (width, height) = (buffer.width() as usize, buffer.height() as usize);
+    Frame {
+        image: ImageBuf::from_raw(buffer.into_raw(), ImageFormat::RgbaSeparate, width, height),
+        delay: frame_delay(delay),
+    }
+}
+
+/// The time a frame with the given delay is shown for.
+fn frame_delay(delay: Duration) -> Duration {
+    if delay < MIN_DELAY {
+        DEFAULT_DELAY
+    } else {
+        delay
+    }
+}
+
+impl<T: Data> GifImage<T> {
+    /// Create a new `GifImage` from the contents of a GIF file.
+    ///
+    /// This decodes the first frame, and returns an error if that fails.
+    pub fn from_data(data: impl Into<Arc<[u8]>>) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let (frames, first) = Frames::new(data.into())?;
+        Ok(GifImage {
+            image: Image::new(first.image),
+            frames,
+            delay: first.delay,
+            elapsed: Duration::ZERO,
+            looping: true,
+            is_playing: None,
+            state: Playback::Running,
+        })
+    }
+
+    /// Builder-style method for choosing whether the animation starts over
+    /// after the last frame.
+    ///
+    /// By default, it does.
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Builder-style method for pausing and resuming the animation with a
+    /// `bool` in the data.
+    ///
+    /// The animation plays while the `bool` is `true`. If it was finished, it
+    /// starts over when the `bool` becomes `true` again. Without this, the
+    /// animation plays as soon as the widget is added.
+    pub fn playing(mut self, lens: impl Lens<T, bool> + 'static) -> Self {
+        self.is_playing = Some(Box::new(move |data| lens.with(data, |playing| *playing)));
+        self
+    }
+
+    /// Builder-style method for specifying the fill strategy.
+    pub fn fill_mode(mut self, mode: FillStrat) -> Self {
+        self.image.set_fill_mode(mode);
+        self
+    }
+
+    /// Builder-style method for setting the interpolation mode.
+    pub fn interpolation_mode(mut self, interpolation: InterpolationMode) -> Self {
+        self.image.set_interpolation_mode(interpolation);
+        self
+    }
+
+    /// Returns `true` if the animation is playing.
+    ///
+    /// This is also `true` while the animation is paused because the widget is
+    /// hidden.
+    pub fn is_playing(&self) -> bool {
+        !matches!(self.state, Playback::Paused | Playback::Finished)
+    }
+
+    fn should_play(&self, data: &T) -> bool {
+        self.is_playing.as_ref().map(|f| f(data)).unwrap_or(true)
+    }
+
+    fn show(&mut self, frame: Frame) {
+        self.image.set_image_data(frame.image);
+        self.delay = frame.delay;
+    }
+
+    /// Start animating again, from the first frame if the animation had finished.
+    fn resume(&mut self) {
+        if self.state == Playback::Finished {
+            match self.frames.restart() {
+                Ok(frame) => self.show(frame),
+                Err(e) => {
+                    warn!("failed to decode GIF: {}", e);
+                    return;
+                }
+            }
+        }
+        self.elapsed = Duration::ZERO;
+        self.state = Playback::Running;
+    }
+
+    /// Advance the animation by `interval`, returning `true` if a new frame is shown.
+    fn advance(&mut self, interval: Duration) -> bool {
+        // Don't catch up after a long hiccup.
+        self.elapsed += interval.min(self.delay);
+        let mut changed = false;
+        while self.elapsed >= self.delay {
+            self.elapsed -= self.delay;
+            match self.frames.next(self.looping) {
+                Some(frame) => {
+                    self.show(frame);
+                    changed = true;
+                }
+                None => {
+                    self.state = Playback::Finished;
+                    break;
+                }
+            }
+        }
+        changed
+    }
+}
+
+impl<T: Data> Widget<T> for GifImage<T> {
+    #[instrument(name = "GifImage", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::AnimFrame(interval) => match self.state {
+                Playback::Running => {
+                    if self.advance(Duration::from_nanos(*interval)) {
+                        ctx.request_paint();
+                        if self.state == Playback::Running {
+                            self.state = Playback::NeedsPaint;
+                        }
+                    }
+                    if self.state != Playback::Finished {
+                        ctx.request_anim_frame();
+                    }
+                }
+                Playback::NeedsPaint => {
+                    trace!("GifImage {:?} is hidden", ctx.widget_id());
+                    self.state = Playback::Hidden(ctx.request_timer(HIDDEN_POLL_INTERVAL));
+                }
+                _ => (),
+            },
+            Event::Timer(token) if self.state == Playback::Hidden(*token) => {
+                // The frame that was not painted is still pending; painting it
+                // resumes the animation.
+                self.state = Playback::NeedsPaint;
+                ctx.request_paint();
+                ctx.request_anim_frame();
+            }
+            _ => self.image.event(ctx, event, data, env),
+        }
+    }
+
+    #[instrument(name = "GifImage", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            if self.should_play(data) {
+                ctx.request_anim_frame();
+            } else {
+                self.state = Playback::Paused;
+            }
+        }
+        self.image.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "GifImage",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let playing = self.should_play(data);
+        if playing != self.should_play(old_data) {
+            if playing {
+                self.resume();
+                ctx.request_paint();
+                ctx.request_anim_frame();
+            } else if self.state != Playback::Finished {
+                self.state = Playback::Paused;
+            }
+        }
+        self.image.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "GifImage", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("GifImage");
+        self.image.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "GifImage", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if self.state == Playback::NeedsPaint {
+            self.state = Playback::Running;
+        }
+        self.image.paint(ctx, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::codecs::gif::GifEncoder;
+    use crate::image::{Delay, Rgba, RgbaImage};
+
+    /// A GIF with one frame of each of the given colors.
+    fn gif(colors: &[[u8; 4]]) -> Vec<u8> {
+        let mut data = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut data);
+            for color in colors {
+                let buffer = RgbaImage::from_pixel(2, 2, Rgba(*color));
+                let frame = crate::image::Frame::from_parts(
+                    buffer,
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(50, 1),
+                );
+                encoder.encode_frame(frame).unwrap();
+            }
+        }
+        data
+    }
+
+    /// The brightest channel of the top left pixel; the encoder may not keep
+    /// the exact colors.
+    fn channel(frame: Option<Frame>) -> Option<usize> {
+        let pixels = frame?.image.raw_pixels()[..3].to_vec();
+        (0..3).max_by_key(|i| pixels[*i])
+    }
+
+    #[test]
+    fn frames_loop_or_stop() {
+        let colors = [[0xff, 0, 0, 0xff], [0, 0xff, 0, 0xff], [0, 0, 0xff, 0xff]];
+        let data: Arc<[u8]> = gif(&colors).into();
+
+        let (mut frames, first) = Frames::new(data.clone()).unwrap();
+        assert_eq!(first.delay, Duration::from_millis(50));
+        assert_eq!(channel(Some(first)), Some(0));
+        assert_eq!(channel(frames.next(true)), Some(1));
+        assert_eq!(channel(frames.next(true)), Some(2));
+        // After decoding every frame once, they come from the cache.
+        assert_eq!(channel(frames.next(true)), Some(0));
+        assert!(frames.complete);
+        assert_eq!(channel(frames.next(true)), Some(1));
+
+        let (mut frames, _) = Frames::new(data).unwrap();
+        assert!(frames.next(false).is_some());
+        assert!(frames.next(false).is_some());
+        assert!(frames.next(false).is_none());
+
+        // A still image is never repeated.
+        let (mut frames, _) = Frames::new(gif(&colors[..1]).into()).unwrap();
+        assert!(frames.next(true).is_none());
+
+        assert!(Frames::new(Arc::from(&b"not a gif"[..])).is_err());
+    }
+
+    #[test]
+    fn short_delays_are_lengthened() {
+        assert_eq!(frame_delay(Duration::ZERO), DEFAULT_DELAY);
+        assert_eq!(frame_delay(Duration::from_millis(10)), DEFAULT_DELAY);
+        assert_eq!(
+            frame_delay(Duration::from_millis(40)),
+            Duration::from_millis(40)
+        );
+    }
+}
@@ -0,0 +1,554 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A container that lays out its children in rows and columns.
+
+use std::ops::Range;
+
+use tracing::{instrument, trace, warn};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{Data, KeyOrValue, Point, Rect, WidgetPod};
+
+/// How the size of a row or column of a [`Grid`] is chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TrackSize {
+    /// A fixed size.
+    Fixed(f64),
+    /// Just large enough for the children in the track.
+    ///
+    /// This is the size of rows and columns that are not given a size
+    /// explicitly.
+    #[default]
+    Auto,
+    /// A share of the space that is left once the fixed and auto tracks
+    /// have their size, in proportion to the given flex factor.
+    ///
+    /// If the grid is given unbounded constraints in that direction, there is
+    /// no space to share, and the track is sized like an auto track instead.
+    Flex(f64),
+}
+
+/// A container that lays out its children in a grid of rows and columns.
+///
+/// Each child is placed in a cell, given by its row and column, and can span
+/// several rows and columns. Children are given exactly the size of the cells
+/// they cover; wrap a child in an [`Align`] to have it keep its own size
+/// instead.
+///
+/// The size of each row and column is given by a [`TrackSize`]. Fixed tracks
+/// are sized first, then auto tracks are made large enough for the children
+/// in them, and finally the remaining space is shared between the flexible
+/// tracks. When a child spans several tracks and doesn't fit, the auto
+/// tracks it spans are grown evenly. Rows and columns that are not declared
+/// with [`with_row`] or [`with_column`] are auto tracks.
+///
+/// Cells can't overlap: adding a child that covers a cell already covered by
+/// an earlier child replaces the earlier child, and logs a warning.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Grid, Label, TextBox, TrackSize};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Contact {
+///     name: String,
+///     email: String,
+///     notes: String,
+/// }
+///
+/// fn contact_form() -> impl Widget<Contact> {
+///     Grid::new()
+///         .with_column(TrackSize::Auto)
+///         .with_column(TrackSize::Flex(1.0))
+///         .with_gap(8.0)
+///         .with_child(Label::new("Name"), 0, 0, 1, 1)
+///         .with_child(TextBox::new().lens(Contact::name), 0, 1, 1, 1)
+///         .with_child(Label::new("Email"), 1, 0, 1, 1)
+///         .with_child(TextBox::new().lens(Contact::email), 1, 1, 1, 1)
+///         .with_child(TextBox::multiline().lens(Contact::notes), 2, 0, 1, 2)
+/// }
+/// ```
+///
+/// [`Align`]: crate::widget::Align
+/// [`with_row`]: Grid::with_row
+/// [`with_column`]: Grid::with_column
+pub struct Grid<T> {
+    children: Vec<GridChild<T>>,
+    rows: Vec<TrackSize>,
+    columns: Vec<TrackSize>,
+    row_gap: KeyOrValue<f64>,
+    column_gap: KeyOrValue<f64>,
+}
+
+struct GridChild<T> {
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    area: Area,
+}
+
+/// The cells covered by a child.
+#[derive(Debug, Clone, PartialEq)]
+struct Area {
+    rows: Range<usize>,
+    columns: Range<usize>,
+}
+
+impl Area {
+    fn overlaps(&self, other: &Area) -> bool {
+        fn overlap(a: &Range<usize>, b: &Range<usize>) -> bool {
+            a.start < b.end && b.start < a.end
+        }
+        overlap(&self.rows, &other.rows) && overlap(&self.columns, &other.columns)
+    }
+}
+
+impl<T: Data> Grid<T> {
+    /// Create a new, empty grid.
+    pub fn new() -> Self {
+        Grid {
+            children: Vec::new(),
+            rows: Vec::new(),
+            columns: Vec::new(),
+            row_gap: 0.0.into(),
+            column_gap: 0.0.into(),
+        }
+    }
+
+    /// Builder-style method for declaring the next row.
+    ///
+    /// Rows are declared from the top down.
+    pub fn with_row(mut self, size: TrackSize) -> Self {
+        self.rows.push(size);
+        self
+    }
+
+    /// Builder-style method for declaring the next column.
+    ///
+    /// Columns are declared from left to right.
+    pub fn with_column(mut self, size: TrackSize) -> Self {
+        self.columns.push(size);
+        self
+    }
+
+    /// Builder-style method for setting the space between rows and between
+    /// columns.
+    pub fn with_gap(mut self, gap: impl Into<KeyOrValue<f64>> + Clone) -> Self {
+        self.set_row_gap(gap.clone());
+        self.set_column_gap(gap);
+        self
+    }
+
+    /// Builder-style method for setting the space between rows.
+    pub fn with_row_gap(mut self, gap: impl Into<KeyOrValue<f64>>) -> Self {
+        self.set_row_gap(gap);
+        self
+    }
+
+    /// Builder-style method for setting the space between columns.
+    pub fn with_column_gap(mut self, gap: impl Into<KeyOrValue<f64>>) -> Self {
+        self.set_column_gap(gap);
+        self
+    }
+
+    /// Builder-style variant of [`add_child`].
+    ///
+    /// [`add_child`]: Grid::add_child
+    pub fn with_child(
+        mut self,
+        child: impl Widget<T> + 'static,
+        row: usize,
+        column: usize,
+        row_span: usize,
+        column_span: usize,
+    ) -> Self {
+        self.add_child(child, row, column, row_span, column_span);
+        self
+    }
+
+    /// Set the size of the row at `index`, declaring it and the rows before
+    /// it if needed.
+    pub fn set_row(&mut self, index: usize, size: TrackSize) {
+        set_track(&mut self.rows, index, size);
+    }
+
+    /// Set the size of the column at `index`, declaring it and the columns
+    /// before it if needed.
+    pub fn set_column(&mut self, index: usize, size: TrackSize) {
+        set_track(&mut self.columns, index, size);
+    }
+
+    /// Set the space between rows.
+    pub fn set_row_gap(&mut self, gap: impl Into<KeyOrValue<f64>>) {
+        self.row_gap = gap.into();
+    }
+
+    /// Set the space between columns.
+    pub fn set_column_gap(&mut self, gap: impl Into<KeyOrValue<f64>>) {
+        self.column_gap = gap.into();
+    }
+
+    /// Add a child, covering `row_span` rows starting at `row` and
+    /// `column_span` columns starting at `column`.
+    ///
+    /// Any earlier child that covers one of the same cells is removed.
+    ///
+    /// If this is called after the grid has been added to the widget tree,
+    /// [`children_changed`] must be called.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if one of the spans is zero.
+    ///
+    /// [`children_changed`]: crate::EventCtx::children_changed
+    pub fn add_child(
+        &mut self,
+        child: impl Widget<T> + 'static,
+        row: usize,
+        column: usize,
+        row_span: usize,
+        column_span: usize,
+    ) {
+        if row_span == 0 || column_span == 0 {
+            debug_panic!(
+                "Grid spans should be at least 1. Spans given were: {}x{}",
+                row_span,
+                column_span
+            );
+        }
+        let area = Area {
+            rows: row..row + row_span.max(1),
+            columns: column..column + column_span.max(1),
+        };
+        let before = self.children.len();
+        self.children.retain(|child| !child.area.overlaps(&area));
+        if self.children.len() < before {
+            warn!(
+                "Grid child at row {}, column {} replaces {} overlapping children",
+                row,
+                column,
+                before - self.children.len()
+            );
+        }
+        self.children.push(GridChild {
+            widget: WidgetPod::new(Box::new(child)),
+            area,
+        });
+    }
+
+    /// The number of rows and columns, including the ones that are only
+    /// implied by the children.
+    fn track_counts(&self) -> (usize, usize) {
+        self.children.iter().fold(
+            (self.rows.len(), self.columns.len()),
+            |(rows, columns), child| {
+                (
+                    rows.max(child.area.rows.end),
+                    columns.max(child.area.columns.end),
+                )
+            },
+        )
+    }
+}
+
+impl<T: Data> Default for Grid<T> {
+    fn default() -> Self {
+        Grid::new()
+    }
+}
+
+fn set_track(tracks: &mut Vec<TrackSize>, index: usize, size: TrackSize) {
+    if tracks.len() <= index {
+        tracks.resize(index + 1, TrackSize::Auto);
+    }
+    tracks[index] = size;
+}
+
+/// Compute the sizes of `count` tracks.
+///
+/// `available` is the space for the tracks and the gaps between them, if it
+/// is bounded. `items` are the tracks covered by each child that needs to be
+/// measured, with its measured size.
+fn resolve_tracks(
+    tracks: &[TrackSize],
+    count: usize,
+    available: Option<f64>,
+    gap: f64,
+    items: &[(Range<usize>, f64)],
+) -> Vec<f64> {
+    let kind = |i: usize| tracks.get(i).copied().unwrap_or_default();
+    let mut sizes: Vec<f64> = (0..count)
+        .map(|i| match kind(i) {
+            TrackSize::Fixed(size) => size.max(0.0),
+            _ => 0.0,
+        })
+        .collect();
+
+    // Children spanning a single track are handled first, so that spanning
+    // children only grow the tracks when they really need to.
+    let mut items: Vec<_> = items.iter().collect();
+    items.sort_by_key(|(tracks, _)| tracks.len());
+    for (range, size) in items {
+        let current: f64 =
+            sizes[range.clone()].iter().sum::<f64>() + gap * (range.len() - 1) as f64;
+        let measured: Vec<usize> = range
+            .clone()
+            .filter(|i| is_measured(kind(*i), available.is_some()))
+            .collect();
+        if *size > current && !measured.is_empty() {
+            let extra = (size - current) / measured.len() as f64;
+            for i in measured {
+                sizes[i] += extra;
+            }
+        }
+    }
+
+    if let Some(available) = available {
+        let total_flex: f64 = (0..count)
+            .filter_map(|i| match kind(i) {
+                TrackSize::Flex(flex) => Some(flex.max(0.0)),
+                _ => None,
+            })
+            .sum();
+        if total_flex > 0.0 {
+            let used = sizes.iter().sum::<f64>() + gap * count.saturating_sub(1) as f64;
+            let remaining = (available - used).max(0.0);
+            for (i, size) in sizes.iter_mut().enumerate() {
+                if let TrackSize::Flex(flex) = kind(i) {
+                    *size = remaining * flex.max(0.0) / total_flex;
+                }
+            }
+        }
+    }
+    sizes
+}
+
+/// Whether the size of a track depends on the size of its children.
+fn is_measured(track: TrackSize, bounded: bool) -> bool {
+    match track {
+        TrackSize::Fixed(_) => false,
+        TrackSize::Auto => true,
+        TrackSize::Flex(_) => !bounded,
+    }
+}
+
+/// The start of each track, followed by the end of the last one.
+fn offsets(sizes: &[f64], gap: f64) -> Vec<f64> {
+    let mut offsets = Vec::with_capacity(sizes.len() + 1);
+    let mut offset = 0.0;
+    for (i, size) in sizes.iter().enumerate() {
+        offsets.push(offset);
+        offset += size;
+        if i + 1 < sizes.len() {
+            offset += gap;
+        }
+    }
+    offsets.push(offset);
+    offsets
+}
+
+/// The extent of the tracks in `range`, and the gaps between them.
+fn span(offsets: &[f64], sizes: &[f64], range: &Range<usize>) -> (f64, f64) {
+    let start = offsets[range.start];
+    let last = range.end - 1;
+    (start, offsets[last] + sizes[last] - start)
+}
+
+impl<T: Data> Widget<T> for Grid<T> {
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.update(ctx, data, env);
+        }
+        if ctx.env_key_changed(&self.row_gap) || ctx.env_key_changed(&self.column_gap) {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Grid");
+        let row_gap = self.row_gap.resolve(env).max(0.0);
+        let column_gap = self.column_gap.resolve(env).max(0.0);
+        let (row_count, column_count) = self.track_counts();
+        let (row_tracks, column_tracks) = (&self.rows, &self.columns);
+
+        // Columns first, measuring the children with unbounded constraints.
+        let width_bounded = bc.is_width_bounded();
+        let mut items = Vec::new();
+        for child in &mut self.children {
+            let columns = child.area.columns.clone();
+            if columns.clone().any(|i| {
+                is_measured(
+                    column_tracks.get(i).copied().unwrap_or_default(),
+                    width_bounded,
+                )
+            }) {
+                let size = child
+                    .widget
+                    .layout(ctx, &BoxConstraints::UNBOUNDED, data, env);
+                items.push((columns, size.width));
+            }
+        }
+        let available = if width_bounded {
+            Some(bc.max().width)
+        } else {
+            None
+        };
+        let column_sizes =
+            resolve_tracks(column_tracks, column_count, available, column_gap, &items);
+        let column_offsets = offsets(&column_sizes, column_gap);
+
+        // Then rows, measuring the children at the width of their columns.
+        let height_bounded = bc.is_height_bounded();
+        items.clear();
+        for child in &mut self.children {
+            let rows = child.area.rows.clone();
+            if rows.clone().any(|i| {
+                is_measured(
+                    row_tracks.get(i).copied().unwrap_or_default(),
+                    height_bounded,
+                )
+            }) {
+                let (_, width) = span(&column_offsets, &column_sizes, &child.area.columns);
+                let child_bc =
+                    BoxConstraints::new(Size::new(width, 0.0), Size::new(width, f64::INFINITY));
+                let size = child.widget.layout(ctx, &child_bc, data, env);
+                items.push((rows, size.height));
+            }
+        }
+        let available = if height_bounded {
+            Some(bc.max().height)
+        } else {
+            None
+        };
+        let row_sizes = resolve_tracks(row_tracks, row_count, available, row_gap, &items);
+        let row_offsets = offsets(&row_sizes, row_gap);
+
+        let mut paint_rect = Rect::ZERO;
+        for child in &mut self.children {
+            let (x, width) = span(&column_offsets, &column_sizes, &child.area.columns);
+            let (y, height) = span(&row_offsets, &row_sizes, &child.area.rows);
+            let child_bc = BoxConstraints::tight(Size::new(width, height));
+            child.widget.layout(ctx, &child_bc, data, env);
+            child.widget.set_origin(ctx, data, env, Point::new(x, y));
+            paint_rect = paint_rect.union(child.widget.paint_rect());
+        }
+
+        let size = bc.constrain(Size::new(
+            column_offsets.last().copied().unwrap_or_default(),
+            row_offsets.last().copied().unwrap_or_default(),
+        ));
+        ctx.set_paint_insets(paint_rect - size.to_rect());
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.widget.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::widget::SizedBox;
+    use crate::WidgetExt;
+    use test_log::test;
+
+    #[test]
+    fn tracks_are_resolved_in_order() {
+        let tracks = [
+            TrackSize::Fixed(50.0),
+            TrackSize::Auto,
+            TrackSize::Flex(1.0),
+        ];
+        // The third track is implicitly auto.
+        let items = [(1..2, 30.0), (1..2, 20.0), (3..4, 10.0), (0..2, 100.0)];
+        let sizes = resolve_tracks(&tracks, 4, Some(200.0), 5.0, &items);
+        // The spanning child grows the auto track to 100 - 50 - 5.
+        assert_eq!(sizes, vec![50.0, 45.0, 80.0, 10.0]);
+
+        // Unbounded, the flex track is measured like an auto one.
+        let items = [(2..3, 25.0)];
+        let sizes = resolve_tracks(&tracks, 3, None, 0.0, &items);
+        assert_eq!(sizes, vec![50.0, 0.0, 25.0]);
+
+        assert_eq!(offsets(&[10.0, 20.0], 5.0), vec![0.0, 15.0, 35.0]);
+    }
+
+    #[test]
+    fn spanning_children_and_overlaps() {
+        let [label_id, field_id, wide_id] = widget_ids();
+        let grid = Grid::<()>::new()
+            .with_column(TrackSize::Auto)
+            .with_column(TrackSize::Flex(1.0))
+            .with_gap(10.0)
+            .with_child(SizedBox::empty().width(50.0).height(20.0), 0, 0, 1, 1)
+            .with_child(SizedBox::empty().width(80.0).with_id(label_id), 0, 0, 1, 1)
+            .with_child(SizedBox::empty().height(30.0).with_id(field_id), 0, 1, 1, 1)
+            .with_child(SizedBox::empty().height(40.0).with_id(wide_id), 1, 0, 1, 2)
+            .fix_width(300.0)
+            .center();
+
+        Harness::create_simple((), grid, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let origin = harness.get_state(label_id).window_origin();
+            let rect = |harness: &mut Harness<()>, id| {
+                let state = harness.get_state(id);
+                let origin = (state.window_origin() - origin).to_point();
+                Rect::from_origin_size(origin, state.layout_rect().size())
+            };
+
+            // The first child was replaced, so the first column fits the label.
+            assert_eq!(rect(harness, label_id), Rect::new(0.0, 0.0, 80.0, 30.0));
+            assert_eq!(rect(harness, field_id), Rect::new(90.0, 0.0, 300.0, 30.0));
+            assert_eq!(rect(harness, wide_id), Rect::new(0.0, 40.0, 300.0, 80.0));
+        });
+    }
+}
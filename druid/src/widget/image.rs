@@ -15,14 +15,31 @@
 //! An Image widget.
 //! Please consider using SVG and the SVG widget as it scales much better.
 
+use std::error::Error;
+use std::sync::Arc;
+
 use crate::{
     kurbo::Rect,
     piet::{Image as _, ImageBuf, InterpolationMode, PietImage},
     widget::common::FillStrat,
     widget::prelude::*,
-    Data,
+    Data, ExtEventSink, Selector, SingleUse, Target,
 };
-use tracing::{instrument, trace};
+use tracing::{instrument, trace, warn};
+
+/// The result of loading an image on a background thread.
+///
+/// This is the same as the return type of [`ImageBuf::from_file`], so such
+/// functions can be used as loaders directly.
+type LoadResult = Result<ImageBuf, Box<dyn Error + Send + Sync>>;
+
+type KeyFn<T, K> = dyn Fn(&T, &Env) -> K;
+type LoaderFn<K> = dyn Fn(&K) -> LoadResult + Send + Sync;
+
+/// Sent by a background loader to the widget that requested it, along with the
+/// generation of the request, so that stale results can be discarded.
+const IMAGE_LOADED: Selector<SingleUse<(u64, LoadResult)>> =
+    Selector::new("druid-builtin.image-loaded");
 
 /// A widget that renders a bitmap Image.
 ///
@@ -67,6 +84,22 @@ use tracing::{instrument, trace};
 /// image_widget.set_interpolation_mode(InterpolationMode::Bilinear);
 /// ```
 ///
+/// Load an image on a background thread, showing a placeholder until it arrives
+/// ```
+/// use druid::{widget::Image, piet::ImageBuf};
+///
+/// # fn load_image_from_disk() -> Result<ImageBuf, Box<dyn std::error::Error + Send + Sync>> {
+/// #     Ok(ImageBuf::empty())
+/// # }
+/// let placeholder = ImageBuf::empty();
+/// let fallback = ImageBuf::empty();
+/// let image_widget = Image::lazy(load_image_from_disk)
+///     // shown while the image is loading
+///     .placeholder(placeholder)
+///     // shown if loading fails
+///     .fallback(fallback);
+/// ```
+///
 /// [scaling a bitmap image]: ../struct.Scale.html#pixels-and-display-points
 /// [SVG files]: https://en.wikipedia.org/wiki/Scalable_Vector_Graphics
 pub struct Image {
@@ -75,6 +108,19 @@ pub struct Image {
     fill: FillStrat,
     interpolation: InterpolationMode,
     clip_area: Option<Rect>,
+    placeholder: Option<ImageBuf>,
+    fallback: Option<ImageBuf>,
+    load_state: LoadState,
+}
+
+/// The state of an image that is loaded in the background.
+enum LoadState {
+    /// There is no loading in progress.
+    Idle,
+    /// The loader will be started once the widget is added to the tree.
+    Pending(Box<dyn FnOnce() -> LoadResult + Send>),
+    /// A loader with the given generation is running.
+    Loading(u64),
 }
 
 impl Image {
@@ -95,7 +141,61 @@ impl Image {
             fill: FillStrat::default(),
             interpolation: InterpolationMode::Bilinear,
             clip_area: None,
+            placeholder: None,
+            fallback: None,
+            load_state: LoadState::Idle,
+        }
+    }
+
+    /// Create an image drawing widget that loads its image on a background thread.
+    ///
+    /// The `loader` is run once the widget is added to the widget tree. Until it
+    /// finishes, the [`placeholder`] is shown (an empty image by default). If the
+    /// loader fails, the [`fallback`] is shown instead, if one is set; otherwise
+    /// the placeholder stays in place.
+    ///
+    /// If the widget is removed before the loader finishes, the result is discarded.
+    ///
+    /// On `wasm32` there are no threads: the loader runs synchronously on the UI
+    /// thread when loading starts, and blocks it until the loader returns.
+    ///
+    /// [`placeholder`]: Image::placeholder
+    /// [`fallback`]: Image::fallback
+    pub fn lazy(
+        loader: impl FnOnce() -> Result<ImageBuf, Box<dyn Error + Send + Sync>> + Send + 'static,
+    ) -> Self {
+        let mut image = Image::new(ImageBuf::empty());
+        image.load_state = LoadState::Pending(Box::new(loader));
+        image
+    }
+
+    /// Builder-style method for setting the image shown while loading.
+    pub fn placeholder(mut self, placeholder: ImageBuf) -> Self {
+        self.set_placeholder(Some(placeholder));
+        self
+    }
+
+    /// Set the image shown while loading.
+    ///
+    /// If an image is currently being loaded, the placeholder is shown immediately.
+    pub fn set_placeholder(&mut self, placeholder: Option<ImageBuf>) {
+        if !matches!(self.load_state, LoadState::Idle) {
+            self.set_image_data(placeholder.clone().unwrap_or_else(ImageBuf::empty));
         }
+        self.placeholder = placeholder;
+    }
+
+    /// Builder-style method for setting the image shown if loading fails.
+    #[inline]
+    pub fn fallback(mut self, fallback: ImageBuf) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Set the image shown if loading fails.
+    #[inline]
+    pub fn set_fallback(&mut self, fallback: Option<ImageBuf>) {
+        self.fallback = fallback;
     }
 
     /// Builder-style method for specifying the fill strategy.
@@ -159,14 +259,86 @@ impl Image {
     fn invalidate(&mut self) {
         self.paint_data = None;
     }
+
+    /// Run `loader` on a background thread (or right away on `wasm32`), replacing
+    /// any load that is in progress.
+    ///
+    /// The result is sent to the widget with the given `id`, which must be the id of
+    /// the widget containing this `Image`.
+    fn start_loading(
+        &mut self,
+        sink: ExtEventSink,
+        id: WidgetId,
+        loader: impl FnOnce() -> LoadResult + Send + 'static,
+    ) {
+        let generation = match self.load_state {
+            LoadState::Loading(generation) => generation.wrapping_add(1),
+            _ => 0,
+        };
+        self.load_state = LoadState::Loading(generation);
+        if let Some(placeholder) = self.placeholder.clone() {
+            self.set_image_data(placeholder);
+        }
+        let load = move || {
+            let result = loader();
+            // If the app has quit, there is nobody left to tell.
+            let _ = sink.submit_command(
+                IMAGE_LOADED,
+                SingleUse::new((generation, result)),
+                Target::Widget(id),
+            );
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(load);
+        #[cfg(target_arch = "wasm32")]
+        load();
+    }
+
+    /// Handle the result of a background load. Returns `true` if the result
+    /// was for the current load.
+    fn finish_loading(&mut self, generation: u64, result: LoadResult) -> bool {
+        if !matches!(self.load_state, LoadState::Loading(current) if current == generation) {
+            return false;
+        }
+        self.load_state = LoadState::Idle;
+        match result {
+            Ok(image_data) => self.set_image_data(image_data),
+            Err(e) => {
+                warn!("failed to load image: {}", e);
+                if let Some(fallback) = self.fallback.clone() {
+                    self.set_image_data(fallback);
+                }
+            }
+        }
+        true
+    }
 }
 
 impl<T: Data> Widget<T> for Image {
-    #[instrument(name = "Image", level = "trace", skip(self, _ctx, _event, _data, _env))]
-    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+    #[instrument(name = "Image", level = "trace", skip(self, ctx, event, _data, _env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some((generation, result)) = cmd.get(IMAGE_LOADED).and_then(SingleUse::take) {
+                ctx.set_handled();
+                if self.finish_loading(generation, result) {
+                    trace!("Image {:?} loaded", ctx.widget_id());
+                    ctx.request_layout();
+                }
+            }
+        }
+    }
 
-    #[instrument(name = "Image", level = "trace", skip(self, _ctx, _event, _data, _env))]
-    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &T, _env: &Env) {}
+    #[instrument(name = "Image", level = "trace", skip(self, ctx, event, _data, _env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            match std::mem::replace(&mut self.load_state, LoadState::Idle) {
+                LoadState::Pending(loader) => {
+                    self.start_loading(ctx.get_external_handle(), ctx.widget_id(), loader)
+                }
+                other => self.load_state = other,
+            }
+        }
+    }
 
     #[instrument(
         name = "Image",
@@ -253,6 +425,120 @@ impl<T: Data> Widget<T> for Image {
     }
 }
 
+/// An [`Image`] whose image is chosen by the data and loaded on a background thread.
+///
+/// A key, such as a path or a URL, is computed from the data. Whenever the key
+/// changes, the loader is run with the new key on a background thread, and the
+/// inner `Image` shows its [`placeholder`] until the image arrives, or its
+/// [`fallback`] if loading fails. Results for outdated keys are discarded.
+///
+/// As with [`Image::lazy`], the loader runs synchronously on the UI thread on
+/// `wasm32`, blocking it while it loads.
+///
+/// # Example
+///
+/// ```
+/// use druid::{widget::{Image, LazyImage}, piet::ImageBuf, Data, Lens, Widget};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Profile {
+///     avatar_path: String,
+/// }
+///
+/// # fn load_image_from_disk(_path: &str) -> Result<ImageBuf, Box<dyn std::error::Error + Send + Sync>> {
+/// #     Ok(ImageBuf::empty())
+/// # }
+/// fn avatar() -> impl Widget<Profile> {
+///     LazyImage::new(
+///         Image::new(ImageBuf::empty()).fallback(ImageBuf::empty()),
+///         |data: &Profile, _env| data.avatar_path.clone(),
+///         |path: &String| load_image_from_disk(path),
+///     )
+/// }
+/// ```
+///
+/// [`placeholder`]: Image::placeholder
+/// [`fallback`]: Image::fallback
+pub struct LazyImage<T, K> {
+    image: Image,
+    key: Box<KeyFn<T, K>>,
+    loader: Arc<LoaderFn<K>>,
+    current_key: Option<K>,
+}
+
+impl<T: Data, K: Data + Send> LazyImage<T, K> {
+    /// Create a new `LazyImage`.
+    ///
+    /// The `image` is used to display the loaded images, and its configuration
+    /// (fill strategy, placeholder, fallback, ...) is kept. The `key` closure
+    /// computes the key from the data, and `loader` turns a key into an image.
+    pub fn new(
+        image: Image,
+        key: impl Fn(&T, &Env) -> K + 'static,
+        loader: impl Fn(&K) -> Result<ImageBuf, Box<dyn Error + Send + Sync>> + Send + Sync + 'static,
+    ) -> Self {
+        LazyImage {
+            image,
+            key: Box::new(key),
+            loader: Arc::new(loader),
+            current_key: None,
+        }
+    }
+
+    /// Start loading the image for `key`, if it differs from the current one.
+    ///
+    /// Returns `true` if a new load was started.
+    fn load_if_changed(&mut self, sink: ExtEventSink, id: WidgetId, key: K) -> bool {
+        if matches!(&self.current_key, Some(current) if current.same(&key)) {
+            return false;
+        }
+        self.current_key = Some(key.clone());
+        let loader = self.loader.clone();
+        self.image.start_loading(sink, id, move || loader(&key));
+        true
+    }
+}
+
+impl<T: Data, K: Data + Send> Widget<T> for LazyImage<T, K> {
+    #[instrument(name = "LazyImage", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.image.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "LazyImage", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let key = (self.key)(data, env);
+            self.load_if_changed(ctx.get_external_handle(), ctx.widget_id(), key);
+        }
+        self.image.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "LazyImage",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let key = (self.key)(data, env);
+        if self.load_if_changed(ctx.get_external_handle(), ctx.widget_id(), key) {
+            // We may be showing the placeholder now.
+            ctx.request_layout();
+        }
+        self.image.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "LazyImage", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.image.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "LazyImage", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.image.paint(ctx, data, env);
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
@@ -514,4 +800,70 @@ mod tests {
             },
         )
     }
+
+    #[test]
+    fn lazy_loading() {
+        use crate::{tests::harness::Harness, widget::Container, WidgetExt, WidgetId};
+
+        let id = WidgetId::next();
+        let placeholder = ImageBuf::from_raw(vec![0; 3], ImageFormat::Rgb, 1, 1);
+        let loaded = ImageBuf::from_raw(vec![0; 2 * 3 * 3], ImageFormat::Rgb, 2, 3);
+
+        // The background result is sent to a harness that doesn't run the
+        // external event loop, so we deliver results by hand below.
+        let image_widget =
+            Container::new(Image::lazy(|| Err("never delivered".into())).placeholder(placeholder))
+                .with_id(id)
+                .center();
+
+        Harness::create_simple((), image_widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(1., 1.)
+            );
+
+            // A result from an outdated load is ignored.
+            let stale = SingleUse::new((1, Ok(loaded.clone())));
+            harness.submit_command(IMAGE_LOADED.with(stale).to(id));
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(1., 1.)
+            );
+
+            let current = SingleUse::new((0, Ok(loaded.clone())));
+            harness.submit_command(IMAGE_LOADED.with(current).to(id));
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(2., 3.)
+            );
+        });
+    }
+
+    #[test]
+    fn lazy_loading_fallback() {
+        use crate::{tests::harness::Harness, widget::Container, WidgetExt, WidgetId};
+
+        let id = WidgetId::next();
+        let fallback = ImageBuf::from_raw(vec![0; 4 * 3], ImageFormat::Rgb, 4, 1);
+
+        let image_widget =
+            Container::new(Image::lazy(|| Err("never delivered".into())).fallback(fallback))
+                .with_id(id)
+                .center();
+
+        Harness::create_simple((), image_widget, |harness| {
+            harness.send_initial_events();
+            let failed = SingleUse::new((0, Err("failed".into())));
+            harness.submit_command(IMAGE_LOADED.with(failed).to(id));
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(4., 1.)
+            );
+        });
+    }
 }
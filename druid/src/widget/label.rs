@@ -19,13 +19,14 @@ use std::sync::Arc;
 
 use druid_shell::Cursor;
 
+use crate::accessibility::Role;
 use crate::debug_state::DebugState;
 use crate::kurbo::Vec2;
-use crate::text::TextStorage;
+use crate::text::{Selection, TextStorage};
 use crate::widget::prelude::*;
 use crate::{
-    ArcStr, Color, Data, FontDescriptor, KeyOrValue, LocalizedString, Point, TextAlignment,
-    TextLayout,
+    commands, theme, Application, ArcStr, Color, Data, FontDescriptor, HotKey, KeyOrValue,
+    LocalizedString, Point, SysMods, TextAlignment, TextLayout,
 };
 use tracing::{instrument, trace};
 
@@ -93,12 +94,21 @@ pub struct Label<T> {
 ///
 /// This requires the `Data` to implement [`TextStorage`]; to handle static, dynamic, or
 /// localized text, use [`Label`].
+///
+/// The text can be made selectable with [`selectable`]. The selection is never
+/// written back to the data; it is cleared when the text changes or the label
+/// loses focus.
+///
+/// [`selectable`]: RawLabel::selectable
 pub struct RawLabel<T> {
     layout: TextLayout<T>,
     line_break_mode: LineBreaking,
 
     disabled: bool,
     default_text_color: KeyOrValue<Color>,
+
+    selectable: bool,
+    selection: Selection,
 }
 
 /// Options for handling lines that are too wide for the label.
@@ -160,6 +170,8 @@ impl<T: TextStorage> RawLabel<T> {
             line_break_mode: LineBreaking::Overflow,
             disabled: false,
             default_text_color: crate::theme::TEXT_COLOR.into(),
+            selectable: false,
+            selection: Selection::caret(0),
         }
     }
 
@@ -212,6 +224,69 @@ impl<T: TextStorage> RawLabel<T> {
         self
     }
 
+    /// Builder-style method to allow selecting the text.
+    ///
+    /// See [`set_selectable`] for more details.
+    ///
+    /// [`set_selectable`]: RawLabel::set_selectable
+    pub fn selectable(mut self, selectable: bool) -> Self {
+        self.set_selectable(selectable);
+        self
+    }
+
+    /// Set whether the text can be selected.
+    ///
+    /// The text of a selectable label can be selected by dragging with the mouse,
+    /// or by shift-clicking. Clicking the label gives it keyboard focus, and while
+    /// it is focused all of its text can be selected with Ctrl+A (Cmd+A on macOS),
+    /// and the selection copied to the clipboard with Ctrl+C (Cmd+C). The
+    /// [`COPY`] and [`SELECT_ALL`] commands are handled as well.
+    ///
+    /// The selection is highlighted with [`SELECTED_TEXT_BACKGROUND_COLOR`]. The
+    /// text itself can't be edited.
+    ///
+    /// [`COPY`]: crate::commands::COPY
+    /// [`SELECT_ALL`]: crate::commands::SELECT_ALL
+    /// [`SELECTED_TEXT_BACKGROUND_COLOR`]: crate::theme::SELECTED_TEXT_BACKGROUND_COLOR
+    pub fn set_selectable(&mut self, selectable: bool) {
+        self.selectable = selectable;
+        if !selectable {
+            self.selection = Selection::caret(0);
+        }
+    }
+
+    /// Returns the currently selected text, if any.
+    pub fn selected_text(&self) -> Option<&str> {
+        let text = self.layout.text()?.as_str();
+        Some(&text[self.selection.range()]).filter(|text| !text.is_empty())
+    }
+
+    fn select_all(&mut self) -> bool {
+        let len = self
+            .layout
+            .text()
+            .map(|text| text.as_str().len())
+            .unwrap_or(0);
+        self.set_selection(Selection::new(0, len))
+    }
+
+    /// Set the selection, returning `true` if it changed.
+    fn set_selection(&mut self, selection: Selection) -> bool {
+        let changed = selection.range() != self.selection.range();
+        self.selection = selection;
+        changed
+    }
+
+    fn copy_selection(&self) -> bool {
+        match self.selected_text() {
+            Some(text) => {
+                Application::global().clipboard().put_string(text);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Set the text color.
     ///
     /// The argument can be either a `Color` or a [`Key<Color>`].
@@ -423,6 +498,14 @@ impl<T: Data> Label<T> {
         self
     }
 
+    /// Builder-style method to allow selecting the text.
+    ///
+    /// See [`RawLabel::set_selectable`] for more details.
+    pub fn selectable(mut self, selectable: bool) -> Self {
+        self.label.set_selectable(selectable);
+        self
+    }
+
     /// Draw this label's text at the provided `Point`, without internal padding.
     ///
     /// This is a convenience for widgets that want to use Label as a way
@@ -490,14 +573,19 @@ impl<T: Data> LabelText<T> {
 }
 
 impl<T: Data> Widget<T> for Label<T> {
-    #[instrument(name = "Label", level = "trace", skip(self, _ctx, _event, _data, _env))]
-    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+    #[instrument(name = "Label", level = "trace", skip(self, ctx, event, _data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
+        self.label
+            .event(ctx, event, &mut self.current_text.clone(), env);
+    }
 
     #[instrument(name = "Label", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
         if matches!(event, LifeCycle::WidgetAdded) {
             self.text.resolve(data, env);
             self.text_should_be_updated = false;
+            ctx.set_access_role(Role::Label);
+            ctx.set_access_name(self.text.display_text().to_string());
         }
         self.label
             .lifecycle(ctx, event, &self.text.display_text(), env);
@@ -509,6 +597,7 @@ impl<T: Data> Widget<T> for Label<T> {
         self.text_should_be_updated = false;
         if data_changed {
             let new_text = self.text.display_text();
+            ctx.set_access_name(new_text.to_string());
             self.label.update(ctx, &self.current_text, &new_text, env);
             self.current_text = new_text;
         } else if ctx.env_changed() {
@@ -531,9 +620,14 @@ impl<T: Data> Widget<T> for Label<T> {
     }
 
     fn debug_state(&self, _data: &T) -> DebugState {
+        let mut other_values = std::collections::HashMap::new();
+        if let Some(text) = self.label.selected_text() {
+            other_values.insert("selection".to_string(), text.to_string());
+        }
         DebugState {
             display_name: self.short_type_name().to_string(),
             main_value: self.current_text.to_string(),
+            other_values,
             ..Default::default()
         }
     }
@@ -546,23 +640,86 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
         skip(self, ctx, event, _data, _env)
     )]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        let selectable = self.selectable && !ctx.is_disabled();
         match event {
+            Event::MouseDown(mouse) if selectable && mouse.button.is_left() => {
+                let pos = mouse.pos - Vec2::new(LABEL_X_PADDING, 0.0);
+                let offset = self.layout.text_position_for_point(pos);
+                let selection = if mouse.mods.shift() {
+                    Selection::new(self.selection.anchor, offset)
+                } else {
+                    Selection::caret(offset)
+                };
+                if self.set_selection(selection) {
+                    ctx.request_paint();
+                }
+                ctx.set_active(true);
+                ctx.request_focus();
+            }
+            Event::MouseMove(mouse) if selectable && ctx.is_active() => {
+                let pos = mouse.pos - Vec2::new(LABEL_X_PADDING, 0.0);
+                let offset = self.layout.text_position_for_point(pos);
+                if self.set_selection(Selection::new(self.selection.anchor, offset)) {
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() && !self.selection.is_caret() => {
+                // The end of a selection is not a click on a link.
+                ctx.set_active(false);
+            }
             Event::MouseUp(event) => {
+                if ctx.is_active() {
+                    ctx.set_active(false);
+                }
                 // Account for the padding
                 let pos = event.pos - Vec2::new(LABEL_X_PADDING, 0.0);
-                if let Some(link) = self.layout.link_for_pos(pos) {
-                    ctx.submit_command(link.command.clone());
+                if let Some(i) = self.layout.link_index_for_pos(pos) {
+                    if let Some(link) = self.layout.link_for_pos(pos) {
+                        ctx.submit_command(link.command.clone());
+                    }
+                    if self.layout.set_link_visited(i) {
+                        ctx.request_layout();
+                    }
                 }
             }
             Event::MouseMove(event) => {
                 // Account for the padding
                 let pos = event.pos - Vec2::new(LABEL_X_PADDING, 0.0);
+                let hovered = self.layout.link_index_for_pos(pos);
 
-                if self.layout.link_for_pos(pos).is_some() {
+                if hovered.is_some() {
                     ctx.set_cursor(&Cursor::Pointer);
+                } else if selectable {
+                    ctx.set_cursor(&Cursor::IBeam);
                 } else {
                     ctx.clear_cursor();
                 }
+                if self.layout.set_hovered_link(hovered) {
+                    ctx.request_layout();
+                }
+            }
+            Event::KeyDown(key) if selectable && ctx.has_focus() => {
+                if HotKey::new(SysMods::Cmd, "a").matches(key) {
+                    if self.select_all() {
+                        ctx.request_paint();
+                    }
+                    ctx.set_handled();
+                } else if HotKey::new(SysMods::Cmd, "c").matches(key) {
+                    self.copy_selection();
+                    ctx.set_handled();
+                }
+            }
+            Event::Command(cmd) if selectable && ctx.is_focused() && cmd.is(commands::COPY) => {
+                self.copy_selection();
+                ctx.set_handled();
+            }
+            Event::Command(cmd)
+                if selectable && ctx.is_focused() && cmd.is(commands::SELECT_ALL) =>
+            {
+                if self.select_all() {
+                    ctx.request_paint();
+                }
+                ctx.set_handled();
             }
             _ => {}
         }
@@ -581,10 +738,20 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
                     self.default_text_color.clone()
                 };
                 self.layout.set_text_color(color);
+                if *disabled {
+                    self.selection = Selection::caret(0);
+                }
                 ctx.request_layout();
             }
+            LifeCycle::FocusChanged(false) if !self.selection.is_caret() => {
+                self.selection = Selection::caret(self.selection.active);
+                ctx.request_paint();
+            }
             _ => {}
         }
+        if matches!(event, LifeCycle::HotChanged(false)) && self.layout.set_hovered_link(None) {
+            ctx.request_layout();
+        }
     }
 
     #[instrument(
@@ -595,6 +762,7 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
         if !old_data.same(data) {
             self.layout.set_text(data.clone());
+            self.selection = Selection::caret(0);
             ctx.request_layout();
         }
         if self.layout.needs_rebuild_after_update(ctx) {
@@ -624,14 +792,20 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
         size
     }
 
-    #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, _data, _env))]
-    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, _env: &Env) {
+    #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
         let origin = Point::new(LABEL_X_PADDING, 0.0);
         let label_size = ctx.size();
 
         if self.line_break_mode == LineBreaking::Clip {
             ctx.clip(label_size.to_rect());
         }
+        if self.selectable && !self.selection.is_caret() {
+            let color = env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR);
+            for rect in self.layout.rects_for_range(self.selection.range()) {
+                ctx.fill(rect + origin.to_vec2(), &color);
+            }
+        }
         self.draw_at(ctx, origin)
     }
 }
@@ -691,3 +865,90 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, Record, Recording, TestWidgetExt};
+    use crate::tests::move_mouse;
+    use crate::text::RichTextBuilder;
+    use crate::{KeyEvent, Modifiers, MouseButton, MouseEvent, Selector, WidgetExt};
+    use test_log::test;
+
+    #[test]
+    fn clicking_a_link() {
+        const OPEN: Selector = Selector::new("druid-test.label-open-link");
+        let [id] = widget_ids();
+        let recording = Recording::default();
+        let mut builder = RichTextBuilder::new();
+        builder.push("open").link(OPEN);
+        let label = RawLabel::new().with_id(id).center().record(&recording);
+
+        Harness::create_simple(builder.build(), label, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let pos = harness.get_state(id).window_origin() + (LABEL_X_PADDING + 4.0, 4.0);
+
+            // Hovering the link restyles it, which needs a new layout.
+            harness.event(Event::MouseMove(move_mouse(pos)));
+            assert!(harness.get_state(id).needs_layout);
+            harness.just_layout();
+            recording.clear();
+            harness.event(Event::MouseUp(MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse(pos)
+            }));
+            assert!(recording
+                .drain()
+                .any(|r| matches!(r, Record::E(Event::Command(cmd)) if cmd.is(OPEN))));
+        });
+    }
+
+    #[test]
+    fn selecting_text() {
+        let [id] = widget_ids();
+        let label = Label::new("hello world")
+            .selectable(true)
+            .with_id(id)
+            .center();
+        let mouse = |pos: Point| MouseEvent {
+            button: MouseButton::Left,
+            count: 1,
+            ..move_mouse(pos)
+        };
+        let selection = |harness: &mut Harness<()>| {
+            let mut state = harness.get_debug_state(id).children.remove(0);
+            state.other_values.remove("selection")
+        };
+
+        Harness::create_simple((), label, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let rect = harness.get_state(id).layout_rect();
+            let start = harness.get_state(id).window_origin() + (1.0, rect.height() / 2.0);
+            let end = start + (rect.width(), 0.0);
+
+            harness.event(Event::MouseDown(mouse(start)));
+            harness.event(Event::MouseMove(move_mouse(end)));
+            harness.event(Event::MouseUp(mouse(end)));
+            assert_eq!(selection(harness).as_deref(), Some("hello world"));
+            assert_eq!(harness.window().focus, Some(id));
+
+            // A click collapses the selection, and the keyboard selects all again.
+            harness.event(Event::MouseDown(mouse(start)));
+            harness.event(Event::MouseUp(mouse(start)));
+            assert_eq!(selection(harness), None);
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                if cfg!(target_os = "macos") {
+                    Modifiers::META
+                } else {
+                    Modifiers::CONTROL
+                },
+                "a",
+            )));
+            assert_eq!(selection(harness).as_deref(), Some("hello world"));
+        });
+    }
+}
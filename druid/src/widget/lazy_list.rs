@@ -0,0 +1,507 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A list widget that only creates widgets for the visible items.
+
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use tracing::{instrument, trace};
+
+use crate::commands::{VIEWPORT_CHANGED, WATCH_VIEWPORT};
+use crate::debug_state::DebugState;
+use crate::kurbo::{Point, Rect, Size};
+use crate::widget::ListIter;
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Selector, UpdateCtx, Widget, WidgetPod,
+};
+
+/// The number of items that are created above and below the visible area.
+const DEFAULT_OVERSCAN: usize = 3;
+/// The height that is assumed for items that have not been measured yet.
+const DEFAULT_ESTIMATED_HEIGHT: f64 = 24.0;
+
+/// Sent by a [`LazyList`] to itself when it needs to create or remove rows
+/// after `layout`.
+const REFRESH_ROWS: Selector = Selector::new("druid.builtin.lazy-list-refresh-rows");
+
+/// Sent by a [`LazyList`] to itself when it is added, so it can ask the
+/// enclosing scroll for the viewport; notifications can only be sent from `event`.
+const WATCH_SCROLL: Selector = Selector::new("druid.builtin.lazy-list-watch-scroll");
+
+/// A vertical list widget for very large collections.
+///
+/// Unlike [`List`], which creates a widget for every item, a `LazyList` only
+/// creates widgets for the items that are visible, plus a few above and below
+/// them (see [`with_overscan`]). As the list is scrolled, the widgets of items
+/// that move out of view are reused for the items that come into view.
+///
+/// To know what is visible, the list has to be inside a [`Scroll`]. It asks
+/// the scroll for the visible area with the [`WATCH_VIEWPORT`] notification,
+/// and is then sent [`VIEWPORT_CHANGED`]. It works best as the direct child of
+/// the scroll.
+///
+/// By default, every item is measured when it is laid out, and items that
+/// have not been measured are assumed to be as tall as the average measured
+/// item. If all items are equally tall, [`with_row_height`] is considerably
+/// cheaper.
+///
+/// Note that a widget that is reused for another item keeps its internal
+/// state; only its data changes.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::{Label, LazyList, Scroll};
+/// use druid::Widget;
+///
+/// fn log_view() -> impl Widget<Arc<Vec<String>>> {
+///     Scroll::new(
+///         LazyList::new(|| Label::dynamic(|line: &String, _| line.clone())).with_row_height(20.0),
+///     )
+///     .vertical()
+/// }
+/// ```
+///
+/// [`List`]: crate::widget::List
+/// [`Scroll`]: crate::widget::Scroll
+/// [`VIEWPORT_CHANGED`]: crate::commands::VIEWPORT_CHANGED
+/// [`WATCH_VIEWPORT`]: crate::commands::WATCH_VIEWPORT
+/// [`with_overscan`]: LazyList::with_overscan
+/// [`with_row_height`]: LazyList::with_row_height
+pub struct LazyList<T> {
+    closure: Box<dyn Fn() -> Box<dyn Widget<T>>>,
+    /// The widgets for the items starting at `first`.
+    rows: VecDeque<WidgetPod<T, Box<dyn Widget<T>>>>,
+    first: usize,
+    row_height: RowHeight,
+    overscan: usize,
+    viewport: Rect,
+}
+
+/// How a [`LazyList`] determines the height of its items.
+enum RowHeight {
+    Fixed(f64),
+    Measured {
+        estimate: f64,
+        /// The measured heights, by item index.
+        heights: Vec<Option<f64>>,
+    },
+}
+
+impl RowHeight {
+    /// The height assumed for items that have not been measured.
+    fn estimate(&self) -> f64 {
+        match self {
+            RowHeight::Fixed(height) => *height,
+            RowHeight::Measured { estimate, heights } => {
+                let (sum, count) = heights
+                    .iter()
+                    .flatten()
+                    .fold((0.0, 0), |(sum, count), height| (sum + height, count + 1));
+                if count > 0 {
+                    sum / count as f64
+                } else {
+                    *estimate
+                }
+            }
+        }
+    }
+
+    /// Returns the position of the top of the item at `index`.
+    fn offset(&self, index: usize) -> f64 {
+        match self {
+            RowHeight::Fixed(height) => index as f64 * height,
+            RowHeight::Measured { heights, .. } => {
+                let estimate = self.estimate();
+                let measured = heights[..index.min(heights.len())]
+                    .iter()
+                    .map(|height| height.unwrap_or(estimate))
+                    .sum::<f64>();
+                measured + index.saturating_sub(heights.len()) as f64 * estimate
+            }
+        }
+    }
+
+    /// Returns the number of items that end at or above the vertical position `y`
+    /// (if `round_up` is `false`), or that start above it (if it is `true`).
+    fn count_above(&self, y: f64, len: usize, round_up: bool) -> usize {
+        if y <= 0.0 {
+            return 0;
+        }
+        match self {
+            RowHeight::Fixed(height) if round_up => ((y / height).ceil() as usize).min(len),
+            RowHeight::Fixed(height) => ((y / height).floor() as usize).min(len),
+            RowHeight::Measured { heights, .. } => {
+                let estimate = self.estimate();
+                let mut top = 0.0;
+                for index in 0..len {
+                    let bottom = top + heights.get(index).copied().flatten().unwrap_or(estimate);
+                    let edge = if round_up { top } else { bottom };
+                    if edge >= y {
+                        return index;
+                    }
+                    top = bottom;
+                }
+                len
+            }
+        }
+    }
+}
+
+impl<T: Data> LazyList<T> {
+    /// Create a new lazy list widget. The closure is called every time a new
+    /// widget is needed for an item.
+    pub fn new<W: Widget<T> + 'static>(closure: impl Fn() -> W + 'static) -> Self {
+        LazyList {
+            closure: Box::new(move || Box::new(closure())),
+            rows: VecDeque::new(),
+            first: 0,
+            row_height: RowHeight::Measured {
+                estimate: DEFAULT_ESTIMATED_HEIGHT,
+                heights: Vec::new(),
+            },
+            overscan: DEFAULT_OVERSCAN,
+            viewport: Rect::ZERO,
+        }
+    }
+
+    /// Builder-style method to give every item the same height.
+    ///
+    /// Items are not measured, and are laid out with exactly this height.
+    pub fn with_row_height(mut self, height: f64) -> Self {
+        assert!(height > 0.0, "row height must be positive");
+        self.row_height = RowHeight::Fixed(height);
+        self
+    }
+
+    /// Builder-style method to set the height that is assumed for items that
+    /// have not been measured yet.
+    ///
+    /// Once some items have been measured, their average height is used
+    /// instead. This has no effect if a [fixed row height] is set.
+    ///
+    /// [fixed row height]: LazyList::with_row_height
+    pub fn with_estimated_row_height(mut self, height: f64) -> Self {
+        assert!(height > 0.0, "row height must be positive");
+        if let RowHeight::Measured { estimate, .. } = &mut self.row_height {
+            *estimate = height;
+        }
+        self
+    }
+
+    /// Builder-style method to set the number of items that are created above
+    /// and below the visible ones.
+    ///
+    /// The default is `3`.
+    pub fn with_overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    /// Returns the range of items that currently have a widget.
+    pub fn created_range(&self) -> Range<usize> {
+        self.first..self.first + self.rows.len()
+    }
+
+    /// The range of items that should have a widget.
+    fn wanted_range(&self, len: usize) -> Range<usize> {
+        let start = self.row_height.count_above(self.viewport.y0, len, false);
+        let end = self.row_height.count_above(self.viewport.y1, len, true);
+        start.saturating_sub(self.overscan)..(end + self.overscan).min(len)
+    }
+
+    /// Create, reuse or remove widgets, so that there is one for each of the
+    /// wanted items.
+    ///
+    /// Returns `true` if widgets were created or removed.
+    fn update_rows(&mut self, len: usize) -> bool {
+        let wanted = self.wanted_range(len);
+        if wanted == self.created_range() {
+            return false;
+        }
+        trace!("Rows {:?} are wanted", wanted);
+
+        let mut spare = Vec::new();
+        while !self.rows.is_empty() && !wanted.contains(&self.first) {
+            spare.extend(self.rows.pop_front());
+            self.first += 1;
+        }
+        while !self.rows.is_empty() && !wanted.contains(&(self.created_range().end - 1)) {
+            spare.extend(self.rows.pop_back());
+        }
+        if self.rows.is_empty() {
+            self.first = wanted.start;
+        }
+
+        let mut children_changed = false;
+        let closure = &self.closure;
+        let mut next_row = || {
+            spare.pop().unwrap_or_else(|| {
+                children_changed = true;
+                WidgetPod::new(closure())
+            })
+        };
+        while self.first > wanted.start {
+            self.rows.push_front(next_row());
+            self.first -= 1;
+        }
+        while self.first + self.rows.len() < wanted.end {
+            self.rows.push_back(next_row());
+        }
+        children_changed || !spare.is_empty()
+    }
+}
+
+impl<C: Data, T: ListIter<C>> Widget<T> for LazyList<C> {
+    #[instrument(name = "LazyList", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(VIEWPORT_CHANGED) => {
+                // This is in the coordinate space of the scroll's content, which
+                // is ours if we are its direct child.
+                self.viewport = *cmd.get_unchecked(VIEWPORT_CHANGED);
+                ctx.set_handled();
+                if self.wanted_range(data.data_len()) != self.created_range() {
+                    ctx.request_update();
+                }
+                return;
+            }
+            Event::Command(cmd) if cmd.is(WATCH_SCROLL) => {
+                ctx.set_handled();
+                ctx.submit_notification(WATCH_VIEWPORT);
+                return;
+            }
+            Event::Command(cmd) if cmd.is(REFRESH_ROWS) => {
+                ctx.set_handled();
+                ctx.request_update();
+                return;
+            }
+            _ => (),
+        }
+
+        let range = self.created_range();
+        let mut rows = self.rows.iter_mut();
+        data.for_each_mut_in_range(range, |child_data, _| {
+            if let Some(row) = rows.next() {
+                row.event(ctx, event, child_data, env);
+            }
+        });
+    }
+
+    #[instrument(name = "LazyList", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            ctx.submit_command(WATCH_SCROLL.to(ctx.widget_id()));
+            if let RowHeight::Measured { heights, .. } = &mut self.row_height {
+                heights.resize(data.data_len(), None);
+            }
+            if self.update_rows(data.data_len()) {
+                ctx.children_changed();
+            }
+        }
+
+        let range = self.created_range();
+        let mut rows = self.rows.iter_mut();
+        data.for_each_in_range(range, |child_data, _| {
+            if let Some(row) = rows.next() {
+                row.lifecycle(ctx, event, child_data, env);
+            }
+        });
+    }
+
+    #[instrument(
+        name = "LazyList",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let len = data.data_len();
+        if let RowHeight::Measured { heights, .. } = &mut self.row_height {
+            heights.resize(len, None);
+        }
+        let old_range = self.created_range();
+        if self.update_rows(len) {
+            ctx.children_changed();
+        }
+        if self.created_range() != old_range {
+            ctx.request_layout();
+        }
+
+        // Reused widgets see the data of their new item as changed data; widgets
+        // that were just created are skipped until they have been added.
+        let range = self.created_range();
+        let mut rows = self.rows.iter_mut();
+        data.for_each_in_range(range, |child_data, _| {
+            if let Some(row) = rows.next() {
+                if row.is_initialized() {
+                    row.update(ctx, child_data, env);
+                }
+            }
+        });
+    }
+
+    #[instrument(name = "LazyList", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("LazyList");
+
+        let (min_height, max_height) = match self.row_height {
+            RowHeight::Fixed(height) => (height, height),
+            RowHeight::Measured { .. } => (0.0, f64::INFINITY),
+        };
+        let child_bc = BoxConstraints::new(
+            Size::new(bc.min().width, min_height),
+            Size::new(bc.max().width, max_height),
+        );
+
+        let mut width = bc.min().width;
+        let mut y = self.row_height.offset(self.first);
+        let mut paint_rect = Rect::ZERO;
+        let mut rows = self.rows.iter_mut();
+        let row_height = &mut self.row_height;
+        data.for_each_in_range(self.first..self.first + rows.len(), |child_data, index| {
+            let row = match rows.next() {
+                Some(row) => row,
+                None => return,
+            };
+            let size = row.layout(ctx, &child_bc, child_data, env);
+            row.set_origin(ctx, child_data, env, Point::new(0.0, y));
+            paint_rect = paint_rect.union(row.paint_rect());
+            width = width.max(size.width);
+            y += size.height;
+            if let RowHeight::Measured { heights, .. } = row_height {
+                if let Some(height) = heights.get_mut(index) {
+                    *height = Some(size.height);
+                }
+            }
+        });
+
+        let len = data.data_len();
+        let my_size = bc.constrain(Size::new(width, self.row_height.offset(len)));
+        let insets = paint_rect - my_size.to_rect();
+        ctx.set_paint_insets(insets);
+
+        // Measuring items may have changed which ones are visible.
+        if self.wanted_range(len) != self.created_range() {
+            ctx.submit_command(REFRESH_ROWS.to(ctx.widget_id()));
+        }
+
+        trace!("Computed layout: size={}, insets={:?}", my_size, insets);
+        my_size
+    }
+
+    #[instrument(name = "LazyList", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let mut rows = self.rows.iter_mut();
+        data.for_each_in_range(self.first..self.first + rows.len(), |child_data, _| {
+            if let Some(row) = rows.next() {
+                row.paint(ctx, child_data, env);
+            }
+        });
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut rows = self.rows.iter();
+        let mut children_state = Vec::with_capacity(rows.len());
+        data.for_each_in_range(self.created_range(), |child_data, _| {
+            if let Some(row) = rows.next() {
+                children_state.push(row.widget().debug_state(child_data));
+            }
+        });
+
+        DebugState {
+            display_name: "LazyList".to_string(),
+            main_value: format!("{:?}", self.created_range()),
+            children: children_state,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::{move_mouse, scroll_mouse};
+    use crate::widget::{Label, Scroll, SizedBox};
+    use crate::WidgetExt;
+    use std::sync::Arc;
+    use test_log::test;
+
+    fn items(count: usize) -> Arc<Vec<String>> {
+        Arc::new((0..count).map(|i| i.to_string()).collect())
+    }
+
+    fn created_range(harness: &mut Harness<Arc<Vec<String>>>, id: crate::WidgetId) -> String {
+        // `id` belongs to the `IdentityWrapper` around the list.
+        harness.get_debug_state(id).children.remove(0).main_value
+    }
+
+    #[test]
+    fn fixed_row_height() {
+        let id = crate::WidgetId::next();
+        let list = WidgetExt::<Arc<Vec<String>>>::with_id(
+            LazyList::new(|| Label::dynamic(|item: &String, _| item.clone()))
+                .with_row_height(20.0)
+                .with_overscan(2),
+            id,
+        );
+        let widget = Scroll::new(list).vertical().fix_height(100.0).center();
+
+        Harness::create_simple(items(100_000), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // Deliver the viewport the scroll sent after its layout.
+            harness.event(Event::MouseMove(move_mouse((1.0, 1.0))));
+            harness.just_layout();
+
+            let state = harness.get_state(id);
+            assert_eq!(state.layout_rect().height(), 2_000_000.0);
+            // Five rows are visible, and the overscan adds two below them.
+            assert_eq!(created_range(harness, id), "0..7");
+
+            harness.event(Event::Wheel(scroll_mouse((200.0, 200.0), (0.0, 1000.0))));
+            harness.just_layout();
+            assert_eq!(created_range(harness, id), "48..57");
+        });
+    }
+
+    #[test]
+    fn measured_row_height() {
+        let id = crate::WidgetId::next();
+        let list = WidgetExt::<Arc<Vec<String>>>::with_id(
+            LazyList::new(|| SizedBox::empty().height(10.0))
+                .with_estimated_row_height(50.0)
+                .with_overscan(0),
+            id,
+        );
+        let widget = Scroll::new(list).vertical().fix_height(100.0).center();
+
+        Harness::create_simple(items(1000), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseMove(move_mouse((1.0, 1.0))));
+            harness.just_layout();
+            // After measuring the first two, we know that ten items are visible.
+            harness.event(Event::MouseMove(move_mouse((1.0, 2.0))));
+            harness.just_layout();
+
+            assert_eq!(created_range(harness, id), "0..10");
+            let state = harness.get_state(id);
+            assert_eq!(state.layout_rect().height(), 10_000.0);
+        });
+    }
+}
@@ -15,10 +15,11 @@
 //! Simple list view widget.
 
 use std::cmp::Ordering;
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::f64;
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 use std::sync::Arc;
+use std::time::Duration;
 
 use tracing::{instrument, trace};
 
@@ -27,20 +28,117 @@ use crate::im::{OrdMap, Vector};
 
 use crate::kurbo::{Point, Rect, Size};
 
+use crate::accessibility::Role;
+use crate::commands::LIST_REORDER;
 use crate::debug_state::DebugState;
+use crate::lens::BiMap;
 use crate::{
-    widget::Axis, BoxConstraints, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, UpdateCtx, Widget, WidgetPod,
+    theme, widget::Axis, BoxConstraints, Data, Env, Event, EventCtx, KbKey, KeyOrValue, LayoutCtx,
+    Lens, LifeCycle, LifeCycleCtx, Modifiers, MouseButton, MouseEvent, PaintCtx, RenderContext,
+    Selector, TimerToken, UpdateCtx, Widget, WidgetPod,
 };
 
+/// How long typed characters are accumulated for type-ahead search.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How far the pointer has to move before pressing an item starts a drag.
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// How close to the edge of the viewport a drag has to be to scroll it.
+const DRAG_SCROLL_MARGIN: f64 = 30.0;
+
+/// The thickness of the line showing where a dragged item will be dropped.
+const DROP_INDICATOR_WIDTH: f64 = 2.0;
+
 /// A list widget for a variable-size collection of items.
+///
+/// If [type-ahead search] is enabled, the list can be focused, and typing
+/// highlights the first item whose text starts with the typed characters.
+///
+/// If the list is [draggable], items can be moved by dragging them.
+///
+/// [type-ahead search]: List::with_type_ahead
+/// [draggable]: List::draggable
 pub struct List<T> {
     closure: Box<dyn Fn() -> Box<dyn Widget<T>>>,
     children: Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
     axis: Axis,
     spacing: KeyOrValue<f64>,
+    type_ahead: Option<TypeAhead<T>>,
+    /// The item highlighted by type-ahead search or keyboard navigation.
+    cursor: Option<usize>,
+    draggable: bool,
+    reorder: bool,
+    drag: Option<Drag>,
+}
+
+/// An item being dragged in a [`List`].
+struct Drag {
+    /// The index of the dragged item.
+    from: usize,
+    /// Where the item was pressed.
+    start: Point,
+    /// The current position of the pointer.
+    pos: Point,
+    /// Whether the pointer has moved far enough to start dragging.
+    dragging: bool,
 }
 
+/// The state of type-ahead search in a [`List`].
+struct TypeAhead<T> {
+    text: Box<dyn Fn(&T) -> String>,
+    /// The characters typed so far, in lowercase.
+    buffer: String,
+    /// The timer that clears `buffer`.
+    timer: TimerToken,
+}
+
+/// A [`List`] whose items can be selected.
+///
+/// This is created with [`List::selection`]. The indices of the selected
+/// items are read from and written to the list's data through a [`Lens`];
+/// a convenient way to store them next to the items is a tuple with shared
+/// data, such as `(Arc<HashSet<usize>>, Arc<Vec<T>>)`.
+///
+/// Clicking an item selects it, Ctrl-clicking (Cmd on macOS) toggles it, and
+/// Shift-clicking selects every item between it and the last item clicked.
+/// Clicking outside of the items clears the selection. When the list has
+/// focus, the arrow keys move a cursor through the items, in the same way; if
+/// [type-ahead search] is enabled, a match is selected.
+///
+/// Selected items are painted with the [`SELECTED_ITEM_BACKGROUND_COLOR`]
+/// behind them. When items are removed, their indices are removed from the
+/// selection as well.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use std::sync::Arc;
+/// use druid::widget::{Label, List};
+/// use druid::{lens, Widget, WidgetExt};
+///
+/// type Items = (Arc<HashSet<usize>>, Arc<Vec<String>>);
+///
+/// fn item_list() -> impl Widget<Items> {
+///     List::new(|| Label::dynamic(|(_, item): &(_, String), _| item.clone()))
+///         .selection(lens!(Items, 0))
+/// }
+/// ```
+///
+/// [`Lens`]: crate::Lens
+/// [type-ahead search]: List::with_type_ahead
+/// [`SELECTED_ITEM_BACKGROUND_COLOR`]: crate::theme::SELECTED_ITEM_BACKGROUND_COLOR
+pub struct SelectableList<T, L> {
+    list: List<T>,
+    selection: L,
+    /// The item that range selections start from.
+    anchor: Option<usize>,
+}
+
+/// Sent by a [`SelectableList`] to itself to remove out of range indices.
+const PRUNE_SELECTION: Selector = Selector::new("druid.builtin.list-prune-selection");
+
 impl<T: Data> List<T> {
     /// Create a new list widget. Closure will be called every time when a new child
     /// needs to be constructed.
@@ -50,6 +148,11 @@ impl<T: Data> List<T> {
             children: Vec::new(),
             axis: Axis::Vertical,
             spacing: KeyOrValue::Concrete(0.),
+            type_ahead: None,
+            cursor: None,
+            draggable: false,
+            reorder: false,
+            drag: None,
         }
     }
 
@@ -71,6 +174,258 @@ impl<T: Data> List<T> {
         self
     }
 
+    /// Builder-style method to enable type-ahead search.
+    ///
+    /// The closure provides the text that is searched for each item. When the
+    /// list has focus, typed characters are collected until no key has been
+    /// pressed for a moment, and the first item (after the highlighted one)
+    /// whose text starts with them, ignoring case, is highlighted and scrolled
+    /// into view. Key presses with Ctrl, Alt or Meta held are left alone, so that
+    /// shortcuts keep working.
+    pub fn with_type_ahead(mut self, text: impl Fn(&T) -> String + 'static) -> Self {
+        self.type_ahead = Some(TypeAhead {
+            text: Box::new(text),
+            buffer: String::new(),
+            timer: TimerToken::INVALID,
+        });
+        self
+    }
+
+    /// Builder-style method to allow items to be reordered by dragging them.
+    ///
+    /// While an item is dragged, a line shows where it will be dropped, and
+    /// an enclosing [`Scroll`] is scrolled when the pointer gets close to its
+    /// edges. When the item is released somewhere else, the list submits a
+    /// [`LIST_REORDER`] notification with the old and new index of the item;
+    /// the data is left unchanged, unless [`with_reorder`] is used.
+    ///
+    /// [`Scroll`]: super::Scroll
+    /// [`LIST_REORDER`]: crate::commands::LIST_REORDER
+    /// [`with_reorder`]: List::with_reorder
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        self
+    }
+
+    /// Builder-style method to allow items to be reordered by dragging them,
+    /// moving them in the list's data.
+    ///
+    /// This works like [`draggable`], but the item is also moved in the data
+    /// with [`ListIter::move_item`]; the list already owns its data, so
+    /// without a lens. To reorder a collection that is part of a larger
+    /// state, use [`WidgetExt::lens`] on the list.
+    ///
+    /// [`draggable`]: List::draggable
+    /// [`WidgetExt::lens`]: crate::WidgetExt::lens
+    pub fn with_reorder(mut self) -> Self {
+        self.draggable = true;
+        self.reorder = true;
+        self
+    }
+
+    /// Builder-style method to enable selecting items.
+    ///
+    /// The `lens` gives access to the indices of the selected items; see
+    /// [`SelectableList`] for details.
+    pub fn selection<L>(self, lens: L) -> SelectableList<T, L> {
+        SelectableList {
+            list: self,
+            selection: lens,
+            anchor: None,
+        }
+    }
+
+    /// Returns the index of the item highlighted by type-ahead search or
+    /// keyboard navigation, if any.
+    pub fn highlighted_index(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// Returns the index of the item at `pos`, if any.
+    fn index_at(&self, pos: Point) -> Option<usize> {
+        self.children
+            .iter()
+            .position(|child| child.layout_rect().contains(pos))
+    }
+
+    /// Returns the index an item dropped at `pos` would be inserted at,
+    /// before removing the dragged item.
+    ///
+    /// This is the number of items whose middle is before `pos`, so it works
+    /// with items of any size.
+    fn insertion_index(&self, pos: Point) -> usize {
+        let major = self.axis.major_pos(pos);
+        self.children
+            .iter()
+            .take_while(|child| self.axis.major_pos(child.layout_rect().center()) < major)
+            .count()
+    }
+
+    /// Returns the line showing that an item will be inserted at `idx`.
+    fn drop_indicator(&self, idx: usize, size: Size, spacing: f64) -> Rect {
+        let major = match idx.checked_sub(1).and_then(|idx| self.children.get(idx)) {
+            Some(child) => self.axis.major_span(child.layout_rect()).1 + spacing / 2.0,
+            None => 0.0,
+        };
+        let start = major - DROP_INDICATOR_WIDTH / 2.0;
+        let end = major + DROP_INDICATOR_WIDTH / 2.0;
+        let minor = self.axis.minor(size);
+        Rect::from_points(self.axis.pack(start, 0.0), self.axis.pack(end, minor))
+    }
+
+    /// Handle the pointer events of a drag. Returns `true` if the event was used.
+    fn drag_event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut impl ListIter<T>,
+        env: &Env,
+    ) -> bool {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if let Some(from) = self.index_at(mouse.pos) {
+                    self.drag = Some(Drag {
+                        from,
+                        start: mouse.pos,
+                        pos: mouse.pos,
+                        dragging: false,
+                    });
+                    ctx.set_active(true);
+                }
+                false
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                let drag = match &mut self.drag {
+                    Some(drag) => drag,
+                    None => return false,
+                };
+                drag.pos = mouse.pos;
+                let started = !drag.dragging && (mouse.pos - drag.start).hypot() > DRAG_THRESHOLD;
+                if started {
+                    drag.dragging = true;
+                }
+                let (dragging, from) = (drag.dragging, drag.from);
+                if started {
+                    self.cancel_press(ctx, from, mouse, data, env);
+                }
+                if dragging {
+                    let major = self.axis.major_pos(mouse.pos);
+                    let minor = self.axis.minor_pos(mouse.pos);
+                    ctx.scroll_area_to_view(Rect::from_points(
+                        self.axis.pack(major - DRAG_SCROLL_MARGIN, minor),
+                        self.axis.pack(major + DRAG_SCROLL_MARGIN, minor),
+                    ));
+                    ctx.request_paint();
+                }
+                dragging
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() => {
+                let drag = match self.drag.take() {
+                    Some(drag) => drag,
+                    None => return false,
+                };
+                ctx.set_active(false);
+                if !drag.dragging {
+                    return false;
+                }
+                ctx.request_paint();
+                let insert = self.insertion_index(mouse.pos);
+                let to = if insert > drag.from {
+                    insert - 1
+                } else {
+                    insert
+                };
+                if to != drag.from {
+                    trace!("dragged item {} to {}", drag.from, to);
+                    if self.reorder {
+                        data.move_item(drag.from, to);
+                    }
+                    ctx.submit_notification(LIST_REORDER.with((drag.from, to)));
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Release the mouse outside of the item at `idx`.
+    ///
+    /// The item doesn't see the pointer while it is dragged, so this makes an
+    /// item that reacts to presses, like a [`Button`], let go of the press
+    /// that started the drag without acting on it.
+    ///
+    /// [`Button`]: crate::widget::Button
+    fn cancel_press(
+        &mut self,
+        ctx: &mut EventCtx,
+        idx: usize,
+        mouse: &MouseEvent,
+        data: &mut impl ListIter<T>,
+        env: &Env,
+    ) {
+        let child = match self.children.get_mut(idx) {
+            Some(child) => child,
+            None => return,
+        };
+        let rect = child.layout_rect();
+        let release = Event::MouseUp(MouseEvent {
+            pos: Point::new(rect.x0 - 1.0, rect.y0 - 1.0),
+            buttons: mouse.buttons.without(MouseButton::Left),
+            button: MouseButton::Left,
+            ..mouse.clone()
+        });
+        data.for_each_mut_in_range(idx..idx + 1, |child_data, _| {
+            child.event(ctx, &release, child_data, env);
+        });
+    }
+
+    /// Move the cursor to `idx` and scroll that item into view.
+    fn set_cursor(&mut self, ctx: &mut EventCtx, idx: usize) {
+        if self.cursor != Some(idx) {
+            self.cursor = Some(idx);
+            ctx.request_paint();
+        }
+        if let Some(child) = self.children.get(idx) {
+            ctx.scroll_area_to_view(child.layout_rect());
+        }
+    }
+
+    /// Handle a type-ahead key press. Returns `true` if the key was used.
+    fn type_ahead_key(&mut self, ctx: &mut EventCtx, key: &KbKey, data: &impl ListIter<T>) -> bool {
+        let type_ahead = match &mut self.type_ahead {
+            Some(type_ahead) => type_ahead,
+            None => return false,
+        };
+        let typed = match key {
+            KbKey::Character(c) if !c.chars().any(char::is_control) => c.to_lowercase(),
+            _ => return false,
+        };
+        // A single space is only part of a search that is already in progress.
+        if typed == " " && type_ahead.buffer.is_empty() {
+            return false;
+        }
+        type_ahead.buffer.push_str(&typed);
+        type_ahead.timer = ctx.request_timer(TYPE_AHEAD_TIMEOUT);
+
+        let mut texts = Vec::with_capacity(data.data_len());
+        data.for_each(|item, _| texts.push((type_ahead.text)(item).to_lowercase()));
+        // Continuing a search may keep the current item; a new search starts after it.
+        let start = match self.cursor {
+            Some(idx) if type_ahead.buffer.chars().count() > 1 => idx,
+            Some(idx) => idx + 1,
+            None => 0,
+        };
+        let len = texts.len();
+        let found = (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|&idx| texts[idx].starts_with(&type_ahead.buffer));
+        if let Some(idx) = found {
+            trace!("type-ahead {:?} matched item {}", type_ahead.buffer, idx);
+            self.set_cursor(ctx, idx);
+        }
+        true
+    }
+
     /// When the widget is created or the data changes, create or remove children as needed
     ///
     /// Returns `true` if children were added or removed.
@@ -88,6 +443,23 @@ impl<T: Data> List<T> {
         }
         len != data.data_len()
     }
+
+    fn paint_drop_indicator(&self, ctx: &mut PaintCtx, env: &Env) {
+        if let Some(drag) = self.drag.as_ref().filter(|drag| drag.dragging) {
+            let idx = self.insertion_index(drag.pos);
+            let line = self.drop_indicator(idx, ctx.size(), self.spacing.resolve(env));
+            ctx.fill(line, &env.get(theme::PRIMARY_LIGHT));
+        }
+    }
+
+    fn paint_children(&mut self, ctx: &mut PaintCtx, data: &impl ListIter<T>, env: &Env) {
+        let mut children = self.children.iter_mut();
+        data.for_each(|child_data, _| {
+            if let Some(child) = children.next() {
+                child.paint(ctx, child_data, env);
+            }
+        });
+    }
 }
 
 /// This iterator enables writing List widget for any `Data`.
@@ -100,6 +472,102 @@ pub trait ListIter<T>: Data {
 
     /// Return data length.
     fn data_len(&self) -> usize;
+
+    /// Move the child at `from` so that it ends up at index `to`, shifting the
+    /// children in between.
+    ///
+    /// Returns `false` if the indices are out of range, or if the collection
+    /// can't be reordered; the default implementation doesn't support it.
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        let _ = (from, to);
+        false
+    }
+
+    /// Iterate over the data children with an index in `range`.
+    ///
+    /// The default implementation uses [`for_each`] and skips the other
+    /// children; collections that can be indexed override it.
+    ///
+    /// [`for_each`]: ListIter::for_each
+    fn for_each_in_range(&self, range: Range<usize>, mut cb: impl FnMut(&T, usize)) {
+        self.for_each(|item, i| {
+            if range.contains(&i) {
+                cb(item, i);
+            }
+        });
+    }
+
+    /// Iterate over the data children with an index in `range`. Keep track of
+    /// changed data and update self.
+    ///
+    /// The default implementation uses [`for_each_mut`] and skips the other
+    /// children; collections that can be indexed override it.
+    ///
+    /// [`for_each_mut`]: ListIter::for_each_mut
+    fn for_each_mut_in_range(&mut self, range: Range<usize>, mut cb: impl FnMut(&mut T, usize)) {
+        self.for_each_mut(|item, i| {
+            if range.contains(&i) {
+                cb(item, i);
+            }
+        });
+    }
+}
+
+/// The collections that [`ListIter::move_item`] is implemented for.
+trait MoveItem {
+    fn len(&self) -> usize;
+    fn move_unchecked(&mut self, from: usize, to: usize);
+}
+
+impl<T> MoveItem for Vec<T> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn move_unchecked(&mut self, from: usize, to: usize) {
+        let item = self.remove(from);
+        self.insert(to, item);
+    }
+}
+
+impl<T> MoveItem for VecDeque<T> {
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn move_unchecked(&mut self, from: usize, to: usize) {
+        if let Some(item) = self.remove(from) {
+            self.insert(to, item);
+        }
+    }
+}
+
+#[cfg(feature = "im")]
+impl<T: Clone> MoveItem for Vector<T> {
+    fn len(&self) -> usize {
+        Vector::len(self)
+    }
+
+    fn move_unchecked(&mut self, from: usize, to: usize) {
+        let item = self.remove(from);
+        self.insert(to, item);
+    }
+}
+
+/// Move an item within `items`, if both indices are valid.
+fn move_in(items: &mut impl MoveItem, from: usize, to: usize) -> bool {
+    if from >= items.len() || to >= items.len() {
+        return false;
+    }
+    if from != to {
+        items.move_unchecked(from, to);
+    }
+    true
+}
+
+/// Clamp `range` to the indices of a collection of length `len`.
+fn clamp_range(range: Range<usize>, len: usize) -> Range<usize> {
+    range.start.min(len)..range.end.min(len)
 }
 #[cfg(feature = "im")]
 impl<T: Data> ListIter<T> for Vector<T> {
@@ -122,6 +590,26 @@ impl<T: Data> ListIter<T> for Vector<T> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        move_in(&mut *self, from, to)
+    }
+
+    fn for_each_in_range(&self, range: Range<usize>, mut cb: impl FnMut(&T, usize)) {
+        for i in clamp_range(range, self.len()) {
+            cb(&self[i], i);
+        }
+    }
+
+    fn for_each_mut_in_range(&mut self, range: Range<usize>, mut cb: impl FnMut(&mut T, usize)) {
+        for index in clamp_range(range, self.len()) {
+            let mut new_element = self[index].to_owned();
+            cb(&mut new_element, index);
+            if !new_element.same(&self[index]) {
+                self[index] = new_element;
+            }
+        }
+    }
 }
 
 //An implementation for ListIter<(K, V)> has been ommitted due to problems
@@ -182,6 +670,10 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Vector<T>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        move_in(&mut self.1, from, to)
+    }
 }
 
 impl<T: Data> ListIter<T> for Arc<Vec<T>> {
@@ -219,6 +711,28 @@ impl<T: Data> ListIter<T> for Arc<Vec<T>> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        move_in(Arc::make_mut(self), from, to)
+    }
+
+    fn for_each_in_range(&self, range: Range<usize>, mut cb: impl FnMut(&T, usize)) {
+        let range = clamp_range(range, self.len());
+        for (i, item) in self[range.clone()].iter().enumerate() {
+            cb(item, range.start + i);
+        }
+    }
+
+    fn for_each_mut_in_range(&mut self, range: Range<usize>, mut cb: impl FnMut(&mut T, usize)) {
+        for i in clamp_range(range, self.len()) {
+            let mut d = self[i].to_owned();
+            cb(&mut d, i);
+
+            if !self[i].same(&d) {
+                Arc::make_mut(self)[i] = d;
+            }
+        }
+    }
 }
 
 // S == shared data type
@@ -260,6 +774,35 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Arc<Vec<T>>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        move_in(Arc::make_mut(&mut self.1), from, to)
+    }
+
+    fn for_each_in_range(&self, range: Range<usize>, mut cb: impl FnMut(&(S, T), usize)) {
+        let range = clamp_range(range, self.1.len());
+        for (i, item) in self.1[range.clone()].iter().enumerate() {
+            let d = (self.0.clone(), item.to_owned());
+            cb(&d, range.start + i);
+        }
+    }
+
+    fn for_each_mut_in_range(
+        &mut self,
+        range: Range<usize>,
+        mut cb: impl FnMut(&mut (S, T), usize),
+    ) {
+        for i in clamp_range(range, self.1.len()) {
+            let mut d = (self.0.clone(), self.1[i].to_owned());
+            cb(&mut d, i);
+
+            self.0 = d.0;
+
+            if !self.1[i].same(&d.1) {
+                Arc::make_mut(&mut self.1)[i] = d.1;
+            }
+        }
+    }
 }
 
 impl<T: Data> ListIter<T> for Arc<VecDeque<T>> {
@@ -297,6 +840,10 @@ impl<T: Data> ListIter<T> for Arc<VecDeque<T>> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        move_in(Arc::make_mut(self), from, to)
+    }
 }
 
 // S == shared data type
@@ -338,11 +885,123 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Arc<VecDeque<T>>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        move_in(Arc::make_mut(&mut self.1), from, to)
+    }
+}
+
+/// A [`ListIter`] adapter that gives each item its index.
+///
+/// A [`List`] over an `Indexed<L>` gets `(usize, T)` as the data of its
+/// children, where `L` is a `ListIter<T>`. This is useful to show row numbers,
+/// or to style items depending on their position. The index is part of the
+/// child's data, so when items are inserted or removed the children after
+/// them are updated with their new index; the others aren't updated.
+///
+/// Changes to the index made by a child are ignored.
+///
+/// Use [`Indexed::lens`] to wrap a list's data.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::{Indexed, Label, List};
+/// use druid::{Widget, WidgetExt};
+///
+/// fn numbered_list() -> impl Widget<Arc<Vec<String>>> {
+///     List::new(|| {
+///         Label::dynamic(|(i, item): &(usize, String), _| format!("{}. {}", i + 1, item))
+///     })
+///     .lens(Indexed::lens())
+/// }
+/// ```
+#[derive(Clone, Debug, Data)]
+pub struct Indexed<L>(pub L);
+
+impl<L: Data> Indexed<L> {
+    /// A [`Lens`] from a collection to the collection wrapped in `Indexed`.
+    pub fn lens() -> impl Lens<L, Indexed<L>> {
+        BiMap::new(
+            |items: &L| Indexed(items.clone()),
+            |indexed: &Indexed<L>| indexed.0.clone(),
+        )
+    }
+}
+
+impl<T: Data, L: ListIter<T>> ListIter<(usize, T)> for Indexed<L> {
+    fn for_each(&self, mut cb: impl FnMut(&(usize, T), usize)) {
+        self.0.for_each(|item, i| cb(&(i, item.to_owned()), i));
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut (usize, T), usize)) {
+        self.0.for_each_mut(|item, i| {
+            let mut d = (i, item.to_owned());
+            cb(&mut d, i);
+            if !item.same(&d.1) {
+                *item = d.1;
+            }
+        });
+    }
+
+    fn data_len(&self) -> usize {
+        self.0.data_len()
+    }
+
+    fn move_item(&mut self, from: usize, to: usize) -> bool {
+        self.0.move_item(from, to)
+    }
+
+    fn for_each_in_range(&self, range: Range<usize>, mut cb: impl FnMut(&(usize, T), usize)) {
+        self.0
+            .for_each_in_range(range, |item, i| cb(&(i, item.to_owned()), i));
+    }
+
+    fn for_each_mut_in_range(
+        &mut self,
+        range: Range<usize>,
+        mut cb: impl FnMut(&mut (usize, T), usize),
+    ) {
+        self.0.for_each_mut_in_range(range, |item, i| {
+            let mut d = (i, item.to_owned());
+            cb(&mut d, i);
+            if !item.same(&d.1) {
+                *item = d.1;
+            }
+        });
+    }
 }
 
 impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
     #[instrument(name = "List", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Some(type_ahead) = &mut self.type_ahead {
+            match event {
+                Event::MouseDown(_) => ctx.request_focus(),
+                Event::Timer(token) if *token == type_ahead.timer => {
+                    type_ahead.buffer.clear();
+                    type_ahead.timer = TimerToken::INVALID;
+                    ctx.set_handled();
+                    return;
+                }
+                Event::KeyDown(key)
+                    if ctx.is_focused()
+                        && !(key.mods.ctrl() || key.mods.alt() || key.mods.meta())
+                        && self.type_ahead_key(ctx, &key.key, data) =>
+                {
+                    ctx.set_handled();
+                    return;
+                }
+                _ => (),
+            }
+        }
+
+        // Once an item is being dragged, its children don't see the pointer.
+        if self.draggable && self.drag_event(ctx, event, data, env) {
+            return;
+        }
+
         let mut children = self.children.iter_mut();
         data.for_each_mut(|child_data, _| {
             if let Some(child) = children.next() {
@@ -354,10 +1013,21 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
     #[instrument(name = "List", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
         if let LifeCycle::WidgetAdded = event {
+            ctx.set_access_role(Role::List);
             if self.update_child_count(data, env) {
                 ctx.children_changed();
             }
         }
+        if let Some(type_ahead) = &mut self.type_ahead {
+            match event {
+                LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+                LifeCycle::FocusChanged(_) => {
+                    type_ahead.buffer.clear();
+                    ctx.request_paint();
+                }
+                _ => (),
+            }
+        }
 
         let mut children = self.children.iter_mut();
         data.for_each(|child_data, _| {
@@ -383,6 +1053,11 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
             ctx.children_changed();
         }
 
+        if matches!(self.cursor, Some(idx) if idx >= data.data_len()) {
+            self.cursor = None;
+            ctx.request_paint();
+        }
+
         if ctx.env_key_changed(&self.spacing) {
             ctx.request_layout();
         }
@@ -424,12 +1099,20 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
 
     #[instrument(name = "List", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        let mut children = self.children.iter_mut();
-        data.for_each(|child_data, _| {
-            if let Some(child) = children.next() {
-                child.paint(ctx, child_data, env);
-            }
-        });
+        if let Some(child) = self
+            .highlighted_index()
+            .and_then(|idx| self.children.get(idx))
+        {
+            let color = if ctx.is_focused() {
+                env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+            } else {
+                env.get(theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR)
+            };
+            ctx.fill(child.layout_rect(), &color);
+        }
+
+        self.paint_children(ctx, data, env);
+        self.paint_drop_indicator(ctx, env);
     }
 
     fn debug_state(&self, data: &T) -> DebugState {
@@ -441,10 +1124,548 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
             }
         });
 
+        let mut other_values = HashMap::new();
+        if let Some(idx) = self.highlighted_index() {
+            other_values.insert("highlighted".to_string(), idx.to_string());
+        }
+
         DebugState {
             display_name: "List".to_string(),
             children: children_state,
+            other_values,
             ..Default::default()
         }
     }
 }
+
+/// Returns `true` if `mods` toggle items, rather than replacing the selection.
+fn toggles_selection(mods: Modifiers) -> bool {
+    mods.ctrl() || mods.meta()
+}
+
+impl<C: Data, L> SelectableList<C, L> {
+    /// Returns the index of the item under the keyboard cursor, if any.
+    pub fn cursor_index(&self) -> Option<usize> {
+        self.list.cursor
+    }
+
+    fn replace_selection<T>(&self, data: &mut T, selection: HashSet<usize>)
+    where
+        L: Lens<T, Arc<HashSet<usize>>>,
+    {
+        self.selection.with_mut(data, |current| {
+            if **current != selection {
+                *current = Arc::new(selection);
+            }
+        });
+    }
+
+    /// Select `idx` according to the modifiers, in response to a click or
+    /// to moving the cursor.
+    fn select<T>(&mut self, data: &mut T, idx: usize, mods: Modifiers, toggle: bool)
+    where
+        L: Lens<T, Arc<HashSet<usize>>>,
+    {
+        let mut selection = self.selection.with(data, |current| (**current).clone());
+        if mods.shift() {
+            let anchor = *self.anchor.get_or_insert(idx);
+            if !toggles_selection(mods) {
+                selection.clear();
+            }
+            selection.extend(anchor.min(idx)..=anchor.max(idx));
+        } else if toggles_selection(mods) {
+            if toggle && !selection.remove(&idx) {
+                selection.insert(idx);
+            }
+            self.anchor = Some(idx);
+        } else {
+            selection.clear();
+            selection.insert(idx);
+            self.anchor = Some(idx);
+        }
+        self.replace_selection(data, selection);
+    }
+}
+
+impl<C: Data, T: ListIter<C>, L: Lens<T, Arc<HashSet<usize>>>> Widget<T> for SelectableList<C, L> {
+    #[instrument(
+        name = "SelectableList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(PRUNE_SELECTION) => {
+                let len = data.data_len();
+                let selection = self.selection.with(data, |current| {
+                    current.iter().copied().filter(|&idx| idx < len).collect()
+                });
+                self.replace_selection(data, selection);
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseDown(mouse) => {
+                ctx.request_focus();
+                let idx = self.list.index_at(mouse.pos);
+                self.list.event(ctx, event, data, env);
+                if ctx.is_handled() {
+                    return;
+                }
+                match idx {
+                    Some(idx) => {
+                        // Shift-clicking ranges from the cursor, if nothing was clicked yet.
+                        if self.anchor.is_none() {
+                            self.anchor = self.list.cursor;
+                        }
+                        self.select(data, idx, mouse.mods, true);
+                        self.list.set_cursor(ctx, idx);
+                    }
+                    None => {
+                        self.anchor = None;
+                        self.replace_selection(data, HashSet::new());
+                    }
+                }
+                ctx.request_paint();
+                return;
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let step = match (&key.key, self.list.axis) {
+                    (KbKey::ArrowDown, Axis::Vertical) | (KbKey::ArrowRight, Axis::Horizontal) => 1,
+                    (KbKey::ArrowUp, Axis::Vertical) | (KbKey::ArrowLeft, Axis::Horizontal) => -1,
+                    _ => 0,
+                };
+                let len = data.data_len();
+                if step != 0 && len > 0 {
+                    let idx = match self.list.cursor {
+                        Some(idx) => (idx as isize + step).clamp(0, len as isize - 1) as usize,
+                        None if step > 0 => 0,
+                        None => len - 1,
+                    };
+                    if self.anchor.is_none() {
+                        self.anchor = self.list.cursor;
+                    }
+                    // With Ctrl held, the cursor moves without changing the selection.
+                    self.select(data, idx, key.mods, false);
+                    self.list.set_cursor(ctx, idx);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            _ => (),
+        }
+
+        let cursor = self.list.cursor;
+        self.list.event(ctx, event, data, env);
+        if let (Event::KeyDown(_), Some(idx)) = (event, self.list.cursor) {
+            if cursor != Some(idx) {
+                // The cursor was moved by type-ahead search.
+                self.select(data, idx, Modifiers::default(), false);
+            }
+        }
+    }
+
+    #[instrument(
+        name = "SelectableList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            // A list with type-ahead search registers itself.
+            LifeCycle::BuildFocusChain if self.list.type_ahead.is_none() => {
+                ctx.register_for_focus()
+            }
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+        self.list.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "SelectableList",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.list.update(ctx, old_data, data, env);
+
+        let len = data.data_len();
+        if matches!(self.anchor, Some(idx) if idx >= len) {
+            self.anchor = None;
+        }
+        // We can't change the data here, so we ask ourselves to do it.
+        if self
+            .selection
+            .with(data, |selection| selection.iter().any(|&idx| idx >= len))
+        {
+            ctx.submit_command(PRUNE_SELECTION.to(ctx.widget_id()));
+        }
+
+        let old_selection = self.selection.with(old_data, Arc::clone);
+        if self
+            .selection
+            .with(data, |selection| !selection.same(&old_selection))
+        {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(
+        name = "SelectableList",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.list.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "SelectableList", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let color = env.get(theme::SELECTED_ITEM_BACKGROUND_COLOR);
+        let children = &self.list.children;
+        self.selection.with(data, |selection| {
+            for child in selection.iter().filter_map(|&idx| children.get(idx)) {
+                ctx.fill(child.layout_rect(), &color);
+            }
+        });
+
+        self.list.paint_children(ctx, data, env);
+        self.list.paint_drop_indicator(ctx, env);
+
+        if ctx.is_focused() {
+            let cursor = self.list.cursor.and_then(|idx| self.list.children.get(idx));
+            if let Some(child) = cursor {
+                let rect = child.layout_rect().inset(-0.5);
+                ctx.stroke(rect, &env.get(theme::PRIMARY_LIGHT), 1.0);
+            }
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut debug_state = self.list.debug_state(data);
+        debug_state.display_name = self.short_type_name().to_string();
+        let mut selection = self.selection.with(data, |selection| {
+            selection.iter().copied().collect::<Vec<_>>()
+        });
+        selection.sort_unstable();
+        debug_state
+            .other_values
+            .insert("selection".to_string(), format!("{:?}", selection));
+        debug_state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{Button, Controller, Label, Painter, SizedBox};
+    use crate::{KeyEvent, WidgetExt, WidgetId};
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use test_log::test;
+
+    type Items = (Arc<HashSet<usize>>, Arc<Vec<String>>);
+
+    const TRUNCATE: Selector<usize> = Selector::new("druid-test.list-truncate");
+
+    /// Handles `TRUNCATE` by removing items from the end of the list.
+    struct Truncate;
+
+    impl<W: Widget<Items>> Controller<Items, W> for Truncate {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut Items,
+            env: &Env,
+        ) {
+            if let Event::Command(cmd) = event {
+                if let Some(len) = cmd.get(TRUNCATE) {
+                    data.1 = Arc::new(data.1[..*len].to_vec());
+                }
+            }
+            child.event(ctx, event, data, env)
+        }
+    }
+
+    fn selectable_list() -> impl Widget<Items> {
+        List::new(|| SizedBox::<(Arc<HashSet<usize>>, String)>::empty().height(20.))
+            .selection(lens!(Items, 0))
+            .controller(Truncate)
+    }
+
+    fn items(count: usize) -> Items {
+        let items = (0..count).map(|i| i.to_string()).collect();
+        (Arc::new(HashSet::new()), Arc::new(items))
+    }
+
+    fn click(harness: &mut Harness<Items>, y: f64, mods: Modifiers) {
+        let mut mouse = move_mouse((10., y));
+        mouse.mods = mods;
+        harness.event(Event::MouseDown(mouse.clone()));
+        harness.event(Event::MouseUp(mouse));
+    }
+
+    fn press(harness: &mut Harness<Items>, mods: Modifiers, key: KbKey) {
+        harness.event(Event::KeyDown(KeyEvent::for_test(mods, key)));
+    }
+
+    fn selection(harness: &Harness<Items>) -> Vec<usize> {
+        let mut selection: Vec<_> = harness.data().0.iter().copied().collect();
+        selection.sort_unstable();
+        selection
+    }
+
+    fn type_key(harness: &mut Harness<Arc<Vec<String>>>, mods: Modifiers, key: &str) {
+        let key = KbKey::Character(key.into());
+        harness.event(Event::KeyDown(KeyEvent::for_test(mods, key)));
+    }
+
+    fn highlighted(harness: &mut Harness<Arc<Vec<String>>>, id: WidgetId) -> Option<usize> {
+        // `id` belongs to the `IdentityWrapper` around the list.
+        let debug_state = harness.get_debug_state(id).children.remove(0);
+        debug_state
+            .other_values
+            .get("highlighted")
+            .map(|v| v.parse().unwrap())
+    }
+
+    fn drag(harness: &mut Harness<Arc<Vec<u32>>>, from_y: f64, to_y: f64) {
+        harness.event(Event::MouseDown(MouseEvent {
+            button: MouseButton::Left,
+            count: 1,
+            ..move_mouse((10., from_y))
+        }));
+        harness.event(Event::MouseMove(move_mouse((10., to_y))));
+        harness.event(Event::MouseUp(MouseEvent {
+            button: MouseButton::Left,
+            ..move_mouse((10., to_y))
+        }));
+    }
+
+    #[test]
+    fn drag_to_reorder() {
+        let list = List::new(|| SizedBox::<u32>::empty().height(20.)).with_reorder();
+
+        Harness::create_simple(Arc::new(vec![0, 1, 2, 3, 4]), list, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            // Dropping the first item between the third and the fourth.
+            drag(harness, 10., 65.);
+            assert_eq!(**harness.data(), [1, 2, 0, 3, 4]);
+            // Dropping the last item above the first.
+            drag(harness, 90., 2.);
+            assert_eq!(**harness.data(), [4, 1, 2, 0, 3]);
+            // Small movements are clicks, not drags.
+            drag(harness, 10., 12.);
+            assert_eq!(**harness.data(), [4, 1, 2, 0, 3]);
+        });
+    }
+
+    #[test]
+    fn drag_releases_pressed_item() {
+        let ids = Rc::new(RefCell::new(Vec::new()));
+        let clicks = Rc::new(Cell::new(0));
+        let (ids_2, clicks_2) = (ids.clone(), clicks.clone());
+        let list = List::new(move || {
+            let id = WidgetId::next();
+            ids_2.borrow_mut().push(id);
+            let clicks = clicks_2.clone();
+            Button::new("item")
+                .on_click(move |_, _, _| clicks.set(clicks.get() + 1))
+                .with_id(id)
+        })
+        .with_reorder();
+
+        Harness::create_simple(Arc::new(vec![0, 1, 2]), list, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let ids = ids.borrow().clone();
+            let first = harness.get_state(ids[0]).layout_rect();
+            let last = harness.get_state(ids[2]).layout_rect();
+
+            drag(harness, first.center().y, last.max_y() - 1.);
+            assert_eq!(**harness.data(), [1, 2, 0]);
+            assert_eq!(clicks.get(), 0);
+            for id in &ids {
+                assert!(!harness.get_state(*id).is_active);
+            }
+
+            // the items can still be clicked
+            drag(harness, first.center().y, first.center().y);
+            assert_eq!(clicks.get(), 1);
+        });
+    }
+
+    #[test]
+    fn move_item() {
+        let mut items = Arc::new(vec!['a', 'b', 'c', 'd']);
+        assert!(items.move_item(0, 2));
+        assert_eq!(*items, ['b', 'c', 'a', 'd']);
+        assert!(items.move_item(3, 0));
+        assert_eq!(*items, ['d', 'b', 'c', 'a']);
+        assert!(!items.move_item(1, 4));
+        assert_eq!(*items, ['d', 'b', 'c', 'a']);
+    }
+
+    #[test]
+    fn indexed_items() {
+        let painted = Rc::new(RefCell::new(Vec::new()));
+        let log = painted.clone();
+        let list = List::new(move || {
+            let log = log.clone();
+            Painter::new(move |_, (i, item): &(usize, char), _| {
+                log.borrow_mut().push(format!("{}{}", i, item))
+            })
+            .fix_height(10.)
+        })
+        .lens(Indexed::lens());
+
+        Harness::create_simple(Arc::new(vec!['a', 'b', 'c']), list, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let paint = |harness: &mut Harness<Arc<Vec<char>>>| {
+                painted.borrow_mut().clear();
+                harness.paint();
+                painted.borrow().clone()
+            };
+            assert_eq!(paint(harness), ["0a", "1b", "2c"]);
+
+            harness.update_data(|items| Arc::make_mut(items).insert(1, 'x'));
+            harness.just_layout();
+            assert_eq!(paint(harness), ["0a", "1x", "2b", "3c"]);
+            harness.update_data(|items| {
+                Arc::make_mut(items).remove(0);
+            });
+            harness.just_layout();
+            assert_eq!(paint(harness), ["0x", "1b", "2c"]);
+        });
+    }
+
+    #[test]
+    fn indexed_writes_back_items() {
+        let mut items = Indexed(Arc::new(vec![1, 2, 3]));
+        let original = items.clone();
+        // changing only the index doesn't change the items
+        items.for_each_mut(|(i, _), _| *i += 1);
+        assert!(items.same(&original));
+
+        items.for_each_mut_in_range(1..2, |(i, item), _| *item *= *i * 10);
+        assert_eq!(*items.0, [1, 20, 3]);
+        assert!(items.move_item(0, 2));
+        let mut seen = Vec::new();
+        items.for_each(|(i, item), _| seen.push((*i, *item)));
+        assert_eq!(seen, [(0, 20), (1, 3), (2, 1)]);
+    }
+
+    #[test]
+    fn type_ahead() {
+        let id = WidgetId::next();
+        let items: Vec<String> = ["apple", "banana", "blueberry", "cherry"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let list = WidgetExt::<Arc<Vec<String>>>::with_id(
+            List::new(|| Label::dynamic(|item: &String, _| item.clone()))
+                .with_type_ahead(|item: &String| item.clone()),
+            id,
+        );
+
+        Harness::create_simple(Arc::new(items), list, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseDown(move_mouse((1., 1.))));
+
+            type_key(harness, Modifiers::default(), "B");
+            assert_eq!(highlighted(harness, id), Some(1));
+            type_key(harness, Modifiers::default(), "l");
+            assert_eq!(highlighted(harness, id), Some(2));
+            // Shortcuts are ignored.
+            type_key(harness, Modifiers::CONTROL, "c");
+            assert_eq!(highlighted(harness, id), Some(2));
+            // No match keeps the current item.
+            type_key(harness, Modifiers::default(), "x");
+            assert_eq!(highlighted(harness, id), Some(2));
+
+            // After the timeout a new search starts, after the current item.
+            let timers: Vec<_> = harness.window().timers.keys().copied().collect();
+            for token in timers {
+                harness.event(Event::Timer(token));
+            }
+            type_key(harness, Modifiers::default(), "b");
+            assert_eq!(highlighted(harness, id), Some(1));
+        });
+    }
+
+    #[test]
+    fn type_ahead_empty_list() {
+        let id = WidgetId::next();
+        let list = WidgetExt::<Arc<Vec<String>>>::with_id(
+            List::new(|| Label::dynamic(|item: &String, _| item.clone()))
+                .with_type_ahead(|item: &String| item.clone()),
+            id,
+        );
+
+        Harness::create_simple(Arc::new(Vec::new()), list, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseDown(move_mouse((1., 1.))));
+            type_key(harness, Modifiers::default(), "a");
+            assert_eq!(highlighted(harness, id), None);
+        });
+    }
+
+    #[test]
+    fn select_with_mouse() {
+        Harness::create_simple(items(4), selectable_list(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            click(harness, 10., Modifiers::default());
+            assert_eq!(selection(harness), vec![0]);
+            click(harness, 50., Modifiers::CONTROL);
+            assert_eq!(selection(harness), vec![0, 2]);
+            click(harness, 10., Modifiers::CONTROL);
+            assert_eq!(selection(harness), vec![2]);
+            click(harness, 70., Modifiers::SHIFT);
+            assert_eq!(selection(harness), vec![0, 1, 2, 3]);
+            // Clicking empty space clears the selection.
+            click(harness, 200., Modifiers::default());
+            assert_eq!(selection(harness), Vec::<usize>::new());
+        });
+    }
+
+    #[test]
+    fn select_with_keyboard() {
+        Harness::create_simple(items(4), selectable_list(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            click(harness, 10., Modifiers::default());
+            press(harness, Modifiers::default(), KbKey::ArrowDown);
+            assert_eq!(selection(harness), vec![1]);
+            press(harness, Modifiers::SHIFT, KbKey::ArrowDown);
+            press(harness, Modifiers::SHIFT, KbKey::ArrowDown);
+            assert_eq!(selection(harness), vec![1, 2, 3]);
+            // The cursor stops at the last item.
+            press(harness, Modifiers::SHIFT, KbKey::ArrowDown);
+            assert_eq!(selection(harness), vec![1, 2, 3]);
+
+            // Removed items are removed from the selection.
+            harness.submit_command(TRUNCATE.with(2));
+            assert_eq!(harness.data().1.len(), 2);
+            // The harness only runs commands submitted during `update` on the next event.
+            harness.event(Event::MouseMove(move_mouse((10., 10.))));
+            assert_eq!(selection(harness), vec![1]);
+            // The cursor was removed as well, so we start again from the end.
+            press(harness, Modifiers::default(), KbKey::ArrowUp);
+            assert_eq!(selection(harness), vec![1]);
+            press(harness, Modifiers::default(), KbKey::ArrowUp);
+            assert_eq!(selection(harness), vec![0]);
+        });
+    }
+}
@@ -0,0 +1,617 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that renders Markdown.
+
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use pulldown_cmark::{Event as ParseEvent, Options, Parser, Tag};
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::Line;
+use crate::piet::TextStorage as PietTextStorage;
+use crate::text::{AttributesAdder, RichText, RichTextBuilder};
+use crate::widget::prelude::*;
+use crate::widget::{CrossAxisAlignment, Flex, LineBreaking, RawLabel, Scroll};
+use crate::{theme, FontFamily, FontStyle, FontWeight, Point, Rect, Selector, WidgetPod};
+
+/// How much list items and quotes are indented, per level.
+const INDENT: f64 = 20.0;
+/// The padding around the text of code blocks.
+const CODE_PADDING: f64 = 6.0;
+/// The width of the bar to the left of quotes.
+const QUOTE_BAR_WIDTH: f64 = 3.0;
+/// The height of horizontal rules, including the space around the line.
+const RULE_HEIGHT: f64 = 9.0;
+/// The text size of headings, relative to [`theme::TEXT_SIZE_NORMAL`], by level.
+const HEADING_SCALES: [f64; 6] = [2.0, 1.6, 1.3, 1.15, 1.0, 0.9];
+
+/// A widget that renders a Markdown string.
+///
+/// The text is parsed with [pulldown-cmark], and shown in a vertically
+/// scrolling view. Headings, emphasis, strikethrough, code, lists, quotes
+/// and rules are styled; images are shown as their alternative text.
+///
+/// The text uses the fonts and colors of the [`Env`]; headings are sized
+/// relative to [`TEXT_SIZE_NORMAL`], code blocks have a
+/// [`BACKGROUND_LIGHT`] background, and links have the link colors.
+/// Clicking a link submits [`Markdown::OPEN_LINK`] with its destination.
+///
+/// The text is rendered again whenever the data changes.
+///
+/// This widget requires the `markdown` feature.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::Markdown;
+/// use druid::{AppDelegate, Command, DelegateCtx, Env, Handled, Target};
+///
+/// let release_notes = Markdown::<String>::new();
+///
+/// struct Delegate;
+///
+/// impl AppDelegate<String> for Delegate {
+///     fn command(
+///         &mut self,
+///         _: &mut DelegateCtx,
+///         _: Target,
+///         cmd: &Command,
+///         _: &mut String,
+///         _: &Env,
+///     ) -> Handled {
+///         if let Some(url) = cmd.get(Markdown::OPEN_LINK) {
+///             println!("Opening {}", url);
+///             return Handled::Yes;
+///         }
+///         Handled::No
+///     }
+/// }
+/// ```
+///
+/// [pulldown-cmark]: https://crates.io/crates/pulldown-cmark
+/// [`TEXT_SIZE_NORMAL`]: theme::TEXT_SIZE_NORMAL
+/// [`BACKGROUND_LIGHT`]: theme::BACKGROUND_LIGHT
+pub struct Markdown<T> {
+    content: WidgetPod<Blocks, Scroll<Blocks, BlockList>>,
+    blocks: Blocks,
+    phantom: PhantomData<T>,
+}
+
+impl Markdown<()> {
+    /// The command submitted when a link is clicked, with the link's
+    /// destination.
+    pub const OPEN_LINK: Selector<String> = Selector::new("druid-builtin.markdown-open-link");
+}
+
+impl<T: Data + AsRef<str>> Markdown<T> {
+    /// Create a widget that renders its data as Markdown.
+    pub fn new() -> Self {
+        Markdown {
+            content: WidgetPod::new(Scroll::new(BlockList::new()).vertical()),
+            blocks: Rc::new(Vec::new()),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Data + AsRef<str>> Default for Markdown<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data + AsRef<str>> Widget<T> for Markdown<T> {
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, event, _data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
+        let mut blocks = self.blocks.clone();
+        self.content.event(ctx, event, &mut blocks, env);
+    }
+
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.blocks = Rc::new(render(data.as_ref(), env));
+        }
+        self.content.lifecycle(ctx, event, &self.blocks, env);
+    }
+
+    #[instrument(
+        name = "Markdown",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) || ctx.env_key_changed(&theme::TEXT_SIZE_NORMAL) {
+            trace!("Rendering markdown");
+            self.blocks = Rc::new(render(data.as_ref(), env));
+        }
+        self.content.update(ctx, &self.blocks, env);
+    }
+
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("Markdown");
+        let size = self.content.layout(ctx, bc, &self.blocks, env);
+        self.content
+            .set_origin(ctx, &self.blocks, env, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        self.content.paint(ctx, &self.blocks, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.as_ref().to_string(),
+            children: vec![self.content.widget().child().debug_state(&self.blocks)],
+            ..Default::default()
+        }
+    }
+}
+
+/// The column of widgets showing the rendered blocks, which is rebuilt when
+/// they change.
+struct BlockList {
+    column: Flex<()>,
+}
+
+impl BlockList {
+    fn new() -> Self {
+        BlockList {
+            column: Flex::column(),
+        }
+    }
+
+    fn rebuild(&mut self, blocks: &[Block]) {
+        let mut column = Flex::column().cross_axis_alignment(CrossAxisAlignment::Fill);
+        for (i, block) in blocks.iter().enumerate() {
+            if i > 0 {
+                column.add_default_spacer();
+            }
+            column.add_child(BlockView::new(block.clone()));
+        }
+        self.column = column;
+    }
+}
+
+impl Widget<Blocks> for BlockList {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _: &mut Blocks, env: &Env) {
+        self.column.event(ctx, event, &mut (), env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Blocks, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.rebuild(data);
+        }
+        self.column.lifecycle(ctx, event, &(), env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &Blocks, data: &Blocks, env: &Env) {
+        if !old_data.same(data) {
+            self.rebuild(data);
+            ctx.children_changed();
+        } else {
+            self.column.update(ctx, &(), &(), env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _: &Blocks, env: &Env) -> Size {
+        self.column.layout(ctx, bc, &(), env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _: &Blocks, env: &Env) {
+        self.column.paint(ctx, &(), env);
+    }
+
+    fn debug_state(&self, _: &Blocks) -> DebugState {
+        self.column.debug_state(&())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BlockKind {
+    Text,
+    Heading(u32),
+    Code,
+    Rule,
+}
+
+/// The rendered blocks; they are only compared by identity.
+type Blocks = Rc<Vec<Block>>;
+
+/// A paragraph, heading, list item, code block or rule.
+#[derive(Clone)]
+struct Block {
+    text: RichText,
+    kind: BlockKind,
+    /// How deep the block is in lists.
+    lists: usize,
+    /// How deep the block is in quotes.
+    quotes: usize,
+}
+
+/// Turns the events of the Markdown parser into [`Block`]s.
+#[derive(Default)]
+struct Renderer<'a> {
+    blocks: Vec<Block>,
+    /// The text of the block being rendered, and its kind.
+    current: Option<(RichTextBuilder, BlockKind)>,
+    /// The length of the current block's text.
+    len: usize,
+    /// Whether a code block's text still needs its last newline.
+    newline: bool,
+    /// The open inline tags, with the positions where they start.
+    inline: Vec<(usize, Tag<'a>)>,
+    /// The number of the next item of each open list, or `None` for bullet lists.
+    lists: Vec<Option<u64>>,
+    quotes: usize,
+}
+
+/// Parse `source`, and render it into blocks with the fonts of `env`.
+fn render(source: &str, env: &Env) -> Vec<Block> {
+    let mut renderer = Renderer::default();
+    let options = Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    for event in Parser::new_ext(source, options) {
+        renderer.event(event, env);
+    }
+    renderer.finish_block();
+    renderer.blocks
+}
+
+impl<'a> Renderer<'a> {
+    fn event(&mut self, event: ParseEvent<'a>, env: &Env) {
+        match event {
+            ParseEvent::Start(tag) => self.start(tag),
+            ParseEvent::End(tag) => self.end(tag, env),
+            ParseEvent::Text(text) => self.push(&text),
+            ParseEvent::Code(code) => {
+                let start = self.len;
+                self.push(&code);
+                self.attributes(start).font_family(FontFamily::MONOSPACE);
+            }
+            ParseEvent::Html(html) => self.push(&html),
+            ParseEvent::FootnoteReference(label) => self.push(&format!("[{}]", label)),
+            ParseEvent::SoftBreak => self.push(" "),
+            ParseEvent::HardBreak => self.push("\n"),
+            ParseEvent::Rule => {
+                self.finish_block();
+                self.blocks.push(Block {
+                    text: RichText::new("".into()),
+                    kind: BlockKind::Rule,
+                    lists: self.lists.len(),
+                    quotes: self.quotes,
+                });
+            }
+            ParseEvent::TaskListMarker(done) => self.push(if done { "☑ " } else { "☐ " }),
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'a>) {
+        match tag {
+            // a paragraph in a list item continues the item's text
+            Tag::Paragraph if self.current.is_some() => (),
+            Tag::Paragraph | Tag::TableHead | Tag::TableRow => self.start_block(BlockKind::Text),
+            Tag::Heading(level) => self.start_block(BlockKind::Heading(level)),
+            Tag::CodeBlock(_) => self.start_block(BlockKind::Code),
+            Tag::List(first) => {
+                self.finish_block();
+                self.lists.push(first);
+            }
+            Tag::Item => {
+                self.start_block(BlockKind::Text);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.push(&marker);
+            }
+            Tag::BlockQuote => {
+                self.finish_block();
+                self.quotes += 1;
+            }
+            Tag::FootnoteDefinition(ref label) => {
+                self.start_block(BlockKind::Text);
+                self.push(&format!("[{}] ", label));
+            }
+            Tag::Table(_) | Tag::TableCell => (),
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link(..) | Tag::Image(..) => {
+                self.inline.push((self.len, tag))
+            }
+        }
+    }
+
+    fn end(&mut self, tag: Tag<'a>, env: &Env) {
+        match tag {
+            Tag::Paragraph | Tag::CodeBlock(_) | Tag::Item | Tag::TableRow => self.finish_block(),
+            Tag::FootnoteDefinition(_) => self.finish_block(),
+            Tag::Heading(level) => {
+                let index = (level as usize).clamp(1, HEADING_SCALES.len()) - 1;
+                let size = env.get(theme::TEXT_SIZE_NORMAL) * HEADING_SCALES[index];
+                self.attributes(0).size(size).weight(FontWeight::BOLD);
+                self.finish_block();
+            }
+            Tag::TableHead => {
+                self.attributes(0).weight(FontWeight::BOLD);
+                self.finish_block();
+            }
+            Tag::TableCell => self.push("   "),
+            Tag::List(_) => {
+                self.finish_block();
+                self.lists.pop();
+            }
+            Tag::BlockQuote => {
+                self.finish_block();
+                self.quotes -= 1;
+            }
+            Tag::Table(_) => (),
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link(..) | Tag::Image(..) => {
+                let (start, tag) = self.inline.pop().expect("the parser balances tags");
+                let mut attributes = self.attributes(start);
+                match tag {
+                    Tag::Emphasis => {
+                        attributes.style(FontStyle::Italic);
+                    }
+                    Tag::Strong => {
+                        attributes.weight(FontWeight::BOLD);
+                    }
+                    Tag::Strikethrough => {
+                        attributes.strikethrough(true);
+                    }
+                    Tag::Link(_, destination, _) => {
+                        attributes.link(Markdown::OPEN_LINK.with(destination.to_string()));
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    /// Add `text` to the current block, starting a paragraph if there is none.
+    fn push(&mut self, text: &str) {
+        if self.current.is_none() {
+            self.start_block(BlockKind::Text);
+        }
+        let (builder, kind) = self.current.as_mut().unwrap();
+        // the newline at the end of a code block would make an empty line
+        let text = if *kind == BlockKind::Code {
+            if std::mem::take(&mut self.newline) {
+                builder.push("\n");
+                self.len += 1;
+            }
+            match text.strip_suffix('\n') {
+                Some(text) => {
+                    self.newline = true;
+                    text
+                }
+                None => text,
+            }
+        } else {
+            text
+        };
+        builder.push(text);
+        self.len += text.len();
+    }
+
+    /// The attributes of the current block's text from `start` to its end.
+    fn attributes(&mut self, start: usize) -> AttributesAdder<'_> {
+        let len = self.len;
+        match &mut self.current {
+            Some((builder, _)) => builder.add_attributes_for_range(start..len),
+            None => unreachable!("inline tags are in blocks"),
+        }
+    }
+
+    fn start_block(&mut self, kind: BlockKind) {
+        self.finish_block();
+        self.current = Some((RichTextBuilder::new(), kind));
+    }
+
+    fn finish_block(&mut self) {
+        if let Some((mut builder, kind)) = self.current.take() {
+            if kind == BlockKind::Code {
+                builder
+                    .add_attributes_for_range(..)
+                    .font_family(FontFamily::MONOSPACE);
+            }
+            if self.len > 0 {
+                self.blocks.push(Block {
+                    text: builder.build(),
+                    kind,
+                    lists: self.lists.len(),
+                    quotes: self.quotes,
+                });
+            }
+        }
+        self.len = 0;
+        self.newline = false;
+    }
+}
+
+/// The widget showing a [`Block`].
+struct BlockView {
+    block: Block,
+    label: WidgetPod<RichText, RawLabel<RichText>>,
+}
+
+impl BlockView {
+    fn new(block: Block) -> Self {
+        let label = RawLabel::new().with_line_break_mode(LineBreaking::WordWrap);
+        BlockView {
+            block,
+            label: WidgetPod::new(label),
+        }
+    }
+
+    /// The space left of the text, for lists and quotes.
+    fn indent(&self) -> f64 {
+        (self.block.lists + self.block.quotes) as f64 * INDENT
+    }
+}
+
+impl Widget<()> for BlockView {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _: &mut (), env: &Env) {
+        self.label.event(ctx, event, &mut self.block.text, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _: &(), env: &Env) {
+        self.label.lifecycle(ctx, event, &self.block.text, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _: &(), _: &(), env: &Env) {
+        self.label.update(ctx, &self.block.text, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _: &(), env: &Env) -> Size {
+        let indent = self.indent();
+        if self.block.kind == BlockKind::Rule {
+            let width = if bc.is_width_bounded() {
+                bc.max().width
+            } else {
+                indent
+            };
+            return bc.constrain(Size::new(width, RULE_HEIGHT));
+        }
+        let padding = if self.block.kind == BlockKind::Code {
+            CODE_PADDING
+        } else {
+            0.0
+        };
+        let label_bc = bc.shrink((indent + 2.0 * padding, 2.0 * padding)).loosen();
+        let label_size = self.label.layout(ctx, &label_bc, &self.block.text, env);
+        let origin = Point::new(indent + padding, padding);
+        self.label.set_origin(ctx, &self.block.text, env, origin);
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            label_size.width + indent + 2.0 * padding
+        };
+        bc.constrain(Size::new(width, label_size.height + 2.0 * padding))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _: &(), env: &Env) {
+        let size = ctx.size();
+        let lists = self.block.lists as f64 * INDENT;
+        for quote in 0..self.block.quotes {
+            let x = lists + quote as f64 * INDENT;
+            let bar = Rect::new(x, 0.0, x + QUOTE_BAR_WIDTH, size.height);
+            ctx.fill(bar, &env.get(theme::BORDER_LIGHT));
+        }
+        match self.block.kind {
+            BlockKind::Code => {
+                let background = Rect::new(self.indent(), 0.0, size.width, size.height)
+                    .to_rounded_rect(env.get(theme::TEXTBOX_BORDER_RADIUS));
+                ctx.fill(background, &env.get(theme::BACKGROUND_LIGHT));
+            }
+            BlockKind::Rule => {
+                let y = (size.height / 2.0).round() + 0.5;
+                let line = Line::new((self.indent(), y), (size.width, y));
+                ctx.stroke(line, &env.get(theme::BORDER_LIGHT), 1.0);
+                return;
+            }
+            BlockKind::Text | BlockKind::Heading(_) => (),
+        }
+        self.label.paint(ctx, &self.block.text, env);
+    }
+
+    fn debug_state(&self, _: &()) -> DebugState {
+        DebugState {
+            display_name: format!("{:?}", self.block.kind),
+            main_value: self.block.text.as_str().to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::text::TextStorage;
+    use test_log::test;
+
+    /// The kinds, indentation and text of the blocks rendered from `source`.
+    fn rendered(source: &str) -> Vec<(BlockKind, usize, String)> {
+        render(source, &Env::empty().adding(theme::TEXT_SIZE_NORMAL, 15.0))
+            .into_iter()
+            .map(|block| (block.kind, block.lists, block.text.as_str().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn blocks() {
+        let source = "# Notes\n\n\
+                      Some *new* features:\n\n\
+                      1. Markdown\n   - with lists\n2. `code`\n\n\
+                      ```\nfn main() {}\n```\n\n\
+                      ---";
+        assert_eq!(
+            rendered(source),
+            vec![
+                (BlockKind::Heading(1), 0, "Notes".to_string()),
+                (BlockKind::Text, 0, "Some new features:".to_string()),
+                (BlockKind::Text, 1, "1. Markdown".to_string()),
+                (BlockKind::Text, 2, "• with lists".to_string()),
+                (BlockKind::Text, 1, "2. code".to_string()),
+                (BlockKind::Code, 0, "fn main() {}".to_string()),
+                (BlockKind::Rule, 0, "".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn links() {
+        let env = Env::empty().adding(theme::TEXT_SIZE_NORMAL, 15.0);
+        let blocks = render("See [the docs](https://docs.rs/druid).", &env);
+        let links = blocks[0].text.links();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].range, 4..12);
+        assert_eq!(
+            links[0]
+                .command
+                .get(Markdown::OPEN_LINK)
+                .map(String::as_str),
+            Some("https://docs.rs/druid")
+        );
+    }
+
+    #[test]
+    fn renders_changes() {
+        let blocks = |harness: &Harness<String>| -> Vec<String> {
+            harness.get_root_debug_state().children[0]
+                .children
+                .iter()
+                .map(|block| block.main_value.clone())
+                .collect()
+        };
+        let source = "One paragraph.".to_string();
+        Harness::create_simple(source, Markdown::new(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(blocks(harness), ["One paragraph."]);
+
+            harness.update_data(|source| source.push_str("\n\nAnd *another*."));
+            harness.just_layout();
+            assert_eq!(blocks(harness), ["One paragraph.", "And another."]);
+        });
+    }
+}
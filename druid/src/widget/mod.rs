@@ -20,34 +20,67 @@ mod widget_wrapper;
 
 mod added;
 mod align;
+mod animated_switcher;
+mod annotated_textbox;
 mod aspect_ratio_box;
+mod badge;
+mod blur;
 mod button;
+mod canvas;
+mod chart;
 mod checkbox;
 mod click;
 mod clip_box;
+mod code_view;
+mod color_picker;
+mod combo_box;
+mod command_palette;
 mod common;
 mod container;
 mod controller;
+mod date_picker;
+mod debounce;
+mod dialog;
+mod diff_view;
 mod disable_if;
+mod divider;
+mod drag_and_drop;
 mod either;
 mod env_scope;
+mod find_replace;
 mod flex;
+mod focus_order;
+#[cfg(all(feature = "image", feature = "gif"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "image", feature = "gif"))))]
+mod gif_image;
+mod grid;
 mod identity_wrapper;
 mod image;
 mod invalidation;
 mod label;
+mod lazy_list;
 mod lens_wrap;
 mod list;
+#[cfg(feature = "markdown")]
+#[cfg_attr(docsrs, doc(cfg(feature = "markdown")))]
+mod markdown;
 mod maybe;
+mod numeric_textbox;
+mod opacity;
+mod overlay;
 mod padding;
 mod painter;
+mod pan_zoom;
 mod parse;
 mod progress_bar;
 mod radio;
 mod scope;
 mod scroll;
+mod segmented_control;
+mod set_cursor;
 mod sized_box;
 mod slider;
+mod sparkline;
 mod spinner;
 mod split;
 mod stepper;
@@ -57,56 +90,108 @@ mod svg;
 mod switch;
 mod tabs;
 mod textbox;
+mod throttle;
+mod toast;
+mod tooltip;
+mod tree;
 mod value_textbox;
 mod view_switcher;
+mod visible;
 #[allow(clippy::module_inception)]
 mod widget;
 mod widget_ext;
+mod window_drag_region;
+mod wire;
+mod z_stack;
 
-pub use self::image::Image;
+pub use self::image::{Image, LazyImage};
 pub use added::Added;
 pub use align::Align;
+pub use animated_switcher::{AnimatedSwitcher, SwitcherTransition};
+pub use annotated_textbox::AnnotatedTextBox;
 pub use aspect_ratio_box::AspectRatioBox;
+pub use badge::Badge;
+pub use blur::{Backdrop, Blur};
 pub use button::Button;
+pub use canvas::Canvas;
+pub use chart::{Chart, ChartKind, Series};
 pub use checkbox::Checkbox;
 pub use click::Click;
 pub use clip_box::{ClipBox, Viewport};
-pub use common::FillStrat;
+pub use code_view::{CodeView, Highlighter, PlainText, TokenKind};
+pub use color_picker::{ColorPicker, HsvColor};
+pub use combo_box::{AutoComplete, ComboBox};
+pub use command_palette::{CommandPalette, PaletteAction};
+pub use common::{FillStrat, LayoutDirection};
 pub use container::Container;
 pub use controller::{Controller, ControllerHost};
+pub use date_picker::{CalendarDate, DatePicker};
+pub use debounce::Debounce;
+pub use dialog::Dialog;
+pub use diff_view::{DiffLayout, DiffView};
 pub use disable_if::DisabledIf;
+pub use divider::Divider;
+pub use drag_and_drop::{DragSource, DropTarget};
 pub use either::Either;
 pub use env_scope::EnvScope;
+pub use find_replace::{FindFieldController, FindReplace};
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use focus_order::{FocusGroup, FocusOrder};
+#[cfg(all(feature = "image", feature = "gif"))]
+pub use gif_image::GifImage;
+pub use grid::{Grid, TrackSize};
 pub use identity_wrapper::IdentityWrapper;
 pub use label::{Label, LabelText, LineBreaking, RawLabel};
+pub use lazy_list::LazyList;
 pub use lens_wrap::LensWrap;
-pub use list::{List, ListIter};
+pub use list::{Indexed, List, ListIter, SelectableList};
+#[cfg(feature = "markdown")]
+pub use markdown::Markdown;
 pub use maybe::Maybe;
+pub use numeric_textbox::NumericTextBox;
+pub use opacity::Opacity;
+pub use overlay::{OverlayLayer, Popup, PopupAnchor, PopupId};
 pub use padding::Padding;
 pub use painter::{BackgroundBrush, Painter};
+pub use pan_zoom::{PanZoom, ViewTransform};
 pub use parse::Parse;
 pub use progress_bar::ProgressBar;
 pub use radio::{Radio, RadioGroup};
-pub use scope::{DefaultScopePolicy, LensScopeTransfer, Scope, ScopePolicy, ScopeTransfer};
+pub use scope::{
+    DefaultScopePolicy, LensScopeTransfer, LocalStatePolicy, LocalStateTransfer, Scope,
+    ScopePolicy, ScopeTransfer,
+};
 pub use scroll::Scroll;
+pub use segmented_control::SegmentedControl;
+pub use set_cursor::SetCursor;
 pub use sized_box::SizedBox;
 pub use slider::{KnobStyle, RangeSlider, Slider};
-pub use spinner::Spinner;
+pub use sparkline::{Sparkline, SparklineData};
+pub use spinner::{Spinner, SpinnerStyle};
 pub use split::Split;
-pub use stepper::Stepper;
+pub use stepper::{Stepper, StepperValue};
 #[cfg(feature = "svg")]
 pub use svg::{Svg, SvgData};
 pub use switch::Switch;
+#[cfg(feature = "im")]
+pub use tabs::{DynamicTabs, TabModel};
 pub use tabs::{TabInfo, Tabs, TabsEdge, TabsPolicy, TabsState, TabsTransition};
-pub use textbox::TextBox;
+pub use textbox::{GutterClick, TextBox};
+pub use throttle::Throttle;
+pub use toast::{Toast, ToastLevel, ToastOverlay};
+pub use tooltip::Tooltip;
+pub use tree::{Tree, TreeNode};
 pub use value_textbox::{TextBoxEvent, ValidationDelegate, ValueTextBox};
 pub use view_switcher::ViewSwitcher;
+pub use visible::{VisibilityMode, Visible};
 #[doc(hidden)]
 pub use widget::{Widget, WidgetId};
 #[doc(hidden)]
 pub use widget_ext::WidgetExt;
 pub use widget_wrapper::WidgetWrapper;
+pub use window_drag_region::WindowDragRegion;
+pub use wire::Wire;
+pub use z_stack::ZStack;
 
 /// The types required to implement a `Widget`.
 ///
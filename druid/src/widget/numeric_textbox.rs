@@ -0,0 +1,374 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A textbox for editing numbers.
+
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use tracing::instrument;
+
+use super::{TextBox, TextBoxEvent, ValidationDelegate, ValueTextBox};
+use crate::debug_state::DebugState;
+use crate::text::{Formatter, Selection, Validation, ValidationError};
+use crate::widget::prelude::*;
+use crate::{theme, Data, LocalizedString};
+
+/// A text box for editing a number.
+///
+/// The value is parsed with [`FromStr`] and displayed with [`Display`], using
+/// the decimal and thousands separators of the current locale. Separators are
+/// omitted while editing, and are accepted (and ignored) when parsing.
+///
+/// Edits are committed when the user presses enter or the text box loses
+/// focus, and reverted when the user presses escape. While the text cannot be
+/// parsed the data is left untouched, and the border is drawn with the
+/// [`TEXTBOX_ERROR_BORDER_COLOR`] color.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::NumericTextBox;
+/// use druid::Widget;
+///
+/// fn volume_box() -> impl Widget<f64> {
+///     NumericTextBox::new().with_range(0.0..=100.0)
+/// }
+/// ```
+///
+/// [`TEXTBOX_ERROR_BORDER_COLOR`]: crate::theme::TEXTBOX_ERROR_BORDER_COLOR
+pub struct NumericTextBox<T> {
+    inner: ValueTextBox<T>,
+    separators: Rc<Cell<Separators>>,
+    invalid: Rc<Cell<bool>>,
+    decimal_str: LocalizedString<()>,
+    group_str: LocalizedString<()>,
+}
+
+/// The locale-dependent characters used when formatting a number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Separators {
+    decimal: char,
+    group: char,
+}
+
+/// The [`Formatter`] used by [`NumericTextBox`].
+struct NumericFormatter<T> {
+    separators: Rc<Cell<Separators>>,
+    range: Option<RangeInclusive<T>>,
+}
+
+/// Tracks whether the current text of a [`NumericTextBox`] is valid.
+struct InvalidFlag(Rc<Cell<bool>>);
+
+impl Default for Separators {
+    fn default() -> Self {
+        Separators {
+            decimal: '.',
+            group: ',',
+        }
+    }
+}
+
+impl<T> NumericTextBox<T>
+where
+    T: Data + FromStr + Display + PartialOrd + Debug,
+    <T as FromStr>::Err: Error + 'static,
+{
+    /// Create a new `NumericTextBox`.
+    pub fn new() -> Self {
+        let separators = Rc::new(Cell::new(Separators::default()));
+        let invalid = Rc::new(Cell::new(false));
+        NumericTextBox {
+            inner: Self::make_inner(separators.clone(), None, invalid.clone()),
+            separators,
+            invalid,
+            decimal_str: LocalizedString::new("number-decimal-separator").with_placeholder("."),
+            group_str: LocalizedString::new("number-group-separator").with_placeholder(","),
+        }
+    }
+
+    /// Builder-style method to clamp committed values to `range`.
+    ///
+    /// Values outside of the range are not considered invalid; they are
+    /// replaced by the nearest bound when editing completes.
+    pub fn with_range(mut self, range: RangeInclusive<T>) -> Self {
+        self.inner = Self::make_inner(self.separators.clone(), Some(range), self.invalid.clone());
+        self
+    }
+
+    /// Returns `true` if the text currently being edited is not a valid number.
+    pub fn is_invalid(&self) -> bool {
+        self.invalid.get()
+    }
+
+    fn make_inner(
+        separators: Rc<Cell<Separators>>,
+        range: Option<RangeInclusive<T>>,
+        invalid: Rc<Cell<bool>>,
+    ) -> ValueTextBox<T> {
+        ValueTextBox::new(TextBox::new(), NumericFormatter { separators, range })
+            .validate_while_editing(false)
+            .delegate(InvalidFlag(invalid))
+    }
+
+    /// Update the separators from the locale; returns `true` if they changed.
+    fn resolve_separators(&mut self, env: &Env) -> bool {
+        self.decimal_str.resolve(&(), env);
+        self.group_str.resolve(&(), env);
+        let default = Separators::default();
+        let separators = Separators {
+            decimal: first_char(&self.decimal_str.localized_str()).unwrap_or(default.decimal),
+            group: first_char(&self.group_str.localized_str()).unwrap_or(default.group),
+        };
+        if separators == self.separators.get() {
+            false
+        } else {
+            self.separators.set(separators);
+            true
+        }
+    }
+}
+
+impl<T> Default for NumericTextBox<T>
+where
+    T: Data + FromStr + Display + PartialOrd + Debug,
+    <T as FromStr>::Err: Error + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: FromStr + PartialOrd + Clone> NumericFormatter<T> {
+    fn parse(&self, input: &str) -> Result<T, <T as FromStr>::Err> {
+        let separators = self.separators.get();
+        let normalized: String = input
+            .trim()
+            .chars()
+            .filter(|c| {
+                *c != separators.group && !(separators.group.is_whitespace() && c.is_whitespace())
+            })
+            .map(|c| if c == separators.decimal { '.' } else { c })
+            .collect();
+        let value = normalized.parse::<T>()?;
+        Ok(match &self.range {
+            Some(range) if value < *range.start() => range.start().clone(),
+            Some(range) if value > *range.end() => range.end().clone(),
+            _ => value,
+        })
+    }
+}
+
+impl<T: Display> NumericFormatter<T> {
+    fn localize(&self, value: &T, grouped: bool) -> String {
+        let separators = self.separators.get();
+        let plain = value.to_string();
+        let (sign, rest) = match plain.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", plain.as_str()),
+        };
+        let int_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (int, tail) = rest.split_at(int_len);
+
+        let mut result = String::with_capacity(plain.len() + int_len / 3);
+        result.push_str(sign);
+        for (i, digit) in int.chars().enumerate() {
+            if grouped && i > 0 && (int_len - i) % 3 == 0 {
+                result.push(separators.group);
+            }
+            result.push(digit);
+        }
+        result.extend(
+            tail.chars()
+                .map(|c| if c == '.' { separators.decimal } else { c }),
+        );
+        result
+    }
+}
+
+impl<T> Formatter<T> for NumericFormatter<T>
+where
+    T: FromStr + Display + PartialOrd + Clone,
+    <T as FromStr>::Err: Error + 'static,
+{
+    fn format(&self, value: &T) -> String {
+        self.localize(value, true)
+    }
+
+    fn format_for_editing(&self, value: &T) -> String {
+        self.localize(value, false)
+    }
+
+    fn validate_partial_input(&self, input: &str, _sel: &Selection) -> Validation {
+        match self.parse(input) {
+            Ok(_) => Validation::success(),
+            Err(err) => Validation::failure(err),
+        }
+    }
+
+    fn value(&self, input: &str) -> Result<T, ValidationError> {
+        self.parse(input).map_err(ValidationError::new)
+    }
+}
+
+impl ValidationDelegate for InvalidFlag {
+    fn event(&mut self, ctx: &mut EventCtx, event: TextBoxEvent, _current_text: &str) {
+        let invalid = matches!(
+            event,
+            TextBoxEvent::PartiallyInvalid(_) | TextBoxEvent::Invalid(_)
+        );
+        if invalid != self.0.get() {
+            self.0.set(invalid);
+            ctx.request_paint();
+        }
+    }
+}
+
+impl<T> Widget<T> for NumericTextBox<T>
+where
+    T: Data + FromStr + Display + PartialOrd + Debug,
+    <T as FromStr>::Err: Error + 'static,
+{
+    #[instrument(
+        name = "NumericTextBox",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.inner.event(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "NumericTextBox",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.resolve_separators(env);
+        }
+        self.inner.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "NumericTextBox",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if ctx.env_changed() && self.resolve_separators(env) {
+            self.inner.reformat(data);
+        }
+        self.inner.update(ctx, old_data, data, env)
+    }
+
+    #[instrument(
+        name = "NumericTextBox",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.inner.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "NumericTextBox", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if self.invalid.get() {
+            let color = env.get(theme::TEXTBOX_ERROR_BORDER_COLOR);
+            let env = env
+                .clone()
+                .adding(theme::BORDER_DARK, color.clone())
+                .adding(theme::PRIMARY_LIGHT, color);
+            self.inner.paint(ctx, data, &env);
+        } else {
+            self.inner.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut state = self.inner.debug_state(data);
+        state.display_name = self.short_type_name().to_string();
+        if self.invalid.get() {
+            state
+                .other_values
+                .insert("invalid".to_string(), "true".to_string());
+        }
+        state
+    }
+}
+
+fn first_char(s: &str) -> Option<char> {
+    s.chars().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    fn formatter<T>(decimal: char, group: char) -> NumericFormatter<T> {
+        NumericFormatter {
+            separators: Rc::new(Cell::new(Separators { decimal, group })),
+            range: None,
+        }
+    }
+
+    #[test]
+    fn formats_with_separators() {
+        let en = formatter::<f64>('.', ',');
+        assert_eq!(en.format(&1234567.5), "1,234,567.5");
+        assert_eq!(en.format(&-123.25), "-123.25");
+        assert_eq!(en.format_for_editing(&1234567.5), "1234567.5");
+
+        let de = formatter::<i64>(',', '.');
+        assert_eq!(de.format(&-1234567), "-1.234.567");
+        assert_eq!(de.format(&100), "100");
+
+        let fr = formatter::<f64>(',', '\u{a0}');
+        assert_eq!(fr.format(&12345.5), "12\u{a0}345,5");
+    }
+
+    #[test]
+    fn parses_localized_input() {
+        let de = formatter::<f64>(',', '.');
+        assert_eq!(de.value("1.234,5").unwrap(), 1234.5);
+        assert_eq!(de.value(" 12,25 ").unwrap(), 12.25);
+        assert!(de.value("1,2,3").is_err());
+        assert!(de
+            .validate_partial_input("12a", &Selection::caret(3))
+            .is_err());
+
+        let fr = formatter::<i32>(',', '\u{a0}');
+        assert_eq!(fr.value("12 345").unwrap(), 12345);
+    }
+
+    #[test]
+    fn clamps_to_range() {
+        let mut formatter = formatter::<i32>('.', ',');
+        formatter.range = Some(-10..=10);
+        assert_eq!(formatter.value("5").unwrap(), 5);
+        assert_eq!(formatter.value("1,000").unwrap(), 10);
+        assert_eq!(formatter.value("-11").unwrap(), -10);
+        assert!(!formatter
+            .validate_partial_input("99", &Selection::caret(2))
+            .is_err());
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that paints its child partially transparent.
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{Data, KeyOrValue, Lens, LensExt, Point, WidgetPod};
+use tracing::{instrument, trace};
+
+type OpacityFn<T> = dyn Fn(&T, &Env) -> f64;
+
+/// Where an [`Opacity`] gets its opacity from.
+enum OpacitySource<T> {
+    Value(KeyOrValue<f64>),
+    Dynamic(Box<OpacityFn<T>>),
+}
+
+/// A widget that paints its child with a reduced opacity.
+///
+/// The child is painted into a separate layer, which is then blended into the
+/// window, so the child doesn't need to support transparency itself. This
+/// makes it easy to fade widgets in and out, for instance with a
+/// [`LensAnimator`]. Nested `Opacity` widgets multiply their opacities.
+///
+/// The opacity goes from `0.0`, fully transparent, to `1.0`, opaque. A fully
+/// transparent child still receives mouse events, unless
+/// [`disable_events_when_transparent`] is set.
+///
+/// This is more conveniently used through [`WidgetExt::with_opacity`] and
+/// [`WidgetExt::with_opacity_lens`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Label, Opacity};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct State {
+///     fade: f64,
+/// }
+///
+/// fn faded() -> impl Widget<State> {
+///     Label::new("Now you see me").with_opacity_lens(State::fade)
+/// }
+///
+/// let _: Opacity<(), _> = Opacity::new(Label::new("Half there"), 0.5);
+/// ```
+///
+/// [`LensAnimator`]: crate::animation::LensAnimator
+/// [`disable_events_when_transparent`]: Opacity::disable_events_when_transparent
+/// [`WidgetExt::with_opacity`]: crate::WidgetExt::with_opacity
+/// [`WidgetExt::with_opacity_lens`]: crate::WidgetExt::with_opacity_lens
+pub struct Opacity<T, W> {
+    child: WidgetPod<T, W>,
+    opacity: OpacitySource<T>,
+    disable_events_when_transparent: bool,
+    /// The opacity as of the last update, to know when to repaint.
+    current: f64,
+}
+
+impl<T: Data, W: Widget<T>> Opacity<T, W> {
+    /// Create a new `Opacity` with a fixed opacity, or a [`Key`] referring to
+    /// one in the [`Env`].
+    ///
+    /// [`Key`]: crate::Key
+    pub fn new(child: W, opacity: impl Into<KeyOrValue<f64>>) -> Self {
+        Self::with_source(child, OpacitySource::Value(opacity.into()))
+    }
+
+    /// Create a new `Opacity` with the opacity selected by `lens`.
+    pub fn lens(child: W, lens: impl Lens<T, f64> + 'static) -> Self {
+        Self::dynamic(child, move |data, _| lens.get(data))
+    }
+
+    /// Create a new `Opacity` with the opacity computed from the data.
+    pub fn dynamic(child: W, opacity: impl Fn(&T, &Env) -> f64 + 'static) -> Self {
+        Self::with_source(child, OpacitySource::Dynamic(Box::new(opacity)))
+    }
+
+    fn with_source(child: W, opacity: OpacitySource<T>) -> Self {
+        Opacity {
+            child: WidgetPod::new(child),
+            opacity,
+            disable_events_when_transparent: false,
+            current: 1.0,
+        }
+    }
+
+    /// Builder-style method to stop sending mouse events to the child while
+    /// it is fully transparent.
+    pub fn disable_events_when_transparent(mut self, disable: bool) -> Self {
+        self.disable_events_when_transparent = disable;
+        self
+    }
+
+    fn resolve(&self, data: &T, env: &Env) -> f64 {
+        let opacity = match &self.opacity {
+            OpacitySource::Value(opacity) => opacity.resolve(env),
+            OpacitySource::Dynamic(opacity) => opacity(data, env),
+        };
+        opacity.clamp(0.0, 1.0)
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Opacity<T, W> {
+    #[instrument(name = "Opacity", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let transparent = self.disable_events_when_transparent && self.current <= 0.0;
+        let is_mouse_event = matches!(
+            event,
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::MouseMove(_) | Event::Wheel(_)
+        );
+        if transparent && is_mouse_event {
+            return;
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Opacity", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.current = self.resolve(data, env);
+        }
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Opacity", level = "trace", skip(self, ctx, _old, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        let opacity = self.resolve(data, env);
+        if opacity != self.current {
+            trace!("opacity changed to {}", opacity);
+            self.current = opacity;
+            ctx.request_paint();
+        }
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Opacity", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ZERO);
+        ctx.set_paint_insets(self.child.paint_insets());
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "Opacity", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let child = &mut self.child;
+        ctx.with_opacity(self.current, |ctx| child.paint(ctx, data, env));
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::widget::Painter;
+    use crate::{Color, RenderContext};
+
+    #[test]
+    fn opacities_multiply() {
+        let white = Painter::new(|ctx, _, _| {
+            let rect = ctx.size().to_rect();
+            ctx.fill(rect, &Color::WHITE);
+        });
+        let widget = Opacity::new(Opacity::new(white, 0.5), 0.5);
+        Harness::create_with_render(
+            (),
+            widget,
+            Size::new(10.0, 10.0),
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                harness.paint();
+            },
+            |target| {
+                let pixels = target.into_raw();
+                // white at a quarter opacity over the window background
+                let (r, g, b, _) = Env::with_default_i10n()
+                    .get(crate::theme::WINDOW_BACKGROUND_COLOR)
+                    .as_rgba8();
+                let expected = |c: u8| (c as f64 * 0.75 + 255.0 * 0.25).round() as i32;
+                for (actual, background) in pixels[..3].iter().zip([r, g, b]) {
+                    let diff = (*actual as i32 - expected(background)).abs();
+                    assert!(diff <= 2, "got {:?}", &pixels[..4]);
+                }
+            },
+        );
+    }
+}
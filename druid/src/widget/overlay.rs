@@ -0,0 +1,727 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Popups floating on top of the window.
+
+use tracing::{instrument, trace, warn};
+
+use crate::commands::{CLOSE_POPUP, DISMISS_POPUP, POPUP_DISMISSED, SHOW_POPUP};
+use crate::debug_state::DebugState;
+use crate::shell::Counter;
+use crate::widget::prelude::*;
+use crate::{InternalEvent, InternalLifeCycle, KbKey, Point, Rect, Selector, Vec2, WidgetPod};
+
+/// Sent by an [`OverlayLayer`] to itself to focus a popup, once the popup is
+/// part of the focus chain.
+const FOCUS_POPUP: Selector<PopupId> = Selector::new("druid-builtin.focus-popup");
+
+/// A unique identifier for a [`Popup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PopupId(u64);
+
+/// Where a [`Popup`] is placed, in window coordinates.
+///
+/// A popup is always moved so that it stays within its [`OverlayLayer`], if
+/// it fits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PopupAnchor {
+    /// Below the rectangle, aligned with its left edge; or above it, if the
+    /// popup doesn't fit below and there is more room above.
+    ///
+    /// This is meant for drop-downs attached to a widget, whose rectangle is
+    /// `Rect::from_origin_size(ctx.window_origin(), ctx.size())`.
+    Rect(Rect),
+    /// With the top left corner at the point. If the popup doesn't fit to the
+    /// right of or below the point, it is flipped to the other side, like a
+    /// context menu.
+    Point(Point),
+}
+
+/// Floating content for an [`OverlayLayer`], shown with
+/// [`EventCtx::show_popup`].
+///
+/// By default a popup takes the focus when it is shown, and is dismissed
+/// when the user clicks outside of it or presses escape.
+///
+/// [`EventCtx::show_popup`]: crate::EventCtx::show_popup
+pub struct Popup<T> {
+    id: PopupId,
+    widget: Box<dyn Widget<T>>,
+    anchor: PopupAnchor,
+    z_index: i32,
+    take_focus: bool,
+    dismiss_on_click_outside: bool,
+    dismiss_on_escape: bool,
+    dismiss_on_focus_loss: bool,
+    pub(crate) requester: Option<WidgetId>,
+}
+
+/// A widget that shows [`Popup`]s on top of its child.
+///
+/// This is usually the root widget of a window. Any widget in the window can
+/// then show a popup with [`EventCtx::show_popup`], for drop-downs,
+/// suggestions, and other floating content that should stay in the window.
+/// Popups have the same data as the layer, or `()` for popups that keep
+/// their state to themselves, such as those of widgets that can't know the
+/// type of the root data. They are laid out with loose constraints up to the
+/// size of the layer.
+///
+/// # Event routing
+///
+/// Popups are stacked by their [`z_index`], and then in the order they were
+/// shown, with the last one on top. Pointer events go to the topmost popup
+/// under the pointer, or to the child if there is none; the widgets covered
+/// by it don't become hot. A widget that is active keeps getting the pointer
+/// events, as usual.
+///
+/// A mouse down dismisses the popups above the one it hits that have
+/// [`dismiss_on_click_outside`] set. If it doesn't hit any popup and it
+/// dismissed one, it isn't passed to the child, so that closing a popup
+/// doesn't also click whatever is under it.
+///
+/// Keyboard events go to the focused widget, as usual; a popup that
+/// [takes the focus] gets it when it is shown, and gives it back to the
+/// widget that had it when it is dismissed. Escape dismisses the topmost
+/// popup with [`dismiss_on_escape`] set, before the focused widget sees it,
+/// as long as the focus is somewhere inside the layer.
+///
+/// A popup can be dismissed with [`CLOSE_POPUP`], or by its contents with the
+/// [`DISMISS_POPUP`] notification. The widget that showed it gets
+/// [`POPUP_DISMISSED`] however it was dismissed.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Label, OverlayLayer, Popup};
+/// use druid::{Color, Rect, Widget, WidgetExt};
+///
+/// fn root() -> impl Widget<()> {
+///     OverlayLayer::new(Button::new("Open").on_click(|ctx, _, _| {
+///         let anchor = Rect::from_origin_size(ctx.window_origin(), ctx.size());
+///         let content = Label::new("Hello").background(Color::BLACK);
+///         ctx.show_popup(Popup::<()>::new(anchor, content));
+///     }))
+/// }
+/// ```
+///
+/// [`EventCtx::show_popup`]: crate::EventCtx::show_popup
+/// [`z_index`]: Popup::z_index
+/// [`dismiss_on_click_outside`]: Popup::dismiss_on_click_outside
+/// [`dismiss_on_escape`]: Popup::dismiss_on_escape
+/// [takes the focus]: Popup::take_focus
+/// [`CLOSE_POPUP`]: crate::commands::CLOSE_POPUP
+/// [`DISMISS_POPUP`]: crate::commands::DISMISS_POPUP
+/// [`POPUP_DISMISSED`]: crate::commands::POPUP_DISMISSED
+pub struct OverlayLayer<T, W> {
+    child: WidgetPod<T, W>,
+    /// The popups, from bottom to top.
+    popups: Vec<ActivePopup<T>>,
+}
+
+/// A popup that is on screen.
+struct ActivePopup<T> {
+    id: PopupId,
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    /// The anchor, in the layer's coordinates.
+    anchor: PopupAnchor,
+    z_index: i32,
+    dismiss_on_click_outside: bool,
+    dismiss_on_escape: bool,
+    dismiss_on_focus_loss: bool,
+    requester: Option<WidgetId>,
+    /// The widget that had the focus when the popup took it.
+    previous_focus: Option<WidgetId>,
+    /// Whether the focus was in the popup, as of the last focus change.
+    had_focus: bool,
+}
+
+impl PopupId {
+    /// Allocate a new, unique popup id.
+    pub fn next() -> PopupId {
+        static POPUP_COUNTER: Counter = Counter::new();
+        PopupId(POPUP_COUNTER.next())
+    }
+}
+
+impl PopupAnchor {
+    /// Where a popup of `size` goes, to stay within `bounds`.
+    fn place(self, size: Size, bounds: Rect) -> Point {
+        let origin = match self {
+            PopupAnchor::Rect(rect) => {
+                let fits_below = rect.y1 + size.height <= bounds.y1;
+                let more_room_above = rect.y0 - bounds.y0 > bounds.y1 - rect.y1;
+                if fits_below || !more_room_above {
+                    Point::new(rect.x0, rect.y1)
+                } else {
+                    Point::new(rect.x0, rect.y0 - size.height)
+                }
+            }
+            PopupAnchor::Point(point) => {
+                let flip = |pos: f64, len: f64, min: f64, max: f64| {
+                    if pos + len > max && pos - len >= min {
+                        pos - len
+                    } else {
+                        pos
+                    }
+                };
+                Point::new(
+                    flip(point.x, size.width, bounds.x0, bounds.x1),
+                    flip(point.y, size.height, bounds.y0, bounds.y1),
+                )
+            }
+        };
+        // If the popup is too big, its top left corner stays visible.
+        Point::new(
+            origin.x.min(bounds.x1 - size.width).max(bounds.x0),
+            origin.y.min(bounds.y1 - size.height).max(bounds.y0),
+        )
+    }
+
+    fn translate(self, offset: Vec2) -> PopupAnchor {
+        match self {
+            PopupAnchor::Rect(rect) => PopupAnchor::Rect(rect + offset),
+            PopupAnchor::Point(point) => PopupAnchor::Point(point + offset),
+        }
+    }
+}
+
+impl From<Rect> for PopupAnchor {
+    fn from(rect: Rect) -> PopupAnchor {
+        PopupAnchor::Rect(rect)
+    }
+}
+
+impl From<Point> for PopupAnchor {
+    fn from(point: Point) -> PopupAnchor {
+        PopupAnchor::Point(point)
+    }
+}
+
+impl<T> Popup<T> {
+    /// Create a popup showing `widget` at `anchor`, a [`Rect`] or a [`Point`]
+    /// in window coordinates.
+    pub fn new(anchor: impl Into<PopupAnchor>, widget: impl Widget<T> + 'static) -> Self {
+        Popup {
+            id: PopupId::next(),
+            widget: Box::new(widget),
+            anchor: anchor.into(),
+            z_index: 0,
+            take_focus: true,
+            dismiss_on_click_outside: true,
+            dismiss_on_escape: true,
+            dismiss_on_focus_loss: false,
+            requester: None,
+        }
+    }
+
+    /// The id of this popup, to close it with [`CLOSE_POPUP`].
+    ///
+    /// [`CLOSE_POPUP`]: crate::commands::CLOSE_POPUP
+    pub fn id(&self) -> PopupId {
+        self.id
+    }
+
+    /// Builder-style method to set where the popup is stacked. Popups with a
+    /// higher `z_index` are on top of the others; the default is `0`.
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Builder-style method to set whether the popup takes the focus when it
+    /// is shown.
+    ///
+    /// The focus goes to the first widget in the popup that accepts focus, or
+    /// to the popup's widget itself. A popup that doesn't take the focus, such
+    /// as a list of suggestions for a text box, leaves it where it is.
+    pub fn take_focus(mut self, take_focus: bool) -> Self {
+        self.take_focus = take_focus;
+        self
+    }
+
+    /// Builder-style method to set whether the popup is dismissed by a click
+    /// outside of it.
+    pub fn dismiss_on_click_outside(mut self, dismiss: bool) -> Self {
+        self.dismiss_on_click_outside = dismiss;
+        self
+    }
+
+    /// Builder-style method to set whether the popup is dismissed by the
+    /// escape key.
+    pub fn dismiss_on_escape(mut self, dismiss: bool) -> Self {
+        self.dismiss_on_escape = dismiss;
+        self
+    }
+
+    /// Builder-style method to set whether the popup is dismissed when the
+    /// focus leaves it, or the window loses focus.
+    pub fn dismiss_on_focus_loss(mut self, dismiss: bool) -> Self {
+        self.dismiss_on_focus_loss = dismiss;
+        self
+    }
+}
+
+impl Popup<()> {
+    /// Turn a popup without data into one that ignores the data of the layer.
+    fn without_data<T: Data>(self) -> Popup<T> {
+        Popup {
+            id: self.id,
+            widget: Box::new(WithoutData(self.widget)),
+            anchor: self.anchor,
+            z_index: self.z_index,
+            take_focus: self.take_focus,
+            dismiss_on_click_outside: self.dismiss_on_click_outside,
+            dismiss_on_escape: self.dismiss_on_escape,
+            dismiss_on_focus_loss: self.dismiss_on_focus_loss,
+            requester: self.requester,
+        }
+    }
+}
+
+/// Shows the widget of a `Popup<()>` in a layer with other data.
+struct WithoutData(Box<dyn Widget<()>>);
+
+impl<T: Data> Widget<T> for WithoutData {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
+        self.0.event(ctx, event, &mut (), env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, env: &Env) {
+        self.0.lifecycle(ctx, event, &(), env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, _data: &T, env: &Env) {
+        self.0.update(ctx, &(), &(), env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        self.0.layout(ctx, bc, &(), env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        self.0.paint(ctx, &(), env)
+    }
+
+    fn debug_state(&self, _data: &T) -> DebugState {
+        self.0.debug_state(&())
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.0.id()
+    }
+}
+
+impl<T, W: Widget<T>> OverlayLayer<T, W> {
+    /// Create a widget that shows popups on top of `child`.
+    pub fn new(child: W) -> Self {
+        OverlayLayer {
+            child: WidgetPod::new(child),
+            popups: Vec::new(),
+        }
+    }
+
+    fn show(&mut self, ctx: &mut EventCtx, popup: Popup<T>) {
+        trace!("Showing popup {:?}", popup.id);
+        // the last popup with the same z_index is below the new one
+        let index = self
+            .popups
+            .iter()
+            .rposition(|other| other.z_index <= popup.z_index)
+            .map_or(0, |i| i + 1);
+        self.popups.insert(
+            index,
+            ActivePopup {
+                id: popup.id,
+                widget: WidgetPod::new(popup.widget),
+                anchor: popup.anchor.translate(-ctx.window_origin().to_vec2()),
+                z_index: popup.z_index,
+                dismiss_on_click_outside: popup.dismiss_on_click_outside,
+                dismiss_on_escape: popup.dismiss_on_escape,
+                dismiss_on_focus_loss: popup.dismiss_on_focus_loss,
+                requester: popup.requester,
+                previous_focus: ctx.state.focus_widget,
+                had_focus: false,
+            },
+        );
+        if popup.take_focus {
+            ctx.submit_command(FOCUS_POPUP.with(popup.id).to(ctx.widget_id()));
+        }
+        ctx.children_changed();
+    }
+
+    fn dismiss(&mut self, ctx: &mut EventCtx, index: usize) {
+        let popup = self.popups.remove(index);
+        trace!("Dismissing popup {:?}", popup.id);
+        // popups that took the focus from this one give it back to where it came from
+        let in_popup = |id: WidgetId| {
+            id == popup.widget.id() || popup.widget.state().children.may_contain(&id)
+        };
+        for other in &mut self.popups {
+            if matches!(other.previous_focus, Some(id) if in_popup(id)) {
+                other.previous_focus = popup.previous_focus;
+            }
+        }
+        if popup.widget.has_focus() {
+            match popup.previous_focus {
+                Some(widget) => ctx.set_focus(widget),
+                None => ctx.resign_focus(),
+            }
+        }
+        if let Some(requester) = popup.requester {
+            ctx.submit_command(POPUP_DISMISSED.with(popup.id).to(requester));
+        }
+        ctx.children_changed();
+    }
+
+    fn index_of(&self, id: PopupId) -> Option<usize> {
+        self.popups.iter().position(|popup| popup.id == id)
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for OverlayLayer<T, W> {
+    #[instrument(
+        name = "OverlayLayer",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(SHOW_POPUP) => {
+                let popup = cmd.get_unchecked(SHOW_POPUP).take().and_then(|popup| {
+                    match popup.downcast::<Popup<T>>() {
+                        Ok(popup) => Some(*popup),
+                        Err(popup) => popup
+                            .downcast::<Popup<()>>()
+                            .ok()
+                            .map(|popup| popup.without_data()),
+                    }
+                });
+                match popup {
+                    Some(popup) => self.show(ctx, popup),
+                    None => warn!(
+                        "{} command must carry a Popup with the data of the OverlayLayer, or ().",
+                        SHOW_POPUP
+                    ),
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(CLOSE_POPUP) => {
+                if let Some(index) = self.index_of(*cmd.get_unchecked(CLOSE_POPUP)) {
+                    self.dismiss(ctx, index);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::Command(cmd) if cmd.is(FOCUS_POPUP) => {
+                if let Some(index) = self.index_of(*cmd.get_unchecked(FOCUS_POPUP)) {
+                    let popup = &mut self.popups[index];
+                    let chain = &popup.widget.state().focus_chain;
+                    let target = chain
+                        .iter()
+                        .min_by_key(|entry| entry.order.unwrap_or(0))
+                        .map(|entry| entry.id);
+                    ctx.set_focus(target.unwrap_or_else(|| popup.widget.id()));
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::Notification(note) if note.is(DISMISS_POPUP) => {
+                let route = note.route();
+                if let Some(index) = self.popups.iter().position(|p| p.widget.id() == route) {
+                    self.dismiss(ctx, index);
+                    ctx.set_handled();
+                }
+                return;
+            }
+            Event::KeyDown(key) if key.key == KbKey::Escape => {
+                // a focused popup that handles escape itself, like a dialog,
+                // keeps the popups below it open
+                let index = self
+                    .popups
+                    .iter()
+                    .rposition(|p| p.dismiss_on_escape || p.widget.has_focus());
+                if let Some(index) = index.filter(|&i| self.popups[i].dismiss_on_escape) {
+                    self.dismiss(ctx, index);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::MouseDown(mouse) => {
+                let hit = self
+                    .popups
+                    .iter()
+                    .rposition(|p| p.widget.layout_rect().contains(mouse.pos));
+                let mut dismissed = false;
+                for index in (hit.map_or(0, |i| i + 1)..self.popups.len()).rev() {
+                    if self.popups[index].dismiss_on_click_outside {
+                        self.dismiss(ctx, index);
+                        dismissed = true;
+                    }
+                }
+                if dismissed && hit.is_none() {
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::WindowLostFocus => {
+                for index in (0..self.popups.len()).rev() {
+                    if self.popups[index].dismiss_on_focus_loss {
+                        self.dismiss(ctx, index);
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        let is_pointer_event = matches!(
+            event,
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::MouseMove(_) | Event::Wheel(_)
+        );
+        // While a widget is active it gets the pointer, whatever is on top of it.
+        let has_active =
+            self.child.has_active() || self.popups.iter().any(|p| p.widget.has_active());
+        let mut covered = false;
+        for popup in self.popups.iter_mut().rev() {
+            if is_pointer_event && covered && !has_active {
+                if popup.widget.is_hot() {
+                    let leave = Event::Internal(InternalEvent::MouseLeave);
+                    popup.widget.event(ctx, &leave, data, env);
+                }
+            } else {
+                popup.widget.event(ctx, event, data, env);
+                covered |= popup.widget.is_hot();
+            }
+        }
+        if is_pointer_event && covered && !has_active {
+            if self.child.is_hot() {
+                let leave = Event::Internal(InternalEvent::MouseLeave);
+                self.child.event(ctx, &leave, data, env);
+            }
+        } else {
+            self.child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "OverlayLayer",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+        for popup in &mut self.popups {
+            popup.widget.lifecycle(ctx, event, data, env);
+        }
+
+        if let LifeCycle::Internal(InternalLifeCycle::RouteFocusChanged { .. }) = event {
+            for popup in &mut self.popups {
+                let has_focus = popup.widget.has_focus();
+                if popup.had_focus && !has_focus && popup.dismiss_on_focus_loss {
+                    ctx.submit_command(CLOSE_POPUP.with(popup.id).to(ctx.widget_id()));
+                }
+                popup.had_focus = has_focus;
+            }
+        }
+    }
+
+    #[instrument(
+        name = "OverlayLayer",
+        level = "trace",
+        skip(self, ctx, _old, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+        for popup in &mut self.popups {
+            popup.widget.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "OverlayLayer", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+
+        let popup_bc = BoxConstraints::new(Size::ZERO, size);
+        for popup in &mut self.popups {
+            let popup_size = popup.widget.layout(ctx, &popup_bc, data, env);
+            let origin = popup.anchor.place(popup_size, size.to_rect());
+            popup.widget.set_origin(ctx, data, env, origin);
+        }
+
+        ctx.set_paint_insets(self.child.paint_insets());
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "OverlayLayer", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+        for popup in &mut self.popups {
+            popup.widget.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children = std::iter::once(self.child.widget().debug_state(data))
+            .chain(
+                self.popups
+                    .iter()
+                    .map(|popup| popup.widget.widget().debug_state(data)),
+            )
+            .collect();
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::tests::move_mouse;
+    use crate::{KeyEvent, Modifiers, MouseButton, MouseEvent, SingleUse, Target};
+    use std::any::Any;
+    use test_log::test;
+
+    /// Counts clicks.
+    fn counter() -> impl Widget<u32> {
+        ModularWidget::new(())
+            .event_fn(|_, _, event, data: &mut u32, _| {
+                if let Event::MouseDown(_) = event {
+                    *data += 1;
+                }
+            })
+            .layout_fn(|_, _, bc, _, _| bc.max())
+    }
+
+    /// A popup that counts clicks in the hundreds, and dismisses itself on a
+    /// right click.
+    fn popup(anchor: impl Into<PopupAnchor>) -> Popup<u32> {
+        let clicks = ModularWidget::new(())
+            .event_fn(|_, ctx, event, data: &mut u32, _| match event {
+                Event::MouseDown(mouse) if mouse.button.is_right() => {
+                    ctx.submit_notification(DISMISS_POPUP)
+                }
+                Event::MouseDown(_) => *data += 100,
+                _ => (),
+            })
+            .layout_fn(|_, _, _, _, _| Size::new(50.0, 50.0));
+        Popup::new(anchor, clicks)
+    }
+
+    fn show(harness: &mut Harness<u32>, popup: Popup<u32>) -> PopupId {
+        let id = popup.id();
+        let popup: Box<dyn Any> = Box::new(popup);
+        let window = harness.window().id;
+        harness.submit_command(
+            SHOW_POPUP
+                .with(SingleUse::new(popup))
+                .to(Target::Window(window)),
+        );
+        harness.just_layout();
+        id
+    }
+
+    fn click(harness: &mut Harness<u32>, pos: (f64, f64), button: MouseButton) {
+        harness.event(Event::MouseDown(MouseEvent {
+            button,
+            count: 1,
+            ..move_mouse(pos)
+        }));
+        harness.event(Event::MouseUp(MouseEvent {
+            button,
+            count: 1,
+            ..move_mouse(pos)
+        }));
+    }
+
+    fn popup_count(harness: &Harness<u32>) -> usize {
+        harness.get_root_debug_state().children.len() - 1
+    }
+
+    #[test]
+    fn anchor_placement() {
+        let bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
+        let size = Size::new(20.0, 30.0);
+        let below = PopupAnchor::Rect(Rect::new(10.0, 10.0, 40.0, 20.0));
+        assert_eq!(below.place(size, bounds), Point::new(10.0, 20.0));
+        let above = PopupAnchor::Rect(Rect::new(90.0, 80.0, 120.0, 90.0));
+        assert_eq!(above.place(size, bounds), Point::new(80.0, 50.0));
+        let point = PopupAnchor::Point(Point::new(90.0, 10.0));
+        assert_eq!(point.place(size, bounds), Point::new(70.0, 10.0));
+        // too big: the top left corner stays visible
+        let big = Size::new(200.0, 50.0);
+        assert_eq!(point.place(big, bounds), Point::new(0.0, 10.0));
+    }
+
+    #[test]
+    fn clicks_and_dismissal() {
+        Harness::create_simple(0, OverlayLayer::new(counter()), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            show(harness, popup(Point::new(10.0, 10.0)));
+            assert_eq!(popup_count(harness), 1);
+
+            // Clicks in the popup don't reach the widgets under it.
+            click(harness, (20.0, 20.0), MouseButton::Left);
+            assert_eq!(*harness.data(), 100);
+            // A click outside of the popup only dismisses it.
+            click(harness, (200.0, 200.0), MouseButton::Left);
+            assert_eq!(*harness.data(), 100);
+            assert_eq!(popup_count(harness), 0);
+            click(harness, (20.0, 20.0), MouseButton::Left);
+            assert_eq!(*harness.data(), 101);
+
+            // The popup can close itself.
+            show(harness, popup(Point::new(10.0, 10.0)));
+            click(harness, (20.0, 20.0), MouseButton::Right);
+            assert_eq!(popup_count(harness), 0);
+        });
+    }
+
+    #[test]
+    fn stacking_and_escape() {
+        Harness::create_simple(0, OverlayLayer::new(counter()), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            show(harness, popup(Point::new(10.0, 10.0)).z_index(1));
+            show(harness, popup(Point::new(30.0, 30.0)));
+            assert_eq!(popup_count(harness), 2);
+
+            // The popup with the higher z_index is on top, even though it was
+            // shown first, and gets the click; it closes itself.
+            click(harness, (40.0, 40.0), MouseButton::Right);
+            assert_eq!(popup_count(harness), 1);
+            click(harness, (40.0, 40.0), MouseButton::Left);
+            assert_eq!(*harness.data(), 100);
+
+            // The remaining popup took the focus, and escape dismisses it.
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::default(),
+                KbKey::Escape,
+            )));
+            assert_eq!(popup_count(harness), 0);
+
+            let id = show(harness, popup(Point::new(10.0, 10.0)));
+            harness.submit_command(CLOSE_POPUP.with(id));
+            assert_eq!(popup_count(harness), 0);
+        });
+    }
+}
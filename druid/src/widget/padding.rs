@@ -16,7 +16,7 @@
 
 use crate::debug_state::DebugState;
 use crate::widget::{prelude::*, WidgetWrapper};
-use crate::{Data, Insets, KeyOrValue, Point, WidgetPod};
+use crate::{theme, Data, Insets, KeyOrValue, Point, WidgetPod};
 
 use tracing::{instrument, trace};
 
@@ -67,6 +67,18 @@ impl<T, W: Widget<T>> Padding<T, W> {
     }
 }
 
+impl<T, W> Padding<T, W> {
+    /// Set the padding around the child.
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`].
+    ///
+    /// [`request_layout`]: crate::EventCtx::request_layout
+    pub fn set_insets(&mut self, insets: impl Into<KeyOrValue<Insets>>) {
+        self.insets = insets.into();
+    }
+}
+
 impl<T, W> WidgetWrapper for Padding<T, W> {
     widget_wrapper_pod_body!(W, child);
 }
@@ -100,7 +112,13 @@ impl<T: Data, W: Widget<T>> Widget<T> for Padding<T, W> {
 
         let child_bc = bc.shrink((hpad, vpad));
         let size = self.child.layout(ctx, &child_bc, data, env);
-        let origin = Point::new(insets.x0, insets.y0);
+        // The leading inset goes on the right in a right-to-left layout.
+        let origin_x = if env.get(theme::LAYOUT_DIRECTION).is_rtl() {
+            insets.x1
+        } else {
+            insets.x0
+        };
+        let origin = Point::new(origin_x, insets.y0);
         self.child.set_origin(ctx, data, env, origin);
 
         let my_size = Size::new(size.width + hpad, size.height + vpad);
@@ -22,12 +22,19 @@ use tracing::instrument;
 /// This is useful in a situation where layout is controlled elsewhere and you
 /// do not need to handle events, but you would like to customize appearance.
 ///
+/// The paint closure can use [`PaintCtx::is_hot`], [`PaintCtx::is_active`],
+/// [`PaintCtx::is_focused`] and [`PaintCtx::is_disabled`] to draw interaction
+/// state; [`PaintCtx::resolve_disabled`] picks the disabled colors of the
+/// theme, like the built-in widgets. To make a custom clickable control, wrap
+/// the painter with [`on_click`], which makes it active while the mouse button
+/// is held down, like a [`Button`].
+///
 /// **When is paint called?**
 ///
 /// The `Painter` widget will call its [`paint`]  method anytime its [`Data`]
-/// is changed. If you would like it to repaint at other times (such as when
-/// hot or active state changes) you will need to call [`request_paint`] further
-/// up the tree, perhaps in a [`Controller`] widget.
+/// is changed, and when it becomes hot, focused or disabled, or stops being
+/// so. If you would like it to repaint at other times you will need to call
+/// [`request_paint`] further up the tree, perhaps in a [`Controller`] widget.
 ///
 /// # Examples
 ///
@@ -72,10 +79,42 @@ use tracing::instrument;
 /// });
 /// ```
 ///
+/// A round button that lights up when hovered and darkens when pressed:
+///
+/// ```
+/// use druid::kurbo::Circle;
+/// use druid::widget::Painter;
+/// use druid::{theme, RenderContext, Widget, WidgetExt};
+///
+/// fn round_button() -> impl Widget<u32> {
+///     Painter::new(|ctx, _: &u32, env| {
+///         let size = ctx.size();
+///         let circle = Circle::new(size.to_rect().center(), size.min_side() / 2.0);
+///         let color = if ctx.is_active() {
+///             env.get(theme::PRIMARY_DARK)
+///         } else if ctx.is_hot() {
+///             env.get(theme::PRIMARY_LIGHT)
+///         } else {
+///             env.get(theme::BUTTON_LIGHT)
+///         };
+///         ctx.fill(circle, &color);
+///     })
+///     .fix_size(32.0, 32.0)
+///     .on_click(|_ctx, count, _env| *count += 1)
+/// }
+/// ```
+///
 /// [`paint`]: ../trait.Widget.html#tymethod.paint
 /// [`Data`]: ../trait.Data.html
 /// [`request_paint`]: ../EventCtx.html#method.request_paint
 /// [`Controller`]: trait.Controller.html
+/// [`PaintCtx::is_hot`]: crate::PaintCtx::is_hot
+/// [`PaintCtx::is_active`]: crate::PaintCtx::is_active
+/// [`PaintCtx::is_focused`]: crate::PaintCtx::is_focused
+/// [`PaintCtx::is_disabled`]: crate::PaintCtx::is_disabled
+/// [`PaintCtx::resolve_disabled`]: crate::PaintCtx::resolve_disabled
+/// [`on_click`]: crate::WidgetExt::on_click
+/// [`Button`]: super::Button
 pub struct Painter<T>(Box<dyn FnMut(&mut PaintCtx, &T, &Env)>);
 
 /// Something that can be used as the background for a widget.
@@ -136,7 +175,15 @@ impl<T: Data> BackgroundBrush<T> {
 
 impl<T: Data> Widget<T> for Painter<T> {
     fn event(&mut self, _: &mut EventCtx, _: &Event, _: &mut T, _: &Env) {}
-    fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &T, _: &Env) {}
+    #[instrument(name = "Painter", level = "trace", skip(self, ctx, event))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _: &T, _: &Env) {
+        if let LifeCycle::HotChanged(_)
+        | LifeCycle::FocusChanged(_)
+        | LifeCycle::DisabledChanged(_) = event
+        {
+            ctx.request_paint();
+        }
+    }
     #[instrument(name = "Painter", level = "trace", skip(self, ctx, old_data, data))]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _: &Env) {
         if !old_data.same(data) {
@@ -199,3 +246,36 @@ impl<T> From<PaintBrush> for BackgroundBrush<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::WidgetExt;
+    use test_log::test;
+
+    #[test]
+    fn repaints_when_hot_changes() {
+        let widget = Painter::new(|_, _: &(), _| ())
+            .fix_size(50.0, 50.0)
+            .center();
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            assert!(harness.window().invalid().is_empty());
+
+            harness.event(Event::MouseMove(move_mouse((200.0, 200.0))));
+            assert!(!harness.window().invalid().is_empty());
+            harness.paint();
+
+            harness.event(Event::MouseMove(move_mouse((210.0, 210.0))));
+            assert!(harness.window().invalid().is_empty());
+
+            harness.event(Event::MouseMove(move_mouse((10.0, 10.0))));
+            assert!(!harness.window().invalid().is_empty());
+        });
+    }
+}
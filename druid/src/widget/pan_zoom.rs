@@ -0,0 +1,452 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that pans and zooms its child.
+
+use std::mem;
+
+use tracing::{instrument, trace};
+
+use crate::core::WidgetState;
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{
+    Affine, Data, InternalEvent, Lens, MouseButton, MouseEvent, Point, Region, Vec2, WidgetPod,
+};
+
+/// How much one notch of the mouse wheel zooms.
+const ZOOM_PER_NOTCH: f64 = 1.2;
+/// The wheel delta of one notch.
+const NOTCH_DELTA: f64 = 120.0;
+
+/// How the content of a [`PanZoom`] is moved and scaled in the view.
+///
+/// A point in the content is drawn at `point * zoom + offset` in the view.
+#[derive(Debug, Clone, Copy, PartialEq, Data)]
+pub struct ViewTransform {
+    /// Where the origin of the content is in the view.
+    pub offset: Vec2,
+    /// How much the content is scaled.
+    pub zoom: f64,
+}
+
+impl ViewTransform {
+    /// The transform that shows the content as it is.
+    pub const IDENTITY: ViewTransform = ViewTransform {
+        offset: Vec2::ZERO,
+        zoom: 1.0,
+    };
+
+    /// Create a new `ViewTransform`.
+    pub fn new(offset: impl Into<Vec2>, zoom: f64) -> Self {
+        ViewTransform {
+            offset: offset.into(),
+            zoom,
+        }
+    }
+
+    /// The transform as an [`Affine`], from content to view coordinates.
+    pub fn to_affine(self) -> Affine {
+        Affine::translate(self.offset) * Affine::scale(self.zoom)
+    }
+
+    /// Where `point` in the content is in the view.
+    pub fn to_view(self, point: Point) -> Point {
+        (point.to_vec2() * self.zoom + self.offset).to_point()
+    }
+
+    /// Which point of the content is at `point` in the view.
+    pub fn to_content(self, point: Point) -> Point {
+        ((point.to_vec2() - self.offset) / self.zoom).to_point()
+    }
+
+    /// Returns this transform zoomed by `factor`, with the zoom clamped to
+    /// `min_zoom..=max_zoom`.
+    ///
+    /// The content keeps the same point at `focus` in the view, so that it
+    /// zooms around that point.
+    pub fn zoomed_around(self, focus: Point, factor: f64, min_zoom: f64, max_zoom: f64) -> Self {
+        let zoom = (self.zoom * factor).clamp(min_zoom, max_zoom);
+        let content = self.to_content(focus);
+        ViewTransform {
+            offset: focus.to_vec2() - content.to_vec2() * zoom,
+            zoom,
+        }
+    }
+}
+
+impl Default for ViewTransform {
+    fn default() -> Self {
+        ViewTransform::IDENTITY
+    }
+}
+
+/// A widget that shows its child moved and scaled by a [`ViewTransform`]
+/// from the data, like a canvas.
+///
+/// The user zooms around the pointer by scrolling with Ctrl held, or by
+/// pinching the trackpad, and pans by dragging with the middle mouse button
+/// or by scrolling, if the child doesn't handle the scroll itself. The zoom
+/// stays between the bounds set with [`with_zoom_range`], 0.1 and 10 by
+/// default.
+///
+/// The child is laid out without constraints, at its natural size, and
+/// clipped to the `PanZoom`, which takes all of the available space. Pointer
+/// events are mapped to the coordinates of the child, so it can handle them
+/// as usual, but positions that widgets in the child convert to window
+/// coordinates, for example to show a context menu, don't account for the
+/// zoom.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{PanZoom, SizedBox, ViewTransform};
+/// use druid::{Color, Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Drawing {
+///     view: ViewTransform,
+/// }
+///
+/// fn canvas() -> impl Widget<Drawing> {
+///     let page = SizedBox::empty()
+///         .fix_size(800.0, 600.0)
+///         .background(Color::WHITE);
+///     PanZoom::new(page, Drawing::view).with_zoom_range(0.25, 4.0)
+/// }
+/// ```
+///
+/// [`with_zoom_range`]: PanZoom::with_zoom_range
+pub struct PanZoom<T, L> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    lens: L,
+    min_zoom: f64,
+    max_zoom: f64,
+    /// The pointer position and the offset when a middle button drag started.
+    pan_start: Option<(Point, Vec2)>,
+    last_mouse_pos: Option<Point>,
+}
+
+impl<T: Data, L: Lens<T, ViewTransform>> PanZoom<T, L> {
+    /// Create a new `PanZoom` that shows `child` with the transform selected
+    /// by `lens`.
+    pub fn new(child: impl Widget<T> + 'static, lens: L) -> Self {
+        PanZoom {
+            child: WidgetPod::new(child).boxed(),
+            lens,
+            min_zoom: 0.1,
+            max_zoom: 10.0,
+            pan_start: None,
+            last_mouse_pos: None,
+        }
+    }
+
+    /// Builder-style method for setting the smallest and the largest zoom.
+    pub fn with_zoom_range(mut self, min_zoom: f64, max_zoom: f64) -> Self {
+        self.set_zoom_range(min_zoom, max_zoom);
+        self
+    }
+
+    /// Set the smallest and the largest zoom.
+    ///
+    /// This only applies to zooming by the user; a transform with another
+    /// zoom in the data is shown as it is.
+    pub fn set_zoom_range(&mut self, min_zoom: f64, max_zoom: f64) {
+        self.min_zoom = min_zoom;
+        self.max_zoom = max_zoom.max(min_zoom);
+    }
+
+    fn transform(&self, data: &T) -> ViewTransform {
+        self.lens.with(data, |transform| *transform)
+    }
+
+    fn zoom(&self, ctx: &mut EventCtx, data: &mut T, focus: Point, factor: f64) {
+        let (min_zoom, max_zoom) = (self.min_zoom, self.max_zoom);
+        self.lens.with_mut(data, |transform| {
+            *transform = transform.zoomed_around(focus, factor, min_zoom, max_zoom);
+        });
+        ctx.request_paint();
+        ctx.set_handled();
+    }
+
+    fn pan(&self, ctx: &mut EventCtx, data: &mut T, offset: Vec2) {
+        self.lens
+            .with_mut(data, |transform| transform.offset = offset);
+        ctx.request_paint();
+        ctx.set_handled();
+    }
+
+    /// Handle the events that pan and zoom, returning `true` if `event` was
+    /// one of them.
+    fn pan_zoom_event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T) -> bool {
+        match event {
+            Event::Wheel(mouse) if mouse.mods.ctrl() => {
+                let factor = ZOOM_PER_NOTCH.powf(-mouse.wheel_delta.y / NOTCH_DELTA);
+                self.zoom(ctx, data, mouse.pos, factor);
+            }
+            Event::Zoom(delta) => {
+                let focus = self
+                    .last_mouse_pos
+                    .unwrap_or_else(|| ctx.size().to_rect().center());
+                self.zoom(ctx, data, focus, 1.0 + delta);
+            }
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Middle => {
+                self.pan_start = Some((mouse.pos, self.transform(data).offset));
+                ctx.set_active(true);
+                ctx.set_handled();
+            }
+            Event::MouseMove(mouse) if self.pan_start.is_some() => {
+                let (start, offset) = self.pan_start.unwrap();
+                self.pan(ctx, data, offset + (mouse.pos - start));
+            }
+            Event::MouseUp(mouse)
+                if mouse.button == MouseButton::Middle && self.pan_start.is_some() =>
+            {
+                self.pan_start = None;
+                ctx.set_active(false);
+                ctx.set_handled();
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Map the position of a pointer event from the view to the content.
+fn to_content(mouse: &MouseEvent, transform: ViewTransform) -> MouseEvent {
+    MouseEvent {
+        pos: transform.to_content(mouse.pos),
+        ..mouse.clone()
+    }
+}
+
+/// Returns `true` once if the child requested a paint since the last call.
+///
+/// The regions that the child invalidated aren't transformed, so we repaint
+/// all of the view instead.
+fn take_child_invalidated(state: &mut WidgetState) -> bool {
+    mem::take(&mut state.child_invalidated)
+}
+
+impl<T: Data, L: Lens<T, ViewTransform>> Widget<T> for PanZoom<T, L> {
+    #[instrument(name = "PanZoom", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::MouseMove(mouse) = event {
+            self.last_mouse_pos = Some(mouse.pos);
+        }
+        if self.pan_zoom_event(ctx, event, data) {
+            return;
+        }
+
+        let transform = self.transform(data);
+        let child_event = match event {
+            Event::MouseDown(mouse)
+            | Event::MouseUp(mouse)
+            | Event::MouseMove(mouse)
+            | Event::Wheel(mouse)
+                if !ctx.size().to_rect().contains(mouse.pos) && !self.child.has_active() =>
+            {
+                // the content outside of the view isn't visible, so it can't be hot
+                Event::Internal(InternalEvent::MouseLeave)
+            }
+            Event::MouseDown(mouse) => Event::MouseDown(to_content(mouse, transform)),
+            Event::MouseUp(mouse) => Event::MouseUp(to_content(mouse, transform)),
+            Event::MouseMove(mouse) => Event::MouseMove(to_content(mouse, transform)),
+            Event::Wheel(mouse) => Event::Wheel(to_content(mouse, transform)),
+            _ => event.clone(),
+        };
+        self.child.event(ctx, &child_event, data, env);
+        if take_child_invalidated(ctx.widget_state) {
+            ctx.request_paint();
+        }
+
+        // scrolling that the child doesn't use pans
+        if let Event::Wheel(mouse) = event {
+            if !ctx.is_handled() && ctx.size().to_rect().contains(mouse.pos) {
+                let offset = self.transform(data).offset - mouse.wheel_delta;
+                self.pan(ctx, data, offset);
+            }
+        }
+    }
+
+    #[instrument(name = "PanZoom", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+        if take_child_invalidated(ctx.widget_state) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(
+        name = "PanZoom",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+        if take_child_invalidated(ctx.widget_state)
+            || !self.transform(old_data).same(&self.transform(data))
+        {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "PanZoom", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("PanZoom");
+        let child_size = self
+            .child
+            .layout(ctx, &BoxConstraints::UNBOUNDED, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+        let max = bc.max();
+        let size = bc.constrain(Size::new(
+            if bc.is_width_bounded() {
+                max.width
+            } else {
+                child_size.width
+            },
+            if bc.is_height_bounded() {
+                max.height
+            } else {
+                child_size.height
+            },
+        ));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "PanZoom", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let affine = self.transform(data).to_affine();
+        if affine.determinant() == 0.0 {
+            return;
+        }
+        let visible = affine
+            .inverse()
+            .transform_rect_bbox(ctx.region().bounding_box());
+        let size = ctx.size();
+        ctx.with_save(|ctx| {
+            ctx.clip(size.to_rect());
+            ctx.transform(affine);
+            ctx.with_child_ctx(Region::from(visible), |ctx| {
+                self.child.paint_raw(ctx, data, env)
+            });
+        });
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let transform = self.transform(data);
+        DebugState {
+            display_name: "PanZoom".to_string(),
+            main_value: format!("{:?} x{}", transform.offset, transform.zoom),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::{move_mouse, scroll_mouse};
+    use crate::widget::SizedBox;
+    use crate::{Modifiers, WidgetExt};
+    use test_log::test;
+
+    #[derive(Clone, Data, Lens)]
+    struct Canvas {
+        view: ViewTransform,
+        clicks: u32,
+    }
+
+    fn press(pos: impl Into<Point>, button: MouseButton) -> MouseEvent {
+        MouseEvent {
+            button,
+            count: 1,
+            ..move_mouse(pos)
+        }
+    }
+
+    #[test]
+    fn zoom_around_focus() {
+        let transform = ViewTransform::new((10., 20.), 2.0);
+        let focus = Point::new(50., 60.);
+        let zoomed = transform.zoomed_around(focus, 1.5, 0.5, 4.0);
+        assert_eq!(zoomed.zoom, 3.0);
+        assert_eq!(zoomed.to_content(focus), transform.to_content(focus));
+        assert_eq!(zoomed.to_view(zoomed.to_content(focus)), focus);
+
+        let clamped = transform.zoomed_around(focus, 10.0, 0.5, 4.0);
+        assert_eq!(clamped.zoom, 4.0);
+        assert_eq!(clamped.to_content(focus), transform.to_content(focus));
+        assert_eq!(transform.zoomed_around(focus, 0.01, 0.5, 4.0).zoom, 0.5);
+    }
+
+    #[test]
+    fn zoom_pan_and_click() {
+        let child = WidgetId::next();
+        let widget = PanZoom::new(
+            SizedBox::empty()
+                .fix_size(100., 100.)
+                .on_click(|_, canvas: &mut Canvas, _| canvas.clicks += 1)
+                .with_id(child),
+            Canvas::view,
+        )
+        .with_zoom_range(0.5, 2.0);
+        let canvas = Canvas {
+            view: ViewTransform::IDENTITY,
+            clicks: 0,
+        };
+
+        Harness::create_simple(canvas, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            // ctrl+scroll zooms in around the pointer, up to the maximum
+            let mut wheel = scroll_mouse((100., 100.), Vec2::new(0., -600.));
+            wheel.mods = Modifiers::CONTROL;
+            harness.event(Event::Wheel(wheel));
+            let view = harness.data().view;
+            assert_eq!(view.zoom, 2.0);
+            assert_eq!(
+                view.to_content(Point::new(100., 100.)),
+                Point::new(100., 100.)
+            );
+
+            // the child is hit under the transform: (150, 150) in the view is
+            // (125, 125) in the content, which is outside the child
+            harness.event(Event::MouseMove(move_mouse((90., 90.))));
+            assert!(harness.get_state(child).is_hot());
+            harness.event(Event::MouseMove(move_mouse((150., 150.))));
+            assert!(!harness.get_state(child).is_hot());
+
+            // dragging with the middle button pans
+            harness.event(Event::MouseDown(press((300., 300.), MouseButton::Middle)));
+            harness.event(Event::MouseMove(move_mouse((250., 260.))));
+            harness.event(Event::MouseUp(press((250., 260.), MouseButton::Middle)));
+            assert_eq!(harness.data().view.offset, Vec2::new(-150., -140.));
+
+            // (60, 60) in the view is (105, 100) in the content
+            harness.event(Event::MouseMove(move_mouse((60., 60.))));
+            harness.event(Event::MouseDown(press((60., 60.), MouseButton::Left)));
+            harness.event(Event::MouseUp(press((60., 60.), MouseButton::Left)));
+            assert_eq!(harness.data().clicks, 0);
+            harness.event(Event::MouseMove(move_mouse((40., 50.))));
+            harness.event(Event::MouseDown(press((40., 50.), MouseButton::Left)));
+            harness.event(Event::MouseUp(press((40., 50.), MouseButton::Left)));
+            assert_eq!(harness.data().clicks, 1);
+        });
+    }
+}
@@ -17,32 +17,196 @@
 use crate::debug_state::DebugState;
 use crate::kurbo::Circle;
 use crate::widget::prelude::*;
-use crate::widget::{CrossAxisAlignment, Flex, Label, LabelText};
-use crate::{theme, Data, LinearGradient, UnitPoint};
+use crate::widget::{Axis, CrossAxisAlignment, Flex, Label, LabelText};
+use crate::{theme, Data, KbKey, KeyOrValue, LinearGradient, UnitPoint};
 use tracing::{instrument, trace};
 
 const DEFAULT_RADIO_RADIUS: f64 = 7.0;
 const INNER_CIRCLE_RADIUS: f64 = 2.0;
-/// A group of radio buttons
-#[derive(Debug, Clone)]
-pub struct RadioGroup;
 
-impl RadioGroup {
+/// A group of radio buttons.
+///
+/// The buttons are laid out either in a column (with [`RadioGroup::column`])
+/// or in a row (with [`RadioGroup::row`]).
+///
+/// When the group has keyboard focus, the arrow keys along its axis
+/// (Up/Down for a column, Left/Right for a row) select the previous or next
+/// option.
+pub struct RadioGroup<T> {
+    inner: Flex<T>,
+    axis: Axis,
+    variants: Vec<T>,
+}
+
+// `Flex` holds its children as trait objects, so this can't be derived.
+impl<T: std::fmt::Debug> std::fmt::Debug for RadioGroup<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RadioGroup")
+            .field("axis", &self.axis)
+            .field("variants", &self.variants)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Data + PartialEq> RadioGroup<T> {
     /// Given a vector of `(label_text, enum_variant)` tuples, create a group of Radio buttons
-    pub fn new<T: Data + PartialEq>(
+    /// laid out in a column.
+    ///
+    /// This is equivalent to [`RadioGroup::column`].
+    pub fn new(variants: impl IntoIterator<Item = (impl Into<LabelText<T>> + 'static, T)>) -> Self {
+        RadioGroup::column(variants)
+    }
+
+    /// Given a vector of `(label_text, enum_variant)` tuples, create a group of Radio buttons
+    /// laid out in a column.
+    pub fn column(
         variants: impl IntoIterator<Item = (impl Into<LabelText<T>> + 'static, T)>,
-    ) -> impl Widget<T> {
-        let mut col = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
-        let mut is_first = true;
+    ) -> Self {
+        RadioGroup::for_axis(Axis::Vertical, variants)
+    }
+
+    /// Given a vector of `(label_text, enum_variant)` tuples, create a group of Radio buttons
+    /// laid out in a row.
+    pub fn row(variants: impl IntoIterator<Item = (impl Into<LabelText<T>> + 'static, T)>) -> Self {
+        RadioGroup::for_axis(Axis::Horizontal, variants)
+    }
+
+    /// Given a vector of `(label_text, enum_variant)` tuples, create a group of Radio buttons
+    /// laid out along the given axis.
+    pub fn for_axis(
+        axis: Axis,
+        variants: impl IntoIterator<Item = (impl Into<LabelText<T>> + 'static, T)>,
+    ) -> Self {
+        let spacing = match axis {
+            Axis::Horizontal => theme::WIDGET_PADDING_HORIZONTAL,
+            Axis::Vertical => theme::WIDGET_PADDING_VERTICAL,
+        };
+        let cross_alignment = match axis {
+            Axis::Horizontal => CrossAxisAlignment::Baseline,
+            Axis::Vertical => CrossAxisAlignment::Start,
+        };
+        let mut inner = Flex::for_axis(axis)
+            .cross_axis_alignment(cross_alignment)
+            .with_main_axis_gap(spacing);
+        let mut all_variants = Vec::new();
         for (label, variant) in variants.into_iter() {
-            if !is_first {
-                col.add_default_spacer();
+            inner.add_child(Radio::new(label, variant.clone()));
+            all_variants.push(variant);
+        }
+        RadioGroup {
+            inner,
+            axis,
+            variants: all_variants,
+        }
+    }
+
+    /// Builder-style method to set the spacing between the radio buttons.
+    ///
+    /// The default is [`WIDGET_PADDING_VERTICAL`] for a column and
+    /// [`WIDGET_PADDING_HORIZONTAL`] for a row.
+    ///
+    /// [`WIDGET_PADDING_VERTICAL`]: crate::theme::WIDGET_PADDING_VERTICAL
+    /// [`WIDGET_PADDING_HORIZONTAL`]: crate::theme::WIDGET_PADDING_HORIZONTAL
+    pub fn with_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.set_spacing(spacing);
+        self
+    }
+
+    /// Set the spacing between the radio buttons.
+    pub fn set_spacing(&mut self, spacing: impl Into<KeyOrValue<f64>>) {
+        self.inner.set_main_axis_gap(spacing);
+    }
+
+    /// Select the option `delta` steps away from the current one, wrapping around.
+    fn select_relative(&self, data: &mut T, delta: isize) {
+        let len = self.variants.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let idx = match self.variants.iter().position(|v| v == data) {
+            Some(idx) => (idx as isize + delta).rem_euclid(len),
+            None if delta > 0 => 0,
+            None => len - 1,
+        };
+        *data = self.variants[idx as usize].clone();
+    }
+}
+
+impl<T: Data + PartialEq> Widget<T> for RadioGroup<T> {
+    #[instrument(
+        name = "RadioGroup",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(_) if !ctx.is_disabled() => ctx.request_focus(),
+            Event::KeyDown(key) if ctx.is_focused() && !ctx.is_disabled() => {
+                let delta = match (self.axis, &key.key) {
+                    (Axis::Horizontal, KbKey::ArrowLeft) | (Axis::Vertical, KbKey::ArrowUp) => -1,
+                    (Axis::Horizontal, KbKey::ArrowRight) | (Axis::Vertical, KbKey::ArrowDown) => 1,
+                    _ => 0,
+                };
+                if delta != 0 {
+                    self.select_relative(data, delta);
+                    ctx.set_handled();
+                    return;
+                }
             }
-            let radio = Radio::new(label, variant);
-            col.add_child(radio);
-            is_first = false;
+            _ => (),
+        }
+        self.inner.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "RadioGroup",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "RadioGroup",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "RadioGroup", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("RadioGroup");
+        // `Flex` sets our baseline offset, which is what a surrounding row will align on.
+        self.inner.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "RadioGroup", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+        if ctx.is_focused() && !ctx.is_disabled() {
+            let rect = ctx
+                .size()
+                .to_rect()
+                .inset(-0.5)
+                .to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
+            ctx.stroke(rect, &env.get(theme::FOCUS_RING_COLOR), 1.0);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.inner.debug_state(data)],
+            ..Default::default()
         }
-        col
     }
 }
 
@@ -116,7 +280,9 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
             radio_diam.max(label_size.height),
         );
         let size = bc.constrain(desired_size);
-        trace!("Computed size: {}", size);
+        let baseline = self.child_label.baseline_offset() + (size.height - label_size.height);
+        ctx.set_baseline_offset(baseline);
+        trace!("Computed layout: size={}, baseline={}", size, baseline);
         size
     }
 
@@ -177,3 +343,62 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{press_key, widget_ids};
+    use crate::tests::move_mouse;
+    use crate::WidgetExt;
+    use test_log::test;
+
+    #[test]
+    fn row_layout_and_arrow_keys() {
+        let [group_id] = widget_ids();
+        let group = RadioGroup::row(vec![("one", 1u32), ("two", 2), ("three", 3)])
+            .with_spacing(10.0)
+            .with_id(group_id)
+            .center();
+
+        Harness::create_simple(1u32, group, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let rect = harness.get_state(group_id).layout_rect();
+            assert!(rect.width() > rect.height() * 3.0);
+
+            harness.event(Event::MouseDown(move_mouse(rect.center())));
+            assert!(harness.get_state(group_id).has_focus);
+
+            press_key(harness, KbKey::ArrowRight);
+            assert_eq!(*harness.data(), 2);
+            // Up and down don't move along a row.
+            press_key(harness, KbKey::ArrowDown);
+            assert_eq!(*harness.data(), 2);
+            press_key(harness, KbKey::ArrowLeft);
+            press_key(harness, KbKey::ArrowLeft);
+            assert_eq!(*harness.data(), 3);
+        });
+    }
+
+    #[test]
+    fn column_arrow_keys() {
+        let [group_id] = widget_ids();
+        let group = RadioGroup::column(vec![("one", 1u32), ("two", 2)])
+            .with_id(group_id)
+            .center();
+
+        Harness::create_simple(1u32, group, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let rect = harness.get_state(group_id).layout_rect();
+            assert!(rect.height() > rect.width() / 2.0);
+
+            harness.event(Event::MouseDown(move_mouse(rect.center())));
+            press_key(harness, KbKey::ArrowRight);
+            assert_eq!(*harness.data(), 1);
+            press_key(harness, KbKey::ArrowDown);
+            assert_eq!(*harness.data(), 2);
+        });
+    }
+}
@@ -130,6 +130,56 @@ impl<L: Lens<State, In>, In: Data, State: Data> ScopeTransfer for LensScopeTrans
     }
 }
 
+/// The [`ScopePolicy`] of [`WidgetExt::with_local_state`], which pairs the
+/// input with some state that is private to the [`Scope`].
+///
+/// [`WidgetExt::with_local_state`]: crate::WidgetExt::with_local_state
+pub struct LocalStatePolicy<In, S> {
+    init: S,
+    phantom_in: PhantomData<In>,
+}
+
+impl<In, S> LocalStatePolicy<In, S> {
+    /// Create a `ScopePolicy` whose local state starts out as `init`.
+    pub fn new(init: S) -> Self {
+        LocalStatePolicy {
+            init,
+            phantom_in: PhantomData,
+        }
+    }
+}
+
+impl<In: Data, S: Data> ScopePolicy for LocalStatePolicy<In, S> {
+    type In = In;
+    type State = (In, S);
+    type Transfer = LocalStateTransfer<In, S>;
+
+    fn create(self, input: &In) -> ((In, S), Self::Transfer) {
+        ((input.clone(), self.init), LocalStateTransfer(PhantomData))
+    }
+}
+
+/// The [`ScopeTransfer`] of a [`LocalStatePolicy`], which synchronises the
+/// first element of the state with the input.
+pub struct LocalStateTransfer<In, S>(PhantomData<(In, S)>);
+
+impl<In: Data, S: Data> ScopeTransfer for LocalStateTransfer<In, S> {
+    type In = In;
+    type State = (In, S);
+
+    fn read_input(&self, state: &mut (In, S), input: &In) {
+        if !state.0.same(input) {
+            state.0 = input.clone();
+        }
+    }
+
+    fn write_back_input(&self, state: &(In, S), input: &mut In) {
+        if !state.0.same(input) {
+            *input = state.0.clone();
+        }
+    }
+}
+
 enum ScopeContent<SP: ScopePolicy> {
     Policy {
         policy: Option<SP>,
@@ -343,3 +393,43 @@ impl<SP: ScopePolicy, W: Widget<SP::State>> Widget<SP::In> for Scope<SP, W> {
 impl<SP: ScopePolicy, W: Widget<SP::State>> WidgetWrapper for Scope<SP, W> {
     widget_wrapper_pod_body!(W, inner);
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::{Selector, WidgetExt};
+    use test_log::test;
+
+    #[test]
+    fn local_state_is_private() {
+        const BUMP_STATE: Selector = Selector::new("druid-test.bump-state");
+        const BUMP_DATA: Selector = Selector::new("druid-test.bump-data");
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let seen_clone = seen.clone();
+
+        let widget = ModularWidget::new(())
+            .event_fn(|_, _, event, data: &mut (u32, u32), _| match event {
+                Event::Command(cmd) if cmd.is(BUMP_STATE) => data.1 += 1,
+                Event::Command(cmd) if cmd.is(BUMP_DATA) => data.0 += 1,
+                _ => (),
+            })
+            .update_fn(move |_, _, _, data, _| seen_clone.borrow_mut().push(*data))
+            .with_local_state(10);
+
+        Harness::create_simple(0u32, widget, |harness| {
+            harness.send_initial_events();
+            harness.submit_command(BUMP_STATE);
+            assert_eq!(*harness.data(), 0);
+            harness.submit_command(BUMP_DATA);
+            assert_eq!(*harness.data(), 1);
+            // Changes to the app data keep the local state.
+            harness.update_data(|data| *data = 5);
+            assert_eq!(*seen.borrow(), [(0, 11), (1, 11), (5, 11)]);
+        });
+    }
+}
@@ -14,13 +14,34 @@
 
 //! A container that scrolls its contents.
 
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use instant::Instant;
+
 use crate::commands::SCROLL_TO_VIEW;
 use crate::debug_state::DebugState;
 use crate::widget::prelude::*;
-use crate::widget::{Axis, ClipBox};
-use crate::{scroll_component::*, Data, Rect, Vec2};
+use crate::widget::{Axis, ClipBox, Viewport};
+use crate::{scroll_component::*, Affine, Data, Lens, Rect, TimerToken, Vec2};
 use tracing::{instrument, trace};
 
+/// How long a smooth scroll to a data-driven offset takes.
+const SMOOTH_SCROLL_DURATION: Duration = Duration::from_millis(200);
+
+/// The default rate at which a fling slows down, in pixels per second squared.
+const FLING_DECELERATION: f64 = 2500.0;
+/// The default stiffness of the spring that pulls overscrolled content back.
+const OVERSCROLL_STIFFNESS: f64 = 300.0;
+/// How long after the last wheel event a fling starts.
+const FLING_START_DELAY: Duration = Duration::from_millis(40);
+/// How far back wheel events are used to estimate the velocity of a fling.
+const FLING_SAMPLE_WINDOW: Duration = Duration::from_millis(100);
+/// Slower flings than this, in pixels per second, are ignored.
+const MIN_FLING_VELOCITY: f64 = 100.0;
+/// The longest time step used when simulating the overscroll spring.
+const MAX_SPRING_STEP: f64 = 1.0 / 240.0;
+
 /// A container that scrolls its contents.
 ///
 /// This container holds a single child, and uses the wheel to scroll it
@@ -31,11 +52,69 @@ use tracing::{instrument, trace};
 /// When restricted to scrolling on a specific axis the child's size is
 /// locked on the opposite axis.
 ///
+/// The scroll offset can be bound to the data with [`bind_offset`], which makes
+/// it possible to scroll programmatically and to observe or restore the
+/// current position.
+///
 /// [`vertical`]: struct.Scroll.html#method.vertical
 /// [`horizontal`]: struct.Scroll.html#method.horizontal
+/// [`bind_offset`]: Scroll::bind_offset
 pub struct Scroll<T, W> {
     clip: ClipBox<T, W>,
     scroll_component: ScrollComponent,
+    offset_binding: Option<OffsetBinding<T>>,
+    smooth_scroll: bool,
+    fling: Fling,
+}
+
+/// The state of a scroll offset that is bound to the data.
+struct OffsetBinding<T> {
+    lens: Box<dyn OffsetLens<T>>,
+    /// The offset that was last read from or written to the data.
+    synced: Vec2,
+    /// Whether `synced` still has to be applied once the child size is known.
+    needs_initial_scroll: bool,
+    animation: Option<ScrollAnimation>,
+}
+
+/// A smooth scroll towards a data-driven offset.
+struct ScrollAnimation {
+    from: Vec2,
+    to: Vec2,
+    elapsed: Duration,
+}
+
+/// Inertial scrolling after the wheel stops, and the rubber band at the content edges.
+struct Fling {
+    enabled: bool,
+    deceleration: f64,
+    overscroll_enabled: bool,
+    stiffness: f64,
+    /// Recent wheel deltas, used to estimate the velocity when the wheel stops.
+    samples: VecDeque<(Instant, Vec2)>,
+    /// The timer that starts the fling once the wheel has stopped.
+    timer: TimerToken,
+    /// The velocity of the running fling, in pixels per second.
+    velocity: Option<Vec2>,
+    /// How far the content is pulled past its edges.
+    overscroll: Vec2,
+    overscroll_velocity: Vec2,
+}
+
+/// An object-safe version of `Lens<T, Vec2>`.
+trait OffsetLens<T> {
+    fn get(&self, data: &T) -> Vec2;
+    fn put(&self, data: &mut T, offset: Vec2);
+}
+
+impl<T, L: Lens<T, Vec2>> OffsetLens<T> for L {
+    fn get(&self, data: &T) -> Vec2 {
+        self.with(data, |offset| *offset)
+    }
+
+    fn put(&self, data: &mut T, offset: Vec2) {
+        self.with_mut(data, |old| *old = offset)
+    }
 }
 
 impl<T, W: Widget<T>> Scroll<T, W> {
@@ -48,6 +127,9 @@ impl<T, W: Widget<T>> Scroll<T, W> {
         Scroll {
             clip: ClipBox::new(child),
             scroll_component: ScrollComponent::new(),
+            offset_binding: None,
+            smooth_scroll: false,
+            fling: Fling::new(),
         }
     }
 
@@ -55,6 +137,7 @@ impl<T, W: Widget<T>> Scroll<T, W> {
     ///
     /// Returns `true` if the scroll offset has changed.
     pub fn scroll_by(&mut self, delta: Vec2) -> bool {
+        self.fling.cancel();
         self.clip.pan_by(delta)
     }
 
@@ -63,6 +146,7 @@ impl<T, W: Widget<T>> Scroll<T, W> {
     /// If the target region is larger than the viewport, we will display the
     /// portion that fits, prioritizing the portion closest to the origin.
     pub fn scroll_to(&mut self, region: Rect) -> bool {
+        self.fling.cancel();
         self.clip.pan_to_visible(region)
     }
 
@@ -70,6 +154,7 @@ impl<T, W: Widget<T>> Scroll<T, W> {
     ///
     /// Returns `true` if the scroll offset has changed.
     pub fn scroll_to_on_axis(&mut self, axis: Axis, position: f64) -> bool {
+        self.fling.cancel();
         self.clip.pan_to_on_axis(axis, position)
     }
 }
@@ -101,12 +186,105 @@ impl<T, W> Scroll<T, W> {
         self
     }
 
+    /// Builder-style method to bind the scroll offset to the data.
+    ///
+    /// Writing to the bound [`Vec2`] moves the viewport, and scrolling by the user
+    /// writes the new offset back. Offsets outside of the content bounds are
+    /// clamped; the clamped offset is written back the next time the `Scroll`
+    /// receives an event.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::{Label, Scroll};
+    /// use druid::{Data, Lens, Vec2, Widget, WidgetExt};
+    ///
+    /// #[derive(Clone, Data, Lens)]
+    /// struct AppState {
+    ///     text: String,
+    ///     scroll_offset: Vec2,
+    /// }
+    ///
+    /// fn scrolling_text() -> impl Widget<AppState> {
+    ///     Scroll::new(Label::raw().lens(AppState::text))
+    ///         .vertical()
+    ///         .bind_offset(AppState::scroll_offset)
+    /// }
+    /// ```
+    pub fn bind_offset(mut self, lens: impl Lens<T, Vec2> + 'static) -> Self {
+        self.offset_binding = Some(OffsetBinding {
+            lens: Box::new(lens),
+            synced: Vec2::ZERO,
+            needs_initial_scroll: false,
+            animation: None,
+        });
+        self
+    }
+
+    /// Builder-style method to set whether changes to a [bound offset] are animated.
+    ///
+    /// The default is `false`. Scrolling by the user is never animated.
+    ///
+    /// [bound offset]: Scroll::bind_offset
+    pub fn with_smooth_scroll(mut self, smooth: bool) -> Self {
+        self.smooth_scroll = smooth;
+        self
+    }
+
+    /// Builder-style method to set whether scrolling keeps going after the wheel stops.
+    ///
+    /// If `true`, a quick flick of the trackpad or wheel makes the content
+    /// continue to scroll, slowing down at the [fling deceleration]. The fling
+    /// stops when the user scrolls again, presses the mouse, or the offset is
+    /// changed programmatically. The default is `false`.
+    ///
+    /// [fling deceleration]: Scroll::with_fling_deceleration
+    pub fn with_fling(mut self, fling: bool) -> Self {
+        self.fling.enabled = fling;
+        self
+    }
+
+    /// Builder-style method to set how quickly a fling slows down, in pixels per second squared.
+    ///
+    /// The default is 2500.
+    pub fn with_fling_deceleration(mut self, deceleration: f64) -> Self {
+        self.fling.deceleration = deceleration;
+        self
+    }
+
+    /// Builder-style method to set whether a fling can pull the content past its edges.
+    ///
+    /// If `true`, a fling that reaches the edge of the content keeps going for
+    /// a bit, and a spring then pulls the content back. The default is `false`.
+    pub fn with_overscroll(mut self, overscroll: bool) -> Self {
+        self.fling.overscroll_enabled = overscroll;
+        self
+    }
+
+    /// Builder-style method to set the stiffness of the overscroll spring.
+    ///
+    /// Stiffer springs let the content go less far past the edge, and pull it
+    /// back more quickly. The default is 300.
+    pub fn with_overscroll_stiffness(mut self, stiffness: f64) -> Self {
+        self.fling.stiffness = stiffness;
+        self
+    }
+
     /// Disable both scrollbars
     pub fn disable_scrollbars(mut self) -> Self {
         self.scroll_component.enabled = ScrollbarsEnabled::None;
         self
     }
 
+    /// Builder-style method to set when the scrollbars are shown.
+    ///
+    /// The default is [`ScrollbarBehavior::WhenScrolling`], which shows them
+    /// while scrolling and lets them fade out afterwards.
+    pub fn scrollbar_behavior(mut self, behavior: ScrollbarBehavior) -> Self {
+        self.scroll_component.behavior = behavior;
+        self
+    }
+
     /// Set whether the child's size must be greater than or equal the size of
     /// the `Scroll` widget.
     ///
@@ -125,6 +303,11 @@ impl<T, W> Scroll<T, W> {
         self.scroll_component.enabled = enabled;
     }
 
+    /// Set when the scrollbars are shown.
+    pub fn set_scrollbar_behavior(&mut self, behavior: ScrollbarBehavior) {
+        self.scroll_component.behavior = behavior;
+    }
+
     /// Set whether the content can be scrolled in the vertical direction.
     pub fn set_vertical_scroll_enabled(&mut self, enabled: bool) {
         self.clip.set_constrain_vertical(!enabled);
@@ -177,6 +360,53 @@ impl<T, W> Scroll<T, W> {
 impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Some(binding) = &mut self.offset_binding {
+            match (&mut binding.animation, event) {
+                (Some(animation), Event::AnimFrame(interval)) => {
+                    animation.elapsed += Duration::from_nanos(*interval);
+                    let t = (animation.elapsed.as_secs_f64()
+                        / SMOOTH_SCROLL_DURATION.as_secs_f64())
+                    .min(1.0);
+                    // ease out
+                    let offset = animation.from.lerp(animation.to, 1.0 - (1.0 - t).powi(3));
+                    self.clip.pan_to(offset.to_point());
+                    if t < 1.0 {
+                        ctx.request_anim_frame();
+                    } else {
+                        binding.animation = None;
+                    }
+                    ctx.request_paint();
+                }
+                // The user takes over.
+                (_, Event::Wheel(_) | Event::MouseDown(_)) => binding.animation = None,
+                _ => (),
+            }
+        }
+
+        match event {
+            Event::Wheel(_) | Event::MouseDown(_) => self.fling.stop(),
+            Event::Timer(token) if *token == self.fling.timer => {
+                self.fling.timer = TimerToken::INVALID;
+                let port = self.clip.viewport();
+                if self.fling.start(&port) {
+                    ctx.request_anim_frame();
+                    self.scroll_component
+                        .reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+                }
+                ctx.set_handled();
+            }
+            Event::AnimFrame(interval) if self.fling.is_moving() => {
+                let fling = &mut self.fling;
+                let elapsed = Duration::from_nanos(*interval).as_secs_f64();
+                self.clip.with_port(|port| fling.advance(port, elapsed));
+                if self.fling.is_moving() {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+
         let scroll_component = &mut self.scroll_component;
         self.clip.with_port(|port| {
             scroll_component.event(port, ctx, event, env);
@@ -187,15 +417,23 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
 
         // Handle scroll after the inner widget processed the events, to prefer inner widgets while
         // scrolling.
+        let handled_by_child = ctx.is_handled();
         self.clip.with_port(|port| {
             scroll_component.handle_scroll(port, ctx, event, env);
         });
+        if let Event::Wheel(mouse) = event {
+            if self.fling.enabled && !handled_by_child && ctx.is_handled() {
+                self.fling.record(Instant::now(), mouse.wheel_delta);
+                self.fling.timer = ctx.request_timer(FLING_START_DELAY);
+            }
+        }
 
         if !self.scroll_component.are_bars_held() {
             // We only scroll to the component if the user is not trying to move the scrollbar.
             if let Event::Notification(notification) = event {
                 if let Some(&global_highlight_rect) = notification.get(SCROLL_TO_VIEW) {
                     ctx.set_handled();
+                    self.fling.cancel();
                     let view_port_changed = self
                         .clip
                         .default_scroll_to_view_handling(ctx, global_highlight_rect);
@@ -206,10 +444,28 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
                 }
             }
         }
+
+        // Write the offset back, unless we are still moving towards the offset from the data.
+        let offset = self.offset();
+        if let Some(binding) = &mut self.offset_binding {
+            if binding.animation.is_none()
+                && !binding.needs_initial_scroll
+                && binding.synced != offset
+            {
+                binding.synced = offset;
+                binding.lens.put(data, offset);
+            }
+        }
+        self.clip.notify_viewport_change(ctx);
     }
 
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let (Some(binding), LifeCycle::WidgetAdded) = (&mut self.offset_binding, event) {
+            // The child has no size yet, so we apply this after the first layout.
+            binding.synced = binding.lens.get(data);
+            binding.needs_initial_scroll = true;
+        }
         self.scroll_component.lifecycle(ctx, event, env);
         self.clip.lifecycle(ctx, event, data, env);
     }
@@ -217,6 +473,37 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
         self.clip.update(ctx, old_data, data, env);
+
+        if let Some(binding) = &mut self.offset_binding {
+            let target = binding.lens.get(data);
+            // Only react to changes that didn't originate from us, so that user
+            // scrolling and data changes don't feed back into each other.
+            if !target.same(&binding.synced) {
+                binding.synced = target;
+                self.fling.cancel();
+                if binding.needs_initial_scroll {
+                    // This will be applied after the first layout.
+                } else if self.smooth_scroll {
+                    binding.animation = Some(ScrollAnimation {
+                        from: self.clip.viewport_origin().to_vec2(),
+                        to: self
+                            .clip
+                            .viewport()
+                            .clamp_view_origin(target.to_point())
+                            .to_vec2(),
+                        elapsed: Duration::ZERO,
+                    });
+                    ctx.request_anim_frame();
+                } else {
+                    binding.animation = None;
+                    if self.clip.pan_to(target.to_point()) {
+                        ctx.request_paint();
+                        self.scroll_component
+                            .reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+                    }
+                }
+            }
+        }
     }
 
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, bc, data, env))]
@@ -230,7 +517,13 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
         let self_size = bc.constrain(child_size);
         // The new size might have made the current scroll offset invalid. This makes it valid
         // again.
-        let _ = self.scroll_by(Vec2::ZERO);
+        let _ = self.clip.pan_by(Vec2::ZERO);
+        if let Some(binding) = &mut self.offset_binding {
+            if binding.needs_initial_scroll {
+                binding.needs_initial_scroll = false;
+                let _ = self.clip.pan_to(binding.synced.to_point());
+            }
+        }
         if old_size != self_size {
             self.scroll_component
                 .reset_scrollbar_fade(|d| ctx.request_timer(d), env);
@@ -242,7 +535,18 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
 
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        self.clip.paint(ctx, data, env);
+        let overscroll = self.fling.overscroll;
+        if overscroll == Vec2::ZERO {
+            self.clip.paint(ctx, data, env);
+        } else {
+            let clip = &mut self.clip;
+            let bounds = ctx.size().to_rect();
+            ctx.with_save(|ctx| {
+                ctx.clip(bounds);
+                ctx.transform(Affine::translate(-overscroll));
+                clip.paint(ctx, data, env);
+            });
+        }
         self.scroll_component
             .draw_bars(ctx, &self.clip.viewport(), env);
     }
@@ -256,6 +560,126 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
     }
 }
 
+impl Fling {
+    fn new() -> Fling {
+        Fling {
+            enabled: false,
+            deceleration: FLING_DECELERATION,
+            overscroll_enabled: false,
+            stiffness: OVERSCROLL_STIFFNESS,
+            samples: VecDeque::new(),
+            timer: TimerToken::INVALID,
+            velocity: None,
+            overscroll: Vec2::ZERO,
+            overscroll_velocity: Vec2::ZERO,
+        }
+    }
+
+    fn is_moving(&self) -> bool {
+        self.velocity.is_some()
+            || self.overscroll != Vec2::ZERO
+            || self.overscroll_velocity != Vec2::ZERO
+    }
+
+    /// Remember a wheel delta, to estimate the velocity once the wheel stops.
+    fn record(&mut self, at: Instant, delta: Vec2) {
+        while matches!(self.samples.front(), Some((time, _)) if at - *time > FLING_SAMPLE_WINDOW) {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((at, delta));
+    }
+
+    /// Start a fling with the velocity of the recent wheel events.
+    ///
+    /// Returns `true` if they were fast enough to start one.
+    fn start(&mut self, port: &Viewport) -> bool {
+        let samples = std::mem::take(&mut self.samples);
+        let (first, last) = match (samples.front(), samples.back()) {
+            (Some(first), Some(last)) if last.0 > first.0 => (first.0, last.0),
+            _ => return false,
+        };
+        // The first delta happened before the sampled time span started.
+        let distance = samples.iter().skip(1).fold(Vec2::ZERO, |sum, s| sum + s.1);
+        let mut velocity = distance / (last - first).as_secs_f64();
+        if port.content_size.width <= port.view_size.width {
+            velocity.x = 0.0;
+        }
+        if port.content_size.height <= port.view_size.height {
+            velocity.y = 0.0;
+        }
+        if velocity.hypot() < MIN_FLING_VELOCITY {
+            return false;
+        }
+        trace!("Starting fling at {:?}", velocity);
+        self.velocity = Some(velocity);
+        true
+    }
+
+    /// Stop the fling because the user took over.
+    fn stop(&mut self) {
+        self.samples.clear();
+        self.velocity = None;
+    }
+
+    /// Stop the fling and any overscroll because the offset was set programmatically.
+    fn cancel(&mut self) {
+        self.stop();
+        self.overscroll = Vec2::ZERO;
+        self.overscroll_velocity = Vec2::ZERO;
+    }
+
+    /// Move the viewport and the overscroll ahead by `elapsed` seconds.
+    fn advance(&mut self, port: &mut Viewport, elapsed: f64) {
+        if let Some(velocity) = &mut self.velocity {
+            let delta = *velocity * elapsed;
+            let before = port.view_origin;
+            port.pan_by(delta);
+            let blocked = delta - (port.view_origin - before);
+            // At an edge, the fling either stops or turns into overscroll.
+            for (velocity, overscroll_velocity, blocked) in [
+                (&mut velocity.x, &mut self.overscroll_velocity.x, blocked.x),
+                (&mut velocity.y, &mut self.overscroll_velocity.y, blocked.y),
+            ] {
+                if blocked.abs() > 1e-9 {
+                    if self.overscroll_enabled {
+                        *overscroll_velocity = *velocity;
+                    }
+                    *velocity = 0.0;
+                }
+            }
+
+            let speed = velocity.hypot();
+            let slowed = speed - self.deceleration * elapsed;
+            if slowed <= 0.0 {
+                self.velocity = None;
+            } else {
+                *velocity *= slowed / speed;
+            }
+        }
+
+        if self.is_moving() {
+            self.advance_spring(elapsed);
+        }
+    }
+
+    /// Advance the critically damped spring that pulls the overscroll back to zero.
+    fn advance_spring(&mut self, elapsed: f64) {
+        let steps = (elapsed / MAX_SPRING_STEP).ceil().max(1.0);
+        let dt = elapsed / steps;
+        let damping = 2.0 * self.stiffness.sqrt();
+        for _ in 0..steps as usize {
+            let acceleration =
+                -self.overscroll * self.stiffness - self.overscroll_velocity * damping;
+            self.overscroll_velocity += acceleration * dt;
+            self.overscroll += self.overscroll_velocity * dt;
+        }
+        if self.overscroll.hypot() < 0.5 && self.overscroll_velocity.hypot() < 5.0 {
+            self.overscroll = Vec2::ZERO;
+            self.overscroll_velocity = Vec2::ZERO;
+        }
+    }
+}
+
 fn log_size_warnings(size: Size) {
     if size.width.is_infinite() {
         tracing::warn!("Scroll widget's child has an infinite width.");
@@ -265,3 +689,176 @@ fn log_size_warnings(size: Size) {
         tracing::warn!("Scroll widget's child has an infinite height.");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::tests::{move_mouse, scroll_mouse};
+    use crate::{MouseButton, MouseEvent, Point, WidgetExt};
+    use test_log::test;
+
+    fn scrolled_content() -> impl Widget<(u32, Vec2)> {
+        ModularWidget::new(()).layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(400., 1000.)))
+    }
+
+    #[test]
+    fn bound_offset() {
+        let [content_id] = widget_ids();
+        let scroll = Scroll::new(scrolled_content().with_id(content_id))
+            .vertical()
+            .bind_offset(lens!((u32, Vec2), 1));
+
+        Harness::create_simple((0, Vec2::new(0., 300.)), scroll, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // The initial offset is applied once the content size is known.
+            assert_eq!(
+                harness.get_state(content_id).viewport_offset,
+                Vec2::new(0., 300.)
+            );
+
+            // Scrolling by the user is written back.
+            harness.event(Event::Wheel(scroll_mouse((10., 10.), (0., 50.))));
+            assert_eq!(harness.data().1, Vec2::new(0., 350.));
+
+            // Writing to the data moves the viewport, clamped to the content bounds.
+            harness.update_data(|data| data.1 = Vec2::new(0., 5000.));
+            assert_eq!(
+                harness.get_state(content_id).viewport_offset,
+                Vec2::new(0., 600.)
+            );
+            harness.event(Event::Wheel(scroll_mouse((10., 10.), (0., 0.))));
+            assert_eq!(harness.data().1, Vec2::new(0., 600.));
+        });
+    }
+
+    #[test]
+    fn track_and_thumb() {
+        let [content_id] = widget_ids();
+        let scroll = Scroll::new(scrolled_content().with_id(content_id))
+            .vertical()
+            .scrollbar_behavior(ScrollbarBehavior::Always);
+
+        Harness::create_simple((0, Vec2::ZERO), scroll, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let offset = |harness: &mut Harness<_>| harness.get_state(content_id).viewport_offset.y;
+            let press = |pos: (f64, f64)| MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse(pos)
+            };
+
+            // The thumb is at the top, so clicking the track below it pages down.
+            harness.event(Event::MouseDown(press((395., 300.))));
+            harness.event(Event::MouseUp(press((395., 300.))));
+            assert_eq!(offset(harness), 400.);
+
+            // The thumb moves 236px for the 600px of content, and dragging it
+            // follows the mouse.
+            harness.event(Event::MouseDown(press((395., 200.))));
+            harness.event(Event::MouseMove(move_mouse((395., 82.))));
+            harness.event(Event::MouseUp(press((395., 82.))));
+            assert!((offset(harness) - 100.).abs() <= 1.);
+        });
+    }
+
+    #[test]
+    fn smooth_bound_offset() {
+        let [content_id] = widget_ids();
+        let scroll = Scroll::new(scrolled_content().with_id(content_id))
+            .vertical()
+            .bind_offset(lens!((u32, Vec2), 1))
+            .with_smooth_scroll(true);
+
+        Harness::create_simple((0, Vec2::ZERO), scroll, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.update_data(|data| data.1 = Vec2::new(0., 400.));
+            harness.event(Event::AnimFrame(Duration::from_millis(50).as_nanos() as u64));
+            let offset = harness.get_state(content_id).viewport_offset;
+            assert!(offset.y > 0. && offset.y < 400.);
+            // Intermediate offsets are not written back.
+            assert_eq!(harness.data().1, Vec2::new(0., 400.));
+
+            harness.event(Event::AnimFrame(SMOOTH_SCROLL_DURATION.as_nanos() as u64));
+            assert_eq!(
+                harness.get_state(content_id).viewport_offset,
+                Vec2::new(0., 400.)
+            );
+        });
+    }
+
+    fn tall_viewport(y: f64) -> Viewport {
+        Viewport {
+            content_size: Size::new(400., 2000.),
+            view_origin: Point::new(0., y),
+            view_size: Size::new(400., 400.),
+        }
+    }
+
+    fn flick(fling: &mut Fling, port: &Viewport, step: Duration, delta: f64) -> bool {
+        let start = Instant::now();
+        for i in 0..3 {
+            fling.record(start + step * i, Vec2::new(0., delta));
+        }
+        fling.start(port)
+    }
+
+    #[test]
+    fn fling_decelerates() {
+        let mut fling = Fling::new();
+        let mut port = tall_viewport(0.);
+        assert!(!flick(&mut fling, &port, Duration::from_millis(10), 0.5));
+        assert!(!fling.is_moving());
+
+        // 40 pixels in 20ms is 2000 pixels per second.
+        assert!(flick(&mut fling, &port, Duration::from_millis(10), 20.));
+        fling.advance(&mut port, 0.1);
+        assert_eq!(port.view_origin.y, 200.);
+        assert_eq!(fling.velocity, Some(Vec2::new(0., 1750.)));
+
+        for _ in 0..100 {
+            fling.advance(&mut port, 1.0 / 60.0);
+        }
+        assert!(!fling.is_moving());
+        assert!(port.view_origin.y > 600. && port.view_origin.y < 1000.);
+
+        // Without overscroll, the edge stops the fling.
+        let mut port = tall_viewport(1550.);
+        assert!(flick(&mut fling, &port, Duration::from_millis(10), 20.));
+        fling.advance(&mut port, 0.1);
+        assert_eq!(port.view_origin.y, 1600.);
+        assert!(!fling.is_moving());
+    }
+
+    #[test]
+    fn overscroll_springs_back() {
+        let mut fling = Fling::new();
+        fling.overscroll_enabled = true;
+        let mut port = tall_viewport(1550.);
+        assert!(flick(&mut fling, &port, Duration::from_millis(10), 20.));
+
+        let mut furthest: f64 = 0.;
+        for _ in 0..120 {
+            fling.advance(&mut port, 1.0 / 60.0);
+            furthest = furthest.max(fling.overscroll.y);
+        }
+        assert_eq!(port.view_origin.y, 1600.);
+        assert!(furthest > 10. && furthest < 100.);
+        assert!(!fling.is_moving());
+        assert_eq!(fling.overscroll, Vec2::ZERO);
+    }
+
+    #[test]
+    fn scrolling_programmatically_cancels_fling() {
+        let mut scroll = Scroll::<(), _>::new(crate::widget::SizedBox::empty()).with_fling(true);
+        scroll.fling.velocity = Some(Vec2::new(0., 1000.));
+        scroll.fling.overscroll = Vec2::new(0., 20.);
+        scroll.scroll_by(Vec2::new(0., 10.));
+        assert!(!scroll.fling.is_moving());
+    }
+}
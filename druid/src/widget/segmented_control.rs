@@ -0,0 +1,385 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A row of mutually exclusive segments.
+
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{Line, RoundedRectRadii};
+use crate::piet::{LinearGradient, RenderContext, UnitPoint};
+use crate::widget::prelude::*;
+use crate::widget::{Label, LabelText};
+use crate::{theme, KbKey, Point, WidgetPod};
+
+/// How long the selection takes to slide to another segment, in seconds.
+const SLIDE_TIME: f64 = 0.15;
+/// How far the selection is drawn inside of the border.
+const SELECTION_INSET: f64 = 2.0;
+
+/// A row of segments that share a border, of which one is selected, like
+/// the segmented controls of iOS and macOS.
+///
+/// The data is the index of the selected segment; use [`lens`] to select it
+/// from a larger type. The segments are labels or other widgets, and all of
+/// them are as wide as the widest one. The selection slides to a segment
+/// when it is clicked, and while the control has keyboard focus, the Left
+/// and Right arrow keys select the previous or next segment.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::SegmentedControl;
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Calendar {
+///     period: usize,
+/// }
+///
+/// fn period_picker() -> impl Widget<Calendar> {
+///     SegmentedControl::new(["Day", "Week", "Month"]).lens(Calendar::period)
+/// }
+/// ```
+///
+/// [`lens`]: crate::WidgetExt::lens
+pub struct SegmentedControl {
+    segments: Vec<WidgetPod<usize, Box<dyn Widget<usize>>>>,
+    segment_width: f64,
+    /// Where the selection is drawn, in segments from the left.
+    selection_pos: f64,
+    /// How fast the selection slides, in segments per second.
+    slide_speed: f64,
+    /// The segment under the pointer when the mouse button was pressed.
+    pressed: Option<usize>,
+}
+
+impl SegmentedControl {
+    /// Create a new `SegmentedControl` with a segment for each label.
+    pub fn new(labels: impl IntoIterator<Item = impl Into<LabelText<usize>> + 'static>) -> Self {
+        let mut control = SegmentedControl {
+            segments: Vec::new(),
+            segment_width: 0.0,
+            selection_pos: 0.0,
+            slide_speed: 0.0,
+            pressed: None,
+        };
+        for label in labels {
+            control.add_segment(Label::new(label));
+        }
+        control
+    }
+
+    /// Builder-style method to add a segment that shows `child`, after the
+    /// existing ones.
+    pub fn with_segment(mut self, child: impl Widget<usize> + 'static) -> Self {
+        self.add_segment(child);
+        self
+    }
+
+    /// Add a segment that shows `child`, after the existing ones.
+    pub fn add_segment(&mut self, child: impl Widget<usize> + 'static) {
+        self.segments.push(WidgetPod::new(child).boxed());
+    }
+
+    /// The segment at `x`, if there are any.
+    fn segment_at(&self, x: f64) -> Option<usize> {
+        if self.segments.is_empty() || self.segment_width <= 0.0 {
+            return None;
+        }
+        let index = (x / self.segment_width).floor().max(0.0) as usize;
+        Some(index.min(self.segments.len() - 1))
+    }
+
+    /// The selected segment, as shown; `data` may be past the last segment.
+    fn target_pos(&self, data: usize) -> f64 {
+        data.min(self.segments.len().saturating_sub(1)) as f64
+    }
+}
+
+impl Widget<usize> for SegmentedControl {
+    #[instrument(
+        name = "SegmentedControl",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut usize, env: &Env) {
+        for segment in &mut self.segments {
+            segment.event(ctx, event, data, env);
+        }
+        if ctx.is_handled() {
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) if !ctx.is_disabled() => {
+                ctx.set_active(true);
+                ctx.request_focus();
+                self.pressed = self.segment_at(mouse.pos.x);
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                let pressed = self.pressed.take();
+                if ctx.is_hot() && !ctx.is_disabled() && pressed == self.segment_at(mouse.pos.x) {
+                    if let Some(index) = pressed {
+                        *data = index;
+                    }
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() && !ctx.is_disabled() => {
+                let last = self.segments.len().saturating_sub(1);
+                let selected = (*data).min(last);
+                match key.key {
+                    KbKey::ArrowLeft => *data = selected.saturating_sub(1),
+                    KbKey::ArrowRight => *data = (selected + 1).min(last),
+                    _ => return,
+                }
+                ctx.set_handled();
+            }
+            Event::AnimFrame(interval) => {
+                let target = self.target_pos(*data);
+                let step = self.slide_speed * Duration::from_nanos(*interval).as_secs_f64();
+                let distance = target - self.selection_pos;
+                if distance.abs() <= step {
+                    self.selection_pos = target;
+                } else {
+                    self.selection_pos += step.copysign(distance);
+                    ctx.request_anim_frame();
+                }
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "SegmentedControl",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &usize, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => self.selection_pos = self.target_pos(*data),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_)
+            | LifeCycle::HotChanged(_)
+            | LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+        for segment in &mut self.segments {
+            segment.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "SegmentedControl",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &usize, data: &usize, env: &Env) {
+        for segment in &mut self.segments {
+            segment.update(ctx, data, env);
+        }
+        let distance = (self.target_pos(*data) - self.selection_pos).abs();
+        if old_data != data && distance > 0.0 {
+            self.slide_speed = distance / SLIDE_TIME;
+            ctx.request_anim_frame();
+        }
+    }
+
+    #[instrument(
+        name = "SegmentedControl",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &usize,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("SegmentedControl");
+        let count = self.segments.len().max(1) as f64;
+        let padding = Size::new(
+            env.get(theme::WIDGET_PADDING_HORIZONTAL),
+            env.get(theme::WIDGET_PADDING_VERTICAL) / 2.0,
+        );
+
+        // every segment gets an equal share of the largest width
+        let max_child_width = if bc.is_width_bounded() {
+            (bc.max().width / count - 2.0 * padding.width).max(0.0)
+        } else {
+            f64::INFINITY
+        };
+        let max_child_height = (bc.max().height - 2.0 * padding.height).max(0.0);
+        let child_bc =
+            BoxConstraints::new(Size::ZERO, Size::new(max_child_width, max_child_height));
+        let mut widest = 0.0f64;
+        let mut tallest = 0.0f64;
+        for segment in &mut self.segments {
+            let child_size = segment.layout(ctx, &child_bc, data, env);
+            widest = widest.max(child_size.width);
+            tallest = tallest.max(child_size.height);
+        }
+
+        let height = (tallest + 2.0 * padding.height).max(env.get(theme::BORDERED_WIDGET_HEIGHT));
+        let size = bc.constrain(Size::new((widest + 2.0 * padding.width) * count, height));
+        self.segment_width = size.width / count;
+
+        let mut baseline = None;
+        for (i, segment) in self.segments.iter_mut().enumerate() {
+            let child_size = segment.layout_rect().size();
+            let origin = Point::new(
+                i as f64 * self.segment_width + (self.segment_width - child_size.width) / 2.0,
+                (size.height - child_size.height) / 2.0,
+            );
+            segment.set_origin(ctx, data, env, origin);
+            let child_baseline =
+                size.height - origin.y - child_size.height + segment.baseline_offset();
+            baseline.get_or_insert(child_baseline);
+        }
+        ctx.set_baseline_offset(baseline.unwrap_or(0.0));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "SegmentedControl", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &usize, env: &Env) {
+        let size = ctx.size();
+        let radius = env.get(theme::BUTTON_BORDER_RADIUS);
+        let stroke_width = env.get(theme::BUTTON_BORDER_WIDTH);
+        let outline = size
+            .to_rect()
+            .inset(-stroke_width / 2.0)
+            .to_rounded_rect(radius);
+        ctx.fill(outline, &env.get(theme::BACKGROUND_DARK));
+
+        // the dividers next to the selection are hidden by it
+        let border_color = env.get(theme::BORDER_DARK);
+        for i in 1..self.segments.len() {
+            if (i as f64 - self.selection_pos - 0.5).abs() < 1.0 {
+                continue;
+            }
+            let x = i as f64 * self.segment_width;
+            let divider = Line::new(
+                (x, SELECTION_INSET * 2.0),
+                (x, size.height - SELECTION_INSET * 2.0),
+            );
+            ctx.stroke(divider, &border_color, 1.0);
+        }
+
+        if !self.segments.is_empty() {
+            let selection = Size::new(self.segment_width, size.height)
+                .to_rect()
+                .with_origin((self.selection_pos * self.segment_width, 0.0))
+                .inset(-SELECTION_INSET)
+                .to_rounded_rect(RoundedRectRadii::new(
+                    (radius.top_left - SELECTION_INSET).max(0.0),
+                    (radius.top_right - SELECTION_INSET).max(0.0),
+                    (radius.bottom_right - SELECTION_INSET).max(0.0),
+                    (radius.bottom_left - SELECTION_INSET).max(0.0),
+                ));
+            let (light, dark) = if ctx.is_disabled() {
+                (theme::DISABLED_BUTTON_LIGHT, theme::DISABLED_BUTTON_DARK)
+            } else {
+                (theme::BUTTON_LIGHT, theme::BUTTON_DARK)
+            };
+            let gradient = LinearGradient::new(
+                UnitPoint::TOP,
+                UnitPoint::BOTTOM,
+                (env.get(light), env.get(dark)),
+            );
+            ctx.fill(selection, &gradient);
+            ctx.stroke(selection, &env.get(theme::BORDER_LIGHT), 1.0);
+        }
+
+        let outline_color = if ctx.is_focused() && !ctx.is_disabled() {
+            env.get(theme::FOCUS_RING_COLOR)
+        } else if ctx.is_hot() && !ctx.is_disabled() {
+            env.get(theme::BORDER_LIGHT)
+        } else {
+            border_color
+        };
+        ctx.stroke(outline, &outline_color, stroke_width);
+
+        for segment in &mut self.segments {
+            segment.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &usize) -> DebugState {
+        DebugState {
+            display_name: "SegmentedControl".to_string(),
+            main_value: data.to_string(),
+            children: self
+                .segments
+                .iter()
+                .map(|segment| segment.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{click, press_key, widget_ids};
+    use crate::widget::SizedBox;
+    use crate::WidgetExt;
+    use test_log::test;
+
+    #[test]
+    fn equal_segments_clicks_and_keys() {
+        let [control_id, wide_id, narrow_id] = widget_ids();
+        let control = SegmentedControl::new(["One"])
+            .with_segment(SizedBox::empty().width(80.).with_id(wide_id))
+            .with_segment(SizedBox::empty().width(20.).with_id(narrow_id))
+            .with_id(control_id)
+            .center();
+
+        Harness::create_simple(0usize, control, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let rect = harness.get_state(control_id).layout_rect();
+            let segment = rect.width() / 3.0;
+            assert!(segment >= 80.0);
+            let wide = harness.get_state(wide_id).layout_rect();
+            let narrow = harness.get_state(narrow_id).layout_rect();
+            // the segments are equally wide, with the children centered in them
+            assert_eq!(wide.center().x, segment * 1.5);
+            assert_eq!(narrow.center().x, segment * 2.5);
+
+            click(
+                harness,
+                Point::new(rect.x0 + segment * 2.5, rect.center().y),
+            );
+            assert_eq!(*harness.data(), 2);
+            // the selection slides over
+            assert!(harness.window().wants_animation_frame());
+            harness.event(Event::AnimFrame(Duration::from_secs(1).as_nanos() as u64));
+            assert!(!harness.window().wants_animation_frame());
+
+            press_key(harness, KbKey::ArrowLeft);
+            assert_eq!(*harness.data(), 1);
+            press_key(harness, KbKey::ArrowRight);
+            press_key(harness, KbKey::ArrowRight);
+            assert_eq!(*harness.data(), 2);
+        });
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that sets the cursor while the pointer is over a widget.
+//!
+//! [`Controller`]: crate::widget::Controller
+
+use crate::widget::Controller;
+use crate::{Cursor, Data, Env, Event, EventCtx, Widget};
+use tracing::{instrument, trace};
+
+/// A [`Controller`] that shows a particular [`Cursor`] while its child is
+/// [`hot`] or [`active`].
+///
+/// The default cursor is restored when the pointer leaves the widget. If the
+/// child (or one of its descendants) sets a cursor of its own, that cursor
+/// takes precedence. While the widget is [`disabled`], the default cursor is
+/// shown.
+///
+/// This is also available, for convenience, as a `with_cursor` method
+/// via [`WidgetExt`].
+///
+/// [`Controller`]: crate::widget::Controller
+/// [`WidgetExt`]: crate::widget::WidgetExt
+/// [`hot`]: crate::EventCtx::is_hot
+/// [`active`]: crate::EventCtx::is_active
+/// [`disabled`]: crate::EventCtx::is_disabled
+pub struct SetCursor {
+    cursor: Cursor,
+    is_set: bool,
+}
+
+impl SetCursor {
+    /// Create a new [`Controller`] that shows the given cursor over its child.
+    ///
+    /// [`Controller`]: crate::widget::Controller
+    pub fn new(cursor: Cursor) -> Self {
+        SetCursor {
+            cursor,
+            is_set: false,
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for SetCursor {
+    #[instrument(
+        name = "SetCursor",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::MouseMove(_) | Event::MouseDown(_) = event {
+            if ctx.is_disabled() {
+                if self.is_set {
+                    trace!("clearing cursor");
+                    ctx.clear_cursor();
+                    self.is_set = false;
+                }
+            } else {
+                // We share our widget state with the child, so we do this before the
+                // child sees the event; any cursor it sets will then replace ours.
+                ctx.set_cursor(&self.cursor);
+                self.is_set = true;
+            }
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{Align, SizedBox};
+    use crate::{UnitPoint, WidgetExt};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use test_log::test;
+
+    /// Records the cursor its child asks the window for after each event.
+    struct CursorProbe(Rc<RefCell<Option<Cursor>>>);
+
+    impl<T, W: Widget<T>> Controller<T, W> for CursorProbe {
+        fn event(
+            &mut self,
+            child: &mut W,
+            ctx: &mut EventCtx,
+            event: &Event,
+            data: &mut T,
+            env: &Env,
+        ) {
+            child.event(ctx, event, data, env);
+            *self.0.borrow_mut() = ctx.widget_state.cursor.clone();
+        }
+    }
+
+    /// Put `widget` in the top left corner, with a probe for the window's cursor.
+    fn probed<T: Data>(
+        widget: impl Widget<T> + 'static,
+    ) -> (impl Widget<T>, Rc<RefCell<Option<Cursor>>>) {
+        let cursor = Rc::new(RefCell::new(None));
+        let probe = CursorProbe(cursor.clone());
+        let widget = Align::new(UnitPoint::TOP_LEFT, widget.fix_size(50.0, 50.0)).controller(probe);
+        (widget, cursor)
+    }
+
+    #[test]
+    fn set_while_hot() {
+        let (widget, cursor) = probed(SizedBox::empty().with_cursor(Cursor::Pointer));
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseMove(move_mouse((100.0, 100.0))));
+            assert_eq!(*cursor.borrow(), None);
+
+            harness.event(Event::MouseMove(move_mouse((10.0, 10.0))));
+            assert_eq!(*cursor.borrow(), Some(Cursor::Pointer));
+
+            harness.event(Event::MouseMove(move_mouse((100.0, 100.0))));
+            assert_eq!(*cursor.borrow(), None);
+        });
+    }
+
+    #[test]
+    fn inner_cursor_wins() {
+        let (widget, cursor) = probed(
+            SizedBox::empty()
+                .with_cursor(Cursor::Crosshair)
+                .with_cursor(Cursor::Pointer),
+        );
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseMove(move_mouse((10.0, 10.0))));
+            assert_eq!(*cursor.borrow(), Some(Cursor::Crosshair));
+        });
+    }
+
+    #[test]
+    fn not_set_while_disabled() {
+        let (widget, cursor) = probed(
+            SizedBox::empty()
+                .with_cursor(Cursor::Pointer)
+                .disabled_if(|disabled: &bool, _| *disabled),
+        );
+
+        Harness::create_simple(true, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseMove(move_mouse((10.0, 10.0))));
+            assert_eq!(*cursor.borrow(), None);
+
+            harness.update_data(|disabled| *disabled = false);
+            harness.event(Event::MouseMove(move_mouse((12.0, 12.0))));
+            assert_eq!(*cursor.borrow(), Some(Cursor::Pointer));
+
+            // Disabling it again restores the default cursor.
+            harness.update_data(|disabled| *disabled = true);
+            harness.event(Event::MouseMove(move_mouse((10.0, 10.0))));
+            assert_eq!(*cursor.borrow(), None);
+        });
+    }
+}
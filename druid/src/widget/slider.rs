@@ -14,12 +14,16 @@
 
 //! A slider widget.
 
+use crate::accessibility::Role;
 use crate::debug_state::DebugState;
 use crate::kurbo::{Circle, Line};
 use crate::theme::TEXT_COLOR;
 use crate::widget::prelude::*;
 use crate::widget::Axis;
-use crate::{theme, Color, KeyOrValue, LinearGradient, Point, Rect, UnitPoint, Vec2, WidgetPod};
+use crate::{
+    theme, Color, KbKey, KeyEvent, KeyOrValue, LinearGradient, Point, Rect, UnitPoint, Vec2,
+    WidgetPod,
+};
 use druid::kurbo::{PathEl, Shape};
 use druid::piet::{PietText, PietTextLayout, Text, TextLayout, TextLayoutBuilder};
 use tracing::{instrument, trace, warn};
@@ -27,30 +31,54 @@ use tracing::{instrument, trace, warn};
 const TRACK_THICKNESS: f64 = 4.0;
 const BORDER_WIDTH: f64 = 2.0;
 const KNOB_STROKE_WIDTH: f64 = 2.0;
+const TICK_LENGTH: f64 = 4.0;
 
 /// A slider, allowing interactive update of a numeric value.
 ///
 /// This slider implements `Widget<f64>`, and works on values clamped
 /// in the range `min..max`.
+///
+/// The slider can be focused with the keyboard. While it has focus, the arrow
+/// keys move the value by one step, page up and page down move it by a
+/// [page step], and home and end move it to the ends of the range.
+///
+/// [page step]: Slider::with_page_step
 #[derive(Debug, Clone, Default)]
 pub struct Slider {
     mapping: SliderValueMapping,
     knob: SliderKnob,
     track_color: Option<KeyOrValue<Color>>,
     knob_style: KnobStyle,
+    ticks: usize,
+    page_step: Option<f64>,
 }
 
 /// A range slider, allowing interactive update of two numeric values .
 ///
 /// This slider implements `Widget<(f64, f64)>`, and works on value pairs clamped
 /// in the range `min..max`, where the left value is always smaller than the right.
+///
+/// Each knob is a stop in the focus chain: tab moves from the lower knob to
+/// the upper knob before leaving the slider. The focused knob is moved with
+/// the same keys as a [`Slider`], and can't be moved past the other knob.
+/// Clicking the track moves the nearest knob to the click and focuses it.
 #[derive(Debug, Clone, Default)]
 pub struct RangeSlider {
     mapping: SliderValueMapping,
     left_knob: SliderKnob,
     right_knob: SliderKnob,
+    focused_knob: RangeKnob,
     track_color: Option<KeyOrValue<Color>>,
     knob_style: KnobStyle,
+    page_step: Option<f64>,
+}
+
+/// The knob of a [`RangeSlider`] that receives keyboard input.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+enum RangeKnob {
+    #[default]
+    Lower,
+    Upper,
 }
 
 /// A annotated Slider or RangeSlider
@@ -111,7 +139,9 @@ impl Slider {
 
     /// Builder-style method to set the stepping.
     ///
-    /// The default step size is `0.0` (smooth).
+    /// The value is snapped to `min` plus a multiple of the step, or to `max`.
+    /// The default step size is `0.0` (smooth), in which case the arrow keys
+    /// move the value by a hundredth of the range.
     pub fn with_step(mut self, step: f64) -> Self {
         if step < 0.0 {
             warn!("bad stepping (must be positive): {}", step);
@@ -127,6 +157,28 @@ impl Slider {
         self
     }
 
+    /// Builder-style method to set how far page up and page down move the
+    /// value.
+    ///
+    /// The default is ten steps.
+    pub fn with_page_step(mut self, page_step: f64) -> Self {
+        if page_step <= 0.0 {
+            warn!("bad page step (must be positive): {}", page_step);
+            return self;
+        }
+        self.page_step = Some(page_step);
+        self
+    }
+
+    /// Builder-style method to draw tick marks along the track.
+    ///
+    /// The `count` tick marks are spread evenly from `min` to `max`. The
+    /// default is `0`, for no tick marks.
+    pub fn with_ticks(mut self, count: usize) -> Self {
+        self.ticks = count;
+        self
+    }
+
     /// Builder-style method to set the track color.
     ///
     /// The default color is `None`.
@@ -156,6 +208,11 @@ impl Slider {
         self.mapping
     }
 
+    /// The value after pressing `key`, if it changes the value.
+    fn value_for_key(&self, key: &KbKey, value: f64) -> Option<f64> {
+        self.mapping.value_for_key(key, value, self.page_step)
+    }
+
     /// Builder-style method to create an annotated range slider.
     ///
     pub fn annotated(self, named_steps: f64, unnamed_steps: f64) -> Annotated<f64, Self> {
@@ -173,26 +230,47 @@ impl Widget<f64> for Slider {
 
             ctx.set_active(self.knob.is_active());
 
-            if let Event::MouseDown(me) = event {
-                if !self.knob.active {
-                    self.knob.activate(0.0);
-                    let knob_size = env.get(theme::BASIC_WIDGET_HEIGHT);
-                    *data = self
-                        .mapping
-                        .calculate_value(me.pos, knob_size, ctx.size(), 0.0);
-                    ctx.request_paint();
-                    ctx.set_active(true);
+            match event {
+                Event::MouseDown(me) => {
+                    ctx.request_focus();
+                    if !self.knob.active {
+                        self.knob.activate(0.0);
+                        let knob_size = env.get(theme::BASIC_WIDGET_HEIGHT);
+                        *data = self
+                            .mapping
+                            .calculate_value(me.pos, knob_size, ctx.size(), 0.0);
+                        ctx.request_paint();
+                        ctx.set_active(true);
+                    }
                 }
+                Event::KeyDown(key) if ctx.is_focused() => {
+                    if let Some(value) = self.value_for_key(&key.key, *data) {
+                        trace!(
+                            "Slider {:?} moved to {} with the keyboard",
+                            ctx.widget_id(),
+                            value
+                        );
+                        *data = value;
+                        ctx.request_paint();
+                        ctx.set_handled();
+                    }
+                }
+                _ => (),
             }
         }
     }
 
-    #[instrument(name = "Slider", level = "trace", skip(self, ctx, event, _data, _env))]
-    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &f64, _env: &Env) {
+    #[instrument(name = "Slider", level = "trace", skip(self, ctx, event, data, _env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &f64, _env: &Env) {
         match event {
             // checked in LifeCycle::WidgetAdded because logging may not be setup in with_range
-            LifeCycle::WidgetAdded => self.mapping.check_range(),
-            LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            LifeCycle::WidgetAdded => {
+                self.mapping.check_range();
+                ctx.set_access_role(Role::Slider);
+                ctx.set_access_value(data.to_string());
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::DisabledChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
             _ => (),
         }
     }
@@ -200,9 +278,10 @@ impl Widget<f64> for Slider {
     #[instrument(
         name = "Slider",
         level = "trace",
-        skip(self, ctx, _old_data, _data, _env)
+        skip(self, ctx, _old_data, data, _env)
     )]
-    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &f64, _data: &f64, _env: &Env) {
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &f64, data: &f64, _env: &Env) {
+        ctx.set_access_value(data.to_string());
         ctx.request_paint();
     }
 
@@ -215,6 +294,12 @@ impl Widget<f64> for Slider {
     #[instrument(name = "Slider", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &f64, env: &Env) {
         paint_slider_background(ctx, 0.0, *data, &self.track_color, self.mapping, env);
+        paint_ticks(ctx, self.ticks, self.mapping, env);
+
+        if ctx.is_focused() && !ctx.is_disabled() {
+            let ring = ctx.size().to_rect().inset(-0.5).to_rounded_rect(3.0);
+            ctx.stroke(ring, &env.get(theme::FOCUS_RING_COLOR), 1.0);
+        }
 
         self.knob
             .paint(ctx, *data, env, self.mapping, self.knob_style);
@@ -262,9 +347,18 @@ impl RangeSlider {
         self
     }
 
-    /// Builder-style method to set the track color.
+    /// Builder-style method to set the amount page up and page down move the
+    /// focused knob by.
     ///
-    /// The default color is `None`.
+    /// The default is ten steps.
+    pub fn with_page_step(mut self, page_step: f64) -> Self {
+        self.page_step = Some(page_step.abs());
+        self
+    }
+
+    /// Builder-style method to set the color of the track between the knobs.
+    ///
+    /// The default is `None`, which uses [`theme::PRIMARY_LIGHT`].
     pub fn track_color(mut self, color: impl Into<Option<KeyOrValue<Color>>>) -> Self {
         self.track_color = color.into();
         self
@@ -291,6 +385,42 @@ impl RangeSlider {
         self.mapping
     }
 
+    /// Handle a key press while the slider is focused.
+    ///
+    /// Tab moves between the knobs before moving focus out of the slider.
+    fn key_down(&mut self, ctx: &mut EventCtx, key: &KeyEvent, data: &mut (f64, f64)) {
+        if key.key == KbKey::Tab {
+            match (self.focused_knob, key.mods.shift()) {
+                (RangeKnob::Lower, false) => self.focused_knob = RangeKnob::Upper,
+                (RangeKnob::Upper, true) => self.focused_knob = RangeKnob::Lower,
+                (_, false) => ctx.focus_next(),
+                (_, true) => ctx.focus_prev(),
+            }
+            ctx.request_paint();
+            ctx.set_handled();
+            return;
+        }
+
+        let (lower, upper) = *data;
+        let value = match self.focused_knob {
+            RangeKnob::Lower => lower,
+            RangeKnob::Upper => upper,
+        };
+        if let Some(value) = self.mapping.value_for_key(&key.key, value, self.page_step) {
+            match self.focused_knob {
+                RangeKnob::Lower => data.0 = value.min(upper),
+                RangeKnob::Upper => data.1 = value.max(lower),
+            }
+            trace!(
+                "RangeSlider {:?} moved to {:?} with the keyboard",
+                ctx.widget_id(),
+                data
+            );
+            ctx.request_paint();
+            ctx.set_handled();
+        }
+    }
+
     /// Builder-style method to create an annotated range slider.
     ///
     pub fn annotated(self, named_steps: f64, unnamed_steps: f64) -> Annotated<(f64, f64), Self> {
@@ -341,7 +471,14 @@ impl Widget<(f64, f64)> for RangeSlider {
             }
             ctx.set_active(self.left_knob.is_active() || self.right_knob.is_active());
 
+            if let Event::KeyDown(key) = event {
+                if ctx.is_focused() {
+                    self.key_down(ctx, key, data);
+                }
+            }
+
             if let Event::MouseDown(me) = event {
+                ctx.request_focus();
                 if !self.left_knob.is_active() && !self.right_knob.is_active() {
                     let knob_size = env.get(theme::BASIC_WIDGET_HEIGHT);
                     let press_value =
@@ -358,6 +495,11 @@ impl Widget<(f64, f64)> for RangeSlider {
                     ctx.set_active(true);
                     ctx.request_paint();
                 }
+                self.focused_knob = if self.left_knob.is_active() {
+                    RangeKnob::Lower
+                } else {
+                    RangeKnob::Upper
+                };
             }
         }
     }
@@ -377,7 +519,8 @@ impl Widget<(f64, f64)> for RangeSlider {
         match event {
             // checked in LifeCycle::WidgetAdded because logging may not be setup in with_range
             LifeCycle::WidgetAdded => self.mapping.check_range(),
-            LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::DisabledChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
             _ => (),
         }
     }
@@ -411,7 +554,22 @@ impl Widget<(f64, f64)> for RangeSlider {
 
     #[instrument(name = "RangeSlider", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &(f64, f64), env: &Env) {
-        paint_slider_background(ctx, data.0, data.1, &self.track_color, self.mapping, env);
+        let track_color = self
+            .track_color
+            .clone()
+            .unwrap_or_else(|| theme::PRIMARY_LIGHT.into());
+        paint_slider_background(ctx, data.0, data.1, &Some(track_color), self.mapping, env);
+
+        if ctx.is_focused() && !ctx.is_disabled() {
+            let knob_size = env.get(theme::BASIC_WIDGET_HEIGHT);
+            let value = match self.focused_knob {
+                RangeKnob::Lower => data.0,
+                RangeKnob::Upper => data.1,
+            };
+            let center = self.mapping.get_point(value, knob_size, ctx.size());
+            let ring = Circle::new(center, knob_size / 2.0 + 1.5);
+            ctx.stroke(ring, &env.get(theme::FOCUS_RING_COLOR), 1.0);
+        }
 
         // We paint the left knob at last since it receives events first and therefore behaves like
         // being "on top".
@@ -634,27 +792,49 @@ impl SliderValueMapping {
         let scalar = (self.axis.major_pos(mouse_pos) - knob_size / 2.)
             / (self.axis.major(slider_size) - knob_size);
 
-        let mut value = (self.min + scalar * (self.max - self.min) + offset)
-            .min(self.max)
-            .max(self.min);
-
-        if let Some(step) = self.step {
-            let max_step_value = ((self.max - self.min) / step).floor() * step + self.min;
-            if value > max_step_value {
-                // edge case: make sure max is reachable
-                let left_dist = value - max_step_value;
-                let right_dist = self.max - value;
-                value = if left_dist < right_dist {
-                    max_step_value
-                } else {
-                    self.max
-                };
-            } else {
-                // snap to discrete intervals
-                value = (((value - self.min) / step).round() * step + self.min).min(self.max);
-            }
+        self.snap(self.min + scalar * (self.max - self.min) + offset)
+    }
+
+    /// Clamp `value` to the range, and snap it to the closest step.
+    ///
+    /// The steps are computed from their index rather than by adding up the
+    /// step size, and are rounded to the decimal places of the step and of
+    /// `min`, so that a step of `0.1` gives `0.3` and not `0.30000000000000004`.
+    fn snap(&self, value: f64) -> f64 {
+        let value = value.min(self.max).max(self.min);
+        let step = match self.step {
+            Some(step) => step,
+            None => return value,
+        };
+        // The epsilon keeps `0.3 / 0.1` from being floored to `2.0`.
+        let max_index = ((self.max - self.min) / step + 1e-9).floor();
+        let index = ((value - self.min) / step).round().min(max_index);
+        let places = decimal_places(step).max(decimal_places(self.min));
+        let snapped = round_to_places(self.min + index * step, places).min(self.max);
+        // edge case: make sure max is reachable
+        if value - snapped > self.max - value {
+            self.max
+        } else {
+            snapped
         }
-        value
+    }
+
+    /// The value a key press moves `value` to, if the key moves the slider.
+    ///
+    /// Without a `page_step`, page up and page down move by ten steps.
+    fn value_for_key(&self, key: &KbKey, value: f64, page_step: Option<f64>) -> Option<f64> {
+        let step = self.step.unwrap_or(self.range() / 100.0);
+        let page_step = page_step.unwrap_or(step * 10.0);
+        let amount = match key {
+            KbKey::ArrowRight | KbKey::ArrowUp => step,
+            KbKey::ArrowLeft | KbKey::ArrowDown => -step,
+            KbKey::PageUp => page_step,
+            KbKey::PageDown => -page_step,
+            KbKey::Home => return Some(self.min),
+            KbKey::End => return Some(self.max),
+            _ => return None,
+        };
+        Some(self.snap(value + amount))
     }
 
     fn get_point(&self, value: f64, knob_size: f64, widget_size: Size) -> Point {
@@ -696,6 +876,23 @@ impl Default for SliderValueMapping {
     }
 }
 
+/// The number of decimal places of `value`, as it is displayed.
+fn decimal_places(value: f64) -> i32 {
+    let text = value.to_string();
+    text.find('.')
+        .map_or(0, |dot| (text.len() - dot - 1) as i32)
+}
+
+/// Round `value` to `places` decimal places, if that is within the precision
+/// of an `f64`.
+fn round_to_places(value: f64, places: i32) -> f64 {
+    if places > 15 {
+        return value;
+    }
+    let factor = 10f64.powi(places);
+    (value * factor).round() / factor
+}
+
 impl SliderKnob {
     fn handle_input(
         &mut self,
@@ -897,8 +1094,12 @@ fn paint_slider_background(
         UnitPoint::TOP,
         UnitPoint::BOTTOM,
         (
-            env.get(theme::BACKGROUND_LIGHT),
-            env.get(theme::BACKGROUND_DARK),
+            ctx.resolve_disabled(
+                env,
+                theme::BACKGROUND_LIGHT,
+                theme::DISABLED_BACKGROUND_LIGHT,
+            ),
+            ctx.resolve_disabled(env, theme::BACKGROUND_DARK, theme::DISABLED_BACKGROUND_DARK),
         ),
     );
 
@@ -920,6 +1121,35 @@ fn paint_slider_background(
     }
 }
 
+fn paint_ticks(ctx: &mut PaintCtx, count: usize, mapping: SliderValueMapping, env: &Env) {
+    let size = ctx.size();
+    let knob_size = env.get(theme::BASIC_WIDGET_HEIGHT);
+    let color = if ctx.is_disabled() {
+        env.get(theme::DISABLED_TEXT_COLOR)
+    } else {
+        env.get(TEXT_COLOR)
+    };
+    // Perpendicular to the track, away from it.
+    let (dx, dy) = mapping.axis.pack(0.0, 1.0);
+    let direction = Vec2::new(dx, dy);
+
+    for i in 0..count {
+        let fraction = if count == 1 {
+            0.5
+        } else {
+            i as f64 / (count - 1) as f64
+        };
+        let center = mapping
+            .get_point(mapping.min + fraction * mapping.range(), knob_size, size)
+            .to_vec2();
+        let line = Line::new(
+            (center + direction * TRACK_THICKNESS).to_point(),
+            (center + direction * (TRACK_THICKNESS + TICK_LENGTH)).to_point(),
+        );
+        ctx.stroke(line, &color, 1.0);
+    }
+}
+
 fn slider_layout(
     ctx: &mut LayoutCtx,
     bc: &BoxConstraints,
@@ -945,3 +1175,125 @@ fn slider_layout(
 
     size
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::tests::move_mouse;
+    use crate::{KeyEvent, Modifiers, MouseButton, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    #[test]
+    fn steps_do_not_drift() {
+        let mapping = Slider::new()
+            .with_range(0.0, 1.0)
+            .with_step(0.1)
+            .get_mapping();
+        assert_eq!(mapping.snap(0.31), 0.3);
+        assert_eq!(mapping.snap(0.96), 1.0);
+        assert_eq!(mapping.snap(-3.0), 0.0);
+
+        // The maximum is reachable even if it isn't on a step.
+        let mapping = Slider::new()
+            .with_range(0.5, 2.0)
+            .with_step(0.4)
+            .get_mapping();
+        assert_eq!(mapping.snap(1.76), 1.7);
+        assert_eq!(mapping.snap(1.9), 2.0);
+
+        let slider = Slider::new().with_range(0.0, 1.0).with_step(0.1);
+        let mut value = 0.0;
+        for _ in 0..7 {
+            value = slider.value_for_key(&KbKey::ArrowRight, value).unwrap();
+        }
+        assert_eq!(value, 0.7);
+    }
+
+    #[test]
+    fn keyboard_moves_by_steps() {
+        let [id] = widget_ids();
+        let slider = Slider::new()
+            .with_range(0.0, 10.0)
+            .with_step(0.5)
+            .with_page_step(2.0)
+            .with_ticks(3)
+            .with_id(id)
+            .center();
+
+        Harness::create_simple(5.0, slider, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // Clicking the knob focuses the slider without moving it.
+            let state = harness.get_state(id);
+            let knob = state.window_origin() + state.layout_rect().size().to_vec2() / 2.0;
+            let mouse = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse(knob)
+            };
+            harness.event(Event::MouseDown(mouse.clone()));
+            harness.event(Event::MouseUp(mouse));
+            assert_eq!(harness.window().focus, Some(id));
+            assert_eq!(*harness.data(), 5.0);
+
+            let mut press = |key: KbKey| {
+                harness.event(Event::KeyDown(KeyEvent::for_test(
+                    Modifiers::default(),
+                    key,
+                )));
+                *harness.data()
+            };
+            assert_eq!(press(KbKey::ArrowRight), 5.5);
+            assert_eq!(press(KbKey::ArrowDown), 5.0);
+            assert_eq!(press(KbKey::PageDown), 3.0);
+            assert_eq!(press(KbKey::End), 10.0);
+            assert_eq!(press(KbKey::PageUp), 10.0);
+        });
+    }
+
+    #[test]
+    fn range_knobs_are_focused_separately() {
+        let [id] = widget_ids();
+        let slider = RangeSlider::new()
+            .with_range(0.0, 10.0)
+            .with_step(1.0)
+            .with_id(id)
+            .fix_width(218.0)
+            .center();
+
+        Harness::create_simple((2.0, 8.0), slider, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // Clicking the track near the upper knob moves and focuses it.
+            let state = harness.get_state(id);
+            // The knobs are 18px wide, leaving 200px for the range.
+            let click = state.window_origin() + Vec2::new(9.0 + 140.0, 9.0);
+            let mouse = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse(click)
+            };
+            harness.event(Event::MouseDown(mouse.clone()));
+            harness.event(Event::MouseUp(mouse));
+            assert_eq!(harness.window().focus, Some(id));
+            assert_eq!(harness.data().0, 2.0);
+            assert_eq!(harness.data().1, 7.0);
+
+            let mut press = |key: KbKey, mods: Modifiers| {
+                harness.event(Event::KeyDown(KeyEvent::for_test(mods, key)));
+                *harness.data()
+            };
+            assert_eq!(press(KbKey::End, Modifiers::default()), (2.0, 10.0));
+            // Shift-tab moves to the lower knob, which can't pass the upper one.
+            press(KbKey::Tab, Modifiers::SHIFT);
+            assert_eq!(press(KbKey::ArrowRight, Modifiers::default()), (3.0, 10.0));
+            assert_eq!(press(KbKey::End, Modifiers::default()), (10.0, 10.0));
+            assert_eq!(press(KbKey::PageDown, Modifiers::default()), (0.0, 10.0));
+            // Tab moves back to the upper knob, which can't pass the lower one.
+            press(KbKey::Tab, Modifiers::default());
+            assert_eq!(press(KbKey::Home, Modifiers::default()), (0.0, 0.0));
+        });
+    }
+}
@@ -0,0 +1,357 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A small line that shows a trend.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use tracing::instrument;
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, Circle};
+use crate::piet::RenderContext;
+use crate::widget::prelude::*;
+use crate::{theme, Color, KeyOrValue, Point};
+
+/// The size of a sparkline that isn't constrained.
+const DEFAULT_SIZE: Size = Size::new(80.0, 20.0);
+const LINE_WIDTH: f64 = 1.5;
+const MARKER_RADIUS: f64 = 2.0;
+
+/// The values shown by a [`Sparkline`].
+///
+/// This is implemented for `Arc<Vec<f64>>`, for `Arc<VecDeque<f64>>`, which
+/// is a good ring buffer for live data, and for `im::Vector<f64>` with the
+/// `im` feature.
+pub trait SparklineData: Data {
+    /// The number of values.
+    fn len(&self) -> usize;
+
+    /// The value at `index`, which is less than `len`.
+    fn get(&self, index: usize) -> f64;
+
+    /// Returns `true` if there are no values.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl SparklineData for Arc<Vec<f64>> {
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn get(&self, index: usize) -> f64 {
+        self[index]
+    }
+}
+
+impl SparklineData for Arc<VecDeque<f64>> {
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn get(&self, index: usize) -> f64 {
+        self[index]
+    }
+}
+
+#[cfg(feature = "im")]
+impl SparklineData for im::Vector<f64> {
+    fn len(&self) -> usize {
+        im::Vector::len(self)
+    }
+
+    fn get(&self, index: usize) -> f64 {
+        self[index]
+    }
+}
+
+/// A compact line chart without axes, that shows a trend next to a label.
+///
+/// The values are spread out evenly over the width of the widget, and
+/// scaled to its height between their smallest and largest value, or
+/// between the bounds set with [`with_range`]. Values that aren't finite
+/// are skipped. The smallest and largest value, and the last one, can be
+/// marked with a dot.
+///
+/// For live data, append to an `Arc<VecDeque<f64>>` and drop the oldest
+/// values from the front; [`Arc::make_mut`] only copies the buffer if the
+/// old data is still used somewhere else.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::VecDeque;
+/// use std::sync::Arc;
+///
+/// use druid::widget::{Flex, Label, Sparkline};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Monitor {
+///     load: Arc<VecDeque<f64>>,
+/// }
+///
+/// impl Monitor {
+///     fn record(&mut self, load: f64) {
+///         let history = Arc::make_mut(&mut self.load);
+///         if history.len() == 60 {
+///             history.pop_front();
+///         }
+///         history.push_back(load);
+///     }
+/// }
+///
+/// fn load_row() -> impl Widget<Monitor> {
+///     Flex::row().with_child(Label::new("CPU")).with_child(
+///         Sparkline::new()
+///             .with_range(0.0, 1.0)
+///             .show_last(true)
+///             .lens(Monitor::load),
+///     )
+/// }
+/// ```
+///
+/// [`with_range`]: Sparkline::with_range
+pub struct Sparkline {
+    color: KeyOrValue<Color>,
+    range: Option<(f64, f64)>,
+    show_min_max: bool,
+    show_last: bool,
+}
+
+/// Where the values of a [`Sparkline`] are drawn.
+#[derive(Debug, Default, PartialEq)]
+struct Plot {
+    points: Vec<Point>,
+    /// The indices in `points` of the smallest and largest value.
+    min: usize,
+    max: usize,
+}
+
+impl Sparkline {
+    /// Create a new `Sparkline`, scaled to its values.
+    pub fn new() -> Self {
+        Sparkline {
+            color: theme::CHART_SERIES_COLOR.into(),
+            range: None,
+            show_min_max: false,
+            show_last: false,
+        }
+    }
+
+    /// Builder-style method for scaling the line from `min` at the bottom
+    /// to `max` at the top, instead of to the values.
+    ///
+    /// Values outside of the range are drawn at its edge.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Builder-style method for setting the color of the line.
+    ///
+    /// The default is [`CHART_SERIES_COLOR`].
+    ///
+    /// [`CHART_SERIES_COLOR`]: theme::CHART_SERIES_COLOR
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Builder-style method for marking the smallest and largest value.
+    pub fn show_min_max(mut self, show: bool) -> Self {
+        self.show_min_max = show;
+        self
+    }
+
+    /// Builder-style method for marking the last value.
+    pub fn show_last(mut self, show: bool) -> Self {
+        self.show_last = show;
+        self
+    }
+
+    /// Compute where the values are drawn in a widget of `size`.
+    fn plot(&self, data: &impl SparklineData, size: Size) -> Plot {
+        let values: Vec<f64> = (0..data.len())
+            .map(|index| data.get(index))
+            .filter(|value| value.is_finite())
+            .collect();
+        if values.is_empty() {
+            return Plot::default();
+        }
+        let (mut min, mut max) = (0, 0);
+        for (i, &value) in values.iter().enumerate() {
+            if value < values[min] {
+                min = i;
+            }
+            if value > values[max] {
+                max = i;
+            }
+        }
+        let (low, high) = self.range.unwrap_or((values[min], values[max]));
+
+        // inset by the markers, so that they aren't cut off
+        let area = size.to_rect().inset(-MARKER_RADIUS);
+        let step = if values.len() > 1 {
+            area.width() / (values.len() - 1) as f64
+        } else {
+            0.0
+        };
+        let points = values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                let fraction = if high > low {
+                    ((value - low) / (high - low)).clamp(0.0, 1.0)
+                } else {
+                    // a constant line goes through the middle
+                    0.5
+                };
+                let x = if values.len() > 1 {
+                    area.x0 + i as f64 * step
+                } else {
+                    area.center().x
+                };
+                Point::new(x, area.y1 - fraction * area.height())
+            })
+            .collect();
+        Plot { points, min, max }
+    }
+}
+
+impl Default for Sparkline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: SparklineData> Widget<T> for Sparkline {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &T, _env: &Env) {}
+
+    #[instrument(
+        name = "Sparkline",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) || ctx.env_key_changed(&self.color) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Sparkline", level = "trace", skip(self, _ctx, bc, _data, _env))]
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, _env: &Env) -> Size {
+        bc.debug_check("Sparkline");
+        bc.constrain(DEFAULT_SIZE)
+    }
+
+    #[instrument(name = "Sparkline", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let plot = self.plot(data, ctx.size());
+        let (first, rest) = match plot.points.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+        let color = self.color.resolve(env);
+        let mut path = BezPath::new();
+        path.move_to(*first);
+        for point in rest {
+            path.line_to(*point);
+        }
+        ctx.stroke(path, &color, LINE_WIDTH);
+
+        let mut markers = Vec::new();
+        if self.show_min_max {
+            markers.extend([plot.min, plot.max]);
+        }
+        if self.show_last || plot.points.len() == 1 {
+            markers.push(plot.points.len() - 1);
+        }
+        for index in markers {
+            ctx.fill(Circle::new(plot.points[index], MARKER_RADIUS), &color);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let values: Vec<String> = (0..data.len())
+            .map(|index| data.get(index).to_string())
+            .collect();
+        DebugState {
+            display_name: "Sparkline".to_string(),
+            main_value: values.join(" "),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use test_log::test;
+
+    fn ys(sparkline: &Sparkline, values: &[f64]) -> (Vec<f64>, usize, usize) {
+        // 4 points high with the markers
+        let size = Size::new(30.0 + 2.0 * MARKER_RADIUS, 4.0 + 2.0 * MARKER_RADIUS);
+        let plot = sparkline.plot(&Arc::new(values.to_vec()), size);
+        let ys = plot.points.iter().map(|p| p.y - MARKER_RADIUS).collect();
+        (ys, plot.min, plot.max)
+    }
+
+    #[test]
+    fn scaling() {
+        let auto = Sparkline::new();
+        assert_eq!(ys(&auto, &[1.0, 3.0, 2.0]), (vec![4.0, 0.0, 2.0], 0, 1));
+        assert_eq!(ys(&auto, &[]), (vec![], 0, 0));
+        assert_eq!(ys(&auto, &[5.0, 5.0]), (vec![2.0, 2.0], 0, 0));
+        assert_eq!(
+            ys(&auto, &[f64::NAN, 1.0, 2.0]),
+            (vec![4.0, 0.0], 0, 1),
+            "skips values that aren't finite"
+        );
+
+        let fixed = Sparkline::new().with_range(0.0, 4.0);
+        assert_eq!(ys(&fixed, &[1.0, 3.0, 8.0]), (vec![3.0, 1.0, 0.0], 0, 2));
+
+        let size = Size::new(34.0, 8.0);
+        let points = auto.plot(&Arc::new(vec![1.0, 2.0, 3.0, 4.0]), size).points;
+        let xs: Vec<f64> = points.iter().map(|p| p.x).collect();
+        assert_eq!(xs, [2.0, 12.0, 22.0, 32.0]);
+    }
+
+    #[test]
+    fn live_data() {
+        let data = Arc::new(VecDeque::from(vec![1.0, 2.0]));
+        let widget = Sparkline::new().show_min_max(true).show_last(true);
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            for value in 3..6 {
+                harness.update_data(|data| {
+                    let history = Arc::make_mut(data);
+                    history.pop_front();
+                    history.push_back(value as f64);
+                });
+                harness.paint();
+            }
+            assert_eq!(harness.root_debug_state().main_value, "4 5");
+        });
+    }
+}
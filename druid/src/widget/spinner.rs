@@ -17,19 +17,63 @@
 use std::f64::consts::PI;
 use tracing::{instrument, trace};
 
-use druid::kurbo::Line;
+use druid::kurbo::{Arc, Line};
 use druid::widget::prelude::*;
-use druid::{theme, Color, Data, KeyOrValue, Point, Vec2};
+use druid::widget::{Flex, Label, LabelText};
+use druid::{theme, Color, Data, InternalLifeCycle, KeyOrValue, Point, Vec2};
 
 /// An animated spinner widget for showing a loading state.
 ///
-/// To customize the spinner's size, you can place it inside a [`SizedBox`]
-/// that has a fixed width and height.
+/// The spinner fills the space it is given, if that is bounded in both
+/// directions; otherwise it is [`BASIC_WIDGET_HEIGHT`] wide and high. To
+/// customize the spinner's size, you can place it inside a [`SizedBox`] that
+/// has a fixed width and height.
 ///
+/// The spinner only animates while it is painted. When it is hidden, for
+/// instance by an [`Either`] or [`Tabs`], or scrolled out of view, it stops
+/// requesting animation frames. It starts again when it is laid out, moved
+/// or updated while visible, like when its [`Either`] branch is shown again
+/// or it is scrolled back into view.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Spinner, SpinnerStyle};
+/// use druid::{theme, Widget, WidgetExt};
+///
+/// fn loading() -> impl Widget<()> {
+///     Spinner::new()
+///         .with_style(SpinnerStyle::Ring)
+///         .with_color(theme::PRIMARY_LIGHT)
+///         .with_stroke_width(4.0)
+///         .with_speed(0.5)
+///         .fix_size(32.0, 32.0)
+/// }
+/// ```
+///
+/// [`BASIC_WIDGET_HEIGHT`]: crate::theme::BASIC_WIDGET_HEIGHT
 /// [`SizedBox`]: struct.SizedBox.html
+/// [`Either`]: super::Either
+/// [`Tabs`]: super::Tabs
 pub struct Spinner {
     t: f64,
     color: KeyOrValue<Color>,
+    style: SpinnerStyle,
+    stroke_width: Option<KeyOrValue<f64>>,
+    speed: f64,
+    /// Whether we have been painted since the last animation frame.
+    painted: bool,
+    /// Whether we are requesting animation frames.
+    animating: bool,
+}
+
+/// The way a [`Spinner`] is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpinnerStyle {
+    /// Twelve ticks arranged in a circle, fading one after the other.
+    Ticks,
+    /// A rotating arc.
+    Ring,
 }
 
 impl Spinner {
@@ -38,6 +82,14 @@ impl Spinner {
         Spinner::default()
     }
 
+    /// Create a spinner followed by a label, such as "Loading…".
+    pub fn labeled<T: Data>(text: impl Into<LabelText<T>>) -> Flex<T> {
+        Flex::row()
+            .with_child(Spinner::new())
+            .with_default_spacer()
+            .with_child(Label::new(text))
+    }
+
     /// Builder-style method for setting the spinner's color.
     ///
     /// The argument can be either a `Color` or a [`Key<Color>`].
@@ -56,6 +108,86 @@ impl Spinner {
     pub fn set_color(&mut self, color: impl Into<KeyOrValue<Color>>) {
         self.color = color.into();
     }
+
+    /// Builder-style method for setting the way the spinner is drawn.
+    ///
+    /// The default is [`SpinnerStyle::Ticks`].
+    pub fn with_style(mut self, style: SpinnerStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Builder-style method for setting the width of the spinner's lines.
+    ///
+    /// By default, the width is proportional to the size of the spinner.
+    pub fn with_stroke_width(mut self, width: impl Into<KeyOrValue<f64>>) -> Self {
+        self.stroke_width = Some(width.into());
+        self
+    }
+
+    /// Builder-style method for setting how many turns the spinner makes per
+    /// second.
+    ///
+    /// The default is `1.0`.
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Returns `true` if the spinner is requesting animation frames.
+    ///
+    /// This is `false` while the spinner is not visible.
+    pub fn is_animating(&self) -> bool {
+        self.animating
+    }
+
+    fn start_animating(&mut self) {
+        // Assume that we are visible until the first frame shows otherwise.
+        self.painted = true;
+        self.animating = true;
+    }
+
+    fn stroke_width(&self, default: f64, env: &Env) -> f64 {
+        self.stroke_width
+            .as_ref()
+            .map_or(default, |width| width.resolve(env))
+    }
+
+    fn paint_ticks(&self, ctx: &mut PaintCtx, color: Color, env: &Env) {
+        let t = self.t;
+        let (width, height) = (ctx.size().width, ctx.size().height);
+        let center = Point::new(width / 2.0, height / 2.0);
+        let (r, g, b, original_alpha) = Color::as_rgba(&color);
+        let scale_factor = width.min(height) / 40.0;
+        let stroke_width = self.stroke_width(3.0 * scale_factor, env);
+
+        for step in 1..=12 {
+            let step = f64::from(step);
+            let fade_t = (t * 12.0 + 1.0).trunc();
+            let fade = ((fade_t + step).rem_euclid(12.0) / 12.0) + 1.0 / 12.0;
+            let angle = Vec2::from_angle((step / 12.0) * -2.0 * PI);
+            let ambit_start = center + (10.0 * scale_factor * angle);
+            let ambit_end = center + (20.0 * scale_factor * angle);
+            let color = Color::rgba(r, g, b, fade * original_alpha);
+
+            ctx.stroke(Line::new(ambit_start, ambit_end), &color, stroke_width);
+        }
+    }
+
+    fn paint_ring(&self, ctx: &mut PaintCtx, color: Color, env: &Env) {
+        let size = ctx.size();
+        let diameter = size.width.min(size.height);
+        let stroke_width = self.stroke_width(diameter / 10.0, env);
+        let radius = ((diameter - stroke_width) / 2.0).max(0.0);
+        let arc = Arc {
+            center: size.to_rect().center(),
+            radii: Vec2::new(radius, radius),
+            start_angle: self.t * 2.0 * PI,
+            sweep_angle: 1.5 * PI,
+            x_rotation: 0.0,
+        };
+        ctx.stroke(arc, &color, stroke_width);
+    }
 }
 
 impl Default for Spinner {
@@ -63,6 +195,11 @@ impl Default for Spinner {
         Spinner {
             t: 0.0,
             color: theme::TEXT_COLOR.into(),
+            style: SpinnerStyle::Ticks,
+            stroke_width: None,
+            speed: 1.0,
+            painted: false,
+            animating: false,
         }
     }
 }
@@ -70,30 +207,56 @@ impl Default for Spinner {
 impl<T: Data> Widget<T> for Spinner {
     #[instrument(name = "Spinner", level = "trace", skip(self, ctx, event, _data, _env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
-        if let Event::AnimFrame(interval) = event {
-            self.t += (*interval as f64) * 1e-9;
-            if self.t >= 1.0 {
-                self.t = 0.0;
+        match event {
+            Event::AnimFrame(interval) if self.animating => {
+                if !self.painted {
+                    // We weren't painted since the last frame, so we aren't visible.
+                    trace!("Spinner is not visible, stopping animation");
+                    self.animating = false;
+                    return;
+                }
+                self.t += (*interval as f64) * 1e-9 * self.speed;
+                self.t = self.t.rem_euclid(1.0);
+                self.painted = false;
+                ctx.request_anim_frame();
+                ctx.request_paint();
             }
-            ctx.request_anim_frame();
-            ctx.request_paint();
+            _ => (),
         }
     }
 
     #[instrument(name = "Spinner", level = "trace", skip(self, ctx, event, _data, _env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
-        if let LifeCycle::WidgetAdded = event {
-            ctx.request_anim_frame();
-            ctx.request_paint();
+        match event {
+            LifeCycle::WidgetAdded => self.start_animating(),
+            // Hidden widgets don't get `ParentWindowOrigin`, which is sent after
+            // each layout and whenever a `Scroll` moves its content.
+            LifeCycle::Size(_) | LifeCycle::Internal(InternalLifeCycle::ParentWindowOrigin)
+                if !self.animating =>
+            {
+                trace!("Spinner may be visible again, restarting animation");
+                self.start_animating();
+            }
+            _ => return,
         }
+        ctx.request_anim_frame();
+        ctx.request_paint();
     }
 
     #[instrument(
         name = "Spinner",
         level = "trace",
-        skip(self, _ctx, _old_data, _data, _env)
+        skip(self, ctx, _old_data, _data, _env)
     )]
-    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {}
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {
+        // Containers like `Either` and `Tabs` only update the children they
+        // show, so being updated likely means that we are visible again.
+        if !self.animating {
+            self.start_animating();
+            ctx.request_anim_frame();
+            ctx.request_paint();
+        }
+    }
 
     #[instrument(
         name = "Spinner",
@@ -124,26 +287,79 @@ impl<T: Data> Widget<T> for Spinner {
 
     #[instrument(name = "Spinner", level = "trace", skip(self, ctx, _data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
-        let t = self.t;
-        let (width, height) = (ctx.size().width, ctx.size().height);
-        let center = Point::new(width / 2.0, height / 2.0);
-        let (r, g, b, original_alpha) = Color::as_rgba(&self.color.resolve(env));
-        let scale_factor = width.min(height) / 40.0;
+        self.painted = true;
+        let color = self.color.resolve(env);
+        match self.style {
+            SpinnerStyle::Ticks => self.paint_ticks(ctx, color, env),
+            SpinnerStyle::Ring => self.paint_ring(ctx, color, env),
+        }
+    }
+}
 
-        for step in 1..=12 {
-            let step = f64::from(step);
-            let fade_t = (t * 12.0 + 1.0).trunc();
-            let fade = ((fade_t + step).rem_euclid(12.0) / 12.0) + 1.0 / 12.0;
-            let angle = Vec2::from_angle((step / 12.0) * -2.0 * PI);
-            let ambit_start = center + (10.0 * scale_factor * angle);
-            let ambit_end = center + (20.0 * scale_factor * angle);
-            let color = Color::rgba(r, g, b, fade * original_alpha);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::{move_mouse, scroll_mouse};
+    use crate::widget::{Either, Scroll, SizedBox};
+    use crate::WidgetExt;
+    use test_log::test;
 
-            ctx.stroke(
-                Line::new(ambit_start, ambit_end),
-                &color,
-                3.0 * scale_factor,
-            );
-        }
+    const FRAME: u64 = 16_000_000;
+
+    #[test]
+    fn stops_animating_when_hidden() {
+        let widget = Either::new(|show, _| *show, Spinner::new(), SizedBox::empty());
+
+        Harness::create_simple(true, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+            harness.event(Event::AnimFrame(FRAME));
+            harness.paint();
+            assert!(harness.window().wants_animation_frame());
+
+            harness.update_data(|show| *show = false);
+            harness.just_layout();
+            for _ in 0..2 {
+                harness.event(Event::AnimFrame(FRAME));
+                harness.paint();
+            }
+            assert!(!harness.window().wants_animation_frame());
+            // Nothing is polled while hidden.
+            assert!(harness.window().timers.is_empty());
+
+            harness.update_data(|show| *show = true);
+            harness.just_layout();
+            assert!(harness.window().wants_animation_frame());
+        });
+    }
+
+    #[test]
+    fn resumes_when_scrolled_into_view() {
+        let widget = Scroll::new(
+            Flex::column()
+                .with_child(Spinner::new().fix_size(40.0, 40.0))
+                .with_child(SizedBox::empty().fix_size(40.0, 400.0)),
+        )
+        .vertical()
+        .fix_size(40.0, 100.0);
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint_invalid();
+            harness.event(Event::Wheel(scroll_mouse((10.0, 10.0), (0.0, 200.0))));
+            for _ in 0..2 {
+                harness.event(Event::AnimFrame(FRAME));
+                harness.paint_invalid();
+            }
+            assert!(!harness.window().wants_animation_frame());
+
+            harness.event(Event::Wheel(scroll_mouse((10.0, 10.0), (0.0, -200.0))));
+            // The content learns about its new position with the next event.
+            harness.event(Event::MouseMove(move_mouse((10.0, 10.0))));
+            assert!(harness.window().wants_animation_frame());
+        });
     }
 }
@@ -15,17 +15,29 @@
 //! A widget which splits an area in two, with a settable ratio, and optional draggable resizing.
 
 use crate::debug_state::DebugState;
-use crate::kurbo::Line;
+use crate::kurbo::{Affine, Line};
 use crate::widget::flex::Axis;
 use crate::widget::prelude::*;
-use crate::{theme, Color, Cursor, Data, Point, Rect, WidgetPod};
+use crate::{theme, Color, Cursor, Data, Lens, Point, Rect, WidgetPod};
 use tracing::{instrument, trace, warn};
 
 /// A container containing two other widgets, splitting the area either horizontally or vertically.
+///
+/// If the split is [`draggable`], the user can move the bar to resize the two
+/// sides, while respecting their [minimum sizes]; double-clicking the bar
+/// restores the initial [`split_point`]. Use [`bind_split_point`] to keep the
+/// split point in your data, so that it survives the widget being rebuilt.
+///
+/// [`draggable`]: Split::draggable
+/// [minimum sizes]: Split::with_min_size
+/// [`split_point`]: Split::split_point
+/// [`bind_split_point`]: Split::bind_split_point
 pub struct Split<T> {
     split_axis: Axis,
+    split_point_initial: f64,
     split_point_chosen: f64,
     split_point_effective: f64,
+    split_point_lens: Option<Box<dyn SplitPointLens<T>>>,
     min_size: (f64, f64), // Integers only
     bar_size: f64,        // Integers only
     min_bar_area: f64,    // Integers only
@@ -40,10 +52,30 @@ pub struct Split<T> {
     /// bar was clicked. This is used to ensure a click without mouse move is a no-op,
     /// instead of re-centering the bar on the mouse.
     click_offset: f64,
+    /// The columns are laid out right-to-left, with the first child on the right.
+    /// The bar is computed as if they weren't, and mouse positions are mirrored
+    /// to match.
+    mirrored: bool,
     child1: WidgetPod<T, Box<dyn Widget<T>>>,
     child2: WidgetPod<T, Box<dyn Widget<T>>>,
 }
 
+/// An object-safe version of `Lens<T, f64>`.
+trait SplitPointLens<T> {
+    fn get(&self, data: &T) -> f64;
+    fn put(&self, data: &mut T, split_point: f64);
+}
+
+impl<T, L: Lens<T, f64>> SplitPointLens<T> for L {
+    fn get(&self, data: &T) -> f64 {
+        self.with(data, |split_point| *split_point)
+    }
+
+    fn put(&self, data: &mut T, split_point: f64) {
+        self.with_mut(data, |old| *old = split_point)
+    }
+}
+
 impl<T> Split<T> {
     /// Create a new split panel, with the specified axis being split in two.
     ///
@@ -56,8 +88,10 @@ impl<T> Split<T> {
     ) -> Self {
         Split {
             split_axis,
+            split_point_initial: 0.5,
             split_point_chosen: 0.5,
             split_point_effective: 0.5,
+            split_point_lens: None,
             min_size: (0.0, 0.0),
             bar_size: 6.0,
             min_bar_area: 6.0,
@@ -65,6 +99,7 @@ impl<T> Split<T> {
             draggable: false,
             is_bar_hover: false,
             click_offset: 0.0,
+            mirrored: false,
             child1: WidgetPod::new(child1).boxed(),
             child2: WidgetPod::new(child2).boxed(),
         }
@@ -86,26 +121,51 @@ impl<T> Split<T> {
     ///
     /// The value must be between `0.0` and `1.0`, inclusive.
     /// The default split point is `0.5`.
+    ///
+    /// This is also the split point that double-clicking the bar returns to.
     pub fn split_point(mut self, split_point: f64) -> Self {
         assert!(
             (0.0..=1.0).contains(&split_point),
             "split_point must be in the range [0.0-1.0]!"
         );
+        self.split_point_initial = split_point;
         self.split_point_chosen = split_point;
         self
     }
 
+    /// Builder-style method to bind the split point to a field of the data.
+    ///
+    /// The split point is read from the data when the widget is added and
+    /// whenever the data changes, and it is written back when the user drags
+    /// the bar. Like [`split_point`], the value is a fraction of the split axis.
+    ///
+    /// [`split_point`]: Split::split_point
+    pub fn bind_split_point(mut self, lens: impl Lens<T, f64> + 'static) -> Self {
+        self.split_point_lens = Some(Box::new(lens));
+        self
+    }
+
     /// Builder-style method to set the minimum size for both sides of the split axis.
     ///
-    /// The value must be greater than or equal to `0.0`.
-    /// The value will be rounded up to the nearest integer.
-    pub fn min_size(mut self, first: f64, second: f64) -> Self {
+    /// Dragging the bar will not make either side smaller than its minimum.
+    /// If the widget is too small to fit both minimums, the available space is
+    /// shared in proportion to them.
+    ///
+    /// The values must be greater than or equal to `0.0`.
+    /// The values will be rounded up to the nearest integer.
+    pub fn with_min_size(mut self, first: f64, second: f64) -> Self {
         assert!(first >= 0.0);
         assert!(second >= 0.0);
         self.min_size = (first.ceil(), second.ceil());
         self
     }
 
+    /// Builder-style method to set the minimum size for both sides of the split axis.
+    #[deprecated(since = "0.8.0", note = "use with_min_size instead")]
+    pub fn min_size(self, first: f64, second: f64) -> Self {
+        self.with_min_size(first, second)
+    }
+
     /// Builder-style method to set the size of the splitter bar.
     ///
     /// The value must be positive or zero.
@@ -125,6 +185,7 @@ impl<T> Split<T> {
     ///
     /// This can be useful when you want to use a very narrow visual splitter bar,
     /// but don't want to sacrifice user experience by making it hard to click on.
+    /// The extra area is taken from both sides equally.
     ///
     /// The value must be positive or zero.
     /// The value will be rounded up to the nearest integer.
@@ -207,28 +268,72 @@ impl<T> Split<T> {
         }
     }
 
+    /// Returns the position of the mouse in the coordinates the bar is computed in,
+    /// which are mirrored in a right-to-left layout.
+    fn bar_space_pos(&self, size: Size, mouse_pos: Point) -> Point {
+        if self.mirrored {
+            Point::new(size.width - mouse_pos.x, mouse_pos.y)
+        } else {
+            mouse_pos
+        }
+    }
+
     /// Returns the minimum and maximum split coordinate of the provided size.
     fn split_side_limits(&self, size: Size) -> (f64, f64) {
         let split_axis_size = self.split_axis.major(size);
+        let (min_first, min_second) = self.min_size;
+
+        if min_first + min_second > split_axis_size {
+            // Both sides shrink in proportion to their minimum size.
+            let limit = (split_axis_size.max(0.0) * min_first / (min_first + min_second)).floor();
+            return (limit, limit);
+        }
 
-        let (mut min_limit, min_second) = self.min_size;
-        let mut max_limit = (split_axis_size - min_second).max(0.0);
+        (min_first, split_axis_size - min_second)
+    }
 
-        if min_limit > max_limit {
-            min_limit = 0.5 * (min_limit + max_limit);
-            max_limit = min_limit;
+    /// Write the chosen split point to the data, if it is bound.
+    fn store_split_point(&self, data: &mut T) {
+        if let Some(lens) = &self.split_point_lens {
+            if lens.get(data) != self.split_point_chosen {
+                lens.put(data, self.split_point_chosen);
+            }
         }
+    }
 
-        (min_limit, max_limit)
+    /// Read the chosen split point from the data, if it is bound.
+    ///
+    /// Returns `true` if the split point has changed.
+    fn load_split_point(&mut self, data: &T) -> bool {
+        let split_point = match &self.split_point_lens {
+            Some(lens) => lens.get(data).clamp(0.0, 1.0),
+            None => return false,
+        };
+        if split_point != self.split_point_chosen {
+            self.split_point_chosen = split_point;
+            true
+        } else {
+            false
+        }
     }
 
-    /// Set a new chosen split point.
+    /// Set a new chosen split point, given the position of the bar center.
+    ///
+    /// This uses the same reduced size as `layout`, so the minimum sizes are
+    /// respected exactly.
     fn update_split_point(&mut self, size: Size, mouse_pos: Point) {
-        let (min_limit, max_limit) = self.split_side_limits(size);
-        self.split_point_chosen = match self.split_axis {
-            Axis::Horizontal => mouse_pos.x.clamp(min_limit, max_limit) / size.width,
-            Axis::Vertical => mouse_pos.y.clamp(min_limit, max_limit) / size.height,
+        let bar_area = self.bar_area();
+        let reduced_size = Size::new(
+            (size.width - bar_area).max(0.),
+            (size.height - bar_area).max(0.),
+        );
+        let reduced_axis_size = self.split_axis.major(reduced_size);
+        if reduced_axis_size <= f64::EPSILON {
+            return;
         }
+        let (min_limit, max_limit) = self.split_side_limits(reduced_size);
+        let edge1 = self.split_axis.major_pos(mouse_pos) - bar_area / 2.0;
+        self.split_point_chosen = edge1.clamp(min_limit, max_limit) / reduced_axis_size;
     }
 
     /// Returns the color of the splitter bar.
@@ -311,14 +416,28 @@ impl<T: Data> Widget<T> for Split<T> {
         }
         if self.draggable {
             match event {
+                Event::MouseDown(mouse)
+                    if mouse.button.is_left()
+                        && mouse.count == 2
+                        && self.bar_hit_test(
+                            ctx.size(),
+                            self.bar_space_pos(ctx.size(), mouse.pos),
+                        ) =>
+                {
+                    ctx.set_handled();
+                    self.split_point_chosen = self.split_point_initial;
+                    self.store_split_point(data);
+                    ctx.request_layout();
+                }
                 Event::MouseDown(mouse) => {
-                    if mouse.button.is_left() && self.bar_hit_test(ctx.size(), mouse.pos) {
+                    let pos = self.bar_space_pos(ctx.size(), mouse.pos);
+                    if mouse.button.is_left() && self.bar_hit_test(ctx.size(), pos) {
                         ctx.set_handled();
                         ctx.set_active(true);
                         // Save the delta between the mouse click position and the split point
                         self.click_offset = match self.split_axis {
-                            Axis::Horizontal => mouse.pos.x,
-                            Axis::Vertical => mouse.pos.y,
+                            Axis::Horizontal => pos.x,
+                            Axis::Vertical => pos.y,
                         } - self.bar_position(ctx.size());
                         // If not already hovering, force and change cursor appropriately
                         if !self.is_bar_hover {
@@ -331,34 +450,32 @@ impl<T: Data> Widget<T> for Split<T> {
                     }
                 }
                 Event::MouseUp(mouse) => {
+                    let pos = self.bar_space_pos(ctx.size(), mouse.pos);
                     if mouse.button.is_left() && ctx.is_active() {
                         ctx.set_handled();
                         ctx.set_active(false);
                         // Dependending on where the mouse cursor is when the button is released,
                         // the cursor might or might not need to be changed
-                        self.is_bar_hover =
-                            ctx.is_hot() && self.bar_hit_test(ctx.size(), mouse.pos);
+                        self.is_bar_hover = ctx.is_hot() && self.bar_hit_test(ctx.size(), pos);
                         if !self.is_bar_hover {
                             ctx.clear_cursor()
                         }
                     }
                 }
                 Event::MouseMove(mouse) => {
+                    let pos = self.bar_space_pos(ctx.size(), mouse.pos);
                     if ctx.is_active() {
                         // If active, assume always hover/hot
                         let effective_pos = match self.split_axis {
-                            Axis::Horizontal => {
-                                Point::new(mouse.pos.x - self.click_offset, mouse.pos.y)
-                            }
-                            Axis::Vertical => {
-                                Point::new(mouse.pos.x, mouse.pos.y - self.click_offset)
-                            }
+                            Axis::Horizontal => Point::new(pos.x - self.click_offset, pos.y),
+                            Axis::Vertical => Point::new(pos.x, pos.y - self.click_offset),
                         };
                         self.update_split_point(ctx.size(), effective_pos);
+                        self.store_split_point(data);
                         ctx.request_layout();
                     } else {
                         // If not active, set cursor when hovering state changes
-                        let hover = ctx.is_hot() && self.bar_hit_test(ctx.size(), mouse.pos);
+                        let hover = ctx.is_hot() && self.bar_hit_test(ctx.size(), pos);
                         if hover != self.is_bar_hover {
                             self.is_bar_hover = hover;
                             if hover {
@@ -385,12 +502,18 @@ impl<T: Data> Widget<T> for Split<T> {
 
     #[instrument(name = "Split", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.load_split_point(data);
+        }
         self.child1.lifecycle(ctx, event, data, env);
         self.child2.lifecycle(ctx, event, data, env);
     }
 
     #[instrument(name = "Split", level = "trace", skip(self, ctx, _old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if self.load_split_point(data) {
+            ctx.request_layout();
+        }
         self.child1.update(ctx, data, env);
         self.child2.update(ctx, data, env);
     }
@@ -470,8 +593,15 @@ impl<T: Data> Widget<T> for Split<T> {
 
         // Top-left align for both children, out of laziness.
         // Reduce our unsplit direction to the larger of the two widgets
-        let child1_pos = Point::ORIGIN;
+        self.mirrored =
+            self.split_axis == Axis::Horizontal && env.get(theme::LAYOUT_DIRECTION).is_rtl();
+        let mut child1_pos = Point::ORIGIN;
         let child2_pos = match self.split_axis {
+            Axis::Horizontal if self.mirrored => {
+                my_size.height = child1_size.height.max(child2_size.height);
+                child1_pos.x = my_size.width - child1_size.width;
+                Point::new(child1_pos.x - bar_area - child2_size.width, 0.0)
+            }
             Axis::Horizontal => {
                 my_size.height = child1_size.height.max(child2_size.height);
                 Point::new(child1_size.width + bar_area, 0.0)
@@ -494,11 +624,17 @@ impl<T: Data> Widget<T> for Split<T> {
 
     #[instrument(name = "Split", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        if self.solid {
-            self.paint_solid_bar(ctx, env);
-        } else {
-            self.paint_stroked_bar(ctx, env);
-        }
+        ctx.with_save(|ctx| {
+            if self.mirrored {
+                let width = ctx.size().width;
+                ctx.transform(Affine::new([-1.0, 0.0, 0.0, 1.0, width, 0.0]));
+            }
+            if self.solid {
+                self.paint_solid_bar(ctx, env);
+            } else {
+                self.paint_stroked_bar(ctx, env);
+            }
+        });
         self.child1.paint(ctx, data, env);
         self.child2.paint(ctx, data, env);
     }
@@ -514,3 +650,103 @@ impl<T: Data> Widget<T> for Split<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::tests::move_mouse;
+    use crate::widget::{LayoutDirection, SizedBox};
+    use crate::{lens, MouseButton, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    fn mouse(x: f64, count: u8) -> MouseEvent {
+        let mut mouse = move_mouse((x, 10.));
+        mouse.button = MouseButton::Left;
+        mouse.buttons = mouse.buttons.with(MouseButton::Left);
+        mouse.count = count;
+        mouse
+    }
+
+    #[test]
+    fn bound_split_point() {
+        let [id1, id2] = widget_ids();
+        let split = Split::columns(
+            SizedBox::empty().with_id(id1),
+            SizedBox::empty().with_id(id2),
+        )
+        .split_point(0.75)
+        .draggable(true)
+        .bind_split_point(lens::Identity);
+
+        Harness::create_simple(0.25, split, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // The bar area is 6 wide, so 394 is split.
+            assert_eq!(harness.get_state(id1).layout_rect().width(), 98.);
+
+            // Drag the bar.
+            harness.event(Event::MouseDown(mouse(101., 1)));
+            harness.event(Event::MouseMove(mouse(200., 1)));
+            harness.event(Event::MouseUp(mouse(200., 1)));
+            assert_eq!(*harness.data(), 0.5);
+            harness.just_layout();
+            assert_eq!(harness.get_state(id1).layout_rect().width(), 197.);
+
+            // Double-clicking restores the initial split point.
+            harness.event(Event::MouseDown(mouse(200., 2)));
+            assert_eq!(*harness.data(), 0.75);
+            harness.just_layout();
+            assert_eq!(harness.get_state(id1).layout_rect().width(), 295.);
+        });
+    }
+
+    #[test]
+    fn min_size() {
+        let [id1, id2] = widget_ids();
+        let split = Split::columns(
+            SizedBox::empty().with_id(id1),
+            SizedBox::empty().with_id(id2),
+        )
+        .with_min_size(200., 100.)
+        .bind_split_point(lens::Identity);
+
+        Harness::create_simple(0.0, split.fix_width(156.).center(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // There is only room for half of the minimums, so both sides get half.
+            assert_eq!(harness.get_state(id1).layout_rect().width(), 100.);
+            assert_eq!(harness.get_state(id2).layout_rect().width(), 50.);
+        });
+    }
+
+    #[test]
+    fn rtl_columns_are_mirrored() {
+        let [id1, id2] = widget_ids();
+        let split = Split::columns(
+            SizedBox::empty().with_id(id1),
+            SizedBox::empty().with_id(id2),
+        )
+        .split_point(0.25)
+        .draggable(true)
+        .bind_split_point(lens::Identity)
+        .env_scope(|env, _| env.set(theme::LAYOUT_DIRECTION, LayoutDirection::Rtl));
+
+        Harness::create_simple(0.25, split, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // The first child is on the right.
+            assert_eq!(harness.get_state(id1).layout_rect().x0, 302.);
+            assert_eq!(harness.get_state(id2).layout_rect().x1, 296.);
+
+            // Dragging the bar to the left grows the first child.
+            harness.event(Event::MouseDown(mouse(299., 1)));
+            harness.event(Event::MouseMove(mouse(200., 1)));
+            harness.event(Event::MouseUp(mouse(200., 1)));
+            assert_eq!(*harness.data(), 0.5);
+            harness.just_layout();
+            assert_eq!(harness.get_state(id1).layout_rect().width(), 197.);
+        });
+    }
+}
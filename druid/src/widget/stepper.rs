@@ -14,7 +14,7 @@
 
 //! A stepper widget.
 
-use std::f64::EPSILON;
+use std::fmt::Display;
 use std::time::Duration;
 use tracing::{instrument, trace};
 
@@ -22,43 +22,147 @@ use crate::debug_state::DebugState;
 use crate::kurbo::BezPath;
 use crate::piet::{LinearGradient, RenderContext, UnitPoint};
 use crate::widget::prelude::*;
-use crate::{theme, Point, Rect, TimerToken};
+use crate::{theme, KbKey, Point, Rect, TimerToken};
 
 // Delay until stepper starts automatically changing valued when one of the button is held down.
 const STEPPER_REPEAT_DELAY: Duration = Duration::from_millis(500);
 // Delay between value changes when one of the button is held down.
 const STEPPER_REPEAT: Duration = Duration::from_millis(200);
+// The shortest delay between value changes, that the repetition speeds up to.
+const STEPPER_REPEAT_MIN: Duration = Duration::from_millis(40);
+
+/// A number that a [`Stepper`] can step through.
+///
+/// This is implemented for the primitive integer and floating point types.
+pub trait StepperValue: Data + Copy + PartialOrd + Display {
+    /// The smallest value of the type, the default minimum of a stepper.
+    const MIN: Self;
+    /// The largest value of the type, the default maximum of a stepper.
+    const MAX: Self;
+    /// The default step, one.
+    const ONE: Self;
+
+    /// Add `step`, returning `None` if the sum overflows.
+    fn checked_add(self, step: Self) -> Option<Self>;
+
+    /// Subtract `step`, returning `None` if the difference overflows.
+    fn checked_sub(self, step: Self) -> Option<Self>;
+
+    /// Returns `true` if `self` and `other` differ by no more than rounding errors.
+    ///
+    /// This keeps floating point values that were stepped to a bound a tiny
+    /// bit off from stopping short of it.
+    fn approx_eq(self, other: Self) -> bool {
+        self == other
+    }
+}
+
+macro_rules! impl_stepper_value_int {
+    ($($ty:ty),*) => {
+        $(
+            impl StepperValue for $ty {
+                const MIN: Self = <$ty>::MIN;
+                const MAX: Self = <$ty>::MAX;
+                const ONE: Self = 1;
+
+                fn checked_add(self, step: Self) -> Option<Self> {
+                    <$ty>::checked_add(self, step)
+                }
+
+                fn checked_sub(self, step: Self) -> Option<Self> {
+                    <$ty>::checked_sub(self, step)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_stepper_value_float {
+    ($($ty:ident),*) => {
+        $(
+            impl StepperValue for $ty {
+                const MIN: Self = std::$ty::MIN;
+                const MAX: Self = std::$ty::MAX;
+                const ONE: Self = 1.0;
+
+                fn checked_add(self, step: Self) -> Option<Self> {
+                    Some(self + step).filter(|sum| sum.is_finite())
+                }
+
+                fn checked_sub(self, step: Self) -> Option<Self> {
+                    Some(self - step).filter(|difference| difference.is_finite())
+                }
+
+                fn approx_eq(self, other: Self) -> bool {
+                    (self - other).abs() <= std::$ty::EPSILON
+                }
+            }
+        )*
+    };
+}
+
+impl_stepper_value_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_stepper_value_float!(f32, f64);
 
 /// A stepper widget for step-wise increasing and decreasing a value.
-pub struct Stepper {
-    max: f64,
-    min: f64,
-    step: f64,
+///
+/// The value can be any [`StepperValue`], such as `f64` (the default), `i64`
+/// or `usize`. It is changed by clicking the buttons, or with the up and down
+/// arrow keys while the stepper is focused. Holding a button down keeps
+/// changing the value, faster and faster.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::Stepper;
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Order {
+///     quantity: usize,
+/// }
+///
+/// fn quantity_stepper() -> impl Widget<Order> {
+///     Stepper::new()
+///         .with_range(1, 99)
+///         .lens(Order::quantity)
+/// }
+/// ```
+pub struct Stepper<T = f64> {
+    max: T,
+    min: T,
+    step: T,
     wrap: bool,
     /// Keeps track of which button is currently triggered.
     increase_active: bool,
     decrease_active: bool,
     timer_id: TimerToken,
+    /// The delay until the next change while a button is held down.
+    repeat_interval: Duration,
 }
 
-impl Stepper {
+impl<T: StepperValue> Stepper<T> {
     /// Create a new `Stepper`.
     pub fn new() -> Self {
         Stepper {
-            max: std::f64::MAX,
-            min: std::f64::MIN,
-            step: 1.0,
+            max: T::MAX,
+            min: T::MIN,
+            step: T::ONE,
             wrap: false,
             increase_active: false,
             decrease_active: false,
             timer_id: TimerToken::INVALID,
+            repeat_interval: STEPPER_REPEAT,
         }
     }
 
-    /// Set the range covered by this slider.
+    /// Set the range covered by this stepper.
+    ///
+    /// The value is clamped to this range, or wraps around at its ends with
+    /// [`with_wraparound`]. The default range is all the values of the type.
     ///
-    /// The default range is `std::f64::MIN..std::f64::MAX`.
-    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+    /// [`with_wraparound`]: Stepper::with_wraparound
+    pub fn with_range(mut self, min: T, max: T) -> Self {
         self.min = min;
         self.max = max;
         self
@@ -66,8 +170,8 @@ impl Stepper {
 
     /// Set the steppers amount by which the value increases or decreases.
     ///
-    /// The default step is `1.0`.
-    pub fn with_step(mut self, step: f64) -> Self {
+    /// The default step is one.
+    pub fn with_step(mut self, step: T) -> Self {
         self.step = step;
         self
     }
@@ -85,40 +189,40 @@ impl Stepper {
         self
     }
 
-    fn increment(&mut self, data: &mut f64) {
-        let next = *data + self.step;
-        let was_greater = *data + EPSILON >= self.max;
-        let is_greater = next + EPSILON > self.max;
-        *data = match (self.wrap, was_greater, is_greater) {
-            (true, true, true) => self.min,
-            (true, false, true) => self.max,
-            (false, _, true) => self.max,
-            _ => next,
+    fn increment(&self, data: &mut T) {
+        let reaches_max = |value: T| value >= self.max || value.approx_eq(self.max);
+        let next = data
+            .checked_add(self.step)
+            .filter(|&next| !reaches_max(next));
+        *data = match next {
+            Some(next) => next,
+            None if self.wrap && reaches_max(*data) => self.min,
+            None => self.max,
         }
     }
 
-    fn decrement(&mut self, data: &mut f64) {
-        let next = *data - self.step;
-        let was_less = *data - EPSILON <= self.min;
-        let is_less = next - EPSILON < self.min;
-        *data = match (self.wrap, was_less, is_less) {
-            (true, true, true) => self.max,
-            (true, false, true) => self.min,
-            (false, _, true) => self.min,
-            _ => next,
+    fn decrement(&self, data: &mut T) {
+        let reaches_min = |value: T| value <= self.min || value.approx_eq(self.min);
+        let next = data
+            .checked_sub(self.step)
+            .filter(|&next| !reaches_min(next));
+        *data = match next {
+            Some(next) => next,
+            None if self.wrap && reaches_min(*data) => self.max,
+            None => self.min,
         }
     }
 }
 
-impl Default for Stepper {
+impl<T: StepperValue> Default for Stepper<T> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl Widget<f64> for Stepper {
+impl<T: StepperValue> Widget<T> for Stepper<T> {
     #[instrument(name = "Stepper", level = "trace", skip(self, ctx, _data, env))]
-    fn paint(&mut self, ctx: &mut PaintCtx, _data: &f64, env: &Env) {
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
         let stroke_width = 2.0;
         let rounded_rect = ctx
             .size()
@@ -130,7 +234,12 @@ impl Widget<f64> for Stepper {
         let width = env.get(theme::BASIC_WIDGET_HEIGHT);
         let button_size = Size::new(width, height / 2.);
 
-        ctx.stroke(rounded_rect, &env.get(theme::BORDER_DARK), stroke_width);
+        let border_color = if ctx.is_focused() && !ctx.is_disabled() {
+            env.get(theme::FOCUS_RING_COLOR)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        ctx.stroke(rounded_rect, &border_color, stroke_width);
         ctx.clip(rounded_rect);
 
         // draw buttons for increase/decrease
@@ -208,7 +317,7 @@ impl Widget<f64> for Stepper {
         &mut self,
         _layout_ctx: &mut LayoutCtx,
         bc: &BoxConstraints,
-        _data: &f64,
+        _data: &T,
         env: &Env,
     ) -> Size {
         let size = bc.constrain(Size::new(
@@ -220,13 +329,14 @@ impl Widget<f64> for Stepper {
     }
 
     #[instrument(name = "Stepper", level = "trace", skip(self, ctx, event, data, env))]
-    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut f64, env: &Env) {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
         let height = env.get(theme::BORDERED_WIDGET_HEIGHT);
 
         match event {
             Event::MouseDown(mouse) => {
                 if !ctx.is_disabled() {
                     ctx.set_active(true);
+                    ctx.request_focus();
 
                     if mouse.pos.y > height / 2. {
                         self.decrease_active = true;
@@ -237,6 +347,7 @@ impl Widget<f64> for Stepper {
                     }
 
                     self.timer_id = ctx.request_timer(STEPPER_REPEAT_DELAY);
+                    self.repeat_interval = STEPPER_REPEAT;
 
                     ctx.request_paint();
                 }
@@ -258,18 +369,30 @@ impl Widget<f64> for Stepper {
                     if self.decrease_active {
                         self.decrement(data);
                     }
-                    self.timer_id = ctx.request_timer(STEPPER_REPEAT);
+                    self.timer_id = ctx.request_timer(self.repeat_interval);
+                    // speed up while the button stays down
+                    self.repeat_interval = (self.repeat_interval * 4 / 5).max(STEPPER_REPEAT_MIN);
                 } else {
                     ctx.set_active(false);
                 }
             }
+            Event::KeyDown(key) if ctx.is_focused() && !ctx.is_disabled() => {
+                match key.key {
+                    KbKey::ArrowUp => self.increment(data),
+                    KbKey::ArrowDown => self.decrement(data),
+                    _ => return,
+                }
+                ctx.set_handled();
+            }
             _ => (),
         }
     }
 
-    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &f64, _env: &Env) {
-        if let LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::DisabledChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
     }
 
@@ -278,13 +401,13 @@ impl Widget<f64> for Stepper {
         level = "trace",
         skip(self, ctx, old_data, data, _env)
     )]
-    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &f64, data: &f64, _env: &Env) {
-        if (*data - old_data).abs() > EPSILON {
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) {
             ctx.request_paint();
         }
     }
 
-    fn debug_state(&self, data: &f64) -> DebugState {
+    fn debug_state(&self, data: &T) -> DebugState {
         DebugState {
             display_name: self.short_type_name().to_string(),
             main_value: data.to_string(),
@@ -292,3 +415,79 @@ impl Widget<f64> for Stepper {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::{KeyEvent, Modifiers, MouseButton, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    fn steps<T: StepperValue>(stepper: &Stepper<T>, mut value: T, up: bool, n: usize) -> Vec<T> {
+        (0..n)
+            .map(|_| {
+                if up {
+                    stepper.increment(&mut value);
+                } else {
+                    stepper.decrement(&mut value);
+                }
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn clamp_and_wrap() {
+        let clamp = Stepper::new().with_range(1, 5).with_step(2);
+        assert_eq!(steps(&clamp, 1i64, true, 3), [3, 5, 5]);
+        assert_eq!(steps(&clamp, 4i64, false, 2), [2, 1]);
+
+        let wrap = Stepper::new().with_range(0, 3).with_wraparound(true);
+        assert_eq!(steps(&wrap, 2usize, true, 3), [3, 0, 1]);
+        assert_eq!(steps(&wrap, 1usize, false, 3), [0, 3, 2]);
+
+        // the bounds of the type
+        assert_eq!(steps(&Stepper::new(), 254u8, true, 2), [255, 255]);
+        let wrap = Stepper::new().with_wraparound(true);
+        assert_eq!(steps(&wrap, 0usize, false, 2), [usize::MAX, usize::MAX - 1]);
+
+        let float = Stepper::new().with_range(0.0, 0.3).with_step(0.1);
+        assert_eq!(
+            steps(&float, 0.1, true, 2)[1],
+            0.3,
+            "no rounding short of max"
+        );
+    }
+
+    #[test]
+    fn arrow_keys() {
+        let stepper = Stepper::new().with_range(0, 10);
+        let id = WidgetId::next();
+        Harness::create_simple(5i32, stepper.with_id(id), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // Clicking the increase button steps and focuses the stepper.
+            let mouse = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse((2.0, 2.0))
+            };
+            harness.event(Event::MouseDown(mouse.clone()));
+            harness.event(Event::MouseUp(mouse));
+            assert_eq!(harness.window().focus, Some(id));
+            assert_eq!(*harness.data(), 6);
+
+            let mut press = |key| {
+                harness.event(Event::KeyDown(KeyEvent::for_test(
+                    Modifiers::default(),
+                    key,
+                )));
+                *harness.data()
+            };
+            assert_eq!(press(KbKey::ArrowUp), 7);
+            assert_eq!(press(KbKey::ArrowDown), 6);
+            assert_eq!(press(KbKey::ArrowLeft), 6);
+        });
+    }
+}
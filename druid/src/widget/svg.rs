@@ -22,23 +22,117 @@ use crate::{
     piet::{self, GradientStop, LineCap, LineJoin, LinearGradient, RadialGradient, StrokeStyle},
     widget::common::FillStrat,
     widget::prelude::*,
-    Affine, Color, Data, Rect, UnitPoint,
+    Affine, ArcStr, Color, Data, KeyOrValue, Rect, UnitPoint,
 };
 
-/// A widget that renders a SVG
-pub struct Svg {
+/// A widget that renders a SVG.
+///
+/// The SVG can be fixed, with [`Svg::new`], or come from the data, with
+/// [`Svg::dynamic`] or [`Svg::from_source`]; it is then redrawn whenever it
+/// changes. The SVG is scaled to its box constraints according to its
+/// [`FillStrat`], which by default keeps its aspect ratio.
+///
+/// The paint of the shapes in the SVG can be replaced by a color from the
+/// [`Env`], with [`with_fill_color`] and [`with_stroke_color`], which is
+/// useful to draw icons in the color of the text around them.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::Svg;
+/// use druid::{theme, ArcStr, Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Document {
+///     icon: ArcStr,
+/// }
+///
+/// fn document_icon() -> impl Widget<Document> {
+///     Svg::from_source(|icon: &ArcStr, _env: &_| icon.clone())
+///         .with_fill_color(theme::TEXT_COLOR)
+///         .fix_size(16.0, 16.0)
+///         .lens(Document::icon)
+/// }
+/// ```
+///
+/// [`with_fill_color`]: Svg::with_fill_color
+/// [`with_stroke_color`]: Svg::with_stroke_color
+pub struct Svg<T> {
+    source: SvgSource<T>,
     svg_data: SvgData,
     fill: FillStrat,
+    tint: Tint<KeyOrValue<Color>>,
 }
 
-impl Svg {
+type SvgDataFn<T> = Box<dyn Fn(&T, &Env) -> SvgData>;
+type SvgSourceFn<T> = Box<dyn Fn(&T, &Env) -> ArcStr>;
+
+/// Where the SVG of an [`Svg`] comes from.
+enum SvgSource<T> {
+    Static,
+    Dynamic(SvgDataFn<T>),
+    Text {
+        f: SvgSourceFn<T>,
+        /// The text that `svg_data` was parsed from.
+        parsed: Option<ArcStr>,
+    },
+}
+
+/// The paint that replaces the fill and the stroke of the shapes in the SVG.
+#[derive(Clone)]
+struct Tint<C> {
+    fill: Option<C>,
+    stroke: Option<C>,
+}
+
+impl<C> Default for Tint<C> {
+    fn default() -> Self {
+        Tint {
+            fill: None,
+            stroke: None,
+        }
+    }
+}
+
+impl<T> Svg<T> {
     /// Create an SVG-drawing widget from SvgData.
     ///
     /// The SVG will scale to fit its box constraints.
     pub fn new(svg_data: SvgData) -> Self {
         Svg {
+            source: SvgSource::Static,
             svg_data,
             fill: FillStrat::default(),
+            tint: Tint::default(),
+        }
+    }
+
+    /// Create an SVG-drawing widget that gets its SvgData from the data.
+    ///
+    /// The closure is called on update, and the SVG is redrawn if the
+    /// `SvgData` it returns is not the [`same`] as before.
+    ///
+    /// [`same`]: Data::same
+    pub fn dynamic(f: impl Fn(&T, &Env) -> SvgData + 'static) -> Self {
+        Svg {
+            source: SvgSource::Dynamic(Box::new(f)),
+            ..Svg::new(SvgData::empty())
+        }
+    }
+
+    /// Create an SVG-drawing widget that parses the SVG source returned by
+    /// the closure.
+    ///
+    /// The closure is called on update, and the source is only parsed again
+    /// when it has changed. If the source is not a valid SVG, the error is
+    /// logged and nothing is drawn.
+    pub fn from_source(f: impl Fn(&T, &Env) -> ArcStr + 'static) -> Self {
+        Svg {
+            source: SvgSource::Text {
+                f: Box::new(f),
+                parsed: None,
+            },
+            ..Svg::new(SvgData::empty())
         }
     }
 
@@ -53,25 +147,80 @@ impl Svg {
         self.fill = newfil;
     }
 
+    /// Builder-style method for filling all shapes of the SVG with `color`,
+    /// instead of with their own paint.
+    ///
+    /// The opacity of the shapes is kept, and shapes that aren't filled stay
+    /// that way.
+    pub fn with_fill_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.tint.fill = Some(color.into());
+        self
+    }
+
+    /// Builder-style method for stroking all shapes of the SVG with `color`,
+    /// instead of with their own paint.
+    ///
+    /// The opacity of the strokes is kept, and shapes without a stroke stay
+    /// that way.
+    pub fn with_stroke_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.tint.stroke = Some(color.into());
+        self
+    }
+
     /// Set the svg data.
+    ///
+    /// If the SVG comes from the data, it is replaced on the next update.
     pub fn set_svg_data(&mut self, svg_data: SvgData) {
         self.svg_data = svg_data;
     }
+
+    /// Get the SVG from the data, returning `true` if it has changed.
+    fn resolve(&mut self, data: &T, env: &Env) -> bool {
+        match &mut self.source {
+            SvgSource::Static => false,
+            SvgSource::Dynamic(f) => {
+                let svg_data = f(data, env);
+                let changed = !svg_data.same(&self.svg_data);
+                self.svg_data = svg_data;
+                changed
+            }
+            SvgSource::Text { f, parsed } => {
+                let text = f(data, env);
+                if matches!(parsed, Some(old) if *old == text) {
+                    return false;
+                }
+                self.svg_data = SvgData::from_str(&text).unwrap_or_else(|err| {
+                    tracing::warn!("failed to parse SVG: {}", err);
+                    SvgData::empty()
+                });
+                *parsed = Some(text);
+                true
+            }
+        }
+    }
 }
 
-impl<T: Data> Widget<T> for Svg {
+impl<T: Data> Widget<T> for Svg<T> {
     #[instrument(name = "Svg", level = "trace", skip(self, _ctx, _event, _data, _env))]
     fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
 
-    #[instrument(name = "Svg", level = "trace", skip(self, _ctx, _event, _data, _env))]
-    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &T, _env: &Env) {}
+    #[instrument(name = "Svg", level = "trace", skip(self, _ctx, event, data, env))]
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.resolve(data, env);
+        }
+    }
 
-    #[instrument(
-        name = "Svg",
-        level = "trace",
-        skip(self, _ctx, _old_data, _data, _env)
-    )]
-    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {}
+    #[instrument(name = "Svg", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if self.resolve(data, env) {
+            ctx.request_layout();
+        }
+        let mut tint = self.tint.fill.iter().chain(&self.tint.stroke);
+        if tint.any(|color| ctx.env_key_changed(color)) {
+            ctx.request_paint();
+        }
+    }
 
     #[instrument(
         name = "Svg",
@@ -88,13 +237,16 @@ impl<T: Data> Widget<T> for Svg {
         bc.debug_check("SVG");
         // preferred size comes from the svg
         let size = self.svg_data.size();
+        if size.is_empty() {
+            return bc.min();
+        }
         let constrained_size = bc.constrain_aspect_ratio(size.height / size.width, size.width);
         trace!("Computed size: {}", constrained_size);
         constrained_size
     }
 
-    #[instrument(name = "Svg", level = "trace", skip(self, ctx, _data, _env))]
-    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, _env: &Env) {
+    #[instrument(name = "Svg", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
         let offset_matrix = self.fill.affine_to_fill(ctx.size(), self.svg_data.size());
 
         let clip_rect = Rect::ZERO.with_size(ctx.size());
@@ -102,7 +254,11 @@ impl<T: Data> Widget<T> for Svg {
         // The SvgData's to_piet function does not clip to the svg's size
         // CairoRenderContext is very like druids but with some extra goodies like clip
         ctx.clip(clip_rect);
-        self.svg_data.to_piet(offset_matrix, ctx);
+        let tint = Tint {
+            fill: self.tint.fill.as_ref().map(|color| color.resolve(env)),
+            stroke: self.tint.stroke.as_ref().map(|color| color.resolve(env)),
+        };
+        self.svg_data.render(offset_matrix, &tint, ctx);
     }
 }
 
@@ -135,7 +291,12 @@ impl SvgData {
 
     /// Convert SvgData into Piet draw instructions
     pub fn to_piet(&self, offset_matrix: Affine, ctx: &mut PaintCtx) {
-        let mut state = SvgRenderer::new(offset_matrix * self.inner_affine());
+        self.render(offset_matrix, &Tint::default(), ctx);
+    }
+
+    /// Draw the svg, replacing the paint of its shapes by `tint`.
+    fn render(&self, offset_matrix: Affine, tint: &Tint<Color>, ctx: &mut PaintCtx) {
+        let mut state = SvgRenderer::new(offset_matrix * self.inner_affine(), tint.clone());
         // I actually made `SvgRenderer` able to handle a stack of `<defs>`, but I'm gonna see if
         // resvg always puts them at the top.
         let root = self.tree.root();
@@ -225,13 +386,15 @@ impl FromStr for SvgData {
 struct SvgRenderer {
     offset_matrix: Affine,
     defs: Defs,
+    tint: Tint<Color>,
 }
 
 impl SvgRenderer {
-    fn new(offset_matrix: Affine) -> Self {
+    fn new(offset_matrix: Affine, tint: Tint<Color>) -> Self {
         Self {
             offset_matrix,
             defs: Defs::new(),
+            tint,
         }
     }
 
@@ -306,7 +469,7 @@ impl SvgRenderer {
 
         match &p.fill {
             Some(fill) => {
-                let brush = self.brush_from_usvg(&fill.paint, fill.opacity);
+                let brush = self.brush_from_usvg(&fill.paint, fill.opacity, &self.tint.fill);
                 if let usvg::FillRule::EvenOdd = fill.rule {
                     ctx.fill_even_odd(path.clone(), &*brush);
                 } else {
@@ -318,7 +481,7 @@ impl SvgRenderer {
 
         match &p.stroke {
             Some(stroke) => {
-                let brush = self.brush_from_usvg(&stroke.paint, stroke.opacity);
+                let brush = self.brush_from_usvg(&stroke.paint, stroke.opacity, &self.tint.stroke);
                 let mut stroke_style = StrokeStyle::new()
                     .line_join(match stroke.linejoin {
                         usvg::LineJoin::Miter => LineJoin::Miter {
@@ -379,7 +542,17 @@ impl SvgRenderer {
             .add_def(g.id.clone(), piet::PaintBrush::Radial(gradient));
     }
 
-    fn brush_from_usvg(&self, paint: &usvg::Paint, opacity: usvg::Opacity) -> Rc<piet::PaintBrush> {
+    fn brush_from_usvg(
+        &self,
+        paint: &usvg::Paint,
+        opacity: usvg::Opacity,
+        tint: &Option<Color>,
+    ) -> Rc<piet::PaintBrush> {
+        if let Some(color) = tint {
+            let (_, _, _, alpha) = color.as_rgba();
+            let color = color.clone().with_alpha(alpha * opacity.value());
+            return Rc::new(piet::PaintBrush::Color(color));
+        }
         match paint {
             usvg::Paint::Color(c) => {
                 // TODO I'm going to assume here that not retaining colors is OK.
@@ -565,4 +738,70 @@ mod tests {
             },
         )
     }
+
+    #[test]
+    fn parse_source_from_data() {
+        use crate::tests::harness::Harness;
+        use crate::WidgetExt;
+
+        let id = WidgetId::next();
+        let wide: ArcStr =
+            "<svg xmlns='http://www.w3.org/2000/svg' width='40' height='20'/>".into();
+        let widget = Svg::from_source(|source: &ArcStr, _| source.clone())
+            .with_id(id)
+            .center();
+
+        Harness::create_simple(wide, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(40., 20.)
+            );
+
+            harness.update_data(|source| *source = "<svg width='".into());
+            harness.just_layout();
+            // the error is logged, and the empty svg is drawn instead
+            assert_eq!(
+                harness.get_state(id).layout_rect().size(),
+                Size::new(20., 20.)
+            );
+            harness.paint();
+        });
+    }
+
+    #[test]
+    fn tint() {
+        use crate::tests::harness::Harness;
+
+        let svg_data = SvgData::from_str(
+            "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 2 2'>
+        <rect width='2' height='1' fill='red'/>
+        <rect y='1' width='2' height='1' fill='red' fill-opacity='0'/>
+    </svg>",
+        )
+        .unwrap();
+
+        let svg_widget = Svg::new(svg_data).with_fill_color(Color::WHITE);
+
+        Harness::create_with_render(
+            (),
+            svg_widget,
+            Size::new(400., 400.),
+            |harness| {
+                harness.send_initial_events();
+                harness.just_layout();
+                harness.paint();
+            },
+            |target| {
+                let raw_pixels = target.into_raw();
+                // the color replaces the paint, and the opacity is kept
+                assert_eq!(raw_pixels[400 * 100 * 4..400 * 100 * 4 + 4], [255; 4]);
+                assert_eq!(
+                    raw_pixels[400 * 300 * 4..400 * 300 * 4 + 4],
+                    [41, 41, 41, 255]
+                );
+            },
+        )
+    }
 }
@@ -20,14 +20,23 @@ use tracing::{instrument, trace};
 use crate::debug_state::DebugState;
 use crate::kurbo::{Circle, Shape};
 use crate::piet::{LinearGradient, RenderContext, UnitPoint};
+use crate::widget::checkbox::is_toggle_key;
 use crate::widget::prelude::*;
 use crate::{theme, ArcStr, Point, TextLayout};
 
 const SWITCH_CHANGE_TIME: f64 = 0.2;
 const SWITCH_PADDING: f64 = 3.;
 const SWITCH_WIDTH_RATIO: f64 = 2.75;
+/// How far the focus ring is drawn outside of the switch.
+const FOCUS_RING_INSET: f64 = 2.0;
 
 /// A switch that toggles a `bool`.
+///
+/// The switch can be focused with the keyboard; while it has focus, it is
+/// drawn with a [`FOCUS_RING_COLOR`] ring, and pressing space or enter toggles
+/// it just like a click.
+///
+/// [`FOCUS_RING_COLOR`]: crate::theme::FOCUS_RING_COLOR
 #[derive(Debug, Clone)]
 pub struct Switch {
     knob_pos: Point,
@@ -104,9 +113,18 @@ impl Widget<bool> for Switch {
             Event::MouseDown(_) => {
                 if !ctx.is_disabled() {
                     ctx.set_active(true);
+                    ctx.request_focus();
                     ctx.request_paint();
                 }
             }
+            Event::KeyDown(key)
+                if ctx.is_focused() && !ctx.is_disabled() && is_toggle_key(&key.key) =>
+            {
+                if !self.knob_dragged {
+                    *data = !*data;
+                }
+                ctx.set_handled();
+            }
             Event::MouseUp(_) => {
                 if !ctx.is_disabled() {
                     if self.knob_dragged {
@@ -176,6 +194,8 @@ impl Widget<bool> for Switch {
                 self.on_text.rebuild_if_needed(ctx.text(), env);
                 self.off_text.rebuild_if_needed(ctx.text(), env);
             }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
             LifeCycle::DisabledChanged(true) if self.knob_dragged => {
                 self.knob_dragged = false;
                 self.animation_in_progress = true;
@@ -227,6 +247,9 @@ impl Widget<bool> for Switch {
         let text_baseline_offset = text_metrics.size.height - text_metrics.first_baseline;
         ctx.set_baseline_offset(text_bottom_padding + text_baseline_offset);
 
+        // Leave room for the focus ring.
+        ctx.set_paint_insets(FOCUS_RING_INSET);
+
         let size = bc.constrain(Size::new(width, height));
         trace!("Computed size: {}", size);
         size
@@ -285,6 +308,13 @@ impl Widget<bool> for Switch {
         );
 
         ctx.stroke(background_rect, &env.get(theme::BORDER_DARK), stroke_width);
+        if ctx.is_focused() && !ctx.is_disabled() {
+            let ring = Size::new(switch_width, switch_height)
+                .to_rect()
+                .inset(FOCUS_RING_INSET - 0.5)
+                .to_rounded_rect(switch_height / 2. + FOCUS_RING_INSET);
+            ctx.stroke(ring, &env.get(theme::FOCUS_RING_COLOR), 1.0);
+        }
         ctx.fill(background_rect, &background_gradient_on_state);
         ctx.fill(background_rect, &background_gradient_off_state);
         ctx.clip(background_rect);
@@ -344,3 +374,40 @@ impl Widget<bool> for Switch {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, FocusOnCommand, REQUEST_FOCUS};
+    use crate::{KbKey, KeyEvent, Modifiers, WidgetExt};
+    use test_log::test;
+
+    #[test]
+    fn toggles_with_keyboard() {
+        let [id] = widget_ids();
+        let switch = Switch::new()
+            .controller(FocusOnCommand)
+            .with_id(id)
+            .center();
+
+        Harness::create_simple(false, switch, |harness| {
+            harness.send_initial_events();
+            harness.submit_command(REQUEST_FOCUS.to(id));
+            assert!(harness.get_state(id).has_focus);
+
+            let space = KbKey::Character(" ".into());
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::default(),
+                space,
+            )));
+            assert!(*harness.data());
+            assert!(harness.window().wants_animation_frame());
+            harness.event(Event::KeyDown(KeyEvent::for_test(
+                Modifiers::default(),
+                KbKey::Enter,
+            )));
+            assert!(!*harness.data());
+        });
+    }
+}
@@ -22,10 +22,17 @@ use std::marker::PhantomData;
 use std::rc::Rc;
 use tracing::{instrument, trace};
 
+#[cfg(feature = "im")]
+use crate::im::Vector;
 use crate::kurbo::{Circle, Line};
 use crate::widget::prelude::*;
-use crate::widget::{Axis, Flex, Label, LabelText, LensScopeTransfer, Painter, Scope, ScopePolicy};
-use crate::{theme, Affine, Data, Insets, Lens, Point, SingleUse, WidgetExt, WidgetPod};
+use crate::widget::{
+    Axis, Controller, Flex, Label, LabelText, Painter, Scope, ScopePolicy, ScopeTransfer,
+};
+use crate::{
+    theme, Affine, Data, HotKey, Insets, KbKey, Lens, Point, RawMods, SingleUse, WidgetExt,
+    WidgetPod,
+};
 
 type TabsScope<TP> = Scope<TabsScopePolicy<TP>, Box<dyn Widget<TabsState<TP>>>>;
 type TabBodyPod<TP> = WidgetPod<<TP as TabsPolicy>::Input, <TP as TabsPolicy>::BodyWidget>;
@@ -103,6 +110,21 @@ pub trait TabsPolicy: Data {
     #[allow(unused_variables)]
     fn close_tab(&self, key: Self::Key, data: &mut Self::Input) {}
 
+    /// Change the data to reflect the user dragging a tab to the (zero-based) position `to`.
+    ///
+    /// Tabs can only be reordered by dragging if this is implemented. Afterwards,
+    /// [`tabs_changed`] must return `true`, and [`tabs`] must return the tabs in their new order.
+    ///
+    /// [`tabs_changed`]: TabsPolicy::tabs_changed
+    /// [`tabs`]: TabsPolicy::tabs
+    #[allow(unused_variables)]
+    fn move_tab(&self, key: Self::Key, to: usize, data: &mut Self::Input) {}
+
+    /// Change the data to reflect the user requesting a new tab, using the button shown with
+    /// [`Tabs::with_new_tab_button`]. The new tab is selected.
+    #[allow(unused_variables)]
+    fn new_tab(&self, data: &mut Self::Input) {}
+
     #[allow(unused_variables)]
     /// Construct an instance of this TabsFromData from its Build type.
     /// The main use case for this is StaticTabs, where the tabs are provided by the app developer up front.
@@ -212,6 +234,260 @@ impl<T: Data> AddTab for StaticTabs<T> {
     }
 }
 
+/// An item in the list of tabs of [`DynamicTabs`].
+#[cfg(feature = "im")]
+pub trait TabModel: Data {
+    /// The identity of the tab. It must be unique within the list.
+    type Key: Hash + Eq + Clone + 'static;
+
+    /// Returns the identity of this tab.
+    fn key(&self) -> Self::Key;
+
+    /// Returns the name shown in the tab's label.
+    fn name(&self) -> String;
+
+    /// Should the user be able to close the tab?
+    ///
+    /// This is asked once, when the tab is created.
+    fn can_close(&self) -> bool {
+        true
+    }
+}
+
+/// A [`TabsPolicy`] that shows a tab for each item in a [`Vector`].
+///
+/// Closing a tab removes its item from the vector, and dragging a tab moves its item. If
+/// [`with_new_tab`] is used, [`Tabs::with_new_tab_button`] shows a button that adds an item.
+///
+/// Each tab's body is built once from the tab's key, and gets the tab's item as its data.
+///
+/// # Examples
+///
+/// ```
+/// use druid::im::Vector;
+/// use druid::widget::{DynamicTabs, Label, TabModel, Tabs, TextBox};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Document {
+///     id: u32,
+///     title: String,
+/// }
+///
+/// impl TabModel for Document {
+///     type Key = u32;
+///
+///     fn key(&self) -> u32 {
+///         self.id
+///     }
+///
+///     fn name(&self) -> String {
+///         self.title.clone()
+///     }
+/// }
+///
+/// fn documents() -> impl Widget<Vector<Document>> {
+///     let policy = DynamicTabs::new(|_id: &u32| TextBox::new().lens(Document::title))
+///         .with_new_tab(|docs: &Vector<Document>| Document {
+///             id: docs.iter().map(|doc| doc.id + 1).max().unwrap_or(0),
+///             title: "Untitled".into(),
+///         });
+///     Tabs::for_policy(policy)
+///         .with_new_tab_button(true)
+///         .with_keyboard_cycling(true)
+/// }
+/// ```
+///
+/// [`with_new_tab`]: DynamicTabs::with_new_tab
+#[cfg(feature = "im")]
+pub struct DynamicTabs<M: TabModel> {
+    make_body: MakeBody<M>,
+    make_tab: Option<MakeTab<M>>,
+}
+
+#[cfg(feature = "im")]
+type MakeBody<M> = Rc<dyn Fn(&<M as TabModel>::Key) -> Box<dyn Widget<M>>>;
+#[cfg(feature = "im")]
+type MakeTab<M> = Rc<dyn Fn(&Vector<M>) -> M>;
+
+#[cfg(feature = "im")]
+impl<M: TabModel> DynamicTabs<M> {
+    /// Create a new policy that builds the body of each tab with `make_body`.
+    pub fn new<W: Widget<M> + 'static>(make_body: impl Fn(&M::Key) -> W + 'static) -> Self {
+        DynamicTabs {
+            make_body: Rc::new(move |key| make_body(key).boxed()),
+            make_tab: None,
+        }
+    }
+
+    /// Builder-style method for setting how new tabs are created, with the button shown
+    /// with [`Tabs::with_new_tab_button`].
+    pub fn with_new_tab(mut self, make_tab: impl Fn(&Vector<M>) -> M + 'static) -> Self {
+        self.make_tab = Some(Rc::new(make_tab));
+        self
+    }
+
+    fn index_of(data: &Vector<M>, key: &M::Key) -> Option<usize> {
+        data.iter().position(|tab| &tab.key() == key)
+    }
+}
+
+#[cfg(feature = "im")]
+impl<M: TabModel> Clone for DynamicTabs<M> {
+    fn clone(&self) -> Self {
+        DynamicTabs {
+            make_body: self.make_body.clone(),
+            make_tab: self.make_tab.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "im")]
+impl<M: TabModel> Data for DynamicTabs<M> {
+    fn same(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.make_body, &other.make_body)
+            && match (&self.make_tab, &other.make_tab) {
+                (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+#[cfg(feature = "im")]
+impl<M: TabModel> TabsPolicy for DynamicTabs<M> {
+    type Key = M::Key;
+    type Input = Vector<M>;
+    type BodyWidget = Box<dyn Widget<Vector<M>>>;
+    type LabelWidget = Label<Vector<M>>;
+    type Build = ();
+
+    fn tabs_changed(&self, old_data: &Vector<M>, data: &Vector<M>) -> bool {
+        old_data.len() != data.len()
+            || old_data
+                .iter()
+                .zip(data.iter())
+                .any(|(old, new)| !old.same(new) && old.key() != new.key())
+    }
+
+    fn tabs(&self, data: &Vector<M>) -> Vec<M::Key> {
+        data.iter().map(M::key).collect()
+    }
+
+    fn tab_info(&self, key: M::Key, data: &Vector<M>) -> TabInfo<Vector<M>> {
+        let can_close = Self::index_of(data, &key)
+            .map(|idx| data[idx].can_close())
+            .unwrap_or(true);
+        let name = move |data: &Vector<M>, _: &Env| {
+            Self::index_of(data, &key).map_or_else(String::new, |idx| data[idx].name())
+        };
+        TabInfo::new(name, can_close)
+    }
+
+    fn tab_body(&self, key: M::Key, _data: &Vector<M>) -> Self::BodyWidget {
+        let child = WidgetPod::new((self.make_body)(&key));
+        Box::new(TabModelBody { key, child })
+    }
+
+    fn tab_label(
+        &self,
+        _key: M::Key,
+        info: TabInfo<Vector<M>>,
+        _data: &Vector<M>,
+    ) -> Self::LabelWidget {
+        Self::default_make_label(info)
+    }
+
+    fn close_tab(&self, key: M::Key, data: &mut Vector<M>) {
+        if let Some(idx) = Self::index_of(data, &key) {
+            data.remove(idx);
+        }
+    }
+
+    fn move_tab(&self, key: M::Key, to: usize, data: &mut Vector<M>) {
+        if let Some(idx) = Self::index_of(data, &key) {
+            let tab = data.remove(idx);
+            data.insert(to.min(data.len()), tab);
+        }
+    }
+
+    fn new_tab(&self, data: &mut Vector<M>) {
+        if let Some(make_tab) = &self.make_tab {
+            let tab = make_tab(data);
+            data.push_back(tab);
+        }
+    }
+}
+
+/// The body of a tab of [`DynamicTabs`], which gets the tab's item as its data.
+#[cfg(feature = "im")]
+struct TabModelBody<M: TabModel> {
+    key: M::Key,
+    child: WidgetPod<M, Box<dyn Widget<M>>>,
+}
+
+#[cfg(feature = "im")]
+impl<M: TabModel> TabModelBody<M> {
+    // The tab's item may be missing for a moment, before the tab is removed.
+    fn item<'a>(&self, data: &'a Vector<M>) -> Option<&'a M> {
+        DynamicTabs::<M>::index_of(data, &self.key).map(|idx| &data[idx])
+    }
+}
+
+#[cfg(feature = "im")]
+impl<M: TabModel> Widget<Vector<M>> for TabModelBody<M> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Vector<M>, env: &Env) {
+        if let Some(idx) = DynamicTabs::<M>::index_of(data, &self.key) {
+            let mut item = data[idx].clone();
+            self.child.event(ctx, event, &mut item, env);
+            if !item.same(&data[idx]) {
+                data[idx] = item;
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Vector<M>,
+        env: &Env,
+    ) {
+        if let Some(item) = self.item(data) {
+            self.child.lifecycle(ctx, event, item, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &Vector<M>, data: &Vector<M>, env: &Env) {
+        if let Some(item) = self.item(data) {
+            self.child.update(ctx, item, env);
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &Vector<M>,
+        env: &Env,
+    ) -> Size {
+        match self.item(data) {
+            Some(item) => {
+                let size = self.child.layout(ctx, bc, item, env);
+                self.child.set_origin(ctx, item, env, Point::ORIGIN);
+                size
+            }
+            None => bc.min(),
+        }
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Vector<M>, env: &Env) {
+        if let Some(item) = self.item(data) {
+            self.child.paint(ctx, item, env);
+        }
+    }
+}
+
 /// This is the current state of the tabs widget as a whole.
 /// This expands the input data to include a policy that determines how tabs are derived,
 /// and the index of the currently selected tab
@@ -231,6 +507,52 @@ impl<TP: TabsPolicy> TabsState<TP> {
             policy,
         }
     }
+
+    /// Keep the selected tab selected after the tabs changed from those of `old_inner`.
+    /// If it was removed, the tab that took its place is selected, or the last tab if
+    /// there is no such tab.
+    fn reselect(&mut self, old_inner: &TP::Input) {
+        let old = self.policy.tabs(old_inner);
+        let new = self.policy.tabs(&self.inner);
+        self.selected = old
+            .get(self.selected)
+            .and_then(|key| new.iter().position(|k| k == key))
+            .unwrap_or_else(|| self.selected.min(new.len().saturating_sub(1)));
+    }
+
+    /// Select the first tab that was not among the tabs of `old_inner`, if there is one.
+    fn select_added(&mut self, old_inner: &TP::Input) {
+        let old = self.policy.tabs(old_inner);
+        let new = self.policy.tabs(&self.inner);
+        match new.iter().position(|key| !old.contains(key)) {
+            Some(idx) => self.selected = idx,
+            None => self.reselect(old_inner),
+        }
+    }
+}
+
+/// Keeps the input of the tabs in sync with the [`TabsState`], and keeps the selected index
+/// valid when the tabs are changed from outside.
+struct TabsTransfer<TP>(PhantomData<TP>);
+
+impl<TP: TabsPolicy> ScopeTransfer for TabsTransfer<TP> {
+    type In = TP::Input;
+    type State = TabsState<TP>;
+
+    fn read_input(&self, state: &mut TabsState<TP>, input: &TP::Input) {
+        if !state.inner.same(input) {
+            let old = std::mem::replace(&mut state.inner, input.clone());
+            if state.policy.tabs_changed(&old, &state.inner) {
+                state.reselect(&old);
+            }
+        }
+    }
+
+    fn write_back_input(&self, state: &TabsState<TP>, input: &mut TP::Input) {
+        if !state.inner.same(input) {
+            *input = state.inner.clone();
+        }
+    }
 }
 
 /// This widget is the tab bar. It contains widgets that when pressed switch the active tab.
@@ -238,22 +560,80 @@ struct TabBar<TP: TabsPolicy> {
     axis: Axis,
     edge: TabsEdge,
     tabs: Vec<(TP::Key, TabBarPod<TP>)>,
+    new_tab_button: Option<TabBarPod<TP>>,
     hot: Option<TabIndex>,
+    /// The tab that is being dragged.
+    dragging: Option<TP::Key>,
+    /// Set when the tabs were reordered, until their new positions are known.
+    layout_pending: bool,
+    /// Whether the bar takes keyboard focus, for cycling through the tabs.
+    focusable: bool,
     phantom_tp: PhantomData<TP>,
 }
 
 impl<TP: TabsPolicy> TabBar<TP> {
     /// Create a new TabBar widget.
-    fn new(axis: Axis, edge: TabsEdge) -> Self {
+    fn new(axis: Axis, edge: TabsEdge, new_tab_button: bool, focusable: bool) -> Self {
         TabBar {
             axis,
             edge,
             tabs: vec![],
+            new_tab_button: new_tab_button.then(|| WidgetPod::new(Self::make_new_tab_button())),
             hot: None,
+            dragging: None,
+            layout_pending: false,
+            focusable,
             phantom_tp: Default::default(),
         }
     }
 
+    fn make_new_tab_button() -> Box<dyn Widget<TabsState<TP>>> {
+        let button = Painter::new(|ctx, _, env| {
+            let bounds = ctx.size().to_rect().inset(-5.);
+            let color = &env.get(if ctx.is_hot() {
+                theme::FOREGROUND_LIGHT
+            } else {
+                theme::BORDER_LIGHT
+            });
+            let center = bounds.center();
+            ctx.render_ctx.stroke(
+                Line::new((bounds.x0, center.y), (bounds.x1, center.y)),
+                color,
+                2.,
+            );
+            ctx.render_ctx.stroke(
+                Line::new((center.x, bounds.y0), (center.x, bounds.y1)),
+                color,
+                2.,
+            );
+        })
+        .fix_size(20., 20.)
+        .padding(Insets::uniform_xy(5., 3.))
+        .on_click(|_ctx, data: &mut TabsState<TP>, _env| {
+            let old = data.inner.clone();
+            data.policy.new_tab(&mut data.inner);
+            data.select_added(&old);
+        });
+        Box::new(button)
+    }
+
+    /// The index the tab at `from` should be moved to while it is dragged to `pos`.
+    ///
+    /// A tab is moved past another one once the pointer crosses the middle of that tab, so
+    /// that tabs of different sizes don't swap back and forth.
+    fn drag_target(&self, from: TabIndex, pos: Point) -> Option<TabIndex> {
+        let major = self.axis.major_pos(pos);
+        let middle = |idx: TabIndex| {
+            let (near, far) = self.axis.major_span(self.tabs[idx].1.layout_rect());
+            (near + far) / 2.
+        };
+        (0..from).find(|&idx| major < middle(idx)).or_else(|| {
+            (from + 1..self.tabs.len())
+                .rev()
+                .find(|&idx| major > middle(idx))
+        })
+    }
+
     fn find_idx(&self, pos: Point) -> Option<TabIndex> {
         let major_pix = self.axis.major_pos(pos);
         let axis = self.axis;
@@ -324,7 +704,9 @@ impl<TP: TabsPolicy> TabBar<TP> {
                     .with_child(label)
                     .with_child(close_button.on_click(
                         move |_ctx, data: &mut TabsState<TP>, _env| {
+                            let old = data.inner.clone();
                             data.policy.close_tab(key.clone(), &mut data.inner);
+                            data.reselect(&old);
                         },
                     ));
                 WidgetPod::new(Box::new(row))
@@ -342,6 +724,11 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
             Event::MouseDown(e) => {
                 if let Some(idx) = self.find_idx(e.pos) {
                     data.selected = idx;
+                    self.dragging = Some(self.tabs[idx].0.clone());
+                    ctx.set_active(true);
+                    if self.focusable {
+                        ctx.request_focus();
+                    }
                 }
             }
             Event::MouseMove(e) => {
@@ -354,6 +741,23 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
                     self.hot = new_hot;
                     ctx.request_paint();
                 }
+
+                let key = self.dragging.as_ref().filter(|_| ctx.is_active());
+                let from = key.and_then(|key| self.tabs.iter().position(|(k, _)| k == key));
+                if let (Some(from), false) = (from, self.layout_pending) {
+                    if let Some(to) = self.drag_target(from, e.pos) {
+                        trace!("Moving tab {} to {}", from, to);
+                        let old = data.inner.clone();
+                        let key = self.tabs[from].0.clone();
+                        data.policy.move_tab(key, to, &mut data.inner);
+                        data.reselect(&old);
+                        self.layout_pending = data.policy.tabs_changed(&old, &data.inner);
+                    }
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                self.dragging = None;
             }
             _ => {}
         }
@@ -361,6 +765,9 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
         for (_, tab) in self.tabs.iter_mut() {
             tab.event(ctx, event, data, env);
         }
+        if let Some(button) = &mut self.new_tab_button {
+            button.event(ctx, event, data, env);
+        }
     }
 
     #[instrument(name = "TabBar", level = "trace", skip(self, ctx, event, data, env))]
@@ -371,14 +778,22 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
         data: &TabsState<TP>,
         env: &Env,
     ) {
-        if let LifeCycle::WidgetAdded = event {
-            self.ensure_tabs(data);
-            ctx.children_changed();
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.ensure_tabs(data);
+                ctx.children_changed();
+            }
+            LifeCycle::BuildFocusChain if self.focusable => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => {}
         }
 
         for (_, tab) in self.tabs.iter_mut() {
             tab.lifecycle(ctx, event, data, env);
         }
+        if let Some(button) = &mut self.new_tab_button {
+            button.lifecycle(ctx, event, data, env);
+        }
     }
 
     #[instrument(name = "TabBar", level = "trace", skip(self, ctx, old_data, data, env))]
@@ -392,6 +807,9 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
         for (_, tab) in self.tabs.iter_mut() {
             tab.update(ctx, data, env)
         }
+        if let Some(button) = &mut self.new_tab_button {
+            button.update(ctx, data, env);
+        }
 
         if data.policy.tabs_changed(&old_data.inner, &data.inner) {
             self.ensure_tabs(data);
@@ -417,6 +835,14 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
             major += self.axis.major(size);
             minor = minor.max(self.axis.minor(size));
         }
+        if let Some(button) = &mut self.new_tab_button {
+            let size = button.layout(ctx, bc, data, env);
+            let offset = (minor - self.axis.minor(size)).max(0.) / 2.;
+            button.set_origin(ctx, data, env, self.axis.pack(major, offset).into());
+            major += self.axis.major(size);
+            minor = minor.max(self.axis.minor(size));
+        }
+        self.layout_pending = false;
         let wanted = self.axis.pack(major.max(self.axis.major(bc.max())), minor);
         let size = bc.constrain(wanted);
         trace!("Computed size: {}", size);
@@ -442,6 +868,9 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
             ctx.fill(rect, &bg);
 
             tab.paint(ctx, data, env);
+            if idx == data.selected && ctx.has_focus() {
+                ctx.stroke(rect.inset(-1.), &env.get(theme::FOCUS_RING_COLOR), 1.);
+            }
             if idx == data.selected {
                 let (maj_near, maj_far) = self.axis.major_span(rect);
                 let (min_near, min_far) = self.axis.minor_span(rect);
@@ -461,6 +890,44 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
                 )
             }
         }
+        if let Some(button) = &mut self.new_tab_button {
+            button.paint(ctx, data, env);
+        }
+    }
+}
+
+/// Cycles through the tabs with Ctrl+Tab and Ctrl+Shift+Tab.
+///
+/// The tab bar is focused after cycling, as the widget that had focus may now be hidden.
+struct TabsKeyboard {
+    bar: WidgetId,
+}
+
+impl<TP: TabsPolicy, W: Widget<TabsState<TP>>> Controller<TabsState<TP>, W> for TabsKeyboard {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut TabsState<TP>,
+        env: &Env,
+    ) {
+        child.event(ctx, event, data, env);
+        if let Event::KeyDown(key) = event {
+            let len = data.policy.tabs(&data.inner).len();
+            if ctx.is_handled() || len == 0 {
+                return;
+            }
+            if HotKey::new(RawMods::Ctrl, KbKey::Tab).matches(key) {
+                data.selected = (data.selected + 1) % len;
+            } else if HotKey::new(RawMods::CtrlShift, KbKey::Tab).matches(key) {
+                data.selected = (data.selected + len - 1) % len;
+            } else {
+                return;
+            }
+            ctx.set_focus(self.bar);
+            ctx.set_handled();
+        }
     }
 }
 
@@ -648,9 +1115,13 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabsBody<TP> {
         };
 
         if old_data.selected != data.selected {
-            self.transition_state = self
-                .transition
-                .tab_changed(old_data.selected, data.selected);
+            // When tabs were added or removed, the old index may refer to a different tab.
+            self.transition_state = if init.is_some() {
+                None
+            } else {
+                self.transition
+                    .tab_changed(old_data.selected, data.selected)
+            };
             ctx.children_changed();
 
             if self.transition_state.is_some() {
@@ -735,12 +1206,12 @@ impl<TP> TabsScopePolicy<TP> {
 impl<TP: TabsPolicy> ScopePolicy for TabsScopePolicy<TP> {
     type In = TP::Input;
     type State = TabsState<TP>;
-    type Transfer = LensScopeTransfer<tabs_state_derived_lenses::inner<TP>, Self::In, Self::State>;
+    type Transfer = TabsTransfer<TP>;
 
     fn create(self, inner: &Self::In) -> (Self::State, Self::Transfer) {
         (
             TabsState::new(inner.clone(), self.selected, self.tabs_from_data),
-            LensScopeTransfer::new(Self::State::inner),
+            TabsTransfer(PhantomData),
         )
     }
 }
@@ -798,6 +1269,7 @@ impl<T: Data> InitialTab<T> {
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 enum TabsContent<TP: TabsPolicy> {
     Building {
         tabs: TP::Build,
@@ -841,6 +1313,8 @@ pub struct Tabs<TP: TabsPolicy> {
     axis: Axis,
     edge: TabsEdge,
     transition: TabsTransition,
+    new_tab_button: bool,
+    keyboard_cycling: bool,
     content: TabsContent<TP>,
 }
 
@@ -864,6 +1338,8 @@ impl<TP: TabsPolicy> Tabs<TP> {
             axis: Axis::Horizontal,
             edge: Default::default(),
             transition: Default::default(),
+            new_tab_button: false,
+            keyboard_cycling: false,
             content,
         }
     }
@@ -905,6 +1381,22 @@ impl<TP: TabsPolicy> Tabs<TP> {
         self
     }
 
+    /// Show a "+" button after the tabs, which calls [`TabsPolicy::new_tab`].
+    pub fn with_new_tab_button(mut self, show: bool) -> Self {
+        self.new_tab_button = show;
+        self
+    }
+
+    /// Allow cycling through the tabs with Ctrl+Tab and Ctrl+Shift+Tab.
+    ///
+    /// This works while a widget within the tabs has keyboard focus. The tab bar can also be
+    /// focused, either by clicking a tab or with the Tab key, and it keeps the focus after
+    /// cycling, since the widget that had focus may then be hidden.
+    pub fn with_keyboard_cycling(mut self, cycle: bool) -> Self {
+        self.keyboard_cycling = cycle;
+        self
+    }
+
     /// Available when the policy implements AddTab - e.g StaticTabs.
     /// Return this Tabs widget with the named tab added.
     pub fn with_tab(
@@ -972,7 +1464,14 @@ impl<TP: TabsPolicy> Tabs<TP> {
     }
 
     fn make_scope(&self, tabs_from_data: TP, idx: TabIndex) -> WidgetPod<TP::Input, TabsScope<TP>> {
-        let tabs_bar = TabBar::new(self.axis, self.edge);
+        let bar_id = WidgetId::next();
+        let tabs_bar = TabBar::new(
+            self.axis,
+            self.edge,
+            self.new_tab_button,
+            self.keyboard_cycling,
+        )
+        .with_id(bar_id);
         let tabs_body = TabsBody::new(self.axis, self.transition)
             .padding(5.)
             .border(theme::BORDER_DARK, 0.5);
@@ -986,9 +1485,14 @@ impl<TP: TabsPolicy> Tabs<TP> {
             layout.add_flex_child(tabs_body, 1.);
         };
 
+        let layout: Box<dyn Widget<TabsState<TP>>> = if self.keyboard_cycling {
+            Box::new(layout.controller(TabsKeyboard { bar: bar_id }))
+        } else {
+            Box::new(layout)
+        };
         WidgetPod::new(Scope::new(
             TabsScopePolicy::new(tabs_from_data, idx),
-            Box::new(layout),
+            layout,
         ))
     }
 }
@@ -1064,3 +1568,135 @@ impl<TP: TabsPolicy> Widget<TP::Input> for Tabs<TP> {
         }
     }
 }
+
+#[cfg(all(test, feature = "im"))]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{FocusOnCommand, REQUEST_FOCUS};
+    use crate::tests::move_mouse;
+    use crate::{KeyEvent, Modifiers, MouseButton, MouseEvent};
+    use std::cell::Cell;
+    use test_log::test;
+
+    #[derive(Clone, Data)]
+    struct Doc(u16);
+
+    impl TabModel for Doc {
+        type Key = u16;
+
+        fn key(&self) -> u16 {
+            self.0
+        }
+
+        fn name(&self) -> String {
+            format!("Document {}", self.0)
+        }
+    }
+
+    fn docs(ids: &[u16]) -> Vector<Doc> {
+        ids.iter().copied().map(Doc).collect()
+    }
+
+    fn ids(docs: &Vector<Doc>) -> Vec<u16> {
+        docs.iter().map(|doc| doc.0).collect()
+    }
+
+    /// Tabs whose bodies record the key of the last one painted in `painted`.
+    fn dynamic_tabs(painted: Rc<Cell<u16>>) -> DynamicTabs<Doc> {
+        DynamicTabs::new(move |key: &u16| {
+            let painted = painted.clone();
+            Painter::new(move |_, doc: &Doc, _| painted.set(doc.0))
+                .controller(FocusOnCommand)
+                .with_id(WidgetId::reserved(*key))
+        })
+        .with_new_tab(|docs: &Vector<Doc>| Doc(docs.iter().map(|doc| doc.0 + 1).max().unwrap_or(1)))
+    }
+
+    #[test]
+    fn selection_stays_valid() {
+        let policy = dynamic_tabs(Default::default());
+        let mut state = TabsState::new(docs(&[1, 2, 3]), 1, policy.clone());
+        let transfer = TabsTransfer(PhantomData);
+
+        // Removing a tab before the selected one keeps the same tab selected.
+        transfer.read_input(&mut state, &docs(&[2, 3]));
+        assert_eq!(state.selected, 0);
+
+        // Removing the selected tab selects the one that took its place...
+        transfer.read_input(&mut state, &docs(&[3, 4]));
+        assert_eq!(state.selected, 0);
+
+        // ...or the last one, when the last tab is removed.
+        state.selected = 1;
+        transfer.read_input(&mut state, &docs(&[3]));
+        assert_eq!(state.selected, 0);
+        transfer.read_input(&mut state, &docs(&[]));
+        assert_eq!(state.selected, 0);
+
+        // A tab created with the new tab button is selected.
+        let mut state = TabsState::new(docs(&[1, 2]), 0, policy);
+        let old = state.inner.clone();
+        state.policy.new_tab(&mut state.inner);
+        state.select_added(&old);
+        assert_eq!(ids(&state.inner), [1, 2, 3]);
+        assert_eq!(state.selected, 2);
+    }
+
+    #[test]
+    fn dragging_reorders_tabs() {
+        let painted = Rc::new(Cell::new(0));
+        let widget = Tabs::for_policy(dynamic_tabs(painted.clone()))
+            .with_transition(TabsTransition::Instant);
+
+        Harness::create_simple(docs(&[1, 2, 3]), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            let press = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse((5., 10.))
+            };
+            harness.event(Event::MouseDown(press.clone()));
+            harness.event(Event::MouseMove(move_mouse((399., 10.))));
+            harness.event(Event::MouseUp(MouseEvent {
+                pos: (399., 10.).into(),
+                window_pos: (399., 10.).into(),
+                ..press
+            }));
+            assert_eq!(ids(harness.data()), [2, 3, 1]);
+
+            // The dragged tab is still selected.
+            harness.paint();
+            assert_eq!(painted.get(), 1);
+        });
+    }
+
+    #[test]
+    fn keyboard_cycling() {
+        let painted = Rc::new(Cell::new(0));
+        let widget = Tabs::for_policy(dynamic_tabs(painted.clone()))
+            .with_transition(TabsTransition::Instant)
+            .with_keyboard_cycling(true);
+        let key_down = |mods| Event::KeyDown(KeyEvent::for_test(mods, KbKey::Tab));
+
+        Harness::create_simple(docs(&[1, 2, 3]), widget, |harness| {
+            harness.send_initial_events();
+            harness.submit_command(REQUEST_FOCUS.to(WidgetId::reserved(1)));
+            harness.paint();
+            assert_eq!(painted.get(), 1);
+
+            harness.event(key_down(Modifiers::CONTROL));
+            harness.paint();
+            assert_eq!(painted.get(), 2);
+            // The bar takes the focus from the hidden body.
+            assert!(harness.window().focus.is_some());
+
+            harness.event(key_down(Modifiers::CONTROL | Modifiers::SHIFT));
+            harness.event(key_down(Modifiers::CONTROL | Modifiers::SHIFT));
+            harness.paint();
+            assert_eq!(painted.get(), 3);
+        });
+    }
+}
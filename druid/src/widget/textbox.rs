@@ -14,20 +14,25 @@
 
 //! A textbox widget.
 
+use std::ops::Range;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{instrument, trace};
+use unicode_segmentation::UnicodeSegmentation;
 
+use crate::accessibility::Role;
 use crate::debug_state::DebugState;
-use crate::kurbo::Insets;
-use crate::piet::TextLayout as _;
+use crate::kurbo::{BezPath, Insets, Line, Shape};
+use crate::piet::{PietText, PietTextLayout, TextLayout as _};
 use crate::text::{
-    EditableText, ImeInvalidation, Selection, TextComponent, TextLayout, TextStorage,
+    mirror_alignment, AnnotationClick, EditableText, FindQuery, ImeInvalidation, LayoutMetrics,
+    Selection, Snapshot, TextAnnotation, TextComponent, TextLayout, TextStorage, UndoHistory,
 };
 use crate::widget::prelude::*;
-use crate::widget::{Padding, Scroll, WidgetWrapper};
+use crate::widget::{AnnotatedTextBox, FindReplace, Padding, Scroll, WidgetWrapper};
 use crate::{
-    theme, ArcStr, Color, Command, FontDescriptor, HotKey, KeyEvent, KeyOrValue, Point, Rect,
-    SysMods, TextAlignment, TimerToken, Vec2,
+    theme, ArcStr, Color, Command, FontDescriptor, HotKey, KeyEvent, KeyOrValue, Lens, Point, Rect,
+    Selector, SysMods, TextAlignment, TimerToken, Vec2,
 };
 
 use super::LabelText;
@@ -42,8 +47,19 @@ const MAC_OR_LINUX_OR_OBSD: bool = cfg!(any(
 /// When we scroll after editing or movement, we show a little extra of the document.
 const SCROLL_TO_INSETS: Insets = Insets::uniform_xy(40.0, 0.0);
 
+/// The character shown in place of each character of a [`TextBox::password`].
+const PASSWORD_MASK: char = '\u{2022}';
+
+/// The space on either side of the line numbers in a [`TextBox::with_gutter`].
+const GUTTER_PADDING: f64 = 6.0;
+
 /// A widget that allows user text input.
 ///
+/// Edits can be undone with <kbd>Cmd/Ctrl</kbd>+<kbd>Z</kbd>, and redone with
+/// <kbd>Cmd/Ctrl</kbd>+<kbd>Shift</kbd>+<kbd>Z</kbd>, or <kbd>Ctrl</kbd>+<kbd>Y</kbd>
+/// on Windows; the [`UNDO`] and [`REDO`] commands do the same. Consecutive
+/// typing is undone as one step.
+///
 /// # Editing values
 ///
 /// If the text you are editing represents a value of some other type, such
@@ -53,6 +69,32 @@ const SCROLL_TO_INSETS: Insets = Insets::uniform_xy(40.0, 0.0);
 ///
 /// [`Formatter`]: crate::text::format::Formatter
 /// [`ValueTextBox`]: super::ValueTextBox
+/// [`UNDO`]: crate::commands::UNDO
+/// [`REDO`]: crate::commands::REDO
+///
+/// # Passwords
+///
+/// A [`TextBox::password`] shows a bullet in place of each character of its
+/// text, and doesn't allow its text to be copied or cut. It can be revealed
+/// with [`TextBox::set_revealed`], or by sending it the [`TextBox::SET_REVEALED`]
+/// command.
+///
+/// # Line numbers
+///
+/// A [`multiline`] text box can show line numbers in a gutter along its
+/// leading edge, with [`TextBox::with_gutter`]. Clicking the gutter sends
+/// [`TextBox::GUTTER_CLICKED`], for instance to toggle a breakpoint.
+///
+/// # Find and replace
+///
+/// [`TextBox::with_find`] highlights the matches of a [`FindQuery`] in the
+/// app data, and handles the [`TextBox::FIND_NEXT`], [`TextBox::FIND_PREVIOUS`],
+/// [`TextBox::REPLACE`] and [`TextBox::REPLACE_ALL`] commands. The query is
+/// usually edited in other text boxes, using a [`FindFieldController`].
+///
+/// [`multiline`]: TextBox::multiline
+/// [`FindQuery`]: crate::text::FindQuery
+/// [`FindFieldController`]: super::FindFieldController
 pub struct TextBox<T> {
     placeholder_text: LabelText<T>,
     placeholder_layout: TextLayout<ArcStr>,
@@ -73,6 +115,23 @@ pub struct TextBox<T> {
     /// behaviour.
     pub handles_tab_notifications: bool,
     text_pos: Point,
+    /// The character shown in place of the text, if this is a password field.
+    mask: Option<char>,
+    revealed: bool,
+    history: UndoHistory<T>,
+    annotations: Arc<Vec<TextAnnotation>>,
+    highlights: Arc<Vec<Range<usize>>>,
+    current_highlight: Option<usize>,
+    gutter: Gutter,
+}
+
+/// The payload of [`TextBox::GUTTER_CLICKED`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GutterClick {
+    /// The index of the clicked line, counting from zero.
+    pub line: usize,
+    /// The text box that was clicked.
+    pub widget: WidgetId,
 }
 
 impl<T: EditableText + TextStorage> TextBox<T> {
@@ -100,9 +159,35 @@ impl<T: EditableText + TextStorage> TextBox<T> {
             cursor_timer: TimerToken::INVALID,
             handles_tab_notifications: true,
             text_pos: Point::ZERO,
+            mask: None,
+            revealed: false,
+            history: UndoHistory::new(),
+            annotations: Arc::new(Vec::new()),
+            highlights: Arc::new(Vec::new()),
+            current_highlight: None,
+            gutter: Gutter::new(),
         }
     }
 
+    /// Create a new `TextBox` for entering passwords.
+    ///
+    /// Each character of the text is displayed as a bullet, and the text
+    /// can't be copied or cut; pasting still works. The data is the real text.
+    ///
+    /// This can be combined with a placeholder, and with a [`Formatter`]
+    /// for validation.
+    ///
+    /// [`Formatter`]: crate::text::format::Formatter
+    pub fn password() -> Self {
+        let mut this = TextBox::new();
+        this.mask = Some(PASSWORD_MASK);
+        this.text_mut()
+            .borrow_mut()
+            .layout
+            .set_mask(Some(PASSWORD_MASK));
+        this
+    }
+
     /// Create a new multi-line `TextBox`.
     pub fn multiline() -> Self {
         let mut this = TextBox::new();
@@ -122,9 +207,76 @@ impl<T: EditableText + TextStorage> TextBox<T> {
     ///
     /// [`multiline`]: TextBox::multiline
     pub fn with_line_wrapping(mut self, wrap_lines: bool) -> Self {
-        self.inner.set_horizontal_scroll_enabled(!wrap_lines);
+        self.set_line_wrapping(wrap_lines);
         self
     }
+
+    /// Set whether the lines of a [`multiline`] text box are wrapped.
+    ///
+    /// When lines aren't wrapped the text scrolls horizontally, and the cursor
+    /// is kept in view. This can also be changed by sending the text box the
+    /// [`TextBox::SET_LINE_WRAPPING`] command.
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the text is updated.
+    ///
+    /// [`multiline`]: TextBox::multiline
+    /// [`request_layout`]: crate::EventCtx::request_layout
+    pub fn set_line_wrapping(&mut self, wrap_lines: bool) {
+        self.inner.set_horizontal_scroll_enabled(!wrap_lines);
+        self.scroll_to_selection_after_layout = true;
+    }
+}
+
+impl TextBox<()> {
+    /// A command that sets a `TextBox`'s [`revealed`] state.
+    ///
+    /// The payload is `true` to show the text of a [password] field and
+    /// `false` to hide it again. This has no effect on other text boxes.
+    ///
+    /// [`revealed`]: TextBox::set_revealed
+    /// [password]: TextBox::password
+    pub const SET_REVEALED: Selector<bool> = Selector::new("druid-builtin.textbox-set-revealed");
+
+    /// A command that sets whether the lines of a [`multiline`] `TextBox`
+    /// are [wrapped].
+    ///
+    /// [`multiline`]: TextBox::multiline
+    /// [wrapped]: TextBox::set_line_wrapping
+    pub const SET_LINE_WRAPPING: Selector<bool> =
+        Selector::new("druid-builtin.textbox-set-line-wrapping");
+
+    /// A command sent when an [annotated] range of a `TextBox` is right-clicked,
+    /// for instance to show spelling suggestions.
+    ///
+    /// [annotated]: TextBox::set_annotations
+    pub const ANNOTATION_CLICKED: Selector<AnnotationClick> =
+        Selector::new("druid-builtin.textbox-annotation-clicked");
+
+    /// A command sent when the line-number [gutter] of a `TextBox` is clicked.
+    ///
+    /// This does not move the cursor; it is intended for toggling markers
+    /// such as breakpoints.
+    ///
+    /// [gutter]: TextBox::with_gutter
+    pub const GUTTER_CLICKED: Selector<GutterClick> =
+        Selector::new("druid-builtin.textbox-gutter-clicked");
+
+    /// A command that selects the next match of the query of a text box
+    /// created with [`TextBox::with_find`], wrapping around at the end.
+    pub const FIND_NEXT: Selector = Selector::new("druid-builtin.textbox-find-next");
+
+    /// A command that selects the previous match of the query of a text box
+    /// created with [`TextBox::with_find`], wrapping around at the start.
+    pub const FIND_PREVIOUS: Selector = Selector::new("druid-builtin.textbox-find-previous");
+
+    /// A command that replaces the current match of a text box created with
+    /// [`TextBox::with_find`], and selects the next one.
+    pub const REPLACE: Selector = Selector::new("druid-builtin.textbox-replace");
+
+    /// A command that replaces every match of a text box created with
+    /// [`TextBox::with_find`].
+    pub const REPLACE_ALL: Selector = Selector::new("druid-builtin.textbox-replace-all");
 }
 
 impl<T> TextBox<T> {
@@ -200,7 +352,8 @@ impl<T> TextBox<T> {
             .borrow_mut()
             .layout
             .set_text_size(size.clone());
-        self.placeholder_layout.set_text_size(size);
+        self.placeholder_layout.set_text_size(size.clone());
+        self.gutter.set_text_size(size);
     }
 
     /// Set the font.
@@ -218,7 +371,8 @@ impl<T> TextBox<T> {
         }
         let font = font.into();
         self.text_mut().borrow_mut().layout.set_font(font.clone());
-        self.placeholder_layout.set_font(font);
+        self.placeholder_layout.set_font(font.clone());
+        self.gutter.set_font(font);
     }
 
     /// Set the [`TextAlignment`] for this `TextBox``.
@@ -274,6 +428,130 @@ impl<T> TextBox<T> {
     pub fn text_position(&self) -> Point {
         self.text_pos
     }
+
+    /// Set whether the text of a [password] field is shown.
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the text is updated.
+    ///
+    /// [password]: TextBox::password
+    /// [`request_layout`]: crate::EventCtx::request_layout
+    pub fn set_revealed(&mut self, revealed: bool) {
+        if !self.text().can_write() {
+            tracing::warn!("set_revealed called with IME lock held.");
+            return;
+        }
+        self.revealed = revealed;
+        let mask = self.mask.filter(|_| !revealed);
+        self.text_mut().borrow_mut().layout.set_mask(mask);
+    }
+
+    /// Returns `true` if the text of this [password] field is shown.
+    ///
+    /// [password]: TextBox::password
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+
+    /// Builder-style method to show line numbers in a gutter.
+    ///
+    /// This is intended for [`multiline`] text boxes. Each line is numbered
+    /// once, next to its first row when it is wrapped, and the line with the
+    /// cursor is highlighted while the text box is focused. The gutter stays
+    /// in place when the text is scrolled horizontally.
+    ///
+    /// [`multiline`]: TextBox::multiline
+    pub fn with_gutter(mut self, gutter: bool) -> Self {
+        self.set_gutter(gutter);
+        self
+    }
+
+    /// Set whether line numbers are shown in a gutter.
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the text is updated.
+    ///
+    /// [`request_layout`]: crate::EventCtx::request_layout
+    pub fn set_gutter(&mut self, gutter: bool) {
+        self.gutter.enabled = gutter;
+        if !gutter {
+            self.inner.child_mut().set_insets(theme::TEXTBOX_INSETS);
+        }
+    }
+
+    /// Whether line numbers are shown in a gutter.
+    pub fn has_gutter(&self) -> bool {
+        self.gutter.enabled
+    }
+
+    /// Builder-style method to underline ranges of the text, such as spelling
+    /// errors, with annotations stored next to the text in the app data.
+    ///
+    /// `text` selects the text of this `TextBox` and `annotations` its
+    /// annotations; see [`AnnotatedTextBox`] for details.
+    pub fn with_annotations<U, LT, LA>(
+        self,
+        text: LT,
+        annotations: LA,
+    ) -> AnnotatedTextBox<U, T, LT, LA>
+    where
+        LT: Lens<U, T>,
+        LA: Lens<U, Arc<Vec<TextAnnotation>>>,
+    {
+        AnnotatedTextBox::new(self, text, annotations)
+    }
+
+    /// Set the ranges of the text that are underlined.
+    ///
+    /// Annotations with ranges outside of the text are ignored. If you change
+    /// this property, you are responsible for calling [`request_paint`].
+    ///
+    /// [`request_paint`]: crate::EventCtx::request_paint
+    pub fn set_annotations(&mut self, annotations: Arc<Vec<TextAnnotation>>) {
+        self.annotations = annotations;
+    }
+
+    /// The ranges of the text that are underlined.
+    pub fn annotations(&self) -> &Arc<Vec<TextAnnotation>> {
+        &self.annotations
+    }
+
+    /// Builder-style method to search the text for a [`FindQuery`] stored
+    /// next to it in the app data.
+    ///
+    /// `text` selects the text of this `TextBox` and `query` the query; see
+    /// [`FindReplace`] for details.
+    ///
+    /// [`FindQuery`]: crate::text::FindQuery
+    pub fn with_find<U, LT, LQ>(self, text: LT, query: LQ) -> FindReplace<U, T, LT, LQ>
+    where
+        LT: Lens<U, T>,
+        LQ: Lens<U, FindQuery>,
+    {
+        FindReplace::new(self, text, query)
+    }
+
+    /// Set the ranges of the text that are highlighted, such as the matches
+    /// of a search, and the index of the one that is highlighted as current.
+    ///
+    /// Ranges outside of the text are ignored. If you change this property,
+    /// you are responsible for calling [`request_paint`].
+    ///
+    /// [`request_paint`]: crate::EventCtx::request_paint
+    pub fn set_highlights(&mut self, highlights: Arc<Vec<Range<usize>>>, current: Option<usize>) {
+        self.highlights = highlights;
+        self.current_highlight = current;
+    }
+
+    /// The ranges of the text that are highlighted.
+    pub fn highlights(&self) -> &Arc<Vec<Range<usize>>> {
+        &self.highlights
+    }
+
+    /// The index of the highlight that is highlighted as current.
+    pub fn current_highlight(&self) -> Option<usize> {
+        self.current_highlight
+    }
 }
 
 impl<T: Data> TextBox<T> {
@@ -323,17 +601,122 @@ impl<T> TextBox<T> {
 }
 
 impl<T: TextStorage + EditableText> TextBox<T> {
+    /// The annotation whose text is under `point`, relative to this widget.
+    pub fn annotation_at(&self, point: Point) -> Option<&TextAnnotation> {
+        if !self.text().can_read() {
+            return None;
+        }
+        let text = self.text().borrow();
+        let point = point - self.annotation_origin();
+        let content = text.layout.text()?;
+        self.annotations
+            .iter()
+            .filter(|annotation| is_valid_range(content.as_str(), &annotation.range))
+            .find(|annotation| {
+                text.rects_for_range(annotation.range.clone())
+                    .iter()
+                    .any(|rect| rect.contains(point))
+            })
+    }
+
+    /// Select `range`, and scroll it into view after the next layout.
+    ///
+    /// The caller is responsible for requesting layout, and for passing the
+    /// result to [`invalidate_text_input`].
+    ///
+    /// [`invalidate_text_input`]: crate::EventCtx::invalidate_text_input
+    pub(crate) fn reveal_range(&mut self, range: Range<usize>) -> Option<ImeInvalidation> {
+        self.scroll_to_selection_after_layout = true;
+        self.text_mut()
+            .borrow_mut()
+            .set_selection(Selection::new(range.start, range.end))
+    }
+
+    /// Replace `range` of the text with `replacement`, as one step in the
+    /// undo history, leaving the caret after the replacement.
+    pub(crate) fn replace_range(&mut self, data: &mut T, range: Range<usize>, replacement: &str) {
+        let before = self.snapshot(data);
+        data.edit(range.clone(), replacement);
+        let selection = Selection::caret(range.start + replacement.len());
+        let _ = self.text_mut().borrow_mut().set_selection(selection);
+        self.history.break_coalescing();
+        self.history.record(before, data, selection);
+        self.history.break_coalescing();
+    }
+
+    /// The offset from our origin to the origin of the text layout.
+    fn annotation_origin(&self) -> Vec2 {
+        self.text_pos.to_vec2() - self.inner.offset()
+    }
+
+    /// The line the cursor is drawn on, snapped to the pixel grid so that it
+    /// stays sharp.
+    fn cursor_line(&self, data: &T, env: &Env) -> Line {
+        let cursor_pos = self.text().borrow().selection().active;
+        let cursor_line = self
+            .text()
+            .borrow()
+            .cursor_line_for_text_position(cursor_pos);
+
+        let insets = self.text_insets(env);
+        let padding_offset = Vec2::new(insets.x0, insets.y0);
+
+        // if there's no data, we always draw the cursor based on
+        // our alignment.
+        let mut cursor = if data.is_empty() {
+            cursor_line + padding_offset
+        } else {
+            cursor_line + padding_offset - self.inner.offset()
+        };
+        cursor.p0.x = cursor.p0.x.trunc() + 0.5;
+        cursor.p1.x = cursor.p0.x;
+        cursor
+    }
+
+    /// The space around the text: the theme's insets, and the gutter.
+    fn text_insets(&self, env: &Env) -> Insets {
+        let mut insets = env.get(theme::TEXTBOX_INSETS);
+        // like `Padding`, this is the leading inset in a right-to-left layout
+        insets.x0 += self.gutter.width();
+        insets
+    }
+
+    /// The area of the gutter, relative to this widget.
+    fn gutter_rect(&self, size: Size, env: &Env) -> Rect {
+        let width = self.gutter.width();
+        if env.get(theme::LAYOUT_DIRECTION).is_rtl() {
+            Rect::new(size.width - width, 0.0, size.width, size.height)
+        } else {
+            Rect::new(0.0, 0.0, width, size.height)
+        }
+    }
+
+    /// The y position of the top of the text layout, relative to this widget.
+    fn text_top(&self, env: &Env) -> f64 {
+        env.get(theme::TEXTBOX_INSETS).y0 - self.inner.offset().y
+    }
+
+    /// The rows of the line containing the cursor, spanning this widget.
+    fn current_line_rect(&self, size: Size, env: &Env) -> Option<Rect> {
+        let text = self.text().borrow();
+        let cursor = text.layout.layout_offset(text.selection().active);
+        let line = self.gutter.line_for_offset(cursor)?;
+        let top = self.text_top(env);
+        Some(Rect::new(0.0, top + line.y0, size.width, top + line.y1))
+    }
+
     fn rect_for_selection_end(&self) -> Rect {
         let text = self.text().borrow();
         let layout = text.layout.layout().unwrap();
 
-        let hit = layout.hit_test_text_position(text.selection().active);
+        let hit = layout.hit_test_text_position(text.layout.layout_offset(text.selection().active));
         let line = layout.line_metric(hit.line).unwrap();
         let y0 = line.y_offset;
         let y1 = y0 + line.height;
         let x = hit.point.x;
 
-        Rect::new(x, y0, x, y1)
+        // the gutter covers the start of the viewport
+        Rect::new(x, y0, x + self.gutter.width(), y1)
     }
 
     fn scroll_to_selection_end(&mut self) {
@@ -374,11 +757,40 @@ impl<T: TextStorage + EditableText> TextBox<T> {
             _ => None,
         }
     }
+
+    fn snapshot(&self, data: &T) -> Snapshot<T> {
+        Snapshot {
+            text: data.clone(),
+            selection: self.text().borrow().selection(),
+        }
+    }
+
+    /// Restore the text and selection of a snapshot from the undo history.
+    fn restore(&mut self, ctx: &mut EventCtx, data: &mut T, snapshot: Snapshot<T>) {
+        *data = snapshot.text;
+        let _ = self
+            .text_mut()
+            .borrow_mut()
+            .set_selection(snapshot.selection);
+        ctx.invalidate_text_input(ImeInvalidation::Reset);
+        ctx.request_paint();
+    }
 }
 
 impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
     #[instrument(name = "TextBox", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        // Only these events can change the text, so we don't copy it for other events.
+        let may_edit = matches!(
+            event,
+            Event::ImeStateChange | Event::Paste(_) | Event::Command(_)
+        );
+        let before = self.text().can_read().then(|| Snapshot {
+            text: may_edit.then(|| data.clone()),
+            selection: self.text().borrow().selection(),
+        });
+        let mut record_edit = true;
+        let mut forward = true;
         match event {
             Event::Notification(cmd) => match cmd {
                 cmd if cmd.is(TextComponent::SCROLL_TO) => {
@@ -415,7 +827,30 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                     ctx.set_handled();
                 }
             }
+            Event::MouseDown(mouse)
+                if self.gutter.enabled && self.gutter_rect(ctx.size(), env).contains(mouse.pos) =>
+            {
+                if let Some(line) = self.gutter.line_at(mouse.pos.y - self.text_top(env)) {
+                    let click = GutterClick {
+                        line,
+                        widget: ctx.widget_id(),
+                    };
+                    ctx.submit_command(TextBox::GUTTER_CLICKED.with(click));
+                }
+                ctx.set_handled();
+                forward = false;
+            }
             Event::MouseDown(mouse) if self.text().can_write() => {
+                if mouse.button.is_right() && !ctx.is_disabled() {
+                    if let Some(annotation) = self.annotation_at(mouse.pos) {
+                        let click = AnnotationClick {
+                            annotation: annotation.clone(),
+                            widget: ctx.widget_id(),
+                            window_pos: mouse.window_pos,
+                        };
+                        ctx.submit_command(TextBox::ANNOTATION_CLICKED.with(click));
+                    }
+                }
                 if !ctx.is_disabled() {
                     if !mouse.focus {
                         ctx.request_focus();
@@ -430,7 +865,13 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                 if !ctx.is_disabled() {
                     if *id == self.cursor_timer && ctx.has_focus() {
                         self.cursor_on = !self.cursor_on;
-                        ctx.request_paint();
+                        if self.text().can_read() {
+                            // only the cursor changes
+                            let cursor = self.cursor_line(data, env).bounding_box();
+                            ctx.request_paint_rect(cursor.inflate(1.0, 1.0));
+                        } else {
+                            ctx.request_paint();
+                        }
                         self.cursor_timer = ctx.request_timer(CURSOR_BLINK_DURATION);
                     }
                 } else if self.cursor_on {
@@ -460,6 +901,39 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                 }
                 ctx.set_handled();
             }
+            Event::Command(cmd)
+                if !self.text().is_composing()
+                    && ctx.is_focused()
+                    && (cmd.is(crate::commands::UNDO) || cmd.is(crate::commands::REDO)) =>
+            {
+                let current = self.snapshot(data);
+                let snapshot = if cmd.is(crate::commands::UNDO) {
+                    self.history.undo(current)
+                } else {
+                    self.history.redo(current)
+                };
+                if let Some(snapshot) = snapshot {
+                    self.restore(ctx, data, snapshot);
+                }
+                record_edit = false;
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(TextBox::SET_REVEALED) => {
+                if self.text().can_write() {
+                    self.set_revealed(*cmd.get_unchecked(TextBox::SET_REVEALED));
+                    ctx.invalidate_text_input(ImeInvalidation::LayoutChanged);
+                    ctx.request_layout();
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(TextBox::SET_LINE_WRAPPING) => {
+                if self.text().can_write() {
+                    self.set_line_wrapping(*cmd.get_unchecked(TextBox::SET_LINE_WRAPPING));
+                    ctx.invalidate_text_input(ImeInvalidation::LayoutChanged);
+                    ctx.request_layout();
+                }
+                ctx.set_handled();
+            }
             Event::Command(cmd)
                 if !self.text().is_composing()
                     && ctx.is_focused()
@@ -490,7 +964,24 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
             }
             _ => (),
         }
-        self.inner.event(ctx, event, data, env)
+        if forward {
+            self.inner.event(ctx, event, data, env);
+        }
+
+        if let Some(before) = before.filter(|_| record_edit && self.text().can_read()) {
+            let selection = self.text().borrow().selection();
+            match before.text {
+                Some(text) if text.as_str() != data.as_str() => {
+                    let before = Snapshot {
+                        text,
+                        selection: before.selection,
+                    };
+                    self.history.record(before, data, selection);
+                }
+                _ if selection != before.selection => self.history.break_coalescing(),
+                _ => (),
+            }
+        }
     }
 
     #[instrument(name = "TextBox", level = "trace", skip(self, ctx, event, data, env))]
@@ -501,6 +992,9 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                     self.placeholder_text.resolve(data, env);
                 }
                 ctx.register_text_input(self.text().input_handler());
+                ctx.set_access_role(Role::TextInput);
+                ctx.set_access_name(self.placeholder_text.display_text().to_string());
+                ctx.set_access_value(data.as_str());
             }
             LifeCycle::BuildFocusChain => {
                 //TODO: make this a configurable option? maybe?
@@ -540,15 +1034,20 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
 
     #[instrument(name = "TextBox", level = "trace", skip(self, ctx, old, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, old: &T, data: &T, env: &Env) {
+        self.history.sync(data);
         let placeholder_changed = self.placeholder_text.resolve(data, env);
         if placeholder_changed {
             let new_text = self.placeholder_text.display_text();
+            ctx.set_access_name(new_text.to_string());
             self.placeholder_layout.set_text(new_text);
         }
+        ctx.set_access_value(data.as_str());
 
         self.inner.update(ctx, old, data, env);
         if placeholder_changed
             || (ctx.env_changed() && self.placeholder_layout.needs_rebuild_after_update(ctx))
+            || (ctx.env_changed() && self.gutter.needs_rebuild_after_update(ctx))
+            || (self.gutter.enabled && ctx.env_key_changed(&theme::TEXTBOX_INSETS))
         {
             ctx.request_layout();
         }
@@ -566,19 +1065,31 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
             tracing::warn!("Widget::layout called with outstanding IME lock.");
         }
         let min_width = env.get(theme::WIDE_WIDGET_WIDTH);
-        let textbox_insets = env.get(theme::TEXTBOX_INSETS);
+        if self.gutter.enabled {
+            let line_count = data.as_str().matches('\n').count() + 1;
+            self.gutter.measure(ctx.text(), env, line_count);
+            let insets = self.text_insets(env);
+            self.inner.child_mut().set_insets(insets);
+        }
+        let textbox_insets = self.text_insets(env);
 
         self.placeholder_layout.rebuild_if_needed(ctx.text(), env);
         let min_size = bc.constrain((min_width, 0.0));
         let child_bc = BoxConstraints::new(min_size, bc.max());
 
         let size = self.inner.layout(ctx, &child_bc, data, env);
+        self.text_pos = Point::new(textbox_insets.x0, textbox_insets.y0);
 
         let text_metrics = if !self.text().can_read() || data.is_empty() {
             self.placeholder_layout.layout_metrics()
         } else {
             self.text().borrow().layout.layout_metrics()
         };
+        if self.gutter.enabled {
+            let text = self.inner.child().wrapped().borrow();
+            let layout = text.layout.layout().filter(|_| !data.is_empty());
+            self.gutter.measure_lines(layout, text_metrics);
+        }
 
         let layout_baseline = text_metrics.size.height - text_metrics.first_baseline;
         let baseline_off = layout_baseline
@@ -605,10 +1116,14 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
             return;
         }
         let size = ctx.size();
-        let background_color = env.get(theme::BACKGROUND_LIGHT);
+        let background_color = ctx.resolve_disabled(
+            env,
+            theme::BACKGROUND_LIGHT,
+            theme::DISABLED_BACKGROUND_LIGHT,
+        );
         let cursor_color = env.get(theme::CURSOR_COLOR);
         let border_width = env.get(theme::TEXTBOX_BORDER_WIDTH);
-        let textbox_insets = env.get(theme::TEXTBOX_INSETS);
+        let textbox_insets = self.text_insets(env);
 
         let is_focused = ctx.is_focused();
 
@@ -626,12 +1141,66 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
 
         ctx.fill(clip_rect, &background_color);
 
+        let current_line = if self.gutter.enabled && is_focused {
+            self.current_line_rect(size, env)
+        } else {
+            None
+        };
+        let current_line_color = env.get(theme::TEXTBOX_CURRENT_LINE_COLOR);
+        if let Some(rect) = current_line {
+            ctx.with_save(|ctx| {
+                ctx.clip(clip_rect);
+                ctx.fill(rect, &current_line_color);
+            });
+        }
+
         if !data.is_empty() {
+            if !self.highlights.is_empty() {
+                let origin = self.annotation_origin();
+                let text = self.text().borrow();
+                let match_color = env.get(theme::TEXTBOX_MATCH_COLOR);
+                let current_color = env.get(theme::TEXTBOX_CURRENT_MATCH_COLOR);
+                ctx.with_save(|ctx| {
+                    ctx.clip(clip_rect);
+                    for (i, range) in self.highlights.iter().enumerate() {
+                        if !is_valid_range(data.as_str(), range) {
+                            continue;
+                        }
+                        let color = if Some(i) == self.current_highlight {
+                            &current_color
+                        } else {
+                            &match_color
+                        };
+                        for rect in text.rects_for_range(range.clone()) {
+                            ctx.fill(rect + origin, color);
+                        }
+                    }
+                });
+            }
             self.inner.paint(ctx, data, env);
+            if !self.annotations.is_empty() {
+                let origin = self.annotation_origin();
+                let text = self.text().borrow();
+                ctx.with_save(|ctx| {
+                    ctx.clip(clip_rect);
+                    for annotation in self.annotations.iter() {
+                        if !is_valid_range(data.as_str(), &annotation.range) {
+                            continue;
+                        }
+                        let color = env.get(annotation.style.color_key());
+                        for rect in text.rects_for_range(annotation.range.clone()) {
+                            ctx.stroke(squiggle(rect + origin), &color, 1.0);
+                        }
+                    }
+                });
+            }
         } else {
             let text_width = self.placeholder_layout.layout_metrics().size.width;
             let extra_width = (size.width - text_width - textbox_insets.x_value()).max(0.);
-            let alignment = self.text().borrow().text_alignment();
+            let mut alignment = self.text().borrow().text_alignment();
+            if env.get(theme::LAYOUT_DIRECTION).is_rtl() {
+                alignment = mirror_alignment(alignment);
+            }
             // alignment is only used for single-line text boxes
             let x_offset = if self.multiline {
                 0.0
@@ -647,28 +1216,24 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
             })
         }
 
+        // Paint the gutter over any text scrolled beneath it
+        if self.gutter.enabled {
+            let gutter_rect = self.gutter_rect(size, env);
+            let gutter_color = env.get(theme::TEXTBOX_GUTTER_COLOR);
+            let top = self.text_top(env);
+            ctx.with_save(|ctx| {
+                ctx.clip(clip_rect);
+                ctx.fill(gutter_rect, &gutter_color);
+                if let Some(rect) = current_line {
+                    ctx.fill(rect.intersect(gutter_rect), &current_line_color);
+                }
+                self.gutter.paint(ctx, env, gutter_rect, top);
+            });
+        }
+
         // Paint the cursor if focused and there's no selection
         if is_focused && self.should_draw_cursor() {
-            // if there's no data, we always draw the cursor based on
-            // our alignment.
-            let cursor_pos = self.text().borrow().selection().active;
-            let cursor_line = self
-                .text()
-                .borrow()
-                .cursor_line_for_text_position(cursor_pos);
-
-            let padding_offset = Vec2::new(textbox_insets.x0, textbox_insets.y0);
-
-            let mut cursor = if data.is_empty() {
-                cursor_line + padding_offset
-            } else {
-                cursor_line + padding_offset - self.inner.offset()
-            };
-
-            // Snap the cursor to the pixel grid so it stays sharp.
-            cursor.p0.x = cursor.p0.x.trunc() + 0.5;
-            cursor.p1.x = cursor.p0.x;
-
+            let cursor = self.cursor_line(data, env);
             ctx.with_save(|ctx| {
                 ctx.clip(clip_rect);
                 ctx.stroke(cursor, &cursor_color, 1.);
@@ -680,10 +1245,16 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
     }
 
     fn debug_state(&self, data: &T) -> DebugState {
-        let text = data.slice(0..data.len()).unwrap_or_default();
+        let text = match self.mask.filter(|_| !self.revealed) {
+            // One mask character per grapheme, as in the layout.
+            Some(mask) => mask
+                .to_string()
+                .repeat(data.as_str().graphemes(true).count()),
+            None => data.slice(0..data.len()).unwrap_or_default().to_string(),
+        };
         DebugState {
             display_name: self.short_type_name().to_string(),
-            main_value: text.to_string(),
+            main_value: text,
             ..Default::default()
         }
     }
@@ -695,6 +1266,186 @@ impl<T: TextStorage + EditableText> Default for TextBox<T> {
     }
 }
 
+/// `true` if `range` lies inside `text`, on character boundaries.
+fn is_valid_range(text: &str, range: &Range<usize>) -> bool {
+    range.start <= range.end
+        && text.is_char_boundary(range.start)
+        && text.is_char_boundary(range.end)
+}
+
+/// A wavy line along the bottom of `rect`.
+fn squiggle(rect: Rect) -> BezPath {
+    const HALF_PERIOD: f64 = 2.0;
+    const AMPLITUDE: f64 = 1.5;
+    let y = rect.max_y() - AMPLITUDE;
+    let mut path = BezPath::new();
+    path.move_to((rect.min_x(), y));
+    let mut x = rect.min_x();
+    let mut up = true;
+    while x < rect.max_x() {
+        x = (x + HALF_PERIOD).min(rect.max_x());
+        let dy = if up { -AMPLITUDE } else { AMPLITUDE };
+        path.line_to((x, y + dy));
+        up = !up;
+    }
+    path
+}
+
+/// The line-number column of a [`TextBox::with_gutter`].
+struct Gutter {
+    enabled: bool,
+    /// As many zeros as the largest line number has digits, for measuring.
+    digits: TextLayout<ArcStr>,
+    /// The line numbers painted so far, by line index.
+    numbers: Vec<TextLayout<ArcStr>>,
+    /// The lines of the text, as of the last layout.
+    lines: Vec<GutterLine>,
+    width: f64,
+}
+
+/// A line of text, which may be wrapped over several rows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GutterLine {
+    /// The start of the line, in the text layout.
+    start: usize,
+    /// The top of the first row, in text layout coordinates.
+    y0: f64,
+    /// The bottom of the last row, in text layout coordinates.
+    y1: f64,
+    /// The baseline of the first row, relative to `y0`.
+    baseline: f64,
+}
+
+impl Gutter {
+    fn new() -> Self {
+        let mut digits = TextLayout::new();
+        digits.set_text_color(theme::TEXTBOX_LINE_NUMBER_COLOR);
+        Gutter {
+            enabled: false,
+            digits,
+            numbers: Vec::new(),
+            lines: Vec::new(),
+            width: 0.0,
+        }
+    }
+
+    fn set_font(&mut self, font: KeyOrValue<FontDescriptor>) {
+        self.digits.set_font(font);
+        self.numbers.clear();
+    }
+
+    fn set_text_size(&mut self, size: KeyOrValue<f64>) {
+        self.digits.set_text_size(size);
+        self.numbers.clear();
+    }
+
+    /// The width of the gutter, or zero if it is disabled.
+    fn width(&self) -> f64 {
+        if self.enabled {
+            self.width
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns `true` if the gutter needs to be measured again.
+    fn needs_rebuild_after_update(&mut self, ctx: &mut UpdateCtx) -> bool {
+        let rebuild = self.enabled && self.digits.needs_rebuild_after_update(ctx);
+        if rebuild {
+            self.numbers.clear();
+        }
+        rebuild
+    }
+
+    /// Make room for the numbers of `line_count` lines.
+    fn measure(&mut self, factory: &mut PietText, env: &Env, line_count: usize) {
+        let digits = "0".repeat(line_count.to_string().len());
+        if self.digits.text().map(|text| text.as_ref()) != Some(digits.as_str()) {
+            self.digits.set_text(digits.into());
+        }
+        self.digits.rebuild_if_needed(factory, env);
+        self.width = self.digits.size().width.ceil() + 2.0 * GUTTER_PADDING;
+    }
+
+    /// Find the lines of the text from the rows of its layout.
+    ///
+    /// If there is no text, there is a single line measuring `empty`.
+    fn measure_lines(&mut self, layout: Option<&PietTextLayout>, empty: LayoutMetrics) {
+        self.lines.clear();
+        let text = layout.map(|layout| layout.text()).unwrap_or_default();
+        let rows = layout.map(|layout| layout.line_count()).unwrap_or(0);
+        let mut last_row = None;
+        for row in (0..rows).filter_map(|idx| layout?.line_metric(idx)) {
+            let y1 = row.y_offset + row.height;
+            match self.lines.last_mut() {
+                // a wrapped row continues the current line
+                Some(line) if !text[..row.start_offset].ends_with('\n') => line.y1 = y1,
+                _ => self.lines.push(GutterLine {
+                    start: row.start_offset,
+                    y0: row.y_offset,
+                    y1,
+                    baseline: row.baseline,
+                }),
+            }
+            last_row = Some(row);
+        }
+        match last_row {
+            // not every platform has a row for the empty line after a trailing newline
+            Some(row) if text.ends_with('\n') && row.start_offset < text.len() => {
+                let y0 = row.y_offset + row.height;
+                self.lines.push(GutterLine {
+                    start: text.len(),
+                    y0,
+                    y1: y0 + row.height,
+                    baseline: row.baseline,
+                });
+            }
+            None => self.lines.push(GutterLine {
+                start: 0,
+                y0: 0.0,
+                y1: empty.size.height,
+                baseline: empty.first_baseline,
+            }),
+            _ => (),
+        }
+    }
+
+    /// The line containing `offset` in the text layout.
+    fn line_for_offset(&self, offset: usize) -> Option<&GutterLine> {
+        let idx = self.lines.partition_point(|line| line.start <= offset);
+        self.lines.get(idx.checked_sub(1)?)
+    }
+
+    /// The index of the line at `y`, in text layout coordinates.
+    fn line_at(&self, y: f64) -> Option<usize> {
+        self.lines
+            .iter()
+            .position(|line| line.y0 <= y && y < line.y1)
+    }
+
+    /// Paint the numbers of the lines, right-aligned in `rect`.
+    ///
+    /// `top` is the position of the top of the text layout.
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env, rect: Rect, top: f64) {
+        for (idx, line) in self.lines.iter().enumerate() {
+            if top + line.y1 < rect.y0 || top + line.y0 > rect.y1 {
+                continue;
+            }
+            while self.numbers.len() <= idx {
+                let mut number = self.digits.clone();
+                number.set_text((self.numbers.len() + 1).to_string().into());
+                self.numbers.push(number);
+            }
+            let number = &mut self.numbers[idx];
+            number.rebuild_if_needed(ctx.text(), env);
+            let metrics = number.layout_metrics();
+            let x = rect.x1 - GUTTER_PADDING - metrics.size.width;
+            let y = top + line.y0 + line.baseline - metrics.first_baseline;
+            number.draw(ctx, (x, y));
+        }
+    }
+}
+
 fn x_offset_for_extra_width(alignment: TextAlignment, extra_width: f64) -> f64 {
     match alignment {
         TextAlignment::Start | TextAlignment::Justified => 0.0,
@@ -702,3 +1453,302 @@ fn x_offset_for_extra_width(alignment: TextAlignment, extra_width: f64) -> f64 {
         TextAlignment::Center => extra_width / 2.0,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::tests::move_mouse;
+    use crate::text::{Direction, InputHandler, Movement, TextAction};
+    use crate::{InternalEvent, KbKey, MouseButton, MouseEvent, RawMods, WidgetExt};
+    use test_log::test;
+
+    /// Edit the text of the focused text box, the way the platform would.
+    fn ime_edit(harness: &mut Harness<String>, f: impl FnOnce(&mut dyn InputHandler)) {
+        let token = harness.window().ime_handlers[0].0;
+        let mut handler = harness.window_mut().get_ime_handler(token, true);
+        f(&mut *handler);
+        drop(handler);
+        let widget = harness.window_mut().release_ime_lock(token).unwrap();
+        harness.event(Event::Internal(InternalEvent::RouteImeStateChange(widget)));
+    }
+
+    fn type_text(harness: &mut Harness<String>, text: &str) {
+        for c in text.chars() {
+            ime_edit(harness, |handler| {
+                let selection = handler.selection();
+                handler.replace_range(selection.range(), c.encode_utf8(&mut [0; 4]));
+                handler.set_selection(Selection::caret(selection.min() + c.len_utf8()));
+            });
+        }
+    }
+
+    #[test]
+    fn password_is_masked_until_revealed() {
+        let [id] = widget_ids();
+        let textbox = TextBox::password().with_placeholder("Password").with_id(id);
+
+        // The accent is a combining character, so this is five graphemes.
+        Harness::create_simple("he\u{301}llo".to_string(), textbox, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let main_value = |harness: &mut Harness<String>| {
+                harness.get_debug_state(id).children.remove(0).main_value
+            };
+            assert_eq!(main_value(harness), "\u{2022}".repeat(5));
+
+            harness.submit_command(TextBox::SET_REVEALED.with(true).to(id));
+            assert_eq!(main_value(harness), "he\u{301}llo");
+            harness.submit_command(TextBox::SET_REVEALED.with(false).to(id));
+            assert_eq!(main_value(harness), "\u{2022}".repeat(5));
+        });
+    }
+
+    #[test]
+    fn undo_and_redo_round_trip() {
+        let [id] = widget_ids();
+        let textbox = TextBox::multiline().with_id(id);
+
+        Harness::create_simple(String::new(), textbox, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let mouse = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse((5.0, 5.0))
+            };
+            harness.event(Event::MouseDown(mouse.clone()));
+            harness.event(Event::MouseUp(mouse));
+            assert_eq!(harness.window().focus, Some(id));
+
+            type_text(harness, "hello");
+            // Moving the caret ends the first undo step.
+            ime_edit(harness, |handler| {
+                handler.handle_action(TextAction::Move(Movement::Grapheme(Direction::Upstream)))
+            });
+            type_text(harness, "p!");
+            ime_edit(harness, |handler| {
+                handler.handle_action(TextAction::Delete(Movement::Grapheme(Direction::Upstream)))
+            });
+            assert_eq!(harness.data(), "hellpo");
+
+            let undo = Event::KeyDown(KeyEvent::for_test(
+                RawMods::from(SysMods::Cmd),
+                KbKey::Character("z".into()),
+            ));
+            let redo = Event::KeyDown(if cfg!(windows) {
+                KeyEvent::for_test(RawMods::Ctrl, KbKey::Character("y".into()))
+            } else {
+                KeyEvent::for_test(
+                    RawMods::from(SysMods::CmdShift),
+                    KbKey::Character("Z".into()),
+                )
+            });
+            harness.event(undo.clone());
+            assert_eq!(harness.data(), "hellp!o");
+            harness.event(undo.clone());
+            assert_eq!(harness.data(), "hello");
+            harness.event(undo.clone());
+            assert_eq!(harness.data(), "");
+            harness.event(undo.clone());
+            assert_eq!(harness.data(), "");
+
+            harness.event(redo.clone());
+            assert_eq!(harness.data(), "hello");
+            harness.event(redo);
+            assert_eq!(harness.data(), "hellp!o");
+
+            // The selection is restored with the text.
+            harness.event(undo);
+            assert_eq!(harness.data(), "hello");
+            ime_edit(harness, |handler| {
+                assert_eq!(handler.selection(), Selection::caret(4));
+            });
+        });
+    }
+
+    #[test]
+    fn ime_composition() {
+        let [id] = widget_ids();
+        let textbox = TextBox::new().with_id(id).center();
+
+        Harness::create_simple(String::new(), textbox, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let rect = harness.get_state(id).layout_rect();
+            let mouse = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse(rect.center())
+            };
+            harness.event(Event::MouseDown(mouse.clone()));
+            harness.event(Event::MouseUp(mouse));
+            type_text(harness, "a");
+
+            // the preedit string is part of the text while composing
+            ime_edit(harness, |handler| {
+                handler.replace_range(1..1, "ni");
+                handler.set_composition_range(Some(1..3));
+                handler.set_selection(Selection::caret(3));
+            });
+            assert_eq!(harness.data(), "ani");
+            harness.paint();
+            ime_edit(harness, |handler| {
+                assert_eq!(handler.composition_range(), Some(1..3));
+                // the candidate window goes at the caret, in window coordinates
+                let caret = handler.slice_bounding_box(3..3).unwrap();
+                assert!(rect.contains(caret.center()));
+                let start = handler.slice_bounding_box(1..1).unwrap();
+                assert_eq!(handler.hit_test_point(start.center()).idx, 1);
+            });
+
+            // committing replaces the composition and moves the caret after it
+            ime_edit(harness, |handler| {
+                handler.replace_range(1..3, "\u{4f60}");
+                assert_eq!(handler.composition_range(), None);
+                assert_eq!(handler.selection(), Selection::caret(4));
+            });
+            assert_eq!(harness.data(), "a\u{4f60}");
+            ime_edit(harness, |handler| {
+                assert_eq!(handler.selection(), Selection::caret(4));
+                // some IMEs put the caret outside of the composition
+                handler.set_composition_range(Some(0..1));
+            });
+            harness.paint();
+
+            // changing the text from elsewhere ends the composition
+            harness.update_data(|text| *text = "b".into());
+            ime_edit(harness, |handler| {
+                assert_eq!(handler.composition_range(), None);
+            });
+        });
+    }
+
+    #[test]
+    fn gutter_numbers_lines_not_rows() {
+        let [id] = widget_ids();
+        let textbox = TextBox::multiline()
+            .with_gutter(true)
+            .with_id(id)
+            .fix_size(120.0, 200.0)
+            .center();
+        let text = "a first line that is long enough to wrap\nsecond".to_string();
+
+        Harness::create_simple(text, textbox, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let origin = harness.get_state(id).layout_rect().origin();
+            let click = |harness: &mut Harness<String>, x: f64, y: f64| {
+                let mouse = MouseEvent {
+                    button: MouseButton::Left,
+                    count: 1,
+                    ..move_mouse(origin + (x, y))
+                };
+                harness.take_commands();
+                harness.event(Event::MouseDown(mouse.clone()));
+                harness.event(Event::MouseUp(mouse));
+                harness
+                    .take_commands()
+                    .iter()
+                    .find_map(|cmd| cmd.get(TextBox::GUTTER_CLICKED).copied())
+            };
+
+            let lines: Vec<_> = (0..100)
+                .filter_map(|y| click(harness, 2.0, y as f64 * 2.0))
+                .map(|click| {
+                    assert_eq!(click.widget, id);
+                    click.line
+                })
+                .collect();
+            // clicking the gutter doesn't focus the text
+            assert_eq!(harness.window().focus, None);
+            assert!(lines.windows(2).all(|pair| pair[0] <= pair[1]));
+            let first = lines.iter().filter(|line| **line == 0).count();
+            let second = lines.iter().filter(|line| **line == 1).count();
+            assert_eq!(first + second, lines.len());
+            // the first line is wrapped, so its number spans several rows
+            assert!(second > 0 && first > second * 2);
+
+            // the gutter grows with the number of digits
+            let gutter_width = |harness: &mut Harness<String>| {
+                (0..60)
+                    .take_while(|x| click(harness, *x as f64, 8.0).is_some())
+                    .count()
+            };
+            let narrow = gutter_width(harness);
+            harness.update_data(|text| *text = "\n".repeat(11));
+            harness.just_layout();
+            assert!(gutter_width(harness) > narrow);
+        });
+    }
+
+    #[test]
+    fn line_wrapping_toggle_keeps_caret_in_view() {
+        let [id] = widget_ids();
+        let textbox = TextBox::multiline()
+            .with_line_wrapping(false)
+            .with_id(id)
+            .fix_size(120.0, 200.0)
+            .center();
+        let text = "mmmmmmmmmmmmmmmmmmmmmmmmmmmmmmmmmmmmmmmmm\nshort".to_string();
+
+        Harness::create_simple(text, textbox, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let rect = harness.get_state(id).layout_rect();
+            let mouse = MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse(rect.origin() + (10.0, 8.0))
+            };
+            harness.event(Event::MouseDown(mouse.clone()));
+            harness.event(Event::MouseUp(mouse));
+            let caret = |harness: &mut Harness<String>| {
+                let mut result = None;
+                ime_edit(harness, |handler| {
+                    let pos = handler.selection().active;
+                    result = Some((pos, handler.slice_bounding_box(pos..pos).unwrap()));
+                });
+                result.unwrap()
+            };
+            let move_caret = |harness: &mut Harness<String>, movement| {
+                ime_edit(harness, |handler| {
+                    handler.handle_action(TextAction::Move(movement))
+                });
+                harness.just_layout();
+                caret(harness)
+            };
+            let home = Movement::Line(Direction::Upstream);
+            let end = Movement::Line(Direction::Downstream);
+
+            // without wrapping, the line scrolls to keep its end in view
+            let (_, start) = move_caret(harness, home);
+            let (pos, line_end) = move_caret(harness, end);
+            assert_eq!(pos, 41);
+            assert!(line_end.x0 > rect.x0 && line_end.x1 <= rect.x1);
+            assert_eq!(line_end.y0, start.y0);
+
+            // with wrapping, the end of the line is on a later row
+            harness.submit_command(TextBox::SET_LINE_WRAPPING.with(true).to(id));
+            harness.just_layout();
+            let (pos, wrapped_end) = caret(harness);
+            assert_eq!(pos, 41);
+            assert!(rect.contains(wrapped_end.origin()));
+            assert!(wrapped_end.y0 > start.y0);
+
+            // Home and End move to the ends of the row, not of the line
+            let (pos, row_start) = move_caret(harness, home);
+            assert!(pos > 0 && pos < 41);
+            assert_eq!(row_start.y0, wrapped_end.y0);
+            assert_eq!(row_start.x0, start.x0);
+            let (pos, _) = move_caret(harness, Movement::ParagraphStart);
+            assert_eq!(pos, 0);
+            // the first row is wrapped inside the word; the caret stays on it
+            let (pos, row_end) = move_caret(harness, end);
+            assert!(pos > 0 && pos < 41);
+            assert_eq!(row_end.y0, start.y0);
+            assert_eq!(move_caret(harness, end).0, pos);
+        });
+    }
+}
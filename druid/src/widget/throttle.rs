@@ -0,0 +1,186 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that limits how often a widget is updated.
+//!
+//! [`Controller`]: crate::widget::Controller
+
+use std::time::Duration;
+
+use crate::widget::Controller;
+use crate::{Data, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, TimerToken, UpdateCtx, Widget};
+use tracing::{instrument, trace};
+
+/// A [`Controller`] that passes data changes on to its child at most once
+/// per interval.
+///
+/// The first change is passed on right away. Changes during the following
+/// interval are held back, and when it is over, the child is updated once
+/// with the latest data, starting another interval. The child always ends up
+/// with the final data, one interval after the changes stop at the latest.
+///
+/// This is useful for widgets that show data that changes many times a
+/// second, such as live measurements, where repainting on every change would
+/// only waste time. Only `update` is held back: events, layout and paint
+/// still get the current data, so the child may show it early if it is
+/// repainted for another reason.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::widget::{Label, Throttle};
+/// use druid::{Widget, WidgetExt};
+///
+/// fn reading() -> impl Widget<f64> {
+///     Label::new(|value: &f64, _env: &_| format!("{:.2}", value))
+///         .controller(Throttle::new(Duration::from_millis(100)))
+/// }
+/// ```
+///
+/// [`Controller`]: crate::widget::Controller
+pub struct Throttle<T> {
+    interval: Duration,
+    timer: TimerToken,
+    /// The data the child was last updated with, while it is behind.
+    pending: Option<T>,
+}
+
+impl<T: Data> Throttle<T> {
+    /// Create a `Throttle` that updates its child at most once per `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Throttle {
+            interval,
+            timer: TimerToken::INVALID,
+            pending: None,
+        }
+    }
+
+    /// Returns `true` if a change is being held back from the child.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Throttle<T> {
+    #[instrument(
+        name = "Throttle",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Timer(token) if *token == self.timer => {
+                trace!("Throttle interval is over");
+                self.timer = TimerToken::INVALID;
+                ctx.set_handled();
+                if self.pending.is_some() {
+                    ctx.request_update();
+                }
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    #[instrument(
+        name = "Throttle",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            // Don't carry timers over if the widget is moved to a new tree.
+            self.timer = TimerToken::INVALID;
+            self.pending = None;
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "Throttle",
+        level = "trace",
+        skip(self, child, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if self.timer != TimerToken::INVALID && !ctx.env_changed() {
+            if self.pending.is_none() && !old_data.same(data) {
+                self.pending = Some(old_data.clone());
+            }
+            return;
+        }
+        let pending = self.pending.take();
+        let old_data = pending.as_ref().unwrap_or(old_data);
+        if !old_data.same(data) {
+            self.timer = ctx.request_timer(self.interval);
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::WidgetExt;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use test_log::test;
+
+    #[test]
+    fn holds_back_rapid_changes() {
+        let updates = Rc::new(Cell::new(Vec::<(u32, u32)>::new()));
+        let updates_2 = updates.clone();
+
+        let widget = ModularWidget::new(())
+            .update_fn(move |_, _, old_data: &u32, data, _| {
+                let mut seen = updates_2.take();
+                seen.push((*old_data, *data));
+                updates_2.set(seen);
+            })
+            .controller(Throttle::new(Duration::from_millis(100)));
+
+        Harness::create_simple(0u32, widget, |harness| {
+            harness.send_initial_events();
+            updates.take();
+            harness.update_data(|data| *data = 1);
+            harness.update_data(|data| *data = 2);
+            harness.update_data(|data| *data = 3);
+            assert_eq!(updates.take(), vec![(0, 1)]);
+
+            // the child catches up when the interval is over
+            let tokens: Vec<TimerToken> = harness.window().timers.keys().copied().collect();
+            for token in tokens {
+                harness.event(Event::Timer(token));
+            }
+            assert_eq!(updates.take(), vec![(1, 3)]);
+
+            // without further changes, nothing is held back
+            let tokens: Vec<TimerToken> = harness.window().timers.keys().copied().collect();
+            for token in tokens {
+                harness.event(Event::Timer(token));
+            }
+            assert!(updates.take().is_empty());
+            harness.update_data(|data| *data = 4);
+            assert_eq!(updates.take(), vec![(3, 4)]);
+        });
+    }
+}
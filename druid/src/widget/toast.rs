@@ -0,0 +1,392 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Short-lived messages shown on top of the window.
+
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::animation::Easing;
+use crate::commands::SHOW_TOAST;
+use crate::debug_state::DebugState;
+use crate::text::TextLayout;
+use crate::widget::prelude::*;
+use crate::{theme, ArcStr, Color, Insets, Key, Point, Rect, TimerToken, Vec2, WidgetPod};
+
+/// How long a toast is shown by default.
+const DEFAULT_DURATION: Duration = Duration::from_secs(4);
+/// How long a toast takes to appear or disappear.
+const ANIMATION_DURATION: Duration = Duration::from_millis(150);
+/// How far a toast slides while it appears.
+const SLIDE_DISTANCE: f64 = 12.0;
+/// The space between the toasts, and between them and the edge of the window.
+const TOAST_SPACING: f64 = 12.0;
+/// The padding between the text and the edge of a toast.
+const TOAST_INSETS: Insets = Insets::uniform_xy(12.0, 8.0);
+const TOAST_CORNER_RADIUS: f64 = 4.0;
+const MAX_TOAST_WIDTH: f64 = 400.0;
+
+/// A message for a [`ToastOverlay`], shown with the [`SHOW_TOAST`] command.
+///
+/// [`SHOW_TOAST`]: crate::commands::SHOW_TOAST
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    /// The message.
+    pub text: ArcStr,
+    /// How important the message is, which decides its color.
+    pub level: ToastLevel,
+    /// How long the message is shown, unless it is clicked first.
+    pub duration: Duration,
+}
+
+/// The importance of a [`Toast`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToastLevel {
+    /// Shown with [`TOAST_INFO_COLOR`](theme::TOAST_INFO_COLOR).
+    #[default]
+    Info,
+    /// Shown with [`TOAST_WARNING_COLOR`](theme::TOAST_WARNING_COLOR).
+    Warning,
+    /// Shown with [`TOAST_ERROR_COLOR`](theme::TOAST_ERROR_COLOR).
+    Error,
+}
+
+/// A widget that shows [`Toast`]s on top of its child.
+///
+/// This is usually the root widget of a window. The toasts are stacked at the
+/// bottom of the widget, with the newest one at the bottom, and each of them
+/// disappears once its duration has passed, or when it is clicked. They never
+/// take the focus, and don't otherwise get in the way of the child.
+///
+/// A toast is shown by submitting a [`SHOW_TOAST`] command to the window:
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::commands::SHOW_TOAST;
+/// use druid::widget::{Button, Toast, ToastLevel, ToastOverlay};
+/// use druid::Widget;
+///
+/// fn root() -> impl Widget<()> {
+///     ToastOverlay::new(Button::new("Save").on_click(|ctx, _, _| {
+///         ctx.submit_command(SHOW_TOAST.with(Toast {
+///             text: "Saved".into(),
+///             level: ToastLevel::Info,
+///             duration: Duration::from_secs(2),
+///         }))
+///     }))
+/// }
+/// ```
+///
+/// [`SHOW_TOAST`]: crate::commands::SHOW_TOAST
+pub struct ToastOverlay<T, W> {
+    child: WidgetPod<T, W>,
+    toasts: Vec<ActiveToast>,
+}
+
+/// A toast that is on screen.
+struct ActiveToast {
+    level: ToastLevel,
+    layout: TextLayout<ArcStr>,
+    timer: TimerToken,
+    /// How far the toast has appeared, from 0 to 1.
+    shown: f64,
+    dismissed: bool,
+    rect: Rect,
+}
+
+impl Toast {
+    /// Create an [`Info`](ToastLevel::Info) toast that is shown for four seconds.
+    pub fn new(text: impl Into<ArcStr>) -> Toast {
+        Toast {
+            text: text.into(),
+            level: ToastLevel::Info,
+            duration: DEFAULT_DURATION,
+        }
+    }
+
+    /// Builder-style method to set the [`ToastLevel`].
+    pub fn with_level(mut self, level: ToastLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Builder-style method to set how long the toast is shown.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+impl ToastLevel {
+    fn color_key(self) -> Key<Color> {
+        match self {
+            ToastLevel::Info => theme::TOAST_INFO_COLOR,
+            ToastLevel::Warning => theme::TOAST_WARNING_COLOR,
+            ToastLevel::Error => theme::TOAST_ERROR_COLOR,
+        }
+    }
+}
+
+impl<T, W: Widget<T>> ToastOverlay<T, W> {
+    /// Create a widget that shows toasts on top of `child`.
+    pub fn new(child: W) -> Self {
+        ToastOverlay {
+            child: WidgetPod::new(child),
+            toasts: Vec::new(),
+        }
+    }
+
+    /// The toast at `pos`, if any.
+    fn toast_at(&mut self, pos: Point) -> Option<&mut ActiveToast> {
+        self.toasts
+            .iter_mut()
+            .find(|toast| !toast.dismissed && toast.rect.contains(pos))
+    }
+}
+
+impl ActiveToast {
+    fn dismiss(&mut self, ctx: &mut EventCtx) {
+        self.dismissed = true;
+        ctx.request_anim_frame();
+    }
+
+    fn is_animating(&self) -> bool {
+        if self.dismissed {
+            self.shown > 0.0
+        } else {
+            self.shown < 1.0
+        }
+    }
+
+    /// How far the toast has appeared, with easing.
+    fn eased(&self) -> f64 {
+        Easing::EaseOut.apply(self.shown)
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for ToastOverlay<T, W> {
+    #[instrument(
+        name = "ToastOverlay",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(SHOW_TOAST) => {
+                let toast = cmd.get_unchecked(SHOW_TOAST);
+                trace!("Showing toast {:?}", toast.text);
+                let mut layout = TextLayout::from_text(toast.text.clone());
+                layout.set_text_color(theme::TEXT_COLOR);
+                self.toasts.push(ActiveToast {
+                    level: toast.level,
+                    layout,
+                    timer: ctx.request_timer(toast.duration),
+                    shown: 0.0,
+                    dismissed: false,
+                    rect: Rect::ZERO,
+                });
+                ctx.request_layout();
+                ctx.request_anim_frame();
+                ctx.set_handled();
+                return;
+            }
+            Event::Timer(token) => {
+                if let Some(toast) = self.toasts.iter_mut().find(|t| t.timer == *token) {
+                    toast.dismiss(ctx);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::MouseDown(mouse) => {
+                if let Some(toast) = self.toast_at(mouse.pos) {
+                    toast.dismiss(ctx);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::AnimFrame(interval) if self.toasts.iter().any(ActiveToast::is_animating) => {
+                let step = *interval as f64 / ANIMATION_DURATION.as_nanos() as f64;
+                for toast in &mut self.toasts {
+                    toast.shown = if toast.dismissed {
+                        (toast.shown - step).max(0.0)
+                    } else {
+                        (toast.shown + step).min(1.0)
+                    };
+                }
+                self.toasts
+                    .retain(|toast| !toast.dismissed || toast.shown > 0.0);
+                if self.toasts.iter().any(ActiveToast::is_animating) {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_layout();
+            }
+            _ => (),
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "ToastOverlay",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "ToastOverlay",
+        level = "trace",
+        skip(self, ctx, _old, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        if self
+            .toasts
+            .iter_mut()
+            .any(|toast| toast.layout.needs_rebuild_after_update(ctx))
+        {
+            ctx.request_layout();
+        }
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "ToastOverlay", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+
+        let wrap_width =
+            (size.width - 2.0 * TOAST_SPACING).min(MAX_TOAST_WIDTH) - TOAST_INSETS.x_value();
+        // Stack the toasts upwards from the bottom, newest first. Toasts that are
+        // appearing or disappearing take up part of their space, so that the
+        // others move smoothly.
+        let mut bottom = size.height - TOAST_SPACING;
+        for toast in self.toasts.iter_mut().rev() {
+            toast.layout.set_wrap_width(wrap_width.max(0.0));
+            toast.layout.rebuild_if_needed(ctx.text(), env);
+            let toast_size = toast.layout.size() + TOAST_INSETS.size();
+            let origin = Point::new(
+                (size.width - toast_size.width) / 2.0,
+                bottom - toast_size.height,
+            );
+            toast.rect = Rect::from_origin_size(origin, toast_size);
+            bottom -= (toast_size.height + TOAST_SPACING) * toast.eased();
+        }
+
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "ToastOverlay", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+
+        for toast in &self.toasts {
+            let eased = toast.eased();
+            let rect = toast.rect + Vec2::new(0.0, SLIDE_DISTANCE * (1.0 - eased));
+            let background = env.get(toast.level.color_key());
+            let opacity = background.as_rgba().3 * eased;
+            ctx.fill(
+                rect.to_rounded_rect(TOAST_CORNER_RADIUS),
+                &background.with_alpha(opacity),
+            );
+            // The text only appears once the toast is mostly there, as it can't be faded.
+            if eased > 0.5 {
+                toast
+                    .layout
+                    .draw(ctx, rect.origin() + (TOAST_INSETS.x0, TOAST_INSETS.y0));
+            }
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::tests::move_mouse;
+    use crate::{MouseButton, MouseEvent};
+    use test_log::test;
+
+    fn counter() -> impl Widget<u32> {
+        ModularWidget::new(())
+            .event_fn(|_, _, event, data: &mut u32, _| {
+                if let Event::MouseDown(_) = event {
+                    *data += 1;
+                }
+            })
+            .layout_fn(|_, _, bc, _, _| bc.max())
+    }
+
+    fn click(harness: &mut Harness<u32>, pos: impl Into<Point>) {
+        harness.event(Event::MouseDown(MouseEvent {
+            button: MouseButton::Left,
+            count: 1,
+            ..move_mouse(pos)
+        }));
+    }
+
+    #[test]
+    fn toasts_stack_and_dismiss() {
+        // Just above the bottom edge, where the newest toast is.
+        let bottom = (200.0, 400.0 - TOAST_SPACING - 4.0);
+        let finish_animation = |harness: &mut Harness<u32>| {
+            harness.event(Event::AnimFrame(ANIMATION_DURATION.as_nanos() as u64));
+            harness.just_layout();
+        };
+
+        Harness::create_simple(0, ToastOverlay::new(counter()), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.submit_command(SHOW_TOAST.with(Toast::new("first")));
+            harness.submit_command(
+                SHOW_TOAST.with(Toast::new("second").with_level(ToastLevel::Error)),
+            );
+            finish_animation(harness);
+
+            // Clicking the newest toast dismisses it, and the older one moves down.
+            click(harness, bottom);
+            assert_eq!(*harness.data(), 0);
+            finish_animation(harness);
+            click(harness, bottom);
+            assert_eq!(*harness.data(), 0);
+            finish_animation(harness);
+
+            // Clicks outside of the toasts reach the child.
+            click(harness, bottom);
+            assert_eq!(*harness.data(), 1);
+
+            // Toasts expire on their own.
+            harness.submit_command(SHOW_TOAST.with(Toast::new("third")));
+            finish_animation(harness);
+            let timers: Vec<_> = harness.window().timers.keys().copied().collect();
+            for token in timers {
+                harness.event(Event::Timer(token));
+            }
+            finish_animation(harness);
+            click(harness, bottom);
+            assert_eq!(*harness.data(), 2);
+        });
+    }
+}
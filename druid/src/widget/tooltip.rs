@@ -0,0 +1,333 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that shows a tooltip when its child is hovered.
+
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::commands::CLOSE_WINDOW;
+use crate::text::TextLayout;
+use crate::widget::prelude::*;
+use crate::widget::{Controller, Label, LabelText};
+use crate::{
+    theme, ArcStr, Command, Data, Insets, Point, TimerToken, Vec2, WidgetExt, WindowConfig,
+    WindowId, WindowLevel, WindowSizePolicy,
+};
+
+/// How far the tooltip is placed from the pointer.
+const CURSOR_OFFSET: Vec2 = Vec2::new(12.0, 16.0);
+/// How far the pointer can move before a visible tooltip is hidden.
+const MOVE_THRESHOLD: f64 = 8.0;
+/// The padding between the text and the edge of the tooltip.
+const TOOLTIP_INSETS: Insets = Insets::uniform_xy(6.0, 3.0);
+/// The horizontal padding [`Label`] puts around its text.
+const LABEL_X_PADDING: f64 = 2.0;
+const TOOLTIP_CORNER_RADIUS: f64 = 3.0;
+
+/// A [`Controller`] that shows a small floating label after the pointer has
+/// rested on its child for a while.
+///
+/// The tooltip appears after [`TOOLTIP_DELAY`], near the pointer, and is kept
+/// within the bounds of the window. It is hidden again when the pointer
+/// moves away from where the tooltip appeared, when the child is clicked, or
+/// when the pointer leaves the child. After a click, the tooltip is not shown
+/// again until the pointer has left the child.
+///
+/// The tooltip is shown in a sub-window with [`WindowLevel::Tooltip`], and its
+/// background is [`TOOLTIP_BACKGROUND_COLOR`].
+///
+/// This is more conveniently used through [`WidgetExt::tooltip`] and
+/// [`WidgetExt::tooltip_lazy`].
+///
+/// Like [`EventCtx::new_sub_window`], the tooltip is kept in sync with the
+/// data of the nearest [`WidgetPod`], so it should be applied outside of any
+/// lens on the child.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::Button;
+/// use druid::{Widget, WidgetExt};
+///
+/// fn save_button() -> impl Widget<u32> {
+///     Button::new("Save").tooltip_lazy(|changes: &u32| format!("{} unsaved changes", changes))
+/// }
+/// ```
+///
+/// [`TOOLTIP_DELAY`]: crate::theme::TOOLTIP_DELAY
+/// [`TOOLTIP_BACKGROUND_COLOR`]: crate::theme::TOOLTIP_BACKGROUND_COLOR
+/// [`WidgetExt::tooltip`]: crate::WidgetExt::tooltip
+/// [`WidgetExt::tooltip_lazy`]: crate::WidgetExt::tooltip_lazy
+/// [`WidgetPod`]: crate::WidgetPod
+pub struct Tooltip<T> {
+    text: LabelText<T>,
+    state: TooltipState,
+}
+
+enum TooltipState {
+    /// The pointer is not over the child.
+    Fresh,
+    /// Waiting for the pointer to rest for the delay; each move restarts it.
+    Waiting { token: TimerToken, pos: Point },
+    /// The tooltip was shown at `pos`, in window coordinates.
+    Showing { window: WindowId, pos: Point },
+    /// The child was clicked; nothing is shown until the pointer leaves.
+    Dismissed,
+}
+
+impl<T: Data> Tooltip<T> {
+    /// Create a new `Tooltip` with the given text.
+    ///
+    /// Like a [`Label`], the text can be static, localized, or computed from
+    /// the data.
+    pub fn new(text: impl Into<LabelText<T>>) -> Self {
+        Tooltip {
+            text: text.into(),
+            state: TooltipState::Fresh,
+        }
+    }
+
+    /// Returns `true` if the tooltip is currently visible.
+    pub fn is_showing(&self) -> bool {
+        matches!(self.state, TooltipState::Showing { .. })
+    }
+
+    fn show(&mut self, ctx: &mut EventCtx, pos: Point, data: &T, env: &Env) -> WindowId {
+        self.text.resolve(data, env);
+        let mut layout = TextLayout::<ArcStr>::from_text(self.text.display_text());
+        layout.rebuild_if_needed(ctx.text(), env);
+        let size = layout.size() + TOOLTIP_INSETS.size() + Size::new(LABEL_X_PADDING * 2.0, 0.0);
+        let origin = tooltip_origin(pos, size, ctx.window().get_size());
+        trace!("Showing tooltip at {}", origin);
+
+        let tip = Label::new(self.text.clone())
+            .padding(TOOLTIP_INSETS)
+            .background(theme::TOOLTIP_BACKGROUND_COLOR)
+            .rounded(TOOLTIP_CORNER_RADIUS);
+        ctx.new_sub_window(
+            WindowConfig::default()
+                .show_titlebar(false)
+                .resizable(false)
+                .window_size_policy(WindowSizePolicy::Content)
+                .set_level(WindowLevel::Tooltip(ctx.window().clone()))
+                .set_position(origin),
+            tip,
+            data.clone(),
+            env.clone(),
+        )
+    }
+
+    /// The command that closes the tooltip window, if it is visible.
+    fn close_command(&self) -> Option<Command> {
+        match self.state {
+            TooltipState::Showing { window, .. } => Some(CLOSE_WINDOW.to(window)),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the origin of a tooltip of `size` for a pointer at `pos`, keeping
+/// it within a window of `window_size` if possible.
+fn tooltip_origin(pos: Point, size: Size, window_size: Size) -> Point {
+    let origin = pos + CURSOR_OFFSET;
+    if window_size.is_empty() {
+        return origin;
+    }
+    let max = (window_size - size).to_vec2().to_point();
+    Point::new(origin.x.min(max.x).max(0.0), origin.y.min(max.y).max(0.0))
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Tooltip<T> {
+    #[instrument(
+        name = "Tooltip",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match (&self.state, event) {
+            (TooltipState::Fresh, Event::MouseMove(mouse)) if ctx.is_hot() => {
+                let delay = Duration::from_millis(env.get(theme::TOOLTIP_DELAY));
+                self.state = TooltipState::Waiting {
+                    token: ctx.request_timer(delay),
+                    pos: mouse.window_pos,
+                };
+            }
+            (TooltipState::Waiting { .. }, Event::MouseMove(mouse)) => {
+                // Start the delay over; the previous timer is ignored when it
+                // fires, so the tooltip only shows once the pointer rests.
+                let delay = Duration::from_millis(env.get(theme::TOOLTIP_DELAY));
+                self.state = TooltipState::Waiting {
+                    token: ctx.request_timer(delay),
+                    pos: mouse.window_pos,
+                };
+            }
+            (TooltipState::Waiting { token, pos }, Event::Timer(timer)) if token == timer => {
+                let pos = *pos;
+                let window = self.show(ctx, pos, data, env);
+                self.state = TooltipState::Showing { window, pos };
+                ctx.set_handled();
+                return;
+            }
+            (TooltipState::Showing { pos, .. }, Event::MouseMove(mouse))
+                if (mouse.window_pos - *pos).hypot() > MOVE_THRESHOLD =>
+            {
+                if let Some(cmd) = self.close_command() {
+                    ctx.submit_command(cmd);
+                }
+                let delay = Duration::from_millis(env.get(theme::TOOLTIP_DELAY));
+                self.state = TooltipState::Waiting {
+                    token: ctx.request_timer(delay),
+                    pos: mouse.window_pos,
+                };
+            }
+            (_, Event::MouseDown(_)) => {
+                if let Some(cmd) = self.close_command() {
+                    ctx.submit_command(cmd);
+                }
+                self.state = TooltipState::Dismissed;
+            }
+            _ => (),
+        }
+        child.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "Tooltip",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::HotChanged(false) = event {
+            if let Some(cmd) = self.close_command() {
+                ctx.submit_command(cmd);
+            }
+            self.state = TooltipState::Fresh;
+        }
+        child.lifecycle(ctx, event, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::NEW_SUB_WINDOW;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{Record, Recording, TestWidgetExt};
+    use crate::tests::move_mouse;
+    use crate::widget::SizedBox;
+    use crate::{MouseButton, MouseEvent};
+    use test_log::test;
+
+    #[test]
+    fn origin_is_clamped_to_window() {
+        let window = Size::new(400.0, 300.0);
+        let size = Size::new(100.0, 20.0);
+        assert_eq!(
+            tooltip_origin(Point::new(10.0, 10.0), size, window),
+            Point::ORIGIN + CURSOR_OFFSET + Vec2::new(10.0, 10.0)
+        );
+        assert_eq!(
+            tooltip_origin(Point::new(390.0, 295.0), size, window),
+            Point::new(300.0, 280.0)
+        );
+        // A tooltip larger than the window sticks to the top left.
+        assert_eq!(
+            tooltip_origin(Point::new(390.0, 295.0), Size::new(500.0, 20.0), window),
+            Point::new(0.0, 280.0)
+        );
+    }
+
+    #[test]
+    fn shows_after_delay_and_hides() {
+        let recording = Recording::default();
+        let id = WidgetId::next();
+        let widget = SizedBox::empty()
+            .fix_size(100.0, 100.0)
+            .controller(Tooltip::new("Help"))
+            .with_id(id)
+            .center()
+            .record(&recording);
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let origin = harness.get_state(id).window_origin();
+            let commands = |recording: &Recording| {
+                recording
+                    .drain()
+                    .filter_map(|r| match r {
+                        Record::E(Event::Command(cmd)) => Some(cmd),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            harness.event(Event::MouseMove(move_mouse(origin + (40.0, 40.0))));
+            let first: Vec<_> = harness.window().timers.keys().copied().collect();
+            assert_eq!(first.len(), 1);
+
+            // Moving restarts the delay, so the first timer doesn't show anything.
+            harness.event(Event::MouseMove(move_mouse(origin + (50.0, 50.0))));
+            let timers: Vec<_> = harness
+                .window()
+                .timers
+                .keys()
+                .copied()
+                .filter(|token| *token != first[0])
+                .collect();
+            assert_eq!(timers.len(), 1);
+            recording.clear();
+            harness.event(Event::Timer(first[0]));
+            assert!(!commands(&recording)
+                .iter()
+                .any(|cmd| cmd.is(NEW_SUB_WINDOW)));
+
+            harness.event(Event::Timer(timers[0]));
+            let shown = commands(&recording);
+            assert!(shown.iter().any(|cmd| cmd.is(NEW_SUB_WINDOW)));
+            let window = harness.get_state(id).sub_window_hosts[0].0;
+
+            // Small movements don't hide the tooltip; larger ones do.
+            harness.event(Event::MouseMove(move_mouse(origin + (52.0, 52.0))));
+            assert!(!commands(&recording).iter().any(|cmd| cmd.is(CLOSE_WINDOW)));
+            harness.event(Event::MouseMove(move_mouse(origin + (70.0, 50.0))));
+            assert!(commands(&recording)
+                .iter()
+                .any(|cmd| cmd.is(CLOSE_WINDOW) && cmd.target() == window.into()));
+
+            // A click cancels the pending tooltip for good.
+            harness.event(Event::MouseDown(MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse(origin + (70.0, 50.0))
+            }));
+            let timers: Vec<_> = harness.window().timers.keys().copied().collect();
+            for token in timers {
+                harness.event(Event::Timer(token));
+            }
+            assert!(!commands(&recording)
+                .iter()
+                .any(|cmd| cmd.is(NEW_SUB_WINDOW)));
+        });
+    }
+}
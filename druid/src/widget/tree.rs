@@ -0,0 +1,919 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A tree widget that only creates widgets for the visible rows.
+
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use tracing::{instrument, trace};
+
+use crate::commands::{VIEWPORT_CHANGED, WATCH_VIEWPORT};
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, Line};
+use crate::widget::prelude::*;
+use crate::{theme, KbKey, Point, Rect, Selector, WidgetPod};
+
+/// The number of rows that are created above and below the visible area.
+const OVERSCAN: usize = 3;
+const DEFAULT_ROW_HEIGHT: f64 = 24.0;
+const DEFAULT_INDENT: f64 = 16.0;
+/// The size of the triangle shown next to branches.
+const DISCLOSURE_SIZE: f64 = 8.0;
+
+/// Sent by a [`Tree`] to itself when it is added, so it can ask the
+/// enclosing scroll for the viewport; notifications can only be sent from `event`.
+const WATCH_SCROLL: Selector = Selector::new("druid.builtin.tree-watch-scroll");
+
+/// The data of a node in a [`Tree`].
+///
+/// A node is a branch if it can have children, and a leaf otherwise. Whether
+/// a branch is expanded is part of the node, so it is kept in the
+/// application's data, and can be changed from outside of the tree.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::TreeNode;
+/// use druid::Data;
+///
+/// #[derive(Clone, Data)]
+/// struct Entry {
+///     name: String,
+///     is_dir: bool,
+///     expanded: bool,
+///     children: Arc<Vec<Entry>>,
+/// }
+///
+/// impl TreeNode for Entry {
+///     fn children_count(&self) -> usize {
+///         self.children.len()
+///     }
+///
+///     fn get_child(&self, index: usize) -> &Entry {
+///         &self.children[index]
+///     }
+///
+///     fn for_child_mut(&mut self, index: usize, f: impl FnOnce(&mut Entry)) {
+///         let mut child = self.children[index].clone();
+///         f(&mut child);
+///         if !child.same(&self.children[index]) {
+///             Arc::make_mut(&mut self.children)[index] = child;
+///         }
+///     }
+///
+///     fn is_branch(&self) -> bool {
+///         self.is_dir
+///     }
+///
+///     fn is_expanded(&self) -> bool {
+///         self.expanded
+///     }
+///
+///     fn set_expanded(&mut self, expanded: bool) {
+///         self.expanded = expanded;
+///     }
+/// }
+/// ```
+pub trait TreeNode: Data {
+    /// Returns the number of children of this node.
+    fn children_count(&self) -> usize;
+
+    /// Returns the child at `index`.
+    fn get_child(&self, index: usize) -> &Self;
+
+    /// Call `f` with a mutable reference to the child at `index`.
+    ///
+    /// Implementations that share their children, for instance in an `Arc`,
+    /// should only replace them if `f` changed the child.
+    fn for_child_mut(&mut self, index: usize, f: impl FnOnce(&mut Self));
+
+    /// Returns `true` if this node can have children.
+    ///
+    /// By default, nodes with children are branches.
+    fn is_branch(&self) -> bool {
+        self.children_count() > 0
+    }
+
+    /// Returns `true` if the children of this node are shown.
+    fn is_expanded(&self) -> bool;
+
+    /// Show or hide the children of this node.
+    fn set_expanded(&mut self, expanded: bool);
+}
+
+type LoadFn<T> = dyn Fn(&mut EventCtx, &mut T, &Env);
+
+/// A row of the tree that is shown, because all of its ancestors are expanded.
+#[derive(Debug, Clone, PartialEq)]
+struct VisibleRow {
+    /// The indices of the node and its ancestors, from the top.
+    path: Vec<usize>,
+    branch: bool,
+    expanded: bool,
+}
+
+impl VisibleRow {
+    /// The depth of the row; the children of the root are at depth `0`.
+    fn depth(&self) -> usize {
+        self.path.len() - 1
+    }
+}
+
+/// A vertical tree widget for large hierarchies.
+///
+/// The root node is not shown; its children are the top-level rows of the
+/// tree. Each row shows the widget created by the closure for its node,
+/// indented by its depth, with a triangle before branches that shows whether
+/// they are expanded. Clicking the triangle, double-clicking a row or
+/// pressing Enter expands or collapses a branch, which sets the expansion
+/// state in the data through [`TreeNode::set_expanded`].
+///
+/// Children can be loaded lazily: when a branch without children is
+/// expanded, the closure given to [`with_loader`] is called to add them to
+/// the node's data, for instance from a file system or a server.
+///
+/// Like a [`LazyList`], only the rows that are visible have a widget, plus a
+/// few above and below them. The tree has to be inside a [`Scroll`] to know
+/// which rows are visible, and works best as the direct child of the scroll.
+/// Every row has the same height, set with [`with_row_height`].
+///
+/// Clicking a row selects it; the selected row is painted with the
+/// [`SELECTED_ITEM_BACKGROUND_COLOR`] behind it, and the tree submits a
+/// [`Tree::SELECTION_CHANGED`] notification. When the tree has focus, the
+/// arrow keys move the selection: Up and Down go to the previous and next
+/// row, Right expands a branch or goes to its first child, and Left
+/// collapses a branch or goes to the parent. Selection can be turned off with
+/// [`selectable`], and the lines that show the depth of rows with
+/// [`with_indent_guides`].
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use druid::widget::TreeNode;
+/// # use druid::Data;
+/// # #[derive(Clone, Data)]
+/// # struct Entry { name: String, expanded: bool, children: Arc<Vec<Entry>> }
+/// # impl TreeNode for Entry {
+/// #     fn children_count(&self) -> usize { self.children.len() }
+/// #     fn get_child(&self, index: usize) -> &Entry { &self.children[index] }
+/// #     fn for_child_mut(&mut self, index: usize, f: impl FnOnce(&mut Entry)) {
+/// #         f(&mut Arc::make_mut(&mut self.children)[index])
+/// #     }
+/// #     fn is_expanded(&self) -> bool { self.expanded }
+/// #     fn set_expanded(&mut self, expanded: bool) { self.expanded = expanded }
+/// # }
+/// use druid::widget::{Label, Scroll, Tree};
+/// use druid::Widget;
+///
+/// fn file_tree() -> impl Widget<Entry> {
+///     let tree = Tree::new(|| Label::dynamic(|entry: &Entry, _| entry.name.clone()))
+///         .with_row_height(20.0);
+///     Scroll::new(tree).vertical()
+/// }
+/// ```
+///
+/// [`with_loader`]: Tree::with_loader
+/// [`LazyList`]: super::LazyList
+/// [`Scroll`]: super::Scroll
+/// [`with_row_height`]: Tree::with_row_height
+/// [`SELECTED_ITEM_BACKGROUND_COLOR`]: theme::SELECTED_ITEM_BACKGROUND_COLOR
+/// [`selectable`]: Tree::selectable
+/// [`with_indent_guides`]: Tree::with_indent_guides
+pub struct Tree<T> {
+    closure: Box<dyn Fn() -> Box<dyn Widget<T>>>,
+    loader: Option<Box<LoadFn<T>>>,
+    visible: Vec<VisibleRow>,
+    /// The widgets for the visible rows starting at `first`.
+    rows: VecDeque<WidgetPod<T, Box<dyn Widget<T>>>>,
+    first: usize,
+    /// The index of the selected row in `visible`.
+    selected: Option<usize>,
+    row_height: f64,
+    indent: f64,
+    selectable: bool,
+    indent_guides: bool,
+    viewport: Rect,
+}
+
+impl Tree<()> {
+    /// The notification submitted when the selected row changes, with the
+    /// path of its node: the index of each of its ancestors among their
+    /// siblings, from the top, ending with the node's own index.
+    pub const SELECTION_CHANGED: Selector<Vec<usize>> =
+        Selector::new("druid-builtin.tree-selection-changed");
+}
+
+impl<T: TreeNode> Tree<T> {
+    /// Create a new tree widget. The closure is called every time a new
+    /// widget is needed for a row.
+    pub fn new<W: Widget<T> + 'static>(closure: impl Fn() -> W + 'static) -> Self {
+        Tree {
+            closure: Box::new(move || Box::new(closure())),
+            loader: None,
+            visible: Vec::new(),
+            rows: VecDeque::new(),
+            first: 0,
+            selected: None,
+            row_height: DEFAULT_ROW_HEIGHT,
+            indent: DEFAULT_INDENT,
+            selectable: true,
+            indent_guides: true,
+            viewport: Rect::ZERO,
+        }
+    }
+
+    /// Builder-style method to load the children of branches when they are
+    /// first expanded.
+    ///
+    /// The closure is called with a branch that has no children when it is
+    /// expanded, and should add them to it. It can also start loading them in
+    /// the background, and add them later, for instance with an
+    /// [`ExtEventSink`].
+    ///
+    /// [`ExtEventSink`]: crate::ExtEventSink
+    pub fn with_loader(mut self, loader: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        self.loader = Some(Box::new(loader));
+        self
+    }
+
+    /// Builder-style method to set the height of the rows.
+    ///
+    /// The default is `24.0`.
+    pub fn with_row_height(mut self, height: f64) -> Self {
+        assert!(height > 0.0, "row height must be positive");
+        self.row_height = height;
+        self
+    }
+
+    /// Builder-style method to set how much each level of the tree is
+    /// indented.
+    ///
+    /// The default is `16.0`.
+    pub fn with_indent(mut self, indent: f64) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Builder-style method to set whether rows can be selected.
+    ///
+    /// The default is `true`.
+    pub fn selectable(mut self, selectable: bool) -> Self {
+        self.selectable = selectable;
+        self
+    }
+
+    /// Builder-style method to set whether lines are drawn that show the
+    /// depth of the rows.
+    ///
+    /// The default is `true`.
+    pub fn with_indent_guides(mut self, indent_guides: bool) -> Self {
+        self.indent_guides = indent_guides;
+        self
+    }
+
+    /// Returns the path of the selected node, if any.
+    ///
+    /// See [`Tree::SELECTION_CHANGED`] for what the path contains.
+    pub fn selected_path(&self) -> Option<&[usize]> {
+        self.selected.map(|index| &self.visible[index].path[..])
+    }
+
+    /// Returns the range of visible rows that currently have a widget.
+    pub fn created_range(&self) -> Range<usize> {
+        self.first..self.first + self.rows.len()
+    }
+
+    /// Find the rows to show, keeping the same node selected, or its closest
+    /// visible ancestor.
+    fn update_visible(&mut self, root: &T) {
+        let selected = self.selected_path().map(<[usize]>::to_vec);
+        self.visible.clear();
+        add_visible_rows(root, &mut Vec::new(), &mut self.visible);
+        trace!("{} rows are visible", self.visible.len());
+
+        self.selected = selected.and_then(|mut path| {
+            while !path.is_empty() {
+                if let Some(index) = self.visible.iter().position(|row| row.path == path) {
+                    return Some(index);
+                }
+                path.pop();
+            }
+            None
+        });
+    }
+
+    /// The range of rows that should have a widget.
+    fn wanted_range(&self) -> Range<usize> {
+        let len = self.visible.len();
+        let start = (self.viewport.y0 / self.row_height).floor().max(0.0) as usize;
+        let end = (self.viewport.y1 / self.row_height).ceil().max(0.0) as usize;
+        start.saturating_sub(OVERSCAN).min(len)..(end + OVERSCAN).min(len)
+    }
+
+    /// Create, reuse or remove widgets, so that there is one for each of the
+    /// wanted rows.
+    ///
+    /// Returns `true` if widgets were created or removed.
+    fn update_rows(&mut self) -> bool {
+        let wanted = self.wanted_range();
+        if wanted == self.created_range() {
+            return false;
+        }
+
+        let mut spare = Vec::new();
+        while !self.rows.is_empty() && !wanted.contains(&self.first) {
+            spare.extend(self.rows.pop_front());
+            self.first += 1;
+        }
+        while !self.rows.is_empty() && !wanted.contains(&(self.created_range().end - 1)) {
+            spare.extend(self.rows.pop_back());
+        }
+        if self.rows.is_empty() {
+            self.first = wanted.start;
+        }
+
+        let mut children_changed = false;
+        let closure = &self.closure;
+        let mut next_row = || {
+            spare.pop().unwrap_or_else(|| {
+                children_changed = true;
+                WidgetPod::new(closure())
+            })
+        };
+        while self.first > wanted.start {
+            self.rows.push_front(next_row());
+            self.first -= 1;
+        }
+        while self.first + self.rows.len() < wanted.end {
+            self.rows.push_back(next_row());
+        }
+        children_changed || !spare.is_empty()
+    }
+
+    fn row_rect(&self, index: usize, width: f64) -> Rect {
+        let y = index as f64 * self.row_height;
+        Rect::new(0.0, y, width, y + self.row_height)
+    }
+
+    /// Select the row at `index`, and scroll it into view.
+    fn select(&mut self, ctx: &mut EventCtx, index: usize) {
+        if self.selected != Some(index) {
+            self.selected = Some(index);
+            let path = self.visible[index].path.clone();
+            ctx.submit_notification(Tree::SELECTION_CHANGED.with(path));
+            ctx.request_paint();
+        }
+        ctx.scroll_area_to_view(self.row_rect(index, ctx.size().width));
+    }
+
+    /// Expand or collapse the branch at `index`, loading its children if needed.
+    fn set_expanded(
+        &mut self,
+        ctx: &mut EventCtx,
+        data: &mut T,
+        env: &Env,
+        index: usize,
+        expanded: bool,
+    ) {
+        let row = &self.visible[index];
+        if !row.branch || row.expanded == expanded {
+            return;
+        }
+        let loader = &self.loader;
+        with_node_mut(data, &row.path, |node| {
+            node.set_expanded(expanded);
+            if expanded && node.children_count() == 0 {
+                if let Some(loader) = loader {
+                    loader(ctx, node, env);
+                }
+            }
+        });
+    }
+
+    /// Handle a key press, marking the event as handled if it was used.
+    fn key_down(&mut self, ctx: &mut EventCtx, key: &KbKey, data: &mut T, env: &Env) {
+        let len = self.visible.len();
+        if len == 0 {
+            return;
+        }
+        let selected = match self.selected {
+            Some(index) => index,
+            None => {
+                if matches!(key, KbKey::ArrowUp | KbKey::ArrowDown) {
+                    self.select(ctx, 0);
+                    ctx.set_handled();
+                }
+                return;
+            }
+        };
+        let row = self.visible[selected].clone();
+        match key {
+            KbKey::ArrowDown if selected + 1 < len => self.select(ctx, selected + 1),
+            KbKey::ArrowUp if selected > 0 => self.select(ctx, selected - 1),
+            KbKey::Home => self.select(ctx, 0),
+            KbKey::End => self.select(ctx, len - 1),
+            KbKey::ArrowRight if row.branch && !row.expanded => {
+                self.set_expanded(ctx, data, env, selected, true)
+            }
+            KbKey::ArrowRight if row.expanded => {
+                // the next row is the first child, if there is one
+                let has_child = self
+                    .visible
+                    .get(selected + 1)
+                    .map(|next| next.path.len() > row.path.len())
+                    .unwrap_or(false);
+                if has_child {
+                    self.select(ctx, selected + 1);
+                }
+            }
+            KbKey::ArrowLeft if row.expanded => self.set_expanded(ctx, data, env, selected, false),
+            KbKey::ArrowLeft if row.depth() > 0 => {
+                let parent = &row.path[..row.depth()];
+                if let Some(index) = self.visible[..selected]
+                    .iter()
+                    .rposition(|row| row.path == parent)
+                {
+                    self.select(ctx, index);
+                }
+            }
+            KbKey::Enter if row.branch => {
+                let expanded = !row.expanded;
+                self.set_expanded(ctx, data, env, selected, expanded)
+            }
+            KbKey::ArrowDown | KbKey::ArrowUp | KbKey::ArrowLeft | KbKey::ArrowRight => (),
+            _ => return,
+        }
+        ctx.set_handled();
+    }
+
+    fn paint_row_decorations(&self, ctx: &mut PaintCtx, index: usize, env: &Env) {
+        let row = &self.visible[index];
+        let rect = self.row_rect(index, ctx.size().width);
+        if self.selectable && self.selected == Some(index) {
+            ctx.fill(rect, &env.get(theme::SELECTED_ITEM_BACKGROUND_COLOR));
+        }
+        if self.indent_guides {
+            let color = env.get(theme::BORDER_DARK);
+            for level in 0..row.depth() {
+                let x = (level as f64 + 0.5) * self.indent;
+                ctx.stroke(Line::new((x, rect.y0), (x, rect.y1)), &color, 1.0);
+            }
+        }
+        if row.branch {
+            let center = Point::new((row.depth() as f64 + 0.5) * self.indent, rect.center().y);
+            let half = DISCLOSURE_SIZE / 2.0;
+            let mut arrow = BezPath::new();
+            if row.expanded {
+                arrow.move_to(center + (-half, -half / 2.0));
+                arrow.line_to(center + (half, -half / 2.0));
+                arrow.line_to(center + (0.0, half / 2.0));
+            } else {
+                arrow.move_to(center + (-half / 2.0, -half));
+                arrow.line_to(center + (half / 2.0, 0.0));
+                arrow.line_to(center + (-half / 2.0, half));
+            }
+            arrow.close_path();
+            ctx.fill(arrow, &env.get(theme::FOREGROUND_DARK));
+        }
+    }
+}
+
+/// Add the visible descendants of `node`, whose path is `path`, to `rows`.
+fn add_visible_rows<T: TreeNode>(node: &T, path: &mut Vec<usize>, rows: &mut Vec<VisibleRow>) {
+    for index in 0..node.children_count() {
+        let child = node.get_child(index);
+        path.push(index);
+        rows.push(VisibleRow {
+            path: path.clone(),
+            branch: child.is_branch(),
+            expanded: child.is_branch() && child.is_expanded(),
+        });
+        if child.is_expanded() {
+            add_visible_rows(child, path, rows);
+        }
+        path.pop();
+    }
+}
+
+/// The descendant of `node` at `path`.
+fn node_at<'a, T: TreeNode>(node: &'a T, path: &[usize]) -> &'a T {
+    path.iter().fold(node, |node, index| node.get_child(*index))
+}
+
+/// Call `f` with the descendant of `node` at `path`.
+fn with_node_mut<T: TreeNode>(node: &mut T, path: &[usize], f: impl FnOnce(&mut T)) {
+    match path.split_first() {
+        Some((index, rest)) => node.for_child_mut(*index, |child| with_node_mut(child, rest, f)),
+        None => f(node),
+    }
+}
+
+impl<T: TreeNode> Widget<T> for Tree<T> {
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(VIEWPORT_CHANGED) => {
+                // This is in the coordinate space of the scroll's content, which
+                // is ours if we are its direct child.
+                self.viewport = *cmd.get_unchecked(VIEWPORT_CHANGED);
+                ctx.set_handled();
+                if self.wanted_range() != self.created_range() {
+                    ctx.request_update();
+                }
+                return;
+            }
+            Event::Command(cmd) if cmd.is(WATCH_SCROLL) => {
+                ctx.set_handled();
+                ctx.submit_notification(WATCH_VIEWPORT);
+                return;
+            }
+            _ => (),
+        }
+
+        for (row, widget) in self.visible[self.created_range()]
+            .iter()
+            .zip(self.rows.iter_mut())
+        {
+            with_node_mut(data, &row.path, |node| widget.event(ctx, event, node, env));
+        }
+        if ctx.is_handled() {
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                let index = (mouse.pos.y / self.row_height).floor();
+                if index < 0.0 || index as usize >= self.visible.len() {
+                    return;
+                }
+                let index = index as usize;
+                let row = self.visible[index].clone();
+                let disclosure = row.depth() as f64 * self.indent;
+                let on_disclosure = (disclosure..disclosure + self.indent).contains(&mouse.pos.x);
+                if row.branch && (on_disclosure || mouse.count == 2) {
+                    let expanded = !row.expanded;
+                    self.set_expanded(ctx, data, env, index, expanded);
+                }
+                if self.selectable {
+                    self.select(ctx, index);
+                    ctx.request_focus();
+                }
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if self.selectable && ctx.is_focused() => {
+                self.key_down(ctx, &key.key, data, env);
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                ctx.submit_command(WATCH_SCROLL.to(ctx.widget_id()));
+                self.update_visible(data);
+                if self.update_rows() {
+                    ctx.children_changed();
+                }
+            }
+            LifeCycle::BuildFocusChain if self.selectable => ctx.register_for_focus(),
+            _ => (),
+        }
+
+        for (row, widget) in self.visible[self.created_range()]
+            .iter()
+            .zip(self.rows.iter_mut())
+        {
+            widget.lifecycle(ctx, event, node_at(data, &row.path), env);
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) {
+            let visible = self.visible.clone();
+            self.update_visible(data);
+            if self.visible != visible {
+                ctx.request_layout();
+            }
+        }
+        let old_range = self.created_range();
+        if self.update_rows() {
+            ctx.children_changed();
+        }
+        if self.created_range() != old_range {
+            ctx.request_layout();
+        }
+
+        // Reused widgets see the data of their new node as changed data; widgets
+        // that were just created are skipped until they have been added.
+        for (row, widget) in self.visible[self.created_range()]
+            .iter()
+            .zip(self.rows.iter_mut())
+        {
+            if widget.is_initialized() {
+                widget.update(ctx, node_at(data, &row.path), env);
+            }
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Tree");
+
+        let mut width = bc.min().width;
+        let mut paint_rect = Rect::ZERO;
+        let range = self.created_range();
+        for ((index, row), widget) in range
+            .zip(self.visible[self.created_range()].iter())
+            .zip(self.rows.iter_mut())
+        {
+            let x = (row.depth() + 1) as f64 * self.indent;
+            let child_bc = BoxConstraints::new(
+                Size::new(0.0, self.row_height),
+                Size::new((bc.max().width - x).max(0.0), self.row_height),
+            );
+            let node = node_at(data, &row.path);
+            let size = widget.layout(ctx, &child_bc, node, env);
+            widget.set_origin(
+                ctx,
+                node,
+                env,
+                Point::new(x, index as f64 * self.row_height),
+            );
+            paint_rect = paint_rect.union(widget.paint_rect());
+            width = width.max(x + size.width);
+        }
+
+        let height = self.visible.len() as f64 * self.row_height;
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            width
+        };
+        let my_size = bc.constrain(Size::new(width, height));
+        let insets = paint_rect - my_size.to_rect();
+        ctx.set_paint_insets(insets);
+        trace!("Computed layout: size={}, insets={:?}", my_size, insets);
+        my_size
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for index in self.created_range() {
+            self.paint_row_decorations(ctx, index, env);
+        }
+        for (row, widget) in self.visible[self.created_range()]
+            .iter()
+            .zip(self.rows.iter_mut())
+        {
+            widget.paint(ctx, node_at(data, &row.path), env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children = self.visible[self.created_range()]
+            .iter()
+            .zip(self.rows.iter())
+            .map(|(row, widget)| widget.widget().debug_state(node_at(data, &row.path)))
+            .collect();
+        let mut state = DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!("{:?}", self.created_range()),
+            children,
+            ..Default::default()
+        };
+        if let Some(path) = self.selected_path() {
+            state
+                .other_values
+                .insert("selected".to_string(), format!("{:?}", path));
+        }
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{press_key, widget_ids};
+    use crate::tests::{move_mouse, scroll_mouse};
+    use crate::widget::Scroll;
+    use crate::{MouseButton, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    #[derive(Clone, Data)]
+    struct Node {
+        name: String,
+        branch: bool,
+        expanded: bool,
+        children: Arc<Vec<Node>>,
+    }
+
+    impl Node {
+        fn leaf(name: &str) -> Node {
+            Node {
+                name: name.to_string(),
+                branch: false,
+                expanded: false,
+                children: Arc::new(Vec::new()),
+            }
+        }
+
+        fn branch(name: &str, children: Vec<Node>) -> Node {
+            Node {
+                branch: true,
+                children: Arc::new(children),
+                ..Node::leaf(name)
+            }
+        }
+    }
+
+    impl TreeNode for Node {
+        fn children_count(&self) -> usize {
+            self.children.len()
+        }
+
+        fn get_child(&self, index: usize) -> &Node {
+            &self.children[index]
+        }
+
+        fn for_child_mut(&mut self, index: usize, f: impl FnOnce(&mut Node)) {
+            let mut child = self.children[index].clone();
+            f(&mut child);
+            if !child.same(&self.children[index]) {
+                Arc::make_mut(&mut self.children)[index] = child;
+            }
+        }
+
+        fn is_branch(&self) -> bool {
+            self.branch
+        }
+
+        fn is_expanded(&self) -> bool {
+            self.expanded
+        }
+
+        fn set_expanded(&mut self, expanded: bool) {
+            self.expanded = expanded;
+        }
+    }
+
+    /// A row that shows the name of its node in its debug state.
+    struct Name;
+
+    impl Widget<Node> for Name {
+        fn event(&mut self, _: &mut EventCtx, _: &Event, _: &mut Node, _: &Env) {}
+        fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &Node, _: &Env) {}
+        fn update(&mut self, _: &mut UpdateCtx, _: &Node, _: &Node, _: &Env) {}
+        fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &Node, _: &Env) -> Size {
+            bc.max()
+        }
+        fn paint(&mut self, _: &mut PaintCtx, _: &Node, _: &Env) {}
+        fn debug_state(&self, data: &Node) -> DebugState {
+            DebugState {
+                main_value: data.name.clone(),
+                ..Default::default()
+            }
+        }
+    }
+
+    fn tree_widget(id: WidgetId) -> impl Widget<Node> {
+        let tree = Tree::new(|| Name).with_row_height(20.0).with_id(id);
+        Scroll::new(tree).vertical().fix_height(100.0).center()
+    }
+
+    /// The names of the rows that have a widget.
+    fn rows(harness: &mut Harness<Node>, id: WidgetId) -> Vec<String> {
+        // `id` belongs to the `IdentityWrapper` around the tree.
+        let state = harness.get_debug_state(id).children.remove(0);
+        state
+            .children
+            .into_iter()
+            .map(|label| label.main_value)
+            .collect()
+    }
+
+    #[test]
+    fn virtualized_rows() {
+        let [id] = widget_ids();
+        let children = (0..1000)
+            .map(|i| Node::branch(&i.to_string(), vec![Node::leaf("a"), Node::leaf("b")]))
+            .collect();
+        let mut root = Node::branch("root", children);
+        Arc::make_mut(&mut root.children)[1].expanded = true;
+
+        Harness::create_simple(root, tree_widget(id), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // Deliver the viewport the scroll sent after its layout.
+            harness.event(Event::MouseMove(move_mouse((1.0, 1.0))));
+            harness.just_layout();
+
+            assert_eq!(harness.get_state(id).layout_rect().height(), 1002.0 * 20.0);
+            // Five rows are visible, and the overscan adds three below them.
+            assert_eq!(rows(harness, id), ["0", "1", "a", "b", "2", "3", "4", "5"]);
+
+            // Collapsing a branch above the viewport moves the rows up.
+            harness.event(Event::Wheel(scroll_mouse((200.0, 200.0), (0.0, 200.0))));
+            harness.just_layout();
+            assert_eq!(rows(harness, id)[3..6], ["8", "9", "10"]);
+            harness.update_data(|root| Arc::make_mut(&mut root.children)[1].expanded = false);
+            harness.event(Event::MouseMove(move_mouse((1.0, 2.0))));
+            harness.just_layout();
+            assert_eq!(harness.get_state(id).layout_rect().height(), 1000.0 * 20.0);
+            assert_eq!(rows(harness, id)[3..6], ["10", "11", "12"]);
+        });
+    }
+
+    #[test]
+    fn keyboard_navigation_and_loading() {
+        let [id] = widget_ids();
+        let loads = Rc::new(Cell::new(0));
+        let root = Node::branch(
+            "root",
+            vec![
+                Node::branch("lazy", Vec::new()),
+                Node::branch("dir", vec![Node::leaf("file")]),
+            ],
+        );
+        let loader = {
+            let loads = loads.clone();
+            move |_: &mut EventCtx, node: &mut Node, _: &Env| {
+                loads.set(loads.get() + 1);
+                node.children = Arc::new(vec![Node::leaf("loaded")]);
+            }
+        };
+        let tree = Tree::new(|| Name)
+            .with_row_height(20.0)
+            .with_loader(loader)
+            .with_id(id);
+        let widget = Scroll::new(tree).vertical().fix_height(100.0).center();
+        let selected = |harness: &mut Harness<Node>| {
+            let mut state = harness.get_debug_state(id).children.remove(0);
+            state.other_values.remove("selected")
+        };
+
+        Harness::create_simple(root, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseMove(move_mouse((1.0, 1.0))));
+            harness.just_layout();
+            assert_eq!(rows(harness, id), ["lazy", "dir"]);
+
+            // Clicking a row selects it, and gives the tree focus.
+            let origin = harness.get_state(id).window_origin();
+            harness.event(Event::MouseDown(MouseEvent {
+                button: MouseButton::Left,
+                count: 1,
+                ..move_mouse(origin + (40.0, 30.0))
+            }));
+            assert_eq!(selected(harness).as_deref(), Some("[1]"));
+
+            press_key(harness, KbKey::ArrowRight);
+            assert!(harness.data().children[1].expanded);
+            press_key(harness, KbKey::ArrowRight);
+            assert_eq!(selected(harness).as_deref(), Some("[1, 0]"));
+            // Left goes to the parent, and then collapses it.
+            press_key(harness, KbKey::ArrowLeft);
+            assert_eq!(selected(harness).as_deref(), Some("[1]"));
+            press_key(harness, KbKey::ArrowLeft);
+            assert!(!harness.data().children[1].expanded);
+
+            // Expanding the empty branch loads its children, only once.
+            press_key(harness, KbKey::ArrowUp);
+            press_key(harness, KbKey::Enter);
+            press_key(harness, KbKey::Enter);
+            press_key(harness, KbKey::Enter);
+            assert_eq!(loads.get(), 1);
+            harness.event(Event::MouseMove(move_mouse((1.0, 2.0))));
+            harness.just_layout();
+            assert_eq!(rows(harness, id), ["lazy", "loaded", "dir"]);
+
+            // Collapsing a branch selects it instead of its hidden child.
+            press_key(harness, KbKey::ArrowDown);
+            assert_eq!(selected(harness).as_deref(), Some("[0, 0]"));
+            harness.update_data(|root| Arc::make_mut(&mut root.children)[0].expanded = false);
+            assert_eq!(selected(harness).as_deref(), Some("[0]"));
+        });
+    }
+}
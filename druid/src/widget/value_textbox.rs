@@ -18,7 +18,7 @@ use tracing::instrument;
 
 use super::TextBox;
 use crate::debug_state::DebugState;
-use crate::text::{Formatter, Selection, TextComponent, ValidationError};
+use crate::text::{Formatter, MaskFormatter, Selection, TextComponent, ValidationError};
 use crate::widget::prelude::*;
 use crate::{Data, Selector};
 
@@ -97,6 +97,21 @@ impl TextBox<String> {
     ) -> ValueTextBox<T> {
         ValueTextBox::new(self, formatter)
     }
+
+    /// Turn this `TextBox` into a [`ValueTextBox`] that arranges its input
+    /// according to a mask, such as `(###) ###-####`.
+    ///
+    /// Each `#` accepts a digit, and other characters are separators that are
+    /// inserted automatically. The data is updated while editing, and contains
+    /// the text including separators; to store only the digits, use
+    /// [`with_formatter`] with a [`MaskFormatter`] directly.
+    ///
+    /// [`ValueTextBox`]: ValueTextBox
+    /// [`with_formatter`]: TextBox::with_formatter
+    /// [`MaskFormatter`]: crate::text::MaskFormatter
+    pub fn with_mask(self, mask: &str) -> ValueTextBox<String> {
+        ValueTextBox::new(self, MaskFormatter::new(mask)).update_data_while_editing(true)
+    }
 }
 
 impl<T: Data> ValueTextBox<T> {
@@ -149,6 +164,14 @@ impl<T: Data> ValueTextBox<T> {
         self
     }
 
+    /// Format `data` again, for instance because the formatter's settings have
+    /// changed. This does nothing while editing is in progress.
+    pub(crate) fn reformat(&mut self, data: &T) {
+        if !self.is_editing {
+            self.buffer = self.formatter.format(data);
+        }
+    }
+
     fn complete(&mut self, ctx: &mut EventCtx, data: &mut T) -> bool {
         match self.formatter.value(&self.buffer) {
             Ok(new_data) => {
@@ -424,3 +447,77 @@ impl<T: Data + std::fmt::Debug> Widget<T> for ValueTextBox<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::MaskFormatter;
+    use test_log::test;
+
+    const PHONE: &str = "(###) ###-####";
+
+    /// Run validation and apply any changes, returning the new text and caret.
+    fn edit(formatter: &MaskFormatter, input: &str, caret: usize) -> Option<(String, usize)> {
+        let validation = formatter.validate_partial_input(input, &Selection::caret(caret));
+        if validation.is_err() {
+            return None;
+        }
+        let text = validation.text_change.unwrap_or_else(|| input.to_string());
+        let caret = validation
+            .selection_change
+            .map(|s| s.active)
+            .unwrap_or(caret);
+        Some((text, caret))
+    }
+
+    #[test]
+    fn mask_inserts_separators() {
+        let formatter = MaskFormatter::new(PHONE);
+        assert_eq!(edit(&formatter, "5", 1), Some(("(5".into(), 2)));
+        assert_eq!(edit(&formatter, "(5551", 5), Some(("(555) 1".into(), 7)));
+        assert_eq!(
+            edit(&formatter, "(555) 1234", 10),
+            Some(("(555) 123-4".into(), 11))
+        );
+        // Typing in the middle keeps the caret after the typed digit.
+        assert_eq!(
+            edit(&formatter, "(5595) 1", 4),
+            Some(("(559) 51".into(), 4))
+        );
+    }
+
+    #[test]
+    fn mask_rejects_invalid_input() {
+        let formatter = MaskFormatter::new(PHONE);
+        assert_eq!(edit(&formatter, "(55a", 4), None);
+        assert_eq!(edit(&formatter, "(555) 123-45678", 15), None);
+        // A separator typed where a digit is expected is dropped.
+        assert_eq!(edit(&formatter, "(555-", 5), Some(("(555".into(), 4)));
+    }
+
+    #[test]
+    fn mask_backspace() {
+        let formatter = MaskFormatter::new(PHONE);
+        // Deleting the last digit also removes the separators in front of it.
+        assert_eq!(edit(&formatter, "(555) ", 6), Some(("(555".into(), 4)));
+        // Deleting a separator deletes the digit before it.
+        assert_eq!(
+            edit(&formatter, "(555)123", 5),
+            Some(("(551) 23".into(), 3))
+        );
+        assert_eq!(
+            edit(&formatter, "(555 123", 4),
+            Some(("(551) 23".into(), 3))
+        );
+    }
+
+    #[test]
+    fn mask_value() {
+        let masked = MaskFormatter::new(PHONE);
+        let unmasked = MaskFormatter::new(PHONE).with_masked_value(false);
+        assert_eq!(masked.value("(555) 123-4567").unwrap(), "(555) 123-4567");
+        assert_eq!(unmasked.value("(555) 123-4567").unwrap(), "5551234567");
+        assert_eq!(unmasked.format(&"5551234567".to_string()), "(555) 123-4567");
+        assert_eq!(masked.format(&"(555) 12".to_string()), "(555) 12");
+    }
+}
@@ -0,0 +1,285 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that can hide its child.
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{Data, Insets, InternalEvent, Lens, Point, Selector, WidgetPod};
+use tracing::{instrument, trace};
+
+/// Sent to ourselves when the child is hidden, so that it stops being hot.
+const CHILD_HIDDEN: Selector = Selector::new("druid.builtin.visible-child-hidden");
+
+/// What a [`Visible`] does with its child while it is hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisibilityMode {
+    /// The child is removed from the layout, and the widget has no size.
+    Collapse,
+    /// The child is still laid out, and the widget keeps its size, but the
+    /// child is neither painted nor does it receive events.
+    Hidden,
+}
+
+/// A widget that shows or hides its child based on a `bool` in the data.
+///
+/// While hidden, the child is treated like the hidden branch of an
+/// [`Either`]: it only receives the events and lifecycle events that
+/// [should propagate to hidden widgets], and it is not part of the focus
+/// chain. Whether it keeps its space in the layout is decided by the
+/// [`VisibilityMode`].
+///
+/// Note that a collapsed child is still a child of its parent; a [`Flex`]
+/// with a [`main_axis_gap`] still puts gaps around it.
+///
+/// This is more conveniently used through [`WidgetExt::visible`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Flex, Label, VisibilityMode};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Settings {
+///     advanced: bool,
+/// }
+///
+/// fn settings() -> impl Widget<Settings> {
+///     Flex::column()
+///         .with_child(Label::new("Basic settings"))
+///         .with_child(Label::new("Advanced settings").visible(Settings::advanced))
+///         .with_child(
+///             Label::new("Even more settings")
+///                 .visible(Settings::advanced)
+///                 .with_mode(VisibilityMode::Hidden),
+///         )
+/// }
+/// ```
+///
+/// [`Either`]: crate::widget::Either
+/// [should propagate to hidden widgets]: crate::Event::should_propagate_to_hidden
+/// [`Flex`]: crate::widget::Flex
+/// [`main_axis_gap`]: crate::widget::Flex::with_main_axis_gap
+/// [`WidgetExt::visible`]: crate::WidgetExt::visible
+pub struct Visible<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    is_visible: Box<dyn Fn(&T) -> bool>,
+    mode: VisibilityMode,
+    visible: bool,
+}
+
+impl<T: Data> Visible<T> {
+    /// Create a new `Visible` that shows `child` while the `bool` selected by
+    /// `lens` is `true`.
+    ///
+    /// The child is collapsed while it is hidden; use [`with_mode`] to keep
+    /// its space instead.
+    ///
+    /// [`with_mode`]: Visible::with_mode
+    pub fn new(child: impl Widget<T> + 'static, lens: impl Lens<T, bool> + 'static) -> Self {
+        Visible {
+            child: WidgetPod::new(child).boxed(),
+            is_visible: Box::new(move |data| lens.with(data, |visible| *visible)),
+            mode: VisibilityMode::Collapse,
+            visible: true,
+        }
+    }
+
+    /// Builder-style method for setting what happens to the child while it
+    /// is hidden.
+    pub fn with_mode(mut self, mode: VisibilityMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set what happens to the child while it is hidden.
+    pub fn set_mode(&mut self, mode: VisibilityMode) {
+        self.mode = mode;
+    }
+
+    /// Returns `true` if the child is currently shown.
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns `true` if the child should be laid out.
+    fn is_laid_out(&self) -> bool {
+        self.visible || self.mode == VisibilityMode::Hidden
+    }
+
+    fn clear_hot(&mut self, ctx: &mut EventCtx, data: &mut T, env: &Env) {
+        if self.child.is_hot() {
+            let event = Event::Internal(InternalEvent::MouseLeave);
+            self.child.event(ctx, &event, data, env);
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for Visible<T> {
+    #[instrument(name = "Visible", level = "trace", skip(self, ctx, event, data, env), fields(visible = self.visible))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if self.visible {
+            self.child.event(ctx, event, data, env);
+            return;
+        }
+        self.clear_hot(ctx, data, env);
+        match event {
+            Event::Command(cmd) if cmd.is(CHILD_HIDDEN) => ctx.set_handled(),
+            _ if event.should_propagate_to_hidden() => self.child.event(ctx, event, data, env),
+            _ => (),
+        }
+    }
+
+    #[instrument(name = "Visible", level = "trace", skip(self, ctx, event, data, env), fields(visible = self.visible))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.visible = (self.is_visible)(data);
+        }
+        if self.visible || event.should_propagate_to_hidden() {
+            self.child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Visible", level = "trace", skip(self, ctx, _old_data, data, env), fields(visible = self.visible))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let visible = (self.is_visible)(data);
+        if visible != self.visible {
+            trace!("Visible {:?}: visible = {}", ctx.widget_id(), visible);
+            self.visible = visible;
+            if !visible {
+                ctx.submit_command(CHILD_HIDDEN.to(ctx.widget_id()));
+            }
+            ctx.children_changed();
+            ctx.request_paint();
+        }
+        if self.is_laid_out() {
+            self.child.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Visible", level = "trace", skip(self, ctx, bc, data, env), fields(visible = self.visible))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Visible");
+        if !self.is_laid_out() {
+            ctx.set_paint_insets(Insets::ZERO);
+            ctx.set_baseline_offset(0.0);
+            return bc.min();
+        }
+
+        let size = self.child.layout(ctx, bc, data, env);
+        // The origin is only set while the child is shown, because setting it
+        // also recomputes whether the child is hot.
+        if self.visible {
+            self.child.set_origin(ctx, data, env, Point::ORIGIN);
+            ctx.set_paint_insets(self.child.paint_insets());
+        } else {
+            ctx.set_paint_insets(Insets::ZERO);
+        }
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "Visible", level = "trace", skip(self, ctx, data, env), fields(visible = self.visible))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if self.visible {
+            self.child.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children = if self.visible {
+            vec![self.child.widget().debug_state(data)]
+        } else {
+            Vec::new()
+        };
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::tests::move_mouse;
+    use crate::widget::{Flex, SizedBox};
+    use crate::WidgetExt;
+    use test_log::test;
+
+    fn column(mode: VisibilityMode, child_id: WidgetId, after_id: WidgetId) -> impl Widget<bool> {
+        Flex::column()
+            .with_child(
+                SizedBox::empty()
+                    .fix_size(50.0, 40.0)
+                    .with_id(child_id)
+                    .visible(crate::lens::Identity)
+                    .with_mode(mode),
+            )
+            .with_child(SizedBox::empty().fix_size(50.0, 10.0).with_id(after_id))
+    }
+
+    #[test]
+    fn collapse_removes_from_layout() {
+        let [child_id, after_id] = widget_ids();
+        let widget = column(VisibilityMode::Collapse, child_id, after_id);
+
+        Harness::create_simple(true, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(harness.get_state(after_id).layout_rect().y0, 40.0);
+
+            harness.update_data(|visible| *visible = false);
+            harness.just_layout();
+            assert_eq!(harness.get_state(after_id).layout_rect().y0, 0.0);
+
+            harness.update_data(|visible| *visible = true);
+            harness.just_layout();
+            assert_eq!(harness.get_state(after_id).layout_rect().y0, 40.0);
+        });
+    }
+
+    #[test]
+    fn hidden_keeps_space_and_is_not_hot() {
+        let [child_id, after_id] = widget_ids();
+        let widget = column(VisibilityMode::Hidden, child_id, after_id);
+
+        Harness::create_simple(true, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let center = harness.get_state(child_id).window_origin() + (25.0, 20.0);
+            harness.event(Event::MouseMove(move_mouse(center)));
+            assert!(harness.get_state(child_id).is_hot);
+
+            harness.update_data(|visible| *visible = false);
+            harness.just_layout();
+            assert_eq!(harness.get_state(after_id).layout_rect().y0, 40.0);
+            // The child stops being hot as soon as it has been hidden.
+            harness.submit_command(Selector::NOOP);
+            assert!(!harness.get_state(child_id).is_hot);
+
+            harness.event(Event::MouseMove(move_mouse(center)));
+            assert!(!harness.get_state(child_id).is_hot);
+
+            harness.update_data(|visible| *visible = true);
+            harness.just_layout();
+            harness.event(Event::MouseMove(move_mouse(center)));
+            assert!(harness.get_state(child_id).is_hot);
+        });
+    }
+}
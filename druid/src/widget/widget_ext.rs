@@ -14,14 +14,19 @@
 
 //! Convenience methods for widgets.
 
+use std::any::Any;
+
 use super::invalidation::DebugInvalidation;
 use super::{
-    Added, Align, BackgroundBrush, Click, Container, Controller, ControllerHost, EnvScope,
-    IdentityWrapper, LensWrap, Padding, Parse, SizedBox, WidgetId,
+    Added, Align, BackgroundBrush, Badge, Click, Container, Controller, ControllerHost, DragSource,
+    DropTarget, EnvScope, IdentityWrapper, LabelText, LensWrap, LocalStatePolicy, Opacity, Padding,
+    Parse, Scope, SetCursor, SizedBox, Tooltip, Visible, WidgetId, WindowDragRegion,
 };
-use crate::widget::{DisabledIf, Scroll};
+use crate::theme::Theme;
+use crate::widget::{DisabledIf, FocusGroup, FocusOrder, Scroll};
 use crate::{
-    Color, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, UnitPoint, Widget,
+    Color, Cursor, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, Point, UnitPoint,
+    Widget,
 };
 
 /// A trait that provides extra methods for combining `Widget`s.
@@ -158,6 +163,16 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         EnvScope::new(f, self)
     }
 
+    /// Wrap this widget in an [`EnvScope`] widget, setting the values of a
+    /// [`Theme`] in the parent [`Env`].
+    ///
+    /// See [`EnvScope::with_theme`].
+    ///
+    /// [`Theme`]: crate::theme::Theme
+    fn env_scope_typed(self, theme: Theme) -> EnvScope<T, Self> {
+        EnvScope::with_theme(theme, self)
+    }
+
     /// Wrap this widget with the provided [`Controller`].
     ///
     /// [`Controller`]: widget/trait.Controller.html
@@ -197,6 +212,113 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         ControllerHost::new(self, Click::new(f))
     }
 
+    /// Let the user drag the value returned by `payload` out of this widget,
+    /// with a [`DragSource`].
+    ///
+    /// [`DragSource`]: widget/struct.DragSource.html
+    fn drag_source<P: Any>(
+        self,
+        payload: impl Fn(&T, &Env) -> P + 'static,
+    ) -> ControllerHost<Self, DragSource<T>> {
+        ControllerHost::new(self, DragSource::new(payload))
+    }
+
+    /// Accept values of type `P` that are dropped onto this widget, with a
+    /// [`DropTarget`].
+    ///
+    /// The closure gets the dropped value and the position of the pointer
+    /// in this widget.
+    ///
+    /// [`DropTarget`]: widget/struct.DropTarget.html
+    fn drop_target<P: Any>(
+        self,
+        on_drop: impl Fn(&mut EventCtx, &P, Point, &mut T, &Env) + 'static,
+    ) -> ControllerHost<Self, DropTarget<T, P>> {
+        ControllerHost::new(self, DropTarget::new(on_drop))
+    }
+
+    /// Show the given [`Cursor`] while the pointer is over this widget.
+    ///
+    /// The default cursor is restored when the pointer leaves. If this widget
+    /// also sets a cursor itself, that cursor takes precedence, and while the
+    /// widget is disabled the default cursor is shown.
+    ///
+    /// This is equivalent to wrapping the widget in a [`SetCursor`] controller.
+    ///
+    /// [`Cursor`]: crate::Cursor
+    /// [`SetCursor`]: crate::widget::SetCursor
+    fn with_cursor(self, cursor: Cursor) -> ControllerHost<Self, SetCursor> {
+        ControllerHost::new(self, SetCursor::new(cursor))
+    }
+
+    /// Show a tooltip with the given text when the pointer rests on this widget.
+    ///
+    /// The text can be anything a [`Label`] accepts. See [`Tooltip`] for
+    /// how the tooltip behaves.
+    ///
+    /// [`Label`]: crate::widget::Label
+    /// [`Tooltip`]: crate::widget::Tooltip
+    fn tooltip(self, text: impl Into<LabelText<T>>) -> ControllerHost<Self, Tooltip<T>> {
+        ControllerHost::new(self, Tooltip::new(text))
+    }
+
+    /// Let the user move the window by dragging this widget, as if it was the titlebar.
+    ///
+    /// See [`WindowDragRegion`] for details.
+    ///
+    /// [`WindowDragRegion`]: crate::widget::WindowDragRegion
+    fn draggable_window_region(self) -> ControllerHost<Self, WindowDragRegion> {
+        ControllerHost::new(self, WindowDragRegion::new())
+    }
+
+    /// Show a tooltip with text computed from the data when the pointer rests
+    /// on this widget.
+    ///
+    /// See [`Tooltip`] for how the tooltip behaves.
+    ///
+    /// [`Tooltip`]: crate::widget::Tooltip
+    fn tooltip_lazy(self, f: impl Fn(&T) -> String + 'static) -> ControllerHost<Self, Tooltip<T>> {
+        ControllerHost::new(self, Tooltip::new(move |data: &T, _: &Env| f(data)))
+    }
+
+    /// Show the count selected by `lens` in a small bubble over the top right
+    /// corner of this widget, while it isn't zero.
+    ///
+    /// See [`Badge`] for how to change the corner and the colors.
+    ///
+    /// [`Badge`]: crate::widget::Badge
+    fn badge(self, lens: impl Lens<T, u32> + 'static) -> Badge<T> {
+        Badge::new(self, lens)
+    }
+
+    /// Show this widget only while the `bool` selected by `lens` is `true`.
+    ///
+    /// While hidden, the widget is collapsed; use [`Visible::with_mode`] to
+    /// keep its space reserved instead.
+    ///
+    /// [`Visible::with_mode`]: crate::widget::Visible::with_mode
+    fn visible(self, lens: impl Lens<T, bool> + 'static) -> Visible<T> {
+        Visible::new(self, lens)
+    }
+
+    /// Paint this widget with a fixed opacity, from `0.0` to `1.0`.
+    ///
+    /// See [`Opacity`] for details.
+    ///
+    /// [`Opacity`]: crate::widget::Opacity
+    fn with_opacity(self, opacity: impl Into<KeyOrValue<f64>>) -> Opacity<T, Self> {
+        Opacity::new(self, opacity)
+    }
+
+    /// Paint this widget with the opacity selected by `lens`, from `0.0` to `1.0`.
+    ///
+    /// See [`Opacity`] for details.
+    ///
+    /// [`Opacity`]: crate::widget::Opacity
+    fn with_opacity_lens(self, lens: impl Lens<T, f64> + 'static) -> Opacity<T, Self> {
+        Opacity::lens(self, lens)
+    }
+
     /// Draw the [`layout`] `Rect`s of  this widget and its children.
     ///
     /// [`layout`]: trait.Widget.html#tymethod.layout
@@ -258,6 +380,44 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         IdentityWrapper::wrap(self, id)
     }
 
+    /// Give this widget some state of its own, that isn't part of the surrounding data.
+    ///
+    /// The widget sees a tuple of the surrounding data and its state, which
+    /// starts out as `init`. Changes to either part are propagated as usual,
+    /// but only the surrounding data is written back; the state lives in the
+    /// returned [`Scope`], and so starts over whenever the widget is recreated.
+    ///
+    /// This is a shorthand for a [`Scope`] with a [`LocalStatePolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::{Button, Flex, Label};
+    /// use druid::{lens, Widget, WidgetExt};
+    ///
+    /// fn counter() -> impl Widget<String> {
+    ///     Flex::row()
+    ///         .with_child(Label::dynamic(|(name, count): &(String, u32), _| {
+    ///             format!("{} clicked {} times", name, count)
+    ///         }))
+    ///         .with_child(
+    ///             Button::new("Click")
+    ///                 .on_click(|_, count: &mut u32, _| *count += 1)
+    ///                 .lens(lens!((String, u32), 1)),
+    ///         )
+    ///         .with_local_state(0)
+    /// }
+    /// ```
+    ///
+    /// [`Scope`]: crate::widget::Scope
+    /// [`LocalStatePolicy`]: crate::widget::LocalStatePolicy
+    fn with_local_state<In: Data, S: Data>(self, init: S) -> Scope<LocalStatePolicy<In, S>, Self>
+    where
+        Self: Widget<(In, S)>,
+    {
+        Scope::new(LocalStatePolicy::new(init), self)
+    }
+
     /// Wrap this widget in a `Box`.
     fn boxed(self) -> Box<dyn Widget<T>> {
         Box::new(self)
@@ -275,12 +435,31 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
     /// The provided closure will determine if the widget is disabled.
     /// See [`is_disabled`] or [`set_disabled`] for more info about disabled state.
     ///
+    /// Disabled widgets and their descendants are skipped by keyboard focus,
+    /// and the built-in controls are dimmed and ignore input.
+    ///
     /// [`is_disabled`]: crate::EventCtx::is_disabled
     /// [`set_disabled`]: crate::EventCtx::set_disabled
     /// [`DisabledIf`]: crate::widget::DisabledIf
     fn disabled_if(self, disabled_if: impl Fn(&T, &Env) -> bool + 'static) -> DisabledIf<T, Self> {
         DisabledIf::new(self, disabled_if)
     }
+
+    /// Wrap this widget in a [`FocusOrder`], to change where the focusable
+    /// widgets in it are in the order that tab visits them.
+    ///
+    /// [`FocusOrder`]: crate::widget::FocusOrder
+    fn focus_order(self, order: i32) -> FocusOrder<T, Self> {
+        FocusOrder::new(self, order)
+    }
+
+    /// Wrap this widget in a [`FocusGroup`], to keep the focus inside of it
+    /// when tab is pressed.
+    ///
+    /// [`FocusGroup`]: crate::widget::FocusGroup
+    fn focus_group(self) -> FocusGroup<T, Self> {
+        FocusGroup::new(self)
+    }
 }
 
 impl<T: Data, W: Widget<T> + 'static> WidgetExt<T> for W {}
@@ -315,6 +494,10 @@ impl<T: Data, W> EnvScope<T, W> {
         }
     }
 
+    pub fn env_scope_typed(self, theme: Theme) -> EnvScope<T, W> {
+        self.env_scope(move |env, _| theme.apply(env))
+    }
+
     pub fn debug_paint_layout(self) -> EnvScope<T, W> {
         self.env_scope(|env, _| env.set(Env::DEBUG_PAINT, true))
     }
@@ -0,0 +1,77 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that lets the user move the window by dragging a widget.
+//!
+//! [`Controller`]: crate::widget::Controller
+
+use crate::widget::Controller;
+use crate::{Data, Env, Event, EventCtx, Widget, WindowState};
+use tracing::{instrument, trace};
+
+/// A [`Controller`] that makes its child behave like the titlebar of the window.
+///
+/// Dragging the child with the left mouse button moves the window, and a
+/// double click maximizes or restores it. This lets you build custom window
+/// chrome for a window created with [`show_titlebar(false)`]. Clicks that
+/// the child handles itself, for instance on a button inside the region, are
+/// left alone.
+///
+/// This is also available, for convenience, as a `draggable_window_region`
+/// method via [`WidgetExt`]. Moving the window is currently supported on
+/// Windows, macOS and GTK.
+///
+/// [`Controller`]: crate::widget::Controller
+/// [`WidgetExt`]: crate::widget::WidgetExt
+/// [`show_titlebar(false)`]: crate::WindowDesc::show_titlebar
+#[derive(Debug, Default)]
+pub struct WindowDragRegion;
+
+impl WindowDragRegion {
+    /// Create a new [`Controller`] that moves the window when its child is dragged.
+    ///
+    /// [`Controller`]: crate::widget::Controller
+    pub fn new() -> Self {
+        WindowDragRegion
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for WindowDragRegion {
+    #[instrument(
+        name = "WindowDragRegion",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        child.event(ctx, event, data, env);
+
+        if let Event::MouseDown(mouse) = event {
+            if !mouse.button.is_left() || ctx.is_handled() || ctx.is_disabled() {
+                return;
+            }
+            if mouse.count == 2 {
+                let mut handle = ctx.window().clone();
+                let state = match handle.get_window_state() {
+                    WindowState::Maximized => WindowState::Restored,
+                    _ => WindowState::Maximized,
+                };
+                trace!("double click, setting window state to {:?}", state);
+                handle.set_window_state(state);
+            } else {
+                ctx.window().begin_move_drag();
+            }
+            ctx.set_handled();
+        }
+    }
+}
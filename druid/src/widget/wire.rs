@@ -0,0 +1,388 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A curve that connects two points, like the ports of a node graph.
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{
+    self, BezPath, CubicBez, Line, ParamCurve, ParamCurveDeriv, ParamCurveNearest, PathEl, Shape,
+};
+use crate::piet::RenderContext;
+use crate::widget::prelude::*;
+use crate::{theme, Color, Data, Insets, KeyOrValue, Point, Rect, Vec2};
+
+/// The accuracy of the hit-testing, in pixels.
+const HIT_ACCURACY: f64 = 0.1;
+const ARROW_LENGTH: f64 = 10.0;
+const ARROW_WIDTH: f64 = 8.0;
+
+type WireEndsFn<T> = Box<dyn Fn(&T) -> (Point, Point)>;
+type WireAction<T> = Box<dyn Fn(&mut EventCtx, &mut T, &Env)>;
+
+/// A smooth curve from one point to another, for the connections between
+/// the nodes of a node graph.
+///
+/// The curve is a cubic bézier that leaves the start point and enters the end
+/// point horizontally, as from the output port of one node to the input port
+/// of another. How far it bends out is its curvature, a fraction of the
+/// horizontal distance between the points, which is [`WIRE_CURVATURE`] by
+/// default; a curvature of 0 is a straight line. The end can have an
+/// arrowhead, and the curve is drawn with the [`WIRE_COLOR`] unless another
+/// color is set.
+///
+/// The points are in the coordinates of the parent, and are either fixed or
+/// computed from the data, for example from the positions of the nodes. A
+/// `Wire` is meant to be added to a [`Canvas`] with
+/// [`Canvas::with_connection`], so that its coordinates are those of the
+/// canvas and the pointer reaches the nodes below it. Clicks within the hit
+/// tolerance of the curve call the action set with [`on_click`], to select
+/// the connection; [`hit_test`] does the same test for other uses.
+///
+/// The curve itself is available from [`Wire::curve`], to draw connections
+/// without a widget.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Canvas, Label, Wire};
+/// use druid::{Data, Lens, Point, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Graph {
+///     source: Point,
+///     sink: Point,
+///     selected: bool,
+/// }
+///
+/// fn graph() -> impl Widget<Graph> {
+///     let wire = Wire::dynamic(|graph: &Graph| {
+///         // from the right edge of the source to the left edge of the sink
+///         (graph.source + (60.0, 10.0), graph.sink + (0.0, 10.0))
+///     })
+///     .with_arrow(true)
+///     .on_click(|_, graph: &mut Graph, _| graph.selected = !graph.selected);
+///
+///     Canvas::new()
+///         .with_connection(wire)
+///         .with_dynamic_child(Label::new("Source").fix_size(60.0, 20.0), |graph: &Graph| {
+///             graph.source
+///         })
+///         .with_dynamic_child(Label::new("Sink").fix_size(60.0, 20.0), |graph: &Graph| {
+///             graph.sink
+///         })
+/// }
+/// ```
+///
+/// [`WIRE_CURVATURE`]: theme::WIRE_CURVATURE
+/// [`WIRE_COLOR`]: theme::WIRE_COLOR
+/// [`Canvas`]: crate::widget::Canvas
+/// [`Canvas::with_connection`]: crate::widget::Canvas::with_connection
+/// [`on_click`]: Wire::on_click
+/// [`hit_test`]: Wire::hit_test
+pub struct Wire<T> {
+    ends: WireEndsFn<T>,
+    curvature: KeyOrValue<f64>,
+    color: KeyOrValue<Color>,
+    width: f64,
+    tolerance: f64,
+    arrow: bool,
+    action: Option<WireAction<T>>,
+    /// The curve as of the last layout.
+    curve: CubicBez,
+}
+
+impl<T: Data> Wire<T> {
+    /// Create a new `Wire` from `start` to `end`.
+    pub fn new(start: impl Into<Point>, end: impl Into<Point>) -> Self {
+        let (start, end) = (start.into(), end.into());
+        Self::dynamic(move |_| (start, end))
+    }
+
+    /// Create a new `Wire` between the start and end points computed from
+    /// the data by `ends`.
+    pub fn dynamic(ends: impl Fn(&T) -> (Point, Point) + 'static) -> Self {
+        Wire {
+            ends: Box::new(ends),
+            curvature: theme::WIRE_CURVATURE.into(),
+            color: theme::WIRE_COLOR.into(),
+            width: 2.0,
+            tolerance: 4.0,
+            arrow: false,
+            action: None,
+            curve: CubicBez::new(Point::ORIGIN, Point::ORIGIN, Point::ORIGIN, Point::ORIGIN),
+        }
+    }
+
+    /// Builder-style method for setting how far the ends bend out, as a
+    /// fraction of the horizontal distance between them.
+    pub fn with_curvature(mut self, curvature: impl Into<KeyOrValue<f64>>) -> Self {
+        self.curvature = curvature.into();
+        self
+    }
+
+    /// Builder-style method for setting the color of the curve and the arrowhead.
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Builder-style method for setting the width of the curve.
+    ///
+    /// The default is 2.0.
+    pub fn with_width(mut self, width: f64) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Builder-style method for setting how far from the curve a click still
+    /// hits it.
+    ///
+    /// The default is 4.0, in addition to half the width of the curve.
+    pub fn with_hit_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Builder-style method for drawing an arrowhead at the end.
+    pub fn with_arrow(mut self, arrow: bool) -> Self {
+        self.arrow = arrow;
+        self
+    }
+
+    /// Builder-style method for setting the action that is called when the
+    /// curve is clicked.
+    pub fn on_click(mut self, f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        self.action = Some(Box::new(f));
+        self
+    }
+
+    /// The curve from `start` to `end` with the given `curvature`, as drawn
+    /// by a `Wire`.
+    pub fn curve(start: Point, end: Point, curvature: f64) -> CubicBez {
+        let bend = Vec2::new((end.x - start.x).abs() * curvature, 0.0);
+        CubicBez::new(start, start + bend, end - bend, end)
+    }
+
+    /// Returns `true` if `point`, in the coordinates of the parent, is within
+    /// the hit tolerance of the curve, as of the last layout.
+    pub fn hit_test(&self, point: Point) -> bool {
+        let reach = self.tolerance + self.width / 2.0;
+        // the nearest point on the flattened curve, which is more reliable
+        // than solving for the nearest point on the cubic
+        let mut nearest = f64::INFINITY;
+        let mut last = self.curve.p0;
+        kurbo::flatten(self.curve.path_elements(HIT_ACCURACY), HIT_ACCURACY, |el| {
+            if let PathEl::LineTo(p) = el {
+                let segment = Line::new(last, p);
+                nearest = nearest.min(segment.nearest(point, HIT_ACCURACY).distance_sq);
+                last = p;
+            }
+        });
+        nearest <= reach * reach
+    }
+
+    /// The arrowhead at the end of the curve.
+    fn arrowhead(&self) -> BezPath {
+        let curve = self.curve;
+        let mut direction = curve.deriv().eval(1.0).to_vec2();
+        if direction.hypot() < 1e-9 {
+            direction = curve.p3 - curve.p0;
+        }
+        let direction = if direction.hypot() < 1e-9 {
+            Vec2::new(1.0, 0.0)
+        } else {
+            direction.normalize()
+        };
+        let back = curve.p3 - direction * ARROW_LENGTH;
+        let side = Vec2::new(-direction.y, direction.x) * (ARROW_WIDTH / 2.0);
+        let mut path = BezPath::new();
+        path.move_to(curve.p3);
+        path.line_to(back + side);
+        path.line_to(back - side);
+        path.close_path();
+        path
+    }
+
+    /// The bounds of everything that is drawn, in the coordinates of the parent.
+    fn paint_bounds(&self) -> Rect {
+        let mut bounds = self.curve.bounding_box();
+        if self.arrow {
+            bounds = bounds.union(self.arrowhead().bounding_box());
+        }
+        bounds.inflate(self.width, self.width)
+    }
+}
+
+impl<T: Data> Widget<T> for Wire<T> {
+    #[instrument(name = "Wire", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if self.action.is_none() {
+            return;
+        }
+        // the layout rect starts at the origin of the parent, so this is in its coordinates
+        match event {
+            Event::MouseDown(mouse) if !ctx.is_disabled() && self.hit_test(mouse.pos) => {
+                ctx.set_active(true);
+                ctx.set_handled();
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                if self.hit_test(mouse.pos) && !ctx.is_disabled() {
+                    if let Some(action) = &self.action {
+                        action(ctx, data, env);
+                    }
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &T, _env: &Env) {}
+
+    #[instrument(name = "Wire", level = "trace", skip(self, ctx, old_data, data, _env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if (self.ends)(old_data) != (self.ends)(data) || ctx.env_key_changed(&self.curvature) {
+            ctx.request_layout();
+        }
+        if ctx.env_key_changed(&self.color) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Wire", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Wire");
+        let (start, end) = (self.ends)(data);
+        self.curve = Self::curve(start, end, self.curvature.resolve(env));
+
+        // reach the far corner of what is drawn, so that it is painted and hit
+        let bounds = self.paint_bounds();
+        let size = bc.constrain(Size::new(bounds.x1.max(0.0), bounds.y1.max(0.0)));
+        ctx.set_paint_insets(Insets::new(
+            (-bounds.x0).max(0.0),
+            (-bounds.y0).max(0.0),
+            0.0,
+            0.0,
+        ));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Wire", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let color = self.color.resolve(env);
+        ctx.stroke(self.curve, &color, self.width);
+        if self.arrow {
+            ctx.fill(self.arrowhead(), &color);
+        }
+    }
+
+    fn debug_state(&self, _data: &T) -> DebugState {
+        DebugState {
+            display_name: "Wire".to_string(),
+            main_value: format!("{} -> {}", self.curve.p0, self.curve.p3),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{Canvas, SizedBox};
+    use crate::{Lens, MouseButton, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    #[derive(Clone, Data, Lens)]
+    struct Graph {
+        sink: Point,
+        wire_clicks: u32,
+        node_clicks: u32,
+    }
+
+    fn click(harness: &mut Harness<Graph>, pos: impl Into<Point>) {
+        let press = MouseEvent {
+            button: MouseButton::Left,
+            count: 1,
+            ..move_mouse(pos)
+        };
+        harness.event(Event::MouseMove(press.clone()));
+        harness.event(Event::MouseDown(press.clone()));
+        harness.event(Event::MouseUp(press));
+    }
+
+    #[test]
+    fn curve_shape() {
+        let curve = Wire::<()>::curve(Point::new(0., 0.), Point::new(100., 50.), 0.5);
+        assert_eq!(curve.p1, Point::new(50., 0.));
+        assert_eq!(curve.p2, Point::new(50., 50.));
+        assert_eq!(curve.eval(0.5), Point::new(50., 25.));
+
+        let straight = Wire::<()>::curve(Point::new(0., 0.), Point::new(100., 50.), 0.0);
+        assert_eq!(straight.eval(0.5), Point::new(50., 25.));
+        assert_eq!(straight.p1, straight.p0);
+    }
+
+    #[test]
+    fn hits_near_the_curve() {
+        let wire = Wire::dynamic(|graph: &Graph| (Point::new(10., 10.), graph.sink))
+            .with_arrow(true)
+            .on_click(|_, graph: &mut Graph, _| graph.wire_clicks += 1);
+        let node = SizedBox::empty()
+            .fix_size(100., 100.)
+            .on_click(|_, graph: &mut Graph, _| graph.node_clicks += 1);
+        // the node is below the wire, which is on top
+        let canvas = Canvas::new()
+            .with_child(node, (0., 0.))
+            .with_connection(wire);
+        let graph = Graph {
+            sink: Point::new(210., 110.),
+            wire_clicks: 0,
+            node_clicks: 0,
+        };
+
+        Harness::create_simple(graph, canvas, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            // the middle of the curve
+            click(harness, (110., 62.));
+            assert_eq!(harness.data().wire_clicks, 1);
+            // close to the start, which is over the node
+            click(harness, (12., 8.));
+            assert_eq!(harness.data().wire_clicks, 2);
+            assert_eq!(harness.data().node_clicks, 0);
+            // away from the curve, the click goes to the node below
+            click(harness, (50., 50.));
+            assert_eq!(harness.data().wire_clicks, 2);
+            assert_eq!(harness.data().node_clicks, 1);
+            // and nothing is there
+            click(harness, (200., 20.));
+            assert_eq!(harness.data().wire_clicks, 2);
+
+            // the wire follows the data
+            harness.update_data(|graph| graph.sink = Point::new(210., 300.));
+            harness.just_layout();
+            click(harness, (110., 62.));
+            assert_eq!(harness.data().wire_clicks, 2);
+            click(harness, (110., 155.));
+            assert_eq!(harness.data().wire_clicks, 3);
+        });
+    }
+}
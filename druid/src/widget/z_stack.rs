@@ -0,0 +1,278 @@
+// Copyright 2022 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that stacks its children on top of each other.
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{Data, InternalEvent, Point, Rect, UnitPoint, Vec2, WidgetPod};
+use tracing::{instrument, trace};
+
+/// A widget that stacks its children on top of each other.
+///
+/// The first child is the base: it is laid out with the constraints of the
+/// `ZStack`, and its size becomes the size of the stack. Every other child is
+/// laid out within the bounds of the base child, and positioned according to
+/// its alignment and offset.
+///
+/// Children are painted in the order they were added, so later children are
+/// drawn on top. Pointer events go to the topmost child under the pointer;
+/// children below it don't see the pointer until it leaves that child.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Label, ZStack};
+/// use druid::{UnitPoint, Widget, WidgetExt};
+///
+/// fn inbox_button() -> impl Widget<u32> {
+///     ZStack::new(Label::new("Inbox").padding(10.0)).with_child(
+///         Label::dynamic(|unread: &u32, _| unread.to_string()),
+///         UnitPoint::TOP_RIGHT,
+///         (4.0, -4.0),
+///     )
+/// }
+/// ```
+pub struct ZStack<T> {
+    base: WidgetPod<T, Box<dyn Widget<T>>>,
+    layers: Vec<ZChild<T>>,
+}
+
+/// A child of a [`ZStack`] that is stacked on top of the base.
+struct ZChild<T> {
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    alignment: UnitPoint,
+    offset: Vec2,
+    /// Whether the children below get the pointer, even when it is over this child.
+    pointer_passes_through: bool,
+}
+
+impl<T: Data> ZStack<T> {
+    /// Create a new `ZStack` with the given base child.
+    pub fn new(base: impl Widget<T> + 'static) -> Self {
+        ZStack {
+            base: WidgetPod::new(base).boxed(),
+            layers: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to add a child on top of the existing ones.
+    ///
+    /// The child is aligned within the base child using `alignment`, and then
+    /// moved by `offset`.
+    pub fn with_child(
+        mut self,
+        child: impl Widget<T> + 'static,
+        alignment: UnitPoint,
+        offset: impl Into<Vec2>,
+    ) -> Self {
+        self.add_child(child, alignment, offset);
+        self
+    }
+
+    /// Add a child on top of the existing ones.
+    ///
+    /// See [`with_child`] for more details.
+    ///
+    /// [`with_child`]: ZStack::with_child
+    pub fn add_child(
+        &mut self,
+        child: impl Widget<T> + 'static,
+        alignment: UnitPoint,
+        offset: impl Into<Vec2>,
+    ) {
+        self.layers.push(ZChild {
+            widget: WidgetPod::new(child).boxed(),
+            alignment,
+            offset: offset.into(),
+            pointer_passes_through: false,
+        });
+    }
+
+    /// Change where the child at `index` of the children on top of the base
+    /// is positioned.
+    pub(crate) fn set_child_position(&mut self, index: usize, alignment: UnitPoint, offset: Vec2) {
+        let layer = &mut self.layers[index];
+        layer.alignment = alignment;
+        layer.offset = offset;
+    }
+
+    /// Let the children below the child at `index` get the pointer, as if that
+    /// child wasn't there.
+    pub(crate) fn set_pointer_passes_through(&mut self, index: usize) {
+        self.layers[index].pointer_passes_through = true;
+    }
+}
+
+impl<T: Data> Widget<T> for ZStack<T> {
+    #[instrument(name = "ZStack", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let is_pointer_event = matches!(
+            event,
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::MouseMove(_) | Event::Wheel(_)
+        );
+        // While a child is active it gets the pointer, whatever is on top of it.
+        let has_active =
+            self.base.has_active() || self.layers.iter().any(|l| l.widget.has_active());
+
+        let mut covered = false;
+        let layers = self
+            .layers
+            .iter_mut()
+            .map(|layer| (&mut layer.widget, layer.pointer_passes_through));
+        for (child, passes_through) in layers.rev().chain(std::iter::once((&mut self.base, false)))
+        {
+            if is_pointer_event && covered && !has_active {
+                if child.is_hot() {
+                    child.event(ctx, &Event::Internal(InternalEvent::MouseLeave), data, env);
+                }
+            } else {
+                child.event(ctx, event, data, env);
+                covered |= child.is_hot() && !passes_through;
+            }
+        }
+    }
+
+    #[instrument(name = "ZStack", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.base.lifecycle(ctx, event, data, env);
+        for layer in &mut self.layers {
+            layer.widget.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "ZStack",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.base.update(ctx, data, env);
+        for layer in &mut self.layers {
+            layer.widget.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "ZStack", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("ZStack");
+
+        let size = self.base.layout(ctx, bc, data, env);
+        self.base.set_origin(ctx, data, env, Point::ORIGIN);
+        let mut paint_rect = self.base.paint_rect();
+
+        let layer_bc = BoxConstraints::new(Size::ZERO, size);
+        for layer in &mut self.layers {
+            let child_size = layer.widget.layout(ctx, &layer_bc, data, env);
+            let extra = (size - child_size).to_rect();
+            let origin = layer.alignment.resolve(extra) + layer.offset;
+            layer.widget.set_origin(ctx, data, env, origin);
+            paint_rect = paint_rect.union(layer.widget.paint_rect());
+        }
+
+        let insets = paint_rect - Rect::ZERO.with_size(size);
+        ctx.set_paint_insets(insets);
+        ctx.set_baseline_offset(self.base.baseline_offset());
+        trace!("Computed layout: size={}, insets={:?}", size, insets);
+        size
+    }
+
+    #[instrument(name = "ZStack", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.base.paint(ctx, data, env);
+        for layer in &mut self.layers {
+            layer.widget.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children = std::iter::once(&self.base)
+            .chain(self.layers.iter().map(|layer| &layer.widget))
+            .map(|child| child.widget().debug_state(data))
+            .collect();
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{Record, Recording, TestWidgetExt};
+    use crate::tests::move_mouse;
+    use crate::widget::SizedBox;
+    use crate::WidgetExt;
+    use test_log::test;
+
+    #[test]
+    fn pointer_goes_to_topmost_child() {
+        let base = Recording::default();
+        let badge = Recording::default();
+        let base_id = WidgetId::next();
+        let badge_id = WidgetId::next();
+
+        let widget = ZStack::new(
+            SizedBox::empty()
+                .fix_size(100.0, 50.0)
+                .record(&base)
+                .with_id(base_id),
+        )
+        .with_child(
+            SizedBox::empty()
+                .fix_size(20.0, 20.0)
+                .record(&badge)
+                .with_id(badge_id),
+            UnitPoint::TOP_RIGHT,
+            (5.0, -5.0),
+        )
+        .center();
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            let base_rect = harness.get_state(base_id).layout_rect();
+            let badge_rect = harness.get_state(badge_id).layout_rect();
+            assert_eq!(base_rect.size(), Size::new(100.0, 50.0));
+            assert_eq!(badge_rect, Rect::new(85.0, -5.0, 105.0, 15.0));
+
+            let origin = harness.get_state(base_id).window_origin();
+            base.clear();
+            badge.clear();
+
+            // Over the badge, the base doesn't see the pointer.
+            harness.event(Event::MouseMove(move_mouse(origin + (90.0, 5.0))));
+            assert!(harness.get_state(badge_id).is_hot);
+            assert!(!harness.get_state(base_id).is_hot);
+            assert!(badge
+                .drain()
+                .any(|r| matches!(r, Record::E(Event::MouseMove(_)))));
+            assert!(!base
+                .drain()
+                .any(|r| matches!(r, Record::E(Event::MouseMove(_)))));
+
+            // Outside of the badge, the base gets it.
+            harness.event(Event::MouseMove(move_mouse(origin + (10.0, 25.0))));
+            assert!(!harness.get_state(badge_id).is_hot);
+            assert!(harness.get_state(base_id).is_hot);
+            assert!(base
+                .drain()
+                .any(|r| matches!(r, Record::E(Event::MouseMove(_)))));
+        });
+    }
+}
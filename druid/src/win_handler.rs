@@ -22,18 +22,20 @@ use std::rc::Rc;
 use crate::kurbo::Size;
 use crate::piet::Piet;
 use crate::shell::{
-    text::InputHandler, Application, FileDialogToken, FileInfo, IdleToken, MouseEvent, Region,
-    Scale, TextFieldToken, WinHandler, WindowHandle,
+    text::InputHandler, Application, ColorScheme, FileDialogToken, FileDropEvent, FileInfo,
+    IdleToken, MouseEvent, Region, Scale, TextFieldToken, TrayHandler,
+    TrayIcon as PlatformTrayIcon, WinHandler, WindowHandle,
 };
 
+use crate::accessibility::AccessHandlerFn;
 use crate::app_delegate::{AppDelegate, DelegateCtx};
 use crate::core::CommandQueue;
-use crate::ext_event::{ExtEventHost, ExtEventSink};
+use crate::ext_event::{ExtEvent, ExtEventHost, ExtEventSink};
 use crate::menu::{ContextMenu, MenuItemId, MenuManager};
 use crate::window::{ImeUpdateFn, Window};
 use crate::{
-    Command, Data, Env, Event, Handled, InternalEvent, KeyEvent, PlatformError, Selector, Target,
-    TimerToken, WidgetId, WindowDesc, WindowId,
+    Command, Data, Env, Event, Handled, InternalEvent, KeyEvent, KeyMap, PlatformError, Rect,
+    Selector, Target, TimerToken, TrayIcon, WidgetId, WindowDesc, WindowId,
 };
 
 use crate::app::{PendingWindow, WindowConfig};
@@ -68,6 +70,11 @@ pub(crate) struct AppHandler<T> {
     app_state: AppState<T>,
 }
 
+/// The druid-shell `TrayHandler` of the application's tray icon.
+struct DruidTrayHandler<T> {
+    app_state: AppState<T>,
+}
+
 /// State shared by all windows in the UI.
 #[derive(Clone)]
 pub(crate) struct AppState<T> {
@@ -86,12 +93,20 @@ struct DialogInfo {
     cancel_cmd: Selector<()>,
 }
 
+/// The application's icon in the system tray.
+struct Tray<T> {
+    icon: PlatformTrayIcon,
+    menu: Option<MenuManager<T>>,
+}
+
 struct InnerAppState<T> {
     app: Application,
     delegate: Option<Box<dyn AppDelegate<T>>>,
     command_queue: CommandQueue,
     file_dialogs: HashMap<FileDialogToken, DialogInfo>,
     ext_event_host: ExtEventHost,
+    /// The keyboard shortcuts of the application.
+    keymap: KeyMap,
     windows: Windows<T>,
     /// the application-level menu, only set on macos and only if there
     /// are no open windows.
@@ -100,9 +115,14 @@ struct InnerAppState<T> {
     /// is the window that's currently in charge of the app menu.
     #[allow(unused)]
     menu_window: Option<WindowId>,
+    tray: Option<Tray<T>>,
+    /// The last color scheme reported by the platform.
+    color_scheme: ColorScheme,
     pub(crate) env: Env,
     pub(crate) data: T,
     ime_focus_change: Option<Box<dyn Fn()>>,
+    /// Receives the accessibility tree of each window, if set.
+    access_handler: Option<Box<AccessHandlerFn>>,
 }
 
 /// All active windows.
@@ -158,6 +178,7 @@ impl<T> AppState<T> {
         data: T,
         env: Env,
         delegate: Option<Box<dyn AppDelegate<T>>>,
+        keymap: KeyMap,
         ext_event_host: ExtEventHost,
     ) -> Self {
         let inner = Rc::new(RefCell::new(InnerAppState {
@@ -167,11 +188,15 @@ impl<T> AppState<T> {
             file_dialogs: HashMap::new(),
             root_menu: None,
             menu_window: None,
+            tray: None,
+            color_scheme: Application::get_color_scheme(),
             ext_event_host,
+            keymap,
             data,
             env,
             windows: Windows::default(),
             ime_focus_change: None,
+            access_handler: None,
         }));
 
         AppState { inner }
@@ -199,6 +224,42 @@ impl<T: Data> InnerAppState<T> {
         };
     }
 
+    fn handle_tray_menu_cmd(&mut self, cmd_id: MenuItemId) {
+        if let Some(menu) = self.tray.as_mut().and_then(|tray| tray.menu.as_mut()) {
+            menu.event(
+                &mut self.command_queue,
+                None,
+                cmd_id,
+                &mut self.data,
+                &self.env,
+            );
+        }
+    }
+
+    fn set_tray_icon(&mut self, cmd: &Command) {
+        if let Some(tray) = &self.tray {
+            tray.icon
+                .set_icon(cmd.get_unchecked(sys_cmd::SET_TRAY_ICON));
+        }
+    }
+
+    fn set_tray_tooltip(&mut self, cmd: &Command) {
+        if let Some(tray) = &self.tray {
+            let tooltip: &String = cmd.get_unchecked(sys_cmd::SET_TRAY_TOOLTIP);
+            tray.icon.set_tooltip(tooltip);
+        }
+    }
+
+    fn set_theme(&mut self, cmd: &Command) {
+        let theme = cmd.get_unchecked(sys_cmd::SET_THEME);
+        theme.apply(&mut self.env);
+        // widgets don't have to request a paint when only colors change
+        for window in self.windows.iter_mut() {
+            window.handle.invalidate();
+        }
+        self.append_command(sys_cmd::THEME_CHANGED.to(Target::Global));
+    }
+
     fn append_command(&mut self, cmd: Command) {
         self.command_queue.push_back(cmd);
     }
@@ -215,11 +276,13 @@ impl<T: Data> InnerAppState<T> {
             ref mut command_queue,
             ref mut data,
             ref ext_event_host,
+            ref mut keymap,
             ref env,
             ..
         } = self;
         let mut ctx = DelegateCtx {
             command_queue,
+            keymap,
             app_data_type: TypeId::of::<T>(),
             ext_event_host,
         };
@@ -237,6 +300,22 @@ impl<T: Data> InnerAppState<T> {
         }
     }
 
+    fn delegate_filter_cmd(&mut self, cmd: Command) -> Option<Command> {
+        if self.delegate.is_some() {
+            let old_data = self.data.clone();
+            let cmd = self
+                .with_delegate(|del, data, env, ctx| del.filter_command(ctx, cmd, data, env))
+                .unwrap();
+            // only update if the delegate changed the data
+            if !old_data.same(&self.data) {
+                self.do_update();
+            }
+            cmd
+        } else {
+            Some(cmd)
+        }
+    }
+
     fn delegate_cmd(&mut self, cmd: &Command) -> Handled {
         self.with_delegate(|del, data, env, ctx| del.command(ctx, cmd.target(), cmd, data, env))
             .unwrap_or(Handled::No)
@@ -265,8 +344,9 @@ impl<T: Data> InnerAppState<T> {
             if self.windows.windows.is_empty() {
                 // on mac we need to keep the menu around
                 self.root_menu = win.menu.take();
-                // If there are even no pending windows, we quit the run loop.
-                if self.windows.count() == 0 {
+                // If there are even no pending windows, we quit the run loop,
+                // unless the app lives on in the tray.
+                if self.windows.count() == 0 && self.tray.is_none() {
                     #[cfg(any(target_os = "windows", feature = "x11"))]
                     self.app.quit();
                 }
@@ -330,6 +410,13 @@ impl<T: Data> InnerAppState<T> {
         }
     }
 
+    fn capture_window(&mut self, region: Option<Rect>, id: WindowId) {
+        if let Some(win) = self.windows.get_mut(id) {
+            let result = win.capture(region, &mut self.command_queue, &self.data, &self.env);
+            self.append_command(sys_cmd::WINDOW_CAPTURED.with(result).to(id));
+        }
+    }
+
     fn prepare_paint(&mut self, window_id: WindowId) {
         if let Some(win) = self.windows.get_mut(window_id) {
             win.prepare_paint(&mut self.command_queue, &mut self.data, &self.env);
@@ -432,8 +519,20 @@ impl<T: Data> InnerAppState<T> {
             None => return Handled::Yes,
         };
 
+        let key = match &event {
+            Event::KeyDown(key) => Some(key.clone()),
+            _ => None,
+        };
         if let Some(win) = self.windows.get_mut(source_id) {
-            win.event(&mut self.command_queue, event, &mut self.data, &self.env)
+            let handled = win.event(&mut self.command_queue, event, &mut self.data, &self.env);
+            // the application's shortcuts come after those of the window
+            let key = key.filter(|key| !handled.is_handled() && !win.focused_text_field_wants(key));
+            if let Some(cmd) = key.and_then(|key| self.keymap.command_for(&key)) {
+                let cmd = cmd.clone().default_to(source_id.into());
+                self.command_queue.push_back(cmd);
+                return Handled::Yes;
+            }
+            handled
         } else {
             Handled::No
         }
@@ -449,7 +548,7 @@ impl<T: Data> InnerAppState<T> {
                 Some(menu) => {
                     win.show_context_menu(menu.menu, menu.location, &self.data, &self.env)
                 }
-                None => panic!(
+                None => tracing::error!(
                     "{} command must carry a ContextMenu<application state>.",
                     sys_cmd::SHOW_CONTEXT_MENU
                 ),
@@ -488,6 +587,14 @@ impl<T: Data> InnerAppState<T> {
                 }
             }
         }
+
+        if let Some(tray) = &mut self.tray {
+            if let Some(menu) = &mut tray.menu {
+                if let Some(new_menu) = menu.update(None, &self.data, &self.env) {
+                    tray.icon.set_menu(Some(new_menu));
+                }
+            }
+        }
         self.invalidate_and_finalize();
     }
 
@@ -498,6 +605,13 @@ impl<T: Data> InnerAppState<T> {
     fn invalidate_and_finalize(&mut self) {
         for win in self.windows.iter_mut() {
             win.invalidate_and_finalize();
+            if let Some(handler) = &mut self.access_handler {
+                if let Some(update) =
+                    win.accessibility_update(&mut self.command_queue, &self.data, &self.env)
+                {
+                    handler(win.id, &update);
+                }
+            }
         }
     }
 
@@ -559,6 +673,29 @@ impl<T: Data> AppState<T> {
         self.inner.borrow().env.clone()
     }
 
+    /// Set the function that receives the accessibility tree of each window.
+    pub(crate) fn set_access_handler(&mut self, handler: Box<AccessHandlerFn>) {
+        self.inner.borrow_mut().access_handler = Some(handler);
+    }
+
+    /// Add the application's icon to the system tray.
+    pub(crate) fn add_tray_icon(&mut self, desc: TrayIcon<T>) -> Result<(), PlatformError> {
+        let handler = DruidTrayHandler {
+            app_state: self.clone(),
+        };
+        let icon = PlatformTrayIcon::new(Box::new(handler))?;
+        icon.set_icon(&desc.icon);
+        icon.set_tooltip(&desc.tooltip);
+
+        let mut inner = self.inner.borrow_mut();
+        let mut menu = desc.menu;
+        if let Some(menu) = &mut menu {
+            icon.set_menu(Some(menu.initialize(None, &inner.data, &inner.env)));
+        }
+        inner.tray = Some(Tray { icon, menu });
+        Ok(())
+    }
+
     pub(crate) fn add_window(&self, id: WindowId, window: PendingWindow<T>) {
         self.inner.borrow_mut().windows.add(id, window);
     }
@@ -615,7 +752,7 @@ impl<T: Data> AppState<T> {
         }
     }
 
-    pub(crate) fn handle_idle_callback(&mut self, cb: impl FnOnce(&mut T)) {
+    fn handle_idle_callback(&mut self, cb: impl FnOnce(&mut T)) {
         let mut inner = self.inner.borrow_mut();
         cb(&mut inner.data);
         inner.do_update();
@@ -633,9 +770,12 @@ impl<T: Data> AppState<T> {
 
     fn process_ext_events(&mut self) {
         loop {
-            let ext_cmd = self.inner.borrow_mut().ext_event_host.recv();
-            match ext_cmd {
-                Some(cmd) => self.handle_cmd(cmd),
+            let ext_event = self.inner.borrow_mut().ext_event_host.recv();
+            match ext_event {
+                Some(ExtEvent::Command((selector, payload, target))) => {
+                    self.handle_cmd(Command::from_ext(selector, payload, target))
+                }
+                Some(ExtEvent::Callback(cb)) => self.handle_idle_callback(|data| cb(data)),
                 None => break,
             }
         }
@@ -655,10 +795,49 @@ impl<T: Data> AppState<T> {
         self.inner.borrow_mut().do_update();
     }
 
+    /// Handle a click on the tray icon, by sending `selector` to the whole app.
+    fn handle_tray_click(&mut self, selector: Selector) {
+        self.inner
+            .borrow_mut()
+            .append_command(selector.to(Target::Global));
+        self.process_commands();
+        self.inner.borrow_mut().do_update();
+    }
+
+    /// Every window reports the change, so it is only forwarded once.
+    fn color_scheme_changed(&mut self, scheme: ColorScheme) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.color_scheme == scheme {
+            return;
+        }
+        inner.color_scheme = scheme;
+        inner.append_command(
+            sys_cmd::COLOR_SCHEME_CHANGED
+                .with(scheme)
+                .to(Target::Global),
+        );
+        drop(inner);
+        self.process_commands();
+        self.inner.borrow_mut().do_update();
+    }
+
+    /// Handle the selection of an item in the tray icon's menu.
+    fn handle_tray_menu_cmd(&mut self, cmd_id: u32) {
+        self.inner
+            .borrow_mut()
+            .handle_tray_menu_cmd(MenuItemId::new(cmd_id));
+        self.process_commands();
+        self.inner.borrow_mut().do_update();
+    }
+
     /// Handle a command. Top level commands (e.g. for creating and destroying
     /// windows) have their logic here; other commands are passed to the window.
     fn handle_cmd(&mut self, cmd: Command) {
         use Target as T;
+        let cmd = match self.inner.borrow_mut().delegate_filter_cmd(cmd) {
+            Some(cmd) => cmd,
+            None => return,
+        };
         match cmd.target() {
             // these are handled the same no matter where they come from
             _ if cmd.is(sys_cmd::QUIT_APP) => self.quit(),
@@ -677,12 +856,18 @@ impl<T: Data> AppState<T> {
                 }
             }
             _ if cmd.is(sys_cmd::CLOSE_ALL_WINDOWS) => self.request_close_all_windows(),
+            _ if cmd.is(sys_cmd::SET_TRAY_ICON) => self.inner.borrow_mut().set_tray_icon(&cmd),
+            _ if cmd.is(sys_cmd::SET_TRAY_TOOLTIP) => {
+                self.inner.borrow_mut().set_tray_tooltip(&cmd)
+            }
+            _ if cmd.is(sys_cmd::SET_THEME) => self.inner.borrow_mut().set_theme(&cmd),
             T::Window(id) if cmd.is(sys_cmd::INVALIDATE_IME) => self.invalidate_ime(cmd, id),
             // these should come from a window
             // FIXME: we need to be able to open a file without a window handle
             T::Window(id) if cmd.is(sys_cmd::SHOW_OPEN_PANEL) => self.show_open_panel(cmd, id),
             T::Window(id) if cmd.is(sys_cmd::SHOW_SAVE_PANEL) => self.show_save_panel(cmd, id),
             T::Window(id) if cmd.is(sys_cmd::CONFIGURE_WINDOW) => self.configure_window(cmd, id),
+            T::Window(id) if cmd.is(sys_cmd::CAPTURE_WINDOW) => self.capture_window(cmd, id),
             T::Window(id) if cmd.is(sys_cmd::CLOSE_WINDOW) => {
                 if !self.inner.borrow_mut().dispatch_cmd(cmd).is_handled() {
                     self.request_close_window(id);
@@ -699,6 +884,12 @@ impl<T: Data> AppState<T> {
             _ if cmd.is(sys_cmd::SHOW_OPEN_PANEL) => {
                 tracing::warn!("SHOW_OPEN_PANEL command must target a window.")
             }
+            _ if cmd.is(sys_cmd::CAPTURE_WINDOW) => {
+                tracing::warn!("CAPTURE_WINDOW command must target a window.")
+            }
+            _ if cmd.is(sys_cmd::SHOW_CONTEXT_MENU) && !matches!(cmd.target(), T::Window(_)) => {
+                tracing::warn!("SHOW_CONTEXT_MENU command must target a window.")
+            }
             _ => {
                 self.inner.borrow_mut().dispatch_cmd(cmd);
             }
@@ -854,6 +1045,11 @@ impl<T: Data> AppState<T> {
         }
     }
 
+    fn capture_window(&mut self, cmd: Command, id: WindowId) {
+        let region = *cmd.get_unchecked(sys_cmd::CAPTURE_WINDOW);
+        self.inner.borrow_mut().capture_window(region, id);
+    }
+
     fn do_paste(&mut self, window_id: WindowId) {
         let event = Event::Paste(self.inner.borrow().app.clipboard());
         self.inner.borrow_mut().do_window_event(window_id, event);
@@ -876,6 +1072,10 @@ impl<T: Data> AppState<T> {
     }
 
     fn quit(&self) {
+        // Remove the tray icon right away; on Windows it would otherwise stick
+        // around until the user hovers over it.
+        let tray = self.inner.borrow_mut().tray.take();
+        drop(tray);
         self.inner.borrow().app.quit()
     }
 
@@ -904,6 +1104,8 @@ impl<T: Data> AppState<T> {
         let env = self.env();
 
         pending.size_policy = config.size_policy;
+        pending.min_size = config.min_size;
+        pending.max_size = config.max_size;
         pending.title.resolve(&data, &env);
         builder.set_title(pending.title.display_text().to_string());
 
@@ -929,6 +1131,21 @@ impl<T: Data> crate::shell::AppHandler for AppHandler<T> {
     }
 }
 
+impl<T: Data> TrayHandler for DruidTrayHandler<T> {
+    fn clicked(&mut self) {
+        self.app_state.handle_tray_click(sys_cmd::TRAY_ICON_CLICKED)
+    }
+
+    fn double_clicked(&mut self) {
+        self.app_state
+            .handle_tray_click(sys_cmd::TRAY_ICON_DOUBLE_CLICKED)
+    }
+
+    fn command(&mut self, id: u32) {
+        self.app_state.handle_tray_menu_cmd(id)
+    }
+}
+
 impl<T: Data> WinHandler for DruidHandler<T> {
     fn connect(&mut self, handle: &WindowHandle) {
         self.app_state
@@ -955,6 +1172,10 @@ impl<T: Data> WinHandler for DruidHandler<T> {
         // TODO: Do something with the scale
     }
 
+    fn color_scheme_changed(&mut self, scheme: ColorScheme) {
+        self.app_state.color_scheme_changed(scheme);
+    }
+
     fn command(&mut self, id: u32) {
         self.app_state.handle_system_cmd(id, Some(self.window_id));
     }
@@ -993,6 +1214,23 @@ impl<T: Data> WinHandler for DruidHandler<T> {
             .do_window_event(Event::Internal(InternalEvent::MouseLeave), self.window_id);
     }
 
+    fn file_drag_move(&mut self, event: &FileDropEvent) -> bool {
+        self.app_state
+            .do_window_event(Event::FileDragMove(event.clone().into()), self.window_id)
+            .is_handled()
+    }
+
+    fn file_drag_leave(&mut self) {
+        self.app_state
+            .do_window_event(Event::FileDragLeave, self.window_id);
+    }
+
+    fn file_drop(&mut self, event: &FileDropEvent) -> bool {
+        self.app_state
+            .do_window_event(Event::DropFiles(event.clone().into()), self.window_id)
+            .is_handled()
+    }
+
     fn key_down(&mut self, event: KeyEvent) -> bool {
         self.app_state
             .do_window_event(Event::KeyDown(event), self.window_id)
@@ -1018,6 +1256,11 @@ impl<T: Data> WinHandler for DruidHandler<T> {
         self.app_state.window_got_focus(self.window_id);
     }
 
+    fn lost_focus(&mut self) {
+        self.app_state
+            .do_window_event(Event::WindowLostFocus, self.window_id);
+    }
+
     fn timer(&mut self, token: TimerToken) {
         self.app_state
             .do_window_event(Event::Timer(token), self.window_id);
@@ -15,27 +15,34 @@
 //! Management of multiple windows.
 
 use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::mem;
 use tracing::{error, info, info_span};
 
 // Automatically defaults to std::time::Instant on non Wasm platforms
 use instant::Instant;
 
-use crate::piet::{Color, Piet, RenderContext};
+use crate::kurbo::{Affine, Rect};
+use crate::piet::{Color, Device, ImageFormat, Piet, RenderContext};
 use crate::shell::{text::InputHandler, Counter, Cursor, Region, TextFieldToken, WindowHandle};
 
+use crate::accessibility::{AccessNode, AccessUpdate, Role};
 use crate::app::{PendingWindow, WindowSizePolicy};
+use crate::commands::ADD_KEY_BINDING;
 use crate::contexts::ContextState;
-use crate::core::{CommandQueue, FocusChange, WidgetState};
+use crate::core::{CommandQueue, FocusChange, FocusEntry, WidgetState};
 use crate::debug_state::DebugState;
+use crate::drag::{ActiveDrag, BEGIN_DRAG};
+use crate::event::AccessTreeCell;
 use crate::menu::{MenuItemId, MenuManager};
 use crate::text::TextFieldRegistration;
 use crate::widget::LabelText;
 use crate::win_handler::RUN_COMMANDS_TOKEN;
 use crate::{
-    BoxConstraints, Data, Env, Event, EventCtx, ExtEventSink, Handled, InternalEvent,
-    InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, Menu, PaintCtx, Point, Size, TimerToken,
-    UpdateCtx, Widget, WidgetId, WidgetPod,
+    BoxConstraints, Data, DragEvent, Env, Event, EventCtx, ExtEventSink, Handled, ImageBuf,
+    InternalEvent, InternalLifeCycle, KbKey, KeyEvent, KeyMap, LayoutCtx, LifeCycle, LifeCycleCtx,
+    Menu, Modifiers, PaintCtx, Point, SingleUse, Size, TimerToken, UpdateCtx, Widget, WidgetId,
+    WidgetPod,
 };
 
 pub type ImeUpdateFn = dyn FnOnce(crate::shell::text::Event);
@@ -44,6 +51,31 @@ pub type ImeUpdateFn = dyn FnOnce(crate::shell::text::Event);
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct WindowId(u64);
 
+/// The reasons a [`CAPTURE_WINDOW`] command can fail.
+///
+/// [`CAPTURE_WINDOW`]: crate::commands::CAPTURE_WINDOW
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureError {
+    /// The window hasn't been painted yet, so there is nothing to capture.
+    NotPainted,
+    /// The requested region doesn't overlap the window.
+    EmptyRegion,
+    /// The platform failed to render the image.
+    Render(String),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CaptureError::NotPainted => write!(f, "the window has not been painted yet"),
+            CaptureError::EmptyRegion => write!(f, "the region does not overlap the window"),
+            CaptureError::Render(err) => write!(f, "rendering failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
 /// Per-window state not owned by user code.
 pub struct Window<T> {
     pub(crate) id: WindowId,
@@ -51,13 +83,18 @@ pub struct Window<T> {
     pub(crate) title: LabelText<T>,
     size_policy: WindowSizePolicy,
     size: Size,
+    min_size: Option<Size>,
+    max_size: Option<Size>,
     invalid: Region,
     pub(crate) menu: Option<MenuManager<T>>,
+    pub(crate) keymap: KeyMap,
     pub(crate) context_menu: Option<(MenuManager<T>, Point)>,
     // This will be `Some` whenever the most recently displayed frame was an animation frame.
     pub(crate) last_anim: Option<Instant>,
     pub(crate) last_mouse_pos: Option<Point>,
     pub(crate) focus: Option<WidgetId>,
+    /// The drag started with `EventCtx::start_drag` that is in progress.
+    drag: Option<ActiveDrag>,
     pub(crate) handle: WindowHandle,
     pub(crate) timers: HashMap<TimerToken, WidgetId>,
     pub(crate) pending_text_registrations: Vec<TextFieldRegistration>,
@@ -65,6 +102,14 @@ pub struct Window<T> {
     pub(crate) ime_handlers: Vec<(TextFieldToken, TextFieldRegistration)>,
     ext_handle: ExtEventSink,
     pub(crate) ime_focus_change: Option<Option<TextFieldToken>>,
+    /// `true` once the window has been painted, and so can be captured.
+    painted: bool,
+    /// The most recently collected accessibility tree.
+    access_tree: Option<AccessNode>,
+    /// Layout or focus changed since `access_tree` was collected.
+    access_dirty: bool,
+    /// The id of the window's node in the accessibility tree.
+    access_id: WidgetId,
 }
 
 impl<T> Window<T> {
@@ -79,20 +124,28 @@ impl<T> Window<T> {
             root: WidgetPod::new(pending.root),
             size_policy: pending.size_policy,
             size: Size::ZERO,
+            min_size: pending.min_size,
+            max_size: pending.max_size,
             invalid: Region::EMPTY,
             title: pending.title,
             transparent: pending.transparent,
             menu: pending.menu,
+            keymap: pending.keymap,
             context_menu: None,
             last_anim: None,
             last_mouse_pos: None,
             focus: None,
+            drag: None,
             handle,
             timers: HashMap::new(),
             ext_handle,
             ime_handlers: Vec::new(),
             ime_focus_change: None,
             pending_text_registrations: Vec::new(),
+            painted: false,
+            access_tree: None,
+            access_dirty: true,
+            access_id: WidgetId::next(),
         }
     }
 }
@@ -103,8 +156,23 @@ impl<T: Data> Window<T> {
         self.root.state().request_anim
     }
 
-    pub(crate) fn focus_chain(&self) -> &[WidgetId] {
-        &self.root.state().focus_chain
+    /// The focusable widgets, in the order that tab visits them.
+    #[cfg(test)]
+    pub(crate) fn focus_chain(&self) -> Vec<WidgetId> {
+        self.sorted_focus_chain()
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect()
+    }
+
+    /// The focus chain sorted by the [`FocusOrder`] of the widgets, and in the
+    /// order they registered for widgets with the same order.
+    ///
+    /// [`FocusOrder`]: crate::widget::FocusOrder
+    fn sorted_focus_chain(&self) -> Vec<FocusEntry> {
+        let mut chain = self.root.state().focus_chain.clone();
+        chain.sort_by_key(|entry| entry.order.unwrap_or(0));
+        chain
     }
 
     /// Returns `true` if the provided widget may be in this window,
@@ -125,7 +193,8 @@ impl<T: Data> Window<T> {
         if let Some(menu) = &mut self.menu {
             menu.event(queue, Some(self.id), cmd_id, data, env);
         }
-        if let Some((menu, _)) = &mut self.context_menu {
+        // The platform closes the context menu when one of its items is chosen.
+        if let Some((mut menu, _)) = self.context_menu.take() {
             menu.event(queue, Some(self.id), cmd_id, data, env);
         }
     }
@@ -226,6 +295,18 @@ impl<T: Data> Window<T> {
         }
     }
 
+    /// Clamp `size` to the window's minimum and maximum size.
+    fn clamp_size(&self, size: Size) -> Size {
+        let size = match self.max_size {
+            Some(max) => Size::new(size.width.min(max.width), size.height.min(max.height)),
+            None => size,
+        };
+        match self.min_size {
+            Some(min) => Size::new(size.width.max(min.width), size.height.max(min.height)),
+            None => size,
+        }
+    }
+
     pub(crate) fn event(
         &mut self,
         queue: &mut CommandQueue,
@@ -233,8 +314,29 @@ impl<T: Data> Window<T> {
         data: &mut T,
         env: &Env,
     ) -> Handled {
+        if let Some(handled) = self.drag_event(queue, &event, data, env) {
+            return handled;
+        }
+
+        if let Event::Command(cmd) | Event::Internal(InternalEvent::TargetedCommand(cmd)) = &event {
+            if let Some((hotkey, binding)) = cmd.get(ADD_KEY_BINDING) {
+                self.keymap.insert(hotkey.clone(), binding.clone());
+                return Handled::Yes;
+            }
+        }
+
         match &event {
-            Event::WindowSize(size) => self.size = *size,
+            Event::WindowSize(size) => {
+                self.size = self.clamp_size(*size);
+                // Not every platform enforces the limits, so we do it ourselves.
+                // A bit of slack avoids fighting over rounding with those that do.
+                let overshoot = *size - self.size;
+                if self.size_policy == WindowSizePolicy::User
+                    && (overshoot.width.abs() > 1.0 || overshoot.height.abs() > 1.0)
+                {
+                    self.handle.set_size(self.size);
+                }
+            }
             Event::MouseDown(e) | Event::MouseUp(e) | Event::MouseMove(e) | Event::Wheel(e) => {
                 self.last_mouse_pos = Some(e.pos)
             }
@@ -299,6 +401,13 @@ impl<T: Data> Window<T> {
             Handled::from(ctx.is_handled)
         };
 
+        let is_handled = match &event {
+            Event::KeyDown(key) if !is_handled.is_handled() => {
+                self.hotkey_event(queue, key, data, env)
+            }
+            _ => is_handled,
+        };
+
         if let Some(cursor) = &widget_state.cursor {
             self.handle.set_cursor(cursor);
         } else if matches!(
@@ -322,6 +431,105 @@ impl<T: Data> Window<T> {
         is_handled
     }
 
+    /// Turns pointer events into drag events while a drag is in progress.
+    ///
+    /// Returns `None` if `event` should be dispatched as usual.
+    fn drag_event(
+        &mut self,
+        queue: &mut CommandQueue,
+        event: &Event,
+        data: &mut T,
+        env: &Env,
+    ) -> Option<Handled> {
+        if let Event::Command(cmd) | Event::Internal(InternalEvent::TargetedCommand(cmd)) = event {
+            let drag = cmd.get(BEGIN_DRAG).and_then(SingleUse::take)?;
+            // The widgets under the pointer get `DragEnter`, not the mouse events they
+            // would get otherwise.
+            let pos = self.last_mouse_pos;
+            self.event(queue, Event::Internal(InternalEvent::MouseLeave), data, env);
+            self.last_mouse_pos = pos;
+            self.drag = Some(drag);
+            if let Some(pos) = pos {
+                self.drag_over(queue, pos, Modifiers::default(), data, env);
+            }
+            return Some(Handled::Yes);
+        }
+
+        let drag = self.drag.as_ref()?;
+        match event {
+            Event::MouseMove(mouse) => {
+                Some(self.drag_over(queue, mouse.pos, mouse.mods, data, env))
+            }
+            Event::MouseUp(mouse) => {
+                let drop = Event::Drop(DragEvent {
+                    pos: mouse.pos,
+                    window_pos: mouse.pos,
+                    mods: mouse.mods,
+                    source: drag.source,
+                    payload: drag.payload.clone(),
+                });
+                self.invalidate_ghost();
+                self.drag = None;
+                self.event(queue, drop, data, env);
+                self.handle.set_cursor(&Cursor::Arrow);
+                // The source still needs the mouse up.
+                None
+            }
+            Event::KeyDown(key) if key.key == KbKey::Escape => {
+                self.invalidate_ghost();
+                self.drag = None;
+                self.event(queue, Event::DragLeave, data, env);
+                self.handle.set_cursor(&Cursor::Arrow);
+                Some(Handled::Yes)
+            }
+            Event::Internal(InternalEvent::MouseLeave) => {
+                self.invalidate_ghost();
+                self.last_mouse_pos = None;
+                Some(self.event(queue, Event::DragLeave, data, env))
+            }
+            _ => None,
+        }
+    }
+
+    fn drag_over(
+        &mut self,
+        queue: &mut CommandQueue,
+        pos: Point,
+        mods: Modifiers,
+        data: &mut T,
+        env: &Env,
+    ) -> Handled {
+        let drag = match &self.drag {
+            Some(drag) => drag,
+            None => return Handled::No,
+        };
+        let event = Event::DragOver(DragEvent {
+            pos,
+            window_pos: pos,
+            mods,
+            source: drag.source,
+            payload: drag.payload.clone(),
+        });
+        self.invalidate_ghost();
+        self.last_mouse_pos = Some(pos);
+        self.invalidate_ghost();
+
+        let handled = self.event(queue, event, data, env);
+        if handled.is_handled() {
+            self.handle.set_cursor(&Cursor::Arrow);
+        } else {
+            self.handle.set_cursor(&Cursor::NotAllowed);
+        }
+        handled
+    }
+
+    fn invalidate_ghost(&mut self) {
+        let ghost = self.drag.as_ref().and_then(|drag| drag.ghost.as_ref());
+        if let (Some(ghost), Some(pos)) = (ghost, self.last_mouse_pos) {
+            self.invalid.add_rect(ghost.rect(pos));
+        }
+    }
+
     pub(crate) fn lifecycle(
         &mut self,
         queue: &mut CommandQueue,
@@ -436,17 +644,79 @@ impl<T: Data> Window<T> {
             self.layout(queue, data, env);
         }
 
+        // Widgets may paint outside of the invalid region, for instance when
+        // only a part of them was invalidated; clip that away, so that it
+        // doesn't draw over what was painted before.
+        let saved = piet.save();
+        if let Err(e) = &saved {
+            error!("Failed to save RenderContext: '{}'", e);
+        } else {
+            piet.clip(invalid.to_bez_path());
+        }
+        let background = self.background_color(env);
         for &r in invalid.rects() {
-            piet.clear(
-                Some(r),
-                if self.transparent {
-                    Color::TRANSPARENT
-                } else {
-                    env.get(crate::theme::WINDOW_BACKGROUND_COLOR)
-                },
-            );
+            piet.clear(Some(r), background.clone());
         }
         self.paint(piet, invalid, queue, data, env);
+        if saved.is_ok() {
+            if let Err(e) = piet.restore() {
+                error!("Failed to restore RenderContext: '{}'", e);
+            }
+        }
+        self.painted = true;
+    }
+
+    /// Render the contents of the window, or of `region` within it, into an image.
+    ///
+    /// The image is rendered at the window's current scale.
+    pub(crate) fn capture(
+        &mut self,
+        region: Option<Rect>,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) -> Result<ImageBuf, CaptureError> {
+        if !self.painted {
+            return Err(CaptureError::NotPainted);
+        }
+        let window_rect = self.size.to_rect();
+        let rect = region.map_or(window_rect, |region| region.intersect(window_rect));
+        if rect.area() <= 0.0 {
+            return Err(CaptureError::EmptyRegion);
+        }
+        if self.root.state().needs_layout {
+            self.layout(queue, data, env);
+        }
+
+        let scale = self.handle.get_scale().unwrap_or_default();
+        let width = (rect.width() * scale.x()).round() as usize;
+        let height = (rect.height() * scale.y()).round() as usize;
+        let render_err = |err: crate::piet::Error| CaptureError::Render(err.to_string());
+        let mut device = Device::new().map_err(render_err)?;
+        let mut target = device
+            .bitmap_target(width, height, 1.0)
+            .map_err(render_err)?;
+        {
+            let mut piet = target.render_context();
+            piet.clear(None, self.background_color(env));
+            piet.transform(
+                Affine::scale_non_uniform(scale.x(), scale.y())
+                    * Affine::translate(-rect.origin().to_vec2()),
+            );
+            self.paint(&mut piet, &rect.into(), queue, data, env);
+            piet.finish().map_err(render_err)?;
+        }
+        target
+            .to_image_buf(ImageFormat::RgbaPremul)
+            .map_err(render_err)
+    }
+
+    fn background_color(&self, env: &Env) -> Color {
+        if self.transparent {
+            Color::TRANSPARENT
+        } else {
+            env.get(crate::theme::WINDOW_BACKGROUND_COLOR)
+        }
     }
 
     fn layout(&mut self, queue: &mut CommandQueue, data: &T, env: &Env) {
@@ -467,7 +737,11 @@ impl<T: Data> Window<T> {
         };
         let bc = match self.size_policy {
             WindowSizePolicy::User => BoxConstraints::tight(self.size),
-            WindowSizePolicy::Content => BoxConstraints::UNBOUNDED,
+            WindowSizePolicy::Content => BoxConstraints::new(
+                self.min_size.unwrap_or(Size::ZERO),
+                self.max_size
+                    .unwrap_or(Size::new(f64::INFINITY, f64::INFINITY)),
+            ),
         };
 
         let content_size = {
@@ -493,6 +767,7 @@ impl<T: Data> Window<T> {
             env,
             false,
         );
+        self.access_dirty = true;
         self.post_event_processing(&mut widget_state, queue, data, env, true);
     }
 
@@ -546,11 +821,57 @@ impl<T: Data> Window<T> {
             });
         }
 
+        let ghost = self.drag.as_ref().and_then(|drag| drag.ghost.as_ref());
+        if let (Some(ghost), Some(pos)) = (ghost, self.last_mouse_pos) {
+            let rect = ghost.rect(pos);
+            ctx.with_child_ctx(invalid.clone(), |ctx| {
+                ctx.with_save(|ctx| {
+                    ctx.render_ctx
+                        .transform(Affine::translate(rect.origin().to_vec2()));
+                    ghost.paint(ctx, env);
+                });
+            });
+        }
+
         if self.wants_animation_frame() {
             self.handle.request_anim_frame();
         }
     }
 
+    /// Collect the accessibility tree, if it may have changed since the last update.
+    pub(crate) fn accessibility_update(
+        &mut self,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) -> Option<AccessUpdate> {
+        if !self.access_dirty && !self.root.state().access_changed && self.access_tree.is_some() {
+            return None;
+        }
+        let tree = self.access_tree(queue, data, env);
+        let update = AccessUpdate::diff(self.access_tree.as_ref(), tree);
+        self.access_tree = Some(update.tree.clone());
+        self.access_dirty = false;
+        Some(update)
+    }
+
+    /// Collect the current accessibility tree.
+    pub(crate) fn access_tree(
+        &mut self,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) -> AccessNode {
+        let mut root = AccessNode::new(self.access_id, Role::Window);
+        root.name = Some(self.title.display_text().to_string());
+        root.bounds = self.size.to_rect();
+        let cell = AccessTreeCell::new(root);
+        let event = LifeCycle::Internal(InternalLifeCycle::CollectAccessTree(cell.clone()));
+        self.lifecycle(queue, &event, data, env, false);
+        drop(event);
+        cell.finish()
+    }
+
     /// Get a best-effort representation of the entire widget tree for debug purposes.
     pub fn root_debug_state(&self, data: &T) -> DebugState {
         self.root.widget().debug_state(data)
@@ -568,11 +889,49 @@ impl<T: Data> Window<T> {
                 self.handle.set_menu(new_menu);
             }
         }
-        if let Some((menu, point)) = &mut self.context_menu {
-            if let Some(new_menu) = menu.update(Some(self.id), data, env) {
-                self.handle.show_context_menu(new_menu, *point);
+        // We can't tell whether the context menu is still open, and showing it again would
+        // bring it back after the user dismissed it, so only its state is kept up to date.
+        if let Some((menu, _)) = &mut self.context_menu {
+            menu.update(Some(self.id), data, env);
+        }
+    }
+
+    /// Activate the menu item with a hotkey matching this unhandled key press, if any,
+    /// or else submit the command bound to it in the window's [`KeyMap`].
+    fn hotkey_event(
+        &mut self,
+        queue: &mut CommandQueue,
+        key: &KeyEvent,
+        data: &mut T,
+        env: &Env,
+    ) -> Handled {
+        if self.focused_text_field_wants(key) {
+            return Handled::No;
+        }
+        if let Some(menu) = &mut self.menu {
+            if menu.hotkey_event(queue, Some(self.id), key, data, env) {
+                return Handled::Yes;
             }
         }
+        match self.keymap.command_for(key) {
+            Some(cmd) => {
+                queue.push_back(cmd.clone().default_to(self.id.into()));
+                Handled::Yes
+            }
+            None => Handled::No,
+        }
+    }
+
+    /// Returns `true` if the focused widget is a text field that will insert
+    /// text for this key press, which should then not trigger hotkeys.
+    pub(crate) fn focused_text_field_wants(&self, key: &KeyEvent) -> bool {
+        let inserts_text = matches!(key.key, KbKey::Character(_))
+            && !(key.mods.ctrl() || key.mods.alt() || key.mods.meta());
+        inserts_text
+            && self
+                .ime_handlers
+                .iter()
+                .any(|(_, reg)| Some(reg.widget_id) == self.focus)
     }
 
     pub(crate) fn get_ime_handler(
@@ -602,6 +961,7 @@ impl<T: Data> Window<T> {
                 let event = LifeCycle::Internal(InternalLifeCycle::RouteFocusChanged { old, new });
                 self.lifecycle(queue, &event, data, env, false);
                 self.focus = new;
+                self.access_dirty = true;
                 // check if the newly focused widget has an IME session, and
                 // notify the system if so.
                 //
@@ -669,27 +1029,36 @@ impl<T: Data> Window<T> {
 
     fn widget_from_focus_chain(&self, forward: bool) -> Option<WidgetId> {
         self.focus.and_then(|focus| {
-            self.focus_chain()
+            let mut chain = self.sorted_focus_chain();
+            // Inside of a focus group, the focus stays in the group.
+            let group = chain
+                .iter()
+                .find(|entry| entry.id == focus)
+                .and_then(|entry| entry.group);
+            if group.is_some() {
+                chain.retain(|entry| entry.group == group);
+            }
+            chain
                 .iter()
                 // Find where the focused widget is in the focus chain
-                .position(|id| id == &focus)
+                .position(|entry| entry.id == focus)
                 .map(|idx| {
                     // Return the id that's next to it in the focus chain
-                    let len = self.focus_chain().len();
+                    let len = chain.len();
                     let new_idx = if forward {
                         (idx + 1) % len
                     } else {
                         (idx + len - 1) % len
                     };
-                    self.focus_chain()[new_idx]
+                    chain[new_idx].id
                 })
                 .or_else(|| {
                     // If the currently focused widget isn't in the focus chain,
                     // then we'll just return the first/last entry of the chain, if any.
                     if forward {
-                        self.focus_chain().first().copied()
+                        chain.first().map(|entry| entry.id)
                     } else {
-                        self.focus_chain().last().copied()
+                        chain.last().map(|entry| entry.id)
                     }
                 })
         })